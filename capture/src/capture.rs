@@ -1,31 +1,88 @@
 //! Audio capture – spawns `ffmpeg` as child processes.
 //!
 //! Reused from `birdnet-server/src/capture.rs`.
+//!
+//! RTSP/HTTP streams are watched: ffmpeg can hang without exiting when
+//! a camera reboots, so a stream that writes no audio for two segment
+//! lengths has its ffmpeg killed and started again.
 
 use std::io::{BufRead, BufReader};
+use std::path::Path;
 use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, Result};
 use tracing::{debug, info, warn};
 
 use gaia_common::config::Config;
+use gaia_common::protocol::{StreamState, StreamStatus};
 
 /// Opaque handle that owns the recording child process(es).
 pub struct CaptureHandle {
     children: Vec<Child>,
+    /// Network streams, each with its own ffmpeg under the watchdog.
+    streams: Vec<RtspStream>,
+}
+
+/// One `RTSP_STREAMS` entry and its ffmpeg process.
+struct RtspStream {
+    index: usize,
+    url: String,
+    /// `None` after a failed respawn.
+    child: Option<Child>,
+    spawned_at: SystemTime,
+    restarts: u32,
+    last_restart: Option<String>,
 }
 
 impl CaptureHandle {
-    #[allow(dead_code)]
-    pub fn kill(&mut self) -> Result<()> {
-        for child in &mut self.children {
-            let _ = child.kill();
+    /// Stop the children cleanly: SIGTERM lets ffmpeg close the segment
+    /// it is writing and patch the WAV header sizes.  Anything still
+    /// running after `grace` is killed.
+    pub fn stop(&mut self, grace: Duration) {
+        let children = self
+            .children
+            .iter_mut()
+            .chain(self.streams.iter_mut().filter_map(|s| s.child.as_mut()));
+        let mut children: Vec<&mut Child> = children.collect();
+        for child in children.iter_mut() {
+            if let Ok(None) = child.try_wait() {
+                // SAFETY: plain kill(2) on a PID we spawned and have not reaped.
+                unsafe {
+                    libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+                }
+            }
+        }
+
+        let deadline = Instant::now() + grace;
+        for (i, child) in children.into_iter().enumerate() {
+            loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        debug!("Capture child {i} exited with {status}");
+                        break;
+                    }
+                    Ok(None) if Instant::now() < deadline => {
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                    _ => {
+                        warn!("Capture child {i} ignored SIGTERM — killing it");
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        break;
+                    }
+                }
+            }
         }
-        Ok(())
     }
 
     /// Check whether any child has exited.  Returns `Some(status_msg)` if
     /// a child died, `None` if all are still running.
+    ///
+    /// Stream children are not checked here; [`watch_streams`] restarts
+    /// them instead.
+    ///
+    /// [`watch_streams`]: CaptureHandle::watch_streams
     pub fn check_alive(&mut self) -> Option<String> {
         for (i, child) in self.children.iter_mut().enumerate() {
             match child.try_wait() {
@@ -46,6 +103,69 @@ impl CaptureHandle {
         }
         None
     }
+
+    /// Restart the ffmpeg of every stream that exited or has written no
+    /// audio for two segment lengths, and report each stream's state.
+    pub fn watch_streams(&mut self, config: &Config) -> Vec<StreamStatus> {
+        let stream_dir = config.stream_data_dir();
+        let now = SystemTime::now();
+        self.streams
+            .iter_mut()
+            .map(|stream| {
+                let last_write = newest_segment_mtime(&stream_dir, stream.index);
+                let last_audio = last_write.unwrap_or(stream.spawned_at).max(stream.spawned_at);
+                let exited = match stream.child.as_mut().map(|c| c.try_wait()) {
+                    Some(Ok(None)) => None,
+                    Some(Ok(Some(status))) => Some(format!("ffmpeg exited with {status}")),
+                    Some(Err(e)) => Some(format!("cannot check ffmpeg: {e}")),
+                    None => Some("ffmpeg is not running".to_string()),
+                };
+                let reason = exited.or_else(|| {
+                    is_stalled(last_audio, now, config.recording_length).then(|| {
+                        format!(
+                            "no audio for {}s",
+                            now.duration_since(last_audio).unwrap_or_default().as_secs()
+                        )
+                    })
+                });
+
+                if let Some(reason) = reason {
+                    warn!(
+                        "RTSP stream {} ({}): {reason} — restarting ffmpeg",
+                        stream.index,
+                        redact_url(&stream.url)
+                    );
+                    if let Some(mut child) = stream.child.take() {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    stream.restarts += 1;
+                    stream.last_restart = Some(chrono::Utc::now().to_rfc3339());
+                    stream.spawned_at = now;
+                    match spawn_rtsp(config, stream.index, &stream.url) {
+                        Ok(child) => stream.child = Some(child),
+                        Err(e) => tracing::error!("{e:#}"),
+                    }
+                }
+
+                let state = if stream.child.is_none() {
+                    StreamState::Down
+                } else if last_write.is_some_and(|t| t > stream.spawned_at) {
+                    StreamState::Recording
+                } else {
+                    StreamState::Starting
+                };
+                StreamStatus {
+                    index: stream.index,
+                    url: redact_url(&stream.url),
+                    state,
+                    last_audio_secs: now.duration_since(last_audio).unwrap_or_default().as_secs(),
+                    restarts: stream.restarts,
+                    last_restart: stream.last_restart.clone(),
+                }
+            })
+            .collect()
+    }
 }
 
 /// Start the audio capture pipeline according to the config.
@@ -53,78 +173,151 @@ pub fn start(config: &Config) -> Result<CaptureHandle> {
     std::fs::create_dir_all(config.stream_data_dir())
         .context("Cannot create StreamData directory")?;
 
-    if !config.rtsp_streams.is_empty() {
+    if let Some(spec) = config.udp_listen.as_deref() {
+        start_udp(&UdpListenSpec::parse(spec)?, config)
+    } else if !config.rtsp_streams.is_empty() {
         start_rtsp(config)
     } else {
         start_microphone(config)
     }
 }
 
+/// `ffmpeg` with the timezone its segment names are formatted in.
+fn ffmpeg_command(config: &Config) -> Command {
+    let mut cmd = Command::new("ffmpeg");
+    if config.utc_file_names {
+        cmd.env("TZ", "UTC");
+    }
+    cmd
+}
+
 // ── RTSP via ffmpeg ──────────────────────────────────────────────────────
 
 fn start_rtsp(config: &Config) -> Result<CaptureHandle> {
-    let mut children = Vec::new();
+    let mut streams = Vec::new();
 
     for (i, url) in config.rtsp_streams.iter().enumerate() {
-        let stream_idx = i + 1;
-        let output_pattern = config
-            .stream_data_dir()
-            .join(format!("%F-birdnet-RTSP_{stream_idx}-%H:%M:%S.wav"));
-
-        let timeout_args = if url.starts_with("rtsp://") || url.starts_with("rtsps://") {
-            vec!["-timeout".to_string(), "10000000".to_string()]
-        } else if url.contains("://") {
-            vec!["-rw_timeout".to_string(), "10000000".to_string()]
-        } else {
-            vec![]
-        };
+        let index = i + 1;
+        let child = spawn_rtsp(config, index, url)?;
+        streams.push(RtspStream {
+            index,
+            url: url.clone(),
+            child: Some(child),
+            spawned_at: SystemTime::now(),
+            restarts: 0,
+            last_restart: None,
+        });
+    }
 
-        let mut cmd = Command::new("ffmpeg");
-        cmd.args(["-hide_banner", "-loglevel", "error", "-nostdin"]);
-        for arg in &timeout_args {
-            cmd.arg(arg);
-        }
-        cmd.args([
-            "-i",
-            url,
-            "-vn",
-            "-map",
-            "a:0",
-            "-acodec",
-            "pcm_s16le",
-            "-ac",
-            "2",
-            "-ar",
-            "48000",
-            "-f",
-            "segment",
-            "-segment_format",
-            "wav",
-            "-segment_time",
-            &config.recording_length.to_string(),
-            "-strftime",
-            "1",
-        ]);
-        cmd.arg(output_pattern.to_str().unwrap());
-        cmd.stdout(Stdio::null()).stderr(Stdio::piped());
-
-        let child = cmd
-            .spawn()
-            .with_context(|| format!("Failed to spawn ffmpeg for stream {stream_idx}: {url}"))?;
-
-        info!("ffmpeg started for RTSP stream {stream_idx}: {url}");
-        children.push(child);
-    }
-
-    Ok(CaptureHandle { children })
+    Ok(CaptureHandle {
+        children: Vec::new(),
+        streams,
+    })
+}
+
+/// Spawn the ffmpeg recording stream `stream_idx` (1-based) from `url`.
+fn spawn_rtsp(config: &Config, stream_idx: usize, url: &str) -> Result<Child> {
+    let output_pattern = config
+        .stream_data_dir()
+        .join(config.segment_name_pattern(&format!("RTSP_{stream_idx}-")));
+
+    let timeout_args = if url.starts_with("rtsp://") || url.starts_with("rtsps://") {
+        vec!["-timeout".to_string(), "10000000".to_string()]
+    } else if url.contains("://") {
+        vec!["-rw_timeout".to_string(), "10000000".to_string()]
+    } else {
+        vec![]
+    };
+
+    let mut cmd = ffmpeg_command(config);
+    cmd.args(["-hide_banner", "-loglevel", "error", "-nostdin"]);
+    for arg in &timeout_args {
+        cmd.arg(arg);
+    }
+    cmd.args([
+        "-i",
+        url,
+        "-vn",
+        "-map",
+        "a:0",
+        "-acodec",
+        "pcm_s16le",
+        "-ac",
+        "2",
+        "-ar",
+        "48000",
+        "-f",
+        "segment",
+        "-segment_format",
+        "wav",
+        "-segment_time",
+        &config.recording_length.to_string(),
+        "-strftime",
+        "1",
+    ]);
+    cmd.arg(output_pattern.to_str().unwrap());
+    cmd.stdout(Stdio::null()).stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().with_context(|| {
+        format!("Failed to spawn ffmpeg for stream {stream_idx}: {}", redact_url(url))
+    })?;
+
+    // Drain stderr so a chatty stream cannot fill the pipe and block ffmpeg.
+    if let Some(stderr) = child.stderr.take() {
+        std::thread::Builder::new()
+            .name(format!("ffmpeg-rtsp{stream_idx}-stderr"))
+            .spawn(move || {
+                for line in BufReader::new(stderr).lines() {
+                    match line {
+                        Ok(l) if l.is_empty() => {}
+                        Ok(l) => warn!("[ffmpeg-rtsp{stream_idx}] {l}"),
+                        Err(_) => break,
+                    }
+                }
+                debug!("ffmpeg-rtsp{stream_idx} stderr stream ended");
+            })
+            .ok();
+    }
+
+    info!("ffmpeg started for RTSP stream {stream_idx}: {}", redact_url(url));
+    Ok(child)
+}
+
+/// Modification time of the newest segment of stream `stream_idx`
+/// (including the one ffmpeg is still writing).
+fn newest_segment_mtime(dir: &Path, stream_idx: usize) -> Option<SystemTime> {
+    let tag = format!("-RTSP_{stream_idx}-");
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().contains(&tag))
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Whether a stream that last wrote audio at `last_audio` has stalled:
+/// nothing for two segment lengths.
+fn is_stalled(last_audio: SystemTime, now: SystemTime, segment_secs: u32) -> bool {
+    let limit = Duration::from_secs(u64::from(segment_secs.max(1)) * 2);
+    now.duration_since(last_audio).is_ok_and(|idle| idle > limit)
+}
+
+/// `url` without the `user:password@` part, for logs and the status API.
+fn redact_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{scheme}://{}", &rest[at + 1..]),
+        None => url.to_string(),
+    }
 }
 
 // ── Local microphone via ffmpeg (ALSA input) ────────────────────────────
 
 fn start_microphone(config: &Config) -> Result<CaptureHandle> {
-    let output_pattern = config
-        .stream_data_dir()
-        .join("%F-birdnet-%H:%M:%S.wav");
+    let output_pattern = config.stream_data_dir().join(config.segment_name_pattern(""));
 
     // Symbolic ALSA card name (e.g. "hw:CARD=iCE,DEV=0") resolved via
     // /proc/asound which is bind-mounted into the container.
@@ -133,7 +326,7 @@ fn start_microphone(config: &Config) -> Result<CaptureHandle> {
     let channels = config.channels.to_string();
     let seg_time = config.recording_length.to_string();
 
-    let mut cmd = Command::new("ffmpeg");
+    let mut cmd = ffmpeg_command(config);
     cmd.args([
         "-hide_banner",
         "-loglevel", "error",
@@ -202,5 +395,244 @@ fn start_microphone(config: &Config) -> Result<CaptureHandle> {
 
     Ok(CaptureHandle {
         children: vec![child],
+        streams: Vec::new(),
+    })
+}
+
+// ── Raw PCM over UDP / RTP via ffmpeg ───────────────────────────────────
+
+/// Parsed `UDP_LISTEN` value.
+///
+/// Format: `[udp://|rtp://]HOST:PORT[,format=s16le][,rate=48000][,channels=1]`.
+/// Plain UDP expects headerless PCM datagrams; `rtp://` expects RTP
+/// packets carrying linear PCM (L16/L24), described to ffmpeg via a
+/// generated SDP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UdpListenSpec {
+    pub protocol: UdpProtocol,
+    pub addr: String,
+    /// ffmpeg raw sample format of the incoming stream (e.g. `s16le`).
+    pub format: String,
+    pub rate: u32,
+    /// Channel count; `None` falls back to `CHANNELS` from the config.
+    pub channels: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UdpProtocol {
+    Udp,
+    Rtp,
+}
+
+impl UdpListenSpec {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.split(',').map(str::trim);
+        let target = parts.next().filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("UDP_LISTEN is empty"))?;
+
+        let (protocol, addr) = if let Some(rest) = target.strip_prefix("rtp://") {
+            (UdpProtocol::Rtp, rest)
+        } else if let Some(rest) = target.strip_prefix("udp://") {
+            (UdpProtocol::Udp, rest)
+        } else {
+            (UdpProtocol::Udp, target)
+        };
+        if addr.rsplit_once(':').and_then(|(_, p)| p.parse::<u16>().ok()).is_none() {
+            anyhow::bail!("UDP_LISTEN address must be HOST:PORT, got {addr:?}");
+        }
+
+        let mut out = UdpListenSpec {
+            protocol,
+            addr: addr.to_string(),
+            format: "s16le".into(),
+            rate: 48000,
+            channels: None,
+        };
+
+        for opt in parts.filter(|s| !s.is_empty()) {
+            let (key, val) = opt
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Bad UDP_LISTEN option {opt:?} (expected key=value)"))?;
+            match key.trim() {
+                "format" => out.format = val.trim().to_ascii_lowercase(),
+                "rate" => {
+                    out.rate = val.trim().parse()
+                        .with_context(|| format!("Bad UDP_LISTEN rate {val:?}"))?
+                }
+                "channels" => {
+                    out.channels = Some(val.trim().parse()
+                        .with_context(|| format!("Bad UDP_LISTEN channels {val:?}"))?)
+                }
+                other => anyhow::bail!("Unknown UDP_LISTEN option {other:?}"),
+            }
+        }
+
+        if out.protocol == UdpProtocol::Rtp && rtp_encoding(&out.format).is_none() {
+            anyhow::bail!(
+                "RTP input only supports s16be/s24be (L16/L24), got format={}",
+                out.format
+            );
+        }
+
+        Ok(out)
+    }
+}
+
+/// RTP payload encoding name for a raw sample format (RFC 3551 linear PCM
+/// is always network byte order).
+fn rtp_encoding(format: &str) -> Option<&'static str> {
+    match format {
+        "s16be" => Some("L16"),
+        "s24be" => Some("L24"),
+        _ => None,
+    }
+}
+
+fn start_udp(spec: &UdpListenSpec, config: &Config) -> Result<CaptureHandle> {
+    let output_pattern = config
+        .stream_data_dir()
+        .join(config.segment_name_pattern("UDP_1-"));
+
+    let channels = spec.channels.unwrap_or(config.channels);
+    let rate = spec.rate.to_string();
+    let seg_time = config.recording_length.to_string();
+
+    let mut cmd = ffmpeg_command(config);
+    cmd.args(["-hide_banner", "-loglevel", "error", "-nostdin"]);
+
+    match spec.protocol {
+        UdpProtocol::Udp => {
+            cmd.args([
+                "-f", &spec.format,
+                "-ar", &rate,
+                "-ac", &channels.to_string(),
+                "-i",
+            ]);
+            // Generous socket buffer – 192 kHz streams arrive fast and
+            // dropped datagrams show up as clicks in the recording.
+            cmd.arg(format!("udp://{}?listen=1&fifo_size=1000000&overrun_nonfatal=1", spec.addr));
+        }
+        UdpProtocol::Rtp => {
+            let sdp_path = config.stream_data_dir().join(".udp_listen.sdp");
+            std::fs::write(&sdp_path, rtp_sdp(spec, channels))
+                .with_context(|| format!("Cannot write {}", sdp_path.display()))?;
+            cmd.args(["-protocol_whitelist", "file,udp,rtp", "-i"]);
+            cmd.arg(&sdp_path);
+        }
+    }
+
+    // Keep the native sample rate: ultrasonic front-ends need the full
+    // bandwidth, and the processing side resamples per model anyway.
+    cmd.args([
+        "-acodec", "pcm_s16le",
+        "-f", "segment",
+        "-segment_format", "wav",
+        "-segment_time", &seg_time,
+        "-strftime", "1",
+    ]);
+    cmd.arg(output_pattern.to_str().unwrap());
+    cmd.stdout(Stdio::null()).stderr(Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to spawn ffmpeg for UDP listener on {}", spec.addr))?;
+
+    if let Some(stderr) = child.stderr.take() {
+        std::thread::Builder::new()
+            .name("ffmpeg-udp-stderr".into())
+            .spawn(move || {
+                let reader = BufReader::new(stderr);
+                for line in reader.lines() {
+                    match line {
+                        Ok(l) if l.is_empty() => {}
+                        Ok(l) => warn!("[ffmpeg-udp] {l}"),
+                        Err(_) => break,
+                    }
+                }
+                debug!("ffmpeg-udp stderr stream ended");
+            })
+            .ok();
+    }
+
+    info!(
+        "ffmpeg {:?} listener started on {} (pid={}, format={}, rate={}, channels={})",
+        spec.protocol,
+        spec.addr,
+        child.id(),
+        spec.format,
+        spec.rate,
+        channels
+    );
+
+    Ok(CaptureHandle {
+        children: vec![child],
+        streams: Vec::new(),
     })
 }
+
+/// Minimal SDP describing a single linear-PCM RTP stream on dynamic
+/// payload type 96.
+fn rtp_sdp(spec: &UdpListenSpec, channels: u16) -> String {
+    let (host, port) = spec.addr.rsplit_once(':').unwrap_or((spec.addr.as_str(), "5004"));
+    let encoding = rtp_encoding(&spec.format).unwrap_or("L16");
+    format!(
+        "v=0\r\n\
+         o=- 0 0 IN IP4 {host}\r\n\
+         s=gaia-capture\r\n\
+         c=IN IP4 {host}\r\n\
+         t=0 0\r\n\
+         m=audio {port} RTP/AVP 96\r\n\
+         a=rtpmap:96 {encoding}/{}/{channels}\r\n",
+        spec.rate
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_udp_listen_full() {
+        let spec = UdpListenSpec::parse("0.0.0.0:5004,format=s16le,rate=192000").unwrap();
+        assert_eq!(spec.protocol, UdpProtocol::Udp);
+        assert_eq!(spec.addr, "0.0.0.0:5004");
+        assert_eq!(spec.format, "s16le");
+        assert_eq!(spec.rate, 192000);
+        assert_eq!(spec.channels, None);
+    }
+
+    #[test]
+    fn test_parse_udp_listen_rtp() {
+        let spec = UdpListenSpec::parse("rtp://0.0.0.0:5004,format=s16be,channels=2").unwrap();
+        assert_eq!(spec.protocol, UdpProtocol::Rtp);
+        assert_eq!(spec.rate, 48000);
+        assert_eq!(spec.channels, Some(2));
+        assert!(rtp_sdp(&spec, 2).contains("a=rtpmap:96 L16/48000/2"));
+    }
+
+    #[test]
+    fn test_is_stalled() {
+        let now = SystemTime::now();
+        assert!(!is_stalled(now - Duration::from_secs(29), now, 15));
+        assert!(is_stalled(now - Duration::from_secs(31), now, 15));
+        // A clock step backwards is not a stall.
+        assert!(!is_stalled(now + Duration::from_secs(60), now, 15));
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("rtsp://admin:p@ss@192.168.1.20:554/stream1"),
+            "rtsp://192.168.1.20:554/stream1"
+        );
+        assert_eq!(redact_url("rtsp://cam.local/live"), "rtsp://cam.local/live");
+        assert_eq!(redact_url("http://host/a@b"), "http://host/a@b");
+    }
+
+    #[test]
+    fn test_parse_udp_listen_rejects_bad_input() {
+        assert!(UdpListenSpec::parse("0.0.0.0").is_err());
+        assert!(UdpListenSpec::parse("0.0.0.0:5004,bitrate=1").is_err());
+        assert!(UdpListenSpec::parse("rtp://0.0.0.0:5004,format=f32le").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! ALSA capture-device enumeration (`arecord -l`).
+//!
+//! Lets the web UI offer a microphone dropdown instead of asking users to
+//! shell into the host and work out a `REC_CARD` value themselves.
+
+use anyhow::{bail, Context, Result};
+
+use gaia_common::protocol::AudioDevice;
+
+/// Run `arecord -l` and parse its output.
+pub async fn list() -> Result<Vec<AudioDevice>> {
+    let out = tokio::process::Command::new("arecord")
+        .arg("-l")
+        .output()
+        .await
+        .context("Cannot run arecord (is alsa-utils installed?)")?;
+    // arecord exits non-zero when there are no cards at all; that is an
+    // empty list, not an error.
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    if !out.status.success() && !stdout.contains("card ") {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        if stderr.contains("no soundcards found") {
+            return Ok(Vec::new());
+        }
+        bail!("arecord -l failed: {}", stderr.trim());
+    }
+    Ok(parse_arecord_list(&stdout))
+}
+
+/// Parse the device lines of `arecord -l`:
+///
+/// ```text
+/// card 1: iCE [Blue Snowball iCE], device 0: USB Audio [USB Audio]
+/// ```
+///
+/// Subdevice lines and headers are ignored.
+pub fn parse_arecord_list(output: &str) -> Vec<AudioDevice> {
+    output.lines().filter_map(parse_device_line).collect()
+}
+
+fn parse_device_line(line: &str) -> Option<AudioDevice> {
+    let rest = line.strip_prefix("card ")?;
+    let (card, rest) = rest.split_once(':')?;
+    let (card_part, device_part) = rest.split_once(", device ")?;
+    let (card_id, card_name) = split_bracketed(card_part)?;
+    let (device, device_part) = device_part.split_once(':')?;
+    let (_, device_name) = split_bracketed(device_part)?;
+
+    let card: u32 = card.trim().parse().ok()?;
+    let device: u32 = device.trim().parse().ok()?;
+    Some(AudioDevice {
+        card,
+        device,
+        rec_card: format!("hw:CARD={card_id},DEV={device}"),
+        card_id,
+        card_name,
+        device_name,
+    })
+}
+
+/// `" iCE [Blue Snowball iCE]"` → `("iCE", "Blue Snowball iCE")`.
+fn split_bracketed(s: &str) -> Option<(String, String)> {
+    let (id, rest) = s.trim().split_once(" [")?;
+    let name = rest.strip_suffix(']')?;
+    Some((id.trim().to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arecord_list() {
+        let out = "\
+**** List of CAPTURE Hardware Devices ****
+card 0: PCH [HDA Intel PCH], device 0: ALC3246 Analog [ALC3246 Analog]
+  Subdevices: 1/1
+  Subdevice #0: subdevice #0
+card 1: iCE [Blue Snowball iCE], device 0: USB Audio [USB Audio]
+  Subdevices: 0/1
+  Subdevice #0: subdevice #0
+";
+        let devices = parse_arecord_list(out);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].card, 0);
+        assert_eq!(devices[0].card_name, "HDA Intel PCH");
+        assert_eq!(devices[0].device_name, "ALC3246 Analog");
+        assert_eq!(devices[1].card_id, "iCE");
+        assert_eq!(devices[1].rec_card, "hw:CARD=iCE,DEV=0");
+    }
+
+    #[test]
+    fn test_parse_ignores_noise() {
+        assert!(parse_arecord_list("arecord: device_list:277: no soundcards found...\n").is_empty());
+        assert!(parse_device_line("card x: broken").is_none());
+    }
+}
@@ -0,0 +1,67 @@
+//! Audio capture for Gaia: recording, segment stamping, disk guard and
+//! the HTTP API that serves recordings to processing nodes.
+//!
+//! The `gaia-capture` binary wires these together; `gaia-processing mono`
+//! (feature `mono`) reuses them to record and analyse in one process.
+
+pub mod capture;
+pub mod devices;
+pub mod disk;
+pub mod ring;
+pub mod selftest;
+pub mod server;
+pub mod stamp;
+pub mod status_led;
+pub mod supervisor;
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+
+/// Shared disk-guard state visible to the HTTP health endpoint.
+#[derive(Debug, Default)]
+pub struct DiskState {
+    /// Current disk usage percentage × 100 (e.g. 9500 = 95.00 %).
+    pub usage_centipct: AtomicU32,
+    /// Current inode usage percentage × 100.
+    pub inode_centipct: AtomicU32,
+    /// `true` while capture is paused because of disk pressure.
+    pub capture_paused: AtomicBool,
+}
+
+impl DiskState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn usage_pct(&self) -> f64 {
+        self.usage_centipct.load(Ordering::Relaxed) as f64 / 100.0
+    }
+
+    pub fn inode_pct(&self) -> f64 {
+        self.inode_centipct.load(Ordering::Relaxed) as f64 / 100.0
+    }
+
+    /// Record a fresh reading.
+    pub fn store(&self, usage: &gaia_common::disk::DiskUsage) {
+        self.usage_centipct
+            .store((usage.space_pct * 100.0) as u32, Ordering::Relaxed);
+        self.inode_centipct.store(
+            (usage.inode_pct.unwrap_or(0.0) * 100.0) as u32,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+/// Input level of the most recent finished segment, measured by the
+/// stamping thread and served by `/api/levels`.
+#[derive(Debug, Default)]
+pub struct LevelState {
+    pub latest: Mutex<Option<gaia_common::protocol::InputLevels>>,
+}
+
+/// Per-stream watchdog results, refreshed by the health thread and
+/// served by `/api/status`.
+#[derive(Debug, Default)]
+pub struct StreamStatusState {
+    pub streams: Mutex<Vec<gaia_common::protocol::StreamStatus>>,
+}
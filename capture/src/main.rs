@@ -7,70 +7,103 @@
 //!    (`DISK_USAGE_MAX`, default 95 %), it first recodes settled WAV
 //!    files to Opus to free space, and only pauses capture if that is
 //!    insufficient. Capture resumes automatically once space is freed.
-//! 4. Runs an axum HTTP server that exposes the recordings to the
+//! 4. Stamps each finished segment with its exact start time and the
+//!    node name (a BWF `bext` chunk), so processing does not have to
+//!    trust the filename, and measures its input level (RMS / peak /
+//!    clipping) for `/api/levels`.
+//! 5. Watches RTSP streams and restarts any ffmpeg that stops writing
+//!    audio (see `/api/status`).
+//! 6. In ring-buffer mode (`RING_BUFFER_SECS`), records into RAM and
+//!    drops segments that fall out of the window; the processing server
+//!    moves the ones worth keeping to disk.
+//! 7. Drives an optional status LED / fault relay on GPIO pins
+//!    (`STATUS_LED_PIN`, `FAULT_RELAY_PIN`, feature `gpio`).
+//! 8. Runs an axum HTTP server that exposes the recordings to the
 //!    processing server over the network.
 
-mod capture;
-mod disk;
-mod server;
-
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
 use tracing::info;
 
+use gaia_capture::{
+    disk, ring, server, stamp, status_led, supervisor, DiskState, LevelState, StreamStatusState,
+};
+
 static SHUTDOWN: AtomicBool = AtomicBool::new(false);
 
-/// Shared disk-guard state visible to the HTTP health endpoint.
-#[derive(Debug)]
-pub struct DiskState {
-    /// Current disk usage percentage × 100 (e.g. 9500 = 95.00 %).
-    pub usage_centipct: AtomicU32,
-    /// `true` while capture is paused because of disk pressure.
-    pub capture_paused: AtomicBool,
+/// Without a subcommand the server runs with the config path given as
+/// the only argument, which is how the container image starts it.
+#[derive(Debug, Parser)]
+#[command(name = "gaia-capture", version, about = "Gaia Audio capture server")]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// Config file [default: /etc/gaia/gaia.conf]
+    config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-impl DiskState {
-    pub fn new() -> Self {
-        Self {
-            usage_centipct: AtomicU32::new(0),
-            capture_paused: AtomicBool::new(false),
-        }
-    }
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the capture server (the default)
+    Run {
+        /// Config file [default: /etc/gaia/gaia.conf]
+        config: Option<PathBuf>,
+    },
+    /// Check the config without starting capture
+    CheckConfig {
+        /// Config file [default: /etc/gaia/gaia.conf]
+        #[arg(short, long, env = "GAIA_CONFIG")]
+        config: Option<PathBuf>,
+        #[arg(long)]
+        json: bool,
+    },
+}
 
-    pub fn usage_pct(&self) -> f64 {
-        self.usage_centipct.load(Ordering::Relaxed) as f64 / 100.0
-    }
+fn config_or_default(path: Option<PathBuf>) -> PathBuf {
+    path.unwrap_or_else(|| PathBuf::from(gaia_common::config::Config::default_path()))
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Logs go to stderr so `--json` output on stdout stays parseable.
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "info".into()),
         )
+        .with_writer(std::io::stderr)
         .init();
 
+    let args = Cli::parse();
+    let config_path = match args.command {
+        None => config_or_default(args.config),
+        Some(Command::Run { config }) => config_or_default(config),
+        Some(Command::CheckConfig { config, json }) => {
+            if !check_config(&config_or_default(config), json)? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+    };
+
     if std::env::var("RUST_LOG").map_or(false, |v| v.contains("debug")) {
         info!("🔍 Debug logging ENABLED (RUST_LOG={})", std::env::var("RUST_LOG").unwrap_or_default());
     }
 
     // ── load config ──────────────────────────────────────────────────
-    let config_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| gaia_common::config::Config::default_path().to_string());
-    let config =
-        gaia_common::config::load(&PathBuf::from(&config_path)).context("Config load failed")?;
+    let config = gaia_common::config::load(&config_path).context("Config load failed")?;
 
     info!(
         "Gaia Capture Server starting (listen={}, disk_max={}%)",
         config.capture_listen_addr, config.disk_usage_max,
     );
 
-    // Ensure StreamData directory exists
+    // Ensure StreamData directory (or the ring buffer) exists
     std::fs::create_dir_all(config.stream_data_dir())
         .context("Cannot create StreamData directory")?;
 
@@ -81,57 +114,47 @@ async fn main() -> Result<()> {
     }
 
     // ── ctrl-c ───────────────────────────────────────────────────────
+    // The first signal starts a graceful shutdown: the HTTP server stops,
+    // ffmpeg gets SIGTERM so the segment being written is closed properly,
+    // and that last segment is still stamped.  A second signal exits now.
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_signal = shutdown.clone();
     ctrlc::set_handler(move || {
-        SHUTDOWN.store(true, Ordering::Relaxed);
-        info!("Shutdown signal received");
-        std::process::exit(0);
+        if SHUTDOWN.swap(true, Ordering::Relaxed) {
+            tracing::warn!("Second shutdown signal — exiting immediately");
+            std::process::exit(130);
+        }
+        info!("Shutdown signal received — finishing the current segment");
+        shutdown_signal.store(true, Ordering::Relaxed);
     })
     .context("Cannot set Ctrl-C handler")?;
 
     // ── start capture (with retries) ──────────────────────────────────
-    const MAX_CAPTURE_RETRIES: u32 = 5;
-    const CAPTURE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
-
     let skip_capture = std::env::var("GAIA_SKIP_CAPTURE")
         .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
         .unwrap_or(false);
 
-    let mut capture_handle: Option<capture::CaptureHandle> = None;
-    if skip_capture {
+    let capture_handle = if skip_capture {
         info!(
             "GAIA_SKIP_CAPTURE set — starting in HTTP-only mode with any preloaded audio files"
         );
+        None
     } else {
-        for attempt in 1..=MAX_CAPTURE_RETRIES {
-            match capture::start(&config) {
-                Ok(h) => {
-                    info!("Audio capture started on attempt {attempt}");
-                    capture_handle = Some(h);
-                    break;
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        "Audio capture attempt {attempt}/{MAX_CAPTURE_RETRIES} failed: {e:#}"
-                    );
-                    if attempt < MAX_CAPTURE_RETRIES {
-                        tracing::info!(
-                            "Retrying in {}s…",
-                            CAPTURE_RETRY_DELAY.as_secs()
-                        );
-                        std::thread::sleep(CAPTURE_RETRY_DELAY);
-                    }
-                }
-            }
-        }
-        if capture_handle.is_none() {
-            tracing::warn!(
-                "All {MAX_CAPTURE_RETRIES} capture attempts failed. \
-                 HTTP server will run without active capture."
-            );
-        }
-    }
+        supervisor::start_with_retries(&config)
+    };
+
+    // ── node identity ────────────────────────────────────────────────
+    // Stable across restarts, unlike the mDNS instance number.
+    let identity = gaia_common::node_id::load_or_create(
+        &std::env::var("HOSTNAME").unwrap_or_else(|_| "capture".into()),
+    );
 
     // ── mDNS registration ─────────────────────────────────────────────
+    let tls = config.capture_tls_cert.is_some() && config.capture_tls_key.is_some();
+    let mut txt = identity.txt_properties().to_vec();
+    if tls {
+        txt.push(("tls", "1"));
+    }
     let port: u16 = config
         .capture_listen_addr
         .rsplit(':')
@@ -142,6 +165,7 @@ async fn main() -> Result<()> {
     let discovery = match gaia_common::discovery::register(
         gaia_common::discovery::ServiceRole::Capture,
         port,
+        &txt,
     ) {
         Ok(h) => {
             info!("mDNS: registered as {}", h.instance_name());
@@ -155,17 +179,33 @@ async fn main() -> Result<()> {
 
     // ── shared disk-guard state ──────────────────────────────────────
     let disk_state = Arc::new(DiskState::new());
+    let level_state = Arc::new(LevelState::default());
+    let stream_state = Arc::new(StreamStatusState::default());
+
+    // ── clock sync monitor (reported by /api/health) ─────────────────
+    if let Err(e) = gaia_common::timesync::spawn_monitor(config.clock_max_offset_ms) {
+        tracing::warn!("Cannot start the clock sync monitor: {e}");
+    }
 
     // ── start HTTP server ────────────────────────────────────────────
-    let stream_dir = config.stream_data_dir();
-    let listen_addr = config.capture_listen_addr.clone();
-    let shutdown = Arc::new(AtomicBool::new(false));
+    let server_config = config.clone();
     let shutdown_clone = shutdown.clone();
     let disk_state_server = disk_state.clone();
+    let server_identity = identity.clone();
+    let level_state_server = level_state.clone();
+    let stream_state_server = stream_state.clone();
 
     let server_handle = tokio::spawn(async move {
         if let Err(e) =
-            server::run(stream_dir, &listen_addr, shutdown_clone, disk_state_server).await
+            server::run(
+                server_config,
+                shutdown_clone,
+                disk_state_server,
+                level_state_server,
+                stream_state_server,
+                server_identity,
+            )
+            .await
         {
             tracing::error!("HTTP server error: {e:#}");
         }
@@ -173,106 +213,59 @@ async fn main() -> Result<()> {
 
     // ── periodic capture health check + disk guard ───────────────────
     let capture_shutdown = Arc::new(AtomicBool::new(false));
-    let capture_shutdown_clone = capture_shutdown.clone();
-    let disk_state_health = disk_state.clone();
-    let guard_dir = config.stream_data_dir();
-    let disk_max = config.disk_usage_max;
-    // Keep a clone of config for restarting capture after pause.
-    let config_for_restart = config.clone();
-
-    let health_thread = std::thread::Builder::new()
-        .name("capture-health".into())
-        .spawn(move || {
-            while !capture_shutdown_clone.load(Ordering::Relaxed) {
-                std::thread::sleep(std::time::Duration::from_secs(10));
-
-                // ── disk usage check ─────────────────────────────────
-                if let Some(pct) = disk::usage_pct(&guard_dir) {
-                    disk_state_health
-                        .usage_centipct
-                        .store((pct * 100.0) as u32, Ordering::Relaxed);
-
-                    let is_paused = disk_state_health.capture_paused.load(Ordering::Relaxed);
-
-                    if pct >= disk_max && !is_paused {
-                        // ── disk pressure: try emergency recode first ──
-                        tracing::warn!(
-                            "Disk usage {pct:.1}% >= threshold {disk_max}% — \
-                             recoding settled WAV files to Opus before pausing capture"
-                        );
-
-                        let recode = disk::recode_wav_to_opus(
-                            &guard_dir,
-                            std::time::Duration::from_secs(5),
-                        );
-
-                        if recode.converted > 0 {
-                            tracing::warn!(
-                                "Emergency recode: converted {} WAV file(s), freed {:.1} MB",
-                                recode.converted,
-                                recode.freed_bytes as f64 / 1_048_576.0
-                            );
-                            if let Some(after_pct) = disk::usage_pct(&guard_dir) {
-                                disk_state_health
-                                    .usage_centipct
-                                    .store((after_pct * 100.0) as u32, Ordering::Relaxed);
-                                if after_pct < disk_max {
-                                    tracing::info!(
-                                        "Disk usage dropped to {after_pct:.1}% (< {disk_max}%) after recode — capture continues"
-                                    );
-                                    continue;
-                                }
-                            }
-                        }
-
-                        // ── PAUSE: recode insufficient, kill capture ───
-                        tracing::warn!(
-                            "Disk still above threshold after recode — pausing audio capture to prevent filling the disk"
-                        );
-                        if let Some(ref mut h) = capture_handle {
-                            if let Err(e) = h.kill() {
-                                tracing::error!("Failed to kill capture: {e:#}");
-                            }
-                        }
-                        capture_handle = None;
-                        disk_state_health
-                            .capture_paused
-                            .store(true, Ordering::Relaxed);
-                    } else if pct < disk_max && is_paused {
-                        // ── RESUME: restart capture ──────────────────
-                        tracing::info!(
-                            "Disk usage {pct:.1}% < threshold {disk_max}% — \
-                             resuming audio capture"
-                        );
-                        match capture::start(&config_for_restart) {
-                            Ok(h) => {
-                                capture_handle = Some(h);
-                                disk_state_health
-                                    .capture_paused
-                                    .store(false, Ordering::Relaxed);
-                                tracing::info!("Audio capture resumed");
-                            }
-                            Err(e) => {
-                                tracing::error!(
-                                    "Failed to restart capture after disk-free: {e:#}"
-                                );
-                            }
-                        }
-                    }
-                }
 
-                // ── ffmpeg liveness check ────────────────────────────
-                if let Some(ref mut h) = capture_handle {
-                    if let Some(msg) = h.check_alive() {
-                        tracing::error!(
-                            "{msg}. Recording has stopped — check audio device and restart."
-                        );
-                        break;
-                    }
-                }
-            }
-        })
-        .ok();
+    // ── start-time stamping of finished segments ─────────────────────
+    // Not in HTTP-only mode: the mtime of preloaded files says nothing
+    // about when they were recorded.
+    let node = identity.name.clone();
+    let stamp_dir = config.stream_data_dir();
+    // Stopped only after capture, so the final segment is stamped too.
+    let stamp_shutdown = Arc::new(AtomicBool::new(false));
+    let stamp_shutdown_clone = stamp_shutdown.clone();
+    let stamp_thread = if skip_capture {
+        None
+    } else {
+        std::thread::Builder::new()
+            .name("wav-stamp".into())
+            .spawn(move || {
+                stamp::stamp_loop(stamp_dir, node, level_state, None, stamp_shutdown_clone)
+            })
+            .ok()
+    };
+
+    // ── ring buffer eviction ─────────────────────────────────────────
+    let ring_thread = if config.ring_buffer() {
+        let dir = config.stream_data_dir();
+        let window = std::time::Duration::from_secs(config.ring_buffer_secs.into());
+        let ring_shutdown = capture_shutdown.clone();
+        std::thread::Builder::new()
+            .name("ring-buffer".into())
+            .spawn(move || ring::evict_loop(dir, window, ring_shutdown))
+            .ok()
+    } else {
+        None
+    };
+
+    // ── status LED / fault relay ─────────────────────────────────────
+    let led_thread = if skip_capture {
+        None
+    } else {
+        let led_config = config.clone();
+        let led_disk = disk_state.clone();
+        let led_shutdown = capture_shutdown.clone();
+        std::thread::Builder::new()
+            .name("status-led".into())
+            .spawn(move || status_led::run(&led_config, led_disk, led_shutdown))
+            .ok()
+    };
+
+    let health_thread = supervisor::spawn_health(
+        config.clone(),
+        capture_handle,
+        disk_state,
+        stream_state,
+        capture_shutdown.clone(),
+    );
 
     // Wait for the server task (runs until shutdown)
     let _ = server_handle.await;
@@ -282,6 +275,16 @@ async fn main() -> Result<()> {
     if let Some(t) = health_thread {
         t.join().ok();
     }
+    if let Some(t) = ring_thread {
+        t.join().ok();
+    }
+    if let Some(t) = led_thread {
+        t.join().ok();
+    }
+    stamp_shutdown.store(true, Ordering::Relaxed);
+    if let Some(t) = stamp_thread {
+        t.join().ok();
+    }
     if let Some(dh) = discovery {
         dh.shutdown();
     }
@@ -289,3 +292,40 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Report the config problems (`Config::problems` plus the capture-only
+/// settings); `false` when there are any.
+fn check_config(path: &Path, json: bool) -> Result<bool> {
+    let problems = match gaia_common::config::load(path) {
+        Ok(config) => {
+            let mut problems = config.problems();
+            if let Some(spec) = config.udp_listen.as_deref() {
+                if let Err(e) = gaia_capture::capture::UdpListenSpec::parse(spec) {
+                    problems.push(format!("UDP_LISTEN: {e:#}"));
+                }
+            }
+            let port = config.capture_listen_addr.rsplit_once(':').map(|(_, p)| p);
+            if port.and_then(|p| p.parse::<u16>().ok()).is_none() {
+                problems.push(format!(
+                    "CAPTURE_LISTEN_ADDR={} is not HOST:PORT",
+                    config.capture_listen_addr
+                ));
+            }
+            problems
+        }
+        Err(e) => vec![format!("{e:#}")],
+    };
+    let ok = problems.is_empty();
+    if json {
+        let report = serde_json::json!({ "config": path, "ok": ok, "problems": problems });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if ok {
+        println!("✅ {} is valid", path.display());
+    } else {
+        println!("❌ {} has {} problem(s):", path.display(), problems.len());
+        for p in &problems {
+            println!("  - {p}");
+        }
+    }
+    Ok(ok)
+}
@@ -0,0 +1,162 @@
+//! RAM ring buffer for segments (`RING_BUFFER_SECS`).
+//!
+//! On a 24/7 node every segment written to the SD card and deleted again
+//! minutes later wears it out for nothing.  In ring-buffer mode ffmpeg
+//! writes to a tmpfs directory instead ([`Config::stream_data_dir`]) and
+//! this thread drops segments once they are older than the window,
+//! whether or not a processing node has fetched them.  Segments the
+//! processing node finds interesting are moved to `KEPT_DIR` on disk
+//! through `POST /api/recordings/{name}/keep` ([`keep`]); everything
+//! else only ever lives in RAM.
+//!
+//! [`Config::stream_data_dir`]: gaia_common::config::Config::stream_data_dir
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use tracing::{debug, info, warn};
+
+const POLL: Duration = Duration::from_secs(1);
+
+/// Dropped segments are reported at most this often, so a processing
+/// node that is down for hours does not flood the log.
+const WARN_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Drop segments in `dir` last written more than `window` ago, every
+/// second until `shutdown` is set.
+pub fn evict_loop(dir: PathBuf, window: Duration, shutdown: Arc<AtomicBool>) {
+    info!(
+        "Ring buffer: keeping the last {}s of audio in {}",
+        window.as_secs(),
+        dir.display()
+    );
+    let mut unreported = 0;
+    let mut last_warning: Option<Instant> = None;
+    while !shutdown.load(Ordering::Relaxed) {
+        std::thread::sleep(POLL);
+        // Analysed segments are deleted by the processing node, so
+        // anything left this long was never fetched.
+        unreported += evict(&dir, window, SystemTime::now());
+        if unreported > 0 && last_warning.is_none_or(|t| t.elapsed() >= WARN_INTERVAL) {
+            warn!(
+                "Ring buffer: dropped {unreported} segment(s) older than {}s before they were analysed",
+                window.as_secs()
+            );
+            unreported = 0;
+            last_warning = Some(Instant::now());
+        }
+    }
+}
+
+/// Remove the segments in `dir` that fell out of the window; returns how
+/// many were removed.
+fn evict(dir: &Path, window: Duration, now: SystemTime) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let segments: Vec<(PathBuf, SystemTime)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_segment(p))
+        .filter_map(|p| {
+            let modified = p.metadata().and_then(|m| m.modified()).ok()?;
+            Some((p, modified))
+        })
+        .collect();
+    let mut evicted = 0;
+    for path in expired(segments, window, now) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                debug!("Ring buffer: dropped {}", path.display());
+                evicted += 1;
+            }
+            Err(e) => debug!("Ring buffer: cannot drop {}: {e}", path.display()),
+        }
+    }
+    evicted
+}
+
+/// The segments last written before `now - window`.
+fn expired(
+    segments: Vec<(PathBuf, SystemTime)>,
+    window: Duration,
+    now: SystemTime,
+) -> Vec<PathBuf> {
+    let Some(cutoff) = now.checked_sub(window) else {
+        return Vec::new();
+    };
+    segments
+        .into_iter()
+        .filter(|(_, modified)| *modified < cutoff)
+        .map(|(path, _)| path)
+        .collect()
+}
+
+fn is_segment(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("wav") | Some("opus")
+    )
+}
+
+/// Move the segment at `path` out of the ring buffer into `kept_dir`,
+/// returning its new path.  Copied under a temporary name first, since
+/// tmpfs and the disk are different filesystems and a half-copied file
+/// must not look like a kept recording.
+pub fn keep(path: &Path, kept_dir: &Path) -> std::io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "not a file"))?;
+    std::fs::create_dir_all(kept_dir)?;
+    let target = kept_dir.join(name);
+    let partial = kept_dir.join(format!(".{}.part", name.to_string_lossy()));
+    std::fs::copy(path, &partial)?;
+    std::fs::rename(&partial, &target)?;
+    std::fs::remove_file(path)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expired_segments() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let at = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let segments = vec![
+            (PathBuf::from("a.wav"), at(800)),
+            (PathBuf::from("b.wav"), at(879)),
+            (PathBuf::from("c.wav"), at(880)),
+            (PathBuf::from("d.wav"), at(990)),
+        ];
+        let window = Duration::from_secs(120);
+        assert_eq!(
+            expired(segments.clone(), window, now),
+            vec![PathBuf::from("a.wav"), PathBuf::from("b.wav")]
+        );
+        assert!(expired(segments, Duration::from_secs(2_000), now).is_empty());
+    }
+
+    #[test]
+    fn test_keep_moves_segment() {
+        let dir = std::env::temp_dir().join(format!("gaia_ring_test_{}", std::process::id()));
+        let ring = dir.join("ring");
+        let kept = dir.join("kept");
+        std::fs::create_dir_all(&ring).unwrap();
+        let segment = ring.join("2026-05-01-birdnet-08:00:00.wav");
+        std::fs::write(&segment, b"RIFF").unwrap();
+
+        let target = keep(&segment, &kept).unwrap();
+        assert_eq!(target, kept.join("2026-05-01-birdnet-08:00:00.wav"));
+        assert_eq!(std::fs::read(&target).unwrap(), b"RIFF");
+        assert!(!segment.exists());
+        assert_eq!(std::fs::read_dir(&kept).unwrap().count(), 1);
+
+        // The segment left the ring, so the evictor has nothing to drop.
+        assert_eq!(evict(&ring, Duration::ZERO, SystemTime::now()), 0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,468 @@
+//! End-to-end self-test of a station (`POST /api/selftest`).
+//!
+//! A reference signal — `SELFTEST_FILE`, or a generated 1–4 kHz chirp —
+//! is fed into the pipeline and each stage is checked in turn:
+//!
+//!   1. **segment**: in `inject` mode the signal is written into the
+//!      recording directory under a segment name (tag `selftest-`); in
+//!      `play` mode it is played with `aplay` and the next segment
+//!      recorded must carry signal (not flatline).
+//!   2. **picked up**: a processing server fetched and deleted the
+//!      segment.
+//!   3. **detected** (with `SELFTEST_LABEL` and
+//!      `SELFTEST_PROCESSING_URL`): the processing REST API reports a
+//!      detection of that species since the test started.
+//!
+//! The test runs in the background; `GET /api/selftest` shows its
+//! progress and verdict.  The segment is analysed like any other, so a
+//! detection check leaves a real detection behind.
+
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use tracing::{info, warn};
+
+use gaia_common::config::Config;
+use gaia_common::protocol::{
+    LevelStatus, SelfTestMode, SelfTestReport, SelfTestState, SelfTestStep,
+};
+
+use crate::LevelState;
+
+/// Sample rate of the generated chirp, the same as ffmpeg records at.
+const CHIRP_RATE: u32 = 48_000;
+const CHIRP_SECS: f64 = 3.0;
+const CHIRP_FROM_HZ: f64 = 1_000.0;
+const CHIRP_TO_HZ: f64 = 4_000.0;
+
+/// How long a processing server may take to fetch the segment.
+const PICKUP_TIMEOUT: Duration = Duration::from_secs(600);
+/// How long after pickup the detection may take to be stored.
+const DETECTION_TIMEOUT: Duration = Duration::from_secs(180);
+const POLL: Duration = Duration::from_secs(2);
+
+/// Why a self-test could not be started.
+#[derive(Debug)]
+pub enum StartError {
+    /// Another test is still running.
+    Busy,
+    /// The reference signal is missing or unusable.
+    Reference(anyhow::Error),
+}
+
+/// Self-test settings and the latest report, shared by the HTTP
+/// handlers and the running test.
+pub struct SelfTest {
+    stream_dir: PathBuf,
+    /// Strftime pattern of injected segment names.
+    name_pattern: String,
+    utc_names: bool,
+    recording_length: u32,
+    file: Option<PathBuf>,
+    label: Option<String>,
+    processing_url: Option<String>,
+    levels: Arc<LevelState>,
+    latest: Mutex<Option<SelfTestReport>>,
+}
+
+impl SelfTest {
+    pub fn new(config: &Config, stream_dir: PathBuf, levels: Arc<LevelState>) -> Self {
+        Self {
+            stream_dir,
+            name_pattern: config.segment_name_pattern("selftest-"),
+            utc_names: config.utc_file_names,
+            recording_length: config.recording_length,
+            file: config.selftest_file.clone(),
+            label: config.selftest_label.clone(),
+            processing_url: config.selftest_processing_url.clone(),
+            levels,
+            latest: Mutex::new(None),
+        }
+    }
+
+    /// The latest report, `None` before the first test.
+    pub fn latest(&self) -> Option<SelfTestReport> {
+        self.latest.lock().ok().and_then(|r| r.clone())
+    }
+
+    /// Prepare the reference signal and run the test in the background.
+    /// Returns the initial report.
+    pub fn start(self: &Arc<Self>, mode: SelfTestMode) -> Result<SelfTestReport, StartError> {
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        if latest
+            .as_ref()
+            .is_some_and(|r| r.state == SelfTestState::Running)
+        {
+            return Err(StartError::Busy);
+        }
+        let (wav, description) = self.reference().map_err(StartError::Reference)?;
+        let report = SelfTestReport {
+            mode,
+            state: SelfTestState::Running,
+            started_at: Local::now().to_rfc3339(),
+            finished_at: None,
+            recording: None,
+            expected_label: self.label.clone(),
+            steps: vec![SelfTestStep {
+                name: "reference".into(),
+                ok: true,
+                detail: description,
+            }],
+        };
+        *latest = Some(report.clone());
+        drop(latest);
+
+        info!("Self-test started ({mode:?})");
+        let this = Arc::clone(self);
+        tokio::spawn(async move { this.run(mode, wav).await });
+        Ok(report)
+    }
+
+    /// The reference WAV and a description of it.
+    fn reference(&self) -> Result<(Vec<u8>, String)> {
+        match &self.file {
+            Some(path) => {
+                let stats = gaia_common::audio::verify_wav(path, None)
+                    .map_err(|e| anyhow::anyhow!("SELFTEST_FILE {}: {e}", path.display()))?;
+                let wav = std::fs::read(path)
+                    .with_context(|| format!("Cannot read {}", path.display()))?;
+                Ok((
+                    wav,
+                    format!(
+                        "{} ({:.1} s, {} Hz)",
+                        path.display(),
+                        stats.duration_secs,
+                        stats.sample_rate
+                    ),
+                ))
+            }
+            None => {
+                let samples = chirp(CHIRP_RATE, CHIRP_SECS, CHIRP_FROM_HZ, CHIRP_TO_HZ);
+                Ok((
+                    wav_bytes(&samples, CHIRP_RATE)?,
+                    format!("generated {CHIRP_SECS} s chirp, {CHIRP_FROM_HZ}–{CHIRP_TO_HZ} Hz"),
+                ))
+            }
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut SelfTestReport)) {
+        if let Some(report) = self
+            .latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            f(report);
+        }
+    }
+
+    /// Log a step and add it to the report.
+    fn step(&self, name: &str, ok: bool, detail: String) {
+        if ok {
+            info!("Self-test {name}: {detail}");
+        } else {
+            warn!("Self-test {name} failed: {detail}");
+        }
+        self.update(|r| {
+            r.steps.push(SelfTestStep {
+                name: name.into(),
+                ok,
+                detail,
+            });
+        });
+    }
+
+    fn finish(&self, state: SelfTestState) {
+        info!("Self-test finished: {state:?}");
+        self.update(|r| {
+            r.state = state;
+            r.finished_at = Some(Local::now().to_rfc3339());
+        });
+    }
+
+    async fn run(&self, mode: SelfTestMode, wav: Vec<u8>) {
+        let started = Local::now();
+        let state = match self.stages(mode, wav, started).await {
+            Ok(()) => SelfTestState::Passed,
+            Err(e) => {
+                let stage = format!("{e:#}");
+                let (name, detail) = stage.split_once(": ").unwrap_or(("error", &stage));
+                self.step(name, false, detail.to_string());
+                SelfTestState::Failed
+            }
+        };
+        self.finish(state);
+    }
+
+    /// Run every stage; an error is `"<stage>: <reason>"`.
+    async fn stages(
+        &self,
+        mode: SelfTestMode,
+        wav: Vec<u8>,
+        started: DateTime<Local>,
+    ) -> Result<()> {
+        let name = match mode {
+            SelfTestMode::Inject => self.inject(&wav).context("segment")?,
+            SelfTestMode::Play => self.play(&wav).await.context("segment")?,
+        };
+        self.update(|r| r.recording = Some(name.clone()));
+
+        self.wait_pickup(&name).await.context("picked up")?;
+
+        if let (Some(label), Some(url)) = (&self.label, &self.processing_url) {
+            // The segment may have started up to one recording length
+            // before the test; `since` is exclusive.
+            let since = started - chrono::Duration::seconds(self.recording_length as i64 + 1);
+            self.wait_detection(url, label, since)
+                .await
+                .context("detected")?;
+        }
+        Ok(())
+    }
+
+    /// Write the reference into the recording directory as a segment.
+    fn inject(&self, wav: &[u8]) -> Result<String> {
+        let name = if self.utc_names {
+            Utc::now().format(&self.name_pattern).to_string()
+        } else {
+            Local::now().format(&self.name_pattern).to_string()
+        };
+        let path = self.stream_dir.join(&name);
+        // Written under another extension first, so neither the stamping
+        // thread nor the listing sees a partial file.
+        let partial = path.with_extension("part");
+        std::fs::write(&partial, wav)
+            .with_context(|| format!("Cannot write {}", partial.display()))?;
+        let stats = match gaia_common::audio::verify_wav(&partial, None) {
+            Ok(stats) => stats,
+            Err(e) => {
+                let _ = std::fs::remove_file(&partial);
+                bail!("{name} is not a valid recording: {e}");
+            }
+        };
+        std::fs::rename(&partial, &path)
+            .with_context(|| format!("Cannot write {}", path.display()))?;
+        self.step(
+            "segment",
+            true,
+            format!("injected {name} ({:.1} s)", stats.duration_secs),
+        );
+        Ok(name)
+    }
+
+    /// Play the reference and wait for the segment recorded meanwhile.
+    async fn play(&self, wav: &[u8]) -> Result<String> {
+        let file = std::env::temp_dir().join(format!("gaia-selftest-{}.wav", std::process::id()));
+        tokio::fs::write(&file, wav)
+            .await
+            .with_context(|| format!("Cannot write {}", file.display()))?;
+        let played = tokio::process::Command::new("aplay")
+            .arg("-q")
+            .arg(&file)
+            .output()
+            .await;
+        let _ = tokio::fs::remove_file(&file).await;
+        let out = played.context("Cannot run aplay (is alsa-utils installed?)")?;
+        if !out.status.success() {
+            bail!(
+                "aplay failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        let played_at = Local::now();
+
+        // The segment in progress when playback ended is the first one
+        // measured after it.
+        let deadline = Instant::now() + Duration::from_secs(self.recording_length as u64 * 2 + 30);
+        loop {
+            let latest = self.levels.latest.lock().ok().and_then(|l| l.clone());
+            if let Some(levels) = latest.filter(|l| {
+                DateTime::parse_from_rfc3339(&l.measured_at).is_ok_and(|t| t > played_at)
+            }) {
+                if levels.status == LevelStatus::Flatline {
+                    bail!(
+                        "{} recorded no signal — is the microphone connected?",
+                        levels.filename
+                    );
+                }
+                self.step(
+                    "segment",
+                    true,
+                    format!(
+                        "recorded {} ({:.1} dBFS RMS, {:.1} dBFS peak)",
+                        levels.filename, levels.rms_dbfs, levels.peak_dbfs
+                    ),
+                );
+                return Ok(levels.filename);
+            }
+            if Instant::now() > deadline {
+                bail!("no segment was recorded after playback — is capture running?");
+            }
+            tokio::time::sleep(POLL).await;
+        }
+    }
+
+    /// Wait for a processing server to fetch (and so delete) `name`.
+    async fn wait_pickup(&self, name: &str) -> Result<()> {
+        let path = self.stream_dir.join(name);
+        let since = Instant::now();
+        while path.exists() {
+            if since.elapsed() > PICKUP_TIMEOUT {
+                bail!(
+                    "{name} was not fetched within {} min — is a processing server polling this node?",
+                    PICKUP_TIMEOUT.as_secs() / 60
+                );
+            }
+            tokio::time::sleep(POLL).await;
+        }
+        self.step(
+            "picked up",
+            true,
+            format!("fetched after {} s", since.elapsed().as_secs()),
+        );
+        Ok(())
+    }
+
+    /// Poll the processing REST API for a detection of `label`.
+    async fn wait_detection(&self, base: &str, label: &str, since: DateTime<Local>) -> Result<()> {
+        let url = detections_url(base, label, since)?;
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        let start = Instant::now();
+        loop {
+            let found = match http.get(url.clone()).send().await {
+                Ok(resp) if resp.status().is_success() => resp
+                    .json::<Vec<serde_json::Value>>()
+                    .await
+                    .map(|d| d.first().cloned())
+                    .ok()
+                    .flatten(),
+                Ok(resp) => bail!("{base} answered {}", resp.status()),
+                Err(e) => bail!("cannot reach {base}: {e}"),
+            };
+            if let Some(detection) = found {
+                let confidence = detection["confidence"].as_f64().unwrap_or_default();
+                self.step(
+                    "detected",
+                    true,
+                    format!("{label} at {:.0}% confidence", confidence * 100.0),
+                );
+                return Ok(());
+            }
+            if start.elapsed() > DETECTION_TIMEOUT {
+                bail!("no detection of {label} was stored");
+            }
+            tokio::time::sleep(POLL).await;
+        }
+    }
+}
+
+/// `GET /api/detections` URL for detections of `label` after `since`.
+fn detections_url(base: &str, label: &str, since: DateTime<Local>) -> Result<reqwest::Url> {
+    let since = since.format("%Y-%m-%dT%H:%M:%S").to_string();
+    reqwest::Url::parse_with_params(
+        &format!("{base}/api/detections"),
+        [("since", since.as_str()), ("species", label)],
+    )
+    .with_context(|| format!("Invalid SELFTEST_PROCESSING_URL {base}"))
+}
+
+/// Linear sine sweep from `from_hz` to `to_hz`, at half full scale with
+/// 50 ms fades so it starts and ends without a click.
+pub fn chirp(sample_rate: u32, secs: f64, from_hz: f64, to_hz: f64) -> Vec<f32> {
+    let rate = sample_rate as f64;
+    let n = (secs * rate) as usize;
+    let fade = (0.05 * rate) as usize;
+    let sweep = (to_hz - from_hz) / secs;
+    (0..n)
+        .map(|i| {
+            let t = i as f64 / rate;
+            let phase = std::f64::consts::TAU * (from_hz * t + sweep * t * t / 2.0);
+            let edge = i.min(n - 1 - i);
+            let gain = if edge < fade {
+                edge as f64 / fade as f64
+            } else {
+                1.0
+            };
+            (0.5 * gain * phase.sin()) as f32
+        })
+        .collect()
+}
+
+/// 16-bit mono WAV of `samples`.
+fn wav_bytes(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut buf = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut buf, spec)?;
+    for s in samples {
+        writer.write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(buf.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chirp() {
+        let samples = chirp(8_000, 1.0, 1_000.0, 3_000.0);
+        assert_eq!(samples.len(), 8_000);
+        // Faded in and out, half full scale in between.
+        assert_eq!(samples[0], 0.0);
+        assert!(samples[..40].iter().all(|s| s.abs() < 0.1));
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((0.45..=0.5).contains(&peak), "peak {peak}");
+        // Zero crossings grow with the frequency: ~2 per cycle.
+        let crossings = |s: &[f32]| {
+            s.windows(2)
+                .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+                .count()
+        };
+        let (early, late) = (
+            crossings(&samples[400..1_200]),
+            crossings(&samples[6_800..7_600]),
+        );
+        assert!(late > early * 2, "{early} → {late}");
+    }
+
+    #[test]
+    fn test_reference_wav_is_valid() {
+        let dir = std::env::temp_dir().join(format!("gaia-selftest-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chirp.wav");
+        let samples = chirp(CHIRP_RATE, CHIRP_SECS, CHIRP_FROM_HZ, CHIRP_TO_HZ);
+        std::fs::write(&path, wav_bytes(&samples, CHIRP_RATE).unwrap()).unwrap();
+        let stats = gaia_common::audio::verify_wav(&path, Some(CHIRP_SECS)).unwrap();
+        assert_eq!(stats.sample_rate, CHIRP_RATE);
+        assert!((stats.duration_secs - CHIRP_SECS).abs() < 0.01);
+        assert_eq!(stats.clipped_fraction, 0.0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_detections_url() {
+        let since = Local::now();
+        let url = detections_url("http://processing:8090", "Turdus merula", since).unwrap();
+        assert_eq!(url.path(), "/api/detections");
+        let query = url.query().unwrap();
+        assert!(query.contains("species=Turdus+merula"), "{query}");
+        assert!(query.contains(&format!(
+            "since={}",
+            since.format("%Y-%m-%dT%H%%3A%M%%3A%S")
+        )));
+        assert!(detections_url("not a url", "x", since).is_err());
+    }
+}
@@ -2,30 +2,56 @@
 //!
 //! Routes:
 //!   GET  /api/health              → health check
+//!   GET  /api/openapi.json        → OpenAPI description of these routes
+//!   GET  /api/audio-devices       → ALSA capture devices (`arecord -l`)
+//!   GET  /api/levels              → input level of the last segment
+//!   GET  /api/status              → per-stream watchdog state
+//!   POST /api/selftest            → start an end-to-end self-test
+//!   GET  /api/selftest            → latest self-test report
 //!   GET  /api/recordings          → list available WAV/Opus files
+//!        ?limit=&offset=&order=asc|desc&since=<RFC 3339>
+//!        (total matches in `X-Total-Count`)
 //!   GET  /api/recordings/:name    → download a recording file
 //!   DELETE /api/recordings/:name  → remove a processed recording
+//!   POST /api/recordings/:name/keep → move a ring-buffer segment to
+//!        `KEPT_DIR` (409 when `RING_BUFFER_SECS` is off)
+//!
+//! The contract is `common/openapi/capture.json` (see
+//! `gaia_common::capture_api`); change it together with these handlers.
+//!
+//! Everything but `/api/health` and `/api/openapi.json` requires
+//! `Authorization: Bearer <token>` when `CAPTURE_AUTH_TOKEN` is configured.
 
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
+use anyhow::Context;
 use axum::body::Body;
-use axum::extract::{Path, State};
-use axum::http::{header, StatusCode};
-use axum::response::{IntoResponse, Json};
-use axum::routing::{delete, get};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderName, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{delete, get, post};
 use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
 use tokio::io::BufReader;
 use tokio::net::TcpListener;
 use tokio_util::io::ReaderStream;
+use serde::Deserialize;
 use tower_http::cors::CorsLayer;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use gaia_common::protocol::{HealthResponse, RecordingInfo};
+use gaia_common::config::Config;
+use gaia_common::node_id::NodeIdentity;
+use gaia_common::protocol::{
+    AudioDevicesResponse, CaptureStatus, HealthResponse, InputLevels, RecordingInfo,
+    SelfTestReport, SelfTestRequest,
+};
 
-use crate::DiskState;
+use crate::selftest::{SelfTest, StartError};
+use crate::{DiskState, LevelState, StreamStatusState};
 
 /// Resolve a user-supplied filename to an absolute path inside `base_dir`.
 ///
@@ -69,53 +95,145 @@ struct AppState {
     #[allow(dead_code)]
     shutdown: Arc<AtomicBool>,
     disk: Arc<DiskState>,
+    levels: Arc<LevelState>,
+    streams: Arc<StreamStatusState>,
+    /// Configured `REC_CARD`, reported alongside the device list.
+    rec_card: String,
+    /// Persistent node identity, reported by `/api/health`.
+    identity: NodeIdentity,
+    /// This node's `LATITUDE` / `LONGITUDE`, reported by `/api/health`.
+    location: Option<(f64, f64)>,
+    selftest: Arc<SelfTest>,
+    /// `KEPT_DIR` in ring-buffer mode; `None` when segments are on disk
+    /// already.
+    kept_dir: Option<PathBuf>,
 }
 
-/// Start the HTTP server. Blocks until shutdown.
+/// Start the HTTP(S) server. Blocks until shutdown.
+///
+/// Serves HTTPS when `CAPTURE_TLS_CERT` and `CAPTURE_TLS_KEY` are set,
+/// and requires `CAPTURE_AUTH_TOKEN` as a bearer token when that is set
+/// (`/api/health` stays open for monitoring).
 pub async fn run(
-    stream_dir: PathBuf,
-    listen_addr: &str,
+    config: Config,
     shutdown: Arc<AtomicBool>,
     disk: Arc<DiskState>,
+    levels: Arc<LevelState>,
+    streams: Arc<StreamStatusState>,
+    identity: NodeIdentity,
 ) -> anyhow::Result<()> {
     // Canonicalize the stream directory so all downstream path operations
     // (read_dir, join, metadata, open, remove) use a fully-resolved base.
     // This satisfies CodeQL's path-injection analysis by proving the base
     // is not attacker-controlled.
+    let stream_dir = config.stream_data_dir();
     let stream_dir = stream_dir.canonicalize().unwrap_or(stream_dir);
+    let listen_addr = config.capture_listen_addr.as_str();
 
+    let selftest = Arc::new(SelfTest::new(&config, stream_dir.clone(), levels.clone()));
     let state = AppState {
         stream_dir,
         start_time: Instant::now(),
         shutdown: shutdown.clone(),
         disk,
+        levels,
+        streams,
+        rec_card: config.rec_card.clone().unwrap_or_else(|| "default".into()),
+        identity,
+        location: config.location(),
+        selftest,
+        kept_dir: config.ring_buffer().then(|| config.kept_dir.clone()),
     };
 
-    let app = Router::new()
-        .route("/api/health", get(health))
+    let mut api = Router::new()
+        .route("/api/audio-devices", get(audio_devices))
+        .route("/api/levels", get(input_levels))
+        .route("/api/status", get(status))
+        .route("/api/selftest", get(selftest_report).post(start_selftest))
         .route("/api/recordings", get(list_recordings))
         .route("/api/recordings/{name}", get(download_recording))
         .route("/api/recordings/{name}", delete(delete_recording))
+        .route("/api/recordings/{name}/keep", post(keep_recording));
+    match config.capture_auth_token.as_deref() {
+        Some(token) => {
+            api = api.route_layer(middleware::from_fn_with_state(
+                Arc::<str>::from(token),
+                require_token,
+            ));
+        }
+        None => warn!(
+            "CAPTURE_AUTH_TOKEN not set – anyone on the network can list and delete recordings"
+        ),
+    }
+    let app = api
+        .route("/api/health", get(health))
+        .route("/api/openapi.json", get(openapi))
         .layer(CorsLayer::permissive())
         .with_state(state);
 
-    let listener = TcpListener::bind(listen_addr).await?;
-    info!("Capture HTTP server listening on {listen_addr}");
+    match (&config.capture_tls_cert, &config.capture_tls_key) {
+        (Some(cert), Some(key)) => {
+            let tls = RustlsConfig::from_pem_file(cert, key)
+                .await
+                .with_context(|| {
+                    format!("Cannot load TLS certificate {} / key {}", cert.display(), key.display())
+                })?;
+            let addr = tokio::net::lookup_host(listen_addr)
+                .await?
+                .next()
+                .with_context(|| format!("Cannot resolve {listen_addr}"))?;
+
+            let handle = axum_server::Handle::new();
+            let shutdown_handle = handle.clone();
+            tokio::spawn(async move {
+                wait_for_shutdown(&shutdown).await;
+                shutdown_handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
+            });
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            loop {
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                if shutdown.load(Ordering::Relaxed) {
-                    break;
-                }
-            }
-        })
-        .await?;
+            info!("Capture HTTPS server listening on {addr}");
+            axum_server::bind_rustls(addr, tls)
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        (None, None) => {
+            let listener = TcpListener::bind(listen_addr).await?;
+            info!("Capture HTTP server listening on {listen_addr}");
+
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async move { wait_for_shutdown(&shutdown).await })
+                .await?;
+        }
+        _ => anyhow::bail!("CAPTURE_TLS_CERT and CAPTURE_TLS_KEY must be set together"),
+    }
 
     Ok(())
 }
 
+async fn wait_for_shutdown(shutdown: &AtomicBool) {
+    while !shutdown.load(Ordering::Relaxed) {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+}
+
+/// Reject requests without the shared bearer token.
+async fn require_token(State(token): State<Arc<str>>, req: Request, next: Next) -> Response {
+    let given = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if gaia_common::capture_api::bearer_matches(given, &token) {
+        next.run(req).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "missing or invalid bearer token",
+        )
+            .into_response()
+    }
+}
+
 // ── route handlers ───────────────────────────────────────────────────────
 
 async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
@@ -128,16 +246,114 @@ async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
         },
         uptime_secs: state.start_time.elapsed().as_secs(),
         disk_usage_pct: state.disk.usage_pct(),
+        inode_usage_pct: state.disk.inode_pct(),
         capture_paused: paused,
+        node_id: state.identity.id.clone(),
+        node_name: state.identity.name.clone(),
+        latitude: state.location.map(|(lat, _)| lat),
+        longitude: state.location.map(|(_, lon)| lon),
+        time_sync: gaia_common::timesync::latest(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol: gaia_common::protocol::PROTOCOL_VERSION,
     })
 }
 
+async fn openapi() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        gaia_common::capture_api::OPENAPI_JSON,
+    )
+}
+
+async fn audio_devices(
+    State(state): State<AppState>,
+) -> Result<Json<AudioDevicesResponse>, (StatusCode, String)> {
+    let devices = crate::devices::list().await.map_err(|e| {
+        tracing::warn!("Cannot enumerate audio devices: {e:#}");
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}"))
+    })?;
+    Ok(Json(AudioDevicesResponse {
+        current: state.rec_card.clone(),
+        devices,
+    }))
+}
+
+/// Level of the last finished segment; 404 until one has been measured.
+async fn input_levels(
+    State(state): State<AppState>,
+) -> Result<Json<InputLevels>, (StatusCode, &'static str)> {
+    let latest = state.levels.latest.lock().ok().and_then(|l| l.clone());
+    latest
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "no segment measured yet"))
+}
+
+/// Capture state with the RTSP watchdog's view of each stream.
+async fn status(State(state): State<AppState>) -> Json<CaptureStatus> {
+    Json(CaptureStatus {
+        node_name: state.identity.name.clone(),
+        capture_paused: state.disk.capture_paused.load(Ordering::Relaxed),
+        disk_usage_pct: state.disk.usage_pct(),
+        inode_usage_pct: state.disk.inode_pct(),
+        streams: state.streams.streams.lock().map(|s| s.clone()).unwrap_or_default(),
+    })
+}
+
+/// Latest self-test; 404 until one has been started.
+async fn selftest_report(
+    State(state): State<AppState>,
+) -> Result<Json<SelfTestReport>, (StatusCode, &'static str)> {
+    state
+        .selftest
+        .latest()
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "no self-test has run yet"))
+}
+
+/// Start a self-test in the background; the body is optional and
+/// defaults to `inject` mode.
+async fn start_selftest(
+    State(state): State<AppState>,
+    body: Option<Json<SelfTestRequest>>,
+) -> Result<(StatusCode, Json<SelfTestReport>), (StatusCode, String)> {
+    let mode = body.map(|Json(req)| req.mode).unwrap_or_default();
+    match state.selftest.start(mode) {
+        Ok(report) => Ok((StatusCode::ACCEPTED, Json(report))),
+        Err(StartError::Busy) => Err((
+            StatusCode::CONFLICT,
+            "a self-test is already running".to_string(),
+        )),
+        Err(StartError::Reference(e)) => {
+            warn!("Cannot start self-test: {e:#}");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))
+        }
+    }
+}
+
+/// Header carrying the number of recordings matching a listing before
+/// `limit`/`offset` are applied.
+const TOTAL_COUNT: HeaderName = HeaderName::from_static("x-total-count");
+
+/// Query of `GET /api/recordings`.  Without parameters every recording
+/// is listed, oldest first.
+#[derive(Debug, Default, Deserialize)]
+struct RecordingsQuery {
+    limit: Option<usize>,
+    #[serde(default)]
+    offset: usize,
+    /// `asc` (oldest first, default) or `desc`.
+    order: Option<String>,
+    /// Only recordings finished at or after this RFC 3339 time.
+    since: Option<String>,
+}
+
 async fn list_recordings(
     State(state): State<AppState>,
-) -> Result<Json<Vec<RecordingInfo>>, StatusCode> {
+    Query(query): Query<RecordingsQuery>,
+) -> Result<Response, StatusCode> {
     let dir = &state.stream_dir;
     if !dir.exists() {
-        return Ok(Json(vec![]));
+        return Ok(([(TOTAL_COUNT, "0".to_string())], Json(Vec::<RecordingInfo>::new())).into_response());
     }
 
     let mut recordings = Vec::new();
@@ -184,8 +400,6 @@ async fn list_recordings(
         }
     }
 
-    recordings.sort_by(|a, b| a.filename.cmp(&b.filename));
-
     let total_bytes: u64 = recordings.iter().map(|r| r.size).sum();
     debug!(
         "Listing {} recording(s), total size {:.1} MB",
@@ -193,7 +407,36 @@ async fn list_recordings(
         total_bytes as f64 / 1_048_576.0
     );
 
-    Ok(Json(recordings))
+    let (total, page) = paginate(recordings, &query)?;
+    Ok(([(TOTAL_COUNT, total.to_string())], Json(page)).into_response())
+}
+
+/// Filter, sort and cut `recordings` as asked by `query`; returns the
+/// number of matches before `limit`/`offset` and the page itself.
+fn paginate(
+    mut recordings: Vec<RecordingInfo>,
+    query: &RecordingsQuery,
+) -> Result<(usize, Vec<RecordingInfo>), StatusCode> {
+    if let Some(since) = &query.since {
+        let since = chrono::DateTime::parse_from_rfc3339(since).map_err(|_| StatusCode::BAD_REQUEST)?;
+        recordings.retain(|r| {
+            chrono::DateTime::parse_from_rfc3339(&r.created).is_ok_and(|created| created >= since)
+        });
+    }
+    // Segment names start with their timestamp, so this is capture order.
+    recordings.sort_by(|a, b| a.filename.cmp(&b.filename));
+    match query.order.as_deref() {
+        None | Some("asc") => {}
+        Some("desc") => recordings.reverse(),
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    }
+    let total = recordings.len();
+    let page = recordings
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok((total, page))
 }
 
 async fn download_recording(
@@ -280,3 +523,69 @@ async fn delete_recording(
         }
     }
 }
+
+/// Move a ring-buffer segment to `KEPT_DIR` so it outlives the window.
+async fn keep_recording(State(state): State<AppState>, Path(name): Path<String>) -> StatusCode {
+    let Some(kept_dir) = state.kept_dir.clone() else {
+        return StatusCode::CONFLICT;
+    };
+    let file_path = match safe_recording_path(&state.stream_dir, &name) {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    match tokio::task::spawn_blocking(move || crate::ring::keep(&file_path, &kept_dir)).await {
+        Ok(Ok(target)) => {
+            info!(file = %name, "Kept ring-buffer segment as {}", target.display());
+            StatusCode::NO_CONTENT
+        }
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        Ok(Err(e)) => {
+            warn!(file = %name, error = %e, "Cannot keep ring-buffer segment");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rec(filename: &str, created: &str) -> RecordingInfo {
+        RecordingInfo {
+            filename: filename.to_string(),
+            size: 1,
+            created: created.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_paginate_recordings() {
+        let all = vec![
+            rec("2026-05-01-birdnet-08:00:30.wav", "2026-05-01T08:01:00+00:00"),
+            rec("2026-05-01-birdnet-08:00:00.wav", "2026-05-01T08:00:30+00:00"),
+            rec("2026-05-01-birdnet-08:01:00.wav", "2026-05-01T08:01:30+00:00"),
+        ];
+        let names = |page: Vec<RecordingInfo>| page.into_iter().map(|r| r.filename[19..27].to_string()).collect::<Vec<_>>();
+
+        let (total, page) = paginate(all.clone(), &RecordingsQuery::default()).unwrap();
+        assert_eq!((total, names(page)), (3, vec!["08:00:00".into(), "08:00:30".into(), "08:01:00".into()]));
+
+        let query = RecordingsQuery { limit: Some(1), offset: 1, ..Default::default() };
+        let (total, page) = paginate(all.clone(), &query).unwrap();
+        assert_eq!((total, names(page)), (3, vec!["08:00:30".to_string()]));
+
+        let query = RecordingsQuery {
+            order: Some("desc".into()),
+            since: Some("2026-05-01T08:01:00Z".into()),
+            ..Default::default()
+        };
+        let (total, page) = paginate(all.clone(), &query).unwrap();
+        assert_eq!((total, names(page)), (2, vec!["08:01:00".into(), "08:00:30".into()]));
+
+        let bad = RecordingsQuery { order: Some("sideways".into()), ..Default::default() };
+        assert_eq!(paginate(all.clone(), &bad).unwrap_err(), StatusCode::BAD_REQUEST);
+        let bad = RecordingsQuery { since: Some("yesterday".into()), ..Default::default() };
+        assert_eq!(paginate(all, &bad).unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,143 @@
+//! Embeds the capture start time and node name in finished segments.
+//!
+//! ffmpeg's segment muxer only gives us a strftime filename, accurate to
+//! the second and lost as soon as a file is renamed.  This thread waits
+//! for each segment to be closed and adds a `bext` chunk (see
+//! [`gaia_common::wav_meta`]) before the processing server fetches it.
+//! The newest segment of each pass is also measured for the input level
+//! meter (`/api/levels`).  In `gaia-processing mono` every stamped segment
+//! is also handed to the analysis workers directly.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use gaia_common::protocol::LevelStatus;
+use tracing::{debug, info, warn};
+
+use crate::LevelState;
+
+/// A segment is considered closed once it has not been written to for
+/// this long.  Must stay below the HTTP listing's 2 s settle time so
+/// files are stamped before they are offered for download.
+const SETTLE: Duration = Duration::from_millis(800);
+
+const POLL: Duration = Duration::from_millis(500);
+
+/// Poll `stream_dir` and stamp every settled, unstamped WAV file,
+/// sending each one to `segments` (oldest first) when given.
+///
+/// Once `shutdown` is set (after capture has stopped) one last pass
+/// stamps whatever is left without waiting for it to settle.
+pub fn stamp_loop(
+    stream_dir: PathBuf,
+    node: String,
+    levels: Arc<LevelState>,
+    segments: Option<Sender<PathBuf>>,
+    shutdown: Arc<AtomicBool>,
+) {
+    info!("WAV stamping started (node={node})");
+    // Names already handled, so each file is read only once.
+    let mut seen: HashSet<String> = HashSet::new();
+    let send = |stamped: Vec<PathBuf>| {
+        if let Some(tx) = &segments {
+            for path in stamped {
+                let _ = tx.send(path);
+            }
+        }
+    };
+
+    while !shutdown.load(Ordering::Relaxed) {
+        std::thread::sleep(POLL);
+        let stamped = stamp_pass(&stream_dir, &node, &mut seen, SETTLE);
+        if let Some(newest) = stamped.last() {
+            measure(newest, &levels);
+        }
+        send(stamped);
+    }
+    send(stamp_pass(&stream_dir, &node, &mut seen, Duration::ZERO));
+    info!("WAV stamping stopped");
+}
+
+/// Update the level meter from `path`, logging when the verdict changes.
+fn measure(path: &Path, levels: &LevelState) {
+    let reading = match gaia_common::audio::measure_levels(path) {
+        Ok(r) => r,
+        Err(e) => {
+            debug!("Cannot measure input level of {}: {e:#}", path.display());
+            return;
+        }
+    };
+    let Ok(mut latest) = levels.latest.lock() else {
+        return;
+    };
+    let previous = latest.as_ref().map(|l| l.status);
+    if previous != Some(reading.status) {
+        match reading.status {
+            LevelStatus::Ok => info!("Input level ok ({:.1} dBFS RMS)", reading.rms_dbfs),
+            LevelStatus::Clipping => warn!(
+                "Input is clipping ({:.2}% of samples at full scale) – reduce the gain",
+                reading.clipped_fraction * 100.0
+            ),
+            LevelStatus::Quiet => warn!(
+                "Input level very low ({:.1} dBFS RMS) – increase the gain",
+                reading.rms_dbfs
+            ),
+            LevelStatus::Flatline => {
+                warn!("No signal on the input – is the microphone connected?")
+            }
+        }
+    }
+    *latest = Some(reading);
+}
+
+/// Stamp newly settled segments; returns them oldest first.
+fn stamp_pass(
+    stream_dir: &Path,
+    node: &str,
+    seen: &mut HashSet<String>,
+    settle: Duration,
+) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(stream_dir) else {
+        return Vec::new();
+    };
+    let now = SystemTime::now();
+    let mut present = HashSet::new();
+    let mut stamped: Vec<(SystemTime, PathBuf)> = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wav") {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        present.insert(name.clone());
+        if seen.contains(&name) {
+            continue;
+        }
+        let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+            continue;
+        };
+        if now.duration_since(modified).unwrap_or_default() < settle {
+            continue;
+        }
+
+        match gaia_common::wav_meta::stamp_finished_segment(&path, node) {
+            Ok(Some(stamp)) => debug!("Stamped {name}: start={}", stamp.start),
+            Ok(None) => {}
+            Err(e) => warn!("Cannot stamp {name}: {e:#}"),
+        }
+        seen.insert(name);
+        stamped.push((modified, path));
+    }
+
+    // Forget files that were fetched and deleted.
+    seen.retain(|n| present.contains(n));
+    stamped.sort();
+    stamped.into_iter().map(|(_, path)| path).collect()
+}
@@ -0,0 +1,235 @@
+//! Status LED and fault relay on GPIO pins, for field nodes without a
+//! screen (`STATUS_LED_PIN`, `FAULT_RELAY_PIN`).
+//!
+//! | LED        | Meaning                                                |
+//! |------------|--------------------------------------------------------|
+//! | slow blink | capturing: audio was written in the last few segments  |
+//! | fast blink | capture dead: nothing written for [`STALE_SEGMENTS`]   |
+//! | solid      | disk full: the disk guard paused capture               |
+//!
+//! The relay pin is on in both fault states.  Driving the pins needs the
+//! `gpio` feature (rppal, Raspberry Pi only); without it the thread logs
+//! that the pins are ignored and exits.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use gaia_common::config::Config;
+use tracing::{info, warn};
+
+use crate::DiskState;
+
+/// Capture counts as dead once no audio was written for this many
+/// segment lengths.
+pub const STALE_SEGMENTS: u32 = 3;
+
+/// How often the LED is updated; the fastest pattern toggles every tick.
+const TICK: Duration = Duration::from_millis(125);
+
+/// How often the recordings directory is checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the status LED shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    Capturing,
+    CaptureDead,
+    DiskFull,
+}
+
+impl NodeStatus {
+    /// Whether the LED is lit `elapsed` into the blink pattern.
+    pub fn led_on(self, elapsed: Duration) -> bool {
+        let ms = elapsed.as_millis();
+        match self {
+            // 0.5 Hz
+            NodeStatus::Capturing => ms % 2_000 < 1_000,
+            // 4 Hz
+            NodeStatus::CaptureDead => ms % 250 < 125,
+            NodeStatus::DiskFull => true,
+        }
+    }
+
+    /// Whether the fault relay is switched on.
+    pub fn fault(self) -> bool {
+        self != NodeStatus::Capturing
+    }
+}
+
+/// The node's status from the disk guard and the time audio was last
+/// written (or capture started, before the first segment).
+pub fn status(
+    disk_full: bool,
+    last_write: SystemTime,
+    now: SystemTime,
+    stale_after: Duration,
+) -> NodeStatus {
+    if disk_full {
+        NodeStatus::DiskFull
+    } else if now.duration_since(last_write).unwrap_or_default() > stale_after {
+        NodeStatus::CaptureDead
+    } else {
+        NodeStatus::Capturing
+    }
+}
+
+/// Latest modification time of the segments in `dir`; ffmpeg keeps
+/// touching the one it is writing.
+fn newest_segment(dir: &Path) -> Option<SystemTime> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| {
+            matches!(
+                e.path().extension().and_then(|x| x.to_str()),
+                Some("wav") | Some("opus")
+            )
+        })
+        .filter_map(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .max()
+}
+
+/// Drive the configured pins until `shutdown` is set, then switch them
+/// off.  Returns straight away when no pin is configured or the pins
+/// cannot be opened.
+pub fn run(config: &Config, disk: Arc<DiskState>, shutdown: Arc<AtomicBool>) {
+    if config.status_led_pin.is_none() && config.fault_relay_pin.is_none() {
+        return;
+    }
+    let mut outputs = match Outputs::open(config) {
+        Ok(o) => o,
+        Err(e) => {
+            warn!("Status LED: {e:#}");
+            return;
+        }
+    };
+    info!(
+        "Status LED on GPIO {:?}, fault relay on GPIO {:?}",
+        config.status_led_pin, config.fault_relay_pin
+    );
+
+    let dir = config.stream_data_dir();
+    let stale_after = Duration::from_secs((STALE_SEGMENTS * config.recording_length.max(1)).into());
+    let started = Instant::now();
+    let start_time = SystemTime::now();
+    let mut current = NodeStatus::Capturing;
+    let mut last_check: Option<Instant> = None;
+    while !shutdown.load(Ordering::Relaxed) {
+        if last_check.is_none_or(|t| t.elapsed() >= CHECK_INTERVAL) {
+            let last_write = newest_segment(&dir).map_or(start_time, |t| t.max(start_time));
+            let disk_full = disk.capture_paused.load(Ordering::Relaxed);
+            let next = status(disk_full, last_write, SystemTime::now(), stale_after);
+            if next != current {
+                match next {
+                    NodeStatus::Capturing => info!("Status LED: capturing"),
+                    NodeStatus::CaptureDead => warn!(
+                        "Status LED: no audio written for {}s — capture looks dead",
+                        stale_after.as_secs()
+                    ),
+                    NodeStatus::DiskFull => warn!("Status LED: disk full, capture paused"),
+                }
+                current = next;
+            }
+            last_check = Some(Instant::now());
+        }
+        outputs.set(current.led_on(started.elapsed()), current.fault());
+        std::thread::sleep(TICK);
+    }
+    outputs.set(false, false);
+}
+
+#[cfg(feature = "gpio")]
+struct Outputs {
+    led: Option<rppal::gpio::OutputPin>,
+    relay: Option<rppal::gpio::OutputPin>,
+    active_low: bool,
+}
+
+#[cfg(feature = "gpio")]
+impl Outputs {
+    fn open(config: &Config) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        let gpio = rppal::gpio::Gpio::new().context("Cannot open the GPIO controller")?;
+        let output = |pin: Option<u8>| -> anyhow::Result<Option<rppal::gpio::OutputPin>> {
+            pin.map(|p| {
+                Ok(gpio
+                    .get(p)
+                    .with_context(|| format!("Cannot claim GPIO {p}"))?
+                    .into_output())
+            })
+            .transpose()
+        };
+        Ok(Outputs {
+            led: output(config.status_led_pin)?,
+            relay: output(config.fault_relay_pin)?,
+            active_low: config.gpio_active_low,
+        })
+    }
+
+    fn set(&mut self, led: bool, relay: bool) {
+        let active_low = self.active_low;
+        for (pin, on) in [(&mut self.led, led), (&mut self.relay, relay)] {
+            if let Some(pin) = pin {
+                if on != active_low {
+                    pin.set_high();
+                } else {
+                    pin.set_low();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "gpio"))]
+struct Outputs;
+
+#[cfg(not(feature = "gpio"))]
+impl Outputs {
+    fn open(_config: &Config) -> anyhow::Result<Self> {
+        anyhow::bail!(
+            "STATUS_LED_PIN / FAULT_RELAY_PIN are set, but gaia-capture was built \
+             without the `gpio` feature; ignoring them"
+        )
+    }
+
+    fn set(&mut self, _led: bool, _relay: bool) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let stale = Duration::from_secs(45);
+        let ago = |secs| now - Duration::from_secs(secs);
+        assert_eq!(status(false, ago(10), now, stale), NodeStatus::Capturing);
+        assert_eq!(status(false, ago(46), now, stale), NodeStatus::CaptureDead);
+        assert_eq!(status(true, ago(10), now, stale), NodeStatus::DiskFull);
+        assert_eq!(status(true, ago(100), now, stale), NodeStatus::DiskFull);
+        // A segment stamped slightly in the future is not stale.
+        assert_eq!(
+            status(false, now + Duration::from_secs(5), now, stale),
+            NodeStatus::Capturing
+        );
+    }
+
+    #[test]
+    fn test_blink_patterns() {
+        let lit = |status: NodeStatus| (0..16).filter(|i| status.led_on(TICK * *i)).count();
+        // Over two seconds: half on, toggling once or every tick.
+        assert_eq!(lit(NodeStatus::Capturing), 8);
+        assert!(NodeStatus::Capturing.led_on(Duration::ZERO));
+        assert!(!NodeStatus::Capturing.led_on(Duration::from_millis(1_500)));
+        assert_eq!(lit(NodeStatus::CaptureDead), 8);
+        assert!(!NodeStatus::CaptureDead.led_on(TICK));
+        assert_eq!(lit(NodeStatus::DiskFull), 16);
+
+        assert!(!NodeStatus::Capturing.fault());
+        assert!(NodeStatus::CaptureDead.fault());
+        assert!(NodeStatus::DiskFull.fault());
+    }
+}
@@ -0,0 +1,187 @@
+//! Keeps audio capture running: starts it with retries, then watches
+//! disk and inode usage, RTSP streams and ffmpeg liveness every 10 s.
+//!
+//! When disk usage exceeds `DISK_USAGE_MAX` settled WAV files are first
+//! recoded to Opus; capture is only paused if that is not enough, or
+//! straight away when inode usage exceeds `DISK_INODE_MAX`.  It resumes
+//! once both are back under their thresholds.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use gaia_common::config::Config;
+use tracing::info;
+
+use crate::capture::{self, CaptureHandle};
+use crate::disk;
+use crate::{DiskState, StreamStatusState};
+
+const MAX_CAPTURE_RETRIES: u32 = 5;
+const CAPTURE_RETRY_DELAY: Duration = Duration::from_secs(10);
+const CAPTURE_STOP_GRACE: Duration = Duration::from_secs(5);
+
+/// Start audio capture, retrying a few times (devices may still be
+/// appearing at boot).  `None` when every attempt failed.
+pub fn start_with_retries(config: &Config) -> Option<CaptureHandle> {
+    for attempt in 1..=MAX_CAPTURE_RETRIES {
+        match capture::start(config) {
+            Ok(h) => {
+                info!("Audio capture started on attempt {attempt}");
+                return Some(h);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Audio capture attempt {attempt}/{MAX_CAPTURE_RETRIES} failed: {e:#}"
+                );
+                if attempt < MAX_CAPTURE_RETRIES {
+                    tracing::info!(
+                        "Retrying in {}s…",
+                        CAPTURE_RETRY_DELAY.as_secs()
+                    );
+                    std::thread::sleep(CAPTURE_RETRY_DELAY);
+                }
+            }
+        }
+    }
+    tracing::warn!(
+        "All {MAX_CAPTURE_RETRIES} capture attempts failed. \
+         HTTP server will run without active capture."
+    );
+    None
+}
+
+/// Spawn the health thread.  It owns `capture_handle` and stops capture
+/// (letting ffmpeg close the current segment) once `shutdown` is set.
+pub fn spawn_health(
+    config: Config,
+    mut capture_handle: Option<CaptureHandle>,
+    disk_state: Arc<DiskState>,
+    stream_state: Arc<StreamStatusState>,
+    shutdown: Arc<AtomicBool>,
+) -> Option<JoinHandle<()>> {
+    let guard_dir = config.stream_data_dir();
+    let disk_max = config.disk_usage_max;
+    let inode_max = config.disk_inode_max;
+
+    std::thread::Builder::new()
+        .name("capture-health".into())
+        .spawn(move || {
+            while !shutdown.load(Ordering::Relaxed) {
+                // 10 s between checks, but react to shutdown within a second.
+                for _ in 0..10 {
+                    if shutdown.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_secs(1));
+                }
+                if shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                // ── disk and inode usage check ───────────────────────
+                if let Some(usage) = gaia_common::disk::usage(&guard_dir) {
+                    let pct = usage.space_pct;
+                    disk_state.store(&usage);
+
+                    let is_paused = disk_state.capture_paused.load(Ordering::Relaxed);
+                    let full = usage.exceeds(disk_max, inode_max);
+
+                    if full && !is_paused {
+                        // ── disk pressure: try emergency recode first ──
+                        // Recoding replaces each WAV with one Opus file, so
+                        // it frees space but never inodes.
+                        if pct >= disk_max {
+                            tracing::warn!(
+                                "Disk usage {pct:.1}% >= threshold {disk_max}% — \
+                                 recoding settled WAV files to Opus before pausing capture"
+                            );
+
+                            let recode = disk::recode_wav_to_opus(
+                                &guard_dir,
+                                Duration::from_secs(5),
+                            );
+
+                            if recode.converted > 0 {
+                                tracing::warn!(
+                                    "Emergency recode: converted {} WAV file(s), freed {:.1} MB",
+                                    recode.converted,
+                                    recode.freed_bytes as f64 / 1_048_576.0
+                                );
+                                if let Some(after) = gaia_common::disk::usage(&guard_dir) {
+                                    disk_state.store(&after);
+                                    if !after.exceeds(disk_max, inode_max) {
+                                        tracing::info!(
+                                            "Disk usage dropped to {:.1}% (< {disk_max}%) after recode — capture continues",
+                                            after.space_pct
+                                        );
+                                        continue;
+                                    }
+                                }
+                            }
+                        }
+
+                        // ── PAUSE: recode insufficient, kill capture ───
+                        tracing::warn!(
+                            "Disk still over its limits ({}; max {disk_max}% space, {inode_max}% inodes) — \
+                             pausing audio capture to prevent filling the disk",
+                            usage.describe()
+                        );
+                        if let Some(ref mut h) = capture_handle {
+                            h.stop(CAPTURE_STOP_GRACE);
+                        }
+                        capture_handle = None;
+                        disk_state
+                            .capture_paused
+                            .store(true, Ordering::Relaxed);
+                    } else if !full && is_paused {
+                        // ── RESUME: restart capture ──────────────────
+                        tracing::info!(
+                            "Disk usage back under the thresholds ({}) — resuming audio capture",
+                            usage.describe()
+                        );
+                        match capture::start(&config) {
+                            Ok(h) => {
+                                capture_handle = Some(h);
+                                disk_state
+                                    .capture_paused
+                                    .store(false, Ordering::Relaxed);
+                                tracing::info!("Audio capture resumed");
+                            }
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to restart capture after disk-free: {e:#}"
+                                );
+                            }
+                        }
+                    }
+                }
+
+                // ── RTSP stream watchdog ─────────────────────────────
+                let streams = capture_handle
+                    .as_mut()
+                    .map(|h| h.watch_streams(&config))
+                    .unwrap_or_default();
+                if let Ok(mut s) = stream_state.streams.lock() {
+                    *s = streams;
+                }
+
+                // ── ffmpeg liveness check ────────────────────────────
+                if let Some(ref mut h) = capture_handle {
+                    if let Some(msg) = h.check_alive() {
+                        tracing::error!(
+                            "{msg}. Recording has stopped — check audio device and restart."
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if let Some(ref mut h) = capture_handle {
+                info!("Stopping audio capture");
+                h.stop(CAPTURE_STOP_GRACE);
+            }
+        })
+        .ok()
+}
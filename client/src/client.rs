@@ -0,0 +1,226 @@
+//! Blocking client for the processing server's REST API.
+
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::protocol::{
+    DetectionQuery, DetectionRecord, ModelStatus, PipelineStatus, ProcessingHealth,
+};
+
+/// Why a processing API call failed.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("invalid base URL {url:?}: {reason}")]
+    BaseUrl { url: String, reason: String },
+    #[error("GET {path}: {source}")]
+    Http {
+        path: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    /// Non-success status, with the server's message (e.g. a bad
+    /// `since`).
+    #[error("GET {path} returned {status}: {message}")]
+    Status {
+        path: String,
+        status: StatusCode,
+        message: String,
+    },
+}
+
+/// Client for one processing server, e.g. `http://processing.local:8090`.
+#[derive(Debug, Clone)]
+pub struct GaiaClient {
+    http: Client,
+    base: Url,
+}
+
+impl GaiaClient {
+    /// Client for the server at `base_url`, with a 30 s timeout.
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| ClientError::BaseUrl {
+                url: base_url.into(),
+                reason: format!("cannot create HTTP client: {e}"),
+            })?;
+        Self::with_http_client(base_url, http)
+    }
+
+    /// Client using a caller-configured `reqwest` client (proxy, TLS
+    /// roots, timeouts).
+    pub fn with_http_client(base_url: &str, http: Client) -> Result<Self, ClientError> {
+        let base =
+            Url::parse(base_url.trim_end_matches('/')).map_err(|e| ClientError::BaseUrl {
+                url: base_url.into(),
+                reason: e.to_string(),
+            })?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(ClientError::BaseUrl {
+                url: base_url.into(),
+                reason: "not an http(s) URL".into(),
+            });
+        }
+        Ok(Self { http, base })
+    }
+
+    /// `GET /api/health`.
+    pub fn health(&self) -> Result<ProcessingHealth, ClientError> {
+        self.get_json("/api/health", &[])
+    }
+
+    /// `GET /api/models`.
+    pub fn models(&self) -> Result<Vec<ModelStatus>, ClientError> {
+        self.get_json("/api/models", &[])
+    }
+
+    /// `GET /api/pipeline`.
+    pub fn pipeline(&self) -> Result<PipelineStatus, ClientError> {
+        self.get_json("/api/pipeline", &[])
+    }
+
+    /// `GET /api/detections`, oldest first.
+    pub fn detections(&self, query: &DetectionQuery) -> Result<Vec<DetectionRecord>, ClientError> {
+        self.get_json("/api/detections", &query_pairs(query))
+    }
+
+    /// Up to `limit` detections stored after the one with id `last`
+    /// (the newest `limit` when `None`).  Pass the last id returned back
+    /// in to follow new detections.
+    pub fn detections_after(
+        &self,
+        last: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<DetectionRecord>, ClientError> {
+        self.detections(&DetectionQuery {
+            since: last.map(|id| id.to_string()),
+            limit: Some(limit),
+            ..Default::default()
+        })
+    }
+
+    fn url(&self, path: &str, query: &[(&str, String)]) -> Url {
+        let mut url = self.base.clone();
+        url.set_path(&format!("{}{path}", self.base.path().trim_end_matches('/')));
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        url
+    }
+
+    fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ClientError> {
+        let http_err = |source| ClientError::Http {
+            path: path.into(),
+            source,
+        };
+        let resp = self
+            .http
+            .get(self.url(path, query))
+            .send()
+            .map_err(http_err)?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(ClientError::Status {
+                path: path.into(),
+                status,
+                message: resp.text().unwrap_or_default(),
+            });
+        }
+        resp.json().map_err(http_err)
+    }
+}
+
+/// Query string pairs of the parameters that are set.
+fn query_pairs(query: &DetectionQuery) -> Vec<(&'static str, String)> {
+    let DetectionQuery {
+        since,
+        limit,
+        species,
+        model,
+        min_confidence,
+    } = query;
+    [
+        ("since", since.clone()),
+        ("limit", limit.map(|l| l.to_string())),
+        ("species", species.clone()),
+        ("model", model.clone()),
+        ("min_confidence", min_confidence.map(|c| c.to_string())),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls() {
+        let gaia = GaiaClient::new("http://processing.local:8090/").unwrap();
+        assert_eq!(
+            gaia.url("/api/health", &[]).as_str(),
+            "http://processing.local:8090/api/health"
+        );
+
+        let query = DetectionQuery {
+            since: Some("2026-05-01T06:00:00".into()),
+            species: Some("Turdus merula".into()),
+            min_confidence: Some(0.8),
+            ..Default::default()
+        };
+        assert_eq!(
+            gaia.url("/api/detections", &query_pairs(&query)).as_str(),
+            "http://processing.local:8090/api/detections\
+             ?since=2026-05-01T06%3A00%3A00&species=Turdus+merula&min_confidence=0.8"
+        );
+
+        // Behind a reverse proxy under a path prefix.
+        let proxied = GaiaClient::new("https://example.org/gaia").unwrap();
+        assert_eq!(
+            proxied.url("/api/models", &[]).as_str(),
+            "https://example.org/gaia/api/models"
+        );
+
+        assert!(GaiaClient::new("processing:8090").is_err());
+        assert!(GaiaClient::new("not a url").is_err());
+    }
+
+    #[test]
+    fn test_detection_record_round_trip() {
+        // Older servers omit the model provenance and channel fields.
+        let json = r#"[{"id":7,"date":"2026-05-01","time":"06:12:30","domain":"birds",
+            "scientific_name":"Turdus merula","common_name":"Eurasian Blackbird",
+            "confidence":0.91,"model_slug":"birdnet","model_name":"BirdNET V2.4",
+            "source_node":"garden","file_name":"x.wav","excluded":false,
+            "agreement_score":1.0}]"#;
+        let records: Vec<DetectionRecord> = serde_json::from_str(json).unwrap();
+        assert_eq!(records[0].id, 7);
+        assert_eq!(records[0].model_version, "");
+        assert_eq!(records[0].channel, None);
+    }
+
+    #[test]
+    fn test_health_protocol() {
+        use crate::protocol::{protocol_mismatch, PROTOCOL_VERSION};
+
+        // Servers from before versioning report neither field.
+        let json = r#"{"status":"ok","uptime_secs":12,"models_loaded":2,
+            "parquet_files":3,"buffered_detections":0}"#;
+        let health: ProcessingHealth = serde_json::from_str(json).unwrap();
+        assert_eq!(health.version, "");
+        assert_eq!(health.protocol, 0);
+        assert!(protocol_mismatch(health.protocol).is_some());
+        assert!(protocol_mismatch(PROTOCOL_VERSION).is_none());
+        let newer = protocol_mismatch(PROTOCOL_VERSION + 1).unwrap();
+        assert!(newer.contains("upgrade the older"));
+    }
+}
@@ -0,0 +1,33 @@
+//! Gaia Audio data for third-party Rust programs (alerting bots,
+//! exporters, …) without linking the dashboard or the audio stack.
+//!
+//! - [`protocol`]: the capture and processing HTTP APIs' types, e.g.
+//!   [`DetectionRecord`](protocol::DetectionRecord) from the processing
+//!   server's `GET /api/detections`.
+//! - [`model`]: detections and species as gaia-web shows them.
+//! - With the default `http` feature, [`GaiaClient`]: a blocking client
+//!   for the processing server's REST API (`PROCESSING_API_ADDR`).
+//!
+//! ```no_run
+//! use gaia_client::GaiaClient;
+//!
+//! let gaia = GaiaClient::new("http://processing.local:8090")?;
+//! let mut last = None;
+//! loop {
+//!     for det in gaia.detections_after(last, 100)? {
+//!         println!("{} {} ({:.2})", det.date, det.common_name, det.confidence);
+//!         last = Some(det.id);
+//!     }
+//!     std::thread::sleep(std::time::Duration::from_secs(30));
+//! }
+//! # Ok::<(), gaia_client::ClientError>(())
+//! ```
+
+pub mod model;
+pub mod protocol;
+
+#[cfg(feature = "http")]
+mod client;
+
+#[cfg(feature = "http")]
+pub use client::{ClientError, GaiaClient};
@@ -0,0 +1,494 @@
+//! Data types shared by the dashboard, its server and third-party tools:
+//! detections and species as gaia-web shows them.
+//!
+//! Built for WASM as well, so nothing here may depend on `gaia-common`.
+
+use serde::{Deserialize, Serialize};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Return the `NODE_NAME` env var if set, otherwise `"local"`.
+///
+/// This gives the operator's chosen friendly name for the local station.
+fn node_name_or_local() -> String {
+    std::env::var("NODE_NAME")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "local".into())
+}
+
+/// Maximum length (in bytes) of a single sanitised path component.
+///
+/// Leaves plenty of headroom under the usual 255-byte filename limit when
+/// the component is embedded in a longer clip name.
+pub const MAX_PATH_COMPONENT_BYTES: usize = 96;
+
+/// Maximum length (in bytes) of a sanitised filename stem; the extension
+/// and the `.png` spectrogram suffix still fit under 255 bytes.
+pub const MAX_FILE_STEM_BYTES: usize = 200;
+
+/// Turn an arbitrary label (species common name, model slug, …) into a
+/// string that is safe to use as a single file or directory name and
+/// inside a URL path.
+///
+///   - Apostrophes and quotes are dropped, whitespace becomes `_`
+///     (identical to the historical `common_name_safe` for plain names)
+///   - Path separators and shell/URL-hostile characters become `_`
+///   - Runs of `_` collapse; leading dots and trailing `_`/`.` are trimmed
+///   - Non-ASCII letters and combining marks are kept as-is, so names in
+///     Devanagari, CJK, etc. stay readable
+///   - The result is capped at [`MAX_PATH_COMPONENT_BYTES`] on a char
+///     boundary and is never empty (`"unknown"`)
+pub fn safe_path_component(raw: &str) -> String {
+    sanitize_component(raw, MAX_PATH_COMPONENT_BYTES)
+}
+
+/// Like [`safe_path_component`] but with the longer
+/// [`MAX_FILE_STEM_BYTES`] cap, for complete filename stems.
+pub fn safe_file_stem(raw: &str) -> String {
+    sanitize_component(raw, MAX_FILE_STEM_BYTES)
+}
+
+fn sanitize_component(raw: &str, max_bytes: usize) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        let mapped = match c {
+            '\'' | '"' | '`' | '\u{2019}' => continue,
+            c if c.is_control() => continue,
+            c if c.is_whitespace() => '_',
+            c if c.is_ascii_alphanumeric() => c,
+            '-' | '.' | '_' | '(' | ')' | ',' => c,
+            c if !c.is_ascii() => c,
+            _ => '_',
+        };
+        if mapped == '_' && out.ends_with('_') {
+            continue;
+        }
+        if out.len() + mapped.len_utf8() > max_bytes {
+            break;
+        }
+        out.push(mapped);
+    }
+    let trimmed = out
+        .trim_start_matches(['.', '_'])
+        .trim_end_matches(['.', '_']);
+    if trimmed.is_empty() {
+        "unknown".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+// ─── Detection ───────────────────────────────────────────────────────────────
+
+/// A single detection row, fully serialisable (no DateTime).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebDetection {
+    pub id: i64,
+    pub domain: String,
+    pub scientific_name: String,
+    pub common_name: String,
+    pub confidence: f64,
+    pub date: String,
+    pub time: String,
+    pub file_name: String,
+    pub source_node: String,
+    /// `true` when the detection was excluded by the species-range model.
+    #[serde(default)]
+    pub excluded: bool,
+    /// Species photo URL from iNaturalist (populated server-side).
+    #[serde(default)]
+    pub image_url: Option<String>,
+    /// Short identifier for the model that produced this detection.
+    #[serde(default)]
+    pub model_slug: String,
+    /// Human-readable model name (e.g. `"BirdNET V2.4"`).
+    #[serde(default)]
+    pub model_name: String,
+    /// `true` when the model is marked as beta / experimental.
+    #[serde(default)]
+    pub model_beta: bool,
+    /// Model version (e.g. `"2.4"`); empty for older detections.
+    #[serde(default)]
+    pub model_version: String,
+    /// Download variant of the model files (e.g. `"fp16"`).
+    #[serde(default)]
+    pub model_variant: String,
+    /// Inference runtime (e.g. `"tract-onnx"`).
+    #[serde(default)]
+    pub model_runtime: String,
+    /// Input channel (0-based) for per-channel analysis; `None` when the
+    /// channels were mixed to mono.
+    #[serde(default)]
+    pub channel: Option<u16>,
+    /// Compass bearing of the sound (0° = north, clockwise), for nodes
+    /// with configured microphone positions.
+    #[serde(default)]
+    pub bearing_deg: Option<f64>,
+    /// Cross-model agreement score (0.0 – 1.0).
+    #[serde(default)]
+    pub agreement_score: f64,
+    /// Comma-separated slugs of agreeing models.
+    #[serde(default)]
+    pub agreement_models: String,
+    /// Dominant frequency of the call (Hz), when measured.
+    #[serde(default)]
+    pub peak_freq_hz: Option<f64>,
+    /// Width of the band within 20 dB of the peak (Hz).
+    #[serde(default)]
+    pub bandwidth_hz: Option<f64>,
+    /// Duration of the audible part of the call (seconds).
+    #[serde(default)]
+    pub call_duration_secs: Option<f64>,
+    /// Species the model predicted, when the detection was relabelled.
+    #[serde(default)]
+    pub original_scientific_name: Option<String>,
+    /// Common name the model predicted; the clip directory is named
+    /// after it.
+    #[serde(default)]
+    pub original_common_name: Option<String>,
+    /// Regional rarity (0.0 common – 1.0 never reported), when scored.
+    #[serde(default)]
+    pub rarity: Option<f64>,
+    /// Flagged rare by processing (`RARITY_THRESHOLD`).
+    #[serde(default)]
+    pub rare: bool,
+    /// Timezone-adjusted date for display (YYYY-MM-DD).
+    /// Same as `date` when tz_offset is 0 or unset.
+    /// Kept separate so `clip_url()` always uses the UTC `date` for file paths.
+    #[serde(default)]
+    pub display_date: String,
+    /// Timezone-adjusted time for display (HH:MM:SS).
+    #[serde(default)]
+    pub display_time: String,
+}
+
+impl WebDetection {
+    /// Build the URL to the extracted audio clip served by `/extracted/`.
+    ///
+    /// Clips are stored as:
+    ///   `{extracted_dir}/By_Date/{date}/{common_name_safe}/{file_name}`
+    ///
+    /// Directories written before names were sanitised are still served
+    /// through the legacy-directory fallback on `/extracted`.  Relabelled
+    /// detections keep their clip under the predicted name.
+    ///
+    /// Returns `None` if `file_name` is empty.
+    pub fn clip_url(&self) -> Option<String> {
+        if self.file_name.is_empty() {
+            return None;
+        }
+        let name = self.original_common_name.as_deref().unwrap_or(&self.common_name);
+        let safe_name = safe_path_component(name);
+        Some(format!(
+            "/extracted/By_Date/{}/{}/{}",
+            self.date, safe_name, self.file_name
+        ))
+    }
+
+    /// URL to the spectrogram PNG (generated alongside the audio clip).
+    ///
+    /// Spectrograms are named `{clip_file}.png`.  When the processing
+    /// server later transcodes a clip (e.g. `.wav`/`.mp3` → `.opus`) the
+    /// spectrogram is renamed with it, and `/extracted` resolves the stale
+    /// extension of either file to the transcoded one.
+    pub fn spectrogram_url(&self) -> Option<String> {
+        self.clip_url().map(|url| format!("{url}.png"))
+    }
+
+    /// URL of the time-expanded (slowed-down) companion clip.  Processing
+    /// writes one, as `{stem}.te.{ext}`, only for models flagged
+    /// `ultrasonic`, so whether it exists is for the server to say.
+    pub fn time_expanded_url(&self) -> Option<String> {
+        let url = self.clip_url()?;
+        let (stem, ext) = url.rsplit_once('.')?;
+        Some(format!("{stem}.te.{ext}"))
+    }
+
+    /// Stable link to this detection's own page (`/detection/{id}`).
+    pub fn permalink(&self) -> String {
+        format!("/detection/{}", self.id)
+    }
+
+    /// Human-friendly label for the capture node.
+    ///
+    /// Uses the `NODE_NAME` environment variable when the source is local
+    /// (localhost / 127.x), otherwise extracts hostname from the URL.
+    /// Returns `"local"` when no node was recorded and no name is set.
+    pub fn source_label(&self) -> String {
+        if self.source_node.is_empty() {
+            return node_name_or_local();
+        }
+        let stripped = self.source_node
+            .trim_start_matches("http://")
+            .trim_start_matches("https://")
+            .trim_end_matches('/');
+        let host = stripped.split(':').next().unwrap_or(stripped);
+        if host == "localhost" || host.starts_with("127.") {
+            return node_name_or_local();
+        }
+        // Remote node — show hostname portion (strip port)
+        host.trim_end_matches('.').to_string()
+    }
+
+    /// Display label for the model that produced this detection.
+    ///
+    /// Prefers `model_name` (human-readable) but falls back to
+    /// `model_slug` or `"Unknown model"`.
+    pub fn model_label(&self) -> String {
+        if !self.model_name.is_empty() {
+            self.model_name.clone()
+        } else if !self.model_slug.is_empty() {
+            self.model_slug.clone()
+        } else {
+            "Unknown model".to_string()
+        }
+    }
+
+    /// Version, variant and runtime of the model, e.g.
+    /// `"v2.4 · fp16 · tract-onnx"`; `None` for detections stored before
+    /// they were recorded.
+    pub fn model_provenance(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if !self.model_version.is_empty() {
+            parts.push(format!("v{}", self.model_version));
+        }
+        parts.extend(
+            [&self.model_variant, &self.model_runtime]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .cloned(),
+        );
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    /// Compact call-measurement label, e.g. `"42.1 kHz · bw 3.2 kHz · 12 ms"`.
+    ///
+    /// `None` for detections recorded before these were measured.
+    pub fn call_features_label(&self) -> Option<String> {
+        fn freq(hz: f64) -> String {
+            if hz >= 1000.0 {
+                format!("{:.1} kHz", hz / 1000.0)
+            } else {
+                format!("{hz:.0} Hz")
+            }
+        }
+        let peak = self.peak_freq_hz?;
+        let mut parts = vec![freq(peak)];
+        if let Some(bw) = self.bandwidth_hz {
+            parts.push(format!("bw {}", freq(bw)));
+        }
+        if let Some(d) = self.call_duration_secs {
+            if d < 1.0 {
+                parts.push(format!("{:.0} ms", d * 1000.0));
+            } else {
+                parts.push(format!("{d:.1} s"));
+            }
+        }
+        Some(parts.join(" · "))
+    }
+
+    /// `"132° SE"` for the estimated bearing, if any.
+    pub fn bearing_label(&self) -> Option<String> {
+        const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        let deg = self.bearing_deg?.rem_euclid(360.0);
+        let point = POINTS[((deg + 22.5) / 45.0) as usize % POINTS.len()];
+        Some(format!("{deg:.0}° {point}"))
+    }
+}
+
+// ─── Species summary (for species list) ──────────────────────────────────────
+
+/// Compact species row shown on the home or calendar page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesSummary {
+    pub scientific_name: String,
+    pub common_name: String,
+    pub domain: String,
+    pub detection_count: u32,
+    /// Human-friendly count for display (e.g. `"~230"`, `"42"`).
+    #[serde(default)]
+    pub display_count: String,
+    pub last_seen: Option<String>,
+    pub image_url: Option<String>,
+    /// IUCN conservation status (populated from iNaturalist).
+    #[serde(default)]
+    pub conservation_status: Option<ConservationStatus>,
+    /// Male specimen photo URL (from iNaturalist sex-annotated observations).
+    #[serde(default)]
+    pub male_image_url: Option<String>,
+    /// Female specimen photo URL (from iNaturalist sex-annotated observations).
+    #[serde(default)]
+    pub female_image_url: Option<String>,
+    /// Verification record (ornithologist or iNaturalist observation).
+    #[serde(default)]
+    pub verification: Option<SpeciesVerification>,
+    /// Detections per day, oldest first, for the sidebar sparkline.
+    /// Empty unless the caller asked for it.
+    #[serde(default)]
+    pub daily_counts: Vec<u32>,
+}
+
+/// Verification record for a species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesVerification {
+    /// `"ornithologist"` or `"inaturalist"`.
+    pub method: String,
+    /// iNaturalist observation URL/ID (only when method == "inaturalist").
+    #[serde(default)]
+    pub inaturalist_obs: String,
+    /// When the verification was recorded.
+    #[serde(default)]
+    pub verified_at: String,
+}
+
+// ─── Conservation status ─────────────────────────────────────────────────────
+
+/// IUCN Red List conservation status codes, ordered from most to least
+/// threatened.  The numeric values match the iNaturalist `iucn` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConservationStatus {
+    /// Extinct
+    EX = 70,
+    /// Extinct in the Wild
+    EW = 60,
+    /// Critically Endangered
+    CR = 50,
+    /// Endangered
+    EN = 40,
+    /// Vulnerable
+    VU = 30,
+    /// Near Threatened
+    NT = 20,
+    /// Least Concern
+    LC = 10,
+    /// Data Deficient
+    DD = 5,
+    /// Not Evaluated
+    NE = 0,
+}
+
+impl ConservationStatus {
+    /// Parse from the iNaturalist numeric `iucn` field.
+    pub fn from_iucn(code: u8) -> Option<Self> {
+        match code {
+            70 => Some(Self::EX),
+            60 => Some(Self::EW),
+            50 => Some(Self::CR),
+            40 => Some(Self::EN),
+            30 => Some(Self::VU),
+            20 => Some(Self::NT),
+            10 => Some(Self::LC),
+            5  => Some(Self::DD),
+            0  => Some(Self::NE),
+            _  => None,
+        }
+    }
+
+    /// Parse from a short IUCN code string (e.g. `"VU"`, `"EN"`).
+    pub fn from_code(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "EX" => Some(Self::EX),
+            "EW" => Some(Self::EW),
+            "CR" => Some(Self::CR),
+            "EN" => Some(Self::EN),
+            "VU" => Some(Self::VU),
+            "NT" => Some(Self::NT),
+            "LC" => Some(Self::LC),
+            "DD" => Some(Self::DD),
+            "NE" => Some(Self::NE),
+            _    => None,
+        }
+    }
+
+    /// Short IUCN code (e.g. `"CR"`, `"LC"`).
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::EX => "EX",
+            Self::EW => "EW",
+            Self::CR => "CR",
+            Self::EN => "EN",
+            Self::VU => "VU",
+            Self::NT => "NT",
+            Self::LC => "LC",
+            Self::DD => "DD",
+            Self::NE => "NE",
+        }
+    }
+
+    /// Human-readable label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::EX => "Extinct",
+            Self::EW => "Extinct in the Wild",
+            Self::CR => "Critically Endangered",
+            Self::EN => "Endangered",
+            Self::VU => "Vulnerable",
+            Self::NT => "Near Threatened",
+            Self::LC => "Least Concern",
+            Self::DD => "Data Deficient",
+            Self::NE => "Not Evaluated",
+        }
+    }
+
+    /// Numeric sort key — higher = more threatened.
+    pub fn threat_level(self) -> u8 {
+        self as u8
+    }
+
+    /// CSS modifier class for colour-coding the badge.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            Self::EX | Self::EW => "status-extinct",
+            Self::CR => "status-critical",
+            Self::EN => "status-endangered",
+            Self::VU => "status-vulnerable",
+            Self::NT => "status-near-threatened",
+            Self::LC => "status-least-concern",
+            Self::DD | Self::NE => "status-unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for ConservationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.label(), self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relabelled_detection_keeps_clip_under_predicted_name() {
+        let mut det: WebDetection = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "domain": "birds",
+            "scientific_name": "Corvus corax",
+            "common_name": "Common Raven",
+            "confidence": 0.8,
+            "date": "2025-05-01",
+            "time": "06:00:00",
+            "file_name": "clip.opus",
+            "source_node": "",
+            "original_scientific_name": "Corvus corone",
+            "original_common_name": "Carrion Crow",
+        }))
+        .unwrap();
+        assert_eq!(
+            det.clip_url().as_deref(),
+            Some("/extracted/By_Date/2025-05-01/Carrion_Crow/clip.opus")
+        );
+        assert_eq!(
+            det.spectrogram_url().as_deref(),
+            Some("/extracted/By_Date/2025-05-01/Carrion_Crow/clip.opus.png")
+        );
+
+        det.original_common_name = None;
+        assert_eq!(
+            det.clip_url().as_deref(),
+            Some("/extracted/By_Date/2025-05-01/Common_Raven/clip.opus")
+        );
+    }
+}
@@ -0,0 +1,520 @@
+//! Shared HTTP protocol types for communication between capture and
+//! processing servers, and for the processing server's headless REST API.
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the HTTP protocol spoken between Gaia nodes, i.e. of the
+/// routes and types in this module.  Bumped on incompatible changes only;
+/// nodes report theirs in `/api/health` and as the `protocol` mDNS TXT
+/// property.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Why a peer reporting `protocol` cannot be relied on, or `None` when it
+/// speaks [`PROTOCOL_VERSION`].  Nodes from before versioning report `0`.
+pub fn protocol_mismatch(protocol: u32) -> Option<String> {
+    match protocol {
+        PROTOCOL_VERSION => None,
+        0 => Some(format!(
+            "reports no protocol version (this node speaks {PROTOCOL_VERSION}); upgrade it"
+        )),
+        theirs => Some(format!(
+            "speaks protocol {theirs} but this node speaks {PROTOCOL_VERSION}; \
+             upgrade the older of the two"
+        )),
+    }
+}
+
+/// Information about a single recording available on the capture server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingInfo {
+    pub filename: String,
+    pub size: u64,
+    /// ISO-8601 creation timestamp.
+    pub created: String,
+}
+
+/// Health-check response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthResponse {
+    pub status: String,
+    pub uptime_secs: u64,
+    /// Current disk usage of the recording volume (0–100).
+    #[serde(default)]
+    pub disk_usage_pct: f64,
+    /// Current inode usage of the recording volume (0–100; 0 when the
+    /// filesystem has no inode limit).
+    #[serde(default)]
+    pub inode_usage_pct: f64,
+    /// `true` when capture is paused because disk or inode usage exceeds
+    /// the configured threshold.
+    #[serde(default)]
+    pub capture_paused: bool,
+    /// Persistent node UUID (see `gaia_common::node_id`).
+    #[serde(default)]
+    pub node_id: String,
+    /// Friendly node name, stored as `Source_Node` on detections.
+    #[serde(default)]
+    pub node_name: String,
+    /// Coordinates from the node's own `gaia.conf`, when set.  Processing
+    /// analyses and stores this node's recordings with them instead of
+    /// its own `LATITUDE` / `LONGITUDE`.
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    /// Clock synchronisation; `None` when it cannot be determined.
+    #[serde(default)]
+    pub time_sync: Option<TimeSyncStatus>,
+    /// Software version of the node, e.g. `"0.1.0"`.
+    #[serde(default)]
+    pub version: String,
+    /// [`PROTOCOL_VERSION`] of the node; `0` before nodes reported it.
+    #[serde(default)]
+    pub protocol: u32,
+}
+
+/// Clock synchronisation status of a node (see `gaia_common::timesync`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeSyncStatus {
+    /// Where the status came from: `chrony` or `timedatectl`.
+    pub source: String,
+    pub synchronized: bool,
+    /// Offset of the system clock from the reference, in milliseconds
+    /// (chrony only).
+    #[serde(default)]
+    pub offset_ms: Option<f64>,
+    /// NTP stratum; 1 for a GPS/PPS reference clock (chrony only).
+    #[serde(default)]
+    pub stratum: Option<u32>,
+    /// Time source, e.g. a server name or `GPS` (chrony only).
+    #[serde(default)]
+    pub reference: Option<String>,
+    /// RFC 3339 time of the last update from the reference (chrony only).
+    #[serde(default)]
+    pub last_sync: Option<String>,
+    /// Not synchronised, or off by more than `CLOCK_MAX_OFFSET_MS`.
+    #[serde(default)]
+    pub drifting: bool,
+}
+
+impl TimeSyncStatus {
+    /// Short description for logs, e.g. `offset +0.3 ms, stratum 1 (GPS)`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.synchronized {
+            parts.push("not synchronised".to_string());
+        }
+        if let Some(offset) = self.offset_ms {
+            parts.push(format!("offset {offset:+.1} ms"));
+        }
+        match (self.stratum, &self.reference) {
+            (Some(stratum), Some(reference)) => parts.push(format!("stratum {stratum} ({reference})")),
+            (Some(stratum), None) => parts.push(format!("stratum {stratum}")),
+            _ => {}
+        }
+        if parts.is_empty() {
+            parts.push("synchronised".to_string());
+        }
+        format!("{} via {}", parts.join(", "), self.source)
+    }
+}
+
+/// An ALSA capture device on a capture node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioDevice {
+    /// ALSA card number (order can change across reboots).
+    pub card: u32,
+    pub device: u32,
+    /// Stable card id, e.g. `iCE`.
+    pub card_id: String,
+    /// Human-readable card name, e.g. `Blue Snowball iCE`.
+    pub card_name: String,
+    pub device_name: String,
+    /// Value to put in `REC_CARD` to record from this device.
+    pub rec_card: String,
+}
+
+/// `GET /api/audio-devices` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDevicesResponse {
+    /// The configured `REC_CARD` (`default` when unset).
+    pub current: String,
+    pub devices: Vec<AudioDevice>,
+}
+
+/// Verdict on a capture node's input level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelStatus {
+    Ok,
+    /// Samples hit full scale — turn the gain down.
+    Clipping,
+    /// Signal present but very low — the gain is probably too low.
+    Quiet,
+    /// No signal at all (digital silence or a stuck ADC) — the
+    /// microphone is disconnected or dead.
+    Flatline,
+}
+
+/// `GET /api/levels` response: input level over the last finished
+/// segment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputLevels {
+    /// Segment the levels were measured on.
+    pub filename: String,
+    /// ISO-8601 time of the measurement.
+    pub measured_at: String,
+    pub channels: u16,
+    /// RMS level (DC removed) in dBFS, loudest channel.
+    pub rms_dbfs: f64,
+    /// Absolute peak in dBFS.
+    pub peak_dbfs: f64,
+    /// Share of samples at (or within 0.1% of) full scale.
+    pub clipped_fraction: f64,
+    pub status: LevelStatus,
+}
+
+/// Watchdog verdict on one network input stream of a capture node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamState {
+    /// Segments are being written.
+    Recording,
+    /// ffmpeg was (re)started and has not written audio yet.
+    Starting,
+    /// ffmpeg could not be started; retried on the next check.
+    Down,
+}
+
+/// Health of one `RTSP_STREAMS` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamStatus {
+    /// 1-based stream number, as in the `RTSP_<n>` segment names.
+    pub index: usize,
+    /// Stream URL with any credentials removed.
+    pub url: String,
+    pub state: StreamState,
+    /// Seconds since the stream last wrote audio.
+    pub last_audio_secs: u64,
+    /// Times the watchdog restarted ffmpeg for this stream.
+    pub restarts: u32,
+    /// ISO-8601 time of the latest restart.
+    #[serde(default)]
+    pub last_restart: Option<String>,
+}
+
+/// `GET /api/status` response of a capture node.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureStatus {
+    #[serde(default)]
+    pub node_name: String,
+    #[serde(default)]
+    pub capture_paused: bool,
+    /// Disk and inode usage of the recording volume (0–100).
+    #[serde(default)]
+    pub disk_usage_pct: f64,
+    #[serde(default)]
+    pub inode_usage_pct: f64,
+    /// Network streams; empty when recording from a local microphone.
+    #[serde(default)]
+    pub streams: Vec<StreamStatus>,
+}
+
+/// How the capture self-test feeds its reference signal in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestMode {
+    /// Write the signal into the recording directory as a segment,
+    /// testing everything downstream of the microphone.
+    #[default]
+    Inject,
+    /// Play it on the default ALSA output (`aplay`) and expect the
+    /// microphone to pick it up.
+    Play,
+}
+
+/// Outcome of a capture self-test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestState {
+    Running,
+    Passed,
+    Failed,
+}
+
+/// One check of a self-test, in the order they ran.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfTestStep {
+    pub name: String,
+    pub ok: bool,
+    /// What was seen, or why the check failed.
+    pub detail: String,
+}
+
+/// `POST /api/selftest` body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelfTestRequest {
+    #[serde(default)]
+    pub mode: SelfTestMode,
+}
+
+/// `GET /api/selftest` response: the latest (or running) self-test.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub mode: SelfTestMode,
+    pub state: SelfTestState,
+    /// ISO-8601 start and end times; `finished_at` is `None` while
+    /// running.
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Segment the reference signal ended up in, once known.
+    pub recording: Option<String>,
+    /// Species the processing server was expected to detect, when a
+    /// detection check was configured.
+    pub expected_label: Option<String>,
+    pub steps: Vec<SelfTestStep>,
+}
+
+/// Server-Sent Event payload for new-recording notifications.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewRecordingEvent {
+    pub filename: String,
+    pub size: u64,
+}
+
+/// Query parameters of the processing server's `GET /api/detections`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetectionQuery {
+    /// A detection id (exclusive) to page forward from, or a
+    /// `YYYY-MM-DD` / `YYYY-MM-DDTHH:MM:SS` timestamp.  Without it the
+    /// most recent `limit` detections are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// At most this many detections (default 100, capped at 5000).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Scientific name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub species: Option<String>,
+    /// Model slug.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f64>,
+}
+
+/// A stored detection as returned by the processing server's
+/// `GET /api/detections`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionRecord {
+    /// Unique, chronologically sortable id; pass the last one back as
+    /// `since` to page forward.
+    pub id: i64,
+    pub date: String,
+    pub time: String,
+    pub domain: String,
+    pub scientific_name: String,
+    pub common_name: String,
+    pub confidence: f64,
+    pub model_slug: String,
+    pub model_name: String,
+    /// Model version, download variant and inference runtime; empty for
+    /// detections stored before they were recorded.
+    #[serde(default)]
+    pub model_version: String,
+    #[serde(default)]
+    pub model_variant: String,
+    #[serde(default)]
+    pub model_runtime: String,
+    /// Input channel (0-based) with `CHANNEL_MODE=separate`.
+    #[serde(default)]
+    pub channel: Option<u16>,
+    pub source_node: String,
+    pub file_name: String,
+    pub excluded: bool,
+    pub agreement_score: f64,
+}
+
+/// A model loaded by the processing server (`GET /api/models`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelStatus {
+    pub slug: String,
+    pub name: String,
+    pub domain: String,
+    pub sample_rate: u32,
+    pub chunk_duration: f64,
+    pub beta: bool,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub variant: String,
+    #[serde(default)]
+    pub runtime: String,
+    /// Whether the model is currently enabled in Settings.
+    pub enabled: bool,
+}
+
+/// Processing server health (`GET /api/health`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingHealth {
+    pub status: String,
+    pub uptime_secs: u64,
+    pub models_loaded: usize,
+    /// Parquet files in the detections directory.
+    pub parquet_files: usize,
+    /// Detections buffered in memory, not yet flushed to Parquet.
+    pub buffered_detections: usize,
+    /// Recordings waiting to be analysed, per capture node.
+    #[serde(default)]
+    pub node_backlog: Vec<NodeBacklog>,
+    /// `true` while clip extraction is paused because the data volume is
+    /// nearly full; detections are still stored, without clips.
+    #[serde(default)]
+    pub extraction_paused: bool,
+    /// Clock synchronisation; `None` when it cannot be determined.
+    #[serde(default)]
+    pub time_sync: Option<TimeSyncStatus>,
+    /// Software version of the server.
+    #[serde(default)]
+    pub version: String,
+    /// [`PROTOCOL_VERSION`] of the server.
+    #[serde(default)]
+    pub protocol: u32,
+}
+
+/// Web dashboard health (`GET /api/health` on gaia-web).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebHealth {
+    pub status: String,
+    pub uptime_secs: u64,
+    /// Software version of the dashboard.
+    pub version: String,
+    /// [`PROTOCOL_VERSION`] of the dashboard.
+    pub protocol: u32,
+}
+
+/// Analysis backlog of one capture node, as of the last poll.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeBacklog {
+    /// Capture node name, or its URL for nodes that don't report one.
+    pub node: String,
+    /// Recordings on the node not yet dispatched for analysis.
+    pub pending: usize,
+    /// Scheduling weight from `NODE_PRIORITIES`.
+    pub priority: u32,
+}
+
+/// What the processing pipeline is doing right now: written to
+/// `<data>/pipeline_status.json` and served at `GET /api/pipeline`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineStatus {
+    /// RFC 3339 time of the last update; refreshed every few seconds
+    /// even when idle, so a stale value means the server is down.
+    pub updated_at: String,
+    /// One entry per analysis worker.
+    pub workers: Vec<WorkerActivity>,
+    /// Recordings queued for download from the capture nodes.
+    pub download_queue: usize,
+    /// Downloaded recordings waiting for a free worker.
+    pub analysis_queue: usize,
+    /// Recordings analysed since the server started.
+    pub files_analysed: u64,
+    /// Recordings waiting to be analysed, per capture node.
+    #[serde(default)]
+    pub node_backlog: Vec<NodeBacklog>,
+    /// Clip extraction paused because the data volume is nearly full.
+    #[serde(default)]
+    pub extraction_paused: bool,
+    /// Chunks whose model outputs came from the chunk cache, and chunks
+    /// that had to be scored, since the server started.
+    #[serde(default)]
+    pub chunk_cache_hits: u64,
+    #[serde(default)]
+    pub chunk_cache_misses: u64,
+}
+
+impl PipelineStatus {
+    /// Share of scored chunks answered from the chunk cache, 0–1.
+    pub fn chunk_cache_hit_rate(&self) -> f64 {
+        let total = self.chunk_cache_hits + self.chunk_cache_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.chunk_cache_hits as f64 / total as f64
+        }
+    }
+}
+
+/// The recording an analysis worker is busy with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkerActivity {
+    pub worker: usize,
+    /// File name of the recording; `None` while idle.
+    pub file: Option<String>,
+    /// Capture node the recording came from.
+    #[serde(default)]
+    pub source_node: String,
+    /// Model currently running on it.
+    pub model: Option<String>,
+    /// Chunks scored so far out of `chunks`, for the current model.
+    pub chunk: usize,
+    pub chunks: usize,
+}
+
+/// A folder below the processing server's `IMPORT_DIR`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportFolder {
+    pub name: String,
+    /// Audio files in it, subfolders included.
+    pub audio_files: usize,
+}
+
+/// `POST /api/import` body: analyse the audio files of `folder`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolderImportRequest {
+    /// Name of a folder listed by `GET /api/import`.
+    pub folder: String,
+    /// `Source_Node` of the detections; empty for `import-<folder>`.
+    #[serde(default)]
+    pub node: String,
+}
+
+/// Lifecycle of a folder import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderImportState {
+    /// Copying files into the analysis queue.
+    Queuing,
+    /// Every file queued; waiting for the workers.
+    Analysing,
+    Done,
+    Failed,
+}
+
+/// Progress of a folder import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderImportJob {
+    pub folder: String,
+    pub node: String,
+    pub state: FolderImportState,
+    /// ISO-8601 start time.
+    pub started_at: String,
+    /// Audio files found in the folder.
+    pub files: usize,
+    /// Files imported before (by name and node), not analysed again.
+    pub skipped: usize,
+    /// Files handed to the workers so far.
+    pub queued: usize,
+    /// Queued files the workers are done with.
+    pub analysed: usize,
+    /// Why the import stopped, for `Failed`.
+    pub error: Option<String>,
+}
+
+/// `GET /api/import` response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FolderImports {
+    pub folders: Vec<ImportFolder>,
+    /// The running or last import since the server started.
+    pub job: Option<FolderImportJob>,
+}
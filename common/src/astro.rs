@@ -0,0 +1,83 @@
+//! Moon phase and season of a detection, for nocturnal-activity analytics.
+//!
+//! The phase is taken from the mean synodic month counted from a reference
+//! new moon.  That is within about half a day of the true phase — much finer
+//! than the eight named phases it is reported in.
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone};
+
+/// Mean length of a lunar cycle (days).
+pub const SYNODIC_MONTH_DAYS: f64 = 29.530_588_853;
+
+/// A new moon: 2000-01-06 18:14 UTC, as Unix seconds.
+const REFERENCE_NEW_MOON: i64 = 947_182_440;
+
+/// The eight named phases, starting at new moon.
+pub const PHASE_NAMES: [&str; 8] = [
+    "New moon",
+    "Waxing crescent",
+    "First quarter",
+    "Waxing gibbous",
+    "Full moon",
+    "Waning gibbous",
+    "Last quarter",
+    "Waning crescent",
+];
+
+/// Position in the lunar cycle at `t`: 0.0 = new, 0.5 = full, up to 1.0.
+pub fn moon_phase<Tz: TimeZone>(t: &DateTime<Tz>) -> f64 {
+    let days = (t.timestamp() - REFERENCE_NEW_MOON) as f64 / 86_400.0;
+    (days / SYNODIC_MONTH_DAYS).rem_euclid(1.0)
+}
+
+/// Illuminated fraction of the disc (0.0 – 1.0) at a [`moon_phase`].
+pub fn moon_illumination(phase: f64) -> f64 {
+    (1.0 - (std::f64::consts::TAU * phase).cos()) / 2.0
+}
+
+/// Index into [`PHASE_NAMES`] for a [`moon_phase`].  Each named phase is
+/// centred on its exact moment, e.g. full moon covers 0.4375 – 0.5625.
+pub fn phase_index(phase: f64) -> usize {
+    ((phase * 8.0).round() as usize) % 8
+}
+
+/// Meteorological season (`winter`, `spring`, `summer`, `autumn`) of
+/// `date`, flipped for negative latitudes.
+pub fn season(date: NaiveDate, latitude: f64) -> &'static str {
+    const NORTH: [&str; 4] = ["winter", "spring", "summer", "autumn"];
+    // Dec–Feb → 0, Mar–May → 1, Jun–Aug → 2, Sep–Nov → 3.
+    let idx = (date.month() % 12 / 3) as usize;
+    if latitude < 0.0 {
+        NORTH[(idx + 2) % 4]
+    } else {
+        NORTH[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_moon_phase() {
+        // Full moon 2024-01-25 17:54 UTC, new moon 2024-01-11 11:57 UTC.
+        let full = Utc.with_ymd_and_hms(2024, 1, 25, 17, 54, 0).unwrap();
+        let new = Utc.with_ymd_and_hms(2024, 1, 11, 11, 57, 0).unwrap();
+        assert_eq!(PHASE_NAMES[phase_index(moon_phase(&full))], "Full moon");
+        assert_eq!(PHASE_NAMES[phase_index(moon_phase(&new))], "New moon");
+        assert!(moon_illumination(moon_phase(&full)) > 0.99);
+        assert!(moon_illumination(moon_phase(&new)) < 0.01);
+        assert_eq!(phase_index(0.99), 0);
+    }
+
+    #[test]
+    fn test_season() {
+        let july = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let december = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+        assert_eq!(season(july, 50.0), "summer");
+        assert_eq!(season(july, -33.9), "winter");
+        assert_eq!(season(december, 9.9), "winter");
+        assert_eq!(season(NaiveDate::from_ymd_opt(2024, 4, 15).unwrap(), 9.9), "spring");
+    }
+}
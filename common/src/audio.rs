@@ -2,9 +2,11 @@
 //!
 //! Reused from `birdnet-server/src/audio.rs`.
 //! Provides WAV I/O, mono conversion, rubato-based resampling, overlapping
-//! chunking, and clip extraction.
+//! chunking, clip extraction, and pre-analysis integrity checks.
 
+use std::collections::HashMap;
 use std::io::Cursor;
+use std::path::PathBuf;
 use std::process::Command;
 
 use anyhow::{Context, Result};
@@ -12,14 +14,39 @@ use rubato::{Fft, FixedSync, Resampler};
 use audioadapter_buffers::direct::SequentialSliceOfVecs;
 use tracing::{debug, info};
 
-/// Read a WAV file, convert to mono f32, resample to `target_sr`, and split
-/// into overlapping chunks of `chunk_duration` seconds.
+use crate::protocol::{InputLevels, LevelStatus};
+
+/// Read a WAV file, convert to mono f32, resample to `target_sr`, apply
+/// `filter` and split into overlapping chunks of `chunk_duration` seconds.
 pub fn read_audio(
     path: &std::path::Path,
     target_sr: u32,
     chunk_duration: f64,
     overlap: f64,
+    filter: &PreFilter,
 ) -> Result<Vec<Vec<f32>>> {
+    let mut signal = load_audio(path, target_sr)?;
+    filter.apply(&mut signal, target_sr);
+    let chunks = split_signal(&signal, target_sr, chunk_duration, overlap, 1.5);
+    info!("Split into {} chunk(s)", chunks.len());
+    Ok(chunks)
+}
+
+/// Decode an audio file to a mono f32 signal at `target_sr`.
+///
+/// WAV files are decoded in-process and resampled with rubato; other
+/// formats go through ffmpeg.
+pub fn load_audio(path: &std::path::Path, target_sr: u32) -> Result<Vec<f32>> {
+    load_audio_channel(path, target_sr, None)
+}
+
+/// Like [`load_audio`], but keep only `channel` (0-based) instead of
+/// averaging all channels when one is given.
+pub fn load_audio_channel(
+    path: &std::path::Path,
+    target_sr: u32,
+    channel: Option<u16>,
+) -> Result<Vec<f32>> {
     info!("Reading audio: {}", path.display());
 
     let ext = path
@@ -39,36 +66,15 @@ pub fn read_audio(
         let spec = reader.spec();
         let native_sr = spec.sample_rate;
         let n_channels = spec.channels as usize;
+        let samples = normalised_samples(reader);
 
-        let samples: Vec<f32> = match spec.sample_format {
-            hound::SampleFormat::Int => {
-                let bits = spec.bits_per_sample.clamp(1, 32) as u32;
-                let max_amplitude = if bits == 32 {
-                    i32::MAX as f32
-                } else {
-                    ((1_i64 << (bits - 1)) - 1) as f32
-                };
-
-                reader
-                    .into_samples::<i32>()
-                    .take_while(|s| s.is_ok())
-                    .map(|s| (s.unwrap() as f32 / max_amplitude).clamp(-1.0, 1.0))
-                    .collect()
-            }
-            hound::SampleFormat::Float => reader
-                .into_samples::<f32>()
-                .take_while(|s| s.is_ok())
-                .map(|s| s.unwrap())
-                .collect(),
-        };
-
-        let mono: Vec<f32> = if n_channels == 1 {
-            samples
-        } else {
-            samples
-                .chunks(n_channels)
-                .map(|frame| frame.iter().sum::<f32>() / n_channels as f32)
-                .collect()
+        let mono = match channel {
+            Some(ch) if usize::from(ch) >= n_channels => anyhow::bail!(
+                "{} has {n_channels} channel(s), no channel {ch}",
+                path.display()
+            ),
+            Some(ch) => select_channel(&samples, n_channels, usize::from(ch)),
+            None => downmix(samples, n_channels),
         };
         debug!("Read {} mono samples at {} Hz", mono.len(), native_sr);
 
@@ -78,26 +84,196 @@ pub fn read_audio(
             resample(&mono, native_sr, target_sr)?
         }
     } else {
-        decode_audio_ffmpeg(path, target_sr)?
+        decode_audio_ffmpeg(path, target_sr, channel)?
     };
     info!(
         "Audio ready: {} samples at {} Hz",
         resampled.len(),
         target_sr
     );
+    Ok(resampled)
+}
 
-    let chunks = split_signal(&resampled, target_sr, chunk_duration, overlap, 1.5);
-    info!("Split into {} chunk(s)", chunks.len());
-    Ok(chunks)
+/// Average interleaved `samples` of `n_channels` channels to mono.
+fn downmix(samples: Vec<f32>, n_channels: usize) -> Vec<f32> {
+    if n_channels <= 1 {
+        return samples;
+    }
+    samples
+        .chunks(n_channels)
+        .map(|frame| frame.iter().sum::<f32>() / n_channels as f32)
+        .collect()
+}
+
+/// Channel `channel` of interleaved `samples` of `n_channels` channels.
+fn select_channel(samples: &[f32], n_channels: usize, channel: usize) -> Vec<f32> {
+    samples
+        .chunks_exact(n_channels.max(1))
+        .map(|frame| frame[channel])
+        .collect()
+}
+
+/// Number of channels of a WAV file, read from its header.  `None` for
+/// other formats or unreadable files.
+pub fn channel_count(path: &std::path::Path) -> Option<u16> {
+    let is_wav = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return None;
+    }
+    hound::WavReader::open(path).ok().map(|r| r.spec().channels)
+}
+
+/// How the channels of a multi-channel recording are analysed
+/// (`CHANNEL_MODE`, per node `NODE_CHANNEL_MODES`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelMode {
+    /// Average the channels to mono (default).
+    #[default]
+    Mix,
+    /// Analyse each channel on its own; detections record the channel.
+    Separate,
+}
+
+impl ChannelMode {
+    /// Parse a `CHANNEL_MODE` value; unknown values mean [`ChannelMode::Mix`].
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "separate" | "per-channel" | "split" => Self::Separate,
+            _ => Self::Mix,
+        }
+    }
+}
+
+/// One recording's decoded signal at each sample rate asked for so far.
+///
+/// Several models often share a sample rate; going through the cache
+/// decodes and resamples the file once per rate instead of once per model.
+pub struct AudioCache {
+    path: PathBuf,
+    /// Single channel to decode instead of the downmix.
+    channel: Option<u16>,
+    signals: HashMap<u32, Vec<f32>>,
 }
 
-fn decode_audio_ffmpeg(path: &std::path::Path, target_sr: u32) -> Result<Vec<f32>> {
+impl AudioCache {
+    pub fn new(path: &std::path::Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            channel: None,
+            signals: HashMap::new(),
+        }
+    }
+
+    /// A cache of one channel (0-based) of the recording.
+    pub fn for_channel(path: &std::path::Path, channel: u16) -> Self {
+        Self {
+            channel: Some(channel),
+            ..Self::new(path)
+        }
+    }
+
+    /// The mono signal at `sample_rate`, decoded on first use.
+    pub fn signal(&mut self, sample_rate: u32) -> Result<&[f32]> {
+        if !self.signals.contains_key(&sample_rate) {
+            let signal = load_audio_channel(&self.path, sample_rate, self.channel)?;
+            self.signals.insert(sample_rate, signal);
+        } else {
+            debug!(
+                "Reusing decoded audio at {} Hz: {}",
+                sample_rate,
+                self.path.display()
+            );
+        }
+        Ok(&self.signals[&sample_rate])
+    }
+
+    /// Length in seconds of the recording, if it has been decoded.
+    pub fn duration_secs(&self) -> Option<f64> {
+        self.signals
+            .iter()
+            .next()
+            .map(|(&sr, signal)| signal.len() as f64 / sr as f64)
+    }
+
+    /// Like [`read_audio`] without a filter, from the cached signal.
+    pub fn chunks(
+        &mut self,
+        sample_rate: u32,
+        chunk_duration: f64,
+        overlap: f64,
+    ) -> Result<Vec<Vec<f32>>> {
+        self.chunks_with(
+            sample_rate,
+            Chunking::Fixed(chunk_duration),
+            overlap,
+            &PreFilter::default(),
+        )
+    }
+
+    /// The cached signal, filtered by `filter`, cut as `chunking` says.
+    /// The cache keeps the unfiltered signal, since models sharing a
+    /// sample rate may filter differently.
+    pub fn chunks_with(
+        &mut self,
+        sample_rate: u32,
+        chunking: Chunking,
+        overlap: f64,
+        filter: &PreFilter,
+    ) -> Result<Vec<Vec<f32>>> {
+        let signal = self.signal(sample_rate)?;
+        let chunks = if filter.is_active() {
+            let mut filtered = signal.to_vec();
+            filter.apply(&mut filtered, sample_rate);
+            chunking.split(&filtered, sample_rate, overlap)
+        } else {
+            chunking.split(signal, sample_rate, overlap)
+        };
+        info!("Split into {} chunk(s)", chunks.len());
+        Ok(chunks)
+    }
+}
+
+/// How a model wants its input cut.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Chunking {
+    /// Every chunk exactly this many seconds; the last one is zero-padded.
+    Fixed(f64),
+    /// Windows of up to `max` seconds (the whole recording when `None`),
+    /// zero-padded only when shorter than `min` seconds.
+    Variable { min: f64, max: Option<f64> },
+}
+
+impl Chunking {
+    /// Split `sig` into chunks.  Tails with less than 1.5 s of audio
+    /// (or `min`, if shorter) are dropped, as they are mostly padding.
+    pub fn split(&self, sig: &[f32], rate: u32, overlap: f64) -> Vec<Vec<f32>> {
+        match *self {
+            Chunking::Fixed(seconds) => split_signal(sig, rate, seconds, overlap, 1.5),
+            Chunking::Variable { min, max } => {
+                split_signal_variable(sig, rate, min, max, overlap, min.min(1.5))
+            }
+        }
+    }
+}
+
+fn decode_audio_ffmpeg(
+    path: &std::path::Path,
+    target_sr: u32,
+    channel: Option<u16>,
+) -> Result<Vec<f32>> {
     debug!(
         "Decoding non-WAV audio via ffmpeg: {} → mono f32 @ {} Hz",
         path.display(),
         target_sr
     );
 
+    // `-ac 1` downmixes; the pan filter picks a single channel instead.
+    let to_mono = match channel {
+        Some(ch) => ["-af".to_string(), format!("pan=mono|c0=c{ch}")],
+        None => ["-ac".to_string(), "1".to_string()],
+    };
     let output = Command::new("ffmpeg")
         .args([
             "-hide_banner",
@@ -108,8 +284,8 @@ fn decode_audio_ffmpeg(path: &std::path::Path, target_sr: u32) -> Result<Vec<f32
             path.to_string_lossy().as_ref(),
             "-f",
             "f32le",
-            "-ac",
-            "1",
+            &to_mono[0],
+            &to_mono[1],
             "-ar",
             &target_sr.to_string(),
             "pipe:1",
@@ -203,6 +379,51 @@ pub fn split_signal(
     chunks
 }
 
+/// Split a signal into windows of up to `max` seconds (one window with
+/// the whole signal when `None`), each starting `max - overlap` after
+/// the previous one.  Unlike [`split_signal`] the last window keeps its
+/// own length; windows shorter than `min` are zero-padded to it and
+/// windows with less than `min_len` seconds of audio are discarded.
+pub fn split_signal_variable(
+    sig: &[f32],
+    rate: u32,
+    min: f64,
+    max: Option<f64>,
+    overlap: f64,
+    min_len: f64,
+) -> Vec<Vec<f32>> {
+    let window = max.map_or(sig.len(), |m| (m * rate as f64) as usize).max(1);
+    // An overlap as long as the window would never advance.
+    let step = max
+        .map(|m| ((m - overlap) * rate as f64) as usize)
+        .filter(|&s| s > 0)
+        .unwrap_or(window);
+    let min_samples = (min * rate as f64) as usize;
+    let min_len_samples = (min_len * rate as f64) as usize;
+
+    let mut chunks = Vec::new();
+    let mut i = 0;
+    while i < sig.len() {
+        let end = (i + window).min(sig.len());
+        let split = &sig[i..end];
+
+        if split.len() < min_len_samples {
+            break;
+        }
+
+        let mut chunk = split.to_vec();
+        if chunk.len() < min_samples {
+            chunk.resize(min_samples, 0.0);
+        }
+        chunks.push(chunk);
+        if end == sig.len() {
+            break;
+        }
+        i += step;
+    }
+    chunks
+}
+
 /// Extract a section of a WAV file and write it to `out_path`.
 pub fn extract_clip(
     in_path: &std::path::Path,
@@ -250,54 +471,562 @@ pub fn extract_clip(
         return Ok(());
     }
 
-    let mut raw = std::fs::read(in_path)
-        .with_context(|| format!("Cannot read {}", in_path.display()))?;
-    fix_wav_data_chunk(&mut raw);
-    let reader = hound::WavReader::new(Cursor::new(raw))
-        .with_context(|| format!("Cannot parse WAV: {}", in_path.display()))?;
-    let spec = reader.spec();
+    let (spec, all_samples) = read_pcm16(in_path)?;
     let sr = spec.sample_rate as f64;
     let ch = spec.channels as usize;
 
     let start_sample = (start_sec * sr) as usize * ch;
     let stop_sample = (stop_sec * sr) as usize * ch;
+    let start = start_sample.min(all_samples.len());
+    let stop = stop_sample.min(all_samples.len());
+    write_pcm16(out_path, spec.sample_rate, spec.channels, &all_samples[start..stop])
+}
+
+/// The end of a recording, kept after the file itself is gone so the
+/// clip of a call at the start of the next segment can reach back into
+/// it (see [`extract_clip_with_pre_roll`]).
+#[derive(Debug, Clone)]
+pub struct PreRoll {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Interleaved 16-bit samples of the last seconds of the recording.
+    pub samples: Vec<i16>,
+    /// Length of the whole recording in seconds.
+    pub file_duration: f64,
+}
+
+impl PreRoll {
+    pub fn duration_secs(&self) -> f64 {
+        self.samples.len() as f64 / (self.sample_rate as f64 * self.channels.max(1) as f64)
+    }
+}
+
+/// Read the last `secs` seconds of a WAV file.
+pub fn read_pre_roll(path: &std::path::Path, secs: f64) -> Result<PreRoll> {
+    let (spec, samples) = read_pcm16(path)?;
+    let ch = spec.channels.max(1) as usize;
+    let frames = samples.len() / ch;
+    let keep = ((secs * spec.sample_rate as f64) as usize).min(frames);
+    Ok(PreRoll {
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        samples: samples[(frames - keep) * ch..frames * ch].to_vec(),
+        file_duration: frames as f64 / spec.sample_rate as f64,
+    })
+}
+
+/// Like [`extract_clip`], but a negative `start_sec` reaches back into
+/// `pre_roll`, the end of the segment recorded just before `in_path`.
+/// Falls back to starting at 0 for non-WAV input or when the formats of
+/// the two segments differ.
+pub fn extract_clip_with_pre_roll(
+    pre_roll: &PreRoll,
+    in_path: &std::path::Path,
+    out_path: &std::path::Path,
+    start_sec: f64,
+    stop_sec: f64,
+) -> Result<()> {
+    let is_wav = in_path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if start_sec >= 0.0 || !is_wav {
+        return extract_clip(in_path, out_path, start_sec.max(0.0), stop_sec);
+    }
+    let (spec, all_samples) = read_pcm16(in_path)?;
+    if spec.sample_rate != pre_roll.sample_rate || spec.channels != pre_roll.channels {
+        debug!(
+            "Previous segment has a different format; clip of {} starts at 0",
+            in_path.display()
+        );
+        return extract_clip(in_path, out_path, 0.0, stop_sec);
+    }
+    let sr = spec.sample_rate as f64;
+    let ch = spec.channels as usize;
+
+    let lead = ((-start_sec * sr) as usize * ch).min(pre_roll.samples.len());
+    let stop = ((stop_sec * sr) as usize * ch).min(all_samples.len());
+    let mut clip = Vec::with_capacity(lead + stop);
+    clip.extend_from_slice(&pre_roll.samples[pre_roll.samples.len() - lead..]);
+    clip.extend_from_slice(&all_samples[..stop]);
+    write_pcm16(out_path, spec.sample_rate, spec.channels, &clip)
+}
 
-    let all_samples: Vec<i16> = match spec.sample_format {
-        hound::SampleFormat::Int => reader
+/// Read a WAV file as interleaved 16-bit samples.  16-bit and narrower
+/// integer samples are copied as they are; wider and float encodings are
+/// rescaled to 16 bits.
+fn read_pcm16(path: &std::path::Path) -> Result<(hound::WavSpec, Vec<i16>)> {
+    let mut raw = std::fs::read(path)
+        .with_context(|| format!("Cannot read {}", path.display()))?;
+    fix_wav_data_chunk(&mut raw);
+    let reader = hound::WavReader::new(Cursor::new(raw))
+        .with_context(|| format!("Cannot parse WAV: {}", path.display()))?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Int if spec.bits_per_sample <= 16 => reader
             .into_samples::<i16>()
             .take_while(|s| s.is_ok())
             .map(|s| s.unwrap())
             .collect(),
-        hound::SampleFormat::Float => reader
-            .into_samples::<f32>()
-            .take_while(|s| s.is_ok())
-            .map(|s| (s.unwrap() * i16::MAX as f32) as i16)
+        _ => normalised_samples(reader)
+            .into_iter()
+            .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16)
             .collect(),
     };
+    Ok((spec, samples))
+}
 
-    let start = start_sample.min(all_samples.len());
-    let stop = stop_sample.min(all_samples.len());
-    let clip = &all_samples[start..stop];
-
+fn write_pcm16(out_path: &std::path::Path, sample_rate: u32, channels: u16, samples: &[i16]) -> Result<()> {
     if let Some(parent) = out_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
     let out_spec = hound::WavSpec {
-        channels: spec.channels,
-        sample_rate: spec.sample_rate,
+        channels,
+        sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
     let mut writer = hound::WavWriter::create(out_path, out_spec)
         .with_context(|| format!("Cannot create {}", out_path.display()))?;
-    for &sample in clip {
+    for &sample in samples {
         writer.write_sample(sample)?;
     }
     writer.finalize()?;
     Ok(())
 }
 
+// ── time expansion ───────────────────────────────────────────────────────
+
+/// Name of the time-expanded companion of a clip:
+/// `a.wav` → `a.te.wav`.  gaia-web derives the same name from the clip URL.
+pub fn time_expanded_path(clip: &std::path::Path) -> std::path::PathBuf {
+    let stem = clip.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = clip.extension().unwrap_or_default().to_string_lossy();
+    clip.with_file_name(format!("{stem}.te.{ext}"))
+}
+
+/// Write a time-expanded copy of a WAV clip: the same samples with the
+/// sample rate divided by `factor`, so it plays `factor` times slower and
+/// a 40 kHz bat call comes out at 4 kHz.
+///
+/// `highpass_hz` (at the recorded rate) removes low-frequency noise first;
+/// after expansion it would otherwise become rumble below the calls.
+pub fn time_expand_clip(
+    in_path: &std::path::Path,
+    out_path: &std::path::Path,
+    factor: u32,
+    highpass_hz: Option<f64>,
+) -> Result<()> {
+    let mut raw = std::fs::read(in_path)
+        .with_context(|| format!("Cannot read {}", in_path.display()))?;
+    fix_wav_data_chunk(&mut raw);
+    let reader = hound::WavReader::new(Cursor::new(raw))
+        .with_context(|| format!("Cannot parse WAV: {}", in_path.display()))?;
+    let spec = reader.spec();
+    let mut samples = normalised_samples(reader);
+    if let Some(cutoff) = highpass_hz {
+        highpass(&mut samples, spec.channels, spec.sample_rate, cutoff);
+    }
+
+    let out_spec = hound::WavSpec {
+        channels: spec.channels,
+        sample_rate: (spec.sample_rate / factor.max(1)).max(1),
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(out_path, out_spec)
+        .with_context(|| format!("Cannot create {}", out_path.display()))?;
+    for s in samples {
+        writer.write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// Second-order Butterworth high-pass over interleaved samples, in place.
+/// Cutoffs outside `0 < cutoff < Nyquist` leave the samples untouched.
+pub fn highpass(samples: &mut [f32], channels: u16, sample_rate: u32, cutoff_hz: f64) {
+    if let Some(filter) = Biquad::highpass(sample_rate, cutoff_hz) {
+        filter.process(samples, channels);
+    }
+}
+
+// ── pre-filtering ────────────────────────────────────────────────────────
+
+/// Second-order Butterworth section (RBJ audio-EQ cookbook, Q = 1/√2),
+/// normalised so that `a0 = 1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    /// High-pass at `cutoff_hz`; `None` unless `0 < cutoff < Nyquist`.
+    pub fn highpass(sample_rate: u32, cutoff_hz: f64) -> Option<Self> {
+        let (cos, alpha) = Self::prewarp(sample_rate, cutoff_hz)?;
+        let a0 = 1.0 + alpha;
+        let b0 = (1.0 + cos) / 2.0 / a0;
+        Some(Biquad {
+            b: [b0, -2.0 * b0, b0],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+        })
+    }
+
+    /// Low-pass at `cutoff_hz`; `None` unless `0 < cutoff < Nyquist`.
+    pub fn lowpass(sample_rate: u32, cutoff_hz: f64) -> Option<Self> {
+        let (cos, alpha) = Self::prewarp(sample_rate, cutoff_hz)?;
+        let a0 = 1.0 + alpha;
+        let b0 = (1.0 - cos) / 2.0 / a0;
+        Some(Biquad {
+            b: [b0, 2.0 * b0, b0],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+        })
+    }
+
+    fn prewarp(sample_rate: u32, cutoff_hz: f64) -> Option<(f64, f64)> {
+        let fs = sample_rate as f64;
+        if !(cutoff_hz > 0.0 && cutoff_hz < fs / 2.0) {
+            return None;
+        }
+        let (sin, cos) = (std::f64::consts::TAU * cutoff_hz / fs).sin_cos();
+        Some((cos, sin / std::f64::consts::SQRT_2))
+    }
+
+    /// Filter interleaved `samples` in place, each channel on its own.
+    pub fn process(&self, samples: &mut [f32], channels: u16) {
+        if channels == 0 {
+            return;
+        }
+        let ([b0, b1, b2], [a1, a2]) = (self.b, self.a);
+        let ch = channels as usize;
+        // Per channel: x[n-1], x[n-2], y[n-1], y[n-2].
+        let mut state = vec![[0.0f64; 4]; ch];
+        for (i, s) in samples.iter_mut().enumerate() {
+            let [x1, x2, y1, y2] = &mut state[i % ch];
+            let x = *s as f64;
+            let y = b0 * x + b1 * *x1 + b2 * *x2 - a1 * *y1 - a2 * *y2;
+            (*x2, *x1, *y2, *y1) = (*x1, x, *y1, y);
+            *s = y as f32;
+        }
+    }
+}
+
+/// Length of the frames the noise gate opens and closes on.
+const GATE_FRAME_SECS: f64 = 0.01;
+
+/// Filtering applied to a model's mono signal before it is chunked,
+/// from the `[filter]` section of its manifest:
+///
+/// ```toml
+/// [filter]
+/// highpass_hz = 200       # traffic rumble
+/// lowpass_hz = 12000
+/// noise_gate_dbfs = -70   # silence frames quieter than this
+/// ```
+///
+/// All stages are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(default)]
+pub struct PreFilter {
+    pub highpass_hz: Option<f64>,
+    pub lowpass_hz: Option<f64>,
+    /// 10 ms frames whose RMS stays below this level (dB re full scale)
+    /// are silenced, after the high- and low-pass.
+    pub noise_gate_dbfs: Option<f64>,
+}
+
+impl PreFilter {
+    /// Whether any stage is configured.
+    pub fn is_active(&self) -> bool {
+        self.highpass_hz.is_some() || self.lowpass_hz.is_some() || self.noise_gate_dbfs.is_some()
+    }
+
+    /// Filter the mono `signal` at `sample_rate` in place.
+    pub fn apply(&self, signal: &mut [f32], sample_rate: u32) {
+        if let Some(filter) = self
+            .highpass_hz
+            .and_then(|hz| Biquad::highpass(sample_rate, hz))
+        {
+            filter.process(signal, 1);
+        }
+        if let Some(filter) = self
+            .lowpass_hz
+            .and_then(|hz| Biquad::lowpass(sample_rate, hz))
+        {
+            filter.process(signal, 1);
+        }
+        if let Some(threshold) = self.noise_gate_dbfs {
+            noise_gate(signal, sample_rate, threshold);
+        }
+    }
+}
+
+/// Silence the frames of `signal` quieter than `threshold_dbfs`.  The
+/// gain ramps linearly across each frame where the gate opens or closes,
+/// so gating does not add clicks the models could pick up.
+fn noise_gate(signal: &mut [f32], sample_rate: u32, threshold_dbfs: f64) {
+    let frame = ((sample_rate as f64 * GATE_FRAME_SECS) as usize).max(1);
+    let threshold = 10f64.powf(threshold_dbfs / 20.0);
+    let mut gain = 1.0f32;
+    for chunk in signal.chunks_mut(frame) {
+        let rms =
+            (chunk.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / chunk.len() as f64).sqrt();
+        let target = if rms >= threshold { 1.0 } else { 0.0 };
+        let len = chunk.len() as f32;
+        for (i, s) in chunk.iter_mut().enumerate() {
+            *s *= gain + (target - gain) * (i + 1) as f32 / len;
+        }
+        gain = target;
+    }
+}
+
+// ── integrity checks ─────────────────────────────────────────────────────
+
+/// Share of samples at full scale above which a recording is rejected.
+/// A healthy microphone never sits pinned at the rails for this long;
+/// a failing SD card or a wedged ADC does.
+pub const MAX_CLIPPED_FRACTION: f64 = 0.25;
+
+/// A recording shorter than this share of the expected length is treated
+/// as truncated.
+pub const MIN_DURATION_FRACTION: f64 = 0.5;
+
+/// Why a WAV recording was rejected by [`verify_wav`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum WavDefect {
+    #[error("cannot read file: {0}")]
+    Unreadable(String),
+    #[error("not a RIFF/WAVE file")]
+    NotWav,
+    #[error("implausible header: {0}")]
+    BadHeader(String),
+    #[error("no audio samples")]
+    Empty,
+    #[error("truncated: {actual_secs:.2}s of audio, expected {expected_secs:.2}s")]
+    Truncated { actual_secs: f64, expected_secs: f64 },
+    #[error("{0} non-finite sample(s)")]
+    NonFinite(usize),
+    #[error("{:.0}% of samples clipped", .0 * 100.0)]
+    Clipped(f64),
+}
+
+/// Summary statistics of a WAV file that passed [`verify_wav`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavStats {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub duration_secs: f64,
+    /// Absolute peak, normalised to 0.0–1.0.
+    pub peak: f32,
+    /// Share of samples at (or within 0.1% of) full scale.
+    pub clipped_fraction: f64,
+}
+
+/// Sanity-check a WAV recording before it is handed to the models.
+///
+/// Checks the RIFF header, compares the data-chunk size declared in the
+/// header against the bytes actually on disk (and, when given,
+/// `expected_secs` — usually `RECORDING_LENGTH`), and scans the samples
+/// for NaN/∞ and for sustained clipping.
+pub fn verify_wav(path: &std::path::Path, expected_secs: Option<f64>) -> Result<WavStats, WavDefect> {
+    let mut raw = std::fs::read(path).map_err(|e| WavDefect::Unreadable(e.to_string()))?;
+    if raw.len() < 12 || &raw[0..4] != b"RIFF" || &raw[8..12] != b"WAVE" {
+        return Err(WavDefect::NotWav);
+    }
+    let declared = declared_data_bytes(&raw);
+    fix_wav_data_chunk(&mut raw);
+    let reader = hound::WavReader::new(Cursor::new(raw))
+        .map_err(|e| WavDefect::BadHeader(e.to_string()))?;
+    let spec = reader.spec();
+
+    if !(1_000..=768_000).contains(&spec.sample_rate) {
+        return Err(WavDefect::BadHeader(format!("sample rate {} Hz", spec.sample_rate)));
+    }
+    if spec.channels == 0 || spec.channels > 32 {
+        return Err(WavDefect::BadHeader(format!("{} channels", spec.channels)));
+    }
+
+    let samples = normalised_samples(reader);
+    if samples.is_empty() {
+        return Err(WavDefect::Empty);
+    }
+
+    let frame_bytes = spec.channels as f64 * (spec.bits_per_sample as f64 / 8.0).ceil();
+    let seconds_per_byte = 1.0 / (spec.sample_rate as f64 * frame_bytes);
+    let duration_secs = samples.len() as f64 / spec.channels as f64 / spec.sample_rate as f64;
+
+    // A header that promises more data than the file holds means the
+    // write was cut short (power loss, full or failing card).
+    if let Some(bytes) = declared {
+        let declared_secs = bytes as f64 * seconds_per_byte;
+        if duration_secs + 2.0 * seconds_per_byte * frame_bytes < declared_secs {
+            return Err(WavDefect::Truncated { actual_secs: duration_secs, expected_secs: declared_secs });
+        }
+    }
+    if let Some(expected) = expected_secs.filter(|e| *e > 0.0) {
+        if duration_secs < expected * MIN_DURATION_FRACTION {
+            return Err(WavDefect::Truncated { actual_secs: duration_secs, expected_secs: expected });
+        }
+    }
+
+    let non_finite = samples.iter().filter(|s| !s.is_finite()).count();
+    if non_finite > 0 {
+        return Err(WavDefect::NonFinite(non_finite));
+    }
+
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    let clipped = samples.iter().filter(|s| s.abs() >= 0.999).count();
+    let clipped_fraction = clipped as f64 / samples.len() as f64;
+    if clipped_fraction > MAX_CLIPPED_FRACTION {
+        return Err(WavDefect::Clipped(clipped_fraction));
+    }
+
+    Ok(WavStats {
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        bits_per_sample: spec.bits_per_sample,
+        duration_secs,
+        peak: peak.min(1.0),
+        clipped_fraction,
+    })
+}
+
+/// All readable samples, interleaved, scaled to -1.0..1.0.  Stops at the
+/// first decode error (a truncated final frame).
+///
+/// Integer samples of any width (8/16/24/32-bit) are scaled by their own
+/// full scale; 32-bit float samples are passed through, NaN and all, so
+/// [`verify_wav`] can reject them.
+pub fn normalised_samples<R: std::io::Read>(reader: hound::WavReader<R>) -> Vec<f32> {
+    let spec = reader.spec();
+    match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let bits = spec.bits_per_sample.clamp(1, 32) as u32;
+            let max_amplitude = ((1_i64 << (bits - 1)) - 1) as f32;
+            reader
+                .into_samples::<i32>()
+                .take_while(|s| s.is_ok())
+                .map(|s| (s.unwrap() as f32 / max_amplitude).clamp(-1.0, 1.0))
+                .collect()
+        }
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .take_while(|s| s.is_ok())
+            .map(|s| s.unwrap())
+            .collect(),
+    }
+}
+
+// ── input levels ─────────────────────────────────────────────────────────
+
+/// Share of clipped samples above which a segment is reported as
+/// clipping.  Far below [`MAX_CLIPPED_FRACTION`]: this is a hint to turn
+/// the gain down, not a reason to drop the recording.
+pub const CLIPPING_FRACTION: f64 = 0.0005;
+
+/// RMS level below which the input is reported as dead.  Even a quiet
+/// night through a cheap microphone sits well above this; digital
+/// silence and a stuck ADC do not.
+pub const FLATLINE_DBFS: f64 = -90.0;
+
+/// RMS level below which the gain is reported as too low.
+pub const QUIET_DBFS: f64 = -70.0;
+
+/// Measure the input level of a finished WAV segment.
+pub fn measure_levels(path: &std::path::Path) -> Result<InputLevels> {
+    let mut raw = std::fs::read(path)
+        .with_context(|| format!("Cannot read {}", path.display()))?;
+    fix_wav_data_chunk(&mut raw);
+    let reader = hound::WavReader::new(Cursor::new(raw))
+        .with_context(|| format!("Cannot parse WAV: {}", path.display()))?;
+    let channels = reader.spec().channels;
+    let samples = normalised_samples(reader);
+    let mut levels = levels_from_samples(&samples, channels);
+    levels.filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(levels)
+}
+
+/// [`InputLevels`] of interleaved samples (`filename` left empty).
+pub fn levels_from_samples(samples: &[f32], channels: u16) -> InputLevels {
+    let ch = channels.max(1) as usize;
+    let frames = samples.len() / ch;
+    // Per channel, with the DC offset removed so a stuck ADC reads as
+    // silence rather than as a loud signal.
+    let rms = (0..ch)
+        .map(|c| {
+            if frames == 0 {
+                return 0.0;
+            }
+            let channel = || samples.iter().skip(c).step_by(ch).take(frames).map(|&s| s as f64);
+            let mean = channel().sum::<f64>() / frames as f64;
+            (channel().map(|s| (s - mean).powi(2)).sum::<f64>() / frames as f64).sqrt()
+        })
+        .fold(0.0f64, f64::max);
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs())).min(1.0) as f64;
+    let clipped = samples.iter().filter(|s| s.abs() >= 0.999).count();
+    let clipped_fraction = if samples.is_empty() {
+        0.0
+    } else {
+        clipped as f64 / samples.len() as f64
+    };
+
+    let rms_dbfs = dbfs(rms);
+    let status = if rms_dbfs < FLATLINE_DBFS {
+        LevelStatus::Flatline
+    } else if clipped_fraction > CLIPPING_FRACTION {
+        LevelStatus::Clipping
+    } else if rms_dbfs < QUIET_DBFS {
+        LevelStatus::Quiet
+    } else {
+        LevelStatus::Ok
+    };
+
+    InputLevels {
+        filename: String::new(),
+        measured_at: chrono::Utc::now().to_rfc3339(),
+        channels,
+        rms_dbfs,
+        peak_dbfs: dbfs(peak),
+        clipped_fraction,
+        status,
+    }
+}
+
+/// Linear amplitude to dBFS, floored at -120 dB.
+fn dbfs(amplitude: f64) -> f64 {
+    if amplitude <= 1e-6 {
+        -120.0
+    } else {
+        20.0 * amplitude.log10()
+    }
+}
+
+/// Size of the `data` chunk as declared in the header.
+///
+/// `None` when the chunk is missing or the size is a streaming
+/// placeholder (`0` / `0xFFFFFFFF`, as written by ffmpeg's segmenter).
+fn declared_data_bytes(raw: &[u8]) -> Option<u64> {
+    let mut pos = 12usize;
+    while pos + 8 <= raw.len() {
+        let chunk_size =
+            u32::from_le_bytes([raw[pos + 4], raw[pos + 5], raw[pos + 6], raw[pos + 7]]);
+        if &raw[pos..pos + 4] == b"data" {
+            return match chunk_size {
+                0 | u32::MAX => None,
+                n => Some(n as u64),
+            };
+        }
+        let next = pos.checked_add(8 + chunk_size as usize)?;
+        pos = if chunk_size % 2 != 0 { next + 1 } else { next };
+    }
+    None
+}
+
 /// Fix a WAV file whose data-chunk size is not a multiple of the sample
 /// frame size.  This is common with ffmpeg's `-f segment` muxer which may
 /// not perfectly finalize the RIFF/WAV header.
@@ -389,4 +1118,374 @@ mod tests {
         // step = 2s → chunks at 0s, 2s, 4s (last one 2s → padded)
         assert_eq!(chunks.len(), 3);
     }
+
+    #[test]
+    fn test_split_signal_variable() {
+        let rate = 1000;
+        let sig = vec![1.0f32; rate * 7];
+
+        // Up to 3 s windows: the 1 s tail keeps its length.
+        let chunks = split_signal_variable(&sig, rate as u32, 1.0, Some(3.0), 0.0, 1.0);
+        let lens: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(lens, vec![3000, 3000, 1000]);
+
+        // Whole file: a single chunk, overlap irrelevant.
+        let chunks = split_signal_variable(&sig, rate as u32, 1.0, None, 1.0, 1.0);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), 7000);
+
+        // Short recordings are padded up to the model's minimum.
+        let short = vec![1.0f32; 1600];
+        let variable = Chunking::Variable {
+            min: 2.0,
+            max: None,
+        };
+        let chunks = variable.split(&short, rate as u32, 0.0);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), 2000);
+        assert_eq!(chunks[0][1800], 0.0);
+
+        // Tails with too little audio are dropped.
+        let chunks = split_signal_variable(&sig, rate as u32, 2.0, Some(4.0), 1.0, 1.5);
+        let lens: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(lens, vec![4000, 4000]);
+
+        assert_eq!(
+            Chunking::Fixed(3.0).split(&sig, rate as u32, 0.0),
+            split_signal(&sig, rate as u32, 3.0, 0.0, 1.5)
+        );
+    }
+
+    fn write_wav(path: &std::path::Path, samples: &[i16]) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut w = hound::WavWriter::create(path, spec).unwrap();
+        for &s in samples {
+            w.write_sample(s).unwrap();
+        }
+        w.finalize().unwrap();
+    }
+
+    /// Write 2 s of a 0.5-amplitude 1 kHz sine at 8 kHz in the given encoding.
+    fn write_sine(path: &std::path::Path, bits: u16, format: hound::SampleFormat) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: bits,
+            sample_format: format,
+        };
+        let mut w = hound::WavWriter::create(path, spec).unwrap();
+        let full_scale = ((1_i64 << (bits - 1)) - 1) as f64;
+        for i in 0..16_000 {
+            let s = 0.5 * (std::f64::consts::TAU * 1_000.0 * i as f64 / 8_000.0).sin();
+            match format {
+                hound::SampleFormat::Float => w.write_sample(s as f32).unwrap(),
+                hound::SampleFormat::Int if bits <= 16 => {
+                    w.write_sample((s * full_scale).round() as i16).unwrap()
+                }
+                hound::SampleFormat::Int => w.write_sample((s * full_scale).round() as i32).unwrap(),
+            }
+        }
+        w.finalize().unwrap();
+    }
+
+    const ENCODINGS: [(u16, hound::SampleFormat); 4] = [
+        (16, hound::SampleFormat::Int),
+        (24, hound::SampleFormat::Int),
+        (32, hound::SampleFormat::Int),
+        (32, hound::SampleFormat::Float),
+    ];
+
+    #[test]
+    fn test_read_audio_encodings() {
+        let dir = std::env::temp_dir().join(format!("gaia-read-enc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (bits, format) in ENCODINGS {
+            let path = dir.join(format!("sine-{bits}-{format:?}.wav"));
+            write_sine(&path, bits, format);
+            let chunks = read_audio(&path, 8_000, 3.0, 0.0, &PreFilter::default()).unwrap();
+            assert_eq!(chunks.len(), 1, "{bits}-bit {format:?}");
+            let peak = chunks[0].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            assert!((peak - 0.5).abs() < 1e-3, "{bits}-bit {format:?}: peak {peak}");
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_audio_cache_decodes_once_per_rate() {
+        let dir = std::env::temp_dir().join(format!("gaia-audio-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sine.wav");
+        write_sine(&path, 16, hound::SampleFormat::Int);
+
+        let mut cache = AudioCache::new(&path);
+        let first = cache.chunks(16_000, 3.0, 0.0).unwrap();
+        assert_eq!(
+            first,
+            read_audio(&path, 16_000, 3.0, 0.0, &PreFilter::default()).unwrap()
+        );
+
+        // Once decoded, a rate is served without touching the file again.
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(cache.chunks(16_000, 3.0, 0.0).unwrap(), first);
+        assert_eq!(cache.chunks(16_000, 1.6, 0.0).unwrap()[0].len(), 25_600);
+        assert!(cache.chunks(8_000, 3.0, 0.0).is_err());
+        assert!(cache.duration_secs().is_some_and(|d| (d - 2.0).abs() < 0.01));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_audio_channel() {
+        let dir = std::env::temp_dir().join(format!("gaia-channels-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        // Left carries the signal, right is a dead microphone.
+        let mut w = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..8_000 {
+            w.write_sample(0.5f32).unwrap();
+            w.write_sample(0.0f32).unwrap();
+        }
+        w.finalize().unwrap();
+
+        assert_eq!(channel_count(&path), Some(2));
+        assert!(load_audio(&path, 8_000).unwrap().iter().all(|&s| s == 0.25));
+        let left = load_audio_channel(&path, 8_000, Some(0)).unwrap();
+        assert_eq!(left.len(), 8_000);
+        assert!(left.iter().all(|&s| s == 0.5));
+        let mut right = AudioCache::for_channel(&path, 1);
+        assert!(right.signal(8_000).unwrap().iter().all(|&s| s == 0.0));
+        assert!(load_audio_channel(&path, 8_000, Some(2)).is_err());
+
+        assert_eq!(ChannelMode::parse("Separate"), ChannelMode::Separate);
+        assert_eq!(ChannelMode::parse("mix"), ChannelMode::Mix);
+        assert_eq!(ChannelMode::parse(""), ChannelMode::Mix);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_extract_clip_with_pre_roll() {
+        let dir = std::env::temp_dir().join(format!("gaia-pre-roll-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let previous = dir.join("previous.wav");
+        let current = dir.join("current.wav");
+        write_wav(&previous, &[1; 16_000]);
+        write_wav(&current, &[2; 16_000]);
+
+        let pre_roll = read_pre_roll(&previous, 0.5).unwrap();
+        assert_eq!(pre_roll.samples.len(), 4_000);
+        assert!((pre_roll.file_duration - 2.0).abs() < 1e-9);
+
+        // 0.25 s of the previous segment, then the first second.
+        let out = dir.join("clip.wav");
+        extract_clip_with_pre_roll(&pre_roll, &current, &out, -0.25, 1.0).unwrap();
+        let clip: Vec<i16> = hound::WavReader::open(&out)
+            .unwrap()
+            .into_samples::<i16>()
+            .map(|s| s.unwrap())
+            .collect();
+        assert_eq!(clip.len(), 2_000 + 8_000);
+        assert!(clip[..2_000].iter().all(|&s| s == 1));
+        assert!(clip[2_000..].iter().all(|&s| s == 2));
+
+        // Reaching back further than was kept stops at the pre-roll.
+        extract_clip_with_pre_roll(&pre_roll, &current, &out, -3.0, 1.0).unwrap();
+        assert_eq!(hound::WavReader::open(&out).unwrap().len(), 4_000 + 8_000);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_extract_clip_encodings() {
+        let dir = std::env::temp_dir().join(format!("gaia-clip-enc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (bits, format) in ENCODINGS {
+            let path = dir.join(format!("sine-{bits}-{format:?}.wav"));
+            write_sine(&path, bits, format);
+            let out = dir.join(format!("clip-{bits}-{format:?}.wav"));
+            extract_clip(&path, &out, 0.25, 0.75).unwrap();
+
+            let reader = hound::WavReader::open(&out).unwrap();
+            assert_eq!(reader.spec().bits_per_sample, 16);
+            let clip: Vec<i16> = reader.into_samples().map(|s| s.unwrap()).collect();
+            assert_eq!(clip.len(), 4_000, "{bits}-bit {format:?}");
+            let peak = clip.iter().map(|s| s.unsigned_abs()).max().unwrap();
+            assert!((16_380..=16_386).contains(&peak), "{bits}-bit {format:?}: peak {peak}");
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_verify_wav() {
+        let dir = std::env::temp_dir().join(format!("gaia-verify-wav-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Healthy: 2 s of a quiet sawtooth.
+        let good = dir.join("good.wav");
+        let tone: Vec<i16> = (0..16_000).map(|i| ((i % 64) as i16 - 32) * 100).collect();
+        write_wav(&good, &tone);
+        let stats = verify_wav(&good, Some(2.0)).unwrap();
+        assert_eq!(stats.sample_rate, 8_000);
+        assert!((stats.duration_secs - 2.0).abs() < 1e-9);
+        assert_eq!(stats.clipped_fraction, 0.0);
+
+        // Shorter than the expected recording length.
+        assert!(matches!(verify_wav(&good, Some(15.0)), Err(WavDefect::Truncated { .. })));
+
+        // Header promises 2 s but the tail was never written.
+        let cut = dir.join("cut.wav");
+        let bytes = std::fs::read(&good).unwrap();
+        std::fs::write(&cut, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(verify_wav(&cut, None), Err(WavDefect::Truncated { .. })));
+
+        // Pinned at full scale.
+        let clipped = dir.join("clipped.wav");
+        write_wav(&clipped, &[i16::MAX; 8_000]);
+        assert!(matches!(verify_wav(&clipped, None), Err(WavDefect::Clipped(_))));
+
+        // Garbage.
+        let junk = dir.join("junk.wav");
+        std::fs::write(&junk, b"not a wav file at all").unwrap();
+        assert_eq!(verify_wav(&junk, None), Err(WavDefect::NotWav));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_time_expand_clip() {
+        let dir = std::env::temp_dir().join(format!("gaia-time-expand-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let clip = dir.join("bat.wav");
+        // A DC offset plus a tone near Nyquist.
+        let samples: Vec<i16> = (0..8_000)
+            .map(|i| 8_000 + if i % 2 == 0 { 4_000 } else { -4_000 })
+            .collect();
+        write_wav(&clip, &samples);
+
+        let out = time_expanded_path(&clip);
+        assert_eq!(out, dir.join("bat.te.wav"));
+        time_expand_clip(&clip, &out, 10, Some(1_000.0)).unwrap();
+        let reader = hound::WavReader::open(&out).unwrap();
+        assert_eq!(reader.spec().sample_rate, 800);
+        let expanded: Vec<i16> = reader.into_samples().map(|s| s.unwrap()).collect();
+        assert_eq!(expanded.len(), samples.len());
+        // The offset is filtered out, the tone passes.
+        let tail = &expanded[4_000..];
+        let mean = tail.iter().map(|&s| s as f64).sum::<f64>() / tail.len() as f64;
+        assert!(mean.abs() < 50.0, "{mean}");
+        assert!(tail.iter().any(|&s| s > 3_000));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_biquad_matches_reference() {
+        // Impulse responses of 2nd-order Butterworth filters designed
+        // with the bilinear transform (scipy.signal.butter(2, fc, btype,
+        // fs=48000)).
+        let impulse = |filter: Biquad| {
+            let mut x = [0.0f32; 6];
+            x[0] = 1.0;
+            filter.process(&mut x, 1);
+            x
+        };
+        let cases = [
+            (
+                Biquad::highpass(48_000, 200.0).unwrap(),
+                [
+                    0.981658, -0.036341, -0.035656, -0.034972, -0.034290, -0.033609,
+                ],
+            ),
+            (
+                Biquad::lowpass(48_000, 8_000.0).unwrap(),
+                [0.155051, 0.406265, 0.369743, 0.131647, -0.007241, -0.036140],
+            ),
+        ];
+        for (filter, reference) in cases {
+            for (got, want) in impulse(filter).iter().zip(reference) {
+                assert!((*got as f64 - want).abs() < 1e-5, "{got} != {want}");
+            }
+        }
+        assert!(Biquad::highpass(48_000, 24_000.0).is_none());
+        assert!(Biquad::lowpass(48_000, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_pre_filter() {
+        let sr = 48_000;
+        let tone = |hz: f64, amplitude: f64| -> Vec<f32> {
+            (0..sr)
+                .map(|i| {
+                    (amplitude * (std::f64::consts::TAU * hz * i as f64 / sr as f64).sin()) as f32
+                })
+                .collect()
+        };
+        let rms = |s: &[f32]| {
+            (s.iter().map(|&x| (x as f64).powi(2)).sum::<f64>() / s.len() as f64).sqrt()
+        };
+
+        let filter = PreFilter {
+            highpass_hz: Some(200.0),
+            lowpass_hz: Some(12_000.0),
+            noise_gate_dbfs: None,
+        };
+        assert!(filter.is_active());
+        assert!(!PreFilter::default().is_active());
+        // Rumble is cut by more than 20 dB, a song in the passband kept.
+        let mut rumble = tone(50.0, 0.5);
+        filter.apply(&mut rumble, sr);
+        assert!(rms(&rumble[4_800..]) < 0.5 / std::f64::consts::SQRT_2 / 10.0);
+        let mut song = tone(3_000.0, 0.5);
+        filter.apply(&mut song, sr);
+        assert!((rms(&song[4_800..]) - 0.5 / std::f64::consts::SQRT_2).abs() < 0.01);
+
+        // The gate silences the quiet half (after the first frame's fade)
+        // and keeps the loud one.
+        let gate = PreFilter {
+            noise_gate_dbfs: Some(-40.0),
+            ..PreFilter::default()
+        };
+        let mut signal = tone(3_000.0, 0.001);
+        signal.extend(tone(3_000.0, 0.5));
+        gate.apply(&mut signal, sr);
+        assert!(signal[480..48_000].iter().all(|&s| s == 0.0));
+        assert_eq!(signal[48_480..], tone(3_000.0, 0.5)[480..]);
+    }
+
+    #[test]
+    fn test_levels_from_samples() {
+        // -20 dBFS sine, mono.
+        let sine: Vec<f32> = (0..8_000)
+            .map(|i| 0.1 * std::f32::consts::SQRT_2 * (i as f32 * 0.05).sin())
+            .collect();
+        let ok = levels_from_samples(&sine, 1);
+        assert_eq!(ok.status, LevelStatus::Ok);
+        assert!((ok.rms_dbfs + 20.0).abs() < 0.5, "{}", ok.rms_dbfs);
+
+        // Stuck ADC: constant offset reads as dead, not loud.
+        assert_eq!(levels_from_samples(&[0.3; 8_000], 1).status, LevelStatus::Flatline);
+        assert_eq!(levels_from_samples(&[], 2).status, LevelStatus::Flatline);
+
+        let mut hot = sine.clone();
+        hot[..100].fill(1.0);
+        assert_eq!(levels_from_samples(&hot, 1).status, LevelStatus::Clipping);
+
+        let quiet: Vec<f32> = sine.iter().map(|s| s * 0.001).collect();
+        assert_eq!(levels_from_samples(&quiet, 1).status, LevelStatus::Quiet);
+
+        // Stereo with one dead channel reports the live one.
+        let stereo: Vec<f32> = sine.iter().flat_map(|&s| [s, 0.0]).collect();
+        assert_eq!(levels_from_samples(&stereo, 2).status, LevelStatus::Ok);
+    }
 }
@@ -0,0 +1,386 @@
+//! The capture node HTTP API as a documented contract.
+//!
+//! [`OPENAPI_JSON`] (`common/openapi/capture.json`) is the OpenAPI 3
+//! description of the routes in `gaia-capture`'s `server` module, served
+//! by every capture node at `/api/openapi.json`.  Its schemas are checked
+//! against the [`protocol`](crate::protocol) types by the tests below, so
+//! a field added on one side and not the other fails CI rather than a
+//! third-party capture node.
+//!
+//! With the `client` feature, [`CaptureClient`] is the typed blocking
+//! client the processing server talks to capture nodes with.
+
+/// OpenAPI 3 document of the capture node API.
+pub const OPENAPI_JSON: &str = include_str!("../openapi/capture.json");
+
+/// Whether an `Authorization` header value carries `token` as a bearer
+/// token, compared in constant time.  Used by every server that accepts
+/// `CAPTURE_AUTH_TOKEN`.
+pub fn bearer_matches(header: Option<&str>, token: &str) -> bool {
+    let Some(given) = header.and_then(|h| {
+        h.split_once(' ')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, t)| t.trim())
+    }) else {
+        return false;
+    };
+    let (a, b) = (given.as_bytes(), token.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(feature = "client")]
+pub use client::{CaptureClient, ClientError, RecordingPage};
+
+#[cfg(feature = "client")]
+mod client {
+    use std::time::Duration;
+
+    use reqwest::blocking::Client;
+    use reqwest::StatusCode;
+    use serde::de::DeserializeOwned;
+
+    use crate::config::Config;
+    use crate::protocol::{CaptureStatus, HealthResponse, InputLevels, RecordingInfo};
+
+    /// Why a capture API call failed.
+    #[derive(Debug, thiserror::Error)]
+    pub enum ClientError {
+        #[error("invalid client configuration: {0}")]
+        Config(String),
+        #[error("{method} {path}: {source}")]
+        Http {
+            method: &'static str,
+            path: String,
+            #[source]
+            source: reqwest::Error,
+        },
+        #[error("{method} {path} returned {status}")]
+        Status {
+            method: &'static str,
+            path: String,
+            status: StatusCode,
+        },
+    }
+
+    /// One page of `GET /api/recordings`, oldest first.
+    #[derive(Debug, Clone)]
+    pub struct RecordingPage {
+        pub recordings: Vec<RecordingInfo>,
+        /// Recordings on the node in total (`X-Total-Count`).
+        pub total: usize,
+    }
+
+    /// Blocking client for any number of capture nodes; every call takes
+    /// the node's base URL, e.g. `http://garden.local:8089`.
+    #[derive(Debug, Clone)]
+    pub struct CaptureClient {
+        http: Client,
+    }
+
+    impl CaptureClient {
+        /// Client with the bearer token (`CAPTURE_AUTH_TOKEN`) and extra
+        /// root certificate (`CAPTURE_TLS_CA`) from `config`, and a 30 s
+        /// timeout.
+        pub fn from_config(config: &Config) -> Result<Self, ClientError> {
+            let mut builder = Client::builder().timeout(Duration::from_secs(30));
+            if let Some(token) = &config.capture_auth_token {
+                let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))
+                    .map_err(|_| {
+                        ClientError::Config("CAPTURE_AUTH_TOKEN is not a valid header value".into())
+                    })?;
+                value.set_sensitive(true);
+                let mut headers = reqwest::header::HeaderMap::new();
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+                builder = builder.default_headers(headers);
+            }
+            if let Some(ca) = &config.capture_tls_ca {
+                let pem = std::fs::read(ca).map_err(|e| {
+                    ClientError::Config(format!("cannot read CAPTURE_TLS_CA {}: {e}", ca.display()))
+                })?;
+                let cert = reqwest::Certificate::from_pem(&pem).map_err(|e| {
+                    ClientError::Config(format!("invalid PEM certificate in {}: {e}", ca.display()))
+                })?;
+                builder = builder.add_root_certificate(cert);
+            }
+            let http = builder
+                .build()
+                .map_err(|e| ClientError::Config(format!("cannot create HTTP client: {e}")))?;
+            Ok(Self { http })
+        }
+
+        /// `GET /api/health`.
+        pub fn health(&self, base_url: &str) -> Result<HealthResponse, ClientError> {
+            self.get_json(base_url, "/api/health")
+        }
+
+        /// `GET /api/status`.
+        pub fn status(&self, base_url: &str) -> Result<CaptureStatus, ClientError> {
+            self.get_json(base_url, "/api/status")
+        }
+
+        /// `GET /api/levels`; `None` until the node has measured a segment.
+        pub fn levels(&self, base_url: &str) -> Result<Option<InputLevels>, ClientError> {
+            match self.get_json(base_url, "/api/levels") {
+                Ok(levels) => Ok(Some(levels)),
+                Err(ClientError::Status { status: StatusCode::NOT_FOUND, .. }) => Ok(None),
+                Err(e) => Err(e),
+            }
+        }
+
+        /// The oldest `limit` recordings.  Nodes predating pagination
+        /// ignore the limit and list everything.
+        pub fn list_recordings(
+            &self,
+            base_url: &str,
+            limit: usize,
+        ) -> Result<RecordingPage, ClientError> {
+            let path = "/api/recordings";
+            let resp = self.send(
+                "GET",
+                path,
+                self.http
+                    .get(format!("{base_url}{path}?limit={limit}&order=asc")),
+            )?;
+            let total = resp
+                .headers()
+                .get("x-total-count")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<usize>().ok());
+            let recordings: Vec<RecordingInfo> = resp.json().map_err(|source| ClientError::Http {
+                method: "GET",
+                path: path.into(),
+                source,
+            })?;
+            let total = total.unwrap_or(recordings.len()).max(recordings.len());
+            Ok(RecordingPage { recordings, total })
+        }
+
+        /// `GET /api/recordings/{filename}`: the whole file.
+        pub fn download(&self, base_url: &str, filename: &str) -> Result<Vec<u8>, ClientError> {
+            let path = format!("/api/recordings/{filename}");
+            let resp = self.send("GET", &path, self.http.get(format!("{base_url}{path}")))?;
+            resp.bytes()
+                .map(Vec::from)
+                .map_err(|source| ClientError::Http { method: "GET", path, source })
+        }
+
+        /// `DELETE /api/recordings/{filename}`.  `Ok(false)` when the
+        /// recording was already gone, which callers treat as success.
+        pub fn delete(&self, base_url: &str, filename: &str) -> Result<bool, ClientError> {
+            let path = format!("/api/recordings/{filename}");
+            match self.send("DELETE", &path, self.http.delete(format!("{base_url}{path}"))) {
+                Ok(_) => Ok(true),
+                Err(ClientError::Status { status: StatusCode::NOT_FOUND, .. }) => Ok(false),
+                Err(e) => Err(e),
+            }
+        }
+
+        /// `POST /api/recordings/{filename}/keep`: move a ring-buffer
+        /// segment to the node's disk.  `Ok(false)` when the node is not
+        /// in ring-buffer mode (409), predates the route, or the segment
+        /// is gone (404).
+        pub fn keep(&self, base_url: &str, filename: &str) -> Result<bool, ClientError> {
+            let path = format!("/api/recordings/{filename}/keep");
+            match self.send("POST", &path, self.http.post(format!("{base_url}{path}"))) {
+                Ok(_) => Ok(true),
+                Err(ClientError::Status {
+                    status: StatusCode::NOT_FOUND | StatusCode::CONFLICT,
+                    ..
+                }) => Ok(false),
+                Err(e) => Err(e),
+            }
+        }
+
+        fn get_json<T: DeserializeOwned>(&self, base_url: &str, path: &str) -> Result<T, ClientError> {
+            let resp = self.send("GET", path, self.http.get(format!("{base_url}{path}")))?;
+            resp.json().map_err(|source| ClientError::Http {
+                method: "GET",
+                path: path.into(),
+                source,
+            })
+        }
+
+        fn send(
+            &self,
+            method: &'static str,
+            path: &str,
+            request: reqwest::blocking::RequestBuilder,
+        ) -> Result<reqwest::blocking::Response, ClientError> {
+            let resp = request.send().map_err(|source| ClientError::Http {
+                method,
+                path: path.into(),
+                source,
+            })?;
+            if !resp.status().is_success() {
+                return Err(ClientError::Status {
+                    method,
+                    path: path.into(),
+                    status: resp.status(),
+                });
+            }
+            Ok(resp)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use serde::Serialize;
+    use serde_json::Value;
+
+    use super::{bearer_matches, OPENAPI_JSON};
+    use crate::protocol::*;
+
+    fn schema(spec: &Value, name: &str) -> Value {
+        spec["components"]["schemas"][name].clone()
+    }
+
+    /// The serialised fields of `value` are exactly the schema's
+    /// properties, and every required property is present.
+    fn assert_object_matches<T: Serialize>(spec: &Value, name: &str, value: &T) {
+        let schema = schema(spec, name);
+        let props: BTreeSet<&str> = schema["properties"]
+            .as_object()
+            .unwrap_or_else(|| panic!("schema {name} has no properties"))
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let json = serde_json::to_value(value).unwrap();
+        let fields: BTreeSet<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(props, fields, "schema {name} does not match the protocol type");
+        for required in schema["required"].as_array().into_iter().flatten() {
+            assert!(fields.contains(required.as_str().unwrap()), "{name}.{required}");
+        }
+    }
+
+    fn assert_enum_matches<T: Serialize>(spec: &Value, name: &str, variants: &[T]) {
+        let values: Vec<Value> = variants.iter().map(|v| serde_json::to_value(v).unwrap()).collect();
+        assert_eq!(schema(spec, name)["enum"].as_array().unwrap(), &values, "enum {name}");
+    }
+
+    #[test]
+    fn test_openapi_matches_protocol() {
+        let spec: Value = serde_json::from_str(OPENAPI_JSON).expect("capture.json is valid JSON");
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        let health = HealthResponse {
+            status: "ok".into(),
+            uptime_secs: 1,
+            disk_usage_pct: 0.0,
+            inode_usage_pct: 0.0,
+            capture_paused: false,
+            node_id: String::new(),
+            node_name: String::new(),
+            latitude: None,
+            longitude: None,
+            time_sync: None,
+            version: "0.1.0".into(),
+            protocol: crate::protocol::PROTOCOL_VERSION,
+        };
+        assert_object_matches(&spec, "HealthResponse", &health);
+
+        let sync = TimeSyncStatus {
+            source: "chrony".into(),
+            synchronized: true,
+            offset_ms: None,
+            stratum: None,
+            reference: None,
+            last_sync: None,
+            drifting: false,
+        };
+        assert_object_matches(&spec, "TimeSyncStatus", &sync);
+
+        let stream = StreamStatus {
+            index: 1,
+            url: String::new(),
+            state: StreamState::Recording,
+            last_audio_secs: 0,
+            restarts: 0,
+            last_restart: None,
+        };
+        assert_object_matches(&spec, "StreamStatus", &stream);
+        assert_object_matches(&spec, "CaptureStatus", &CaptureStatus::default());
+        assert_enum_matches(
+            &spec,
+            "StreamState",
+            &[StreamState::Recording, StreamState::Starting, StreamState::Down],
+        );
+
+        let levels = InputLevels {
+            filename: String::new(),
+            measured_at: String::new(),
+            channels: 1,
+            rms_dbfs: 0.0,
+            peak_dbfs: 0.0,
+            clipped_fraction: 0.0,
+            status: LevelStatus::Ok,
+        };
+        assert_object_matches(&spec, "InputLevels", &levels);
+        assert_enum_matches(
+            &spec,
+            "LevelStatus",
+            &[LevelStatus::Ok, LevelStatus::Clipping, LevelStatus::Quiet, LevelStatus::Flatline],
+        );
+
+        let device = AudioDevice {
+            card: 0,
+            device: 0,
+            card_id: String::new(),
+            card_name: String::new(),
+            device_name: String::new(),
+            rec_card: String::new(),
+        };
+        assert_object_matches(&spec, "AudioDevice", &device);
+        let devices = AudioDevicesResponse { current: String::new(), devices: vec![] };
+        assert_object_matches(&spec, "AudioDevicesResponse", &devices);
+
+        let recording = RecordingInfo { filename: String::new(), size: 1, created: String::new() };
+        assert_object_matches(&spec, "RecordingInfo", &recording);
+
+        let report = SelfTestReport {
+            mode: SelfTestMode::Inject,
+            state: SelfTestState::Running,
+            started_at: String::new(),
+            finished_at: None,
+            recording: None,
+            expected_label: None,
+            steps: vec![],
+        };
+        assert_object_matches(&spec, "SelfTestReport", &report);
+        let step = SelfTestStep { name: String::new(), ok: true, detail: String::new() };
+        assert_object_matches(&spec, "SelfTestStep", &step);
+        assert_object_matches(&spec, "SelfTestRequest", &SelfTestRequest::default());
+        assert_enum_matches(&spec, "SelfTestMode", &[SelfTestMode::Inject, SelfTestMode::Play]);
+        assert_enum_matches(
+            &spec,
+            "SelfTestState",
+            &[SelfTestState::Running, SelfTestState::Passed, SelfTestState::Failed],
+        );
+
+        for path in [
+            "/api/health",
+            "/api/openapi.json",
+            "/api/status",
+            "/api/levels",
+            "/api/selftest",
+            "/api/audio-devices",
+            "/api/recordings",
+            "/api/recordings/{name}",
+            "/api/recordings/{name}/keep",
+        ] {
+            assert!(spec["paths"][path].is_object(), "{path} is not documented");
+        }
+    }
+
+    #[test]
+    fn test_bearer_matches() {
+        assert!(bearer_matches(Some("Bearer s3cret"), "s3cret"));
+        assert!(bearer_matches(Some("bearer s3cret"), "s3cret"));
+        assert!(!bearer_matches(Some("Bearer s3cre"), "s3cret"));
+        assert!(!bearer_matches(Some("Basic s3cret"), "s3cret"));
+        assert!(!bearer_matches(Some("s3cret"), "s3cret"));
+        assert!(!bearer_matches(None, "s3cret"));
+    }
+}
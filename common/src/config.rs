@@ -22,6 +22,10 @@ pub struct Config {
     pub confidence: f64,
     pub sensitivity: f64,
     pub overlap: f64,
+    /// How many of the model's best labels to store with each detection
+    /// (`TOP_PREDICTIONS`, at most 10); `0` (the default) stores only
+    /// the thresholded winner.
+    pub top_predictions: usize,
 
     // ── recording (capture) ──────────────────────────────────────────
     pub recording_length: u32,
@@ -31,6 +35,27 @@ pub struct Config {
     pub extracted_dir: PathBuf,
     pub audio_fmt: String,
     pub rtsp_streams: Vec<String>,
+    /// Raw PCM listener (`HOST:PORT[,format=…][,rate=…][,channels=…]`,
+    /// optionally prefixed with `udp://` or `rtp://`).  Takes precedence
+    /// over RTSP streams and the local microphone when set.
+    pub udp_listen: Option<String>,
+    /// Segment names without colons (`…-161937.wav`), for recordings on
+    /// SMB or Windows shares (`FILE_NAME_SCHEME=portable`).
+    pub portable_file_names: bool,
+    /// Segment names in UTC with a `Z` suffix, unambiguous across DST
+    /// changes (`FILE_NAME_TIMEZONE=utc`).
+    pub utc_file_names: bool,
+    /// Seconds of audio kept in the RAM ring buffer (`RING_BUFFER_SECS`);
+    /// `0` (the default) writes every segment to `recs_dir`.  When set,
+    /// segments go to `ring_buffer_dir` and the oldest are dropped once
+    /// they fall out of the window, analysed or not.
+    pub ring_buffer_secs: u32,
+    /// tmpfs directory holding the ring buffer (`RING_BUFFER_DIR`).
+    /// Default: `/dev/shm/gaia-ring`.
+    pub ring_buffer_dir: PathBuf,
+    /// Where ring-buffer segments the processing node asks to keep are
+    /// moved to (`KEPT_DIR`).  Default: `Kept/` under `recs_dir`.
+    pub kept_dir: PathBuf,
 
     // ── model (processing) ───────────────────────────────────────────
     /// Root directory containing model subdirectories (each with a manifest.toml).
@@ -50,16 +75,95 @@ pub struct Config {
     pub processing_instance: String,
     /// Number of parallel analysis threads (default 1).
     pub processing_threads: usize,
-
+    /// Threads tract's matrix kernels may use per inference, shared by all
+    /// analysis threads (default 1).
+    pub inference_threads: usize,
+    /// Model outputs remembered per model for chunks seen before
+    /// (`CHUNK_CACHE_SIZE`, default 32); `0` turns the cache off.
+    /// Silence and zero-padded tails repeat the same samples exactly.
+    pub chunk_cache_size: usize,
+    /// How models of the same domain loaded in one container are
+    /// combined per chunk: `off` (default, parallel detections), `max`,
+    /// `mean` or `weighted` (by manifest `trust_weight`).
+    pub ensemble_mode: String,
+    /// How multi-channel recordings are analysed (`CHANNEL_MODE`): `mix`
+    /// (default) averages the channels to mono, `separate` runs the
+    /// models on each channel and records it in the detection.
+    pub channel_mode: String,
+    /// `CHANNEL_MODE` override per capture node, keyed by node name
+    /// (`NODE_CHANNEL_MODES=garden=separate,roof=mix`).
+    pub node_channel_modes: HashMap<String, String>,
+    /// Microphone positions for direction estimation (`MIC_POSITIONS`):
+    /// space-separated `x:y` metres, east and north of the node, one per
+    /// channel, e.g. `-0.1:0 0.1:0`.  Empty (the default) disables it.
+    pub mic_positions: String,
+    /// `MIC_POSITIONS` per capture node, keyed by node name
+    /// (`NODE_MIC_POSITIONS=garden=-0.1:0 0.1:0,roof=...`).
+    pub node_mic_positions: HashMap<String, String>,
 
     // ── privacy / extraction (processing) ────────────────────────────
     pub raw_spectrogram: bool,
     pub privacy_threshold: f64,
     pub extraction_length: u32,
+    /// Filename template for extracted clips (without extension).
+    /// Placeholders: `{domain}`, `{common_name}`, `{scientific_name}`,
+    /// `{confidence}`, `{date}`, `{time}`, `{model}`, `{stream}`.
+    pub clip_name_template: String,
+    /// Delivery format for extracted clips: `opus` (default), `mp3` or
+    /// `wav` (no transcoding).
+    pub clip_format: String,
+    /// Encoder bitrate for `opus` / `mp3` clips (ffmpeg syntax, e.g. `96k`).
+    pub clip_bitrate: String,
+    /// Keep the extracted WAV next to the transcoded clip.
+    pub keep_wav_clips: bool,
+    /// Slow-down factor of the audible copy written for clips of
+    /// `ultrasonic` models (`0` or `1` disables it).
+    pub time_expansion: u32,
+    /// High-pass cutoff (Hz, at the recorded rate) applied to that copy;
+    /// `0` disables the filter.
+    pub time_expansion_highpass_hz: f64,
+    /// Loudest 100 ms of a ring-buffer segment this many dB above its
+    /// noise floor makes the processing node keep it even without a
+    /// detection (`RING_KEEP_ENERGY_DB`); `0` keeps detections only.
+    /// Default: 18.
+    pub ring_keep_energy_db: f64,
+
+    // ── rarity (processing) ──────────────────────────────────────────
+    /// Regional checklist of per-species reporting frequencies
+    /// (`RARITY_CHECKLIST`).  Defaults to `regional_checklist.csv` next
+    /// to `db_path`; without the file rarity is not scored.
+    pub rarity_checklist: PathBuf,
+    /// Rarity (0.0 – 1.0) from which a detection is flagged and
+    /// announced as rare.  Default: 0.98, i.e. reported on at most 2%
+    /// of the region's checklists.
+    pub rarity_threshold: f64,
+
+    // ── detection rules (processing) ─────────────────────────────────
+    /// TOML file of post-processing rules (`RULES_FILE`) that drop,
+    /// exclude or mute matching detections.  Defaults to `rules.toml`
+    /// next to `db_path`; without the file every detection is kept.
+    pub rules_file: PathBuf,
+    /// TOML file of clip retention policies (`RETENTION_FILE`) pruning
+    /// extracted clips once a day.  Defaults to `retention.toml` next to
+    /// `db_path`; without the file every clip is kept.
+    pub retention_file: PathBuf,
 
     // ── integrations (processing) ────────────────────────────────────
     pub birdweather_id: Option<String>,
     pub heartbeat_url: Option<String>,
+    /// Seconds between heartbeat pings (`HEARTBEAT_INTERVAL_SECS`, at
+    /// least 30).
+    pub heartbeat_interval_secs: u64,
+    /// Send healthchecks.io `/start` and `/fail` signals
+    /// (`HEARTBEAT_SIGNALS`); `None` decides from the URL.
+    pub heartbeat_signals: Option<bool>,
+    /// Hours of detections the quiet-station watchdog looks at
+    /// (`WATCHDOG_HOURS`, at most 72); `0` turns it off.  Default: 24.
+    pub watchdog_hours: u32,
+    /// Alert when a node's detections in one domain over that window fall
+    /// below this share of its usual count (`WATCHDOG_FRACTION`).
+    /// Default: 0.1.
+    pub watchdog_fraction: f64,
 
     // ── database (processing) ────────────────────────────────────────
     pub db_path: PathBuf,
@@ -83,6 +187,28 @@ pub struct Config {
     /// holding `recs_dir` exceeds this threshold the capture process is
     /// paused until space is freed.  Default: 95.
     pub disk_usage_max: f64,
+    /// Same for the share of inodes in use, which many small clip and
+    /// spectrogram files can exhaust before the space runs out.
+    /// Default: 95.
+    pub disk_inode_max: f64,
+
+    // ── status signalling (capture) ──────────────────────────────────
+    /// BCM number of the GPIO pin driving a status LED
+    /// (`STATUS_LED_PIN`): slow blink while capturing, fast blink when
+    /// no audio is being written, solid while the disk guard has paused
+    /// capture.  Needs gaia-capture built with the `gpio` feature.
+    pub status_led_pin: Option<u8>,
+    /// BCM number of a GPIO pin switched on while capture is dead or the
+    /// disk is full (`FAULT_RELAY_PIN`), e.g. for a relay or buzzer.
+    pub fault_relay_pin: Option<u8>,
+    /// Drive the pins low for "on" (`GPIO_ACTIVE_LOW`), as most relay
+    /// boards and LEDs wired to 3.3 V expect.
+    pub gpio_active_low: bool,
+
+    // ── clock (capture / processing) ─────────────────────────────────
+    /// Clock offset from the NTP/GPS reference, in milliseconds, above
+    /// which the node's clock is reported as drifting.  Default: 1000.
+    pub clock_max_offset_ms: f64,
 
     // ── network (capture ↔ processing) ───────────────────────────────
     /// Address the capture HTTP server listens on.
@@ -91,17 +217,183 @@ pub struct Config {
     pub capture_server_url: String,
     /// Polling interval for the processing server (seconds).
     pub poll_interval_secs: u64,
+    /// Threads fetching recordings from capture nodes while the workers
+    /// analyse earlier ones.  Default: 2.
+    pub download_threads: usize,
+    /// A downloaded or uploaded recording whose content matches one seen
+    /// this many hours before is not analysed again
+    /// (`DEDUP_WINDOW_HOURS`); `0` turns the check off.  Default: 24.
+    pub dedup_window_hours: u32,
+    /// Scheduling weight per capture node, keyed by node name or URL
+    /// (`NODE_PRIORITIES=garden=3,roof=1`).  Each polling round a node
+    /// gets this many turns for every one of a weight-1 node; unlisted
+    /// nodes have weight 1.
+    pub node_priorities: HashMap<String, u32>,
+    /// Bind address for the processing server's read-only REST API
+    /// (`/api/detections`, `/api/models`, `/api/health`).  `None`
+    /// (the default) keeps the API off.
+    pub processing_api_addr: Option<String>,
+    /// Largest recording accepted by the API's `POST /api/upload`, in
+    /// MiB (`UPLOAD_MAX_MB`); `0` turns uploads off.  Default: 8.
+    pub upload_max_mb: u32,
+    /// Folder whose subfolders of audio files can be imported through
+    /// the API's `/api/import` (`IMPORT_DIR`); `None` turns it off.
+    pub import_dir: Option<PathBuf>,
+    /// Shared bearer token for the capture API.  When set, the capture
+    /// server rejects requests without it and the processing client
+    /// sends it.  `None` leaves the API open (trusted networks only).
+    pub capture_auth_token: Option<String>,
+    /// PEM certificate chain and private key; with both set the capture
+    /// server speaks HTTPS instead of HTTP.
+    pub capture_tls_cert: Option<PathBuf>,
+    pub capture_tls_key: Option<PathBuf>,
+    /// Extra PEM root certificate the processing client trusts, for
+    /// self-signed capture certificates.
+    pub capture_tls_ca: Option<PathBuf>,
+
+    // ── self-test (capture) ──────────────────────────────────────────
+    /// Reference recording for `POST /api/selftest`; `None` uses a
+    /// generated 1–4 kHz chirp.
+    pub selftest_file: Option<PathBuf>,
+    /// Scientific name the processing server should detect in the
+    /// reference recording.  Unset skips the detection check.
+    pub selftest_label: Option<String>,
+    /// Processing REST API (`PROCESSING_API_ADDR`) queried for that
+    /// detection, e.g. `http://processing:8090`.
+    pub selftest_processing_url: Option<String>,
+
+    // ── backups (processing) ─────────────────────────────────────────
+    /// Directory receiving periodic snapshots of the detection store.
+    /// Defaults to `backups/` next to `db_path`.
+    pub backup_dir: PathBuf,
+    /// Days between automatic backups; `0` disables them.  Default: 7.
+    pub backup_interval_days: u32,
+    /// Number of snapshots kept in `backup_dir`.  Default: 4.
+    pub backup_keep: u32,
+    /// Optional off-device copy target: an rsync destination
+    /// (`user@host:/path/`) or an `s3://bucket/prefix/` URL.
+    pub backup_remote: Option<String>,
 }
 
+/// Default clip filename template – matches the historical layout.
+pub const DEFAULT_CLIP_NAME_TEMPLATE: &str =
+    "{domain}-{common_name}-{confidence}-{date}-{model}-{stream}{time}";
+
+/// Upper bound for `TOP_PREDICTIONS`.
+pub const MAX_TOP_PREDICTIONS: usize = 10;
+
+/// Highest BCM GPIO number on the Raspberry Pi's 40-pin header.
+pub const MAX_GPIO_PIN: u8 = 27;
+
 impl Config {
     /// Default config path.
     pub fn default_path() -> &'static str {
         "/etc/gaia/gaia.conf"
     }
 
-    /// Convenience: the StreamData subdirectory under `recs_dir`.
+    /// Strftime pattern ffmpeg names capture segments with; `tag` is the
+    /// stream id (`"RTSP_1-"`, `"UDP_1-"`) or empty for the microphone.
+    pub fn segment_name_pattern(&self, tag: &str) -> String {
+        crate::detection::segment_name_pattern(tag, self.portable_file_names, self.utc_file_names)
+    }
+
+    /// Configured coordinates, or `None` while `LATITUDE` / `LONGITUDE`
+    /// are unset (`-1`) or out of range.
+    pub fn location(&self) -> Option<(f64, f64)> {
+        let (lat, lon) = (self.latitude, self.longitude);
+        let set = lat != -1.0 && lon != -1.0;
+        (set && (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some((lat, lon))
+    }
+
+    /// Channel handling for recordings of `node`: its
+    /// `NODE_CHANNEL_MODES` entry, else `CHANNEL_MODE`.
+    pub fn channel_mode_for(&self, node: &str) -> crate::audio::ChannelMode {
+        let mode = self.node_channel_modes.get(node);
+        crate::audio::ChannelMode::parse(mode.unwrap_or(&self.channel_mode))
+    }
+
+    /// Microphone positions of `node` (`NODE_MIC_POSITIONS`, else
+    /// `MIC_POSITIONS`); `None` when direction estimation is off for it.
+    pub fn mic_positions_for(&self, node: &str) -> Option<&str> {
+        let positions = self.node_mic_positions.get(node);
+        Some(positions.unwrap_or(&self.mic_positions).as_str()).filter(|p| !p.trim().is_empty())
+    }
+
+    /// Where capture writes segments: the StreamData subdirectory under
+    /// `recs_dir`, or the RAM ring buffer when `RING_BUFFER_SECS` is set.
     pub fn stream_data_dir(&self) -> PathBuf {
-        self.recs_dir.join("StreamData")
+        if self.ring_buffer() {
+            self.ring_buffer_dir.clone()
+        } else {
+            self.recs_dir.join("StreamData")
+        }
+    }
+
+    /// Whether segments are recorded into the RAM ring buffer.
+    pub fn ring_buffer(&self) -> bool {
+        self.ring_buffer_secs > 0
+    }
+
+    /// Values that parsed but cannot work, one message each; empty when
+    /// the config is usable.  [`load`] falls back to defaults silently,
+    /// so `check-config` reports these instead.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let location_set = self.latitude != -1.0 || self.longitude != -1.0;
+        if location_set && self.location().is_none() {
+            problems.push(format!(
+                "LATITUDE/LONGITUDE ({}, {}) must both be set and in range",
+                self.latitude, self.longitude
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.confidence) {
+            problems.push(format!("CONFIDENCE={} is outside 0..1", self.confidence));
+        }
+        if !(0.5..=1.5).contains(&self.sensitivity) {
+            problems.push(format!("SENSITIVITY={} is outside 0.5..1.5", self.sensitivity));
+        }
+        if self.overlap < 0.0 || self.overlap >= 3.0 {
+            problems.push(format!("OVERLAP={} is outside 0..3 seconds", self.overlap));
+        }
+        if self.recording_length == 0 {
+            problems.push("RECORDING_LENGTH must be at least 1 second".into());
+        }
+        if self.extraction_length == 0 {
+            problems.push("EXTRACTION_LENGTH must be at least 1 second".into());
+        }
+        if self.ring_buffer() && self.ring_buffer_secs < 2 * self.recording_length {
+            problems.push(format!(
+                "RING_BUFFER_SECS={} must hold at least two {}-second segments",
+                self.ring_buffer_secs, self.recording_length
+            ));
+        }
+        for (key, pin) in [
+            ("STATUS_LED_PIN", self.status_led_pin),
+            ("FAULT_RELAY_PIN", self.fault_relay_pin),
+        ] {
+            if let Some(pin) = pin.filter(|&p| p > MAX_GPIO_PIN) {
+                problems.push(format!(
+                    "{key}={pin} is not a header GPIO (BCM 0..{MAX_GPIO_PIN})"
+                ));
+            }
+        }
+        if self.status_led_pin.is_some() && self.status_led_pin == self.fault_relay_pin {
+            problems.push("STATUS_LED_PIN and FAULT_RELAY_PIN must be different pins".into());
+        }
+        if self.capture_tls_cert.is_some() != self.capture_tls_key.is_some() {
+            problems.push("CAPTURE_TLS_CERT and CAPTURE_TLS_KEY must be set together".into());
+        }
+        for (key, path) in [
+            ("CAPTURE_TLS_CERT", &self.capture_tls_cert),
+            ("CAPTURE_TLS_KEY", &self.capture_tls_key),
+            ("CAPTURE_TLS_CA", &self.capture_tls_ca),
+        ] {
+            if let Some(path) = path.as_ref().filter(|p| !p.exists()) {
+                problems.push(format!("{key}={} does not exist", path.display()));
+            }
+        }
+        problems
     }
 }
 
@@ -134,6 +426,27 @@ pub fn load(path: &Path) -> Result<Config> {
     let extracted_dir = get("EXTRACTED")
         .map(PathBuf::from)
         .unwrap_or_else(|| recs_dir.join("Extracted"));
+    let kept_dir = get("KEPT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| recs_dir.join("Kept"));
+
+    let db_path = PathBuf::from(
+        get("TURSO_DATABASE_URL")
+            .or_else(|| get("DB_PATH"))
+            .unwrap_or_else(|| "/data/birds.db".into()),
+    );
+    let rarity_checklist = get("RARITY_CHECKLIST").map(PathBuf::from).unwrap_or_else(|| {
+        db_path.parent().unwrap_or(Path::new("/data")).join("regional_checklist.csv")
+    });
+    let rules_file = get("RULES_FILE").map(PathBuf::from).unwrap_or_else(|| {
+        db_path.parent().unwrap_or(Path::new("/data")).join("rules.toml")
+    });
+    let retention_file = get("RETENTION_FILE").map(PathBuf::from).unwrap_or_else(|| {
+        db_path.parent().unwrap_or(Path::new("/data")).join("retention.toml")
+    });
+    let backup_dir = get("BACKUP_DIR").map(PathBuf::from).unwrap_or_else(|| {
+        db_path.parent().unwrap_or(Path::new("/data")).join("backups")
+    });
 
     let rtsp_streams: Vec<String> = get("RTSP_STREAMS")
         .map(|s| {
@@ -150,6 +463,10 @@ pub fn load(path: &Path) -> Result<Config> {
         confidence: get_f64("CONFIDENCE", 0.7),
         sensitivity: get_f64("SENSITIVITY", 1.25),
         overlap: get_f64("OVERLAP", 0.0),
+        top_predictions: get("TOP_PREDICTIONS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+            .min(MAX_TOP_PREDICTIONS),
         recording_length: get_u32("RECORDING_LENGTH", 15),
         channels: get("CHANNELS").and_then(|v| v.parse().ok()).unwrap_or(1),
         rec_card: get("REC_CARD").filter(|s| !s.is_empty()),
@@ -157,6 +474,15 @@ pub fn load(path: &Path) -> Result<Config> {
         extracted_dir,
         audio_fmt: get("AUDIOFMT").unwrap_or_else(|| "wav".into()),
         rtsp_streams,
+        udp_listen: get("UDP_LISTEN").filter(|s| !s.is_empty()),
+        portable_file_names: get("FILE_NAME_SCHEME")
+            .is_some_and(|v| v.eq_ignore_ascii_case("portable")),
+        utc_file_names: get("FILE_NAME_TIMEZONE").is_some_and(|v| v.eq_ignore_ascii_case("utc")),
+        ring_buffer_secs: get_u32("RING_BUFFER_SECS", 0),
+        ring_buffer_dir: PathBuf::from(
+            get("RING_BUFFER_DIR").unwrap_or_else(|| "/dev/shm/gaia-ring".into()),
+        ),
+        kept_dir,
 
         model_dir: PathBuf::from(get("MODEL_DIR").unwrap_or_else(|| "/models".into())),
         database_lang: get("DATABASE_LANG").unwrap_or_else(|| "en".into()),
@@ -176,26 +502,72 @@ pub fn load(path: &Path) -> Result<Config> {
             .and_then(|v| v.parse().ok())
             .unwrap_or(1)
             .max(1),
+        inference_threads: get("INFERENCE_THREADS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1)
+            .max(1),
+        chunk_cache_size: get_u32("CHUNK_CACHE_SIZE", 32).min(1024) as usize,
+        ensemble_mode: get("ENSEMBLE_MODE").unwrap_or_else(|| "off".into()),
+        channel_mode: get("CHANNEL_MODE").unwrap_or_else(|| "mix".into()),
+        node_channel_modes: get("NODE_CHANNEL_MODES")
+            .map(|s| parse_node_values(&s))
+            .unwrap_or_default(),
+        mic_positions: get("MIC_POSITIONS").unwrap_or_default(),
+        node_mic_positions: get("NODE_MIC_POSITIONS")
+            .map(|s| parse_node_values(&s))
+            .unwrap_or_default(),
         raw_spectrogram: get("RAW_SPECTROGRAM")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(false),
         privacy_threshold: get_f64("PRIVACY_THRESHOLD", 0.0),
         extraction_length: get_u32("EXTRACTION_LENGTH", 6),
+        clip_name_template: get("CLIP_NAME_TEMPLATE")
+            .unwrap_or_else(|| DEFAULT_CLIP_NAME_TEMPLATE.into()),
+        clip_format: get("CLIP_FORMAT").unwrap_or_else(|| "opus".into()),
+        clip_bitrate: get("CLIP_BITRATE").unwrap_or_else(|| "96k".into()),
+        keep_wav_clips: get("KEEP_WAV_CLIPS")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false),
+        time_expansion: get_u32("TIME_EXPANSION", 10),
+        time_expansion_highpass_hz: get_f64("TIME_EXPANSION_HIGHPASS_HZ", 15_000.0),
+        ring_keep_energy_db: get_f64("RING_KEEP_ENERGY_DB", 18.0).max(0.0),
+
+        rarity_checklist,
+        rarity_threshold: get_f64("RARITY_THRESHOLD", 0.98).clamp(0.0, 1.0),
+
+        rules_file,
+        retention_file,
 
         birdweather_id: get("BIRDWEATHER_ID").filter(|s| !s.is_empty()),
         heartbeat_url: get("HEARTBEAT_URL").filter(|s| !s.is_empty()),
-
-        db_path: PathBuf::from(
-            get("TURSO_DATABASE_URL")
-                .or_else(|| get("DB_PATH"))
-                .unwrap_or_else(|| "/data/birds.db".into()),
-        ),
+        heartbeat_interval_secs: get("HEARTBEAT_INTERVAL_SECS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300)
+            .max(30),
+        heartbeat_signals: get("HEARTBEAT_SIGNALS").and_then(|v| {
+            match v.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" => Some(true),
+                "0" | "false" | "off" | "no" => Some(false),
+                _ => None,
+            }
+        }),
+        watchdog_hours: get_u32("WATCHDOG_HOURS", 24).min(72),
+        watchdog_fraction: get_f64("WATCHDOG_FRACTION", 0.1).clamp(0.0, 1.0),
+
+        db_path,
         turso_database_url: get("TURSO_DATABASE_URL"),
         turso_auth_token: get("TURSO_AUTH_TOKEN"),
 
         colormap: get("COLORMAP").unwrap_or_else(|| "default".into()),
 
         disk_usage_max: get_f64("DISK_USAGE_MAX", 95.0),
+        disk_inode_max: get_f64("DISK_INODE_MAX", 95.0),
+        status_led_pin: get("STATUS_LED_PIN").and_then(|v| v.parse().ok()),
+        fault_relay_pin: get("FAULT_RELAY_PIN").and_then(|v| v.parse().ok()),
+        gpio_active_low: get("GPIO_ACTIVE_LOW")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false),
+        clock_max_offset_ms: get_f64("CLOCK_MAX_OFFSET_MS", 1000.0),
 
         capture_listen_addr: get("CAPTURE_LISTEN_ADDR")
             .unwrap_or_else(|| "0.0.0.0:8089".into()),
@@ -204,9 +576,76 @@ pub fn load(path: &Path) -> Result<Config> {
         poll_interval_secs: get("POLL_INTERVAL_SECS")
             .and_then(|v| v.parse().ok())
             .unwrap_or(5),
+        download_threads: get("DOWNLOAD_THREADS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(2)
+            .max(1),
+        dedup_window_hours: get_u32("DEDUP_WINDOW_HOURS", 24),
+        node_priorities: get("NODE_PRIORITIES")
+            .map(|s| parse_node_priorities(&s))
+            .unwrap_or_default(),
+        processing_api_addr: get("PROCESSING_API_ADDR").filter(|s| !s.is_empty()),
+        upload_max_mb: get_u32("UPLOAD_MAX_MB", 8),
+        import_dir: get("IMPORT_DIR")
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from),
+        capture_auth_token: get("CAPTURE_AUTH_TOKEN").filter(|s| !s.is_empty()),
+        capture_tls_cert: get("CAPTURE_TLS_CERT")
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from),
+        capture_tls_key: get("CAPTURE_TLS_KEY")
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from),
+        capture_tls_ca: get("CAPTURE_TLS_CA")
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from),
+        selftest_file: get("SELFTEST_FILE")
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from),
+        selftest_label: get("SELFTEST_LABEL").filter(|s| !s.is_empty()),
+        selftest_processing_url: get("SELFTEST_PROCESSING_URL")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.trim_end_matches('/').to_string()),
+
+        backup_dir,
+        backup_interval_days: get_u32("BACKUP_INTERVAL_DAYS", 7),
+        backup_keep: get_u32("BACKUP_KEEP", 4),
+        backup_remote: get("BACKUP_REMOTE").filter(|s| !s.is_empty()),
     })
 }
 
+/// Parse `node=weight` pairs separated by commas.  Malformed entries are
+/// skipped; weights below 1 are raised to 1.
+fn parse_node_priorities(s: &str) -> HashMap<String, u32> {
+    s.split(',')
+        .filter_map(|pair| {
+            let (node, weight) = pair.rsplit_once('=')?;
+            let weight: u32 = weight.trim().parse().ok()?;
+            let node = node.trim();
+            (!node.is_empty()).then(|| (node.to_string(), weight.max(1)))
+        })
+        .collect()
+}
+
+/// Parse `node=value` pairs separated by commas, skipping malformed ones.
+fn parse_node_values(s: &str) -> HashMap<String, String> {
+    s.split(',')
+        .filter_map(|pair| {
+            let (node, value) = pair.split_once('=')?;
+            let (node, value) = (node.trim(), value.trim());
+            (!node.is_empty() && !value.is_empty()).then(|| (node.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The raw `KEY=VALUE` pairs of a configuration file, without defaults
+/// or environment overrides, e.g. to tell which keys changed on reload.
+pub fn read_keys(path: &Path) -> Result<HashMap<String, String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Cannot read config: {}", path.display()))?;
+    Ok(parse_conf(&text))
+}
+
 /// Parse `KEY=VALUE` lines into a map, stripping optional double-quotes.
 fn parse_conf(text: &str) -> HashMap<String, String> {
     let mut map = HashMap::new();
@@ -247,12 +686,107 @@ CAPTURE_LISTEN_ADDR=0.0.0.0:9090
         assert_eq!(map["CAPTURE_LISTEN_ADDR"], "0.0.0.0:9090");
     }
 
+    #[test]
+    fn test_parse_node_priorities() {
+        let map = parse_node_priorities("garden=3, http://roof.local:8089=0,bad,=2");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["garden"], 3);
+        assert_eq!(map["http://roof.local:8089"], 1);
+    }
+
+    #[test]
+    fn test_channel_mode_for() {
+        use crate::audio::ChannelMode;
+        let path = std::env::temp_dir().join("gaia_test_channel_mode.conf");
+        let text = "NODE_CHANNEL_MODES=garden=separate,roof=mix,bad,=x\n";
+        std::fs::write(&path, text).unwrap();
+        let config = load(&path).unwrap();
+        assert_eq!(config.node_channel_modes.len(), 2);
+        assert_eq!(config.channel_mode_for("garden"), ChannelMode::Separate);
+        assert_eq!(config.channel_mode_for("roof"), ChannelMode::Mix);
+        assert_eq!(config.channel_mode_for("field"), ChannelMode::Mix);
+    }
+
+    #[test]
+    fn test_mic_positions_for() {
+        let path = std::env::temp_dir().join("gaia_test_mic_positions.conf");
+        std::fs::write(&path, "NODE_MIC_POSITIONS=roof=0:0 0:0.2\n").unwrap();
+        let config = load(&path).unwrap();
+        assert_eq!(config.mic_positions_for("garden"), None);
+        assert_eq!(config.mic_positions_for("roof"), Some("0:0 0:0.2"));
+
+        let text = "MIC_POSITIONS=-0.1:0 0.1:0\nNODE_MIC_POSITIONS=roof=0:0 0:0.2 0.2:0\n";
+        std::fs::write(&path, text).unwrap();
+        let config = load(&path).unwrap();
+        assert_eq!(config.mic_positions_for("garden"), Some("-0.1:0 0.1:0"));
+        assert_eq!(config.mic_positions_for("roof"), Some("0:0 0:0.2 0.2:0"));
+    }
+
+    #[test]
+    fn test_config_location() {
+        let path = std::env::temp_dir().join("gaia_test_location.conf");
+        let location = |text: &str| {
+            std::fs::write(&path, text).unwrap();
+            load(&path).unwrap().location()
+        };
+        assert_eq!(location("LATITUDE=9.93\nLONGITUDE=-84.07\n"), Some((9.93, -84.07)));
+        assert_eq!(location("LATITUDE=9.93\n"), None);
+        assert_eq!(location("LATITUDE=99\nLONGITUDE=0\n"), None);
+    }
+
+    #[test]
+    fn test_config_problems() {
+        let path = std::env::temp_dir().join("gaia_test_problems.conf");
+        let problems = |text: &str| {
+            std::fs::write(&path, text).unwrap();
+            load(&path).unwrap().problems()
+        };
+        assert!(problems("LATITUDE=9.93\nLONGITUDE=-84.07\n").is_empty());
+        assert_eq!(problems("LATITUDE=9.93\n").len(), 1);
+        assert_eq!(problems("CONFIDENCE=70\nOVERLAP=-1\n").len(), 2);
+        let tls = problems("CAPTURE_TLS_CERT=/nonexistent/cert.pem\n");
+        assert_eq!(tls.len(), 2, "{tls:?}");
+        // Left blank in gaia.conf: TLS stays off.
+        assert!(problems("CAPTURE_TLS_CERT=\nCAPTURE_TLS_KEY=\nCAPTURE_TLS_CA=\n").is_empty());
+    }
+
     #[test]
     fn test_config_stream_data_dir() {
         let text = "RECS_DIR=/tmp/test\n";
         let tmp = tempfile(text);
         let config = load(tmp.as_path()).unwrap();
         assert_eq!(config.stream_data_dir(), PathBuf::from("/tmp/test/StreamData"));
+
+        let tmp = tempfile("RECS_DIR=/tmp/test\nRING_BUFFER_SECS=120\n");
+        let config = load(tmp.as_path()).unwrap();
+        assert_eq!(
+            config.stream_data_dir(),
+            PathBuf::from("/dev/shm/gaia-ring")
+        );
+        assert_eq!(config.kept_dir, PathBuf::from("/tmp/test/Kept"));
+        assert!(config.problems().is_empty());
+
+        let tmp = tempfile("RING_BUFFER_SECS=20\n");
+        assert_eq!(load(tmp.as_path()).unwrap().problems().len(), 1);
+    }
+
+    #[test]
+    fn test_config_gpio_pins() {
+        let path = std::env::temp_dir().join("gaia_test_gpio.conf");
+        let load_text = |text: &str| {
+            std::fs::write(&path, text).unwrap();
+            load(&path).unwrap()
+        };
+        let config = load_text("STATUS_LED_PIN=17\nFAULT_RELAY_PIN=27\nGPIO_ACTIVE_LOW=1\n");
+        assert_eq!(
+            (config.status_led_pin, config.fault_relay_pin),
+            (Some(17), Some(27))
+        );
+        assert!(config.gpio_active_low);
+        assert!(config.problems().is_empty());
+
+        let config = load_text("STATUS_LED_PIN=40\nFAULT_RELAY_PIN=40\n");
+        assert_eq!(config.problems().len(), 3);
     }
 
     fn tempfile(content: &str) -> PathBuf {
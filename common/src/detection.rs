@@ -3,7 +3,13 @@
 //! Reused from `birdnet-server/src/detection.rs`, extended with a `domain`
 //! field so that a single database / pipeline can hold birds, bats, insects, etc.
 
-use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+// Defined in gaia-client so the WASM dashboard names clip directories
+// exactly as processing writes them.
+pub use gaia_client::model::{
+    safe_file_stem, safe_path_component, MAX_FILE_STEM_BYTES, MAX_PATH_COMPONENT_BYTES,
+};
 
 /// Normalise a scientific name to a canonical form:
 ///   - Replace underscores with spaces
@@ -74,6 +80,24 @@ pub struct Detection {
     /// `true` when the model is marked as beta / experimental.
     #[serde(default)]
     pub model_beta: bool,
+    /// Version of the model (e.g. `"2.4"`); empty when unknown.
+    #[serde(default)]
+    pub model_version: String,
+    /// Download variant of the model files (e.g. `"fp16"`), if any.
+    #[serde(default)]
+    pub model_variant: String,
+    /// Inference runtime (e.g. `"tract-onnx"`, `"onnxruntime-cuda"`).
+    #[serde(default)]
+    pub model_runtime: String,
+    /// Input channel (0-based) the detection was made on when channels
+    /// are analysed separately; `None` for the mono downmix.
+    #[serde(default)]
+    pub channel: Option<u16>,
+    /// Compass bearing of the sound in degrees (0 = north, clockwise),
+    /// estimated from the channel delays when the node's microphone
+    /// positions are configured.
+    #[serde(default)]
+    pub bearing_deg: Option<f64>,
     /// Cross-model agreement score (0.0 – 1.0).
     ///
     /// Computed by `agreement::score_agreement()` after all models have
@@ -84,6 +108,49 @@ pub struct Detection {
     /// Comma-separated slugs of models that agree on this detection.
     #[serde(default)]
     pub agreement_models: String,
+    /// Frequency with the most energy in the extracted clip (Hz).
+    /// `None` until reporting has analysed the clip.
+    #[serde(default)]
+    pub peak_freq_hz: Option<f64>,
+    /// Width of the band within 20 dB of the peak (Hz).
+    #[serde(default)]
+    pub bandwidth_hz: Option<f64>,
+    /// Duration of the part of the clip where the call band is active (s).
+    #[serde(default)]
+    pub call_duration_secs: Option<f64>,
+    /// Position in the lunar cycle (0.0 new → 0.5 full → 1.0), see
+    /// [`crate::astro`].  Set by reporting.
+    #[serde(default)]
+    pub moon_phase: Option<f64>,
+    /// Illuminated fraction of the moon (0.0 – 1.0).
+    #[serde(default)]
+    pub moon_illumination: Option<f64>,
+    /// Day of the year (1 – 366).
+    #[serde(default)]
+    pub day_of_year: Option<u32>,
+    /// Meteorological season at the station (`winter`, `spring`, …).
+    #[serde(default)]
+    pub season: Option<String>,
+    /// Regional rarity (0.0 common – 1.0 never reported) from the
+    /// station's checklist; `None` when the species was not scored.
+    #[serde(default)]
+    pub rarity: Option<f64>,
+    /// `true` when `rarity` reached the configured threshold.
+    #[serde(default)]
+    pub rare: bool,
+    /// The model's best labels for the detection's chunk, highest first
+    /// and including the winner; empty unless `TOP_PREDICTIONS` is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_predictions: Vec<TopPrediction>,
+}
+
+/// One of the alternative labels stored with a detection
+/// (`TOP_PREDICTIONS`), kept for later recalibration.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TopPrediction {
+    pub scientific_name: String,
+    pub common_name: String,
+    pub confidence: f64,
 }
 
 impl Detection {
@@ -97,12 +164,13 @@ impl Detection {
         confidence: f64,
     ) -> Self {
         let dt = file_date + chrono::Duration::milliseconds((start * 1000.0) as i64);
+        // In the repeated hour after a DST change, take the first.
         let local_dt = Local
             .from_local_datetime(&dt)
-            .single()
+            .earliest()
             .unwrap_or_else(|| Local::now());
 
-        let common_name_safe = common_name.replace('\'', "").replace(' ', "_");
+        let common_name_safe = safe_path_component(common_name);
 
         Detection {
             domain: domain.to_string(),
@@ -122,11 +190,38 @@ impl Detection {
             model_slug: String::new(),
             model_name: String::new(),
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            moon_phase: None,
+            moon_illumination: None,
+            day_of_year: None,
+            season: None,
+            rarity: None,
+            rare: false,
+            top_predictions: Vec::new(),
         }
     }
 
+    /// Fill in the moon phase, day of year and season for a station at
+    /// `latitude`.
+    pub fn annotate_sky(&mut self, latitude: f64) {
+        use crate::astro;
+        let round = |v: f64| (v * 1000.0).round() / 1000.0;
+        let phase = astro::moon_phase(&self.datetime);
+        self.moon_phase = Some(round(phase));
+        self.moon_illumination = Some(round(astro::moon_illumination(phase)));
+        self.day_of_year = Some(self.datetime.ordinal());
+        self.season = Some(astro::season(self.datetime.date_naive(), latitude).to_string());
+    }
+
     /// Confidence as integer percentage (0..100).
     pub fn confidence_pct(&self) -> u32 {
         (self.confidence * 100.0).round() as u32
@@ -143,16 +238,36 @@ impl std::fmt::Display for Detection {
     }
 }
 
+/// Strftime pattern of capture segment names (see [`ParsedFileName`]).
+///
+/// `portable` drops the colons from the time; `utc` appends `Z`, and the
+/// writer must then format the time in UTC (ffmpeg: `TZ=UTC`).
+pub fn segment_name_pattern(tag: &str, portable: bool, utc: bool) -> String {
+    let time = if portable { "%H%M%S" } else { "%H:%M:%S" };
+    let zone = if utc { "Z" } else { "" };
+    format!("%F-birdnet-{tag}{time}{zone}.wav")
+}
+
 /// Parsed metadata from a recording filename.
 ///
 /// Filenames follow the pattern:
 ///   `2024-02-24-birdnet-RTSP_1-16:19:37.wav`
+///   `2024-02-24-birdnet-UDP_1-16:19:37.wav`
 ///   `2024-02-24-birdnet-16:19:37.wav`
+///
+/// The time may also be written without colons (`…-161937.wav`) and in
+/// UTC with a `Z` suffix (`…-16:19:37Z.wav`, `…-161937Z.wav`); UTC names
+/// are converted to local time.
+///
+/// When the capture server stamped the WAV with its start time (see
+/// [`crate::wav_meta`]), that time wins over the one in the name.
 #[derive(Debug, Clone)]
 pub struct ParsedFileName {
     pub file_path: std::path::PathBuf,
     pub file_date: NaiveDateTime,
     pub rtsp_id: String,
+    /// Capture node named in the embedded stamp, if any.
+    pub capture_node: Option<String>,
 }
 
 impl ParsedFileName {
@@ -170,16 +285,23 @@ impl ParsedFileName {
         let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
             .map_err(|e| anyhow::anyhow!("Bad date in filename {stem}: {e}"))?;
 
-        // Extract time: trailing HH:MM:SS
-        if stem.len() < 8 {
-            anyhow::bail!("Filename too short for time: {stem}");
-        }
-        let time_str = &stem[stem.len() - 8..];
-        let time = NaiveTime::parse_from_str(time_str, "%H:%M:%S")
-            .map_err(|e| anyhow::anyhow!("Bad time in filename {stem}: {e}"))?;
+        // Extract time: trailing HH:MM:SS or HHMMSS, `Z` when in UTC
+        let (body, utc) = match stem.strip_suffix('Z') {
+            Some(body) => (body, true),
+            None => (stem, false),
+        };
+        let time = name_time(body)
+            .ok_or_else(|| anyhow::anyhow!("Bad time in filename {stem}"))?;
+        let named = if utc {
+            Utc.from_utc_datetime(&NaiveDateTime::new(date, time))
+                .with_timezone(&Local)
+                .naive_local()
+        } else {
+            NaiveDateTime::new(date, time)
+        };
 
-        // Extract RTSP id if present
-        let rtsp_id = if let Some(start) = stem.find("RTSP_") {
+        // Extract stream id (RTSP_n / UDP_n) if present
+        let rtsp_id = if let Some(start) = stem.find("RTSP_").or_else(|| stem.find("UDP_")) {
             let rest = &stem[start..];
             if let Some(end) = rest.find('-') {
                 format!("{}-", &rest[..end])
@@ -190,10 +312,22 @@ impl ParsedFileName {
             String::new()
         };
 
+        let stamp = crate::wav_meta::read_capture_stamp(path);
+        if let Some(s) = &stamp {
+            let drift = (s.start - named).num_seconds();
+            if drift.abs() > 2 {
+                tracing::debug!("{stem}: embedded start {} differs from name by {drift}s", s.start);
+            }
+        }
+
         Ok(ParsedFileName {
             file_path: path.to_path_buf(),
-            file_date: NaiveDateTime::new(date, time),
+            file_date: stamp
+                .as_ref()
+                .map(|s| s.start)
+                .unwrap_or(named),
             rtsp_id,
+            capture_node: stamp.map(|s| s.node).filter(|n| !n.is_empty()),
         })
     }
 
@@ -201,7 +335,7 @@ impl ParsedFileName {
     pub fn iso8601(&self) -> String {
         Local
             .from_local_datetime(&self.file_date)
-            .single()
+            .earliest()
             .map(|dt| dt.to_rfc3339())
             .unwrap_or_default()
     }
@@ -212,6 +346,21 @@ impl ParsedFileName {
     }
 }
 
+/// Time at the end of a segment name stem: `HH:MM:SS` or `HHMMSS`.
+fn name_time(body: &str) -> Option<NaiveTime> {
+    if let Some(t) = body
+        .get(body.len().checked_sub(8)?..)
+        .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M:%S").ok())
+    {
+        return Some(t);
+    }
+    let digits = body.get(body.len().checked_sub(6)?..)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    NaiveTime::parse_from_str(digits, "%H%M%S").ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,6 +381,55 @@ mod tests {
         assert_eq!(pf.rtsp_id, "RTSP_1-");
     }
 
+    #[test]
+    fn test_parse_filename_udp() {
+        let p = Path::new("/data/StreamData/2024-02-24-birdnet-UDP_1-16:19:37.wav");
+        let pf = ParsedFileName::parse(p).unwrap();
+        assert_eq!(pf.rtsp_id, "UDP_1-");
+    }
+
+    #[test]
+    fn test_parse_filename_portable_and_utc() {
+        let p = Path::new("/data/StreamData/2024-02-24-birdnet-RTSP_2-161937.wav");
+        let pf = ParsedFileName::parse(p).unwrap();
+        assert_eq!(pf.file_date.format("%F %T").to_string(), "2024-02-24 16:19:37");
+        assert_eq!(pf.rtsp_id, "RTSP_2-");
+
+        let utc = NaiveDate::from_ymd_opt(2024, 2, 24).unwrap().and_hms_opt(16, 19, 37).unwrap();
+        let local = Utc.from_utc_datetime(&utc).with_timezone(&Local).naive_local();
+        for name in ["2024-02-24-birdnet-161937Z.wav", "2024-02-24-birdnet-16:19:37Z.wav"] {
+            let pf = ParsedFileName::parse(&Path::new("/data/StreamData").join(name)).unwrap();
+            assert_eq!(pf.file_date, local, "{name}");
+        }
+
+        assert!(ParsedFileName::parse(Path::new("2024-02-24-birdnet-16x937.wav")).is_err());
+    }
+
+    #[test]
+    fn test_segment_name_pattern() {
+        assert_eq!(segment_name_pattern("", false, false), "%F-birdnet-%H:%M:%S.wav");
+        assert_eq!(segment_name_pattern("RTSP_1-", true, true), "%F-birdnet-RTSP_1-%H%M%SZ.wav");
+    }
+
+    #[test]
+    fn test_safe_path_component() {
+        // Plain names keep the historical `common_name_safe` form.
+        assert_eq!(safe_path_component("Cooper's Hawk"), "Coopers_Hawk");
+        assert_eq!(safe_path_component("Black-capped Chickadee"), "Black-capped_Chickadee");
+        // Hostile characters never produce extra path segments.
+        assert_eq!(safe_path_component("a/b\\c: d?"), "a_b_c_d");
+        assert_eq!(safe_path_component("../etc"), "etc");
+        assert_eq!(safe_path_component("  "), "unknown");
+        // Unicode is preserved, including combining marks.
+        assert_eq!(safe_path_component("गौरैया चिड़िया"), "गौरैया_चिड़िया");
+        assert_eq!(safe_path_component("シジュウカラ"), "シジュウカラ");
+        // Long names are truncated on a char boundary.
+        let long = "鳥".repeat(100);
+        let safe = safe_path_component(&long);
+        assert!(safe.len() <= MAX_PATH_COMPONENT_BYTES);
+        assert!(safe.chars().all(|c| c == '鳥'));
+    }
+
     #[test]
     fn test_detection_display() {
         let d = Detection::new(
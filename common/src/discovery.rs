@@ -4,12 +4,24 @@
 //! local network via mDNS with a sequential instance name like
 //! `capture-01`, `processing-02`, etc.
 //!
+//! Nodes with a persistent identity ([`crate::node_id`]) also advertise
+//! it as `node_id` / `node_name` TXT properties, which survive the
+//! renumbering of instance names across restarts.  Capture nodes serving
+//! HTTPS add `tls=1` so peers build `https://` URLs.  Every node
+//! advertises its software `version` and the HTTP `protocol` version it
+//! speaks ([`crate::protocol::PROTOCOL_VERSION`]).
+//!
 //! The processing node uses discovery to locate capture nodes automatically,
 //! removing the need for hard-coded URLs or DNS when running containers on
 //! different hardware.
+//!
+//! [`DiscoveryHandle::discover_peers`] is a one-off blocking scan.  Long-running
+//! callers use [`DiscoveryHandle::watch`] instead: a background browser keeps a
+//! [`PeerWatcher`] cache up to date, so reading the peer list never blocks.
 
 use std::collections::{BTreeSet, HashMap};
 use std::net::IpAddr;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
@@ -56,7 +68,7 @@ impl ServiceRole {
 // ── Peer ─────────────────────────────────────────────────────────────────────
 
 /// A service discovered on the network.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Peer {
     /// Instance name, e.g. `capture-01`.
     pub instance_name: String,
@@ -64,10 +76,20 @@ pub struct Peer {
     pub addresses: Vec<IpAddr>,
     /// Listening port.
     pub port: u16,
+    /// Persistent node UUID (`node_id` TXT property), if advertised.
+    pub node_id: Option<String>,
+    /// Friendly node name (`node_name` TXT property), if advertised.
+    pub node_name: Option<String>,
+    /// Whether the peer serves HTTPS (`tls=1` TXT property).
+    pub tls: bool,
+    /// Software version (`version` TXT property), if advertised.
+    pub version: Option<String>,
+    /// HTTP protocol version (`protocol` TXT property), if advertised.
+    pub protocol: Option<u32>,
 }
 
 impl Peer {
-    /// Build an HTTP base URL for this peer, preferring IPv4.
+    /// Build an HTTP(S) base URL for this peer, preferring IPv4.
     pub fn http_url(&self) -> Option<String> {
         let addr = self
             .addresses
@@ -75,9 +97,10 @@ impl Peer {
             .find(|a| a.is_ipv4())
             .or_else(|| self.addresses.first())?;
 
+        let scheme = if self.tls { "https" } else { "http" };
         Some(match addr {
-            IpAddr::V4(v4) => format!("http://{}:{}", v4, self.port),
-            IpAddr::V6(v6) => format!("http://[{}]:{}", v6, self.port),
+            IpAddr::V4(v4) => format!("{scheme}://{}:{}", v4, self.port),
+            IpAddr::V6(v6) => format!("{scheme}://[{}]:{}", v6, self.port),
         })
     }
 
@@ -126,7 +149,7 @@ impl DiscoveryHandle {
         debug!("mDNS: browsing for {} (timeout={}s)", role.service_type(), timeout.as_secs());
         // Collect by instance name so multiple ServiceResolved events
         // (one per interface / address family) are merged into a single peer.
-        let mut peer_map: HashMap<String, Peer> = HashMap::new();
+        let mut table = PeerTable::default();
         let deadline = Instant::now() + timeout;
 
         loop {
@@ -135,50 +158,82 @@ impl DiscoveryHandle {
                 break;
             }
             match receiver.recv_timeout(remaining) {
-                Ok(ServiceEvent::ServiceResolved(info)) => {
-                    let name = info.get_fullname().to_string();
-                    // Don't include ourselves
-                    if name == self.fullname {
-                        debug!("mDNS: ignoring self ({})", name);
-                        continue;
-                    }
-                    let addrs: Vec<IpAddr> =
-                        info.get_addresses().iter().map(|a| a.to_ip_addr()).collect();
-                    let port = info.get_port();
-                    let instance = extract_instance_name(&name);
-
-                    let peer = peer_map.entry(instance.clone()).or_insert_with(|| Peer {
-                        instance_name: instance,
-                        addresses: Vec::new(),
-                        port,
-                    });
-                    // Merge addresses, avoiding duplicates.
-                    for addr in addrs {
-                        if !peer.addresses.contains(&addr) {
-                            peer.addresses.push(addr);
+                Ok(event) => match resolved_peer(&event, &self.fullname) {
+                    Some(peer) => {
+                        if let Some(change) = table.resolved(peer) {
+                            debug!(
+                                "mDNS: resolved {} – addrs now {:?}",
+                                change.peer().instance_name,
+                                change.peer().addresses
+                            );
                         }
                     }
-                    debug!("mDNS: resolved {} – addrs now {:?}", name, peer.addresses);
-                }
-                Ok(event) => {
-                    debug!("mDNS: event {:?}", format_event(&event));
-                }
+                    None => debug!("mDNS: event {:?}", format_event(&event)),
+                },
                 Err(_) => break,
             }
         }
 
         let _ = self.daemon.stop_browse(role.service_type());
-        if peer_map.is_empty() {
+        if table.is_empty() {
             debug!("mDNS: browse completed, no peers found for {}", role.service_type());
         }
 
-        let peers: Vec<Peer> = peer_map.into_values().collect();
+        let peers = table.peers();
         for p in &peers {
             info!("mDNS: peer {} at {:?}:{}", p.instance_name, p.addresses, p.port);
         }
         peers
     }
 
+    /// Start a background browser for peers of `role`.
+    ///
+    /// The returned [`PeerWatcher`] is updated as advertisements arrive
+    /// and expire; browsing stops when it is dropped.
+    pub fn watch(&self, role: ServiceRole) -> Result<PeerWatcher> {
+        let receiver = self
+            .daemon
+            .browse(role.service_type())
+            .with_context(|| format!("Cannot browse mDNS for {}", role.service_type()))?;
+        let shared = Arc::new(WatchState::default());
+        let state = Arc::clone(&shared);
+        let own_fullname = self.fullname.clone();
+
+        std::thread::Builder::new()
+            .name(format!("mdns-{}", role.prefix()))
+            .spawn(move || {
+                while let Ok(event) = receiver.recv() {
+                    let change = match &event {
+                        ServiceEvent::ServiceRemoved(_, fullname) => state
+                            .table
+                            .write()
+                            .unwrap()
+                            .removed(&extract_instance_name(fullname)),
+                        ServiceEvent::SearchStopped(_) => break,
+                        _ => match resolved_peer(&event, &own_fullname) {
+                            Some(peer) => state.table.write().unwrap().resolved(peer),
+                            None => {
+                                debug!("mDNS: event {:?}", format_event(&event));
+                                None
+                            }
+                        },
+                    };
+                    if let Some(change) = change {
+                        state.notify(&change);
+                    }
+                }
+                debug!("mDNS: stopped watching {}", role.service_type());
+            })
+            .context("Cannot spawn mDNS browser thread")?;
+
+        debug!("mDNS: watching {}", role.service_type());
+        Ok(PeerWatcher {
+            daemon: self.daemon.clone(),
+            role,
+            state: shared,
+        })
+    }
+
     /// Unregister from mDNS and shut down the daemon.
     pub fn shutdown(self) {
         if !self.fullname.is_empty() {
@@ -188,14 +243,156 @@ impl DiscoveryHandle {
     }
 }
 
+// ── Peer cache ───────────────────────────────────────────────────────────────
+
+/// A change to the set of peers seen by a [`PeerWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerEvent {
+    /// A peer advertised itself for the first time.
+    Added(Peer),
+    /// A known peer re-advertised with new addresses, port or TXT properties.
+    Updated(Peer),
+    /// A peer's advertisement was withdrawn or expired.
+    Removed(Peer),
+}
+
+impl PeerEvent {
+    /// The peer the event is about.
+    pub fn peer(&self) -> &Peer {
+        match self {
+            Self::Added(p) | Self::Updated(p) | Self::Removed(p) => p,
+        }
+    }
+}
+
+type ChangeCallback = Box<dyn Fn(&PeerEvent) + Send + Sync>;
+
+#[derive(Default)]
+struct WatchState {
+    table: RwLock<PeerTable>,
+    callbacks: Mutex<Vec<ChangeCallback>>,
+}
+
+impl WatchState {
+    fn notify(&self, event: &PeerEvent) {
+        match event {
+            PeerEvent::Added(p) => {
+                info!("mDNS: peer {} at {:?}:{}", p.instance_name, p.addresses, p.port)
+            }
+            PeerEvent::Updated(p) => {
+                debug!("mDNS: peer {} now at {:?}:{}", p.instance_name, p.addresses, p.port)
+            }
+            PeerEvent::Removed(p) => info!("mDNS: peer {} went away", p.instance_name),
+        }
+        for callback in self.callbacks.lock().unwrap().iter() {
+            callback(event);
+        }
+    }
+}
+
+/// Continuously updated peers of one role, from [`DiscoveryHandle::watch`].
+pub struct PeerWatcher {
+    daemon: ServiceDaemon,
+    role: ServiceRole,
+    state: Arc<WatchState>,
+}
+
+impl PeerWatcher {
+    /// The role being watched.
+    pub fn role(&self) -> ServiceRole {
+        self.role
+    }
+
+    /// The peers currently known, sorted by instance name.  Never blocks
+    /// on the network.
+    pub fn peers(&self) -> Vec<Peer> {
+        self.state.table.read().unwrap().peers()
+    }
+
+    /// Wait up to `timeout` for at least one peer, e.g. right after
+    /// startup, and return the peers known by then.
+    pub fn wait_for_peers(&self, timeout: Duration) -> Vec<Peer> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let peers = self.peers();
+            if !peers.is_empty() || Instant::now() >= deadline {
+                return peers;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Call `callback` on the browser thread for every later change.
+    pub fn on_change(&self, callback: impl Fn(&PeerEvent) + Send + Sync + 'static) {
+        self.state.callbacks.lock().unwrap().push(Box::new(callback));
+    }
+}
+
+impl Drop for PeerWatcher {
+    fn drop(&mut self) {
+        let _ = self.daemon.stop_browse(self.role.service_type());
+    }
+}
+
+/// Peers by instance name.  Several ServiceResolved events (one per
+/// interface / address family) are merged into a single peer.
+#[derive(Debug, Default)]
+struct PeerTable {
+    peers: HashMap<String, Peer>,
+}
+
+impl PeerTable {
+    /// Merge a resolved advertisement; returns the change, if any.
+    fn resolved(&mut self, peer: Peer) -> Option<PeerEvent> {
+        let Some(known) = self.peers.get_mut(&peer.instance_name) else {
+            self.peers.insert(peer.instance_name.clone(), peer.clone());
+            return Some(PeerEvent::Added(peer));
+        };
+        let mut merged = Peer {
+            addresses: known.addresses.clone(),
+            ..peer.clone()
+        };
+        for addr in peer.addresses {
+            if !merged.addresses.contains(&addr) {
+                merged.addresses.push(addr);
+            }
+        }
+        if *known == merged {
+            return None;
+        }
+        *known = merged.clone();
+        Some(PeerEvent::Updated(merged))
+    }
+
+    /// Forget a peer whose advertisement was withdrawn.
+    fn removed(&mut self, instance_name: &str) -> Option<PeerEvent> {
+        self.peers.remove(instance_name).map(PeerEvent::Removed)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    fn peers(&self) -> Vec<Peer> {
+        let mut peers: Vec<Peer> = self.peers.values().cloned().collect();
+        peers.sort_by(|a, b| a.instance_name.cmp(&b.instance_name));
+        peers
+    }
+}
+
 // ── Public API ───────────────────────────────────────────────────────────────
 
 /// Register this node on the local network via mDNS.
 ///
 /// The function scans for existing peers of the same role, picks the next
 /// available sequential number, and registers an instance like
-/// `capture-01` or `processing-03`.
-pub fn register(role: ServiceRole, port: u16) -> Result<DiscoveryHandle> {
+/// `capture-01` or `processing-03`, advertising `txt` as TXT properties
+/// along with the `version` and `protocol` ones.
+pub fn register(
+    role: ServiceRole,
+    port: u16,
+    txt: &[(&str, &str)],
+) -> Result<DiscoveryHandle> {
     debug!(
         "mDNS: starting daemon for role={} port={}",
         role.prefix(),
@@ -254,13 +451,18 @@ pub fn register(role: ServiceRole, port: u16) -> Result<DiscoveryHandle> {
     let instance_name = format!("{}-{:02}", role.prefix(), our_number);
     let host = format!("{}.local.", instance_name);
 
+    let protocol = crate::protocol::PROTOCOL_VERSION.to_string();
+    let mut properties = txt.to_vec();
+    properties.push(("version", env!("CARGO_PKG_VERSION")));
+    properties.push(("protocol", &protocol));
+
     let service_info = ServiceInfo::new(
         role.service_type(),
         &instance_name,
         &host,
-        "",   // filled automatically by enable_addr_auto()
+        "", // filled automatically by enable_addr_auto()
         port,
-        None, // no TXT properties
+        &properties[..],
     )
     .context("Cannot create mDNS ServiceInfo")?
     .enable_addr_auto();
@@ -301,6 +503,36 @@ fn format_event(event: &ServiceEvent) -> String {
     }
 }
 
+/// The peer advertised by a ServiceResolved event, unless it is
+/// ourselves (`own_fullname`).
+fn resolved_peer(event: &ServiceEvent, own_fullname: &str) -> Option<Peer> {
+    let ServiceEvent::ServiceResolved(info) = event else {
+        return None;
+    };
+    let name = info.get_fullname();
+    if name == own_fullname {
+        debug!("mDNS: ignoring self ({})", name);
+        return None;
+    }
+    let txt = |key: &str| info.get_property_val_str(key).map(str::to_string);
+    let mut addresses: Vec<IpAddr> = Vec::new();
+    for addr in info.get_addresses().iter().map(|a| a.to_ip_addr()) {
+        if !addresses.contains(&addr) {
+            addresses.push(addr);
+        }
+    }
+    Some(Peer {
+        instance_name: extract_instance_name(name),
+        addresses,
+        port: info.get_port(),
+        node_id: txt("node_id"),
+        node_name: txt("node_name"),
+        tls: txt("tls").as_deref() == Some("1"),
+        version: txt("version"),
+        protocol: txt("protocol").and_then(|p| p.parse().ok()),
+    })
+}
+
 /// Extract the instance number from a fullname like
 /// `capture-03._gaia-capture._tcp.local.`
 fn parse_instance_number(fullname: &str, prefix: &str) -> Option<u32> {
@@ -364,6 +596,44 @@ mod tests {
         assert_eq!(next_available(&gap), 2);
     }
 
+    fn peer(name: &str, addr: &str) -> Peer {
+        Peer {
+            instance_name: name.to_string(),
+            addresses: vec![addr.parse().unwrap()],
+            port: 8090,
+            node_id: None,
+            node_name: None,
+            tls: false,
+            version: None,
+            protocol: None,
+        }
+    }
+
+    #[test]
+    fn test_peer_table_events() {
+        let mut table = PeerTable::default();
+        let first = peer("capture-01", "192.168.1.10");
+        assert_eq!(table.resolved(first.clone()), Some(PeerEvent::Added(first.clone())));
+        // The same advertisement again (another interface) changes nothing.
+        assert_eq!(table.resolved(first.clone()), None);
+
+        // A second address family is merged into the known peer.
+        let Some(PeerEvent::Updated(merged)) = table.resolved(peer("capture-01", "fe80::1"))
+        else {
+            panic!("expected an update");
+        };
+        assert_eq!(merged.addresses.len(), 2);
+        assert_eq!(merged.http_url().as_deref(), Some("http://192.168.1.10:8090"));
+
+        table.resolved(peer("capture-02", "192.168.1.11"));
+        let names: Vec<_> = table.peers().into_iter().map(|p| p.instance_name).collect();
+        assert_eq!(names, ["capture-01", "capture-02"]);
+
+        assert!(matches!(table.removed("capture-01"), Some(PeerEvent::Removed(p)) if p == merged));
+        assert_eq!(table.removed("capture-01"), None);
+        assert_eq!(table.peers().len(), 1);
+    }
+
     #[test]
     fn test_extract_instance_name() {
         assert_eq!(
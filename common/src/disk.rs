@@ -0,0 +1,82 @@
+//! Space and inode usage of a filesystem, shared by the capture disk
+//! guard and processing's clip-extraction guard.
+//!
+//! Calls `df` rather than `statvfs`, like capture's disk helpers,
+//! so no `libc` is needed here.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Usage of the filesystem holding a path, in percent (0–100).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskUsage {
+    /// Share of the blocks in use.
+    pub space_pct: f64,
+    /// Share of the inodes in use; `None` for filesystems without a
+    /// fixed inode table (btrfs, vfat), which `df` reports as `-`.
+    pub inode_pct: Option<f64>,
+}
+
+impl DiskUsage {
+    /// Whether either limit is reached.
+    pub fn exceeds(&self, space_max: f64, inode_max: f64) -> bool {
+        self.space_pct >= space_max || self.inode_pct.is_some_and(|p| p >= inode_max)
+    }
+
+    /// Short description for logs, e.g. `space 96.0%, inodes 12.0%`.
+    pub fn describe(&self) -> String {
+        match self.inode_pct {
+            Some(inodes) => format!("space {:.1}%, inodes {inodes:.1}%", self.space_pct),
+            None => format!("space {:.1}%", self.space_pct),
+        }
+    }
+}
+
+/// Space and inode usage of the filesystem containing `path`, or `None`
+/// when `df` is unavailable or fails.
+pub fn usage(path: &Path) -> Option<DiskUsage> {
+    let output = Command::new("df")
+        .args(["--output=pcent,ipcent"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `df --output=pcent,ipcent` output:
+///
+/// ```text
+/// Use% IUse%
+///  42%    7%
+/// ```
+fn parse_df(stdout: &str) -> Option<DiskUsage> {
+    let line = stdout.lines().rev().find(|l| !l.trim().is_empty())?;
+    let mut cols = line.split_whitespace();
+    let pct = |s: &str| s.trim_end_matches('%').parse::<f64>().ok();
+    let space_pct = pct(cols.next()?)?;
+    let inode_pct = cols.next().and_then(pct);
+    Some(DiskUsage { space_pct, inode_pct })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df() {
+        let usage = parse_df("Use% IUse%\n 42%    7%\n").unwrap();
+        assert_eq!(usage, DiskUsage { space_pct: 42.0, inode_pct: Some(7.0) });
+        assert!(!usage.exceeds(95.0, 95.0));
+        assert!(usage.exceeds(95.0, 5.0));
+
+        // btrfs has no inode table.
+        let usage = parse_df("Use% IUse%\n 96%     -\n").unwrap();
+        assert_eq!(usage.inode_pct, None);
+        assert!(usage.exceeds(95.0, 95.0));
+
+        assert!(parse_df("Use% IUse%\n").is_none());
+    }
+}
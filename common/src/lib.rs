@@ -1,5 +1,15 @@
+pub mod astro;
 pub mod audio;
+pub mod capture_api;
 pub mod config;
 pub mod detection;
 pub mod discovery;
-pub mod protocol;
+pub mod disk;
+pub mod node_id;
+pub mod schema;
+pub mod timesync;
+pub mod wav_meta;
+
+/// The HTTP protocol types live in `gaia-client`, so third-party tools
+/// can use them without the audio stack; re-exported under their old path.
+pub use gaia_client::protocol;
@@ -0,0 +1,200 @@
+//! Persistent node identity.
+//!
+//! mDNS instance names (`capture-01`) are handed out first-come at
+//! startup, so the same box can be `capture-02` after a reboot.  Each
+//! capture node therefore keeps a UUID and a friendly name in a small
+//! `KEY=VALUE` file (default `/etc/gaia/node_id`, override with
+//! `GAIA_NODE_ID_FILE`):
+//!
+//! ```text
+//! NODE_ID=6f1c2b0e-5d7a-4c11-9a43-0c2f6f0b8e21
+//! NODE_NAME=garden
+//! ```
+//!
+//! The file is created on first start.  Edit `NODE_NAME` to rename the
+//! node; the `NODE_NAME` environment variable overrides it without
+//! touching the file.  The identity is advertised in mDNS TXT records
+//! and `/api/health`, and stored as `Source_Node` on detections.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+/// Default location of the identity file.
+pub const DEFAULT_PATH: &str = "/etc/gaia/node_id";
+
+/// A node's stable identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeIdentity {
+    /// Random UUID (v4), never changes once written.
+    pub id: String,
+    /// Human-friendly name, e.g. `garden`.
+    pub name: String,
+}
+
+impl NodeIdentity {
+    /// mDNS TXT properties advertising this identity.
+    pub fn txt_properties(&self) -> [(&str, &str); 2] {
+        [("node_id", &self.id), ("node_name", &self.name)]
+    }
+}
+
+/// Path of the identity file (`GAIA_NODE_ID_FILE` or [`DEFAULT_PATH`]).
+pub fn path() -> PathBuf {
+    std::env::var("GAIA_NODE_ID_FILE")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_PATH))
+}
+
+/// Load the identity from [`path()`], creating it on first start.
+///
+/// Never fails: when the file cannot be written the generated identity
+/// is used for this run only, with a warning.
+pub fn load_or_create(default_name: &str) -> NodeIdentity {
+    let path = path();
+    let mut identity = match load_or_create_at(&path, default_name) {
+        Ok(identity) => identity,
+        Err(e) => {
+            warn!(
+                "Cannot persist node identity at {} ({e:#}); it will change on restart. \
+                 Mount a writable file there to keep it.",
+                path.display()
+            );
+            NodeIdentity {
+                id: new_uuid(),
+                name: default_name.to_string(),
+            }
+        }
+    };
+    if let Ok(name) = std::env::var("NODE_NAME") {
+        if !name.trim().is_empty() {
+            identity.name = name.trim().to_string();
+        }
+    }
+    info!("Node identity: {} ({})", identity.name, identity.id);
+    identity
+}
+
+/// [`load_or_create`] for an explicit path, without env overrides.
+pub fn load_or_create_at(path: &Path, default_name: &str) -> Result<NodeIdentity> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Cannot read {}", path.display())),
+    };
+    let (id, name) = parse(&text);
+    if let (Some(id), Some(name)) = (&id, &name) {
+        return Ok(NodeIdentity {
+            id: id.clone(),
+            name: name.clone(),
+        });
+    }
+
+    // Fill in whatever is missing and write the file back.  Written in
+    // place (no rename) so an (empty) bind-mounted file works.
+    let identity = NodeIdentity {
+        id: id.unwrap_or_else(new_uuid),
+        name: name.unwrap_or_else(|| default_name.to_string()),
+    };
+    std::fs::write(
+        path,
+        format!("NODE_ID={}\nNODE_NAME={}\n", identity.id, identity.name),
+    )
+    .with_context(|| format!("Cannot write {}", path.display()))?;
+    info!("Created node identity file {}", path.display());
+    Ok(identity)
+}
+
+/// `(NODE_ID, NODE_NAME)` from the file contents; blanks count as unset.
+fn parse(text: &str) -> (Option<String>, Option<String>) {
+    let mut id = None;
+    let mut name = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim() {
+            "NODE_ID" => id = Some(value.to_string()),
+            "NODE_NAME" => name = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    (id, name)
+}
+
+/// A random (version 4) UUID from the kernel RNG.
+fn new_uuid() -> String {
+    let mut b = [0u8; 16];
+    if std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut b))
+        .is_err()
+    {
+        // No /dev/urandom: time and pid are unique enough for one host.
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        b = (nanos ^ (u128::from(std::process::id()) << 64)).to_le_bytes();
+    }
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex = b.iter().fold(String::with_capacity(32), |mut s, x| {
+        let _ = write!(s, "{x:02x}");
+        s
+    });
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_uuid_format() {
+        let id = new_uuid();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.as_bytes()[14], b'4');
+        assert_ne!(id, new_uuid());
+    }
+
+    #[test]
+    fn test_load_or_create_persists() {
+        let dir = std::env::temp_dir().join(format!("gaia-node-id-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("node_id");
+        // An empty file (as left by `touch` before bind-mounting) is filled in.
+        std::fs::write(&path, "").unwrap();
+
+        let first = load_or_create_at(&path, "capture").unwrap();
+        assert_eq!(first.name, "capture");
+        let again = load_or_create_at(&path, "other").unwrap();
+        assert_eq!(first, again);
+
+        // A renamed node keeps its id.
+        std::fs::write(&path, format!("NODE_ID={}\nNODE_NAME=garden\n", first.id)).unwrap();
+        let renamed = load_or_create_at(&path, "capture").unwrap();
+        assert_eq!(renamed.id, first.id);
+        assert_eq!(renamed.name, "garden");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,349 @@
+//! Versioned migrations for the SQLite database (`birds.db`).
+//!
+//! Processing and web used to each run their own `CREATE TABLE IF NOT
+//! EXISTS` block followed by ad-hoc `ALTER TABLE … ADD COLUMN` calls,
+//! with no record of which schema a database was on.  The schema now
+//! lives here as an ordered list of [`MIGRATIONS`]; applied versions are
+//! recorded in `schema_version` and [`migrate`] (feature `sqlite`)
+//! applies whatever is missing, one transaction per migration.
+//!
+//! Append new migrations at the end; never edit one that has shipped.
+
+/// One schema step.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Strictly increasing, starting at 1.
+    pub version: u32,
+    pub name: &'static str,
+    /// SQL batch run inside the migration's transaction.
+    pub sql: &'static str,
+}
+
+/// All migrations, oldest first.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        sql: V1_BASELINE,
+    },
+    Migration {
+        version: 2,
+        name: "detections_v2",
+        sql: V2_DETECTIONS,
+    },
+];
+
+/// Version a fully migrated database is on.
+pub const LATEST_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+const SCHEMA_VERSION_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_version (
+    version    INTEGER PRIMARY KEY,
+    name       TEXT NOT NULL,
+    applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+);";
+
+/// Columns added ad hoc before migrations existed.  Databases that
+/// predate `schema_version` may lack some of them; they are added when
+/// the baseline is stamped.
+const LEGACY_COLUMNS: &[(&str, &str, &str)] = &[
+    ("detections", "Source_Node", "VARCHAR(200) NOT NULL DEFAULT ''"),
+    ("detections", "Excluded", "INTEGER NOT NULL DEFAULT 0"),
+    ("detections", "Model_Slug", "VARCHAR(100) NOT NULL DEFAULT ''"),
+    ("detections", "Model_Name", "VARCHAR(200) NOT NULL DEFAULT ''"),
+    ("processing_instances", "last_heartbeat", "TEXT NOT NULL DEFAULT ''"),
+];
+
+/// The schema as it stood before versioning: the union of what
+/// processing and web created.
+const V1_BASELINE: &str = "
+CREATE TABLE IF NOT EXISTS detections (
+    Date        DATE,
+    Time        TIME,
+    Domain      VARCHAR(50) NOT NULL DEFAULT 'birds',
+    Sci_Name    VARCHAR(100) NOT NULL,
+    Com_Name    VARCHAR(100) NOT NULL,
+    Confidence  FLOAT,
+    Lat         FLOAT,
+    Lon         FLOAT,
+    Cutoff      FLOAT,
+    Week        INT,
+    Sens        FLOAT,
+    Overlap     FLOAT,
+    File_Name   VARCHAR(100) NOT NULL,
+    Source_Node VARCHAR(200) NOT NULL DEFAULT '',
+    Excluded    INTEGER NOT NULL DEFAULT 0,
+    Model_Slug  VARCHAR(100) NOT NULL DEFAULT '',
+    Model_Name  VARCHAR(200) NOT NULL DEFAULT ''
+);
+CREATE INDEX IF NOT EXISTS detections_Com_Name  ON detections (Com_Name);
+CREATE INDEX IF NOT EXISTS detections_Sci_Name  ON detections (Sci_Name);
+CREATE INDEX IF NOT EXISTS detections_Domain    ON detections (Domain);
+CREATE INDEX IF NOT EXISTS detections_Date_Time ON detections (Date DESC, Time DESC);
+
+CREATE TABLE IF NOT EXISTS urban_noise (
+    Date     DATE NOT NULL,
+    Hour     INT  NOT NULL,
+    Category VARCHAR(50) NOT NULL,
+    Count    INT  NOT NULL DEFAULT 1,
+    UNIQUE(Date, Hour, Category)
+);
+CREATE INDEX IF NOT EXISTS urban_noise_date ON urban_noise (Date DESC);
+
+CREATE TABLE IF NOT EXISTS settings (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS exclusion_overrides (
+    Sci_Name      VARCHAR(100) PRIMARY KEY,
+    overridden_at TEXT NOT NULL DEFAULT (datetime('now')),
+    notes         TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE IF NOT EXISTS species_verifications (
+    Sci_Name        VARCHAR(100) PRIMARY KEY,
+    method          VARCHAR(50) NOT NULL DEFAULT 'ornithologist',
+    inaturalist_obs TEXT NOT NULL DEFAULT '',
+    verified_at     TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS species_stats (
+    Sci_Name        VARCHAR(100) NOT NULL,
+    Com_Name        VARCHAR(100) NOT NULL,
+    Domain          VARCHAR(50)  NOT NULL DEFAULT 'birds',
+    detection_count INTEGER      NOT NULL DEFAULT 0,
+    last_seen       TEXT,
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (Sci_Name, Domain)
+);
+
+CREATE TABLE IF NOT EXISTS species_top_recordings (
+    Sci_Name    VARCHAR(100) NOT NULL,
+    Com_Name    VARCHAR(100) NOT NULL,
+    Date        DATE         NOT NULL,
+    Time        TIME         NOT NULL,
+    Confidence  FLOAT        NOT NULL,
+    File_Name   VARCHAR(100) NOT NULL,
+    Source_Node VARCHAR(200) NOT NULL DEFAULT '',
+    Model_Name  VARCHAR(200) NOT NULL DEFAULT '',
+    rank        INTEGER      NOT NULL DEFAULT 0,
+    PRIMARY KEY (Sci_Name, rank)
+);
+
+CREATE TABLE IF NOT EXISTS file_processing_log (
+    filename     TEXT NOT NULL,
+    instance     TEXT NOT NULL,
+    processed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (filename, instance)
+);
+
+CREATE TABLE IF NOT EXISTS processing_instances (
+    instance       TEXT PRIMARY KEY,
+    registered_at  TEXT NOT NULL DEFAULT (datetime('now')),
+    last_heartbeat TEXT NOT NULL DEFAULT (datetime('now'))
+);";
+
+/// Detections get an explicit `id` (kept equal to the old rowid, so
+/// rowid-based cursors keep working), `UNIQUE(File_Name, Time,
+/// Sci_Name)`, and a `detection_species` table they reference (named so
+/// it is not confused with the DuckDB `species` table).  The old table
+/// is kept as `detections_v1`, so rows dropped as duplicates while
+/// copying are not lost.
+const V2_DETECTIONS: &str = "
+CREATE TABLE detection_species (
+    id       INTEGER PRIMARY KEY,
+    Sci_Name VARCHAR(100) NOT NULL UNIQUE,
+    Com_Name VARCHAR(100) NOT NULL,
+    Domain   VARCHAR(50)  NOT NULL DEFAULT 'birds'
+);
+INSERT INTO detection_species (Sci_Name, Com_Name, Domain)
+    SELECT Sci_Name, MAX(Com_Name), MAX(Domain) FROM detections GROUP BY Sci_Name;
+
+DROP INDEX IF EXISTS detections_Com_Name;
+DROP INDEX IF EXISTS detections_Sci_Name;
+DROP INDEX IF EXISTS detections_Domain;
+DROP INDEX IF EXISTS detections_Date_Time;
+ALTER TABLE detections RENAME TO detections_v1;
+
+CREATE TABLE detections (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    Date        DATE,
+    Time        TIME,
+    Domain      VARCHAR(50) NOT NULL DEFAULT 'birds',
+    Sci_Name    VARCHAR(100) NOT NULL REFERENCES detection_species (Sci_Name),
+    Com_Name    VARCHAR(100) NOT NULL,
+    Confidence  FLOAT,
+    Lat         FLOAT,
+    Lon         FLOAT,
+    Cutoff      FLOAT,
+    Week        INT,
+    Sens        FLOAT,
+    Overlap     FLOAT,
+    File_Name   VARCHAR(100) NOT NULL,
+    Source_Node VARCHAR(200) NOT NULL DEFAULT '',
+    Excluded    INTEGER NOT NULL DEFAULT 0,
+    Model_Slug  VARCHAR(100) NOT NULL DEFAULT '',
+    Model_Name  VARCHAR(200) NOT NULL DEFAULT '',
+    UNIQUE (File_Name, Time, Sci_Name)
+);
+INSERT OR IGNORE INTO detections (
+    id, Date, Time, Domain, Sci_Name, Com_Name, Confidence, Lat, Lon, Cutoff,
+    Week, Sens, Overlap, File_Name, Source_Node, Excluded, Model_Slug, Model_Name
+)
+    SELECT rowid, Date, Time, Domain, Sci_Name, Com_Name, Confidence, Lat, Lon, Cutoff,
+           Week, Sens, Overlap, File_Name, Source_Node, Excluded, Model_Slug, Model_Name
+    FROM detections_v1 ORDER BY rowid;
+
+CREATE INDEX detections_Com_Name  ON detections (Com_Name);
+CREATE INDEX detections_Sci_Name  ON detections (Sci_Name);
+CREATE INDEX detections_Domain    ON detections (Domain);
+CREATE INDEX detections_Date_Time ON detections (Date DESC, Time DESC);";
+
+/// Register a species before inserting detections of it (v2 foreign key,
+/// enforced on connections that set `PRAGMA foreign_keys=ON`).
+pub const UPSERT_SPECIES: &str = "INSERT INTO detection_species (Sci_Name, Com_Name, Domain) \
+     VALUES (?1, ?2, ?3) ON CONFLICT (Sci_Name) DO NOTHING";
+
+/// Bring the database on `conn` up to [`LATEST_VERSION`]; returns the
+/// resulting version.
+///
+/// Safe to run concurrently from several containers: each migration
+/// takes the write lock (`BEGIN IMMEDIATE`) and re-checks the version
+/// before applying.
+#[cfg(feature = "sqlite")]
+pub async fn migrate(conn: &libsql::Connection) -> anyhow::Result<u32> {
+    use anyhow::Context;
+
+    conn.execute_batch(SCHEMA_VERSION_TABLE)
+        .await
+        .context("Cannot create schema_version table")?;
+
+    for m in MIGRATIONS {
+        let tx = conn
+            .transaction_with_behavior(libsql::TransactionBehavior::Immediate)
+            .await
+            .context("Cannot start migration transaction")?;
+        if current_version(&tx).await? >= m.version {
+            tx.rollback().await.ok();
+            continue;
+        }
+        let applied = async {
+            tx.execute_batch(m.sql).await?;
+            if m.version == 1 {
+                add_legacy_columns(&tx).await?;
+            }
+            tx.execute(
+                "INSERT INTO schema_version (version, name) VALUES (?1, ?2)",
+                libsql::params![i64::from(m.version), m.name],
+            )
+            .await?;
+            Ok::<(), libsql::Error>(())
+        }
+        .await;
+        match applied {
+            Ok(()) => tx.commit().await.context("Cannot commit migration")?,
+            Err(e) => {
+                tx.rollback().await.ok();
+                return Err(e).with_context(|| format!("Migration {} ({}) failed", m.version, m.name));
+            }
+        }
+        tracing::info!("Applied schema migration {} ({})", m.version, m.name);
+    }
+
+    current_version(conn).await
+}
+
+/// Highest applied migration, 0 for an unversioned database.
+#[cfg(feature = "sqlite")]
+pub async fn current_version(conn: &libsql::Connection) -> anyhow::Result<u32> {
+    let mut rows = conn
+        .query("SELECT COALESCE(MAX(version), 0) FROM schema_version", ())
+        .await?;
+    let version = match rows.next().await? {
+        Some(row) => row.get::<i64>(0)?,
+        None => 0,
+    };
+    Ok(u32::try_from(version).unwrap_or(0))
+}
+
+#[cfg(feature = "sqlite")]
+async fn add_legacy_columns(conn: &libsql::Connection) -> Result<(), libsql::Error> {
+    for (table, column, typedef) in LEGACY_COLUMNS {
+        let mut rows = conn
+            .query(
+                "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+                libsql::params![*table, *column],
+            )
+            .await?;
+        let present = match rows.next().await? {
+            Some(row) => row.get::<i64>(0)? > 0,
+            None => false,
+        };
+        if !present {
+            conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {typedef};"))
+                .await?;
+            if *column == "last_heartbeat" {
+                conn.execute_batch(
+                    "UPDATE processing_instances SET last_heartbeat = registered_at \
+                     WHERE last_heartbeat = '';",
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for (i, m) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version as usize, i + 1, "migration {} out of order", m.name);
+        }
+        assert_eq!(LATEST_VERSION, MIGRATIONS.len() as u32);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_migrate_legacy_database() {
+        let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+        let conn = db.connect().unwrap();
+        // A pre-versioning database: no model columns, duplicate rows.
+        conn.execute_batch(
+            "CREATE TABLE detections (
+                Date DATE, Time TIME, Domain VARCHAR(50) NOT NULL DEFAULT 'birds',
+                Sci_Name VARCHAR(100) NOT NULL, Com_Name VARCHAR(100) NOT NULL,
+                Confidence FLOAT, Lat FLOAT, Lon FLOAT, Cutoff FLOAT, Week INT,
+                Sens FLOAT, Overlap FLOAT, File_Name VARCHAR(100) NOT NULL
+            );
+            INSERT INTO detections (Date, Time, Sci_Name, Com_Name, Confidence, File_Name) VALUES
+                ('2025-05-01', '06:00:00', 'Turdus merula', 'Blackbird', 0.9, 'a.wav'),
+                ('2025-05-01', '06:00:00', 'Turdus merula', 'Blackbird', 0.9, 'a.wav'),
+                ('2025-05-01', '06:00:03', 'Erithacus rubecula', 'Robin', 0.8, 'a.wav');",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(migrate(&conn).await.unwrap(), LATEST_VERSION);
+        // Idempotent.
+        assert_eq!(migrate(&conn).await.unwrap(), LATEST_VERSION);
+
+        assert_eq!(scalar(&conn, "SELECT COUNT(*) FROM detections").await, 2);
+        assert_eq!(scalar(&conn, "SELECT COUNT(*) FROM detection_species").await, 2);
+        assert_eq!(scalar(&conn, "SELECT COUNT(*) FROM detections WHERE Model_Slug = ''").await, 2);
+        // Ids are the old rowids; the duplicate (rowid 2) is gone.
+        assert_eq!(scalar(&conn, "SELECT MAX(id) FROM detections").await, 3);
+        // The pre-migration rows, duplicate included, are kept aside.
+        assert_eq!(scalar(&conn, "SELECT COUNT(*) FROM detections_v1").await, 3);
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn scalar(conn: &libsql::Connection, sql: &str) -> i64 {
+        let mut rows = conn.query(sql, ()).await.unwrap();
+        rows.next().await.unwrap().unwrap().get::<i64>(0).unwrap()
+    }
+}
@@ -0,0 +1,171 @@
+//! Clock synchronisation status, reported by the capture and processing
+//! health endpoints.
+//!
+//! Recording times come from the system clock, so a node that lost its
+//! NTP or GPS time source stamps every detection wrongly.  The status is
+//! read from `chronyc -c tracking` (chrony, which also serves GPS/PPS
+//! reference clocks) and otherwise from `timedatectl show`
+//! (systemd-timesyncd, which reports only whether the clock is
+//! synchronised).  Neither tool is required: without them the status is
+//! simply unknown.
+//!
+//! [`spawn_monitor`] checks every [`CHECK_INTERVAL`], logs when the clock
+//! starts or stops drifting, and keeps the result for [`latest`].
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use crate::protocol::TimeSyncStatus;
+
+/// How often the monitor thread re-reads the sync status.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+static LATEST: Mutex<Option<TimeSyncStatus>> = Mutex::new(None);
+
+/// The status from the monitor's last check; `None` before the first
+/// check or when no time-sync tool is available.
+pub fn latest() -> Option<TimeSyncStatus> {
+    LATEST.lock().ok().and_then(|s| s.clone())
+}
+
+/// Start the background check.  `max_offset_ms` is the offset above
+/// which the clock counts as drifting (`CLOCK_MAX_OFFSET_MS`).
+pub fn spawn_monitor(max_offset_ms: f64) -> std::io::Result<()> {
+    std::thread::Builder::new()
+        .name("timesync".into())
+        .spawn(move || {
+            let mut was_drifting = None;
+            loop {
+                let status = query(max_offset_ms);
+                match &status {
+                    Some(s) if was_drifting != Some(s.drifting) => {
+                        if s.drifting {
+                            warn!("Clock is not reliably synchronised: {}", s.describe());
+                        } else {
+                            info!("Clock synchronised: {}", s.describe());
+                        }
+                        was_drifting = Some(s.drifting);
+                    }
+                    None if was_drifting.is_none() => {
+                        info!("Clock sync status unavailable (neither chronyc nor timedatectl works here)");
+                        was_drifting = Some(false);
+                    }
+                    _ => {}
+                }
+                if let Ok(mut latest) = LATEST.lock() {
+                    *latest = status;
+                }
+                std::thread::sleep(CHECK_INTERVAL);
+            }
+        })
+        .map(|_| ())
+}
+
+/// Read the current status from chrony, then systemd-timesyncd.
+pub fn query(max_offset_ms: f64) -> Option<TimeSyncStatus> {
+    let run = |cmd: &str, args: &[&str]| -> Option<String> {
+        let output = Command::new(cmd).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let mut status = run("chronyc", &["-c", "tracking"])
+        .and_then(|out| parse_chrony_tracking(&out))
+        .or_else(|| run("timedatectl", &["show"]).and_then(|out| parse_timedatectl(&out)))?;
+    status.drifting =
+        !status.synchronized || status.offset_ms.is_some_and(|o| o.abs() > max_offset_ms);
+    Some(status)
+}
+
+/// Parse the CSV line of `chronyc -c tracking`:
+///
+/// ```text
+/// 47505300,GPS,1,1746080000.123456,0.000001234,...,Normal
+/// ```
+///
+/// Fields: reference ID, reference name, stratum, reference time (Unix
+/// seconds), system time offset (seconds), …, leap status (last).
+fn parse_chrony_tracking(stdout: &str) -> Option<TimeSyncStatus> {
+    let line = stdout.lines().find(|l| !l.trim().is_empty())?;
+    let cols: Vec<&str> = line.trim().split(',').collect();
+    if cols.len() < 14 {
+        return None;
+    }
+    let stratum: u32 = cols[2].parse().ok()?;
+    let offset_secs: f64 = cols[4].parse().ok()?;
+    let last_sync = cols[3]
+        .parse::<f64>()
+        .ok()
+        .filter(|t| *t > 0.0)
+        .and_then(|t| DateTime::<Utc>::from_timestamp(t as i64, 0))
+        .map(|t| t.to_rfc3339());
+    let leap = cols[cols.len() - 1];
+    let unsynced = cols[0].trim_start_matches('0').is_empty();
+    let synchronized = !unsynced && stratum < 16 && !leap.eq_ignore_ascii_case("not synchronised");
+    Some(TimeSyncStatus {
+        source: "chrony".into(),
+        synchronized,
+        offset_ms: Some(offset_secs * 1000.0),
+        stratum: Some(stratum),
+        reference: Some(cols[1].to_string()).filter(|r| !r.is_empty()),
+        last_sync,
+        drifting: false,
+    })
+}
+
+/// Parse `timedatectl show`, which only says whether the clock is
+/// synchronised (`NTPSynchronized=yes`).
+fn parse_timedatectl(stdout: &str) -> Option<TimeSyncStatus> {
+    let synced = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("NTPSynchronized="))?
+        .trim();
+    Some(TimeSyncStatus {
+        source: "timedatectl".into(),
+        synchronized: synced == "yes",
+        offset_ms: None,
+        stratum: None,
+        reference: None,
+        last_sync: None,
+        drifting: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chrony_tracking() {
+        let out = "C0A80101,ntp.local,3,1746080000.500000,0.000250000,-0.000010,0.000020,\
+                   -12.345,0.001,0.050,0.010000,0.000500,1025.5,Normal\n";
+        let s = parse_chrony_tracking(out).unwrap();
+        assert!(s.synchronized);
+        assert_eq!(s.stratum, Some(3));
+        assert_eq!(s.reference.as_deref(), Some("ntp.local"));
+        assert!((s.offset_ms.unwrap() - 0.25).abs() < 1e-9);
+        assert_eq!(s.last_sync.as_deref(), Some("2025-05-01T06:13:20+00:00"));
+
+        let unsynced = "00000000,,0,0.000000,0.000000000,0,0,0,0,0,0,0,0,Not synchronised\n";
+        let s = parse_chrony_tracking(unsynced).unwrap();
+        assert!(!s.synchronized);
+        assert_eq!(s.reference, None);
+        assert_eq!(s.last_sync, None);
+
+        assert!(parse_chrony_tracking("506 Cannot talk to daemon\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_timedatectl() {
+        let out = "Timezone=Europe/Madrid\nNTP=yes\nNTPSynchronized=no\n";
+        let s = parse_timedatectl(out).unwrap();
+        assert_eq!(s.source, "timedatectl");
+        assert!(!s.synchronized);
+        assert!(parse_timedatectl("Timezone=UTC\n").is_none());
+    }
+}
@@ -0,0 +1,302 @@
+//! Capture timestamps embedded in WAV files.
+//!
+//! The capture server stamps each finished segment with a Broadcast Wave
+//! `bext` chunk carrying the segment's start time (to the sample, via
+//! `TimeReference`) and the capturing node.  Unlike the strftime
+//! filename or the file mtime, the stamp survives renames and copies,
+//! and [`crate::detection::ParsedFileName::parse`] prefers it.
+//!
+//! Only the fields we use are filled in; the rest of the 602-byte EBU
+//! Tech 3285 layout is zeroed so other tools still read the file.
+
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// Fixed part of a `bext` chunk (version 1, no coding history).
+const BEXT_LEN: usize = 602;
+const DESCRIPTION: usize = 0; // 256 bytes
+const ORIGINATOR: usize = 256; // 32 bytes
+const ORIGINATOR_REF: usize = 288; // 32 bytes
+const ORIGINATION_DATE: usize = 320; // 10 bytes, yyyy-mm-dd
+const ORIGINATION_TIME: usize = 330; // 8 bytes, hh:mm:ss
+const TIME_REFERENCE: usize = 338; // u64 LE, samples since midnight
+const VERSION: usize = 346; // u16 LE
+
+/// Value written to `OriginatorReference` so we can tell our stamps
+/// apart from bext chunks written by other recorders.
+const ORIGINATOR_TAG: &str = "gaia-capture";
+
+/// How far into the file to look for the stamp; `bext` sits right
+/// after `fmt `, well inside this.
+const HEADER_SCAN_BYTES: u64 = 64 * 1024;
+
+/// When and where a recording was captured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureStamp {
+    /// Local wall-clock time of the first sample.
+    pub start: NaiveDateTime,
+    /// Capture node that recorded the file (e.g. `capture-01`).
+    pub node: String,
+}
+
+struct Layout {
+    sample_rate: u32,
+    block_align: u16,
+    /// Offset and declared size of the `data` payload.
+    data: Option<(usize, usize)>,
+    /// Byte offset just past the `fmt ` chunk.
+    fmt_end: usize,
+    /// Offset of an existing `bext` payload, if any.
+    bext: Option<(usize, usize)>,
+}
+
+/// Walk the RIFF chunks in `raw` up to the `data` chunk.
+fn layout(raw: &[u8]) -> Option<Layout> {
+    if raw.len() < 12 || &raw[0..4] != b"RIFF" || &raw[8..12] != b"WAVE" {
+        return None;
+    }
+    let mut pos = 12usize;
+    let mut sample_rate = None;
+    let mut block_align = 0;
+    let mut fmt_end = None;
+    let mut bext = None;
+    let mut data = None;
+    while pos + 8 <= raw.len() {
+        let id = &raw[pos..pos + 4];
+        let size = u32::from_le_bytes(raw[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = pos + 8;
+        if id == b"data" {
+            data = Some((body, size));
+            break;
+        }
+        if id == b"fmt " && size >= 16 && body + 14 <= raw.len() {
+            sample_rate = Some(u32::from_le_bytes(raw[body + 4..body + 8].try_into().ok()?));
+            block_align = u16::from_le_bytes(raw[body + 12..body + 14].try_into().ok()?);
+            fmt_end = Some(body + size + size % 2);
+        } else if id == b"bext" && body + size <= raw.len() {
+            bext = Some((body, size));
+        }
+        pos = body.checked_add(size + size % 2)?;
+    }
+    Some(Layout {
+        sample_rate: sample_rate?,
+        block_align,
+        data,
+        fmt_end: fmt_end?,
+        bext,
+    })
+}
+
+/// Length of the audio in `raw`, in seconds.  Trusts the bytes actually
+/// present over a `data` size that was never finalised.
+fn duration_secs(raw: &[u8]) -> Option<f64> {
+    let layout = layout(raw)?;
+    let (offset, declared) = layout.data?;
+    let bytes = declared.min(raw.len().saturating_sub(offset));
+    if layout.block_align == 0 || layout.sample_rate == 0 {
+        return None;
+    }
+    Some((bytes / layout.block_align as usize) as f64 / layout.sample_rate as f64)
+}
+
+fn fixed_str(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).trim().to_string()
+}
+
+fn put_str(buf: &mut [u8], offset: usize, len: usize, value: &str) {
+    let bytes = value.as_bytes();
+    let n = bytes.len().min(len);
+    buf[offset..offset + n].copy_from_slice(&bytes[..n]);
+}
+
+/// Decode a stamp from a `bext` payload written by [`stamp_bytes`].
+fn parse_bext(payload: &[u8], sample_rate: u32) -> Option<CaptureStamp> {
+    if payload.len() < VERSION + 2
+        || fixed_str(&payload[ORIGINATOR_REF..ORIGINATOR_REF + 32]) != ORIGINATOR_TAG
+    {
+        return None;
+    }
+    let date = NaiveDate::parse_from_str(
+        &fixed_str(&payload[ORIGINATION_DATE..ORIGINATION_DATE + 10]),
+        "%Y-%m-%d",
+    )
+    .ok()?;
+    let samples = u64::from_le_bytes(payload[TIME_REFERENCE..TIME_REFERENCE + 8].try_into().ok()?);
+    let time = if samples > 0 && sample_rate > 0 {
+        let nanos = (samples as u128 * 1_000_000_000 / sample_rate as u128) as u64;
+        NaiveTime::from_num_seconds_from_midnight_opt(
+            (nanos / 1_000_000_000) as u32,
+            (nanos % 1_000_000_000) as u32,
+        )?
+    } else {
+        NaiveTime::parse_from_str(
+            &fixed_str(&payload[ORIGINATION_TIME..ORIGINATION_TIME + 8]),
+            "%H:%M:%S",
+        )
+        .ok()?
+    };
+    Some(CaptureStamp {
+        start: NaiveDateTime::new(date, time),
+        node: fixed_str(&payload[ORIGINATOR..ORIGINATOR + 32]),
+    })
+}
+
+/// Read the capture stamp of `path`, if it has one.
+pub fn read_capture_stamp(path: &Path) -> Option<CaptureStamp> {
+    let mut head = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(HEADER_SCAN_BYTES)
+        .read_to_end(&mut head)
+        .ok()?;
+    let layout = layout(&head)?;
+    let (body, size) = layout.bext?;
+    parse_bext(&head[body..body + size], layout.sample_rate)
+}
+
+/// Return a copy of the WAV bytes in `raw` with a `bext` stamp inserted
+/// after the `fmt ` chunk.
+fn stamp_bytes(raw: &[u8], stamp: &CaptureStamp) -> Result<Vec<u8>> {
+    let Some(layout) = layout(raw) else {
+        bail!("not a RIFF/WAVE file with a fmt chunk");
+    };
+    if layout.bext.is_some() {
+        bail!("file already has a bext chunk");
+    }
+
+    let mut bext = vec![0u8; BEXT_LEN];
+    let start = stamp.start;
+    put_str(
+        &mut bext,
+        DESCRIPTION,
+        256,
+        &format!("start={} node={}", start.format("%Y-%m-%dT%H:%M:%S%.3f"), stamp.node),
+    );
+    put_str(&mut bext, ORIGINATOR, 32, &stamp.node);
+    put_str(&mut bext, ORIGINATOR_REF, 32, ORIGINATOR_TAG);
+    put_str(&mut bext, ORIGINATION_DATE, 10, &start.format("%Y-%m-%d").to_string());
+    put_str(&mut bext, ORIGINATION_TIME, 8, &start.format("%H:%M:%S").to_string());
+    let since_midnight = start.num_seconds_from_midnight() as u128 * 1_000_000_000
+        + start.nanosecond().min(999_999_999) as u128;
+    let samples = (since_midnight * layout.sample_rate as u128 / 1_000_000_000) as u64;
+    bext[TIME_REFERENCE..TIME_REFERENCE + 8].copy_from_slice(&samples.to_le_bytes());
+    bext[VERSION..VERSION + 2].copy_from_slice(&1u16.to_le_bytes());
+
+    let mut out = Vec::with_capacity(raw.len() + 8 + BEXT_LEN);
+    out.extend_from_slice(&raw[..layout.fmt_end]);
+    out.extend_from_slice(b"bext");
+    out.extend_from_slice(&(BEXT_LEN as u32).to_le_bytes());
+    out.extend_from_slice(&bext);
+    out.extend_from_slice(&raw[layout.fmt_end..]);
+    let riff_size = u32::try_from(out.len() - 8).unwrap_or(u32::MAX);
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Ok(out)
+}
+
+/// Insert a capture stamp into the WAV at `path` (atomically, via a
+/// temporary file in the same directory).
+pub fn write_capture_stamp(path: &Path, stamp: &CaptureStamp) -> Result<()> {
+    let raw = std::fs::read(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    write_stamped(path, &raw, stamp)
+}
+
+/// Stamp a just-finished capture segment.  The start time is the last
+/// write (the segment's end) minus the audio length, which is finer
+/// than the whole second in the strftime filename.  The original mtime
+/// is restored afterwards.  Returns the stamp, or `None` if the file
+/// already carries one.
+pub fn stamp_finished_segment(path: &Path, node: &str) -> Result<Option<CaptureStamp>> {
+    let raw = std::fs::read(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    if layout(&raw).is_some_and(|l| l.bext.is_some()) {
+        return Ok(None);
+    }
+    let Some(secs) = duration_secs(&raw) else {
+        bail!("{}: cannot determine audio length", path.display());
+    };
+    let modified = std::fs::metadata(path)?.modified()?;
+    let ended: chrono::DateTime<chrono::Local> = modified.into();
+    let stamp = CaptureStamp {
+        start: ended.naive_local() - chrono::Duration::milliseconds((secs * 1000.0).round() as i64),
+        node: node.to_string(),
+    };
+    write_stamped(path, &raw, &stamp)?;
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|f| f.set_modified(modified))
+        .with_context(|| format!("Cannot restore mtime of {}", path.display()))?;
+    Ok(Some(stamp))
+}
+
+fn write_stamped(path: &Path, raw: &[u8], stamp: &CaptureStamp) -> Result<()> {
+    let stamped = stamp_bytes(raw, stamp).with_context(|| format!("Cannot stamp {}", path.display()))?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{name}.stamp.tmp"));
+    std::fs::write(&tmp, stamped).with_context(|| format!("Cannot write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Cannot replace {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes() -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 48_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for i in 0..4800 {
+            writer.write_sample((i % 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_stamp_round_trip() {
+        let stamp = CaptureStamp {
+            start: NaiveDate::from_ymd_opt(2026, 5, 1)
+                .unwrap()
+                .and_hms_milli_opt(5, 30, 12, 250)
+                .unwrap(),
+            node: "capture-01".into(),
+        };
+        let stamped = stamp_bytes(&wav_bytes(), &stamp).unwrap();
+        assert!(stamp_bytes(&stamped, &stamp).is_err(), "double stamping");
+
+        let layout = layout(&stamped).unwrap();
+        let (body, size) = layout.bext.unwrap();
+        assert_eq!(parse_bext(&stamped[body..body + size], 48_000), Some(stamp));
+
+        // The audio is untouched and still readable.
+        assert_eq!(duration_secs(&stamped), Some(0.1));
+        let reader = hound::WavReader::new(std::io::Cursor::new(stamped)).unwrap();
+        assert_eq!(reader.len(), 4800);
+    }
+
+    #[test]
+    fn test_unstamped_file_has_no_stamp() {
+        let dir = std::env::temp_dir().join(format!("gaia-wavmeta-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2026-05-01-birdnet-05:30:12.wav");
+        std::fs::write(&path, wav_bytes()).unwrap();
+        assert_eq!(read_capture_stamp(&path), None);
+
+        let stamp = CaptureStamp {
+            start: NaiveDate::from_ymd_opt(2026, 5, 1).unwrap().and_hms_opt(5, 30, 11).unwrap(),
+            node: "capture-02".into(),
+        };
+        write_capture_stamp(&path, &stamp).unwrap();
+        assert_eq!(read_capture_stamp(&path), Some(stamp));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
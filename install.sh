@@ -54,11 +54,15 @@ info "Using: $COMPOSE_CMD"
 # ── Create directory structure ───────────────────────────────────────────────
 info "Installing to ${INSTALL_DIR}"
 mkdir -p "${INSTALL_DIR}"/{models/birds,data/extracted,backups}
+# Capture node identity, filled in on first start.  Must exist before
+# compose bind-mounts it, or a directory is created in its place.
+touch "${INSTALL_DIR}/node_id"
 
 step "Created directories:"
 echo "  ${INSTALL_DIR}/"
 echo "  ├── models/birds/"
 echo "  ├── data/extracted/"
+echo "  ├── node_id"
 echo "  └── backups/"
 
 # ── gaia.conf ────────────────────────────────────────────────────────────────
@@ -103,6 +107,9 @@ DATABASE_LANG=en
 # RTSP streams (comma-separated, leave empty for local mic)
 # RTSP_STREAMS=rtsp://cam1:554/stream
 
+# Raw PCM over UDP/RTP (hydrophones, ultrasonic front-ends)
+# UDP_LISTEN=0.0.0.0:5004,format=s16le,rate=192000
+
 # Network
 # With mDNS enabled the processing node discovers capture nodes
 # automatically.  This URL is only used as a fallback if mDNS
@@ -113,6 +120,13 @@ DATABASE_LANG=en
 CAPTURE_LISTEN_ADDR=0.0.0.0:8089
 CAPTURE_SERVER_URL=http://localhost:8089
 POLL_INTERVAL_SECS=5
+# Shared secret required by the capture API (recommended on shared
+# networks; generate with: openssl rand -hex 32)
+# CAPTURE_AUTH_TOKEN=
+# Serve the capture API over HTTPS (PEM files, paths inside the container)
+# CAPTURE_TLS_CERT=/etc/gaia/capture.crt
+# CAPTURE_TLS_KEY=/etc/gaia/capture.key
+# CAPTURE_TLS_CA=/etc/gaia/capture.crt
 
 # Optional integrations
 # BIRDWEATHER_ID=
@@ -210,6 +224,7 @@ services:
     # privileged: true   # uncomment if group_add alone is not enough
     volumes:
       - ./gaia.conf:/etc/gaia/gaia.conf:ro
+      - ./node_id:/etc/gaia/node_id
       - /proc/asound:/run/asound:ro
 
   # ── Model inference & analysis ──────────────────────────────────────
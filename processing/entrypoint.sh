@@ -13,7 +13,7 @@ MODELS_DIR="/models"
 DIRECT_SUBCOMMAND="${1:-}"
 SKIP_STARTUP_TAXONOMY_REVIEW=0
 case "$DIRECT_SUBCOMMAND" in
-    review-taxonomy|validate-model|validate-manifests)
+    review-taxonomy|validate-model|validate-manifests|validate-models)
         SKIP_STARTUP_TAXONOMY_REVIEW=1
         ;;
 esac
@@ -440,6 +440,8 @@ pub fn is_gpu_requested() -> bool {
 /// Container validation exercises this path for BirdNET V3/Perch.
 pub struct OrtSession {
     session: ort::session::Session,
+    /// GPU backend the session was built for (`None` = CPU only).
+    accel: AccelKind,
 }
 
 impl OrtSession {
@@ -628,6 +630,7 @@ impl OrtSession {
 
         Ok(Self {
             session,
+            accel: kind,
         })
     }
 
@@ -698,9 +701,15 @@ impl OrtSession {
 
         Ok(Self {
             session,
+            accel: AccelKind::None,
         })
     }
 
+    /// GPU backend the session was built for (`None` = CPU only).
+    pub fn accel(&self) -> AccelKind {
+        self.accel
+    }
+
     /// Run inference on a batch of f32 input data.
     ///
     /// `input_data` is the flattened tensor; `input_shape` is its
@@ -713,6 +722,18 @@ impl OrtSession {
         input_shape: Vec<usize>,
         output_index: usize,
     ) -> Result<Vec<f32>> {
+        let mut outputs = self.predict_outputs(input_data, input_shape, &[output_index])?;
+        Ok(outputs.swap_remove(0))
+    }
+
+    /// Run inference once and read several output tensors, in the order
+    /// of `output_indices` (e.g. predictions plus embeddings).
+    pub fn predict_outputs(
+        &mut self,
+        input_data: Vec<f32>,
+        input_shape: Vec<usize>,
+        output_indices: &[usize],
+    ) -> Result<Vec<Vec<f32>>> {
         let session = &mut self.session;
         let input_name = session.inputs()[0].name().to_string();
 
@@ -725,16 +746,19 @@ impl OrtSession {
             .run(ort::inputs![input_name => input_tensor])
             .context("ORT inference failed")?;
 
-        anyhow::ensure!(
-            output_index < outputs.len(),
-            "Model has {} outputs but prediction_output_index = {output_index}",
-            outputs.len()
-        );
-
-        let (_shape, data) = outputs[output_index]
-            .try_extract_tensor::<f32>()
-            .context("Cannot extract f32 output tensor")?;
-
-        Ok(data.to_vec())
+        output_indices
+            .iter()
+            .map(|&output_index| {
+                anyhow::ensure!(
+                    output_index < outputs.len(),
+                    "Model has {} outputs but output index {output_index} was requested",
+                    outputs.len()
+                );
+                let (_shape, data) = outputs[output_index]
+                    .try_extract_tensor::<f32>()
+                    .context("Cannot extract f32 output tensor")?;
+                Ok(data.to_vec())
+            })
+            .collect()
     }
 }
@@ -0,0 +1,215 @@
+//! What the pipeline is doing right now — the recording each worker is
+//! on, how far the current model has got and how long the queues are —
+//! for the dashboard's "System Activity" widget.
+//!
+//! Workers and download threads update an in-memory
+//! [`PipelineStatus`]; the writer thread started by [`spawn_writer`]
+//! saves it to `<data>/pipeline_status.json` whenever it changes (at most
+//! once a second) and at least every [`HEARTBEAT`], so an idle pipeline
+//! can be told apart from a stopped one.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+
+use gaia_common::protocol::{PipelineStatus, WorkerActivity};
+
+/// Longest time between two writes of the status file.
+pub const HEARTBEAT: Duration = Duration::from_secs(10);
+
+static WORKERS: Mutex<Vec<WorkerActivity>> = Mutex::new(Vec::new());
+static DOWNLOAD_QUEUE: AtomicUsize = AtomicUsize::new(0);
+static ANALYSIS_QUEUE: AtomicUsize = AtomicUsize::new(0);
+static FILES_ANALYSED: AtomicU64 = AtomicU64::new(0);
+/// Bumped on every change, so the writer knows when to save.
+static VERSION: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Worker id of the current thread, set by [`register_worker`].
+    static WORKER: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// The queues whose depth is reported.
+#[derive(Debug, Clone, Copy)]
+pub enum Queue {
+    /// Dispatched, waiting for a download thread.
+    Download,
+    /// Downloaded, waiting for an analysis worker.
+    Analysis,
+}
+
+impl Queue {
+    fn counter(self) -> &'static AtomicUsize {
+        match self {
+            Queue::Download => &DOWNLOAD_QUEUE,
+            Queue::Analysis => &ANALYSIS_QUEUE,
+        }
+    }
+}
+
+pub fn enqueue(queue: Queue) {
+    queue.counter().fetch_add(1, Ordering::Relaxed);
+    VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn dequeue(queue: Queue) {
+    let _ = queue
+        .counter()
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Mark the calling thread as analysis worker `id`.
+pub fn register_worker(id: usize) {
+    WORKER.set(Some(id));
+    if let Ok(mut workers) = WORKERS.lock() {
+        if !workers.iter().any(|w| w.worker == id) {
+            workers.push(WorkerActivity {
+                worker: id,
+                ..WorkerActivity::default()
+            });
+            workers.sort_by_key(|w| w.worker);
+        }
+    }
+    VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The calling worker started on `file`.
+pub fn begin_file(file: &str, source_node: &str) {
+    update_worker(|w| {
+        *w = WorkerActivity {
+            worker: w.worker,
+            file: Some(file.to_string()),
+            source_node: source_node.to_string(),
+            ..WorkerActivity::default()
+        };
+    });
+}
+
+/// The calling worker is running `model` over `chunks` chunks.
+pub fn begin_model(model: &str, chunks: usize) {
+    update_worker(|w| {
+        w.model = Some(model.to_string());
+        w.chunk = 0;
+        w.chunks = chunks;
+    });
+}
+
+/// The calling worker has scored `chunk` chunks of the current model.
+pub fn chunk_done(chunk: usize) {
+    update_worker(|w| w.chunk = chunk);
+}
+
+/// The calling worker finished its recording and is idle again.
+pub fn end_file() {
+    update_worker(|w| {
+        *w = WorkerActivity {
+            worker: w.worker,
+            ..WorkerActivity::default()
+        };
+    });
+    FILES_ANALYSED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Ignored on threads that are not registered workers.
+fn update_worker(f: impl FnOnce(&mut WorkerActivity)) {
+    let Some(id) = WORKER.get() else {
+        return;
+    };
+    if let Ok(mut workers) = WORKERS.lock() {
+        if let Some(w) = workers.iter_mut().find(|w| w.worker == id) {
+            f(w);
+            VERSION.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The current state, stamped with the current time.
+pub fn snapshot() -> PipelineStatus {
+    let (chunk_cache_hits, chunk_cache_misses) = crate::chunk_cache::stats();
+    PipelineStatus {
+        updated_at: chrono::Utc::now().to_rfc3339(),
+        workers: WORKERS.lock().map(|w| w.clone()).unwrap_or_default(),
+        download_queue: DOWNLOAD_QUEUE.load(Ordering::Relaxed),
+        analysis_queue: ANALYSIS_QUEUE.load(Ordering::Relaxed),
+        files_analysed: FILES_ANALYSED.load(Ordering::Relaxed),
+        node_backlog: crate::client::node_backlog(),
+        extraction_paused: crate::disk_guard::is_paused(),
+        chunk_cache_hits,
+        chunk_cache_misses,
+    }
+}
+
+/// Start the thread keeping `<data>/pipeline_status.json` up to date.
+pub fn spawn_writer(shutdown: &'static AtomicBool) -> Result<JoinHandle<()>> {
+    std::thread::Builder::new()
+        .name("activity".into())
+        .spawn(move || {
+            let mut written_version = u64::MAX;
+            let mut written_at = Instant::now();
+            while !shutdown.load(Ordering::Relaxed) {
+                let version = VERSION.load(Ordering::Relaxed);
+                if version != written_version || written_at.elapsed() >= HEARTBEAT {
+                    if let Err(e) = write_status(&snapshot()) {
+                        warn!("Cannot write pipeline status: {e:#}");
+                    }
+                    written_version = version;
+                    written_at = Instant::now();
+                }
+                std::thread::sleep(Duration::from_secs(1));
+            }
+        })
+        .context("Cannot spawn activity thread")
+}
+
+fn write_status(status: &PipelineStatus) -> Result<()> {
+    let dir = crate::live_status::live_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("pipeline_status.json");
+    let tmp = dir.join("pipeline_status.json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec(status)?)?;
+    std::fs::rename(&tmp, &path)?;
+    debug!("Pipeline status updated: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_activity() {
+        // Unregistered threads (download, reporting) are ignored.
+        begin_file("ignored.wav", "node");
+
+        std::thread::spawn(|| {
+            register_worker(41);
+            begin_file("2026-05-01-birdnet-08:00:00.wav", "garden");
+            begin_model("BirdNET V2.4", 20);
+            chunk_done(5);
+
+            let status = snapshot();
+            let w = status.workers.iter().find(|w| w.worker == 41).unwrap();
+            assert_eq!(w.file.as_deref(), Some("2026-05-01-birdnet-08:00:00.wav"));
+            assert_eq!(w.source_node, "garden");
+            assert_eq!(w.model.as_deref(), Some("BirdNET V2.4"));
+            assert_eq!((w.chunk, w.chunks), (5, 20));
+
+            end_file();
+            let status = snapshot();
+            let w = status.workers.iter().find(|w| w.worker == 41).unwrap();
+            assert!(w.file.is_none() && w.model.is_none());
+            assert!(status.files_analysed >= 1);
+        })
+        .join()
+        .unwrap();
+
+        let status = snapshot();
+        assert!(status.workers.iter().all(|w| w.file.as_deref() != Some("ignored.wav")));
+    }
+}
@@ -13,12 +13,15 @@ use tracing::{debug, info, warn};
 
 use gaia_common::audio;
 use gaia_common::config::Config;
-use gaia_common::detection::{normalize_sci_name, Detection, ParsedFileName};
+use gaia_common::detection::{normalize_sci_name, Detection, ParsedFileName, TopPrediction};
 
+use crate::direction;
 use crate::live_status::{self, LivePrediction};
 use crate::model::{self, LoadedModel, Prediction};
 use crate::agreement::{self, ModelWeight};
+use crate::ensemble::{self, EnsembleMode};
 use crate::taxonomy;
+use crate::trigger::{self, Interest};
 use crate::ReportPayload;
 
 fn is_one_shot_test_mode() -> bool {
@@ -53,21 +56,86 @@ fn should_merge_shared_csv_names(model: &LoadedModel) -> bool {
     model.csv_common_names().len() <= 8_000
 }
 
-/// Process a single WAV file through all loaded models.
+/// Move a recording that failed [`audio::verify_wav`] to
+/// `<recs_dir>/corrupt/` and append the reason to `corrupt/corrupt.log`.
+fn quarantine(file_path: &Path, recs_dir: &Path, defect: &audio::WavDefect) {
+    let dir = recs_dir.join("corrupt");
+    let name = file_path.file_name().unwrap_or_default();
+    warn!("Quarantining {}: {defect}", file_path.display());
+
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        warn!("Cannot create {}: {e} — discarding {}", dir.display(), file_path.display());
+        std::fs::remove_file(file_path).ok();
+        return;
+    }
+    let dest = dir.join(name);
+    // rename() fails across filesystems (tmp dir on tmpfs); fall back to copy.
+    let moved = std::fs::rename(file_path, &dest).is_ok()
+        || (std::fs::copy(file_path, &dest).is_ok() && std::fs::remove_file(file_path).is_ok());
+    if !moved {
+        warn!("Cannot move {} to {}", file_path.display(), dest.display());
+        std::fs::remove_file(file_path).ok();
+    }
+
+    let log_path = dir.join("corrupt.log");
+    let line = format!(
+        "{}\t{}\t{defect}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        name.to_string_lossy()
+    );
+    if let Err(e) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .and_then(|mut f| {
+            use std::io::Write;
+            f.write_all(line.as_bytes())
+        })
+    {
+        warn!("Cannot write to log {}: {e}", log_path.display());
+    }
+}
+
+/// Process a single WAV file through all loaded models.  Returns what it
+/// found, so the worker can decide whether a ring-buffer segment is kept.
 pub fn process_file(
     file_path: &Path,
     models: &mut [LoadedModel],
     config: &Config,
     report_tx: &std::sync::mpsc::SyncSender<ReportPayload>,
     source_node: &str,
-) -> Result<()> {
+    source_node_id: &str,
+    expected_secs: Option<f64>,
+) -> Result<Interest> {
     let started = Instant::now();
 
     // Skip empty files
     let meta = std::fs::metadata(file_path)?;
     if meta.len() == 0 {
         std::fs::remove_file(file_path).ok();
-        return Ok(());
+        return Ok(Interest::default());
+    }
+
+    // Reject corrupt/truncated WAVs before they reach the models.
+    let is_wav = file_path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if is_wav {
+        match audio::verify_wav(file_path, expected_secs) {
+            Ok(stats) => debug!(
+                "WAV ok: {} ({} Hz, {} ch, {:.1}s, peak {:.2}, {:.2}% clipped)",
+                file_path.display(),
+                stats.sample_rate,
+                stats.channels,
+                stats.duration_secs,
+                stats.peak,
+                stats.clipped_fraction * 100.0
+            ),
+            Err(defect) => {
+                quarantine(file_path, &config.recs_dir, &defect);
+                return Ok(Interest::default());
+            }
+        }
     }
 
     info!("Analysing {}", file_path.display());
@@ -75,10 +143,6 @@ pub fn process_file(
     let file = ParsedFileName::parse(file_path)
         .with_context(|| format!("Cannot parse filename: {}", file_path.display()))?;
 
-    let mut all_detections = Vec::new();
-    // Collect the top raw predictions across models for the live feed.
-    let mut live_predictions: Vec<LivePrediction> = Vec::new();
-
     // Read the set of enabled models from Redis (managed in Settings).
     // In one-shot e2e mode we skip Redis lookups entirely so the worker
     // cannot stall on coordination I/O; the test should exercise the
@@ -92,6 +156,126 @@ pub fn process_file(
         Some(v) => v,
         None => config.model_slugs.clone(),
     };
+    let channel_mode = config.channel_mode_for(source_node);
+    let Analysed {
+        detections: mut all_detections,
+        mut live_predictions,
+        mut audio_cache,
+    } = analyse(&file, models, config, &enabled, channel_mode)?;
+
+    // Bearings come from the delays between all channels of the
+    // recording, whatever the channel mode.
+    if let Some(positions) = config.mic_positions_for(source_node) {
+        match direction::MicArray::parse(positions) {
+            Some(array) => {
+                if let Err(e) = direction::annotate(file_path, &array, &mut all_detections) {
+                    warn!(
+                        "Cannot estimate bearings for {}: {e:#}",
+                        file_path.display()
+                    );
+                }
+            }
+            None => warn!("Ignoring invalid microphone positions for {source_node}: {positions:?}"),
+        }
+    }
+
+    // ── Update live analysis status ──────────────────────────────────
+    // Read a short chunk of audio at 24 kHz for the live spectrogram.
+    let live_sr = 24_000u32;
+    {
+        match audio_cache.chunks(live_sr, 3.0, 0.0) {
+            Ok(chunks) => {
+                let samples: Vec<f32> = chunks.into_iter().flatten().collect();
+                // Keep only the top 5 predictions by confidence.
+                live_predictions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+                live_predictions.truncate(5);
+                let captured_at = file.file_date.format("%Y-%m-%dT%H:%M:%S").to_string();
+                live_status::update(
+                    &file.file_path.file_name().unwrap_or_default().to_string_lossy(),
+                    &samples,
+                    live_sr,
+                    live_predictions,
+                    config.confidence,
+                    &config.colormap,
+                    source_node,
+                    &captured_at,
+                );
+            }
+            Err(e) => {
+                warn!("Cannot read audio for live spectrogram: {e:#}");
+            }
+        }
+    }
+
+    let detection_count = all_detections.len();
+    // Same decoded signal as the live spectrogram.
+    let burst_db = audio_cache
+        .signal(live_sr)
+        .map(|signal| trigger::burst_db(signal, live_sr))
+        .unwrap_or(0.0);
+    let ultrasonic_slugs = models
+        .iter()
+        .filter(|m| m.manifest.manifest.model.ultrasonic)
+        .map(|m| m.manifest.slug())
+        .collect();
+
+    report_tx
+        .send(ReportPayload {
+            file,
+            detections: all_detections,
+            source_node: source_node.to_string(),
+            source_node_id: source_node_id.to_string(),
+            ultrasonic_slugs,
+            location: config.location(),
+        })
+        .map_err(|_| anyhow::anyhow!("Reporting channel closed"))?;
+
+    let elapsed = started.elapsed();
+    // How many times faster than real time the recording was analysed
+    // by all models together; below 1 the pipeline falls behind.
+    let speed = audio_cache
+        .duration_secs()
+        .map(|d| format!(", {:.1}x real time", d / elapsed.as_secs_f64().max(1e-3)))
+        .unwrap_or_default();
+    info!(
+        "Analysis complete: {} (detections={}, elapsed={:.2}s{speed})",
+        file_path.display(),
+        detection_count,
+        elapsed.as_secs_f64()
+    );
+
+    Ok(Interest {
+        detections: detection_count,
+        burst_db,
+    })
+}
+
+/// Detections of the enabled models in one recording, before reporting.
+pub struct Analysed {
+    pub detections: Vec<Detection>,
+    /// Top raw predictions across models, for the live feed.
+    pub live_predictions: Vec<LivePrediction>,
+    /// The decoded recording, reused for the live spectrogram.
+    pub audio_cache: audio::AudioCache,
+}
+
+/// Run the models selected by `enabled` (all of them when empty) over
+/// `file` and score their agreement.  Unlike [`process_file`] this has no
+/// side effects, so the CLI can use it on arbitrary recordings.
+///
+/// With [`audio::ChannelMode::Separate`] each channel of a multi-channel
+/// WAV is analysed on its own and its detections carry the channel.
+pub fn analyse(
+    file: &ParsedFileName,
+    models: &mut [LoadedModel],
+    config: &Config,
+    enabled: &[String],
+    channels: audio::ChannelMode,
+) -> Result<Analysed> {
+    let mut all_detections = Vec::new();
+    // Collect the top raw predictions across models for the live feed.
+    let mut live_predictions: Vec<LivePrediction> = Vec::new();
+
     let all_enabled = enabled.is_empty();
 
     // ── shared species-range data ────────────────────────────────────
@@ -121,7 +305,7 @@ pub fn process_file(
     // proper common names from models that have one (e.g. BirdNET).
     let mut shared_common_names: HashMap<String, String> = HashMap::new();
     for model in models.iter_mut() {
-        if !all_enabled && !crate::manifest::slug_is_selected(&model.manifest.slug(), &enabled) {
+        if !all_enabled && !crate::manifest::slug_is_selected(&model.manifest.slug(), enabled) {
             continue;
         }
         let preload_name = model.manifest.manifest.model.name.clone();
@@ -189,22 +373,75 @@ pub fn process_file(
 
     trace_analysis_step("shared analysis context ready");
 
-    for model in models.iter_mut() {
-        if !all_enabled && !crate::manifest::slug_is_selected(&model.manifest.slug(), &enabled) {
+    // With ENSEMBLE_MODE set, enabled models of the same domain are
+    // merged per chunk instead of each producing its own detections.
+    let ensemble_mode = EnsembleMode::parse(&config.ensemble_mode);
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, model) in models.iter().enumerate() {
+        if !all_enabled && !crate::manifest::slug_is_selected(&model.manifest.slug(), enabled) {
             debug!("Skipping disabled model: {}", model.manifest.manifest.model.name);
             continue;
         }
-        info!(
-            "Running analysis with model: {}",
-            model.manifest.manifest.model.name
-        );
-        let (detections, top_preds) = run_analysis(
-            &file, model, config,
-            &shared_species_range, &known_bird_labels,
-            &shared_common_names,
-        )?;
-        all_detections.extend(detections);
-        live_predictions.extend(top_preds);
+        let same_domain = ensemble_mode.and_then(|_| {
+            groups.iter_mut().find(|g| models[g[0]].domain() == model.domain())
+        });
+        match same_domain {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    let mut ensemble_weights: Vec<ModelWeight> = Vec::new();
+    if ensemble_mode.is_some() {
+        for group in groups.iter_mut().filter(|g| g.len() > 1) {
+            // The most trusted member leads: its chunk grid, class
+            // map and species-range model are used for the merge.
+            group.sort_by(|&a, &b| {
+                models[b].manifest.manifest.model.trust_weight
+                    .total_cmp(&models[a].manifest.manifest.model.trust_weight)
+            });
+            ensemble_weights.push(ModelWeight {
+                slug: ensemble_source(models, group).slug,
+                trust_weight: group
+                    .iter()
+                    .map(|&i| models[i].manifest.manifest.model.trust_weight)
+                    .sum(),
+            });
+        }
+    }
+    let shared = SharedContext {
+        species_range: &shared_species_range,
+        bird_labels: &known_bird_labels,
+        common_names: &shared_common_names,
+    };
+    // Models sharing a sample rate decode and resample the file once.
+    let mut audio_cache = audio::AudioCache::new(&file.file_path);
+
+    let split = match channels {
+        audio::ChannelMode::Separate => audio::channel_count(&file.file_path).filter(|&n| n > 1),
+        audio::ChannelMode::Mix => None,
+    };
+    match split {
+        Some(n) => {
+            info!("Analysing the {n} channels of {} separately", file.file_path.display());
+            for channel in 0..n {
+                let mut channel_cache = audio::AudioCache::for_channel(&file.file_path, channel);
+                let (mut detections, top_preds) = run_groups(
+                    file, &mut channel_cache, models, &groups, ensemble_mode, config, &shared,
+                )?;
+                for d in &mut detections {
+                    d.channel = Some(channel);
+                }
+                all_detections.extend(detections);
+                live_predictions.extend(top_preds);
+            }
+        }
+        None => {
+            let (detections, top_preds) = run_groups(
+                file, &mut audio_cache, models, &groups, ensemble_mode, config, &shared,
+            )?;
+            all_detections.extend(detections);
+            live_predictions.extend(top_preds);
+        }
     }
 
     // ── Cross-model agreement scoring ────────────────────────────────
@@ -214,62 +451,67 @@ pub fn process_file(
     {
         let model_weights: Vec<ModelWeight> = models
             .iter()
-            .filter(|m| all_enabled || crate::manifest::slug_is_selected(&m.manifest.slug(), &enabled))
+            .filter(|m| all_enabled || crate::manifest::slug_is_selected(&m.manifest.slug(), enabled))
             .map(|m| ModelWeight {
                 slug: m.manifest.slug(),
                 trust_weight: m.manifest.manifest.model.trust_weight,
             })
+            .chain(ensemble_weights)
             .collect();
         agreement::score_agreement(&mut all_detections, &model_weights);
     }
 
-    // ── Update live analysis status ──────────────────────────────────
-    // Read a short chunk of audio at 24 kHz for the live spectrogram.
-    {
-        let live_sr = 24_000u32;
-        match gaia_common::audio::read_audio(file_path, live_sr, 3.0, 0.0) {
-            Ok(chunks) => {
-                let samples: Vec<f32> = chunks.into_iter().flatten().collect();
-                // Keep only the top 5 predictions by confidence.
-                live_predictions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
-                live_predictions.truncate(5);
-                let captured_at = file.file_date.format("%Y-%m-%dT%H:%M:%S").to_string();
-                live_status::update(
-                    &file.file_path.file_name().unwrap_or_default().to_string_lossy(),
-                    &samples,
-                    live_sr,
-                    live_predictions,
-                    config.confidence,
-                    &config.colormap,
-                    source_node,
-                    &captured_at,
+    Ok(Analysed {
+        detections: all_detections,
+        live_predictions,
+        audio_cache,
+    })
+}
+
+/// Species-range, bird-label and common-name data shared by all models.
+struct SharedContext<'a> {
+    species_range: &'a [String],
+    bird_labels: &'a HashSet<String>,
+    common_names: &'a HashMap<String, String>,
+}
+
+/// Run every model group over the signal in `audio_cache`: a single
+/// model on its own, several through the ensemble merge.
+fn run_groups(
+    file: &ParsedFileName,
+    audio_cache: &mut audio::AudioCache,
+    models: &mut [LoadedModel],
+    groups: &[Vec<usize>],
+    ensemble_mode: Option<EnsembleMode>,
+    config: &Config,
+    shared: &SharedContext,
+) -> Result<(Vec<Detection>, Vec<LivePrediction>)> {
+    let mut all_detections = Vec::new();
+    let mut live_predictions = Vec::new();
+    for group in groups {
+        let (detections, top_preds) = match ensemble_mode {
+            Some(mode) if group.len() > 1 => run_ensemble(
+                file, audio_cache, models, group, mode, config,
+                shared.species_range, shared.bird_labels,
+                shared.common_names,
+            )?,
+            _ => {
+                let model = &mut models[group[0]];
+                info!(
+                    "Running analysis with model: {}",
+                    model.manifest.manifest.model.name
                 );
+                run_analysis(
+                    file, audio_cache, model, config,
+                    shared.species_range, shared.bird_labels,
+                    shared.common_names,
+                )?
             }
-            Err(e) => {
-                warn!("Cannot read audio for live spectrogram: {e:#}");
-            }
-        }
+        };
+        all_detections.extend(detections);
+        live_predictions.extend(top_preds);
     }
-
-    let detection_count = all_detections.len();
-
-    report_tx
-        .send(ReportPayload {
-            file,
-            detections: all_detections,
-            source_node: source_node.to_string(),
-        })
-        .map_err(|_| anyhow::anyhow!("Reporting channel closed"))?;
-
-    let elapsed = started.elapsed();
-    info!(
-        "Analysis complete: {} (detections={}, elapsed={:.2}s)",
-        file_path.display(),
-        detection_count,
-        elapsed.as_secs_f64()
-    );
-
-    Ok(())
+    Ok((all_detections, live_predictions))
 }
 
 /// Core analysis logic for a single model.
@@ -290,79 +532,171 @@ pub fn process_file(
 /// falling back to the scientific name.
 fn run_analysis(
     file: &ParsedFileName,
+    audio_cache: &mut audio::AudioCache,
     model: &mut LoadedModel,
     config: &Config,
     shared_species_range: &[String],
     known_bird_labels: &HashSet<String>,
     shared_common_names: &HashMap<String, String>,
 ) -> Result<(Vec<Detection>, Vec<LivePrediction>)> {
-    let domain = model.domain().to_string();
-    let class_map = model.csv_classes().clone();
-    let model_slug = model.manifest.slug();
-    let model_name = model.manifest.manifest.model.name.clone();
-    // Tag for log messages: "BirdNET V2.4/birds" or "Google Perch 2.0/wildlife"
-    let tag = format!("{model_name}/{domain}");
+    let source = DetectionSource::of(model);
+    let Some((labeled, head_labeled)) = predict_chunks(file, audio_cache, model, config)? else {
+        return Ok((vec![], vec![]));
+    };
+    let (mut detections, top_preds) = detections_from_chunks(
+        file, model, config, labeled, &source,
+        shared_species_range, known_bird_labels, shared_common_names,
+    )?;
+    detections.extend(detections_from_head(file, model, config, &head_labeled, &source));
+    Ok((detections, top_preds))
+}
 
-    // ── custom species lists ─────────────────────────────────────────
-    let base = std::env::var("GAIA_DIR").unwrap_or_else(|_| "/app".to_string());
-    let include_list =
-        model::load_species_list(Path::new(&base).join("include_species_list.txt").as_path());
-    let exclude_list =
-        model::load_species_list(Path::new(&base).join("exclude_species_list.txt").as_path());
-    let mut whitelist =
-        model::load_species_list(Path::new(&base).join("whitelist_species_list.txt").as_path());
+/// Which model (or ensemble) a set of detections is attributed to.
+struct DetectionSource {
+    slug: String,
+    name: String,
+    beta: bool,
+    version: String,
+    variant: String,
+    runtime: String,
+}
 
-    // Merge in Redis-based exclusion overrides (species confirmed via the
-    // web UI by an ornithologist).  These bypass the occurrence threshold
-    // just like the file-based whitelist.
-    let db_overrides = if is_one_shot_test_mode() {
-        Vec::new()
-    } else {
-        crate::kv::load_exclusion_overrides()
-    };
-    for sp in db_overrides {
-        if !whitelist.contains(&sp) {
-            whitelist.push(sp);
+impl DetectionSource {
+    fn of(model: &LoadedModel) -> Self {
+        DetectionSource {
+            slug: model.manifest.slug(),
+            name: model.manifest.manifest.model.name.clone(),
+            beta: model.manifest.manifest.model.beta,
+            version: model.manifest.version(),
+            variant: model.manifest.variant.clone().unwrap_or_default(),
+            runtime: model.runtime().to_string(),
         }
     }
 
-    // ── language map ─────────────────────────────────────────────────
-    let mut names =
-        model::load_language(&model.manifest.language_dir(), &config.database_lang)
-            .unwrap_or_default();
-    // Fallback: when no language JSON exists (e.g. BirdNET+ V3.0), use
-    // common names parsed from the CSV labels file.
-    if names.is_empty() {
-        names = model.csv_common_names().clone();
+    /// Stamp `det` with the model that produced it.
+    fn tag(&self, det: &mut Detection) {
+        det.model_slug = self.slug.clone();
+        det.model_name = self.name.clone();
+        det.model_beta = self.beta;
+        det.model_version = self.version.clone();
+        det.model_variant = self.variant.clone();
+        det.model_runtime = self.runtime.clone();
     }
-    // Merge in shared common names from other models.  This ensures that
-    // models without their own language file (e.g. Perch) still show
-    // "Keel-billed Toucan" instead of "Ramphastos sulfuratus" as the
-    // common name.  Existing names take priority — only fill gaps.
-    for (sci, com) in shared_common_names {
-        names.entry(sci.clone()).or_insert_with(|| com.clone());
+}
+
+/// Ensemble the models at `members` (all of one domain) and run the
+/// filters once on the merged scores.  `members[0]` supplies the
+/// labels' class map, species-range model and time grid.
+#[allow(clippy::too_many_arguments)]
+fn run_ensemble(
+    file: &ParsedFileName,
+    audio_cache: &mut audio::AudioCache,
+    models: &mut [LoadedModel],
+    members: &[usize],
+    mode: EnsembleMode,
+    config: &Config,
+    shared_species_range: &[String],
+    known_bird_labels: &HashSet<String>,
+    shared_common_names: &HashMap<String, String>,
+) -> Result<(Vec<Detection>, Vec<LivePrediction>)> {
+    let mut scores = Vec::with_capacity(members.len());
+    // Classifier heads are not ensembled: each member's head reports
+    // its local classes on its own.
+    let mut head_detections = Vec::new();
+    for &i in members {
+        let model = &mut models[i];
+        info!("Running analysis with model: {} (ensemble member)", model.manifest.manifest.model.name);
+        if let Some((chunks, head_chunks)) = predict_chunks(file, audio_cache, model, config)? {
+            let source = DetectionSource::of(model);
+            head_detections.extend(detections_from_head(file, model, config, &head_chunks, &source));
+            scores.push(ensemble::MemberScores {
+                weight: model.manifest.manifest.model.trust_weight,
+                chunks,
+            });
+        }
+    }
+    if scores.is_empty() {
+        return Ok((head_detections, vec![]));
+    }
+    let merged = ensemble::merge(&scores, mode);
+    let source = ensemble_source(models, members);
+    let (mut detections, top_preds) = detections_from_chunks(
+        file, &mut models[members[0]], config, merged, &source,
+        shared_species_range, known_bird_labels, shared_common_names,
+    )?;
+    detections.extend(head_detections);
+    Ok((detections, top_preds))
+}
+
+fn ensemble_source(models: &[LoadedModel], members: &[usize]) -> DetectionSource {
+    let slugs: Vec<String> = members.iter().map(|&i| models[i].manifest.slug()).collect();
+    let names: Vec<&str> = members
+        .iter()
+        .map(|&i| models[i].manifest.manifest.model.name.as_str())
+        .collect();
+    // Provenance of each member, in member order: "2.4+2.0".
+    let joined = |field: fn(&LoadedModel) -> String| {
+        let parts: Vec<String> = members.iter().map(|&i| field(&models[i])).collect();
+        if parts.iter().all(String::is_empty) {
+            String::new()
+        } else {
+            parts.join("+")
+        }
+    };
+    DetectionSource {
+        slug: format!("ensemble-{}", slugs.join("-")),
+        name: format!("Ensemble ({})", names.join(" + ")),
+        beta: members.iter().all(|&i| models[i].manifest.manifest.model.beta),
+        version: joined(|m| m.manifest.version()),
+        variant: joined(|m| m.manifest.variant.clone().unwrap_or_default()),
+        runtime: joined(|m| m.runtime().to_string()),
     }
+}
+
+/// Read the file at the model's sample rate (from `audio_cache`, so
+/// models sharing a rate decode it once) and score every chunk.
+/// Returns `None` when the audio cannot be read; otherwise the base
+/// model's chunks and its classifier head's (empty without a head).
+fn predict_chunks(
+    file: &ParsedFileName,
+    audio_cache: &mut audio::AudioCache,
+    model: &mut LoadedModel,
+    config: &Config,
+) -> Result<Option<(Vec<ensemble::Chunk>, Vec<ensemble::Chunk>)>> {
+    let domain = model.domain().to_string();
+    let tag = format!("{}/{domain}", model.manifest.manifest.model.name);
 
     // ── read audio ───────────────────────────────────────────────────
     trace_analysis_step(format!("[{tag}] read-audio start path={}", file.file_path.display()));
-    let chunks = match audio::read_audio(
-        &file.file_path,
+    let chunks = match audio_cache.chunks_with(
         model.sample_rate(),
-        model.chunk_duration(),
+        model.chunking(),
         config.overlap,
+        model.pre_filter(),
     ) {
         Ok(c) => c,
         Err(e) => {
             tracing::error!("[{tag}] Error reading audio: {e}");
-            return Ok((vec![], vec![]));
+            return Ok(None);
         }
     };
     trace_analysis_step(format!("[{tag}] read-audio done chunks={}", chunks.len()));
+    crate::activity::begin_model(&model.manifest.manifest.model.name, chunks.len());
+    // Variable-length models get chunks of different lengths.
+    let durations: Vec<f64> = chunks
+        .iter()
+        .map(|c| c.len() as f64 / model.sample_rate() as f64)
+        .collect();
 
     // ── run inference on each chunk ──────────────────────────────────
     let mut raw_detections: Vec<Vec<Prediction>> = Vec::with_capacity(chunks.len());
+    let mut head_scores: Vec<Vec<Prediction>> = Vec::new();
     for (i, chunk) in chunks.iter().enumerate() {
-        let preds = model.predict(chunk, config.latitude, config.longitude, file.week())?;
+        let (preds, head_preds) =
+            model.predict_with_head(chunk, config.latitude, config.longitude, file.week())?;
+        if model.head_domain().is_some() {
+            head_scores.push(head_preds);
+        }
         // Log top-3 raw scores so operators can tell whether the model
         // produces meaningful output.
         if let Some(top) = preds.first() {
@@ -385,6 +719,7 @@ fn run_analysis(
             }
         }
         raw_detections.push(preds);
+        crate::activity::chunk_done(i + 1);
     }
 
     // ── filter human speech (birds models only) ──────────────────────
@@ -395,12 +730,144 @@ fn run_analysis(
     };
 
     // ── assemble time-labeled detections ─────────────────────────────
-    let mut labeled: Vec<(f64, f64, Vec<Prediction>)> = Vec::new();
-    let mut pred_start = 0.0_f64;
-    for preds in &filtered {
-        let pred_end = pred_start + model.chunk_duration();
-        labeled.push((pred_start, pred_end, preds.clone()));
-        pred_start = pred_end - config.overlap;
+    let label_times = |chunks: Vec<Vec<Prediction>>| {
+        let mut labeled: Vec<ensemble::Chunk> = Vec::new();
+        let mut pred_start = 0.0_f64;
+        for (preds, duration) in chunks.into_iter().zip(&durations) {
+            let pred_end = pred_start + duration;
+            labeled.push((pred_start, pred_end, preds));
+            pred_start = pred_end - config.overlap;
+        }
+        labeled
+    };
+    Ok(Some((label_times(filtered), label_times(head_scores))))
+}
+
+/// The first `k` of a chunk's scores (sorted, highest first) for a
+/// detection's `TOP_PREDICTIONS`.
+fn top_predictions(
+    entries: &[Prediction],
+    k: usize,
+    common_name: impl Fn(&str) -> String,
+) -> Vec<TopPrediction> {
+    entries
+        .iter()
+        .take(k)
+        .map(|(label, confidence)| TopPrediction {
+            scientific_name: taxonomy::canonical_species_name(&normalize_sci_name(label)),
+            common_name: common_name(label),
+            confidence: (confidence * 10000.0).round() / 10000.0,
+        })
+        .collect()
+}
+
+/// Detections from the model's classifier head, tagged with the head's
+/// domain.  The head's classes are trained on local recordings, so the
+/// species-range and taxonomy filters (which do not know them) are
+/// skipped; only the confidence threshold and exclude list apply.
+fn detections_from_head(
+    file: &ParsedFileName,
+    model: &LoadedModel,
+    config: &Config,
+    labeled: &[ensemble::Chunk],
+    source: &DetectionSource,
+) -> Vec<Detection> {
+    let (Some(domain), Some(names)) = (model.head_domain(), model.head_common_names()) else {
+        return Vec::new();
+    };
+    let exclude_list = model::load_named_species_list("exclude_species_list.txt", config);
+
+    let common_name = |label: &str| {
+        names
+            .get(label)
+            .cloned()
+            .unwrap_or_else(|| label.to_string())
+    };
+
+    let mut detections = Vec::new();
+    for (start, end, entries) in labeled {
+        for (label, confidence) in entries {
+            if *confidence < config.confidence {
+                // Entries are sorted, nothing further passes either.
+                break;
+            }
+            if exclude_list.iter().any(|s| normalize_sci_name(s) == *label) {
+                continue;
+            }
+            let mut det = Detection::new(
+                domain, file.file_date, *start, *end, label, &common_name(label), *confidence,
+            );
+            det.top_predictions = top_predictions(entries, config.top_predictions, common_name);
+            source.tag(&mut det);
+            detections.push(det);
+        }
+    }
+    if !detections.is_empty() {
+        info!(
+            "[{}/{domain}] {}: {} classifier-head detection(s)",
+            source.name,
+            file.file_path.display(),
+            detections.len()
+        );
+    }
+    detections
+}
+
+/// Apply the confidence threshold and species filters to scored chunks.
+/// `model` provides the class map, names and species-range model;
+/// `source` is what the detections are attributed to.
+#[allow(clippy::too_many_arguments)]
+fn detections_from_chunks(
+    file: &ParsedFileName,
+    model: &mut LoadedModel,
+    config: &Config,
+    labeled: Vec<ensemble::Chunk>,
+    source: &DetectionSource,
+    shared_species_range: &[String],
+    known_bird_labels: &HashSet<String>,
+    shared_common_names: &HashMap<String, String>,
+) -> Result<(Vec<Detection>, Vec<LivePrediction>)> {
+    let domain = model.domain().to_string();
+    let class_map = model.csv_classes().clone();
+    let model_slug = source.slug.clone();
+    let model_name = source.name.clone();
+    // Tag for log messages: "BirdNET V2.4/birds" or "Google Perch 2.0/wildlife"
+    let tag = format!("{model_name}/{domain}");
+
+    // ── custom species lists ─────────────────────────────────────────
+    let include_list = model::load_named_species_list("include_species_list.txt", config);
+    let exclude_list = model::load_named_species_list("exclude_species_list.txt", config);
+    let mut whitelist = model::load_named_species_list("whitelist_species_list.txt", config);
+
+    // Merge in Redis-based exclusion overrides (species confirmed via the
+    // web UI by an ornithologist).  These bypass the occurrence threshold
+    // just like the file-based whitelist.
+    let db_overrides = if is_one_shot_test_mode() {
+        Vec::new()
+    } else {
+        crate::kv::load_exclusion_overrides()
+    };
+    for sp in db_overrides {
+        if !whitelist.contains(&sp) {
+            whitelist.push(sp);
+        }
+    }
+
+    // ── language map ─────────────────────────────────────────────────
+    let mut names =
+        model::load_language(&model.manifest.language_dir(), &config.database_lang)
+            .unwrap_or_default();
+    // Fallback: when no language JSON exists (e.g. BirdNET+ V3.0), use
+    // common names parsed from the CSV labels file.
+    if names.is_empty() {
+        names = model.csv_common_names().clone();
+    }
+    // Merge in shared common names from other models.  This ensures that
+    // models without their own language file (e.g. Perch) still show
+    // "Keel-billed Toucan" instead of "Ramphastos sulfuratus" as the
+    // common name.  Existing names take priority — only fill gaps.
+    for (sci, com) in shared_common_names {
+        names.entry(sci.clone()).or_insert_with(|| com.clone());
     }
 
     // ── species-range model (location-based filtering) ──────────────
@@ -488,6 +955,16 @@ fn run_analysis(
 
     // ── apply confidence threshold + species filters ─────────────────
 
+    let common_name = |sci_name: &str| {
+        let sci_norm = normalize_sci_name(sci_name);
+        names
+            .get(sci_name)
+            .or_else(|| names.get(sci_norm.as_str()))
+            .or_else(|| names.get(taxonomy::canonical_species_name(&sci_norm).as_str()))
+            .cloned()
+            .unwrap_or_else(|| sci_name.to_string())
+    };
+
     let mut confident_detections = Vec::new();
     for (start, end, entries) in &labeled {
         if let Some((sci_name, confidence)) = entries.first() {
@@ -505,12 +982,7 @@ fn run_analysis(
             let sci_norm = normalize_sci_name(sci_name);
             let sci_canonical = taxonomy::canonical_species_name(&sci_norm);
 
-            let com_name = names
-                .get(sci_name.as_str())
-                .or_else(|| names.get(sci_norm.as_str()))
-                .or_else(|| names.get(sci_canonical.as_str()))
-                .cloned()
-                .unwrap_or_else(|| sci_name.clone());
+            let com_name = common_name(sci_name);
 
             if !include_set.is_empty() && !include_set.contains(sci_canonical.as_str()) {
                 warn!("[{tag}] Excluded (not in include list): {sci_name}");
@@ -611,9 +1083,8 @@ fn run_analysis(
                 *confidence,
             );
             det.excluded = excluded;
-            det.model_slug = model_slug.clone();
-            det.model_name = model_name.clone();
-            det.model_beta = model.manifest.manifest.model.beta;
+            det.top_predictions = top_predictions(entries, config.top_predictions, common_name);
+            source.tag(&mut det);
             confident_detections.push(det);
         }
     }
@@ -0,0 +1,369 @@
+//! Optional REST API for headless installations.
+//!
+//! Enabled by setting `PROCESSING_API_ADDR`; lets home-automation
+//! systems consume detections without running gaia-web.  Apart from
+//! recording uploads (see [`crate::upload`]) and folder imports (see
+//! [`crate::folder_import`]) it is read-only.
+//!
+//! Routes:
+//!   GET /api/health                → [`ProcessingHealth`]
+//!   GET /api/models                → loaded models, with enabled state
+//!   GET /api/pipeline              → [`PipelineStatus`]: what each worker is analysing
+//!   GET /api/detections?since=…    → stored detections, oldest first
+//!   POST /api/upload               → push a WAV segment for analysis
+//!   GET/POST /api/import           → list import folders / import one
+//!
+//! `since` is either a detection id (exclusive) or a `YYYY-MM-DD` /
+//! `YYYY-MM-DDTHH:MM:SS` timestamp.  Without it the most recent
+//! `limit` detections are returned.  Optional filters: `species`
+//! (scientific name), `model` (slug), `min_confidence`.
+//!
+//! The processing binary is synchronous, so the server runs on its own
+//! thread with a small Tokio runtime; DuckDB queries run on the blocking
+//! pool against the Parquet files the reporting thread writes.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::get;
+use axum::Router;
+use tower_http::cors::CorsLayer;
+use tracing::{info, warn};
+
+use gaia_common::protocol::{
+    DetectionQuery, DetectionRecord, ModelStatus, PipelineStatus, ProcessingHealth,
+};
+
+/// Upper bound for `limit` on `/api/detections`.
+const MAX_LIMIT: u32 = 5000;
+
+#[derive(Clone)]
+struct ApiState {
+    det_dir: PathBuf,
+    models: Arc<Vec<ModelStatus>>,
+    start_time: Instant,
+}
+
+/// Start the API on a dedicated thread.  Returns once the listener is
+/// bound, so a bad address fails startup instead of being logged later.
+/// `writes` are the upload and folder import routes, when enabled.
+pub fn spawn(
+    addr: &str,
+    det_dir: PathBuf,
+    models: Vec<ModelStatus>,
+    writes: Option<Router>,
+    shutdown: &'static AtomicBool,
+) -> Result<std::thread::JoinHandle<()>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Cannot create API runtime")?;
+    let listener = runtime
+        .block_on(tokio::net::TcpListener::bind(addr))
+        .with_context(|| format!("Cannot bind processing API to {addr}"))?;
+    info!("Processing REST API listening on {addr}");
+
+    let state = ApiState {
+        det_dir,
+        models: Arc::new(models),
+        start_time: Instant::now(),
+    };
+    let mut app = Router::new()
+        .route("/api/health", get(health))
+        .route("/api/models", get(models_handler))
+        .route("/api/pipeline", get(pipeline))
+        .route("/api/detections", get(detections))
+        .with_state(state);
+    if let Some(writes) = writes {
+        app = app.merge(writes);
+    }
+    let app = app.layer(CorsLayer::permissive());
+
+    std::thread::Builder::new()
+        .name("api".into())
+        .spawn(move || {
+            let served = runtime.block_on(
+                axum::serve(listener, app).with_graceful_shutdown(async move {
+                    while !shutdown.load(Ordering::Relaxed) {
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }),
+            );
+            if let Err(e) = served {
+                warn!("Processing API stopped: {e}");
+            }
+        })
+        .context("Cannot spawn API thread")
+}
+
+// ── route handlers ───────────────────────────────────────────────────────
+
+async fn health(State(state): State<ApiState>) -> Json<ProcessingHealth> {
+    let dir = state.det_dir.clone();
+    let parquet_files = tokio::task::spawn_blocking(move || parquet_files(&dir).len())
+        .await
+        .unwrap_or(0);
+    let extraction_paused = crate::disk_guard::is_paused();
+    Json(ProcessingHealth {
+        status: if extraction_paused { "disk_full" } else { "ok" }.to_string(),
+        uptime_secs: state.start_time.elapsed().as_secs(),
+        models_loaded: state.models.len(),
+        parquet_files,
+        buffered_detections: crate::parquet_store::buffered_count(),
+        node_backlog: crate::client::node_backlog(),
+        extraction_paused,
+        time_sync: gaia_common::timesync::latest(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol: gaia_common::protocol::PROTOCOL_VERSION,
+    })
+}
+
+async fn pipeline() -> Json<PipelineStatus> {
+    Json(crate::activity::snapshot())
+}
+
+async fn models_handler(State(state): State<ApiState>) -> Json<Vec<ModelStatus>> {
+    let enabled = tokio::task::spawn_blocking(crate::kv::get_enabled_models_state)
+        .await
+        .ok()
+        .flatten();
+    let models = state
+        .models
+        .iter()
+        .cloned()
+        .map(|mut m| {
+            // Missing key = legacy "everything on"; an empty set means
+            // the operator switched every model off.
+            m.enabled = match &enabled {
+                None => true,
+                Some(slugs) => !slugs.is_empty() && crate::manifest::slug_is_selected(&m.slug, slugs),
+            };
+            m
+        })
+        .collect();
+    Json(models)
+}
+
+async fn detections(
+    State(state): State<ApiState>,
+    Query(q): Query<DetectionQuery>,
+) -> Result<Json<Vec<DetectionRecord>>, (StatusCode, String)> {
+    let dir = state.det_dir.clone();
+    tokio::task::spawn_blocking(move || query_detections(&dir, &q))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+}
+
+// ── query ────────────────────────────────────────────────────────────────
+
+fn parquet_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|x| x == "parquet"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Translate `since` into a WHERE fragment plus its bind values.
+fn since_clause(since: &str) -> Result<(String, Vec<String>), String> {
+    let since = since.trim();
+    if let Ok(id) = since.parse::<i64>() {
+        return Ok((format!("id > {id}"), vec![]));
+    }
+    if let Ok(d) = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(("Date >= ?".into(), vec![d.format("%Y-%m-%d").to_string()]));
+    }
+    for fmt in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(since, fmt) {
+            let date = dt.format("%Y-%m-%d").to_string();
+            let time = dt.format("%H:%M:%S").to_string();
+            return Ok((
+                "(Date > ? OR (Date = ? AND Time > ?))".into(),
+                vec![date.clone(), date, time],
+            ));
+        }
+    }
+    Err(format!(
+        "invalid since={since:?}: expected a detection id, YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS"
+    ))
+}
+
+fn query_detections(
+    dir: &Path,
+    q: &DetectionQuery,
+) -> Result<Vec<DetectionRecord>, (StatusCode, String)> {
+    let bad_request = |e: String| (StatusCode::BAD_REQUEST, e);
+    let internal = |e: duckdb::Error| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    let files = parquet_files(dir);
+    if files.is_empty() {
+        return Ok(vec![]);
+    }
+    let limit = q.limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+
+    let mut filters = vec!["true".to_string()];
+    let mut binds: Vec<String> = Vec::new();
+    if let Some(since) = q.since.as_deref().filter(|s| !s.is_empty()) {
+        let (clause, values) = since_clause(since).map_err(bad_request)?;
+        filters.push(clause);
+        binds.extend(values);
+    }
+    if let Some(sp) = q.species.as_deref().filter(|s| !s.is_empty()) {
+        filters.push("Sci_Name = ?".into());
+        binds.push(sp.to_string());
+    }
+    if let Some(m) = q.model.as_deref().filter(|s| !s.is_empty()) {
+        filters.push("COALESCE(Model_Slug, '') = ?".into());
+        binds.push(m.to_string());
+    }
+    if let Some(c) = q.min_confidence {
+        filters.push(format!("Confidence >= {}", c.clamp(0.0, 1.0)));
+    }
+
+    // Paging forward from `since` wants the oldest matches; a bare
+    // request wants the newest.  Either way the result is oldest-first.
+    let order = if q.since.as_deref().is_some_and(|s| !s.is_empty()) { "ASC" } else { "DESC" };
+    let files_sql = files
+        .iter()
+        .map(|p| format!("'{}'", p.display().to_string().replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let conn = duckdb::Connection::open_in_memory().map_err(internal)?;
+    conn.execute_batch(&format!(
+        "CREATE VIEW d AS SELECT * FROM read_parquet([{files_sql}], union_by_name=true)"
+    ))
+    .map_err(internal)?;
+    // Batches written before model provenance was recorded lack these.
+    let present: Vec<String> = conn
+        .prepare("SELECT column_name FROM (DESCRIBE d)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<_, _>>()
+        })
+        .map_err(internal)?;
+    let optional = |column: &str| {
+        if present.iter().any(|c| c == column) {
+            format!("COALESCE({column}, '')")
+        } else {
+            "''".to_string()
+        }
+    };
+    // Added later still, by per-channel analysis.
+    let channel = if present.iter().any(|c| c == "Channel") {
+        "Channel"
+    } else {
+        "NULL::INTEGER"
+    };
+    let sql = format!(
+        "SELECT * FROM (\
+           SELECT id, Date, Time, COALESCE(Domain, ''), Sci_Name, Com_Name, Confidence, \
+                  COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
+                  COALESCE(Source_Node, ''), COALESCE(File_Name, ''), \
+                  COALESCE(Excluded, 0), COALESCE(Agreement_Score, 0.0), \
+                  {}, {}, {}, {} \
+           FROM d \
+           WHERE {} ORDER BY id {order} LIMIT {limit}\
+         ) ORDER BY id ASC",
+        optional("Model_Version"),
+        optional("Model_Variant"),
+        optional("Model_Runtime"),
+        channel,
+        filters.join(" AND ")
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(internal)?;
+    let rows = stmt
+        .query_map(duckdb::params_from_iter(binds.iter()), |row| {
+            Ok(DetectionRecord {
+                id: row.get(0)?,
+                date: row.get(1)?,
+                time: row.get(2)?,
+                domain: row.get(3)?,
+                scientific_name: row.get(4)?,
+                common_name: row.get(5)?,
+                confidence: row.get(6)?,
+                model_slug: row.get(7)?,
+                model_name: row.get(8)?,
+                model_version: row.get(13)?,
+                model_variant: row.get(14)?,
+                model_runtime: row.get(15)?,
+                channel: row
+                    .get::<_, Option<i32>>(16)?
+                    .and_then(|c| u16::try_from(c).ok()),
+                source_node: row.get(9)?,
+                file_name: row.get(10)?,
+                excluded: row.get::<_, i32>(11)? != 0,
+                agreement_score: row.get(12)?,
+            })
+        })
+        .map_err(internal)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since_clause() {
+        assert_eq!(since_clause("42").unwrap().0, "id > 42");
+        assert_eq!(since_clause("2026-04-17").unwrap().1, vec!["2026-04-17"]);
+        let (clause, binds) = since_clause("2026-04-17T05:30:00").unwrap();
+        assert!(clause.contains("Time > ?"));
+        assert_eq!(binds, vec!["2026-04-17", "2026-04-17", "05:30:00"]);
+        assert!(since_clause("yesterday'; DROP TABLE x").is_err());
+    }
+
+    #[test]
+    fn test_query_detections_pages_forward() {
+        let dir = std::env::temp_dir().join(format!("gaia-api-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "COPY (SELECT * FROM (VALUES \
+               (1::BIGINT, '2026-04-17', '05:00:00', 'birds', 'Turdus merula', 'Common Blackbird', 0.91, 'birdnet', 'BirdNET', 'node-a', 'a.wav', 0, 1.0), \
+               (2::BIGINT, '2026-04-17', '05:01:00', 'birds', 'Erithacus rubecula', 'European Robin', 0.55, 'birdnet', 'BirdNET', 'node-a', 'b.wav', 0, 1.0), \
+               (3::BIGINT, '2026-04-18', '06:00:00', 'birds', 'Turdus merula', 'Common Blackbird', 0.80, 'perch', 'Perch', 'node-b', 'c.wav', 1, 0.5)) \
+             AS t(id, Date, Time, Domain, Sci_Name, Com_Name, Confidence, Model_Slug, Model_Name, Source_Node, File_Name, Excluded, Agreement_Score)) \
+             TO '{}' (FORMAT PARQUET)",
+            dir.join("default-1.parquet").display()
+        ))
+        .unwrap();
+
+        let all = query_detections(&dir, &DetectionQuery::default()).unwrap();
+        assert_eq!(all.iter().map(|d| d.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        // Written before model provenance was recorded.
+        assert_eq!(all[0].model_version, "");
+
+        let q = DetectionQuery { since: Some("1".into()), limit: Some(1), ..Default::default() };
+        let next = query_detections(&dir, &q).unwrap();
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].scientific_name, "Erithacus rubecula");
+
+        let q = DetectionQuery {
+            species: Some("Turdus merula".into()),
+            min_confidence: Some(0.85),
+            ..Default::default()
+        };
+        assert_eq!(query_detections(&dir, &q).unwrap().len(), 1);
+
+        let q = DetectionQuery { since: Some("2026-04-18".into()), ..Default::default() };
+        let recent = query_detections(&dir, &q).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert!(recent[0].excluded);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
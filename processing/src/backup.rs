@@ -0,0 +1,345 @@
+//! Periodic snapshots of the Parquet detection store and `birds.db`.
+//!
+//! Every `BACKUP_INTERVAL_DAYS` the files in `detections/` are folded
+//! into one ZSTD-compressed Parquet file under `BACKUP_DIR`, next to a
+//! copy of `birds.db` (settings, overrides, caches) taken with `VACUUM
+//! INTO`, which is safe while the web server writes to it.  The oldest
+//! snapshots beyond `BACKUP_KEEP` are deleted, and the new files are
+//! optionally copied off the device (`BACKUP_REMOTE`, via `rsync` or the
+//! `aws` CLI).  Parquet batches are only ever replaced whole, by a
+//! rename (the web UI does this when a detection is relabelled), so each
+//! file read is consistent; batches written after the file list was
+//! taken wait for the next snapshot, and files compaction removes while
+//! the snapshot runs make it list the directory again.
+//!
+//! Part of the library target so `gaia-admin` can offer the manual
+//! `backup` and `restore` commands.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use tracing::{error, info};
+
+use gaia_common::config::Config;
+
+/// Snapshot filename prefix; the rest is a sortable UTC timestamp.
+pub const SNAPSHOT_PREFIX: &str = "gaia-detections-";
+
+/// Prefix of the `birds.db` copy taken with each snapshot; same timestamp.
+const DB_SNAPSHOT_PREFIX: &str = "gaia-db-";
+
+/// Attempts at reading the detection files when compaction removes some
+/// of them mid-snapshot.
+const SNAPSHOT_ATTEMPTS: u32 = 3;
+
+/// How often the loop checks whether a backup is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Snapshots in `dir`, oldest first.
+pub fn list_snapshots(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension().is_some_and(|x| x == "parquet")
+                        && p.file_name()
+                            .and_then(|n| n.to_str())
+                            .is_some_and(|n| n.starts_with(SNAPSHOT_PREFIX))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// The `birds.db` copy belonging to a detections snapshot.
+pub fn db_snapshot_for(snapshot: &Path) -> PathBuf {
+    let name = snapshot
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let stamp = name
+        .strip_prefix(SNAPSHOT_PREFIX)
+        .and_then(|n| n.strip_suffix(".parquet"))
+        .unwrap_or(name);
+    snapshot.with_file_name(format!("{DB_SNAPSHOT_PREFIX}{stamp}.db"))
+}
+
+/// `*.parquet` files directly under `dir`, sorted.
+fn parquet_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read {}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|x| x == "parquet"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Write one snapshot of every Parquet file in `det_dir` into
+/// `backup_dir`, plus a copy of the SQLite database at `db_path` when
+/// given and present.  Returns the detections file and its row count,
+/// or `None` when there is nothing to back up.
+pub fn snapshot(
+    det_dir: &Path,
+    db_path: Option<&Path>,
+    backup_dir: &Path,
+) -> Result<Option<(PathBuf, u64)>> {
+    if parquet_files(det_dir)?.is_empty() {
+        return Ok(None);
+    }
+
+    std::fs::create_dir_all(backup_dir)
+        .with_context(|| format!("Cannot create {}", backup_dir.display()))?;
+    let name = format!(
+        "{SNAPSHOT_PREFIX}{}.parquet",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let dest = backup_dir.join(&name);
+    let tmp = backup_dir.join(format!(".{name}.tmp"));
+
+    // Compaction may delete files between listing and reading them; its
+    // output is already in place by then, so list again and retry.
+    let mut attempt = 1;
+    let rows = loop {
+        let files = parquet_files(det_dir)?;
+        if files.is_empty() {
+            return Ok(None);
+        }
+        match copy_detections(&files, &tmp) {
+            Ok(rows) => break rows,
+            Err(e) if attempt < SNAPSHOT_ATTEMPTS && files.iter().any(|f| !f.exists()) => {
+                info!("Detection files changed during backup, retrying: {e:#}");
+                attempt += 1;
+            }
+            Err(e) => {
+                std::fs::remove_file(&tmp).ok();
+                return Err(e);
+            }
+        }
+    };
+
+    if let Some(db_path) = db_path.filter(|p| p.exists()) {
+        let db_dest = db_snapshot_for(&dest);
+        if let Err(e) = copy_database(db_path, &db_dest) {
+            std::fs::remove_file(&tmp).ok();
+            return Err(e);
+        }
+    }
+    std::fs::rename(&tmp, &dest).with_context(|| format!("Cannot rename {}", tmp.display()))?;
+    Ok(Some((dest, rows)))
+}
+
+/// Fold `files` into one Parquet file at `out`; returns the row count.
+fn copy_detections(files: &[PathBuf], out: &Path) -> Result<u64> {
+    let list = files
+        .iter()
+        .map(|p| format!("'{}'", p.display().to_string().replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+    let source = format!("read_parquet([{list}], union_by_name=true)");
+    conn.execute_batch(&format!(
+        "CREATE TEMP TABLE snapshot AS SELECT * FROM {source}; \
+         COPY snapshot TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD)",
+        out.display().to_string().replace('\'', "''")
+    ))
+    .context("Cannot write backup snapshot")?;
+    Ok(conn.query_row("SELECT COUNT(*) FROM snapshot", [], |r| r.get(0))?)
+}
+
+/// Copy the SQLite database at `db_path` to `dest` with `VACUUM INTO`,
+/// a consistent copy even while other connections write.
+fn copy_database(db_path: &Path, dest: &Path) -> Result<()> {
+    let tmp = dest.with_extension("db.tmp");
+    std::fs::remove_file(&tmp).ok();
+    let conn = crate::db::open_conn_pub(db_path)?;
+    crate::db::block_on(conn.execute("VACUUM INTO ?1", [tmp.display().to_string()]))
+        .with_context(|| format!("Cannot copy {}", db_path.display()))?;
+    std::fs::rename(&tmp, dest).with_context(|| format!("Cannot rename {}", tmp.display()))?;
+    Ok(())
+}
+
+/// Put the `birds.db` copy `db_snapshot` in place of `db_path`.  The
+/// current database and its WAL files are moved aside with a
+/// `.pre-restore-<ts>` suffix; returns the moved database's new path.
+pub fn restore_db(db_snapshot: &Path, db_path: &Path) -> Result<Option<PathBuf>> {
+    let suffix = format!("pre-restore-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+    let mut aside = None;
+    for ext in ["", "-wal", "-shm"] {
+        let current = PathBuf::from(format!("{}{ext}", db_path.display()));
+        if current.exists() {
+            let moved = PathBuf::from(format!("{}.{suffix}", current.display()));
+            std::fs::rename(&current, &moved)
+                .with_context(|| format!("Cannot move {}", current.display()))?;
+            if ext.is_empty() {
+                aside = Some(moved);
+            }
+        }
+    }
+    std::fs::copy(db_snapshot, db_path)
+        .with_context(|| format!("Cannot copy {}", db_snapshot.display()))?;
+    Ok(aside)
+}
+
+/// Delete all but the newest `keep` snapshots; returns how many went.
+pub fn rotate(backup_dir: &Path, keep: u32) -> Result<usize> {
+    let snapshots = list_snapshots(backup_dir);
+    let excess = snapshots.len().saturating_sub(keep.max(1) as usize);
+    for old in &snapshots[..excess] {
+        std::fs::remove_file(old).with_context(|| format!("Cannot remove {}", old.display()))?;
+        std::fs::remove_file(db_snapshot_for(old)).ok();
+    }
+    Ok(excess)
+}
+
+/// Copy `file` to an rsync destination or `s3://` URL.
+pub fn push_remote(file: &Path, remote: &str) -> Result<()> {
+    let mut cmd = if remote.starts_with("s3://") {
+        let mut c = std::process::Command::new("aws");
+        c.args(["s3", "cp", "--only-show-errors"]).arg(file).arg(remote);
+        c
+    } else {
+        let mut c = std::process::Command::new("rsync");
+        c.args(["-a", "--partial"]).arg(file).arg(remote);
+        c
+    };
+    let output = cmd
+        .output()
+        .with_context(|| format!("Cannot run {:?}", cmd.get_program()))?;
+    if !output.status.success() {
+        bail!(
+            "{:?} failed ({}): {}",
+            cmd.get_program(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Snapshot, rotate and (optionally) upload.  Returns the snapshot path.
+pub fn run_once(config: &Config, det_dir: &Path) -> Result<Option<PathBuf>> {
+    let Some((path, rows)) = snapshot(det_dir, Some(&config.db_path), &config.backup_dir)? else {
+        info!("Backup skipped: no detections yet");
+        return Ok(None);
+    };
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    info!(
+        "Backup written: {} ({rows} rows, {:.1} MB)",
+        path.display(),
+        size as f64 / 1_048_576.0
+    );
+
+    let removed = rotate(&config.backup_dir, config.backup_keep)?;
+    if removed > 0 {
+        info!("Backup rotation removed {removed} old snapshot(s)");
+    }
+
+    if let Some(remote) = &config.backup_remote {
+        push_remote(&path, remote).context("Remote backup copy failed")?;
+        let db_copy = db_snapshot_for(&path);
+        if db_copy.exists() {
+            push_remote(&db_copy, remote).context("Remote backup copy failed")?;
+        }
+        info!("Backup copied to {remote}");
+    }
+    Ok(Some(path))
+}
+
+/// Whether the newest snapshot is older than `interval` (or missing).
+fn backup_due(backup_dir: &Path, interval: Duration) -> bool {
+    let newest = list_snapshots(backup_dir)
+        .last()
+        .and_then(|p| p.metadata().ok())
+        .and_then(|m| m.modified().ok());
+    match newest {
+        Some(t) => SystemTime::now().duration_since(t).unwrap_or_default() >= interval,
+        None => true,
+    }
+}
+
+/// Background loop run by the processing server.
+pub fn backup_loop(config: Config, det_dir: PathBuf, shutdown: &AtomicBool) {
+    if config.backup_interval_days == 0 {
+        info!("Automatic backups disabled (BACKUP_INTERVAL_DAYS=0)");
+        return;
+    }
+    let interval = Duration::from_secs(u64::from(config.backup_interval_days) * 86_400);
+    info!(
+        "Backup thread started (every {}d, keep {}, dir={})",
+        config.backup_interval_days,
+        config.backup_keep,
+        config.backup_dir.display()
+    );
+
+    while !shutdown.load(Ordering::Relaxed) {
+        if backup_due(&config.backup_dir, interval) {
+            if let Err(e) = run_once(&config, &det_dir) {
+                error!("Backup failed: {e:#}");
+            }
+        }
+        let mut waited = Duration::ZERO;
+        while waited < CHECK_INTERVAL && !shutdown.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_secs(1));
+            waited += Duration::from_secs(1);
+        }
+    }
+    info!("Backup thread stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_and_rotate() {
+        let root = std::env::temp_dir().join(format!("gaia-backup-test-{}", std::process::id()));
+        let det_dir = root.join("detections");
+        let backup_dir = root.join("backups");
+        std::fs::create_dir_all(&det_dir).unwrap();
+
+        let db_path = root.join("birds.db");
+        assert!(snapshot(&det_dir, Some(&db_path), &backup_dir).unwrap().is_none());
+
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        for (i, sci) in ["Turdus merula", "Erithacus rubecula"].iter().enumerate() {
+            conn.execute_batch(&format!(
+                "COPY (SELECT {i}::BIGINT AS id, '{sci}' AS Sci_Name) TO '{}' (FORMAT PARQUET)",
+                det_dir.join(format!("default-{i}.parquet")).display()
+            ))
+            .unwrap();
+        }
+        let db = crate::db::open_conn_pub(&db_path).unwrap();
+        crate::db::block_on(db.execute_batch("CREATE TABLE kv (k TEXT); INSERT INTO kv VALUES ('a');"))
+            .unwrap();
+
+        let (path, rows) = snapshot(&det_dir, Some(&db_path), &backup_dir).unwrap().unwrap();
+        assert_eq!(rows, 2);
+        assert_eq!(list_snapshots(&backup_dir), vec![path.clone()]);
+        let db_copy = db_snapshot_for(&path);
+        assert!(db_copy.exists());
+
+        let aside = restore_db(&db_copy, &db_path).unwrap().unwrap();
+        assert!(aside.exists());
+        assert!(db_path.exists());
+
+        for stamp in ["20240101-000000", "20240108-000000"] {
+            std::fs::write(backup_dir.join(format!("{SNAPSHOT_PREFIX}{stamp}.parquet")), b"").unwrap();
+            std::fs::write(backup_dir.join(format!("{DB_SNAPSHOT_PREFIX}{stamp}.db")), b"").unwrap();
+        }
+        assert_eq!(rotate(&backup_dir, 2).unwrap(), 1);
+        assert!(!backup_dir.join(format!("{DB_SNAPSHOT_PREFIX}20240101-000000.db")).exists());
+        let left = list_snapshots(&backup_dir);
+        assert_eq!(left.len(), 2);
+        assert!(left[0].ends_with(format!("{SNAPSHOT_PREFIX}20240108-000000.parquet")));
+        assert!(!backup_due(&backup_dir, Duration::from_secs(3600)));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,590 @@
+//! `gaia-admin` – operator CLI for the Parquet detection store.
+//!
+//! Meant to be run over SSH (or `docker exec`) when the web UI is not
+//! reachable.  Works directly on the `detections/` directory next to
+//! `DB_PATH` and on the extracted clip tree, using the same `gaia.conf`
+//! as the processing server.
+//!
+//! Every command that rewrites data only touches the Parquet files it
+//! read at the start, so a processing container writing new batches at
+//! the same time is safe.  The web UI replaces a whole batch when a
+//! detection is relabelled; a rewrite that finds one of its files
+//! changed stops before removing anything.  Destructive commands are
+//! dry-runs unless `--yes` is given.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+
+use gaia_common::config::{self, Config};
+use gaia_common::detection::safe_path_component;
+use gaia_processing::{backup, detections_dir};
+use gaia_processing::export::{self, extension, file_list_sql, sql_str};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "gaia-admin",
+    version,
+    about = "Maintenance of the Gaia Audio detection store",
+    after_help = "Exports are `gaia-processing export`."
+)]
+struct Cli {
+    /// Config file [default: /etc/gaia/gaia.conf]
+    #[arg(short, long, global = true, env = "GAIA_CONFIG")]
+    config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Row / species / file counts
+    Stats,
+    /// Append detections from a .parquet or .csv file
+    Import { input: PathBuf },
+    /// Import rows from another install, skipping ones already present
+    Merge {
+        /// `detections/` directory of the other install
+        other: PathBuf,
+    },
+    /// Remove all detections of a species
+    DeleteSpecies {
+        sci_name: String,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Compact all Parquet files into one; the web UI rebuilds its
+    /// summaries when it sees the new file set
+    #[command(visible_alias = "compact")]
+    Recompute {
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Check every detection's clip exists
+    VerifyMedia {
+        #[arg(long)]
+        verbose: bool,
+    },
+    /// Write a snapshot to BACKUP_DIR now (rotates and uploads like the
+    /// automatic weekly backup)
+    Backup,
+    /// Replace the store and birds.db with a snapshot; current files are
+    /// moved aside
+    Restore {
+        /// Snapshot file, or `latest`
+        snapshot: String,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("error: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let config_path = cli
+        .config
+        .unwrap_or_else(|| PathBuf::from(Config::default_path()));
+    let config = config::load(&config_path).context("Config load failed")?;
+    let det_dir = detections_dir(&config);
+    let open = || Store::open(&det_dir);
+
+    match cli.command {
+        Command::Stats => open()?.stats(),
+        Command::Import { input } => open()?.import(&input),
+        Command::Merge { other } => open()?.merge(&other),
+        Command::DeleteSpecies { sci_name, yes } => open()?.delete_species(&sci_name, yes),
+        Command::Recompute { yes } => open()?.compact(yes),
+        Command::VerifyMedia { verbose } => open()?.verify_media(&config.extracted_dir, verbose),
+        Command::Backup => match backup::run_once(&config, &det_dir)? {
+            Some(path) => {
+                println!("Backup written → {}", path.display());
+                Ok(())
+            }
+            None => bail!("No detections to back up in {}", det_dir.display()),
+        },
+        Command::Restore { snapshot, yes } => {
+            let snapshot = if snapshot == "latest" {
+                backup::list_snapshots(&config.backup_dir)
+                    .pop()
+                    .with_context(|| format!("No snapshots in {}", config.backup_dir.display()))?
+            } else {
+                PathBuf::from(snapshot)
+            };
+            // Restoring onto a fresh install: the store may not exist yet.
+            std::fs::create_dir_all(&det_dir)
+                .with_context(|| format!("Cannot create {}", det_dir.display()))?;
+            open()?.restore(&snapshot, yes)?;
+            restore_database(&snapshot, &config.db_path, yes)
+        }
+    }
+}
+
+/// Put the `birds.db` copy taken with `snapshot` in place of `db_path`.
+fn restore_database(snapshot: &Path, db_path: &Path, yes: bool) -> Result<()> {
+    let db_snapshot = backup::db_snapshot_for(snapshot);
+    if !db_snapshot.exists() {
+        println!(
+            "No database copy next to {}; {} left as is",
+            snapshot.display(),
+            db_path.display()
+        );
+        return Ok(());
+    }
+    if !yes {
+        println!("Would replace {} with {}", db_path.display(), db_snapshot.display());
+        return Ok(());
+    }
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Cannot create {}", parent.display()))?;
+    }
+    if let Some(aside) = backup::restore_db(&db_snapshot, db_path)? {
+        println!("Previous database moved to {}", aside.display());
+    }
+    println!("Restored {} from {}", db_path.display(), db_snapshot.display());
+    Ok(())
+}
+
+// ── store ────────────────────────────────────────────────────────────────
+
+/// Snapshot of the Parquet files present when the command started,
+/// loaded into an in-memory DuckDB `detections` view.
+struct Store {
+    dir: PathBuf,
+    files: Vec<PathBuf>,
+    /// Size and mtime of each of `files` when the snapshot was taken.
+    stamps: Vec<Option<(u64, SystemTime)>>,
+    conn: duckdb::Connection,
+}
+
+/// Size and mtime of `path`, to notice a batch replaced by a relabel.
+fn file_stamp(path: &Path) -> Option<(u64, SystemTime)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()?))
+}
+
+impl Store {
+    fn open(dir: &Path) -> Result<Self> {
+        let files = export::parquet_files(dir)?;
+        let stamps = files.iter().map(PathBuf::as_path).map(file_stamp).collect();
+
+        let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+        if files.is_empty() {
+            conn.execute_batch("CREATE VIEW detections AS SELECT NULL::BIGINT AS id WHERE false")?;
+        } else {
+            conn.execute_batch(&format!(
+                "CREATE VIEW detections AS SELECT * FROM read_parquet({}, union_by_name=true)",
+                file_list_sql(&files)
+            ))
+            .context("Cannot read Parquet files")?;
+        }
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            files,
+            stamps,
+            conn,
+        })
+    }
+
+    fn count(&self, where_sql: &str) -> Result<u64> {
+        if self.files.is_empty() {
+            return Ok(0);
+        }
+        Ok(self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM detections WHERE {where_sql}"),
+            [],
+            |r| r.get(0),
+        )?)
+    }
+
+    fn stats(&self) -> Result<()> {
+        println!("Detections dir: {}", self.dir.display());
+        println!("Parquet files:  {}", self.files.len());
+        println!("Detections:     {}", self.count("true")?);
+        if !self.files.is_empty() {
+            let (species, first, last): (u64, Option<String>, Option<String>) = self.conn.query_row(
+                "SELECT COUNT(DISTINCT Sci_Name), MIN(Date), MAX(Date) FROM detections",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )?;
+            println!("Species:        {species}");
+            println!(
+                "Date range:     {} – {}",
+                first.unwrap_or_default(),
+                last.unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+
+    fn import(&self, input: &Path) -> Result<()> {
+        let reader = match extension(input).as_str() {
+            "parquet" => format!("read_parquet({})", sql_str(&input.display().to_string())),
+            "csv" => format!("read_csv_auto({}, header=true)", sql_str(&input.display().to_string())),
+            other => bail!("Unsupported import format {other:?} (use .parquet or .csv)"),
+        };
+        self.conn
+            .execute_batch(&format!("CREATE TEMP TABLE incoming AS SELECT * FROM {reader}"))
+            .with_context(|| format!("Cannot read {}", input.display()))?;
+        check_required_columns(&self.conn, "incoming")?;
+        let n = self.write_new_file(
+            "SELECT * FROM incoming",
+            "admin-import",
+        )?;
+        println!("Imported {n} detection(s) from {}", input.display());
+        Ok(())
+    }
+
+    fn merge(&self, other_dir: &Path) -> Result<()> {
+        let other = Store::open(other_dir)?;
+        if other.files.is_empty() {
+            bail!("No Parquet files in {}", other_dir.display());
+        }
+        self.conn.execute_batch(&format!(
+            "CREATE TEMP TABLE incoming AS SELECT * FROM read_parquet({}, union_by_name=true)",
+            file_list_sql(&other.files)
+        ))?;
+        check_required_columns(&self.conn, "incoming")?;
+
+        // A detection is identified by when, what, and which model/node
+        // produced it — `id` values from two installs can collide.
+        let key_cols = ["Date", "Time", "Sci_Name", "Model_Slug", "Source_Node"];
+        let new_rows = if self.files.is_empty() {
+            "SELECT * FROM incoming".to_string()
+        } else {
+            let same_key = key_cols
+                .iter()
+                .map(|c| format!("COALESCE(d.{c}, '') = COALESCE(i.{c}, '')"))
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            format!(
+                "SELECT * FROM incoming i \
+                 WHERE NOT EXISTS (SELECT 1 FROM detections d WHERE {same_key})"
+            )
+        };
+        let total: u64 = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM (SELECT DISTINCT {} FROM incoming)",
+                key_cols.join(", ")
+            ),
+            [],
+            |r| r.get(0),
+        )?;
+        let n = self.write_new_file(&new_rows, "admin-merge")?;
+        println!(
+            "Merged {n} new detection(s) from {} ({} already present)",
+            other_dir.display(),
+            total.saturating_sub(n)
+        );
+        Ok(())
+    }
+
+    fn delete_species(&self, sci_name: &str, yes: bool) -> Result<()> {
+        let cond = format!("Sci_Name = {}", sql_str(sci_name));
+        let n = self.count(&cond)?;
+        if n == 0 {
+            println!("No detections of {sci_name:?}");
+            return Ok(());
+        }
+        if !yes {
+            println!("Would delete {n} detection(s) of {sci_name:?} — re-run with --yes");
+            return Ok(());
+        }
+        self.rewrite(&format!("SELECT * FROM detections WHERE NOT ({cond})"))?;
+        println!("Deleted {n} detection(s) of {sci_name:?}");
+        Ok(())
+    }
+
+    fn compact(&self, yes: bool) -> Result<()> {
+        if self.files.len() <= 1 {
+            println!("{} Parquet file(s) — nothing to compact", self.files.len());
+            return Ok(());
+        }
+        if !yes {
+            println!(
+                "Would compact {} Parquet file(s) ({} detections) into one — re-run with --yes",
+                self.files.len(),
+                self.count("true")?
+            );
+            return Ok(());
+        }
+        let n = self.rewrite("SELECT * FROM detections")?;
+        println!("Compacted {} file(s) into one ({n} detections)", self.files.len());
+        Ok(())
+    }
+
+    /// Replace every file in the snapshot with the rows of a backup.  The
+    /// replaced files are moved to `pre-restore-<ts>/` rather than deleted.
+    fn restore(&self, snapshot: &Path, yes: bool) -> Result<()> {
+        self.conn
+            .execute_batch(&format!(
+                "CREATE TEMP TABLE incoming AS SELECT * FROM read_parquet({})",
+                sql_str(&snapshot.display().to_string())
+            ))
+            .with_context(|| format!("Cannot read {}", snapshot.display()))?;
+        check_required_columns(&self.conn, "incoming")?;
+        let incoming: u64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM incoming", [], |r| r.get(0))?;
+        if !yes {
+            println!(
+                "Would replace {} Parquet file(s) ({} detections) with {incoming} detection(s) \
+                 from {} — stop the processing server and re-run with --yes",
+                self.files.len(),
+                self.count("true")?,
+                snapshot.display()
+            );
+            return Ok(());
+        }
+
+        let n = self.write_new_file("SELECT * FROM incoming", "admin-restore")?;
+        if !self.files.is_empty() {
+            let aside = self
+                .dir
+                .join(format!("pre-restore-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+            std::fs::create_dir_all(&aside)
+                .with_context(|| format!("Cannot create {}", aside.display()))?;
+            for f in &self.files {
+                let dest = aside.join(f.file_name().unwrap_or_default());
+                std::fs::rename(f, &dest)
+                    .with_context(|| format!("Cannot move {}", f.display()))?;
+            }
+            println!("Previous files moved to {}", aside.display());
+        }
+        println!("Restored {n} detection(s) from {}", snapshot.display());
+        Ok(())
+    }
+
+    fn verify_media(&self, extracted_dir: &Path, verbose: bool) -> Result<()> {
+        if self.files.is_empty() {
+            println!("No detections");
+            return Ok(());
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT Date, Com_Name, File_Name FROM detections \
+             WHERE COALESCE(File_Name, '') != ''",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?))
+        })?;
+
+        let (mut ok, mut missing, mut no_spectrogram) = (0u64, 0u64, 0u64);
+        for (date, com_name, file_name) in rows.flatten() {
+            match find_clip(extracted_dir, &date, &com_name, &file_name) {
+                Some(path) => {
+                    ok += 1;
+                    if !PathBuf::from(format!("{}.png", path.display())).exists() {
+                        no_spectrogram += 1;
+                    }
+                }
+                None => {
+                    missing += 1;
+                    if verbose {
+                        println!("missing: By_Date/{date}/{}/{file_name}", safe_path_component(&com_name));
+                    }
+                }
+            }
+        }
+        println!("Clips present:        {ok}");
+        println!("Clips missing:        {missing}");
+        println!("Missing spectrograms: {no_spectrogram}");
+        if missing > 0 {
+            bail!("{missing} clip(s) missing (use --verbose to list them)");
+        }
+        Ok(())
+    }
+
+    /// Write the result of `select_sql` to a new Parquet file in the
+    /// detections dir; returns the number of rows written.
+    fn write_new_file(&self, select_sql: &str, prefix: &str) -> Result<u64> {
+        Ok(self.write_file(select_sql, prefix)?.map_or(0, |(_, n)| n))
+    }
+
+    /// [`Store::write_new_file`], also returning the path written (none
+    /// for an empty result).
+    fn write_file(&self, select_sql: &str, prefix: &str) -> Result<Option<(PathBuf, u64)>> {
+        let n: u64 = self
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM ({select_sql})"), [], |r| r.get(0))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        let ts = chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f");
+        let name = format!("{prefix}-{ts}.parquet");
+        let tmp = self.dir.join(format!(".{name}.tmp"));
+        self.conn.execute_batch(&format!(
+            "COPY ({select_sql}) TO {} (FORMAT PARQUET, COMPRESSION ZSTD)",
+            sql_str(&tmp.display().to_string())
+        ))?;
+        let path = self.dir.join(&name);
+        std::fs::rename(&tmp, &path).with_context(|| format!("Cannot rename {}", tmp.display()))?;
+        Ok(Some((path, n)))
+    }
+
+    /// Replace every file in the snapshot with one file holding the
+    /// result of `select_sql`.  Files written after [`Store::open`] are
+    /// left alone; if one of the snapshot's files was replaced meanwhile
+    /// (a relabel in the web UI), the new file is removed again and
+    /// nothing else is touched.
+    fn rewrite(&self, select_sql: &str) -> Result<u64> {
+        let written = self.write_file(select_sql, "_compacted")?;
+        let changed = self
+            .files
+            .iter()
+            .zip(&self.stamps)
+            .find(|(f, stamp)| file_stamp(f) != **stamp);
+        if let Some((f, _)) = changed {
+            if let Some((path, _)) = &written {
+                std::fs::remove_file(path).ok();
+            }
+            bail!("{} changed while running (relabelled?); nothing was removed, re-run", f.display());
+        }
+        let n = written.map_or(0, |(_, n)| n);
+        for f in &self.files {
+            std::fs::remove_file(f).with_context(|| format!("Cannot remove {}", f.display()))?;
+        }
+        Ok(n)
+    }
+}
+
+/// Columns the web UI cannot do without.
+fn check_required_columns(conn: &duckdb::Connection, table: &str) -> Result<()> {
+    const REQUIRED: [&str; 6] = ["Date", "Time", "Domain", "Sci_Name", "Com_Name", "Confidence"];
+    let mut stmt = conn.prepare(&format!(
+        "SELECT column_name FROM information_schema.columns WHERE table_name = {}",
+        sql_str(table)
+    ))?;
+    let present: Vec<String> = stmt.query_map([], |r| r.get(0))?.flatten().collect();
+    let missing: Vec<&str> = REQUIRED
+        .into_iter()
+        .filter(|c| !present.iter().any(|p| p.eq_ignore_ascii_case(c)))
+        .collect();
+    if !missing.is_empty() {
+        bail!("Input is missing required column(s): {}", missing.join(", "));
+    }
+    Ok(())
+}
+
+/// Locate a clip under the current or historical directory naming, also
+/// accepting the Opus file that replaces a `.wav`/`.mp3` after compression.
+fn find_clip(extracted_dir: &Path, date: &str, com_name: &str, file_name: &str) -> Option<PathBuf> {
+    let day = extracted_dir.join("By_Date").join(date);
+    let legacy = com_name.replace('\'', "").replace(' ', "_");
+    let stem = Path::new(file_name).file_stem()?.to_string_lossy().to_string();
+    [safe_path_component(com_name), legacy]
+        .iter()
+        .flat_map(|dir| [day.join(dir).join(file_name), day.join(dir).join(format!("{stem}.opus"))])
+        .find(|p| p.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gaia-admin-{label}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_parquet(dir: &Path, name: &str, rows: &[(&str, &str)]) {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        let values: Vec<String> = rows
+            .iter()
+            .map(|(date, sci)| {
+                format!("('{date}', '10:00:00', 'birds', '{sci}', '{sci}', 0.9, 'birdnet', 'node')")
+            })
+            .collect();
+        conn.execute_batch(&format!(
+            "COPY (SELECT * FROM (VALUES {}) AS t(Date, Time, Domain, Sci_Name, Com_Name, Confidence, Model_Slug, Source_Node)) \
+             TO '{}' (FORMAT PARQUET)",
+            values.join(", "),
+            dir.join(name).display()
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn delete_species_rewrites_snapshot() {
+        let dir = temp_dir("delete");
+        write_parquet(&dir, "a.parquet", &[("2025-01-01", "Pica pica"), ("2025-01-01", "Turdus merula")]);
+        write_parquet(&dir, "b.parquet", &[("2025-01-02", "Pica pica")]);
+
+        Store::open(&dir).unwrap().delete_species("Pica pica", false).unwrap();
+        assert_eq!(Store::open(&dir).unwrap().count("true").unwrap(), 3, "dry run must not write");
+
+        Store::open(&dir).unwrap().delete_species("Pica pica", true).unwrap();
+        let store = Store::open(&dir).unwrap();
+        assert_eq!(store.files.len(), 1);
+        assert_eq!(store.count("true").unwrap(), 1);
+        assert_eq!(store.count("Sci_Name = 'Pica pica'").unwrap(), 0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn compact_leaves_relabelled_batch_alone() {
+        let dir = temp_dir("relabelled");
+        write_parquet(&dir, "a.parquet", &[("2025-01-01", "Pica pica")]);
+        write_parquet(&dir, "b.parquet", &[("2025-01-02", "Pica pica")]);
+
+        let store = Store::open(&dir).unwrap();
+        // The web UI replaces a batch while the command runs.
+        write_parquet(&dir, "b.parquet", &[("2025-01-02", "Corvus corax"), ("2025-01-02", "Pica pica")]);
+        assert!(store.compact(true).is_err());
+
+        let store = Store::open(&dir).unwrap();
+        assert_eq!(store.files.len(), 2, "nothing removed, nothing added");
+        assert_eq!(store.count("Sci_Name = 'Corvus corax'").unwrap(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn merge_skips_existing_rows() {
+        let ours = temp_dir("merge-ours");
+        let theirs = temp_dir("merge-theirs");
+        write_parquet(&ours, "a.parquet", &[("2025-01-01", "Pica pica")]);
+        write_parquet(&theirs, "b.parquet", &[("2025-01-01", "Pica pica"), ("2025-01-03", "Sitta europaea")]);
+
+        Store::open(&ours).unwrap().merge(&theirs).unwrap();
+        let store = Store::open(&ours).unwrap();
+        assert_eq!(store.count("true").unwrap(), 2);
+        assert_eq!(store.count("Sci_Name = 'Sitta europaea'").unwrap(), 1);
+        std::fs::remove_dir_all(&ours).ok();
+        std::fs::remove_dir_all(&theirs).ok();
+    }
+
+    #[test]
+    fn restore_replaces_store_and_keeps_old_files() {
+        let dir = temp_dir("restore");
+        let backups = temp_dir("restore-backups");
+        write_parquet(&dir, "a.parquet", &[("2025-01-01", "Pica pica")]);
+        write_parquet(&dir, "b.parquet", &[("2025-01-02", "Pica pica")]);
+        let (snapshot, rows) = backup::snapshot(&dir, None, &backups).unwrap().unwrap();
+        assert_eq!(rows, 2);
+        // Simulate damage after the backup was taken.
+        std::fs::remove_file(dir.join("b.parquet")).unwrap();
+
+        Store::open(&dir).unwrap().restore(&snapshot, true).unwrap();
+        let store = Store::open(&dir).unwrap();
+        assert_eq!(store.files.len(), 1);
+        assert_eq!(store.count("true").unwrap(), 2);
+        let aside: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("pre-restore-"))
+            .collect();
+        assert_eq!(aside.len(), 1);
+        assert!(aside[0].path().join("a.parquet").exists());
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::remove_dir_all(&backups).ok();
+    }
+}
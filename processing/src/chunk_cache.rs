@@ -0,0 +1,128 @@
+//! Model outputs of recently scored chunks, so identical audio is not
+//! run through the model twice.
+//!
+//! Without a silence gate, quiet nights arrive as recordings of digital
+//! silence, and every recording ends in a zero-padded chunk: the same
+//! samples again and again.  Each [`LoadedModel`](crate::model::LoadedModel)
+//! keeps a small least-recently-used cache of raw model outputs keyed by
+//! a hash of the chunk and the location and week the model was given
+//! (`CHUNK_CACHE_SIZE` entries, 0 to turn it off).  Hits and misses are
+//! counted process-wide for the dashboard's System Activity widget.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// Raw scores and, for models with a classifier head, the embedding.
+pub type Outputs = (Vec<f32>, Option<Vec<f32>>);
+
+pub struct ChunkCache {
+    capacity: usize,
+    /// Least recently used first.
+    entries: VecDeque<(u64, Outputs)>,
+}
+
+impl ChunkCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// The outputs stored under `key`, counted as a hit or a miss.
+    pub fn get(&mut self, key: u64) -> Option<Outputs> {
+        let Some(pos) = self.entries.iter().position(|(k, _)| *k == key) else {
+            MISSES.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        HITS.fetch_add(1, Ordering::Relaxed);
+        let entry = self.entries.remove(pos)?;
+        let outputs = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(outputs)
+    }
+
+    /// Store `outputs`, evicting the least recently used entry when full.
+    pub fn insert(&mut self, key: u64, outputs: Outputs) {
+        if !self.enabled() {
+            return;
+        }
+        self.entries.retain(|(k, _)| *k != key);
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, outputs));
+    }
+}
+
+/// Cache key of `chunk` scored for `lat`/`lon` in `week`.  Samples are
+/// compared bit for bit, so only truly identical chunks match.
+pub fn key(chunk: &[f32], lat: f64, lon: f64, week: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    chunk.len().hash(&mut hasher);
+    for sample in chunk {
+        sample.to_bits().hash(&mut hasher);
+    }
+    lat.to_bits().hash(&mut hasher);
+    lon.to_bits().hash(&mut hasher);
+    week.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cache hits and misses since the server started, over all models.
+pub fn stats() -> (u64, u64) {
+    (HITS.load(Ordering::Relaxed), MISSES.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs(score: f32) -> Outputs {
+        (vec![score], None)
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = ChunkCache::new(2);
+        cache.insert(1, outputs(0.1));
+        cache.insert(2, outputs(0.2));
+        // Using 1 makes 2 the oldest.
+        assert_eq!(cache.get(1), Some(outputs(0.1)));
+        cache.insert(3, outputs(0.3));
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(1), Some(outputs(0.1)));
+        assert_eq!(cache.get(3), Some(outputs(0.3)));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut cache = ChunkCache::new(0);
+        assert!(!cache.enabled());
+        cache.insert(1, outputs(0.1));
+        assert_eq!(cache.get(1), None);
+    }
+
+    #[test]
+    fn test_key() {
+        let silence = vec![0.0_f32; 48_000];
+        let here = |chunk: &[f32]| key(chunk, 9.9, -84.1, 20);
+        assert_eq!(here(&silence), here(&silence.clone()));
+        // Length, sign of zero, location and week all matter.
+        assert_ne!(here(&silence), here(&silence[..47_999]));
+        let mut negative = silence.clone();
+        negative[0] = -0.0;
+        assert_ne!(here(&silence), here(&negative));
+        assert_ne!(here(&silence), key(&silence, 9.9, -84.1, 21));
+        assert_ne!(here(&silence), key(&silence, 10.0, -84.1, 20));
+    }
+}
@@ -0,0 +1,642 @@
+//! Command line of `gaia-processing`.
+//!
+//! Without a subcommand the binary runs the processing server with the
+//! config path given as the only argument, as the container images have
+//! always started it (`gaia-processing /etc/gaia/gaia.conf`).  The other
+//! subcommands are one-shot tools for operators and scripts: those that
+//! report something print it as JSON on stdout with `--json` (logs go to
+//! stderr) and exit 1 when the check or command failed.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use gaia_common::audio::ChannelMode;
+use gaia_common::config::{self, Config};
+use gaia_common::detection::{Detection, ParsedFileName};
+use gaia_processing::detections_dir;
+
+use crate::{
+    analysis, db, export as store_export, journal, manifest, migrate_parquet, reanalyze,
+    retention, rules,
+};
+
+/// Extensions `analyze-dir`, `reanalyze --recordings` and folder imports pick up; anything but WAV is decoded by ffmpeg.
+pub(crate) const AUDIO_EXTENSIONS: [&str; 6] = ["wav", "flac", "mp3", "ogg", "opus", "m4a"];
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "gaia-processing",
+    version,
+    about = "Gaia Audio processing server"
+)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Config file of the server [default: /etc/gaia/gaia.conf]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the processing server (the default)
+    Run {
+        /// Config file [default: /etc/gaia/gaia.conf]
+        config: Option<PathBuf>,
+    },
+    /// Record and analyse in this process, without a capture server
+    Mono {
+        /// Config file [default: /etc/gaia/gaia.conf]
+        config: Option<PathBuf>,
+    },
+    /// Check the config, model manifests, rules and retention files
+    CheckConfig {
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Time each model on a recording
+    Bench {
+        /// Recording to analyse
+        file: PathBuf,
+        /// Timed runs per model, after one warm-up run
+        #[arg(short = 'n', long, default_value_t = 3)]
+        iterations: u32,
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Analyse every recording in a directory and print the detections
+    /// without storing them
+    AnalyzeDir {
+        dir: PathBuf,
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export detections to a .parquet or .csv file
+    Export {
+        out: PathBuf,
+        /// First day to export (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to export (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Re-score stored clips (or retained recordings) with the current
+    /// models into per-model tables under `reanalysis/`
+    Reanalyze {
+        /// Analyse the recordings in this directory instead of the clips
+        /// of stored detections
+        #[arg(long)]
+        recordings: Option<PathBuf>,
+        /// First day to reanalyse (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to reanalyse (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Model slug to run (repeatable) [default: MODEL_SLUGS]
+        #[arg(long = "model")]
+        models: Vec<String>,
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Detection store maintenance
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Load ONNX models with tract-onnx, as at runtime (build-time check)
+    ValidateModel {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Strictly parse every manifest.toml under a models directory,
+    /// rejecting unknown keys
+    #[command(visible_alias = "validate-models")]
+    ValidateManifests { dir: PathBuf },
+    /// Review the taxonomy table and write the merged canonical table
+    ReviewTaxonomy {
+        table: PathBuf,
+        merged: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Apply pending migrations to birds.db, the detection store and the
+    /// journal; run it with the server stopped
+    Migrate {
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigArg {
+    /// Config file [default: /etc/gaia/gaia.conf]
+    #[arg(short, long = "config", env = "GAIA_CONFIG")]
+    config: Option<PathBuf>,
+}
+
+impl ConfigArg {
+    pub fn path(&self) -> PathBuf {
+        config_or_default(self.config.clone())
+    }
+}
+
+pub fn config_or_default(path: Option<PathBuf>) -> PathBuf {
+    path.unwrap_or_else(|| PathBuf::from(Config::default_path()))
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+// ── check-config ─────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct ConfigReport {
+    config: PathBuf,
+    ok: bool,
+    problems: Vec<String>,
+    /// Slugs of the models the server would load.
+    models: Vec<String>,
+}
+
+pub fn check_config(path: &Path, json: bool) -> Result<bool> {
+    let mut report = ConfigReport {
+        config: path.to_path_buf(),
+        ok: false,
+        problems: Vec::new(),
+        models: Vec::new(),
+    };
+    match config::load(path) {
+        Ok(config) => {
+            report.problems = config.problems();
+            report
+                .problems
+                .extend(processing_problems(&config, &mut report.models));
+        }
+        Err(e) => report.problems.push(format!("{e:#}")),
+    }
+    report.ok = report.problems.is_empty();
+
+    if json {
+        print_json(&report)?;
+    } else if report.ok {
+        println!(
+            "✅ {} is valid ({} model(s): {})",
+            path.display(),
+            report.models.len(),
+            report.models.join(", ")
+        );
+    } else {
+        println!(
+            "❌ {} has {} problem(s):",
+            path.display(),
+            report.problems.len()
+        );
+        for p in &report.problems {
+            println!("  - {p}");
+        }
+    }
+    Ok(report.ok)
+}
+
+/// Settings only the processing server reads.  Fills `models` with the
+/// slugs of the manifests `MODEL_SLUGS` selects.
+fn processing_problems(config: &Config, models: &mut Vec<String>) -> Vec<String> {
+    let mut problems = Vec::new();
+    match manifest::discover_manifests(&config.model_dir) {
+        Ok(found) => {
+            let selected = manifest::filter_manifests_by_slugs(found, &config.model_slugs);
+            if selected.is_empty() {
+                problems.push(format!(
+                    "No model manifests in {} match MODEL_SLUGS={:?}",
+                    config.model_dir.display(),
+                    config.model_slugs
+                ));
+            }
+            models.extend(selected.iter().map(|m| m.slug()));
+        }
+        Err(e) => problems.push(format!("MODEL_DIR: {e:#}")),
+    }
+    let ensemble = config.ensemble_mode.trim();
+    if crate::ensemble::EnsembleMode::parse(ensemble).is_none()
+        && !ensemble.is_empty()
+        && !ensemble.eq_ignore_ascii_case("off")
+    {
+        problems.push(format!(
+            "ENSEMBLE_MODE={ensemble} is not off, max, mean or weighted"
+        ));
+    }
+    // Both files are optional; an invalid one is ignored at runtime with
+    // only a warning in the log.
+    if let Ok(text) = std::fs::read_to_string(&config.rules_file) {
+        if let Err(e) = rules::RuleSet::parse(&text) {
+            problems.push(format!("RULES_FILE {}: {e:#}", config.rules_file.display()));
+        }
+    }
+    if let Ok(text) = std::fs::read_to_string(&config.retention_file) {
+        if let Err(e) = retention::RetentionPolicies::parse(&text) {
+            problems.push(format!(
+                "RETENTION_FILE {}: {e:#}",
+                config.retention_file.display()
+            ));
+        }
+    }
+    problems
+}
+
+// ── bench ────────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct BenchResult {
+    /// Model slug, or `all` for every model together.
+    model: String,
+    iterations: u32,
+    audio_secs: f64,
+    mean_secs: f64,
+    min_secs: f64,
+    /// Seconds of audio analysed per second; below 1 the station falls
+    /// behind.
+    realtime_factor: f64,
+}
+
+pub fn bench(config_path: &Path, file: &Path, iterations: u32, json: bool) -> Result<bool> {
+    let config = config::load(config_path).context("Config load failed")?;
+    let recording = recording(file)?;
+    let (_, mut models) = crate::load_models(&config)?;
+    let iterations = iterations.max(1);
+    let channels = ChannelMode::parse(&config.channel_mode);
+
+    let mut results = Vec::new();
+    let mut time = |label: String, models: &mut [crate::model::LoadedModel]| -> Result<()> {
+        // The warm-up run also decodes the file and fills caches.
+        let audio_secs = analysis::analyse(&recording, models, &config, &[], channels)?
+            .audio_cache
+            .duration_secs()
+            .unwrap_or(0.0);
+        let mut runs = Vec::with_capacity(iterations as usize);
+        for _ in 0..iterations {
+            let started = Instant::now();
+            analysis::analyse(&recording, models, &config, &[], channels)?;
+            runs.push(started.elapsed().as_secs_f64());
+        }
+        let mean = runs.iter().sum::<f64>() / runs.len() as f64;
+        results.push(BenchResult {
+            model: label,
+            iterations,
+            audio_secs,
+            mean_secs: mean,
+            min_secs: runs.iter().copied().fold(f64::INFINITY, f64::min),
+            realtime_factor: audio_secs / mean.max(1e-6),
+        });
+        Ok(())
+    };
+    for i in 0..models.len() {
+        let slug = models[i].manifest.slug();
+        time(slug, &mut models[i..=i])?;
+    }
+    if models.len() > 1 {
+        time("all".into(), &mut models)?;
+    }
+
+    if json {
+        print_json(&results)?;
+    } else {
+        println!(
+            "{} ({:.1}s of audio, {iterations} run(s) each)",
+            file.display(),
+            results[0].audio_secs
+        );
+        for r in &results {
+            println!(
+                "  {:<24} mean {:>7.3}s  min {:>7.3}s  {:>6.1}x real time",
+                r.model, r.mean_secs, r.min_secs, r.realtime_factor
+            );
+        }
+    }
+    Ok(true)
+}
+
+// ── analyze-dir ──────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct FileResult {
+    file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    detections: Vec<Detection>,
+}
+
+pub fn analyze_dir(config_path: &Path, dir: &Path, json: bool) -> Result<bool> {
+    let config = config::load(config_path).context("Config load failed")?;
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read {}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| AUDIO_EXTENSIONS.contains(&store_export::extension(p).as_str()))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        warn!("No recordings in {}", dir.display());
+    }
+    let (_, mut models) = crate::load_models(&config)?;
+    let channels = ChannelMode::parse(&config.channel_mode);
+
+    let mut results = Vec::with_capacity(files.len());
+    for path in files {
+        let analysed = recording(&path).and_then(|file| {
+            analysis::analyse(&file, &mut models, &config, &config.model_slugs, channels)
+        });
+        let result = match analysed {
+            Ok(a) => FileResult {
+                file: path,
+                error: None,
+                detections: a.detections,
+            },
+            Err(e) => FileResult {
+                file: path,
+                error: Some(format!("{e:#}")),
+                detections: Vec::new(),
+            },
+        };
+        if !json {
+            print_file_result(&result);
+        }
+        results.push(result);
+    }
+
+    if json {
+        print_json(&results)?;
+    }
+    Ok(results.iter().all(|r| r.error.is_none()))
+}
+
+fn print_file_result(result: &FileResult) {
+    println!("{}", result.file.display());
+    if let Some(e) = &result.error {
+        println!("  error: {e}");
+    }
+    for d in &result.detections {
+        println!(
+            "  {:>6.1}–{:<6.1} {:>3}%  {} ({}) [{}]{}",
+            d.start,
+            d.stop,
+            d.confidence_pct(),
+            d.common_name,
+            d.scientific_name,
+            d.model_slug,
+            if d.excluded { " (out of range)" } else { "" },
+        );
+    }
+}
+
+/// `path` as a recording: dated from its name like capture segments, or
+/// from its modification time for files recorded elsewhere.
+fn recording(path: &Path) -> Result<ParsedFileName> {
+    if let Ok(file) = ParsedFileName::parse(path) {
+        return Ok(file);
+    }
+    let modified = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .with_context(|| format!("Cannot stat {}", path.display()))?;
+    Ok(ParsedFileName {
+        file_path: path.to_path_buf(),
+        file_date: chrono::DateTime::<chrono::Local>::from(modified).naive_local(),
+        rtsp_id: String::new(),
+        capture_node: None,
+    })
+}
+
+// ── export ───────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    out: &'a Path,
+    detections: u64,
+}
+
+pub fn export(
+    config_path: &Path,
+    out: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+    json: bool,
+) -> Result<bool> {
+    let config = config::load(config_path).context("Config load failed")?;
+    let files = store_export::parquet_files(&detections_dir(&config))?;
+    let detections = store_export::export(&files, out, from, to)?;
+    if json {
+        print_json(&ExportReport { out, detections })?;
+    } else {
+        println!("Exported {detections} detection(s) → {}", out.display());
+    }
+    Ok(true)
+}
+
+// ── reanalyze ────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct ReanalyzeReport {
+    files: usize,
+    failed: usize,
+    /// Stored detections whose clip is no longer on disk.
+    missing_clips: usize,
+    tables: Vec<reanalyze::Table>,
+}
+
+pub fn reanalyze(
+    config_path: &Path,
+    recordings: Option<&Path>,
+    from: Option<&str>,
+    to: Option<&str>,
+    models: &[String],
+    json: bool,
+) -> Result<bool> {
+    let config = config::load(config_path).context("Config load failed")?;
+    let (targets, missing_clips) = match recordings {
+        Some(dir) => (recording_targets(dir, from, to)?, 0),
+        None => reanalyze::stored_clips(
+            &store_export::parquet_files(&detections_dir(&config))?,
+            &config.extracted_dir,
+            from,
+            to,
+        )?,
+    };
+    if missing_clips > 0 {
+        warn!("{missing_clips} stored detection(s) have no clip on disk");
+    }
+    if targets.is_empty() {
+        warn!("Nothing to reanalyse");
+    }
+
+    let enabled = if models.is_empty() {
+        config.model_slugs.clone()
+    } else {
+        models.to_vec()
+    };
+    let (_, mut loaded) = crate::load_models(&config)?;
+    let selected: Vec<(String, String)> = loaded
+        .iter()
+        .filter(|m| enabled.is_empty() || manifest::slug_is_selected(&m.manifest.slug(), &enabled))
+        .map(|m| (m.manifest.slug(), m.manifest.manifest.model.name.clone()))
+        .collect();
+    if selected.is_empty() {
+        anyhow::bail!("No loaded model matches {}", enabled.join(", "));
+    }
+
+    let channels = ChannelMode::parse(&config.channel_mode);
+    let mut rows = Vec::new();
+    let mut failed = 0;
+    for (i, target) in targets.iter().enumerate() {
+        let file = ParsedFileName {
+            file_path: target.path.clone(),
+            file_date: target.start,
+            rtsp_id: String::new(),
+            capture_node: None,
+        };
+        match analysis::analyse(&file, &mut loaded, &config, &enabled, channels) {
+            Ok(a) => rows.extend(reanalyze::rows_for(target, &selected, a.detections)),
+            Err(e) => {
+                failed += 1;
+                warn!("{}: {e:#}", target.path.display());
+            }
+        }
+        if (i + 1) % 100 == 0 {
+            info!("Reanalysed {}/{} file(s)", i + 1, targets.len());
+        }
+    }
+    let tables = reanalyze::write_tables(&reanalysis_dir(&config), &rows)?;
+
+    let report = ReanalyzeReport {
+        files: targets.len(),
+        failed,
+        missing_clips,
+        tables,
+    };
+    if json {
+        print_json(&report)?;
+    } else {
+        println!(
+            "Reanalysed {} file(s), {failed} failed, {missing_clips} clip(s) missing",
+            report.files
+        );
+        for t in &report.tables {
+            println!(
+                "  {:<24} {} row(s), {} detection(s) → {}",
+                t.model,
+                t.rows,
+                t.detections,
+                t.path.display()
+            );
+        }
+    }
+    Ok(failed == 0)
+}
+
+/// Recordings in `dir` dated `from..=to`, oldest first.
+fn recording_targets(
+    dir: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<reanalyze::Target>> {
+    let from = from.map(store_export::validate_date).transpose()?;
+    let to = to.map(store_export::validate_date).transpose()?;
+    let mut targets: Vec<reanalyze::Target> = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read {}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| AUDIO_EXTENSIONS.contains(&store_export::extension(p).as_str()))
+        .filter_map(|p| recording(&p).ok())
+        .filter(|file| {
+            let date = file.file_date.format("%Y-%m-%d").to_string();
+            from.is_none_or(|f| date.as_str() >= f) && to.is_none_or(|t| date.as_str() <= t)
+        })
+        .map(|file| reanalyze::Target {
+            path: file.file_path,
+            start: file.file_date,
+            original: None,
+        })
+        .collect();
+    targets.sort_by_key(|t| t.start);
+    Ok(targets)
+}
+
+fn reanalysis_dir(config: &Config) -> PathBuf {
+    config
+        .db_path
+        .parent()
+        .unwrap_or(Path::new("/data"))
+        .join("reanalysis")
+}
+
+// ── db migrate ───────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct Migration {
+    name: &'static str,
+    path: PathBuf,
+    /// `false` when it had already been applied.
+    applied: bool,
+}
+
+pub fn db_migrate(config_path: &Path, json: bool) -> Result<bool> {
+    let config = config::load(config_path).context("Config load failed")?;
+    let det_dir = detections_dir(&config);
+    std::fs::create_dir_all(&det_dir)
+        .with_context(|| format!("Cannot create {}", det_dir.display()))?;
+    let journal_path = crate::journal_path(&config);
+    let journal_existed = journal_path.exists();
+    let migrations = [
+        Migration {
+            name: "sci_name_normalisation",
+            applied: migrate_parquet::run_if_needed(&det_dir)?,
+            path: det_dir,
+        },
+        Migration {
+            name: "sqlite_schema",
+            applied: db::initialize(&config.db_path)?,
+            path: config.db_path.clone(),
+        },
+        Migration {
+            name: "processing_journal",
+            applied: {
+                journal::initialize(&journal_path)?;
+                !journal_existed
+            },
+            path: journal_path,
+        },
+    ];
+
+    if json {
+        print_json(&migrations)?;
+    } else {
+        for m in &migrations {
+            let state = if m.applied { "applied" } else { "up to date" };
+            println!("{:<24} {state:<10} {}", m.name, m.path.display());
+        }
+    }
+    Ok(true)
+}
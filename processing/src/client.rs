@@ -3,27 +3,87 @@
 //! When mDNS discovery is available the processing node automatically
 //! finds all capture nodes on the network.  Otherwise it falls back to
 //! the single `CAPTURE_SERVER_URL` from `gaia.conf`.
+//!
+//! Detections are attributed to the capture node's persistent name (from
+//! its `/api/health`), falling back to the URL for older capture nodes.
+//! A node that reports its own coordinates there has its recordings
+//! analysed and stored with them instead of the global `LATITUDE` /
+//! `LONGITUDE`.
+//!
+//! A node speaking another protocol version than this server (see
+//! [`protocol::PROTOCOL_VERSION`]) is warned about once per version.
+//!
+//! Requests carry `CAPTURE_AUTH_TOKEN` as a bearer token when configured;
+//! `CAPTURE_TLS_CA` adds a trusted root for self-signed HTTPS captures.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::SyncSender;
+use std::sync::mpsc::{Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use tracing::{debug, error, info, warn};
 
 use gaia_common::config::Config;
-use gaia_common::discovery::{DiscoveryHandle, ServiceRole};
-use gaia_common::protocol::RecordingInfo;
+use gaia_common::detection::safe_path_component;
+use gaia_common::discovery::{DiscoveryHandle, PeerWatcher, ServiceRole};
+use gaia_common::capture_api::{CaptureClient, RecordingPage};
+use gaia_common::protocol::{self, HealthResponse, NodeBacklog, RecordingInfo};
 
+use crate::faults::Fault;
 use crate::WorkItem;
 
-/// How often to re-scan mDNS for new/removed capture nodes.
-const REDISCOVERY_INTERVAL: Duration = Duration::from_secs(60);
+/// How long to wait at startup for mDNS to report the first capture node.
+/// A capture node that registered just moments before us may not have
+/// been seen yet.
+const STARTUP_DISCOVERY_WAIT: Duration = Duration::from_secs(8);
+
+/// How often old rows are pruned from the processing journal.
+const JOURNAL_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Recordings dispatched per unit of node priority before all nodes are
+/// polled again, so a node with a long backlog cannot hold up the others.
+const ROUND_QUOTA: usize = 4;
 
-/// Poll all known capture servers for new recordings, download them,
-/// and dispatch work items to the worker pool.
+/// Recordings listed per capture node and poll.  A node holding more
+/// (after an outage) reports the rest in `X-Total-Count`; they are
+/// listed on later polls as the oldest ones are analysed and deleted.
+const LIST_PAGE_SIZE: usize = 500;
+
+/// Per-node backlog from the latest poll, for `/api/health`.
+static BACKLOG: Mutex<BTreeMap<String, NodeBacklog>> = Mutex::new(BTreeMap::new());
+
+/// Capture nodes already warned about as `url@protocol`, so a version
+/// mismatch is logged once rather than on every poll.
+static PROTOCOL_WARNED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// What a capture node's `/api/health` says about it.
+#[derive(Debug, Clone)]
+struct NodeInfo {
+    id: String,
+    name: String,
+    location: Option<(f64, f64)>,
+}
+
+/// Recordings of one capture node waiting to be dispatched.
+struct NodeQueue {
+    base_url: String,
+    source_node: String,
+    /// Persistent node id stored as `Source_Node_Id`.
+    source_node_id: String,
+    /// The node's own coordinates, if it reports them.
+    location: Option<(f64, f64)>,
+    priority: u32,
+    pending: VecDeque<RecordingInfo>,
+    /// Recordings on the node beyond the listed page.
+    unlisted: usize,
+}
+
+/// Poll all known capture servers for new recordings and queue them for
+/// a pool of `DOWNLOAD_THREADS` download threads, which pass each file
+/// to the worker pool once it is on disk.
 ///
 /// This function only handles downloading and dispatching — the actual
 /// analysis is performed by worker threads that receive `WorkItem`s via
@@ -34,13 +94,10 @@ pub fn poll_and_dispatch(
     config: &mut Config,
     discovery: Option<&DiscoveryHandle>,
     work_tx: &SyncSender<WorkItem>,
-    shutdown: &AtomicBool,
+    shutdown: &'static AtomicBool,
 ) -> Result<()> {
     let poll_interval = Duration::from_secs(config.poll_interval_secs);
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .context("Cannot create HTTP client")?;
+    let client = capture_client(config)?;
 
     let instance_suffix = if config.processing_instance.is_empty() {
         "processing_tmp".to_string()
@@ -59,17 +116,29 @@ pub fn poll_and_dispatch(
         );
     }
 
-    // Track which files we've already dispatched this session.
-    // Key = "base_url:filename" to avoid collisions across capture nodes.
-    let mut dispatched: HashSet<String> = HashSet::new();
-
     // Track how many NEW items we actually dispatched per iteration so we
     // can distinguish "new work to do" from "recordings on disk but
     // already dispatched".
     let mut dispatched_this_round: usize;
 
+    // Capture URL → persistent node name and location.  Only successful
+    // lookups are cached, so a node that was down at first contact is
+    // asked again.
+    let mut nodes: HashMap<String, NodeInfo> = HashMap::new();
+
+    // Capture nodes are tracked by a background mDNS browser, so the
+    // list is re-read on every round without blocking.
+    let watcher = discovery.and_then(|dh| match dh.watch(ServiceRole::Capture) {
+        Ok(w) => Some(w),
+        Err(e) => {
+            warn!("mDNS capture discovery unavailable: {e:#}");
+            None
+        }
+    });
+
     // Build initial list of capture URLs
-    let mut capture_urls = resolve_capture_urls(discovery, config);
+    let mut capture_urls =
+        resolve_capture_urls(watcher.as_ref(), config, STARTUP_DISCOVERY_WAIT);
     info!(
         "Polling {} capture server(s) every {}s: {:?}",
         capture_urls.len(),
@@ -81,7 +150,7 @@ pub fn poll_and_dispatch(
     // confirmation (or failure) in the logs immediately.
     for url in &capture_urls {
         match list_recordings(&client, url) {
-            Ok(r) => info!("[{url}] Reachable – {} recording(s) queued", r.len()),
+            Ok(page) => info!("[{url}] Reachable – {} recording(s) queued", page.total),
             Err(e) => warn!("[{url}] Not reachable at startup: {e:#}"),
         }
     }
@@ -90,14 +159,44 @@ pub fn poll_and_dispatch(
     if pruned > 0 {
         info!("Pruned {pruned} stale processing instance(s) from previous runs");
     }
-    let mut last_discovery = Instant::now();
+    let mut last_prune = Instant::now();
+
+    // Downloads run on their own threads so network I/O overlaps with
+    // analysis; failed ones come back on `retry_rx` to be tried again.
+    let (download_tx, download_rx) =
+        std::sync::mpsc::sync_channel::<DownloadJob>(config.download_threads * 2);
+    let download_rx = Arc::new(Mutex::new(download_rx));
+    let (retry_tx, retry_rx) = std::sync::mpsc::channel::<String>();
+    let mut downloaders = Vec::with_capacity(config.download_threads);
+    for id in 0..config.download_threads {
+        let client = client.clone();
+        let jobs = download_rx.clone();
+        let work_tx = work_tx.clone();
+        let retry_tx = retry_tx.clone();
+        let handle = std::thread::Builder::new()
+            .name(format!("download-{id}"))
+            .spawn(move || download_worker(&client, &jobs, &work_tx, &retry_tx, shutdown))
+            .with_context(|| format!("Cannot spawn download-{id}"))?;
+        downloaders.push(handle);
+    }
+    drop(retry_tx);
+    info!("{} download thread(s) started", config.download_threads);
 
-    loop {
+    let mut reloader = crate::reload::Reloader::default();
+    'poll: loop {
         if shutdown.load(Ordering::Relaxed) {
             break;
         }
 
-        // ── refresh settings from DB ─────────────────────────────
+        for key in retry_rx.try_iter() {
+            if let Err(e) = crate::journal::forget(&key) {
+                warn!("Cannot requeue {key}: {e:#}");
+            }
+        }
+
+        // ── refresh settings from gaia.conf and the DB ───────────
+        crate::reload::check();
+        reloader.apply(config);
         crate::kv::apply_settings_overrides(config);
 
         // ── heartbeat so coordination layer knows we're alive ────
@@ -116,7 +215,7 @@ pub fn poll_and_dispatch(
                     info!("No models enabled — idling (container stays running)");
                     IDLE_LOGGED.store(true, Ordering::Relaxed);
                 }
-                std::thread::sleep(poll_interval);
+                sleep_unless_shutdown(poll_interval, shutdown);
                 continue;
             } else if IDLE_LOGGED.swap(false, Ordering::Relaxed) {
                 info!(
@@ -126,26 +225,27 @@ pub fn poll_and_dispatch(
             }
         }
 
-        // ── periodic mDNS re-discovery ───────────────────────────────
-        if last_discovery.elapsed() >= REDISCOVERY_INTERVAL {
-            let new_urls = resolve_capture_urls(discovery, config);
+        // ── mDNS peer changes ────────────────────────────────────────
+        if watcher.is_some() {
+            let new_urls = resolve_capture_urls(watcher.as_ref(), config, Duration::ZERO);
             if new_urls != capture_urls {
                 info!("Capture node list updated: {:?}", new_urls);
                 capture_urls = new_urls;
+                nodes.clear();
             }
-            last_discovery = Instant::now();
         }
 
         // ── poll each capture server ─────────────────────────────────
         dispatched_this_round = 0;
+        let mut queues: Vec<NodeQueue> = Vec::new();
 
         for base_url in &capture_urls {
             if shutdown.load(Ordering::Relaxed) {
                 break;
             }
 
-            let recordings = match list_recordings(&client, base_url) {
-                Ok(r) => r,
+            let RecordingPage { recordings, total } = match list_recordings(&client, base_url) {
+                Ok(page) => page,
                 Err(e) => {
                     warn!("Cannot reach capture server {}: {e}", base_url);
                     continue;
@@ -162,58 +262,116 @@ pub fn poll_and_dispatch(
                 recordings.len()
             );
 
-            for rec in &recordings {
-                if shutdown.load(Ordering::Relaxed) {
-                    break;
-                }
+            let (source_node, source_node_id, location) = match nodes.get(base_url) {
+                Some(node) => (node.name.clone(), node.id.clone(), node.location),
+                None => match fetch_node_info(&client, base_url) {
+                    Some(node) => {
+                        match node.location {
+                            Some((lat, lon)) => info!(
+                                "[{base_url}] Capture node is \"{}\" at {lat:.4}, {lon:.4}",
+                                node.name
+                            ),
+                            None => info!("[{base_url}] Capture node is \"{}\"", node.name),
+                        }
+                        nodes.insert(base_url.clone(), node.clone());
+                        (node.name, node.id, node.location)
+                    }
+                    None => (base_url.clone(), base_url.clone(), None),
+                },
+            };
 
-                let key = format!("{}:{}", base_url, rec.filename);
-                if dispatched.contains(&key) {
-                    continue;
+            let listed = recordings.len();
+            let mut pending = VecDeque::new();
+            for rec in recordings {
+                let key = crate::journal::key(&source_node_id, &rec.filename);
+                match crate::journal::stage(&key) {
+                    Ok(None) => pending.push_back(rec),
+                    // Queued or in flight.
+                    Ok(Some(crate::journal::Stage::Dispatched)) => {}
+                    // Analysed, but the delete never happened (crash,
+                    // restart or capture node unreachable): finish it.
+                    Ok(Some(crate::journal::Stage::Analysed)) => {
+                        debug!(
+                            "[{}] {} already analysed — deleting it",
+                            base_url, rec.filename
+                        );
+                        if let Err(e) = delete_recording(&client, base_url, &rec.filename) {
+                            warn!("[{base_url}] Cannot delete {}: {e:#}", rec.filename);
+                        }
+                    }
+                    Err(e) => warn!("[{base_url}] Journal lookup for {} failed: {e:#}", rec.filename),
                 }
+            }
 
-                // Skip files this instance already processed in a previous
-                // run.  The dispatched HashSet is in-memory only and lost on
-                // restart, but the Redis processed set persists (with TTL).
-                if crate::kv::is_file_processed(&rec.filename, "default") {
-                    debug!(
-                        "[{}] Skipping {} — already processed by this instance",
-                        base_url, rec.filename
-                    );
-                    dispatched.insert(key);
-                    continue;
-                }
+            let priority = config
+                .node_priorities
+                .get(&source_node)
+                .or_else(|| config.node_priorities.get(base_url))
+                .copied()
+                .unwrap_or(1);
+            queues.push(NodeQueue {
+                base_url: base_url.clone(),
+                source_node,
+                source_node_id,
+                location,
+                priority,
+                pending,
+                unlisted: total.saturating_sub(listed),
+            });
+        }
 
-                debug!(
-                    "[{}] New recording: {} ({} bytes)",
-                    base_url, rec.filename, rec.size
-                );
+        publish_backlog(&queues);
 
-                // ── download ─────────────────────────────────────────
-                let local_path = tmp_dir.join(&rec.filename);
-                match download_recording(&client, base_url, &rec.filename, &local_path) {
-                    Ok(()) => {}
-                    Err(e) => {
-                        error!("Failed to download {}: {e}", rec.filename);
-                        continue;
-                    }
-                }
+        // ── dispatch, round-robin across nodes ───────────────────────
+        let weights: Vec<(usize, u32)> = queues
+            .iter()
+            .map(|q| (q.pending.len(), q.priority))
+            .collect();
+        for idx in fair_order(&weights) {
+            if shutdown.load(Ordering::Relaxed) {
+                break;
+            }
+            let queue = &mut queues[idx];
+            let Some(rec) = queue.pending.pop_front() else {
+                continue;
+            };
+            let base_url = &queue.base_url;
 
-                // ── dispatch to worker pool ──────────────────────────
-                let item = WorkItem {
-                    local_path,
-                    filename: rec.filename.clone(),
-                    base_url: base_url.clone(),
-                    config_snapshot: config.clone(),
-                };
-                if work_tx.send(item).is_err() {
-                    warn!("Work channel closed — stopping dispatch");
-                    return Ok(());
-                }
+            debug!(
+                "[{}] New recording: {} ({} bytes)",
+                base_url, rec.filename, rec.size
+            );
 
-                dispatched.insert(key);
-                dispatched_this_round += 1;
+            // ── hand over to the download pool ───────────────────────
+            let key = crate::journal::key(&queue.source_node_id, &rec.filename);
+            if let Err(e) = crate::journal::mark(&key, crate::journal::Stage::Dispatched) {
+                warn!("[{base_url}] Cannot journal {}: {e:#} — skipping", rec.filename);
+                continue;
+            }
+            let mut node_config = config.clone();
+            if let Some((lat, lon)) = queue.location {
+                node_config.latitude = lat;
+                node_config.longitude = lon;
             }
+            // Nodes name recordings alike; one download directory each.
+            let node_dir = tmp_dir.join(safe_path_component(&queue.source_node_id));
+            let job = DownloadJob {
+                local_path: node_dir.join(&rec.filename),
+                key,
+                base_url: base_url.clone(),
+                source_node: queue.source_node.clone(),
+                source_node_id: queue.source_node_id.clone(),
+                filename: rec.filename,
+                config: node_config,
+            };
+            if download_tx.send(job).is_err() {
+                warn!("Download pool stopped — stopping dispatch");
+                break 'poll;
+            }
+            crate::activity::enqueue(crate::activity::Queue::Download);
+
+            dispatched_this_round += 1;
+            publish_backlog(&queues);
         }
 
         if dispatched_this_round > 0 {
@@ -228,11 +386,12 @@ pub fn poll_and_dispatch(
             }
         }
 
-        // Prevent unbounded growth of the dispatched set
-        if dispatched.len() > 10_000 {
-            dispatched.clear();
-            crate::kv::cleanup_processing_log();
+        if last_prune.elapsed() >= JOURNAL_PRUNE_INTERVAL {
+            if let Err(e) = crate::journal::prune() {
+                warn!("Cannot prune processing journal: {e:#}");
+            }
             crate::kv::prune_stale_instances(10);
+            last_prune = Instant::now();
         }
 
         // Only skip the sleep when we actually dispatched new work
@@ -242,44 +401,198 @@ pub fn poll_and_dispatch(
         // the logs and waste CPU.
         if dispatched_this_round == 0 {
             debug!("No new recordings to dispatch – sleeping {poll_interval:?}");
-            std::thread::sleep(poll_interval);
+            sleep_unless_shutdown(poll_interval, shutdown);
         }
     }
 
+    // Let queued downloads finish (or be skipped on shutdown).
+    drop(download_tx);
+    for h in downloaders {
+        h.join().ok();
+    }
+
     info!("Polling loop stopped");
     Ok(())
 }
 
+/// A recording waiting for a download thread.
+struct DownloadJob {
+    key: String,
+    base_url: String,
+    source_node: String,
+    source_node_id: String,
+    filename: String,
+    local_path: PathBuf,
+    config: Config,
+}
+
+/// Download thread: fetch queued recordings and pass them to the worker
+/// pool.  Returns when the job queue or the work channel closes.
+fn download_worker(
+    client: &CaptureClient,
+    jobs: &Mutex<Receiver<DownloadJob>>,
+    work_tx: &SyncSender<WorkItem>,
+    retry_tx: &Sender<String>,
+    shutdown: &AtomicBool,
+) {
+    loop {
+        let job = {
+            let Ok(rx) = jobs.lock() else { return };
+            rx.recv()
+        };
+        let Ok(job) = job else { return };
+        crate::activity::dequeue(crate::activity::Queue::Download);
+        // Not fetched yet: it stays on the capture node for next time.
+        if shutdown.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        let hash = match download_recording(client, &job.base_url, &job.filename, &job.local_path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                error!("Failed to download {}: {e}", job.filename);
+                let _ = retry_tx.send(job.key);
+                continue;
+            }
+        };
+        if is_duplicate(client, &job, &hash) {
+            continue;
+        }
+
+        let item = WorkItem {
+            local_path: job.local_path,
+            filename: job.filename,
+            base_url: job.base_url,
+            source_node: job.source_node,
+            source_node_id: job.source_node_id,
+            expected_secs: Some(job.config.recording_length as f64),
+            config_snapshot: job.config,
+        };
+        crate::activity::enqueue(crate::activity::Queue::Analysis);
+        if work_tx.send(item).is_err() {
+            warn!("Work channel closed — stopping downloads");
+            return;
+        }
+    }
+}
+
+/// Whether `job` has the same content as a recording analysed within
+/// `DEDUP_WINDOW_HOURS`.  A duplicate is journaled as analysed and
+/// deleted locally and on the capture node instead of being analysed.
+fn is_duplicate(client: &CaptureClient, job: &DownloadJob, hash: &str) -> bool {
+    if job.config.dedup_window_hours == 0 {
+        return false;
+    }
+    let window = Duration::from_secs(u64::from(job.config.dedup_window_hours) * 3600);
+    let original = match crate::journal::claim_content(&job.key, hash, window) {
+        Ok(Some(original)) => original,
+        Ok(None) => return false,
+        Err(e) => {
+            warn!("Cannot check {} for duplicates: {e:#}", job.filename);
+            return false;
+        }
+    };
+    info!("{} is identical to {original} — skipping analysis", job.key);
+    if let Err(e) = crate::journal::mark(&job.key, crate::journal::Stage::Analysed) {
+        warn!("Cannot journal {}: {e:#}", job.filename);
+    }
+    let _ = std::fs::remove_file(&job.local_path);
+    // A failed delete is retried by the polling loop like any other.
+    if let Err(e) = delete_recording(client, &job.base_url, &job.filename) {
+        warn!("Failed to delete duplicate {}: {e}", job.filename);
+    }
+    true
+}
+
+/// Order in which to take recordings from each queue this round, given
+/// each queue's `(pending, priority)`.
+///
+/// Weighted round-robin: every pass gives each queue `priority` turns,
+/// higher priorities first, until it has had `priority * ROUND_QUOTA`
+/// turns or runs out of recordings.
+fn fair_order(queues: &[(usize, u32)]) -> Vec<usize> {
+    let mut by_priority: Vec<usize> = (0..queues.len()).collect();
+    by_priority.sort_by_key(|&i| std::cmp::Reverse(queues[i].1));
+
+    let mut taken = vec![0usize; queues.len()];
+    let mut order = Vec::new();
+    loop {
+        let mut progressed = false;
+        for &i in &by_priority {
+            let (pending, priority) = queues[i];
+            let weight = priority.max(1) as usize;
+            let cap = pending.min(weight * ROUND_QUOTA);
+            let turns = weight.min(cap - taken[i]);
+            order.extend(std::iter::repeat(i).take(turns));
+            taken[i] += turns;
+            progressed |= turns > 0;
+        }
+        if !progressed {
+            return order;
+        }
+    }
+}
+
+/// Record the per-node backlog for [`node_backlog`].  Nodes that were not
+/// reachable this round keep their last known value.
+fn publish_backlog(queues: &[NodeQueue]) {
+    let Ok(mut backlog) = BACKLOG.lock() else {
+        return;
+    };
+    for q in queues {
+        backlog.insert(
+            q.source_node.clone(),
+            NodeBacklog {
+                node: q.source_node.clone(),
+                pending: q.pending.len() + q.unlisted,
+                priority: q.priority,
+            },
+        );
+    }
+}
+
+/// Recordings waiting to be analysed on each capture node seen so far.
+pub fn node_backlog() -> Vec<NodeBacklog> {
+    BACKLOG
+        .lock()
+        .map(|b| b.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Sleep for `d`, waking early once `shutdown` is set.
+fn sleep_unless_shutdown(d: Duration, shutdown: &AtomicBool) {
+    let deadline = Instant::now() + d;
+    while !shutdown.load(Ordering::Relaxed) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(200)));
+    }
+}
+
 /// Resolve the list of capture server URLs.
 ///
-/// Tries mDNS first (with a retry); falls back to the config value when
-/// mDNS is unavailable or discovers no capture nodes.
+/// Reads the mDNS peer cache, waiting up to `wait` for a first capture
+/// node; falls back to the config value when mDNS is unavailable or knows
+/// no capture nodes.
 fn resolve_capture_urls(
-    discovery: Option<&DiscoveryHandle>,
+    watcher: Option<&PeerWatcher>,
     config: &Config,
+    wait: Duration,
 ) -> Vec<String> {
-    if let Some(dh) = discovery {
-        // Try twice: the first scan may miss the capture node if it
-        // registered just moments before us and the mDNS cache hasn't
-        // propagated yet.
-        for attempt in 1..=2 {
-            let timeout = if attempt == 1 { 5 } else { 3 };
-            let peers = dh.discover_peers(
-                ServiceRole::Capture,
-                Duration::from_secs(timeout),
-            );
-            if !peers.is_empty() {
-                let urls: Vec<String> = peers
-                    .iter()
-                    .filter_map(|p| p.http_url())
-                    .collect();
-                info!("mDNS discovered {} capture node(s): {:?}", urls.len(), urls);
-                return urls;
-            }
-            if attempt == 1 {
-                debug!("mDNS scan {attempt}: no peers yet, retrying…");
-            }
-        }
+    let Some(watcher) = watcher else {
+        return vec![config.capture_server_url.clone()];
+    };
+    let urls: Vec<String> = watcher
+        .wait_for_peers(wait)
+        .iter()
+        .filter_map(|p| p.http_url())
+        .collect();
+    if !urls.is_empty() {
+        return urls;
+    }
+    if !wait.is_zero() {
         info!("No capture nodes found via mDNS, falling back to config URL");
     }
     vec![config.capture_server_url.clone()]
@@ -287,40 +600,80 @@ fn resolve_capture_urls(
 
 // ── HTTP helpers ─────────────────────────────────────────────────────────
 
-fn list_recordings(
-    client: &reqwest::blocking::Client,
-    base_url: &str,
-) -> Result<Vec<RecordingInfo>> {
-    let url = format!("{base_url}/api/recordings");
-    let resp = client.get(&url).send().context("GET /api/recordings")?;
-
-    if !resp.status().is_success() {
-        anyhow::bail!("GET /api/recordings returned {}", resp.status());
-    }
+/// HTTP client for talking to capture servers: bearer token and extra
+/// root certificate from the config, 30 s timeout.
+pub fn capture_client(config: &Config) -> Result<CaptureClient> {
+    Ok(CaptureClient::from_config(config)?)
+}
 
-    let recordings: Vec<RecordingInfo> = resp.json().context("Parse recordings JSON")?;
+/// The oldest [`LIST_PAGE_SIZE`] recordings on a capture node.
+fn list_recordings(client: &CaptureClient, base_url: &str) -> Result<RecordingPage> {
+    crate::faults::check(Fault::CaptureTimeout).context("GET /api/recordings")?;
+    let page = client.list_recordings(base_url, LIST_PAGE_SIZE)?;
     debug!(
-        "[{base_url}] GET /api/recordings → {} file(s)",
-        recordings.len()
+        "[{base_url}] GET /api/recordings → {} of {} file(s)",
+        page.recordings.len(),
+        page.total
     );
-    Ok(recordings)
+    Ok(page)
 }
 
+/// The capture node's persistent id, name and location, or `None` when
+/// unreachable or too old to report a name.
+fn fetch_node_info(client: &CaptureClient, base_url: &str) -> Option<NodeInfo> {
+    let health = client.health(base_url).ok()?;
+    check_protocol(base_url, &health);
+    if health.node_name.is_empty() {
+        return None;
+    }
+    // Nodes that report a name also report an id; fall back to the name
+    // all the same rather than storing an empty id.
+    let id = if health.node_id.is_empty() {
+        health.node_name.clone()
+    } else {
+        health.node_id
+    };
+    Some(NodeInfo {
+        id,
+        name: health.node_name,
+        location: health.latitude.zip(health.longitude),
+    })
+}
+
+/// Warn when the capture node at `base_url` speaks another protocol
+/// version, so a mixed-version fleet fails loudly.
+fn check_protocol(base_url: &str, health: &HealthResponse) {
+    let Some(problem) = protocol::protocol_mismatch(health.protocol) else {
+        return;
+    };
+    let key = format!("{base_url}@{}", health.protocol);
+    let first = PROTOCOL_WARNED
+        .lock()
+        .map(|mut warned| warned.insert(key))
+        .unwrap_or(true);
+    if first {
+        let version = match health.version.as_str() {
+            "" => String::new(),
+            v => format!(" {v}"),
+        };
+        warn!(
+            "[{base_url}] Capture node{version} {problem}; \
+             recordings may fail to download or be misread"
+        );
+    }
+}
+
+/// Fetch `filename` into `out_path`; returns its content hash.
 fn download_recording(
-    client: &reqwest::blocking::Client,
+    client: &CaptureClient,
     base_url: &str,
     filename: &str,
     out_path: &Path,
-) -> Result<()> {
-    let url = format!("{base_url}/api/recordings/{filename}");
+) -> Result<String> {
     let t0 = Instant::now();
-    let resp = client.get(&url).send().context("GET recording")?;
-
-    if !resp.status().is_success() {
-        anyhow::bail!("GET {} returned {}", url, resp.status());
-    }
-
-    let bytes = resp.bytes()?;
+    crate::faults::check(Fault::CaptureTimeout).context("GET recording")?;
+    let mut bytes = client.download(base_url, filename)?;
+    crate::faults::maybe_corrupt(&mut bytes);
     if let Some(parent) = out_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -341,28 +694,16 @@ fn download_recording(
         rate
     );
     info!("Downloaded {} → {}", filename, out_path.display());
-    Ok(())
+    Ok(crate::journal::content_hash(&bytes))
 }
 
-#[allow(dead_code)]
-fn delete_recording(
-    client: &reqwest::blocking::Client,
-    base_url: &str,
-    filename: &str,
-) -> Result<()> {
-    let url = format!("{base_url}/api/recordings/{filename}");
-    let resp = client.delete(&url).send().context("DELETE recording")?;
-
-    if resp.status().is_success() || resp.status() == reqwest::StatusCode::NOT_FOUND {
-        debug!(
-            "DELETE {filename} from capture server → {} ({})",
-            resp.status(),
-            if resp.status().is_success() { "removed" } else { "already gone" }
-        );
-        Ok(())
-    } else {
-        anyhow::bail!("DELETE {} returned {}", url, resp.status())
-    }
+fn delete_recording(client: &CaptureClient, base_url: &str, filename: &str) -> Result<()> {
+    let removed = client.delete(base_url, filename)?;
+    debug!(
+        "DELETE {filename} from capture server → {}",
+        if removed { "removed" } else { "already gone" }
+    );
+    Ok(())
 }
 
 /// Download a specific recording to a local path. Utility for one-shot use.
@@ -372,8 +713,27 @@ pub fn fetch_recording(
     filename: &str,
     out_path: &PathBuf,
 ) -> Result<()> {
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()?;
-    download_recording(&client, &config.capture_server_url, filename, out_path)
+    let client = capture_client(config)?;
+    download_recording(&client, &config.capture_server_url, filename, out_path).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fair_order() {
+        // A backlogged node doesn't starve a real-time one.
+        let order = fair_order(&[(100, 1), (2, 1)]);
+        assert_eq!(&order[..4], &[0, 1, 0, 1]);
+        assert_eq!(order.len(), ROUND_QUOTA + 2);
+
+        // Higher priority goes first and gets proportionally more turns.
+        let order = fair_order(&[(100, 1), (100, 3)]);
+        assert_eq!(&order[..5], &[1, 1, 1, 0, 1]);
+        assert_eq!(order.iter().filter(|&&i| i == 1).count(), 3 * ROUND_QUOTA);
+        assert_eq!(order.iter().filter(|&&i| i == 0).count(), ROUND_QUOTA);
+
+        assert!(fair_order(&[(0, 5)]).is_empty());
+    }
 }
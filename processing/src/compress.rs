@@ -1,12 +1,16 @@
-//! Periodic compression of extracted audio clips to Opus.
+//! Transcoding of extracted audio clips for web delivery.
 //!
-//! Walks `{extracted_dir}/By_Date/` looking for `.wav` and `.mp3` files,
-//! converts each to Opus via `ffmpeg`, renames the companion spectrogram
-//! PNG, and updates the `File_Name` column in the database so the web
-//! dashboard keeps working.
+//! Clips are extracted as 16-bit WAV (spectrograms and call features are
+//! computed from that) and then transcoded to `CLIP_FORMAT` — Opus by
+//! default, MP3 for browsers without Opus support — via `ffmpeg`.  The
+//! companion spectrogram PNG and waveform peaks are renamed to follow the
+//! clip, and the WAV is deleted unless `KEEP_WAV_CLIPS` is set.
 //!
-//! Designed to run on a background thread 4× per day (every 6 hours).
-//! Each run is idempotent — already-compressed files (`.opus`) are skipped.
+//! A background sweep walks `{extracted_dir}/By_Date/` for `.wav` and
+//! `.mp3` clips that were missed (ffmpeg unavailable, BirdNET-Pi imports)
+//! and converts them the same way.  Each run is idempotent.  `File_Name`
+//! in the detection store is not rewritten; the web server resolves a
+//! stale extension to the transcoded sibling.
 
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -15,9 +19,67 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use anyhow::{Context, Result};
 use tracing::{debug, error, info, warn};
 
-/// Opus encoding bitrate.  96 kbps is transparent quality for bird /
-/// wildlife audio and speech.
-const OPUS_BITRATE: &str = "96k";
+use gaia_common::config::Config;
+
+/// Delivery format for extracted clips (`CLIP_FORMAT`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipFormat {
+    /// Serve the extracted WAV as-is.
+    Wav,
+    Opus,
+    Mp3,
+}
+
+impl ClipFormat {
+    /// Parse `CLIP_FORMAT`; unknown values fall back to Opus.
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "wav" | "none" | "off" => Self::Wav,
+            "mp3" => Self::Mp3,
+            "opus" | "" => Self::Opus,
+            other => {
+                warn!("Unknown CLIP_FORMAT '{other}', using opus");
+                Self::Opus
+            }
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Opus => "opus",
+            Self::Mp3 => "mp3",
+        }
+    }
+
+    fn codec(self) -> Option<&'static str> {
+        match self {
+            Self::Wav => None,
+            Self::Opus => Some("libopus"),
+            Self::Mp3 => Some("libmp3lame"),
+        }
+    }
+}
+
+/// How extracted clips are transcoded.
+#[derive(Debug, Clone)]
+pub struct ClipEncoding {
+    pub format: ClipFormat,
+    /// ffmpeg bitrate (`96k` is transparent for bird / wildlife audio).
+    pub bitrate: String,
+    /// Keep the WAV next to the transcoded clip.
+    pub keep_wav: bool,
+}
+
+impl ClipEncoding {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            format: ClipFormat::parse(&config.clip_format),
+            bitrate: config.clip_bitrate.clone(),
+            keep_wav: config.keep_wav_clips,
+        }
+    }
+}
 
 /// Run one compression sweep over the extracted directory.
 ///
@@ -25,8 +87,13 @@ const OPUS_BITRATE: &str = "96k";
 pub fn compress_sweep(
     extracted_dir: &Path,
     _db_path: &Path,
+    encoding: &ClipEncoding,
     shutdown: &AtomicBool,
 ) -> Result<u64> {
+    if encoding.format == ClipFormat::Wav {
+        debug!("CLIP_FORMAT=wav — nothing to transcode");
+        return Ok(0);
+    }
     let by_date = extracted_dir.join("By_Date");
     if !by_date.is_dir() {
         debug!("No By_Date directory yet — nothing to compress");
@@ -39,9 +106,9 @@ pub fn compress_sweep(
         return Ok(0);
     }
 
-    let candidates = collect_candidates(&by_date)?;
+    let candidates = collect_candidates(&by_date, encoding)?;
     if candidates.is_empty() {
-        info!("Compression sweep: no WAV/MP3 clips to convert");
+        info!("Compression sweep: no clips to convert");
         return Ok(0);
     }
 
@@ -59,7 +126,7 @@ pub fn compress_sweep(
             break;
         }
 
-        match convert_one(src_path) {
+        match convert_one(src_path, encoding) {
             Ok(()) => converted += 1,
             Err(e) => {
                 warn!("Failed to compress {}: {e:#}", src_path.display());
@@ -77,12 +144,16 @@ pub fn compress_sweep(
 pub fn compress_loop(
     extracted_dir: PathBuf,
     db_path: PathBuf,
+    encoding: ClipEncoding,
     interval: std::time::Duration,
     shutdown: &AtomicBool,
 ) {
     info!(
-        "Compression thread started (interval={}h, bitrate={OPUS_BITRATE})",
-        interval.as_secs() / 3600
+        "Compression thread started (interval={}min, format={}, bitrate={}, keep_wav={})",
+        interval.as_secs() / 60,
+        encoding.format.extension(),
+        encoding.bitrate,
+        encoding.keep_wav
     );
 
     // Run one initial sweep shortly after startup (give processing 60 s
@@ -94,7 +165,7 @@ pub fn compress_loop(
             break;
         }
 
-        match compress_sweep(&extracted_dir, &db_path, shutdown) {
+        match compress_sweep(&extracted_dir, &db_path, &encoding, shutdown) {
             Ok(n) if n > 0 => info!("Compressed {n} file(s) this cycle"),
             Ok(_) => {}
             Err(e) => error!("Compression sweep error: {e:#}"),
@@ -108,84 +179,46 @@ pub fn compress_loop(
 
 // ── Inline single-clip conversion ────────────────────────────────────────
 
-/// Convert a single extracted WAV clip to Opus immediately after
-/// extraction + spectrogram generation.
+/// Transcode a single extracted WAV clip right after extraction +
+/// spectrogram generation.
 ///
-/// Returns the path to the new `.opus` file on success, or `None` if
-/// ffmpeg is unavailable or the conversion fails.  The caller should
-/// use the returned path (or fall back to the original WAV path) when
-/// storing the filename in the database.
-pub fn compress_inline(wav_path: &Path) -> Option<PathBuf> {
-    if !ffmpeg_available() {
-        debug!("ffmpeg not available — skipping inline Opus conversion");
+/// Returns the path to the transcoded clip on success, or `None` if
+/// `CLIP_FORMAT=wav`, ffmpeg is unavailable or the conversion fails.
+/// The caller should use the returned path (or fall back to the WAV
+/// path) when storing the filename in the database.
+pub fn compress_inline(wav_path: &Path, encoding: &ClipEncoding) -> Option<PathBuf> {
+    if encoding.format == ClipFormat::Wav {
         return None;
     }
-
-    let src_name = wav_path.file_name()?.to_string_lossy().to_string();
-    if !src_name.ends_with(".wav") {
+    if !ffmpeg_available() {
+        debug!("ffmpeg not available — skipping inline clip transcoding");
         return None;
     }
-
-    let opus_name = format!("{}.opus", &src_name[..src_name.len() - 4]);
-    let opus_path = wav_path.with_file_name(&opus_name);
-
-    // If the Opus file already exists, just clean up the WAV source.
-    if opus_path.exists() {
-        debug!("Opus already exists, removing WAV source: {}", wav_path.display());
-        std::fs::remove_file(wav_path).ok();
-        return Some(opus_path);
-    }
-
-    let status = Command::new("ffmpeg")
-        .args([
-            "-y",
-            "-i",
-            &wav_path.to_string_lossy(),
-            "-c:a",
-            "libopus",
-            "-b:a",
-            OPUS_BITRATE,
-            "-vn",
-            "-loglevel",
-            "error",
-        ])
-        .arg(&opus_path)
-        .status()
-        .ok()?;
-
-    if !status.success() {
-        warn!("Inline Opus conversion failed for {}", wav_path.display());
+    if wav_path.extension().and_then(|e| e.to_str()) != Some("wav") {
         return None;
     }
-
-    // Rename companion spectrogram: .wav.png → .opus.png
-    let old_spec = wav_path.with_file_name(format!("{src_name}.png"));
-    if old_spec.exists() {
-        let new_spec = wav_path.with_file_name(format!("{opus_name}.png"));
-        if let Err(e) = std::fs::rename(&old_spec, &new_spec) {
-            warn!(
-                "Cannot rename spectrogram {} → {}: {e}",
-                old_spec.display(),
-                new_spec.display()
-            );
+    match transcode(wav_path, encoding) {
+        Ok(dest) => Some(dest),
+        Err(e) => {
+            warn!("Inline transcoding failed for {}: {e:#}", wav_path.display());
+            None
         }
     }
-
-    // Remove original WAV
-    if let Err(e) = std::fs::remove_file(wav_path) {
-        warn!("Cannot remove original WAV {}: {e}", wav_path.display());
-    }
-
-    debug!("Inline compressed: {src_name} → {opus_name}");
-    Some(opus_path)
 }
 
 // ── Internals ────────────────────────────────────────────────────────────
 
-/// Recursively collect `.wav` and `.mp3` files under `dir`.
-fn collect_candidates(dir: &Path) -> Result<Vec<PathBuf>> {
+/// Recursively collect `.wav` and `.mp3` clips under `dir` that are not
+/// yet in the target format.  Kept WAV originals whose transcoded
+/// sibling exists are left alone.
+fn collect_candidates(dir: &Path, encoding: &ClipEncoding) -> Result<Vec<PathBuf>> {
     let mut out = Vec::new();
     walk_dir(dir, &mut out)?;
+    let target = encoding.format.extension();
+    out.retain(|p| {
+        let ext = p.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        ext != target && !(encoding.keep_wav && ext == "wav" && p.with_extension(target).exists())
+    });
     Ok(out)
 }
 
@@ -216,75 +249,87 @@ fn walk_dir(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
-/// Convert a single `.wav` or `.mp3` file to `.opus`, rename its
-/// spectrogram.  (File_Name updates in the old SQLite detections table
-/// are no longer needed — detections are stored in Parquet.)
-fn convert_one(src_path: &Path) -> Result<()> {
+/// Convert a single `.wav` or `.mp3` clip to the target format.
+/// (File_Name updates in the old SQLite detections table are no longer
+/// needed — detections are stored in Parquet.)
+fn convert_one(src_path: &Path, encoding: &ClipEncoding) -> Result<()> {
+    let ext = src_path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    if ext != "wav" && ext != "mp3" {
+        anyhow::bail!("Unexpected extension: {}", src_path.display());
+    }
+    transcode(src_path, encoding)?;
+    Ok(())
+}
+
+/// Encode `src` next to itself in the target format, move its
+/// spectrogram (`{src}.png` → `{dest}.png`) and remove `src` — unless it
+/// is a WAV and `keep_wav` is set.  Returns the new clip's path.
+fn transcode(src_path: &Path, encoding: &ClipEncoding) -> Result<PathBuf> {
+    let codec = encoding.format.codec().context("No codec for WAV output")?;
     let src_name = src_path
         .file_name()
         .context("No filename")?
-        .to_string_lossy();
-
-    // Determine the old extension and build the new filename.
-    let (_old_ext, new_name) = if src_name.ends_with(".wav") {
-        (".wav", format!("{}.opus", &src_name[..src_name.len() - 4]))
-    } else if src_name.ends_with(".mp3") {
-        (".mp3", format!("{}.opus", &src_name[..src_name.len() - 4]))
-    } else {
-        anyhow::bail!("Unexpected extension: {src_name}");
-    };
+        .to_string_lossy()
+        .to_string();
+    let dest_path = src_path.with_extension(encoding.format.extension());
+    let dest_name = dest_path.file_name().context("No filename")?.to_string_lossy().to_string();
+    let keep_src = encoding.keep_wav && src_name.ends_with(".wav");
 
-    let dest_path = src_path.with_file_name(&new_name);
-
-    // If the opus file already exists, just clean up the source
     if dest_path.exists() {
-        debug!("Opus already exists, removing source: {}", src_path.display());
-        std::fs::remove_file(src_path).ok();
-        return Ok(());
-    }
-
-    // Convert via ffmpeg
-    let status = Command::new("ffmpeg")
-        .args([
-            "-y",           // overwrite
-            "-i",
-            &src_path.to_string_lossy(),
-            "-c:a",
-            "libopus",
-            "-b:a",
-            OPUS_BITRATE,
-            "-vn",          // no video
-            "-loglevel",
-            "error",
-        ])
-        .arg(&dest_path)
-        .status()
-        .context("Cannot run ffmpeg")?;
-
-    if !status.success() {
-        anyhow::bail!("ffmpeg exited with {status}");
+        debug!("{dest_name} already exists");
+    } else {
+        // Encode to a temporary name so a half-written clip is never served.
+        let tmp_path = src_path.with_file_name(format!(".{dest_name}.tmp"));
+        let status = Command::new("ffmpeg")
+            .args([
+                "-y",           // overwrite
+                "-i",
+                &src_path.to_string_lossy(),
+                "-c:a",
+                codec,
+                "-b:a",
+                &encoding.bitrate,
+                "-vn",          // no video
+                "-f",
+                encoding.format.extension(),
+                "-loglevel",
+                "error",
+            ])
+            .arg(&tmp_path)
+            .status()
+            .context("Cannot run ffmpeg")?;
+        if !status.success() {
+            std::fs::remove_file(&tmp_path).ok();
+            anyhow::bail!("ffmpeg exited with {status}");
+        }
+        std::fs::rename(&tmp_path, &dest_path)
+            .with_context(|| format!("Cannot rename {}", tmp_path.display()))?;
     }
 
-    // Rename companion spectrogram: .wav.png → .opus.png (or .mp3.png → .opus.png)
-    let old_spec = src_path.with_file_name(format!("{src_name}.png"));
-    if old_spec.exists() {
-        let new_spec = src_path.with_file_name(format!("{new_name}.png"));
-        if let Err(e) = std::fs::rename(&old_spec, &new_spec) {
-            warn!(
-                "Cannot rename spectrogram {} → {}: {e}",
-                old_spec.display(),
-                new_spec.display()
-            );
+    // Rename companion spectrogram and waveform peaks:
+    // .wav.png → .opus.png, .wav.peaks.json → .opus.peaks.json (or .mp3.* → …)
+    for suffix in [".png", ".peaks.json"] {
+        let old_side = src_path.with_file_name(format!("{src_name}{suffix}"));
+        if old_side.exists() {
+            let new_side = src_path.with_file_name(format!("{dest_name}{suffix}"));
+            if let Err(e) = std::fs::rename(&old_side, &new_side) {
+                warn!(
+                    "Cannot rename {} → {}: {e}",
+                    old_side.display(),
+                    new_side.display()
+                );
+            }
         }
     }
 
-    // Remove original source file
-    if let Err(e) = std::fs::remove_file(src_path) {
-        warn!("Cannot remove original {}: {e}", src_path.display());
+    if !keep_src {
+        if let Err(e) = std::fs::remove_file(src_path) {
+            warn!("Cannot remove original {}: {e}", src_path.display());
+        }
     }
 
-    debug!("Compressed: {src_name} → {new_name}");
-    Ok(())
+    debug!("Transcoded: {src_name} → {dest_name}");
+    Ok(dest_path)
 }
 
 /// Update `File_Name` in the detections table.
@@ -320,11 +365,19 @@ fn sleep_interruptible(duration: std::time::Duration, shutdown: &AtomicBool) {
 mod tests {
     use super::*;
 
+    fn opus(keep_wav: bool) -> ClipEncoding {
+        ClipEncoding {
+            format: ClipFormat::Opus,
+            bitrate: "96k".into(),
+            keep_wav,
+        }
+    }
+
     #[test]
     fn test_collect_candidates_empty() {
         let dir = std::env::temp_dir().join("gaia_compress_test_empty");
         std::fs::create_dir_all(&dir).unwrap();
-        let c = collect_candidates(&dir).unwrap();
+        let c = collect_candidates(&dir, &opus(false)).unwrap();
         assert!(c.is_empty());
         let _ = std::fs::remove_dir_all(&dir);
     }
@@ -345,7 +398,7 @@ mod tests {
         std::fs::write(sub.join("det.mp3.png"), b"").unwrap();
         std::fs::write(sub.join("notes.txt"), b"").unwrap();
 
-        let c = collect_candidates(&dir).unwrap();
+        let c = collect_candidates(&dir, &opus(false)).unwrap();
         assert_eq!(c.len(), 2);
         assert!(c.iter().all(|p| {
             let name = p.file_name().unwrap().to_string_lossy();
@@ -355,6 +408,41 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_collect_candidates_respects_format_and_kept_wavs() {
+        let dir = std::env::temp_dir().join("gaia_compress_test_format");
+        let sub = dir.join("2026-01-01").join("Blackbird");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(sub.join("kept.wav"), b"").unwrap();
+        std::fs::write(sub.join("kept.opus"), b"").unwrap();
+        std::fs::write(sub.join("new.wav"), b"").unwrap();
+        std::fs::write(sub.join("import.mp3"), b"").unwrap();
+
+        let names = |enc: &ClipEncoding| {
+            let mut v: Vec<String> = collect_candidates(&dir, enc)
+                .unwrap()
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            v.sort();
+            v
+        };
+        assert_eq!(names(&opus(true)), ["import.mp3", "new.wav"]);
+        assert_eq!(names(&opus(false)), ["import.mp3", "kept.wav", "new.wav"]);
+        let mp3 = ClipEncoding { format: ClipFormat::Mp3, ..opus(false) };
+        assert_eq!(names(&mp3), ["kept.wav", "new.wav"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clip_format_parse() {
+        assert_eq!(ClipFormat::parse("MP3"), ClipFormat::Mp3);
+        assert_eq!(ClipFormat::parse("wav"), ClipFormat::Wav);
+        assert_eq!(ClipFormat::parse(""), ClipFormat::Opus);
+        assert_eq!(ClipFormat::parse("flac"), ClipFormat::Opus);
+    }
+
     #[test]
     fn test_update_db_filename_noop() {
         // update_db_filename is now a no-op (detections are in Parquet).
@@ -115,15 +115,15 @@ async fn get_or_open_db(db_path: &Path) -> Result<&'static libsql::Database> {
 
 /// Return the cached `Connection`, creating it on first call.
 ///
-/// Only sets `busy_timeout` — WAL and synchronous are configured
-/// once in [`initialize()`].
+/// Only sets `busy_timeout` and `foreign_keys` — WAL and synchronous
+/// are configured once in [`initialize()`].
 async fn get_or_open_conn(db_path: &Path) -> Result<&'static libsql::Connection> {
     if let Some(c) = CONN.get() {
         return Ok(c);
     }
     let db = get_or_open_db(db_path).await?;
     let c = db.connect().context("Cannot connect to database")?;
-    c.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS};"))
+    c.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS}; PRAGMA foreign_keys=ON;"))
         .await
         .context("Failed to set busy_timeout")?;
     let _ = CONN.set(c);
@@ -152,7 +152,7 @@ pub fn open_conn_pub(db_path: &Path) -> Result<libsql::Connection> {
     let db = rt().block_on(get_or_open_db(db_path))?;
     let c = db.connect().context("Cannot create connection")?;
     rt().block_on(async {
-        c.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS};"))
+        c.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS}; PRAGMA foreign_keys=ON;"))
             .await
             .context("Failed to set busy_timeout")
     })?;
@@ -190,12 +190,13 @@ pub fn is_urban_noise(sci_name: &str) -> bool {
         .any(|&label| sci_name.eq_ignore_ascii_case(label))
 }
 
-/// Create the `detections` table (and indices) if it doesn't exist.
+/// Bring the database up to the latest schema
+/// (see [`gaia_common::schema`]).
 ///
 /// **Must** be called once at startup. Sets WAL mode and
 /// `synchronous=NORMAL` — no other code path needs to touch those
-/// PRAGMAs.
-pub fn initialize(db_path: &Path) -> Result<()> {
+/// PRAGMAs.  Returns whether any migration was applied.
+pub fn initialize(db_path: &Path) -> Result<bool> {
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -211,104 +212,25 @@ pub fn initialize(db_path: &Path) -> Result<()> {
         .await
         .context("Failed to set WAL mode")?;
 
-        conn.execute_batch(
-            "
-            CREATE TABLE IF NOT EXISTS detections (
-                Date       DATE,
-                Time       TIME,
-                Domain     VARCHAR(50) NOT NULL DEFAULT 'birds',
-                Sci_Name   VARCHAR(100) NOT NULL,
-                Com_Name   VARCHAR(100) NOT NULL,
-                Confidence FLOAT,
-                Lat        FLOAT,
-                Lon        FLOAT,
-                Cutoff     FLOAT,
-                Week       INT,
-                Sens       FLOAT,
-                Overlap    FLOAT,
-                File_Name  VARCHAR(100) NOT NULL,
-                Source_Node VARCHAR(200) NOT NULL DEFAULT '',
-                Excluded   INTEGER NOT NULL DEFAULT 0
-            );
-            CREATE INDEX IF NOT EXISTS detections_Com_Name    ON detections (Com_Name);
-            CREATE INDEX IF NOT EXISTS detections_Sci_Name    ON detections (Sci_Name);
-            CREATE INDEX IF NOT EXISTS detections_Domain      ON detections (Domain);
-            CREATE INDEX IF NOT EXISTS detections_Date_Time   ON detections (Date DESC, Time DESC);
-
-            CREATE TABLE IF NOT EXISTS urban_noise (
-                Date       DATE    NOT NULL,
-                Hour       INT     NOT NULL,
-                Category   VARCHAR(50) NOT NULL,
-                Count      INT     NOT NULL DEFAULT 1,
-                UNIQUE(Date, Hour, Category)
-            );
-            CREATE INDEX IF NOT EXISTS urban_noise_date ON urban_noise (Date DESC);
-
-            CREATE TABLE IF NOT EXISTS settings (
-                key   TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS exclusion_overrides (
-                Sci_Name      VARCHAR(100) PRIMARY KEY,
-                overridden_at TEXT NOT NULL DEFAULT (datetime('now')),
-                notes         TEXT NOT NULL DEFAULT ''
-            );
-
-            CREATE TABLE IF NOT EXISTS file_processing_log (
-                filename   TEXT    NOT NULL,
-                instance   TEXT    NOT NULL,
-                processed_at TEXT NOT NULL DEFAULT (datetime('now')),
-                PRIMARY KEY (filename, instance)
-            );
-
-            CREATE TABLE IF NOT EXISTS processing_instances (
-                instance       TEXT PRIMARY KEY,
-                registered_at  TEXT NOT NULL DEFAULT (datetime('now')),
-                last_heartbeat TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-        ",
-        )
-        .await
-        .context("Failed to create tables")?;
-
-        // Migration: add Source_Node to existing databases that lack it.
-        migrate_add_column(&conn, "detections", "Source_Node", "VARCHAR(200) NOT NULL DEFAULT ''").await;
-        // Migration: add Excluded column to existing databases.
-        migrate_add_column(&conn, "detections", "Excluded", "INTEGER NOT NULL DEFAULT 0").await;
-        // Migration: add last_heartbeat to existing processing_instances tables.
-        let added = conn.execute_batch(
-            "ALTER TABLE processing_instances ADD COLUMN last_heartbeat TEXT NOT NULL DEFAULT '';"
-        ).await;
-        if added.is_ok() {
-            conn.execute_batch(
-                "UPDATE processing_instances SET last_heartbeat = registered_at WHERE last_heartbeat = '';"
-            ).await.ok();
-        }
-        // Migration: add Model_Slug and Model_Name columns.
-        migrate_add_column(&conn, "detections", "Model_Slug", "VARCHAR(100) NOT NULL DEFAULT ''").await;
-        migrate_add_column(&conn, "detections", "Model_Name", "VARCHAR(200) NOT NULL DEFAULT ''").await;
-
-        Ok::<(), anyhow::Error>(())
-    })?;
-
-    info!("Database schema verified");
-    Ok(())
-}
+        // Unversioned databases have no `schema_version` table yet.
+        let before = gaia_common::schema::current_version(conn).await.unwrap_or(0);
+        let version = gaia_common::schema::migrate(conn)
+            .await
+            .context("Failed to migrate database schema")?;
+        info!("Database schema at version {version}");
 
-/// Idempotent column addition — ignores "duplicate column" errors.
-async fn migrate_add_column(conn: &libsql::Connection, table: &str, column: &str, typedef: &str) {
-    let _ = conn
-        .execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {typedef};"))
-        .await;
+        Ok::<bool, anyhow::Error>(version > before)
+    })
 }
 
-/// Insert a single detection row.
+/// Insert a single detection row, registering its species first.
 ///
-/// Runs as a single autocommit statement — SQLite handles the
+/// A detection already stored for the same file, time and species is
+/// ignored.  Each statement runs autocommit — SQLite handles the
 /// implicit transaction.  Cross-process contention is absorbed by
 /// `PRAGMA busy_timeout` (30 s) **plus** application-level retry
 /// with exponential backoff.
+#[allow(clippy::too_many_arguments)]
 pub fn insert_detection(
     db_path: &Path,
     d: &Detection,
@@ -324,7 +246,16 @@ pub fn insert_detection(
     for attempt in 0..=MAX_BUSY_RETRIES {
         let res: Result<(), libsql::Error> = rt().block_on(async {
             conn.execute(
-                "INSERT INTO detections (Date, Time, Domain, Sci_Name, Com_Name, Confidence, \
+                gaia_common::schema::UPSERT_SPECIES,
+                params![
+                    d.scientific_name.clone(),
+                    d.common_name.clone(),
+                    d.domain.clone(),
+                ],
+            )
+            .await?;
+            conn.execute(
+                "INSERT OR IGNORE INTO detections (Date, Time, Domain, Sci_Name, Com_Name, Confidence, \
                  Lat, Lon, Cutoff, Week, Sens, Overlap, File_Name, Source_Node, Excluded, \
                  Model_Slug, Model_Name) \
                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
@@ -0,0 +1,286 @@
+//! Sound direction estimation for stereo pairs and microphone arrays.
+//!
+//! When a node's microphone positions are configured (`MIC_POSITIONS`,
+//! per node `NODE_MIC_POSITIONS`), the stretch of each detection is
+//! cross-correlated between every pair of channels with GCC-PHAT.  The
+//! bearing is the compass direction whose plane-wave delays line up best
+//! with those correlations (steered response power), searched in 1°
+//! steps.  Neighbouring territories of the same species then show up as
+//! separate clusters of bearings.
+//!
+//! A pair, or any array with all microphones on one line, cannot tell
+//! front from back: the bearing is taken on the side that is in front
+//! when the first microphone is on the left.
+
+use std::path::Path;
+
+use anyhow::Result;
+use rustfft::{num_complex::Complex, FftPlanner};
+use tracing::{debug, warn};
+
+use gaia_common::audio;
+use gaia_common::detection::Detection;
+
+/// Metres per second at about 20 °C.
+const SPEED_OF_SOUND: f64 = 343.0;
+
+/// Rate the channels are decoded at for correlation.
+const SAMPLE_RATE: u32 = 48_000;
+
+/// Shortest stretch, in samples, worth correlating.
+const MIN_SAMPLES: usize = 256;
+
+/// Microphone positions of a node in metres, `x` towards east and `y`
+/// towards north, one per channel in channel order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicArray {
+    positions: Vec<(f64, f64)>,
+}
+
+impl MicArray {
+    /// Parse space-separated `x:y` pairs, e.g. `-0.1:0 0.1:0` for a
+    /// stereo pair 20 cm apart facing north.  `None` unless there are at
+    /// least two distinct positions.
+    pub fn parse(s: &str) -> Option<Self> {
+        let positions = s
+            .split_whitespace()
+            .map(|pair| {
+                let (x, y) = pair.split_once(':')?;
+                Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+            })
+            .collect::<Option<Vec<(f64, f64)>>>()?;
+        let spread = positions
+            .iter()
+            .any(|&(x, y)| (x - positions[0].0).hypot(y - positions[0].1) > 1e-3);
+        (positions.len() >= 2 && spread).then_some(Self { positions })
+    }
+
+    /// Number of microphones, i.e. channels expected in a recording.
+    pub fn channels(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Direction of the side a collinear array listens to; `None` when
+    /// the microphones span a plane and every bearing can be told apart.
+    fn front(&self) -> Option<(f64, f64)> {
+        let (x0, y0) = self.positions[0];
+        let (ax, ay) = self
+            .positions
+            .iter()
+            .map(|&(x, y)| (x - x0, y - y0))
+            .max_by(|a, b| a.0.hypot(a.1).total_cmp(&b.0.hypot(b.1)))?;
+        let collinear = self
+            .positions
+            .iter()
+            .all(|&(x, y)| (ax * (y - y0) - ay * (x - x0)).abs() < 1e-6);
+        // With the first microphone on the left, front is the axis
+        // turned a quarter counter-clockwise.
+        collinear.then_some((-ay, ax))
+    }
+}
+
+/// Set `bearing_deg` on each of `detections` from the channels of the
+/// recording at `path`.  Recordings whose channel count does not match
+/// the array are left alone.
+pub fn annotate(path: &Path, array: &MicArray, detections: &mut [Detection]) -> Result<()> {
+    if detections.is_empty() {
+        return Ok(());
+    }
+    let channels = audio::channel_count(path).unwrap_or(1);
+    if usize::from(channels) != array.channels() {
+        warn!(
+            "{} has {channels} channel(s) but {} microphone positions are configured; \
+             no bearings",
+            path.display(),
+            array.channels()
+        );
+        return Ok(());
+    }
+    let signals = (0..channels)
+        .map(|ch| audio::load_audio_channel(path, SAMPLE_RATE, Some(ch)))
+        .collect::<Result<Vec<_>>>()?;
+    for d in detections.iter_mut() {
+        let from = (d.start.max(0.0) * SAMPLE_RATE as f64) as usize;
+        let to = (d.stop.max(0.0) * SAMPLE_RATE as f64) as usize;
+        let segments: Vec<&[f32]> = signals
+            .iter()
+            .map(|s| &s[from.min(s.len())..to.max(from).min(s.len())])
+            .collect();
+        d.bearing_deg = estimate(&segments, array, SAMPLE_RATE);
+        debug!(
+            "Bearing of {} at {:.1}s: {:?}",
+            d.common_name, d.start, d.bearing_deg
+        );
+    }
+    Ok(())
+}
+
+/// Compass bearing (0° north, clockwise) of the sound in `channels`, one
+/// slice per microphone of `array`.  `None` for silence or stretches too
+/// short to correlate.
+pub fn estimate(channels: &[&[f32]], array: &MicArray, sample_rate: u32) -> Option<f64> {
+    if channels.len() != array.channels() {
+        return None;
+    }
+    let len = channels.iter().map(|c| c.len()).min()?;
+    if len < MIN_SAMPLES {
+        return None;
+    }
+    let n_fft = (2 * len).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let forward = planner.plan_fft_forward(n_fft);
+    let inverse = planner.plan_fft_inverse(n_fft);
+
+    let spectra: Vec<Vec<Complex<f64>>> = channels
+        .iter()
+        .map(|c| {
+            let mut buf: Vec<Complex<f64>> = c[..len]
+                .iter()
+                .map(|&s| Complex::new(s as f64, 0.0))
+                .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+                .take(n_fft)
+                .collect();
+            forward.process(&mut buf);
+            buf
+        })
+        .collect();
+
+    // GCC-PHAT of every pair: whitening the cross-spectrum leaves a sharp
+    // peak at the delay of channel i behind channel j.
+    let mut pairs = Vec::new();
+    for i in 0..channels.len() {
+        for j in i + 1..channels.len() {
+            let mut cross: Vec<Complex<f64>> = spectra[i]
+                .iter()
+                .zip(&spectra[j])
+                .map(|(a, b)| {
+                    let c = a * b.conj();
+                    let norm = c.norm();
+                    if norm > 1e-12 {
+                        c / norm
+                    } else {
+                        Complex::new(0.0, 0.0)
+                    }
+                })
+                .collect();
+            inverse.process(&mut cross);
+            let cc: Vec<f64> = cross.iter().map(|c| c.re / n_fft as f64).collect();
+            pairs.push((i, j, cc));
+        }
+    }
+
+    let front = array.front();
+    let lag_at = |cc: &[f64], lag: f64| {
+        let floor = lag.floor();
+        let frac = lag - floor;
+        let index = |l: f64| (l as i64).rem_euclid(n_fft as i64) as usize;
+        cc[index(floor)] * (1.0 - frac) + cc[index(floor + 1.0)] * frac
+    };
+    let mut best: Option<(f64, f64)> = None;
+    for step in 0..360 {
+        let bearing = step as f64;
+        let (ux, uy) = (bearing.to_radians().sin(), bearing.to_radians().cos());
+        if front.is_some_and(|(fx, fy)| ux * fx + uy * fy < -1e-9) {
+            continue;
+        }
+        // A microphone further along `u` hears the sound earlier.
+        let power: f64 = pairs
+            .iter()
+            .map(|(i, j, cc)| {
+                let (xi, yi) = array.positions[*i];
+                let (xj, yj) = array.positions[*j];
+                let delay = -((xi - xj) * ux + (yi - yj) * uy) / SPEED_OF_SOUND;
+                lag_at(cc, delay * sample_rate as f64)
+            })
+            .sum();
+        if best.is_none_or(|(_, p)| power > p) {
+            best = Some((bearing, power));
+        }
+    }
+    best.filter(|&(_, power)| power > 1e-9)
+        .map(|(bearing, _)| bearing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// White noise arriving from `bearing` at each microphone of `array`,
+    /// delayed by whole samples.
+    fn plane_wave(array: &MicArray, bearing: f64, sample_rate: u32) -> Vec<Vec<f32>> {
+        let mut seed = 0x2545_f491_u32;
+        let source: Vec<f32> = (0..8_192)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as f32 / u32::MAX as f32 - 0.5
+            })
+            .collect();
+        let (ux, uy) = (bearing.to_radians().sin(), bearing.to_radians().cos());
+        array
+            .positions
+            .iter()
+            .map(|&(x, y)| {
+                let delay = -(x * ux + y * uy) / SPEED_OF_SOUND * sample_rate as f64;
+                let delay = delay.round() as i64;
+                (0..7_000)
+                    .map(|t| source[(t + 500 - delay) as usize])
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn bearing_of(array: &MicArray, bearing: f64) -> f64 {
+        let signals = plane_wave(array, bearing, SAMPLE_RATE);
+        let slices: Vec<&[f32]> = signals.iter().map(Vec::as_slice).collect();
+        estimate(&slices, array, SAMPLE_RATE).unwrap()
+    }
+
+    fn angle_between(a: f64, b: f64) -> f64 {
+        let d = (a - b).rem_euclid(360.0);
+        d.min(360.0 - d)
+    }
+
+    #[test]
+    fn test_parse() {
+        let pair = MicArray::parse("-0.1:0  0.1:0").unwrap();
+        assert_eq!(pair.channels(), 2);
+        assert_eq!(pair.front(), Some((-0.0, 0.2)));
+        assert!(MicArray::parse("0:0").is_none());
+        assert!(MicArray::parse("0:0 0:0").is_none());
+        assert!(MicArray::parse("0:0 x:1").is_none());
+        let square = MicArray::parse("-0.5:-0.5 0.5:-0.5 0.5:0.5 -0.5:0.5").unwrap();
+        assert_eq!(square.front(), None);
+    }
+
+    #[test]
+    fn test_array_bearing() {
+        let square = MicArray::parse("-0.5:-0.5 0.5:-0.5 0.5:0.5 -0.5:0.5").unwrap();
+        for bearing in [0.0, 45.0, 130.0, 200.0, 315.0] {
+            let found = bearing_of(&square, bearing);
+            assert!(
+                angle_between(found, bearing) <= 2.0,
+                "{bearing}° → {found}°"
+            );
+        }
+    }
+
+    #[test]
+    fn test_pair_bearing() {
+        // Facing north: left is west.  A source behind the pair is
+        // reported at its mirror image in front.
+        let pair = MicArray::parse("-0.5:0 0.5:0").unwrap();
+        assert!(angle_between(bearing_of(&pair, 300.0), 300.0) <= 3.0);
+        assert!(angle_between(bearing_of(&pair, 240.0), 300.0) <= 3.0);
+    }
+
+    #[test]
+    fn test_silence() {
+        let pair = MicArray::parse("-0.1:0 0.1:0").unwrap();
+        let silent = vec![0.0f32; 4_096];
+        assert_eq!(estimate(&[&silent, &silent], &pair, SAMPLE_RATE), None);
+        let short = vec![0.5f32; 100];
+        assert_eq!(estimate(&[&short, &short], &pair, SAMPLE_RATE), None);
+    }
+}
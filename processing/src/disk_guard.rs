@@ -0,0 +1,57 @@
+//! Pauses clip extraction while the data volume is nearly full.
+//!
+//! Clips, spectrograms and time-expanded copies are many small files, so
+//! a full volume (or one out of inodes) would leave half-written clips
+//! behind.  Above `DISK_USAGE_MAX` / `DISK_INODE_MAX` detections are
+//! still stored, just without clips, until space is freed.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use gaia_common::config::Config;
+use tracing::{info, warn};
+
+/// How long a reading is trusted before `df` is run again.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+static LAST_CHECK: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Whether extraction into `config.extracted_dir` is paused, re-checking
+/// the volume at most every [`CHECK_INTERVAL`].
+pub fn extraction_paused(config: &Config) -> bool {
+    if let Ok(mut last) = LAST_CHECK.lock() {
+        if last.is_none_or(|t| t.elapsed() >= CHECK_INTERVAL) {
+            *last = Some(Instant::now());
+            check(&config.extracted_dir, config.disk_usage_max, config.disk_inode_max);
+        }
+    }
+    is_paused()
+}
+
+/// The state as of the last check.
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+fn check(dir: &Path, space_max: f64, inode_max: f64) {
+    // The clip directory may not exist yet; its volume is the parent's.
+    let dir = dir.ancestors().find(|d| d.exists()).unwrap_or(dir);
+    let Some(usage) = gaia_common::disk::usage(dir) else {
+        return;
+    };
+    let full = usage.exceeds(space_max, inode_max);
+    if full != PAUSED.swap(full, Ordering::Relaxed) {
+        if full {
+            warn!(
+                "{} is nearly full ({}) — pausing clip extraction; detections are still stored",
+                dir.display(),
+                usage.describe()
+            );
+        } else {
+            info!("{} has room again ({}) — resuming clip extraction", dir.display(), usage.describe());
+        }
+    }
+}
@@ -2,15 +2,15 @@
 //!
 //! When a manifest includes a `[download]` section and the expected model
 //! files are not yet present on disk, this module downloads the appropriate
-//! variant zip from Zenodo, verifies its MD5 checksum, and extracts the
-//! contents into the model directory.
+//! variant zip from Zenodo (or one of its mirrors), verifies its SHA-256 /
+//! MD5 checksum, and extracts the contents into the model directory.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use tracing::{info, warn};
 
-use crate::manifest::ResolvedManifest;
+use crate::manifest::{DownloadSection, ResolvedManifest, VariantInfo};
 
 const ZENODO_FILES_URL: &str = "https://zenodo.org/api/records";
 
@@ -86,12 +86,8 @@ pub fn ensure_model_files(manifest: &mut ResolvedManifest, variant: &str) -> Res
         record_id
     );
 
-    let url = format!(
-        "{}/{}/files/{}/content",
-        ZENODO_FILES_URL, record_id, variant_info.zenodo_file
-    );
-
-    if let Err(e) = download_and_extract(&url, &manifest.base_dir, variant_info.md5.as_deref()) {
+    let fetch = Fetch::variant(download, record_id, variant_info);
+    if let Err(e) = download_and_extract(&fetch, &manifest.base_dir) {
         write_backoff_marker(&manifest.base_dir);
         return Err(e);
     }
@@ -245,9 +241,8 @@ pub fn ensure_onnx_file(manifest: &ResolvedManifest) -> Result<()> {
         if let (Some(record_id), Some(keras_file)) = (&download.zenodo_record_id, &download.keras_zenodo_file) {
             return convert_keras_to_onnx(
                 manifest,
-                record_id,
                 keras_file,
-                download.keras_md5.as_deref(),
+                &Fetch::keras(download, record_id, keras_file),
                 &onnx_path,
             );
         }
@@ -306,9 +301,8 @@ pub fn ensure_meta_onnx_file(manifest: &ResolvedManifest) -> Result<()> {
         if let (Some(record_id), Some(keras_file)) = (&download.zenodo_record_id, &download.keras_zenodo_file) {
             return convert_meta_keras_to_onnx(
                 manifest,
-                record_id,
                 keras_file,
-                download.keras_md5.as_deref(),
+                &Fetch::keras(download, record_id, keras_file),
                 &onnx_path,
             );
         }
@@ -326,9 +320,8 @@ pub fn ensure_meta_onnx_file(manifest: &ResolvedManifest) -> Result<()> {
 /// classifier sub-model to ONNX.
 fn convert_keras_to_onnx(
     manifest: &ResolvedManifest,
-    zenodo_record_id: &str,
     keras_zenodo_file: &str,
-    keras_md5: Option<&str>,
+    fetch: &Fetch,
     onnx_path: &Path,
 ) -> Result<()> {
     // Download the Keras zip into a temporary subdirectory.
@@ -338,16 +331,12 @@ fn convert_keras_to_onnx(
 
     let h5_path = keras_dir.join("audio-model.h5");
     if !h5_path.exists() {
-        let url = format!(
-            "{}/{}/files/{}/content",
-            ZENODO_FILES_URL, zenodo_record_id, keras_zenodo_file
-        );
         info!(
             "Downloading Keras model for ONNX conversion: {} → {}",
             keras_zenodo_file,
             keras_dir.display()
         );
-        download_and_extract(&url, &keras_dir, keras_md5)?;
+        download_and_extract(fetch, &keras_dir)?;
 
         if !h5_path.exists() {
             let available: Vec<String> = std::fs::read_dir(&keras_dir)
@@ -456,9 +445,8 @@ fn convert_keras_to_onnx(
 /// so direct `from_keras()` conversion works without sub-model splitting.
 fn convert_meta_keras_to_onnx(
     manifest: &ResolvedManifest,
-    zenodo_record_id: &str,
     keras_zenodo_file: &str,
-    keras_md5: Option<&str>,
+    fetch: &Fetch,
     onnx_path: &Path,
 ) -> Result<()> {
     let keras_dir = manifest.base_dir.join(".keras_tmp");
@@ -467,16 +455,12 @@ fn convert_meta_keras_to_onnx(
 
     let meta_h5 = keras_dir.join("meta-model.h5");
     if !meta_h5.exists() {
-        let url = format!(
-            "{}/{}/files/{}/content",
-            ZENODO_FILES_URL, zenodo_record_id, keras_zenodo_file
-        );
         info!(
             "Downloading Keras zip for metadata ONNX conversion: {} → {}",
             keras_zenodo_file,
             keras_dir.display()
         );
-        download_and_extract(&url, &keras_dir, keras_md5)?;
+        download_and_extract(fetch, &keras_dir)?;
 
         if !meta_h5.exists() {
             let available: Vec<String> = std::fs::read_dir(&keras_dir)
@@ -587,6 +571,18 @@ fn convert_tflite_to_onnx(manifest: &ResolvedManifest, onnx_path: &Path) -> Resu
         return Ok(());
     }
 
+    if let Err(e) = tflite_to_onnx(&tflite_path, onnx_path) {
+        warn!("{e:#}");
+        warn!("The server will attempt to load the TFLite model directly");
+    }
+    Ok(())
+}
+
+/// Convert `tflite_path` to ONNX at `onnx_path` with tf2onnx.
+///
+/// tf2onnx dequantises float16 weights, so this is also how fp16
+/// variants reach tract (see `model::load_tflite_runner`).
+pub fn tflite_to_onnx(tflite_path: &Path, onnx_path: &Path) -> Result<()> {
     info!(
         "Converting TFLite → ONNX: {} → {}",
         tflite_path.display(),
@@ -617,29 +613,23 @@ fn convert_tflite_to_onnx(manifest: &ResolvedManifest, onnx_path: &Path) -> Resu
             Ok(())
         }
         Ok(result) => {
+            // Don't leave a half-written file for the next start to trust.
+            let _ = std::fs::remove_file(onnx_path);
             let stderr = String::from_utf8_lossy(&result.stderr);
-            warn!(
+            anyhow::bail!(
                 "tf2onnx conversion failed (exit {}): {}",
                 result.status,
                 stderr.lines().take(10).collect::<Vec<_>>().join("\n")
-            );
-            warn!("The server will attempt to load the TFLite model directly");
-            Ok(())
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            warn!(
-                "python3 not found – cannot auto-convert TFLite to ONNX. \
-                 Install Python 3 and tf2onnx (`pip install tf2onnx`) to enable \
-                 automatic conversion, or convert manually with: \
-                 python scripts/convert_tflite_to_onnx.py {}",
-                tflite_path.display()
-            );
-            Ok(())
-        }
-        Err(e) => {
-            warn!("Failed to run tf2onnx conversion: {e}");
-            Ok(())
+            )
         }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => anyhow::bail!(
+            "python3 not found – cannot auto-convert TFLite to ONNX. \
+             Install Python 3 and tf2onnx (`pip install tf2onnx`) to enable \
+             automatic conversion, or convert manually with: \
+             python scripts/convert_tflite_to_onnx.py {}",
+            tflite_path.display()
+        ),
+        Err(e) => Err(e).context("Failed to run tf2onnx conversion"),
     }
 }
 
@@ -743,13 +733,100 @@ fn build_client() -> Result<reqwest::blocking::Client> {
         .context("Cannot build HTTP client")
 }
 
-/// Download a zip from `url`, verify MD5, and extract into `dest_dir`.
+// ── sources and checksums ────────────────────────────────────────────────
+
+/// Expected digest of a downloaded archive.
+#[derive(Debug, Clone, PartialEq)]
+enum Checksum {
+    Md5(String),
+    Sha256(String),
+}
+
+impl Checksum {
+    /// Pick the strongest digest the manifest provides.
+    fn from_manifest(md5: Option<&str>, sha256: Option<&str>) -> Option<Self> {
+        let non_empty = |s: &&str| !s.trim().is_empty();
+        sha256
+            .filter(non_empty)
+            .map(|h| Checksum::Sha256(h.trim().to_lowercase()))
+            .or_else(|| md5.filter(non_empty).map(|h| Checksum::Md5(h.trim().to_lowercase())))
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Checksum::Md5(_) => "MD5",
+            Checksum::Sha256(_) => "SHA-256",
+        }
+    }
+
+    fn verify(&self, bytes: &[u8]) -> Result<()> {
+        let (expected, digest) = match self {
+            Checksum::Md5(h) => (h, format!("{:x}", md5::compute(bytes))),
+            Checksum::Sha256(h) => {
+                use sha2::Digest;
+                (h, format!("{:x}", sha2::Sha256::digest(bytes)))
+            }
+        };
+        if digest != *expected {
+            anyhow::bail!(
+                "{} checksum mismatch: expected {}, got {}. \
+                 The download may be corrupted.",
+                self.name(),
+                expected,
+                digest
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Where to fetch one archive from and how to check it.
+#[derive(Debug, Clone)]
+struct Fetch {
+    /// Zenodo first, then mirrors in manifest order.
+    urls: Vec<String>,
+    checksum: Option<Checksum>,
+    /// Parallel range requests for large archives (1 = off).
+    segments: u32,
+}
+
+impl Fetch {
+    fn variant(download: &DownloadSection, record_id: &str, variant: &VariantInfo) -> Self {
+        Fetch {
+            urls: zenodo_then_mirrors(record_id, &variant.zenodo_file, &variant.mirrors),
+            checksum: Checksum::from_manifest(variant.md5.as_deref(), variant.sha256.as_deref()),
+            segments: download.segments,
+        }
+    }
+
+    fn keras(download: &DownloadSection, record_id: &str, keras_file: &str) -> Self {
+        Fetch {
+            urls: zenodo_then_mirrors(record_id, keras_file, &download.keras_mirrors),
+            checksum: Checksum::from_manifest(
+                download.keras_md5.as_deref(),
+                download.keras_sha256.as_deref(),
+            ),
+            segments: download.segments,
+        }
+    }
+}
+
+fn zenodo_then_mirrors(record_id: &str, file: &str, mirrors: &[String]) -> Vec<String> {
+    let mut urls = vec![format!("{}/{}/files/{}/content", ZENODO_FILES_URL, record_id, file)];
+    urls.extend(mirrors.iter().filter(|m| !m.trim().is_empty()).cloned());
+    urls
+}
+
+/// Download a zip, verify its checksum, and extract into `dest_dir`.
+///
+/// Each URL in `fetch` is tried in turn; a mirror is only used when the
+/// previous source fails outright or serves data with the wrong checksum.
 ///
 /// The download is resumable: data is streamed to a `.part` file and, on
 /// failure, subsequent retries use an HTTP `Range` header to continue where
 /// they left off instead of starting from scratch.  Retries use exponential
 /// backoff to avoid overloading the server.
-fn download_and_extract(url: &str, dest_dir: &Path, expected_md5: Option<&str>) -> Result<()> {
+fn download_and_extract(fetch: &Fetch, dest_dir: &Path) -> Result<()> {
     std::fs::create_dir_all(dest_dir)
         .with_context(|| format!("Cannot create model directory: {}", dest_dir.display()))?;
 
@@ -758,36 +835,222 @@ fn download_and_extract(url: &str, dest_dir: &Path, expected_md5: Option<&str>)
 
     let client = build_client()?;
 
-    download_with_resume(&client, url, &part_path)?;
+    let mut last_err = None;
+    for (i, url) in fetch.urls.iter().enumerate() {
+        if i > 0 {
+            // Never resume one source's partial data from another.
+            let _ = std::fs::remove_file(&part_path);
+            info!("Trying mirror {}/{}: {}", i, fetch.urls.len() - 1, url);
+        }
+        match fetch_verified(&client, url, &part_path, fetch) {
+            Ok(bytes) => {
+                extract_zip(&bytes, dest_dir)?;
+                // Clean up the .part file after successful extraction.
+                let _ = std::fs::remove_file(&part_path);
+                return Ok(());
+            }
+            Err(e) => {
+                warn!("Download from {} failed: {:#}", url, e);
+                last_err = Some(e);
+            }
+        }
+    }
+    Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No download URL configured")))
+}
+
+/// Fetch `url` into `part_path` and return the verified bytes.
+fn fetch_verified(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part_path: &Path,
+    fetch: &Fetch,
+) -> Result<Vec<u8>> {
+    // A leftover .part means a single-stream download was interrupted;
+    // resuming it is cheaper than starting over in segments.
+    let resuming = part_path.metadata().map(|m| m.len() > 0).unwrap_or(false);
+    let mut segmented = false;
+    if fetch.segments > 1 && !resuming {
+        if let Some(total) = probe_ranged_length(client, url).filter(|&t| t >= SEGMENT_MIN_BYTES) {
+            match download_segmented(client, url, part_path, total, fetch.segments) {
+                Ok(()) => segmented = true,
+                Err(e) => {
+                    warn!("Segmented download failed, falling back to a single connection: {e:#}");
+                    let _ = std::fs::remove_file(part_path);
+                }
+            }
+        }
+    }
+    if !segmented {
+        download_with_resume(client, url, part_path)?;
+    }
 
-    // Read the completed file and verify MD5.
     let bytes =
-        std::fs::read(&part_path).with_context(|| format!("Cannot read {}", part_path.display()))?;
+        std::fs::read(part_path).with_context(|| format!("Cannot read {}", part_path.display()))?;
 
     info!("Downloaded {:.1} MB", bytes.len() as f64 / 1_048_576.0);
 
-    if let Some(expected) = expected_md5 {
-        let digest = format!("{:x}", md5::compute(&bytes));
-        if digest != expected {
-            // Remove the corrupt partial file so the next run starts fresh.
-            let _ = std::fs::remove_file(&part_path);
-            anyhow::bail!(
-                "MD5 checksum mismatch: expected {}, got {}. \
-                 The download may be corrupted.",
-                expected,
-                digest
-            );
+    if let Some(checksum) = &fetch.checksum {
+        if let Err(e) = checksum.verify(&bytes) {
+            // Remove the corrupt partial file so the next attempt starts fresh.
+            let _ = std::fs::remove_file(part_path);
+            return Err(e);
         }
-        info!("MD5 checksum verified ✓");
+        info!("{} checksum verified ✓", checksum.name());
     }
+    Ok(bytes)
+}
 
-    // Extract zip
-    extract_zip(&bytes, dest_dir)?;
+// ── segmented download ───────────────────────────────────────────────────
 
-    // Clean up the .part file after successful extraction.
-    let _ = std::fs::remove_file(&part_path);
+/// Archives smaller than this always use a single connection.
+const SEGMENT_MIN_BYTES: u64 = 32 * 1024 * 1024;
 
-    Ok(())
+/// Content length of `url` if the server accepts byte ranges.
+fn probe_ranged_length(client: &reqwest::blocking::Client, url: &str) -> Option<u64> {
+    let response = client.head(url).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let headers = response.headers();
+    let ranges = headers
+        .get(reqwest::header::ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+    if !ranges {
+        return None;
+    }
+    headers
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Split `total` bytes into at most `segments` inclusive byte ranges.
+fn segment_ranges(total: u64, segments: u32) -> Vec<(u64, u64)> {
+    let seg_len = total.div_ceil(u64::from(segments.max(1))).max(1);
+    (0..total)
+        .step_by(seg_len as usize)
+        .map(|start| (start, (start + seg_len).min(total) - 1))
+        .collect()
+}
+
+fn segment_path(part_path: &Path, index: usize) -> PathBuf {
+    let mut name = part_path.as_os_str().to_owned();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+/// Download `url` with one thread per range, then concatenate the pieces
+/// into `part_path`.
+fn download_segmented(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part_path: &Path,
+    total: u64,
+    segments: u32,
+) -> Result<()> {
+    let ranges = segment_ranges(total, segments);
+    info!(
+        "GET {} in {} segments ({:.1} MB)",
+        url,
+        ranges.len(),
+        total as f64 / 1_048_576.0
+    );
+
+    let results: Vec<Result<()>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| {
+                let path = segment_path(part_path, i);
+                scope.spawn(move || download_range(client, url, &path, start, end))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|_| Err(anyhow::anyhow!("segment thread panicked"))))
+            .collect()
+    });
+
+    let outcome = results.into_iter().collect::<Result<Vec<()>>>().and_then(|_| {
+        let mut out = std::fs::File::create(part_path)
+            .with_context(|| format!("Cannot create {}", part_path.display()))?;
+        for i in 0..ranges.len() {
+            let path = segment_path(part_path, i);
+            let mut piece = std::fs::File::open(&path)
+                .with_context(|| format!("Cannot open {}", path.display()))?;
+            std::io::copy(&mut piece, &mut out).context("Error assembling segments")?;
+        }
+        let written = part_path.metadata().map(|m| m.len()).unwrap_or(0);
+        if written != total {
+            anyhow::bail!("assembled {written} bytes, expected {total}");
+        }
+        Ok(())
+    });
+
+    for i in 0..ranges.len() {
+        let _ = std::fs::remove_file(segment_path(part_path, i));
+    }
+    outcome
+}
+
+/// Fetch the inclusive byte range `start..=end` into `path`, resuming and
+/// retrying like [`download_with_resume`].
+fn download_range(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    path: &Path,
+    start: u64,
+    end: u64,
+) -> Result<()> {
+    let wanted = end - start + 1;
+    let mut backoff = INITIAL_BACKOFF;
+
+    for attempt in 1..=MAX_RETRIES {
+        let have = path.metadata().map(|m| m.len()).unwrap_or(0);
+        if have >= wanted {
+            return Ok(());
+        }
+
+        let result = client
+            .get(url)
+            .header(reqwest::header::RANGE, format!("bytes={}-{}", start + have, end))
+            .send()
+            .map_err(anyhow::Error::from)
+            .and_then(|response| {
+                // A plain 200 would hand us the whole file, not our slice.
+                if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                    anyhow::bail!("server ignored Range request (HTTP {})", response.status());
+                }
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Cannot open {}", path.display()))?;
+                stream_to_file(response, &mut file)
+            });
+
+        match result {
+            Ok(()) if path.metadata().map(|m| m.len()).unwrap_or(0) >= wanted => return Ok(()),
+            Ok(()) => warn!(
+                "Segment {}-{} ended early (attempt {}/{})",
+                start, end, attempt, MAX_RETRIES
+            ),
+            Err(e) => warn!(
+                "Segment {}-{} failed (attempt {}/{}): {:#}",
+                start, end, attempt, MAX_RETRIES, e
+            ),
+        }
+        if attempt == MAX_RETRIES {
+            anyhow::bail!("Segment {start}-{end} failed after {MAX_RETRIES} attempts: {url}");
+        }
+        std::thread::sleep(backoff);
+        backoff *= 2;
+    }
+
+    unreachable!()
 }
 
 /// Download `url` into `part_path`, resuming from where a previous attempt
@@ -1042,4 +1305,45 @@ mod tests {
             "https://zenodo.org/api/records/15050749/files/BirdNET_v2.4_tflite_fp16.zip/content"
         );
     }
+
+    #[test]
+    fn test_checksum_prefers_sha256() {
+        let c = Checksum::from_manifest(Some("abc"), Some(" ABCDEF ")).unwrap();
+        assert_eq!(c, Checksum::Sha256("abcdef".into()));
+        assert_eq!(
+            Checksum::from_manifest(Some("abc"), Some("")),
+            Some(Checksum::Md5("abc".into()))
+        );
+        assert_eq!(Checksum::from_manifest(None, None), None);
+
+        let sha = Checksum::Sha256(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".into(),
+        );
+        assert!(sha.verify(b"abc").is_ok());
+        assert!(sha.verify(b"abd").is_err());
+        let md5 = Checksum::Md5("900150983cd24fb0d6963f7d28e17f72".into());
+        assert!(md5.verify(b"abc").is_ok());
+    }
+
+    #[test]
+    fn test_segment_ranges_cover_file() {
+        assert_eq!(segment_ranges(10, 3), vec![(0, 3), (4, 7), (8, 9)]);
+        assert_eq!(segment_ranges(2, 4), vec![(0, 0), (1, 1)]);
+        assert_eq!(segment_ranges(100, 1), vec![(0, 99)]);
+        let ranges = segment_ranges(80_000_001, 4);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges.last().unwrap().1, 80_000_000);
+    }
+
+    #[test]
+    fn test_mirrors_follow_zenodo() {
+        let urls = zenodo_then_mirrors("1", "m.zip", &["https://mirror/m.zip".into(), " ".into()]);
+        assert_eq!(
+            urls,
+            vec![
+                "https://zenodo.org/api/records/1/files/m.zip/content".to_string(),
+                "https://mirror/m.zip".to_string(),
+            ]
+        );
+    }
 }
@@ -0,0 +1,179 @@
+//! Per-chunk ensembling of models that cover the same domain.
+//!
+//! With `ENSEMBLE_MODE` set, models sharing a `domain` (e.g. BirdNET and
+//! a regional custom classifier) no longer emit parallel detections.
+//! Their per-chunk scores are merged first and the confidence threshold
+//! and species filters are applied once, to the merged scores:
+//!
+//! - `max`      — highest score any member gave the species
+//! - `mean`     — plain average over members (a missing species counts 0)
+//! - `weighted` — average weighted by each manifest's `trust_weight`
+//!
+//! Members may use different chunk lengths (BirdNET 3 s, Perch 5 s).
+//! The first member's chunks define the time grid; every other member
+//! contributes the chunk that overlaps each grid window the most.
+
+use std::collections::HashMap;
+
+use gaia_common::detection::normalize_sci_name;
+
+use crate::model::Prediction;
+use crate::taxonomy;
+
+/// How member scores are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnsembleMode {
+    Max,
+    Mean,
+    Weighted,
+}
+
+impl EnsembleMode {
+    /// Parse `ENSEMBLE_MODE`; `off` / empty / unknown values disable it.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "max" => Some(Self::Max),
+            "mean" | "avg" | "average" => Some(Self::Mean),
+            "weighted" => Some(Self::Weighted),
+            _ => None,
+        }
+    }
+}
+
+/// A time-labelled chunk: `(start, end, predictions)`.
+pub type Chunk = (f64, f64, Vec<Prediction>);
+
+/// One member's chunk predictions plus its manifest weight.
+#[derive(Debug, Clone)]
+pub struct MemberScores {
+    pub weight: f64,
+    pub chunks: Vec<Chunk>,
+}
+
+/// Labels come as `"Sci name"` or `"Sci name_Common name"` depending on
+/// the model; merge on the canonical scientific name.
+fn species_key(label: &str) -> String {
+    let sci = label.split('_').next().unwrap_or(label);
+    taxonomy::canonical_species_name(&normalize_sci_name(sci))
+}
+
+/// Index of the chunk in `chunks` overlapping `[start, end)` the most.
+fn best_overlap(chunks: &[Chunk], start: f64, end: f64) -> Option<usize> {
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, (s, e, _))| (i, (e.min(end) - s.max(start)).max(0.0)))
+        .filter(|&(_, overlap)| overlap > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Merge member predictions onto the first member's time grid.
+///
+/// Output predictions use canonical scientific names and are sorted by
+/// descending score; zero scores are dropped.
+pub fn merge(members: &[MemberScores], mode: EnsembleMode) -> Vec<Chunk> {
+    let Some(grid) = members.first() else {
+        return Vec::new();
+    };
+
+    grid.chunks
+        .iter()
+        .map(|(start, end, _)| {
+            let mut combined: HashMap<String, f64> = HashMap::new();
+            let mut total_weight = 0.0;
+            for member in members {
+                let Some(idx) = best_overlap(&member.chunks, *start, *end) else {
+                    continue; // member has no audio here (shorter input)
+                };
+                let weight = match mode {
+                    EnsembleMode::Weighted => member.weight.max(0.0),
+                    EnsembleMode::Max | EnsembleMode::Mean => 1.0,
+                };
+                total_weight += weight;
+
+                // A label can repeat after normalisation; keep its best score.
+                let mut own: HashMap<String, f64> = HashMap::new();
+                for (label, score) in &member.chunks[idx].2 {
+                    let slot = own.entry(species_key(label)).or_insert(0.0);
+                    *slot = slot.max(*score);
+                }
+                for (species, score) in own {
+                    let slot = combined.entry(species).or_insert(0.0);
+                    *slot = match mode {
+                        EnsembleMode::Max => slot.max(score),
+                        EnsembleMode::Mean | EnsembleMode::Weighted => *slot + weight * score,
+                    };
+                }
+            }
+
+            let mut preds: Vec<Prediction> = combined
+                .into_iter()
+                .map(|(species, score)| match mode {
+                    EnsembleMode::Max => (species, score),
+                    _ if total_weight > 0.0 => (species, score / total_weight),
+                    _ => (species, 0.0),
+                })
+                .filter(|(_, score)| *score > 0.0)
+                .collect();
+            preds.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            (*start, *end, preds)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(weight: f64, chunks: &[(f64, f64, &[(&str, f64)])]) -> MemberScores {
+        MemberScores {
+            weight,
+            chunks: chunks
+                .iter()
+                .map(|(s, e, preds)| {
+                    (*s, *e, preds.iter().map(|(n, c)| (n.to_string(), *c)).collect())
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(EnsembleMode::parse("MAX"), Some(EnsembleMode::Max));
+        assert_eq!(EnsembleMode::parse("weighted"), Some(EnsembleMode::Weighted));
+        assert_eq!(EnsembleMode::parse("off"), None);
+        assert_eq!(EnsembleMode::parse(""), None);
+    }
+
+    #[test]
+    fn test_merge_modes() {
+        let a = member(1.0, &[(0.0, 3.0, &[("Turdus merula_Common Blackbird", 0.9)])]);
+        let b = member(0.5, &[(0.0, 3.0, &[("Turdus merula", 0.3), ("Erithacus rubecula", 0.6)])]);
+        let members = [a, b];
+
+        let max = merge(&members, EnsembleMode::Max);
+        assert_eq!(max[0].2[0], ("Turdus merula".to_string(), 0.9));
+
+        let mean = merge(&members, EnsembleMode::Mean);
+        assert!((mean[0].2[0].1 - 0.6).abs() < 1e-9);
+        // Robin only heard by one of two members → halved.
+        assert!((mean[0].2[1].1 - 0.3).abs() < 1e-9);
+
+        let weighted = merge(&members, EnsembleMode::Weighted);
+        let blackbird = weighted[0].2.iter().find(|p| p.0 == "Turdus merula").unwrap();
+        assert!((blackbird.1 - (0.9 + 0.5 * 0.3) / 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge_aligns_different_chunk_lengths() {
+        // 3 s grid vs a 5 s model: the second grid chunk (3–6 s) overlaps
+        // the first 5 s chunk by 2 s and the second by 1 s.
+        let a = member(1.0, &[(0.0, 3.0, &[]), (3.0, 6.0, &[("Pica pica", 0.2)])]);
+        let b = member(1.0, &[(0.0, 5.0, &[("Pica pica", 0.8)]), (5.0, 10.0, &[("Pica pica", 0.1)])]);
+        let merged = merge(&[a, b], EnsembleMode::Max);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].2, vec![("Pica pica".to_string(), 0.8)]);
+        assert_eq!(merged[1].2, vec![("Pica pica".to_string(), 0.8)]);
+    }
+}
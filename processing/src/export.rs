@@ -0,0 +1,121 @@
+//! Detection export to Parquet or CSV for `gaia-processing export`, and
+//! the Parquet file helpers `gaia-admin` reads the store with.
+//!
+//! The Parquet files in the detections directory are read through an
+//! in-memory DuckDB view and copied out in one `COPY` statement; the
+//! output format follows the file extension.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// `*.parquet` files directly under `dir`, sorted.
+pub fn parquet_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read {}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|x| x == "parquet").unwrap_or(false))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Copy the detections in `files` dated `from..=to` (`YYYY-MM-DD`, both
+/// optional) to `out`.  Returns the number of rows written.
+pub fn export(files: &[PathBuf], out: &Path, from: Option<&str>, to: Option<&str>) -> Result<u64> {
+    if files.is_empty() {
+        bail!("No detections to export");
+    }
+    let format = copy_format(out)?;
+    let mut filter = vec!["true".to_string()];
+    if let Some(f) = from {
+        filter.push(format!("Date >= {}", sql_str(validate_date(f)?)));
+    }
+    if let Some(t) = to {
+        filter.push(format!("Date <= {}", sql_str(validate_date(t)?)));
+    }
+    let where_sql = filter.join(" AND ");
+
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+    conn.execute_batch(&format!(
+        "CREATE VIEW detections AS SELECT * FROM read_parquet({}, union_by_name=true)",
+        file_list_sql(files)
+    ))
+    .context("Cannot read Parquet files")?;
+    let n: u64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM detections WHERE {where_sql}"),
+        [],
+        |r| r.get(0),
+    )?;
+    conn.execute_batch(&format!(
+        "COPY (SELECT * FROM detections WHERE {where_sql} ORDER BY Date, Time) \
+         TO {} ({format})",
+        sql_str(&out.display().to_string()),
+    ))?;
+    Ok(n)
+}
+
+pub fn sql_str(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+pub fn validate_date(s: &str) -> Result<&str> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date {s:?} (expected YYYY-MM-DD)"))?;
+    Ok(s)
+}
+
+pub fn file_list_sql(files: &[PathBuf]) -> String {
+    let list: Vec<String> = files
+        .iter()
+        .map(|f| sql_str(&f.display().to_string()))
+        .collect();
+    format!("[{}]", list.join(", "))
+}
+
+pub fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+pub fn copy_format(out: &Path) -> Result<&'static str> {
+    match extension(out).as_str() {
+        "parquet" => Ok("FORMAT PARQUET, COMPRESSION ZSTD"),
+        "csv" => Ok("FORMAT CSV, HEADER"),
+        other => bail!("Unsupported export format {other:?} (use .parquet or .csv)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_date_range() {
+        let dir = std::env::temp_dir().join(format!("gaia_export_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "COPY (SELECT * FROM (VALUES \
+               ('2025-05-01', '06:00:00', 'Turdus merula'), \
+               ('2025-05-02', '06:00:00', 'Erithacus rubecula'), \
+               ('2025-05-03', '06:00:00', 'Turdus merula')) \
+             AS t(Date, Time, Sci_Name)) TO {} (FORMAT PARQUET)",
+            sql_str(&dir.join("a.parquet").display().to_string())
+        ))
+        .unwrap();
+
+        let files = parquet_files(&dir).unwrap();
+        let out = dir.join("out.csv");
+        assert_eq!(export(&files, &out, Some("2025-05-02"), None).unwrap(), 2);
+        let csv = std::fs::read_to_string(&out).unwrap();
+        assert_eq!(csv.lines().count(), 3, "{csv}");
+        assert!(export(&files, &dir.join("out.json"), None, None).is_err());
+        assert!(export(&files, &out, Some("May 2"), None).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,222 @@
+//! Fault injection for exercising the retry and recovery paths.
+//!
+//! Compiled in only with the `fault-injection` cargo feature; without it
+//! every check is a constant `false` and the hooks cost nothing.  Faults
+//! are chosen with `GAIA_FAULTS`, a comma-separated list of
+//! `fault=N` (the first N calls fail) or `fault=N/M` (N out of every M
+//! calls fail, starting with the first):
+//!
+//! ```text
+//! GAIA_FAULTS=capture_timeout=2,corrupt_download=1/3,redis_error=1/2,inference_error=1/10
+//! ```
+//!
+//! | Fault | Hook |
+//! |-------|------|
+//! | `capture_timeout` | Listing and downloading recordings fail as if the capture node timed out |
+//! | `corrupt_download` | A downloaded recording is truncated and loses its header |
+//! | `redis_error` | A Redis command fails, forcing the reconnect path |
+//! | `inference_error` | A model inference returns an error |
+//!
+//! Schedules count calls, not time, so a test run fails the same calls
+//! every time.
+
+/// A failure that can be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    CaptureTimeout,
+    CorruptDownload,
+    RedisError,
+    InferenceError,
+}
+
+impl Fault {
+    #[cfg_attr(not(feature = "fault-injection"), allow(dead_code))]
+    const ALL: [Fault; 4] = [
+        Fault::CaptureTimeout,
+        Fault::CorruptDownload,
+        Fault::RedisError,
+        Fault::InferenceError,
+    ];
+
+    /// Name used in `GAIA_FAULTS`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::CaptureTimeout => "capture_timeout",
+            Self::CorruptDownload => "corrupt_download",
+            Self::RedisError => "redis_error",
+            Self::InferenceError => "inference_error",
+        }
+    }
+}
+
+/// Fail with an "injected fault" error when `fault` fires on this call.
+pub fn check(fault: Fault) -> anyhow::Result<()> {
+    if fires(fault) {
+        anyhow::bail!("injected fault: {}", fault.key());
+    }
+    Ok(())
+}
+
+/// Truncate `bytes` and clobber the container magic when
+/// [`Fault::CorruptDownload`] fires, like an interrupted transfer.
+pub fn maybe_corrupt(bytes: &mut Vec<u8>) {
+    if fires(Fault::CorruptDownload) {
+        bytes.truncate(bytes.len() / 2);
+        for b in bytes.iter_mut().take(4) {
+            *b = 0;
+        }
+    }
+}
+
+#[cfg(not(feature = "fault-injection"))]
+#[inline(always)]
+pub fn fires(_fault: Fault) -> bool {
+    false
+}
+
+#[cfg(not(feature = "fault-injection"))]
+pub fn init() {}
+
+#[cfg(feature = "fault-injection")]
+pub use injection::{fires, init};
+
+#[cfg(feature = "fault-injection")]
+mod injection {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::OnceLock;
+
+    use tracing::warn;
+
+    use super::Fault;
+
+    /// When a fault fires: `fail` of every `every` calls, or only the
+    /// first `fail` calls when `every` is `None`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(super) struct Schedule {
+        pub fail: u64,
+        pub every: Option<u64>,
+    }
+
+    impl Schedule {
+        pub fn fires(&self, call: u64) -> bool {
+            match self.every {
+                Some(m) => call % m < self.fail,
+                None => call < self.fail,
+            }
+        }
+    }
+
+    pub(super) struct Plan {
+        schedules: [Option<Schedule>; 4],
+        calls: [AtomicU64; 4],
+    }
+
+    impl Plan {
+        pub fn parse(spec: &str) -> Result<Self, String> {
+            let mut schedules = [None; 4];
+            for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (name, value) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("'{entry}' is not fault=N or fault=N/M"))?;
+                let idx = Fault::ALL
+                    .iter()
+                    .position(|f| f.key() == name.trim())
+                    .ok_or_else(|| format!("unknown fault '{}'", name.trim()))?;
+                let number = |s: &str| {
+                    s.trim()
+                        .parse::<u64>()
+                        .map_err(|_| format!("'{entry}': '{s}' is not a number"))
+                };
+                let schedule = match value.split_once('/') {
+                    Some((n, m)) => {
+                        let every = number(m)?;
+                        if every == 0 {
+                            return Err(format!("'{entry}': period must be at least 1"));
+                        }
+                        Schedule { fail: number(n)?, every: Some(every) }
+                    }
+                    None => Schedule { fail: number(value)?, every: None },
+                };
+                schedules[idx] = Some(schedule);
+            }
+            Ok(Self {
+                schedules,
+                calls: Default::default(),
+            })
+        }
+
+        pub fn fires(&self, fault: Fault) -> bool {
+            let idx = fault as usize;
+            let Some(schedule) = self.schedules[idx] else {
+                return false;
+            };
+            schedule.fires(self.calls[idx].fetch_add(1, Ordering::Relaxed))
+        }
+    }
+
+    static PLAN: OnceLock<Plan> = OnceLock::new();
+
+    fn plan() -> &'static Plan {
+        PLAN.get_or_init(|| {
+            let spec = std::env::var("GAIA_FAULTS").unwrap_or_default();
+            Plan::parse(&spec).unwrap_or_else(|e| {
+                warn!("Ignoring GAIA_FAULTS: {e}");
+                Plan::parse("").expect("empty plan")
+            })
+        })
+    }
+
+    /// Whether `fault` fires on this call.
+    pub fn fires(fault: Fault) -> bool {
+        let fired = plan().fires(fault);
+        if fired {
+            warn!("Injecting fault: {}", fault.key());
+        }
+        fired
+    }
+
+    /// Read `GAIA_FAULTS` and log the active faults.
+    pub fn init() {
+        let plan = plan();
+        for fault in Fault::ALL {
+            if let Some(s) = plan.schedules[fault as usize] {
+                match s.every {
+                    Some(m) => warn!("Fault injection: {} fails {} of every {m} call(s)", fault.key(), s.fail),
+                    None => warn!("Fault injection: {} fails the first {} call(s)", fault.key(), s.fail),
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn pattern(plan: &Plan, fault: Fault, calls: usize) -> Vec<bool> {
+            (0..calls).map(|_| plan.fires(fault)).collect()
+        }
+
+        #[test]
+        fn test_fault_schedules() {
+            let plan = Plan::parse("capture_timeout=2, redis_error=1/3").unwrap();
+            assert_eq!(
+                pattern(&plan, Fault::CaptureTimeout, 4),
+                [true, true, false, false]
+            );
+            assert_eq!(
+                pattern(&plan, Fault::RedisError, 6),
+                [true, false, false, true, false, false]
+            );
+            assert_eq!(pattern(&plan, Fault::InferenceError, 3), [false; 3]);
+        }
+
+        #[test]
+        fn test_fault_spec_errors() {
+            assert!(Plan::parse("").is_ok());
+            assert!(Plan::parse("disk_full=1").is_err());
+            assert!(Plan::parse("redis_error").is_err());
+            assert!(Plan::parse("redis_error=1/0").is_err());
+            assert!(Plan::parse("redis_error=x").is_err());
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! `/api/import`: analyse folders of audio files exported from other
+//! tools, without a BirdNET-Pi database.
+//!
+//! `GET /api/import` lists the subfolders of `IMPORT_DIR` and the
+//! progress of the current import; `POST /api/import` with
+//! `{"folder": "dawn-chorus", "node": "…"}` starts one (bearer token as
+//! for uploads).  Every audio file below the folder is copied to
+//! `<RECS_DIR>/imports/<node>/` and queued for the workers like an
+//! upload, so the original is left alone and reporting deletes the copy.
+//!
+//! Files named like capture segments keep their name and time.  Others
+//! are dated by their modification time and tagged with a hash of their
+//! content, so importing the same folder again skips everything the
+//! journal has seen.  Detections are stored with `Source_Node` set to
+//! the node, `import-<folder>` unless one is given.  Copies still
+//! waiting at startup are queued again.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::SyncSender;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::NaiveDateTime;
+use tracing::{info, warn};
+
+use gaia_common::config::Config;
+use gaia_common::detection::{segment_name_pattern, ParsedFileName};
+use gaia_common::protocol::{
+    FolderImportJob, FolderImportRequest, FolderImportState, FolderImports, ImportFolder,
+};
+
+use crate::journal::{self, Stage};
+use crate::upload;
+use crate::WorkItem;
+
+struct Imports {
+    config: Config,
+    work_tx: SyncSender<WorkItem>,
+    /// Folder whose subfolders can be imported.
+    root: PathBuf,
+    /// Copies waiting for the workers, one folder per node.
+    queue_dir: PathBuf,
+    job: Mutex<Option<FolderImportJob>>,
+    shutdown: &'static AtomicBool,
+}
+
+/// The import routes, or `None` when `IMPORT_DIR` is not set.
+pub fn router(
+    config: &Config,
+    work_tx: SyncSender<WorkItem>,
+    shutdown: &'static AtomicBool,
+) -> Option<Router> {
+    let root = config.import_dir.clone()?;
+    info!("Importing audio folders from {}", root.display());
+    let state = Arc::new(Imports {
+        config: config.clone(),
+        work_tx,
+        root,
+        queue_dir: config.recs_dir.join("imports"),
+        job: Mutex::new(None),
+        shutdown,
+    });
+    let leftovers = state.clone();
+    let spawned = std::thread::Builder::new()
+        .name("import-requeue".into())
+        .spawn(move || {
+            upload::requeue(
+                &leftovers.queue_dir,
+                &leftovers.config,
+                &leftovers.work_tx,
+                shutdown,
+            )
+        });
+    if let Err(e) = spawned {
+        warn!("Cannot queue earlier imports: {e}");
+    }
+    Some(
+        Router::new()
+            .route("/api/import", get(status).post(start))
+            .with_state(state),
+    )
+}
+
+async fn status(State(state): State<Arc<Imports>>) -> Json<FolderImports> {
+    let imports = tokio::task::spawn_blocking(move || FolderImports {
+        folders: folders(&state.root),
+        job: current_job(&state),
+    })
+    .await
+    .unwrap_or_default();
+    Json(imports)
+}
+
+async fn start(
+    State(state): State<Arc<Imports>>,
+    headers: HeaderMap,
+    Json(request): Json<FolderImportRequest>,
+) -> Response {
+    if let Err(denied) = upload::check_token(&state.config, &headers) {
+        return denied;
+    }
+    let folder = request.folder.trim().to_string();
+    if !valid_folder(&folder) || !state.root.join(&folder).is_dir() {
+        let msg = format!("no folder {folder:?} in {}", state.root.display());
+        return (StatusCode::NOT_FOUND, msg).into_response();
+    }
+    let node = match request.node.trim() {
+        "" => default_node(&folder),
+        node => node.to_string(),
+    };
+    if !upload::valid_node_id(&node) {
+        let msg = "node must be 1–64 letters, digits, '-', '_' or '.'";
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+
+    let running = current_job(&state).filter(|job| {
+        matches!(
+            job.state,
+            FolderImportState::Queuing | FolderImportState::Analysing
+        )
+    });
+    if let Some(job) = running {
+        let msg = format!("the import of {} is still running", job.folder);
+        return (StatusCode::CONFLICT, msg).into_response();
+    }
+    let job = FolderImportJob {
+        folder: folder.clone(),
+        node: node.clone(),
+        state: FolderImportState::Queuing,
+        started_at: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        files: 0,
+        skipped: 0,
+        queued: 0,
+        analysed: 0,
+        error: None,
+    };
+    if let Ok(mut current) = state.job.lock() {
+        *current = Some(job.clone());
+    }
+
+    let runner = state.clone();
+    let spawned = std::thread::Builder::new()
+        .name("folder-import".into())
+        .spawn(move || run(&runner, &folder, &node));
+    if let Err(e) = spawned {
+        update(&state, |job| {
+            fail(job, &anyhow::anyhow!("cannot start: {e}"))
+        });
+        return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+    }
+    (StatusCode::ACCEPTED, Json(job)).into_response()
+}
+
+/// Queue the files of `folder`, recording the outcome on the job.
+fn run(state: &Imports, folder: &str, node: &str) {
+    match queue_folder(state, &state.root.join(folder), node) {
+        Ok(()) => update(state, |job| job.state = FolderImportState::Analysing),
+        Err(e) => {
+            warn!("Import of {folder} failed: {e:#}");
+            update(state, |job| fail(job, &e));
+        }
+    }
+}
+
+fn queue_folder(state: &Imports, dir: &Path, node: &str) -> Result<()> {
+    let files = audio_files(dir);
+    update(state, |job| job.files = files.len());
+    let dest = state.queue_dir.join(node);
+    std::fs::create_dir_all(&dest).with_context(|| format!("Cannot create {}", dest.display()))?;
+
+    let mut queued = 0;
+    for path in files {
+        if state.shutdown.load(Ordering::Relaxed) {
+            anyhow::bail!("processing is shutting down");
+        }
+        let bytes =
+            std::fs::read(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .with_context(|| format!("Cannot stat {}", path.display()))?;
+        let modified = chrono::DateTime::<chrono::Local>::from(modified).naive_local();
+        let name = import_name(&path, modified, &bytes);
+        let key = journal::key(node, &name);
+        if journal::stage(&key)?.is_some() {
+            update(state, |job| job.skipped += 1);
+            continue;
+        }
+
+        // Written under a hidden name, so a restart never queues half a file.
+        let target = dest.join(&name);
+        let tmp = dest.join(format!(".{name}.tmp"));
+        std::fs::write(&tmp, &bytes).with_context(|| format!("Cannot write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &target)
+            .with_context(|| format!("Cannot rename {}", tmp.display()))?;
+        journal::mark(&key, Stage::Dispatched)?;
+        crate::activity::enqueue(crate::activity::Queue::Analysis);
+        // Blocks while the workers are busy, which is what we want here.
+        if state
+            .work_tx
+            .send(upload::work_item(&state.config, &target, node))
+            .is_err()
+        {
+            anyhow::bail!("processing is shutting down");
+        }
+        queued += 1;
+        update(state, |job| job.queued += 1);
+    }
+    info!(
+        "Queued {queued} recording(s) from {} as {node}",
+        dir.display()
+    );
+    Ok(())
+}
+
+/// The current job, with the analysed count brought up to date: reporting
+/// deletes each copy once its detections are stored.
+fn current_job(state: &Imports) -> Option<FolderImportJob> {
+    let mut current = state.job.lock().ok()?;
+    let job = current.as_mut()?;
+    if matches!(
+        job.state,
+        FolderImportState::Queuing | FolderImportState::Analysing
+    ) {
+        let waiting = audio_files(&state.queue_dir.join(&job.node)).len();
+        job.analysed = job.queued.saturating_sub(waiting);
+        if job.state == FolderImportState::Analysing && job.analysed == job.queued {
+            info!(
+                "Import of {} done: {} recording(s) analysed",
+                job.folder, job.analysed
+            );
+            job.state = FolderImportState::Done;
+        }
+    }
+    Some(job.clone())
+}
+
+fn update(state: &Imports, f: impl FnOnce(&mut FolderImportJob)) {
+    if let Ok(mut current) = state.job.lock() {
+        if let Some(job) = current.as_mut() {
+            f(job);
+        }
+    }
+}
+
+fn fail(job: &mut FolderImportJob, e: &anyhow::Error) {
+    job.state = FolderImportState::Failed;
+    job.error = Some(format!("{e:#}"));
+}
+
+/// Subfolders of `root` with their audio file counts, by name.
+fn folders(root: &Path) -> Vec<ImportFolder> {
+    let mut folders: Vec<ImportFolder> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| valid_folder(name))
+        .map(|name| ImportFolder {
+            audio_files: audio_files(&root.join(&name)).len(),
+            name,
+        })
+        .collect();
+    folders.sort_by(|a, b| a.name.cmp(&b.name));
+    folders
+}
+
+/// Audio files below `dir`, subfolders included, skipping hidden
+/// entries; sorted by path.
+fn audio_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if upload::is_audio(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// A single, visible path component: no way out of `IMPORT_DIR`.
+fn valid_folder(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// `import-<folder>`, made safe as a node name.
+fn default_node(folder: &str) -> String {
+    let safe: String = folder
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(57)
+        .collect();
+    format!("import-{safe}")
+}
+
+/// Name of the queued copy of `path`: its own when it is named like a
+/// capture segment, otherwise one dated `modified` and tagged with the
+/// start of the content hash.
+fn import_name(path: &Path, modified: NaiveDateTime, bytes: &[u8]) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if ParsedFileName::parse(path).is_ok() {
+        return name;
+    }
+    let hash = journal::content_hash(bytes);
+    let pattern = segment_name_pattern(&format!("{}-", &hash[..8]), false, false);
+    let named = modified.format(&pattern).to_string();
+    let stem = named.strip_suffix(".wav").unwrap_or(&named);
+    format!("{stem}.{}", crate::export::extension(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_name() {
+        let modified =
+            NaiveDateTime::parse_from_str("2026-05-01 06:12:30", "%Y-%m-%d %H:%M:%S").unwrap();
+        let kept = Path::new("/imports/garden/2024-02-24-birdnet-16:19:37.wav");
+        assert_eq!(
+            import_name(kept, modified, b"abc"),
+            "2024-02-24-birdnet-16:19:37.wav"
+        );
+
+        let name = import_name(
+            Path::new("/imports/trip/Blackbird song.MP3"),
+            modified,
+            b"abc",
+        );
+        assert_eq!(name, "2026-05-01-birdnet-ba7816bf-06:12:30.mp3");
+        let parsed = ParsedFileName::parse(Path::new(&name)).unwrap();
+        assert_eq!(parsed.file_date, modified);
+        assert_eq!(parsed.rtsp_id, "");
+    }
+
+    #[test]
+    fn test_folder_names() {
+        assert!(valid_folder("Dawn chorus 2024"));
+        assert!(!valid_folder(""));
+        assert!(!valid_folder(".."));
+        assert!(!valid_folder("a/../../etc"));
+        assert_eq!(default_node("Dawn chorus 2024"), "import-Dawn-chorus-2024");
+        assert!(upload::valid_node_id(&default_node(&"ñ".repeat(100))));
+    }
+
+    #[test]
+    fn test_audio_files() {
+        let dir = std::env::temp_dir().join(format!("gaia-import-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("day 2/.hidden")).unwrap();
+        for file in [
+            "a.wav",
+            "notes.txt",
+            "day 2/b.flac",
+            "day 2/.c.mp3",
+            "day 2/.hidden/d.wav",
+        ] {
+            std::fs::write(dir.join(file), b"x").unwrap();
+        }
+        let found: Vec<PathBuf> = audio_files(&dir)
+            .into_iter()
+            .map(|p| p.strip_prefix(&dir).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            found,
+            [PathBuf::from("a.wav"), PathBuf::from("day 2/b.flac")]
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,219 @@
+//! Uptime heartbeat (`HEARTBEAT_URL`) on its own schedule.
+//!
+//! The URL used to be pinged after each report, so a quiet night without
+//! detections looked like an outage to the monitor.  [`spawn`] now pings
+//! it every `HEARTBEAT_INTERVAL_SECS` while the pipeline is healthy,
+//! whether or not anything was heard.
+//!
+//! With healthchecks.io signals (`HEARTBEAT_SIGNALS`, on by default for
+//! `hc-ping.com` URLs) the server also reports `<url>/start` when it
+//! starts and `<url>/fail` when the pipeline is stuck, with the reason in
+//! the request body.  Other monitors only get the success pings, so they
+//! alert once those stop.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use gaia_common::config::Config;
+use gaia_common::protocol::{PipelineStatus, TimeSyncStatus};
+
+use crate::activity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Start,
+    Success,
+    Fail,
+}
+
+struct Heartbeat {
+    url: String,
+    /// healthchecks.io `/start` and `/fail` signals.
+    signals: bool,
+    client: reqwest::blocking::Client,
+}
+
+impl Heartbeat {
+    fn from_config(config: &Config) -> Option<Self> {
+        let url = config.heartbeat_url.clone()?;
+        let signals = config
+            .heartbeat_signals
+            .unwrap_or_else(|| url.contains("hc-ping.com"));
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .ok()?;
+        Some(Self {
+            url,
+            signals,
+            client,
+        })
+    }
+
+    /// Send `signal` with `message` as the body.  Plain monitors only
+    /// understand success pings; the other signals are dropped for them.
+    fn send(&self, signal: Signal, message: &str) {
+        if signal != Signal::Success && !self.signals {
+            return;
+        }
+        let url = signal_url(&self.url, signal);
+        let result = if self.signals {
+            self.client.post(&url).body(message.to_string()).send()
+        } else {
+            self.client.get(&url).send()
+        };
+        match result {
+            Ok(r) if r.status().is_success() => info!("Heartbeat {signal:?}: {message}"),
+            Ok(r) => warn!("Heartbeat {signal:?} rejected: {}", r.status()),
+            Err(e) => warn!("Heartbeat {signal:?} failed: {e}"),
+        }
+    }
+}
+
+/// Start the heartbeat thread; `None` without `HEARTBEAT_URL`.
+pub fn spawn(config: &Config, shutdown: &'static AtomicBool) -> Result<Option<JoinHandle<()>>> {
+    let Some(heartbeat) = Heartbeat::from_config(config) else {
+        return Ok(None);
+    };
+    let interval = Duration::from_secs(config.heartbeat_interval_secs);
+    info!(
+        "Heartbeat every {}s ({})",
+        interval.as_secs(),
+        if heartbeat.signals {
+            "with start/fail signals"
+        } else {
+            "success pings only"
+        }
+    );
+    std::thread::Builder::new()
+        .name("heartbeat".into())
+        .spawn(move || {
+            heartbeat.send(Signal::Start, "processing server started");
+            let mut analysed_before = activity::snapshot().files_analysed;
+            let mut last = Instant::now();
+            while !shutdown.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_secs(1));
+                if last.elapsed() < interval {
+                    continue;
+                }
+                last = Instant::now();
+                let status = activity::snapshot();
+                let clock = gaia_common::timesync::latest();
+                match problem(&status, analysed_before, clock.as_ref()) {
+                    Some(reason) => heartbeat.send(Signal::Fail, &reason),
+                    None => heartbeat.send(
+                        Signal::Success,
+                        &format!(
+                            "{} recording(s) analysed since the last beat, {} queued",
+                            status.files_analysed - analysed_before,
+                            status.analysis_queue + status.download_queue
+                        ),
+                    ),
+                }
+                analysed_before = status.files_analysed;
+            }
+        })
+        .map(Some)
+        .context("Cannot spawn heartbeat thread")
+}
+
+/// Report a failure outside the schedule, e.g. the processing loop
+/// exiting with an error.
+pub fn fail(config: &Config, reason: &str) {
+    if let Some(heartbeat) = Heartbeat::from_config(config) {
+        heartbeat.send(Signal::Fail, reason);
+    }
+}
+
+/// Why the pipeline counts as failing, or `None` when it is healthy.
+/// `analysed_before` is the recording count at the previous beat.
+fn problem(
+    status: &PipelineStatus,
+    analysed_before: u64,
+    clock: Option<&TimeSyncStatus>,
+) -> Option<String> {
+    if status.analysis_queue > 0 && status.files_analysed == analysed_before {
+        return Some(format!(
+            "analysis stalled: {} recording(s) queued, none analysed since the last beat",
+            status.analysis_queue
+        ));
+    }
+    if status.extraction_paused {
+        return Some("clip extraction paused: the data volume is nearly full".into());
+    }
+    if let Some(clock) = clock.filter(|c| c.drifting) {
+        return Some(format!("clock drifting: {}", clock.describe()));
+    }
+    None
+}
+
+/// healthchecks.io ping URLs: the URL itself for success, with `/start`
+/// or `/fail` appended to the path (before any query string) otherwise.
+fn signal_url(url: &str, signal: Signal) -> String {
+    let suffix = match signal {
+        Signal::Start => "/start",
+        Signal::Success => return url.to_string(),
+        Signal::Fail => "/fail",
+    };
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path, format!("?{query}")),
+        None => (url, String::new()),
+    };
+    format!("{}{suffix}{query}", path.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_url() {
+        let url = "https://hc-ping.com/0f8e-uuid";
+        assert_eq!(signal_url(url, Signal::Success), url);
+        assert_eq!(
+            signal_url(url, Signal::Start),
+            "https://hc-ping.com/0f8e-uuid/start"
+        );
+        assert_eq!(
+            signal_url("https://hc.example.org/ping/abc/?rid=1", Signal::Fail),
+            "https://hc.example.org/ping/abc/fail?rid=1"
+        );
+    }
+
+    #[test]
+    fn test_problem() {
+        let mut status = PipelineStatus {
+            files_analysed: 10,
+            ..Default::default()
+        };
+        // A quiet night: nothing queued, nothing analysed.
+        assert_eq!(problem(&status, 10, None), None);
+
+        status.analysis_queue = 3;
+        assert!(problem(&status, 10, None)
+            .unwrap()
+            .starts_with("analysis stalled"));
+        assert_eq!(problem(&status, 8, None), None);
+
+        status.extraction_paused = true;
+        assert!(problem(&status, 8, None).unwrap().contains("nearly full"));
+        status.extraction_paused = false;
+
+        let clock = TimeSyncStatus {
+            source: "chrony".into(),
+            synchronized: false,
+            offset_ms: None,
+            stratum: None,
+            reference: None,
+            last_sync: None,
+            drifting: true,
+        };
+        assert!(problem(&status, 8, Some(&clock))
+            .unwrap()
+            .starts_with("clock"));
+    }
+}
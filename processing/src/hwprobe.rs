@@ -0,0 +1,129 @@
+//! Startup hardware probe and automatic model-variant selection.
+//!
+//! Manifests ship several Zenodo variants (`fp32`, `fp16`, `int8`) and
+//! most users have no idea which one suits their board.  When
+//! `MODEL_VARIANT` is unset the processing server probes the machine and
+//! picks per manifest:
+//!
+//! | Hardware                                   | Preference          |
+//! |--------------------------------------------|---------------------|
+//! | GPU requested (`GAIA_ACCEL=cuda`/`rocm`)   | fp16 → fp32 → int8  |
+//! | < 2 GiB RAM                                | int8 → fp32         |
+//! | aarch64 with ≤ 4 cores or < 4 GiB RAM      | int8 → fp32         |
+//! | anything else                              | fp32 → int8         |
+//!
+//! fp16 is only chosen for GPUs: the CPU backends either upcast it
+//! (no gain) or, for tract-tflite, cannot load float16 tensors at all.
+//! The first preference the manifest actually offers wins; if none do,
+//! the manifest's `default_variant` is used.  `MODEL_VARIANT=<name>`
+//! overrides the probe, `MODEL_VARIANT=default` restores the manifest
+//! default.
+
+use crate::accel::{self, AccelKind};
+
+const GIB_MB: u64 = 1024;
+
+/// What the probe found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hardware {
+    /// `std::env::consts::ARCH` (`x86_64`, `aarch64`, …).
+    pub arch: &'static str,
+    pub cpus: usize,
+    /// Total RAM in MiB (0 when `/proc/meminfo` is unreadable).
+    pub total_ram_mb: u64,
+    pub accel: AccelKind,
+}
+
+impl std::fmt::Display for Hardware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} CPU(s), {:.1} GiB RAM, accel={:?}",
+            self.arch,
+            self.cpus,
+            self.total_ram_mb as f64 / GIB_MB as f64,
+            self.accel
+        )
+    }
+}
+
+/// Probe the machine we are running on.
+pub fn probe() -> Hardware {
+    Hardware {
+        arch: std::env::consts::ARCH,
+        cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        total_ram_mb: std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|s| mem_total_mb(&s))
+            .unwrap_or(0),
+        accel: accel::accel_kind(),
+    }
+}
+
+/// `MemTotal` from `/proc/meminfo` text, in MiB.
+fn mem_total_mb(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+/// Variant preference for `hw`, best first, and why.
+fn preference(hw: &Hardware) -> (&'static [&'static str], &'static str) {
+    let known_ram = hw.total_ram_mb > 0;
+    if hw.accel != AccelKind::None {
+        (&["fp16", "fp32", "int8"], "GPU acceleration requested")
+    } else if known_ram && hw.total_ram_mb < 2 * GIB_MB {
+        (&["int8", "fp32"], "less than 2 GiB RAM")
+    } else if hw.arch == "aarch64" && (hw.cpus <= 4 || (known_ram && hw.total_ram_mb < 4 * GIB_MB)) {
+        (&["int8", "fp32"], "small ARM board")
+    } else {
+        (&["fp32", "int8"], "CPU inference")
+    }
+}
+
+/// Pick a variant among `available` for `hw`; falls back to
+/// `manifest_default`.  Returns the variant and a reason for the log.
+pub fn choose_variant(hw: &Hardware, available: &[&str], manifest_default: &str) -> (String, String) {
+    let (order, why) = preference(hw);
+    match order.iter().find(|v| available.contains(v)) {
+        Some(v) => (v.to_string(), why.to_string()),
+        None => (
+            manifest_default.to_string(),
+            format!("manifest default ({why}, none of {order:?} offered)"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hw(arch: &'static str, cpus: usize, ram_gib: u64, accel: AccelKind) -> Hardware {
+        Hardware { arch, cpus, total_ram_mb: ram_gib * GIB_MB, accel }
+    }
+
+    #[test]
+    fn test_mem_total_mb() {
+        let meminfo = "MemTotal:        3884412 kB\nMemFree:          117236 kB\n";
+        assert_eq!(mem_total_mb(meminfo), Some(3793));
+        assert_eq!(mem_total_mb("garbage"), None);
+    }
+
+    #[test]
+    fn test_choose_variant() {
+        let all = ["fp32", "fp16", "int8"];
+        let pick = |h: &Hardware, avail: &[&str]| choose_variant(h, avail, "fp16").0;
+
+        assert_eq!(pick(&hw("x86_64", 8, 16, AccelKind::Cuda), &all), "fp16");
+        assert_eq!(pick(&hw("x86_64", 8, 16, AccelKind::None), &all), "fp32");
+        assert_eq!(pick(&hw("x86_64", 2, 1, AccelKind::None), &all), "int8");
+        // Raspberry Pi 4 / 5 with 4 cores.
+        assert_eq!(pick(&hw("aarch64", 4, 8, AccelKind::None), &all), "int8");
+        assert_eq!(pick(&hw("aarch64", 8, 16, AccelKind::None), &all), "fp32");
+        // Manifest without the preferred variants.
+        assert_eq!(pick(&hw("aarch64", 4, 2, AccelKind::None), &["fp16"]), "fp16");
+        // Unknown RAM does not count as "low RAM".
+        let unknown = Hardware { total_ram_mb: 0, ..hw("x86_64", 8, 0, AccelKind::None) };
+        assert_eq!(pick(&unknown, &all), "fp32");
+    }
+}
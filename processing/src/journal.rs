@@ -0,0 +1,298 @@
+//! Durable journal of the recordings this container has taken on.
+//!
+//! A recording is journaled as `dispatched` when it is handed to the
+//! download pool and as `analysed` once a worker has finished with it,
+//! just before it is deleted from the capture node.  The journal lives in
+//! an on-disk DuckDB database next to the detections, so after a crash
+//! or restart:
+//!
+//! * `analysed` recordings still on the capture node are only deleted,
+//!   never analysed (and their detections inserted) a second time;
+//! * `dispatched` recordings never finished, so they are forgotten at
+//!   startup and picked up again.
+//!
+//! The journal also remembers a hash of each downloaded recording, so a
+//! capture node that serves the same WAV under a second name (e.g. after
+//! an unclean restart) does not get it analysed twice; see
+//! [`claim_content`].
+//!
+//! Rows older than [`RETENTION`] are pruned; by then the capture node
+//! has long deleted the file.
+
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use duckdb::params;
+use tracing::{debug, info};
+
+/// How long journal rows are kept.
+pub const RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Where a recording is in the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Queued for download and analysis.
+    Dispatched,
+    /// Analysed; only the delete on the capture node may be outstanding.
+    Analysed,
+}
+
+impl Stage {
+    fn as_str(self) -> &'static str {
+        match self {
+            Stage::Dispatched => "dispatched",
+            Stage::Analysed => "analysed",
+        }
+    }
+}
+
+static JOURNAL: OnceLock<Mutex<Journal>> = OnceLock::new();
+
+/// Journal key of a recording: capture node id + file name.  The id,
+/// unlike the node's name, survives a rename.
+pub fn key(source_node_id: &str, filename: &str) -> String {
+    format!("{source_node_id}/{filename}")
+}
+
+/// Open (or create) the journal at `path`.  Must be called once before
+/// the polling loop starts.
+pub fn initialize(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Cannot create {}", parent.display()))?;
+    }
+    let conn = duckdb::Connection::open(path)
+        .with_context(|| format!("Cannot open journal {}", path.display()))?;
+    let journal = Journal::new(conn)?;
+    let (analysed, requeued) = journal.recover()?;
+    info!(
+        "Processing journal {} — {analysed} analysed recording(s) remembered, \
+         {requeued} unfinished one(s) will be processed again",
+        path.display()
+    );
+    let _ = JOURNAL.set(Mutex::new(journal));
+    Ok(())
+}
+
+/// Stage of `key`, or `None` when it has not been seen.
+pub fn stage(key: &str) -> Result<Option<Stage>> {
+    with_journal(|j| j.stage(key))
+}
+
+/// Record that `key` reached `stage`.
+pub fn mark(key: &str, stage: Stage) -> Result<()> {
+    with_journal(|j| j.mark(key, stage))
+}
+
+/// Forget `key`, e.g. after a failed download, so it is dispatched again.
+pub fn forget(key: &str) -> Result<()> {
+    with_journal(|j| j.forget(key))
+}
+
+/// SHA-256 of a recording's bytes, as passed to [`claim_content`].
+pub fn content_hash(bytes: &[u8]) -> String {
+    use sha2::Digest;
+    format!("{:x}", sha2::Sha256::digest(bytes))
+}
+
+/// Record that `key` has content `hash`.  When another recording with
+/// the same content was seen within `window`, nothing is recorded and
+/// its key is returned: `key` is a duplicate and need not be analysed.
+pub fn claim_content(key: &str, hash: &str, window: Duration) -> Result<Option<String>> {
+    with_journal(|j| j.claim_content(key, hash, window))
+}
+
+/// Drop rows older than [`RETENTION`].
+pub fn prune() -> Result<usize> {
+    let removed = with_journal(|j| j.prune(RETENTION))?;
+    if removed > 0 {
+        debug!("Processing journal: pruned {removed} old row(s)");
+    }
+    Ok(removed)
+}
+
+fn with_journal<T>(f: impl FnOnce(&Journal) -> Result<T>) -> Result<T> {
+    let journal = JOURNAL.get().context("Processing journal not initialised")?;
+    let j = journal
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Processing journal lock poisoned: {e}"))?;
+    f(&j)
+}
+
+struct Journal {
+    conn: duckdb::Connection,
+}
+
+impl Journal {
+    fn new(conn: duckdb::Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS processed_files (
+                key        VARCHAR   PRIMARY KEY,
+                stage      VARCHAR   NOT NULL,
+                updated_at BIGINT    NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS content_hashes (
+                hash    VARCHAR PRIMARY KEY,
+                key     VARCHAR NOT NULL,
+                seen_at BIGINT  NOT NULL
+            );",
+        )
+        .context("Cannot create journal tables")?;
+        Ok(Self { conn })
+    }
+
+    /// Forget recordings that were dispatched but never finished; returns
+    /// `(analysed, forgotten)` row counts.
+    fn recover(&self) -> Result<(usize, usize)> {
+        let forgotten = self
+            .conn
+            .execute("DELETE FROM processed_files WHERE stage = ?", params![Stage::Dispatched.as_str()])
+            .context("Cannot reset unfinished journal rows")?;
+        let analysed: i64 = self
+            .conn
+            .query_row("SELECT count(*) FROM processed_files", [], |row| row.get(0))
+            .context("Cannot count journal rows")?;
+        Ok((analysed as usize, forgotten))
+    }
+
+    fn stage(&self, key: &str) -> Result<Option<Stage>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT stage FROM processed_files WHERE key = ?")
+            .context("Cannot query journal")?;
+        let mut rows = stmt.query(params![key])?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        let stage: String = row.get(0)?;
+        Ok(Some(if stage == Stage::Analysed.as_str() {
+            Stage::Analysed
+        } else {
+            Stage::Dispatched
+        }))
+    }
+
+    fn mark(&self, key: &str, stage: Stage) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO processed_files (key, stage, updated_at) VALUES (?, ?, ?)",
+                params![key, stage.as_str(), unix_now()],
+            )
+            .with_context(|| format!("Cannot journal {key}"))?;
+        Ok(())
+    }
+
+    fn forget(&self, key: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM processed_files WHERE key = ?", params![key])
+            .with_context(|| format!("Cannot forget {key}"))?;
+        Ok(())
+    }
+
+    fn claim_content(&self, key: &str, hash: &str, window: Duration) -> Result<Option<String>> {
+        let now = unix_now();
+        let seen: Option<(String, i64)> = {
+            let mut stmt = self
+                .conn
+                .prepare_cached("SELECT key, seen_at FROM content_hashes WHERE hash = ?")
+                .context("Cannot query content hashes")?;
+            let mut rows = stmt.query(params![hash])?;
+            match rows.next()? {
+                Some(row) => Some((row.get(0)?, row.get(1)?)),
+                None => None,
+            }
+        };
+        // A retry of the same recording is not a duplicate of itself.
+        if let Some((original, seen_at)) = seen {
+            if original != key && seen_at > now - window.as_secs() as i64 {
+                return Ok(Some(original));
+            }
+        }
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO content_hashes (hash, key, seen_at) VALUES (?, ?, ?)",
+                params![hash, key, now],
+            )
+            .with_context(|| format!("Cannot record the content hash of {key}"))?;
+        Ok(None)
+    }
+
+    fn prune(&self, retention: Duration) -> Result<usize> {
+        let cutoff = unix_now() - retention.as_secs() as i64;
+        let files = self
+            .conn
+            .execute("DELETE FROM processed_files WHERE updated_at <= ?", params![cutoff])
+            .context("Cannot prune journal")?;
+        let hashes = self
+            .conn
+            .execute("DELETE FROM content_hashes WHERE seen_at <= ?", params![cutoff])
+            .context("Cannot prune content hashes")?;
+        Ok(files + hashes)
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_recovery() {
+        let path = std::env::temp_dir().join(format!("gaia-journal-{}.duckdb", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let j = Journal::new(duckdb::Connection::open(&path).unwrap()).unwrap();
+            j.mark("node/a.wav", Stage::Dispatched).unwrap();
+            j.mark("node/a.wav", Stage::Analysed).unwrap();
+            j.mark("node/b.wav", Stage::Dispatched).unwrap();
+            j.mark("node/c.wav", Stage::Dispatched).unwrap();
+            j.forget("node/c.wav").unwrap();
+            assert_eq!(j.stage("node/b.wav").unwrap(), Some(Stage::Dispatched));
+        }
+
+        // After a restart the analysed file is remembered and the
+        // unfinished one is processed again.
+        let j = Journal::new(duckdb::Connection::open(&path).unwrap()).unwrap();
+        assert_eq!(j.recover().unwrap(), (1, 1));
+        assert_eq!(j.stage("node/a.wav").unwrap(), Some(Stage::Analysed));
+        assert_eq!(j.stage("node/b.wav").unwrap(), None);
+        assert_eq!(j.stage("node/c.wav").unwrap(), None);
+
+        assert_eq!(j.prune(RETENTION).unwrap(), 0);
+        assert_eq!(j.prune(Duration::ZERO).unwrap(), 1);
+
+        drop(j);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("duckdb.wal"));
+    }
+
+    #[test]
+    fn test_content_dedup() {
+        let j = Journal::new(duckdb::Connection::open_in_memory().unwrap()).unwrap();
+        let hash = content_hash(b"RIFF....WAVE");
+        assert_eq!(hash.len(), 64);
+        let day = Duration::from_secs(24 * 3600);
+
+        assert_eq!(j.claim_content("node/a.wav", &hash, day).unwrap(), None);
+        // Downloaded again after a failed attempt: still the same file.
+        assert_eq!(j.claim_content("node/a.wav", &hash, day).unwrap(), None);
+        assert_eq!(
+            j.claim_content("node/b.wav", &hash, day).unwrap(),
+            Some("node/a.wav".to_string())
+        );
+        assert_eq!(j.claim_content("node/c.wav", &content_hash(b"other"), day).unwrap(), None);
+        // Outside the window the copy is analysed like any recording.
+        assert_eq!(j.claim_content("node/b.wav", &hash, Duration::ZERO).unwrap(), None);
+
+        assert_eq!(j.prune(Duration::ZERO).unwrap(), 2);
+    }
+}
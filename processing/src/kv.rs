@@ -12,10 +12,18 @@
 //! | `settings`                       | HASH | Runtime tuning knobs             |
 //! | `exclusion_overrides`            | HASH | Sci_Name → "overridden_at\|notes"|
 //! | `instances`                      | HASH | instance_id → unix_timestamp     |
-//! | `processed:{filename}`           | SET  | instance IDs (TTL 1 h)           |
 //! | `urban_noise:total`              | HASH | category → count (all-time)      |
 //! | `urban_noise:day:{YYYY-MM-DD}`   | HASH | category → count (TTL 30 d)      |
 //! | `verification:{Sci_Name}`        | HASH | method, inaturalist_obs, …       |
+//! | `image_pin:{Sci_Name}`           | HASH | url, attribution, pinned_at      |
+//! | `review:verdicts`                | HASH | clip File_Name → review JSON     |
+//! | `favourites[:{user}]`            | HASH | detection id → starred at (web)  |
+//! | `push:subscriptions`             | HASH | endpoint → subscription JSON     |
+//! | `push:vapid`                     | HASH | Web Push signing key pair        |
+//! | `push:events`                    | LIST | detections awaiting notification |
+//! | `audit:log`                      | LIST | web admin actions, newest first  |
+//! | `watchdog:hour:{YYYY-MM-DDTHH}`  | HASH | node\|domain → detections (UTC) |
+//! | `watchdog:alerted`               | HASH | node\|domain → quiet since      |
 
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
@@ -113,7 +121,11 @@ where
                 ).into());
             }
         };
-        f(&mut c)
+        if crate::faults::fires(crate::faults::Fault::RedisError) {
+            Err(std::io::Error::other("injected fault: redis_error").into())
+        } else {
+            f(&mut c)
+        }
     };
     match result {
         Ok(v) => Ok(v),
@@ -138,6 +150,14 @@ where
 /// TTL for daily urban-noise counters — 30 days.
 const URBAN_NOISE_DAY_TTL_SECS: i64 = 30 * 24 * 3600;
 
+/// TTL for the watchdog's hourly detection counters — 30 days, enough
+/// for the baseline of the longest watchdog window.
+const NODE_COUNTS_TTL_SECS: i64 = 30 * 24 * 3600;
+
+/// Maximum backlog of `push:events`; older entries are dropped so a
+/// stopped web container doesn't come back to a flood of stale alerts.
+const PUSH_EVENTS_MAX: isize = 200;
+
 /// Labels that the BirdNET model emits which are not actual bird species.
 /// These are counted in the urban-noise counters instead of detections.
 pub const URBAN_NOISE_LABELS: &[&str] = &[
@@ -210,22 +230,6 @@ pub fn prune_stale_instances(stale_minutes: u32) -> usize {
     removed
 }
 
-// ── File processing coordination ─────────────────────────────────────────────
-
-/// Check whether this instance has already processed a specific file.
-pub fn is_file_processed(filename: &str, instance: &str) -> bool {
-    let key = format!("processed:{filename}");
-    with_retry(|c| c.sismember(&key, instance)).unwrap_or(false)
-}
-
-/// Clean up old processing log entries.
-///
-/// With Redis TTL on `processed:*` keys, entries expire automatically.
-/// This function is a no-op kept for caller compatibility.
-pub fn cleanup_processing_log() {
-    // TTL handles cleanup — nothing to do.
-}
-
 // ── Urban noise ──────────────────────────────────────────────────────────────
 
 /// Increment the urban-noise counter for a category / date / hour.
@@ -247,6 +251,119 @@ pub fn increment_urban_noise(date: &str, _hour: u32, category: &str) -> Result<(
     Ok(())
 }
 
+// ── Web Push ─────────────────────────────────────────────────────────────────
+
+/// Hand a detection to the web container's push notifier.
+///
+/// Skipped when no browser has subscribed, so installations that never
+/// use notifications don't pay for the extra writes.
+pub fn queue_push_event(d: &gaia_common::detection::Detection, source_node: &str) {
+    let subscribers: usize = with_retry(|c| c.hlen("push:subscriptions")).unwrap_or(0);
+    if subscribers == 0 {
+        return;
+    }
+    let event = serde_json::json!({
+        "sci_name": d.scientific_name,
+        "com_name": d.common_name,
+        "confidence": d.confidence,
+        "date": d.date,
+        "time": d.time,
+        "domain": d.domain,
+        "model": d.model_name,
+        "source_node": source_node,
+        "rarity": d.rarity,
+        "rare": d.rare,
+        "queued_at": now_unix(),
+    })
+    .to_string();
+    let res = with_retry(|c| {
+        redis::pipe()
+            .rpush("push:events", &event)
+            .ltrim("push:events", -PUSH_EVENTS_MAX, -1)
+            .exec(c)
+    });
+    if let Err(e) = res {
+        warn!("queue_push_event failed: {e}");
+    }
+}
+
+// ── Station watchdog ─────────────────────────────────────────────────────────
+
+/// Count a stored detection of `node` in `domain` in the hourly bucket
+/// `hour` (`YYYY-MM-DDTHH`, UTC).
+pub fn count_node_detection(hour: &str, node: &str, domain: &str) {
+    let key = format!("watchdog:hour:{hour}");
+    let field = format!("{node}|{domain}");
+    let res = with_retry(|c| {
+        redis::pipe()
+            .hincr(&key, &field, 1i64)
+            .expire(&key, NODE_COUNTS_TTL_SECS)
+            .exec(c)
+    });
+    if let Err(e) = res {
+        debug!("count_node_detection failed: {e}");
+    }
+}
+
+/// The hourly buckets `hours`, each as `node|domain` → detections; empty
+/// for hours without detections.
+pub fn node_detection_counts(hours: &[String]) -> Result<Vec<HashMap<String, u64>>> {
+    with_retry(|c| {
+        let mut pipe = redis::pipe();
+        for hour in hours {
+            pipe.hgetall(format!("watchdog:hour:{hour}"));
+        }
+        pipe.query(c)
+    })
+    .context("node_detection_counts")
+}
+
+/// Record that `field` (`node|domain`) went quiet.  `false` when it was
+/// already recorded, by this or another processing instance.
+pub fn claim_node_alert(field: &str) -> bool {
+    with_retry(|c| c.hset_nx("watchdog:alerted", field, now_unix())).unwrap_or(false)
+}
+
+/// `node|domain` fields currently announced as quiet.
+pub fn node_alerts() -> Vec<String> {
+    with_retry(|c| c.hkeys("watchdog:alerted")).unwrap_or_default()
+}
+
+/// Forget that `field` was quiet, once it detects again.
+pub fn clear_node_alert(field: &str) {
+    if let Err(e) = with_retry(|c| c.hdel::<_, _, ()>("watchdog:alerted", field)) {
+        warn!("clear_node_alert failed: {e}");
+    }
+}
+
+/// Hand a quiet-station alert to the web container's push notifier.
+/// Skipped, like detections, when no browser has subscribed.
+pub fn queue_station_alert(node: &str, domain: &str, detections: u64, baseline: f64, hours: u32) {
+    let subscribers: usize = with_retry(|c| c.hlen("push:subscriptions")).unwrap_or(0);
+    if subscribers == 0 {
+        return;
+    }
+    let event = serde_json::json!({
+        "kind": "station_quiet",
+        "source_node": node,
+        "domain": domain,
+        "detections": detections,
+        "baseline": baseline,
+        "hours": hours,
+        "queued_at": now_unix(),
+    })
+    .to_string();
+    let res = with_retry(|c| {
+        redis::pipe()
+            .rpush("push:events", &event)
+            .ltrim("push:events", -PUSH_EVENTS_MAX, -1)
+            .exec(c)
+    });
+    if let Err(e) = res {
+        warn!("queue_station_alert failed: {e}");
+    }
+}
+
 // ── Settings ─────────────────────────────────────────────────────────────────
 /// Read the set of enabled audio model slugs from Redis.
 ///
@@ -0,0 +1,19 @@
+//! Modules shared by the `gaia-processing` server and the `gaia-admin`
+//! operator CLI.  Everything else lives in the server binary.
+
+use std::path::{Path, PathBuf};
+
+use gaia_common::config::Config;
+
+pub mod backup;
+pub mod db;
+pub mod export;
+
+/// The Parquet detection store: `detections/` next to `birds.db`.
+pub fn detections_dir(config: &Config) -> PathBuf {
+    config
+        .db_path
+        .parent()
+        .unwrap_or(Path::new("/data"))
+        .join("detections")
+}
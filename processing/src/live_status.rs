@@ -44,7 +44,7 @@ pub struct LivePrediction {
 }
 
 /// Directory where the live files are written.
-fn live_dir() -> PathBuf {
+pub fn live_dir() -> PathBuf {
     PathBuf::from(
         std::env::var("GAIA_DATA_DIR").unwrap_or_else(|_| "/data".to_string()),
     )
@@ -2,34 +2,88 @@
 //! runs inference, writes detections to Parquet, coordinates via Redis.
 
 mod accel;
+mod activity;
 mod agreement;
 mod analysis;
+mod api;
+mod chunk_cache;
+mod cli;
 mod client;
 mod compress;
+mod direction;
+mod disk_guard;
 mod download;
+mod ensemble;
+mod faults;
+mod folder_import;
+mod heartbeat;
+mod hwprobe;
+mod journal;
 mod kv;
 mod live_status;
 mod manifest;
 mod mel;
 mod migrate_parquet;
 mod model;
+#[cfg(feature = "mono")]
+mod mono;
+mod occurrence;
 mod parquet_store;
+mod pretrigger;
+mod rarity;
+mod reanalyze;
+mod reload;
 mod reporting;
+mod retention;
+mod rules;
 mod species_range;
+mod species_ref;
 mod spectrogram;
 mod taxonomy;
+mod tflite_probe;
+mod trigger;
+mod upload;
+mod watchdog;
+mod waveform;
 
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 
 use anyhow::{Context, Result};
+use clap::Parser;
 use tracing::{error, info};
 
 use gaia_common::detection::{Detection, ParsedFileName};
+use gaia_processing::{backup, db, detections_dir, export};
 
 static SHUTDOWN: AtomicBool = AtomicBool::new(false);
 
+/// Variant picked by the hardware probe for `m`, or `None` when the
+/// operator set `MODEL_VARIANT` (any value, including `default`) or the
+/// manifest has no variants.
+fn select_variant(
+    m: &manifest::ResolvedManifest,
+    configured: Option<&str>,
+    hardware: &hwprobe::Hardware,
+) -> Option<String> {
+    let download = m.manifest.download.as_ref()?;
+    if download.variants.is_empty() {
+        return None;
+    }
+    if let Some(v) = configured {
+        info!("{}: variant {v} (MODEL_VARIANT)", m.manifest.model.name);
+        return None;
+    }
+    let available: Vec<&str> = download.variants.keys().map(String::as_str).collect();
+    let (variant, why) = hwprobe::choose_variant(hardware, &available, &download.default_variant);
+    info!(
+        "{}: auto-selected variant {variant} ({why}; set MODEL_VARIANT to override)",
+        m.manifest.model.name
+    );
+    Some(variant)
+}
+
 fn exit_after_one_batch_enabled() -> bool {
     std::env::var("GAIA_EXIT_AFTER_ONE_BATCH")
         .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
@@ -76,6 +130,12 @@ pub struct ReportPayload {
     pub file: ParsedFileName,
     pub detections: Vec<Detection>,
     pub source_node: String,
+    pub source_node_id: String,
+    /// Slugs of the models flagged `ultrasonic` in their manifest.
+    pub ultrasonic_slugs: Vec<String>,
+    /// Coordinates the recording was analysed with (the capture node's
+    /// own, when it reports them).
+    pub location: Option<(f64, f64)>,
 }
 
 /// A downloaded file ready for analysis by a worker thread.
@@ -83,176 +143,168 @@ pub struct WorkItem {
     pub local_path: PathBuf,
     pub filename: String,
     pub base_url: String,
+    /// Capture node name stored as `Source_Node` (the URL if unknown).
+    pub source_node: String,
+    /// Persistent capture node id stored as `Source_Node_Id`; the name
+    /// can be edited, the id cannot.  Same as `source_node` for nodes
+    /// without one (uploads, imports, nodes too old to report it).
+    pub source_node_id: String,
+    /// Length the recording should have, checked before analysis;
+    /// `None` for uploads and imports, which come in any length.
+    pub expected_secs: Option<f64>,
     pub config_snapshot: gaia_common::config::Config,
 }
 
 fn main() -> Result<()> {
+    // Logs go to stderr so `--json` output on stdout stays parseable.
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "info".into()),
         )
+        .with_writer(std::io::stderr)
         .init();
-
-    // ── validate-model subcommand (build-time dry-run) ───────────────
-    // Usage: gaia-processing validate-model <path.onnx> [<path2.onnx> …]
-    //
-    // Loads each model with tract-onnx (load → optimise → runnable),
-    // identical to the runtime code path.  Exits 0 on success, 1 on
-    // failure.  This is invoked during `docker build` to catch
-    // tract-incompatible ONNX files before the container is published.
-    let args: Vec<String> = std::env::args().collect();
-    if args.get(1).map(|s| s.as_str()) == Some("validate-model") {
-        let paths = &args[2..];
-        if paths.is_empty() {
-            eprintln!("Usage: gaia-processing validate-model <model.onnx> [<model2.onnx> …]");
-            std::process::exit(2);
-        }
-        let mut failed = false;
-        for path in paths {
-            let p = std::path::Path::new(path);
-            info!("Validating with tract-onnx: {}", p.display());
-            match model::validate_onnx_with_tract(p) {
-                Ok(()) => info!("  PASS ✓  {}", p.display()),
-                Err(e) => {
-                    tracing::error!("  FAIL ✗  {}: {e:#}", p.display());
-                    failed = true;
-                }
+    faults::init();
+
+    let args = cli::Cli::parse();
+    let ok = match args.command {
+        None => return run(&cli::config_or_default(args.config), false),
+        Some(cli::Command::Run { config }) => return run(&cli::config_or_default(config), false),
+        Some(cli::Command::Mono { config }) => {
+            if !cfg!(feature = "mono") {
+                anyhow::bail!("This build has no mono mode (rebuild with --features mono)");
             }
+            return run(&cli::config_or_default(config), true);
         }
-        if failed {
-            std::process::exit(1);
+        Some(cli::Command::CheckConfig { config, json }) => {
+            cli::check_config(&config.path(), json)?
         }
-        return Ok(());
-    }
-
-    // ── validate-manifests subcommand (build-time manifest check) ────
-    // Usage: gaia-processing validate-manifests <models-dir>
-    //
-    // Parses every manifest.toml under models-dir with strict TOML
-    // validation (duplicate keys, missing required fields, wrong types).
-    // Exits 0 when all manifests are valid, 1 on any failure.
-    //
-    // Invoked during `docker build` before the post-build container
-    // e2e check so malformed manifests are caught early instead of
-    // silently skipping models.
-    if args.get(1).map(|s| s.as_str()) == Some("validate-manifests") {
-        let dir = args.get(2).unwrap_or_else(|| {
-            eprintln!("Usage: gaia-processing validate-manifests <models-dir>");
-            std::process::exit(2);
-        });
-        info!("Validating manifests in {dir}");
-        match manifest::validate_all_manifests(Path::new(dir)) {
-            Ok(paths) => {
-                info!("✅ All {} manifest(s) valid", paths.len());
-                return Ok(());
-            }
-            Err(e) => {
-                error!("❌ Manifest validation failed: {e:#}");
-                std::process::exit(1);
-            }
+        Some(cli::Command::Bench {
+            config,
+            file,
+            iterations,
+            json,
+        }) => cli::bench(&config.path(), &file, iterations, json)?,
+        Some(cli::Command::AnalyzeDir { dir, config, json }) => {
+            cli::analyze_dir(&config.path(), &dir, json)?
+        }
+        Some(cli::Command::Export {
+            out,
+            from,
+            to,
+            config,
+            json,
+        }) => cli::export(&config.path(), &out, from.as_deref(), to.as_deref(), json)?,
+        Some(cli::Command::Reanalyze {
+            recordings,
+            from,
+            to,
+            models,
+            config,
+            json,
+        }) => cli::reanalyze(
+            &config.path(),
+            recordings.as_deref(),
+            from.as_deref(),
+            to.as_deref(),
+            &models,
+            json,
+        )?,
+        Some(cli::Command::Db {
+            command: cli::DbCommand::Migrate { config, json },
+        }) => cli::db_migrate(&config.path(), json)?,
+        Some(cli::Command::ValidateModel { paths }) => validate_models(&paths),
+        Some(cli::Command::ValidateManifests { dir }) => validate_manifests(&dir),
+        Some(cli::Command::ReviewTaxonomy { table, merged }) => {
+            review_taxonomy(&table, merged.as_deref())
         }
+    };
+    if !ok {
+        std::process::exit(1);
     }
+    Ok(())
+}
 
-    // ── review-taxonomy subcommand (startup normalization check) ─────
-    // Usage: gaia-processing review-taxonomy <table.toml> [merged.toml]
-    //
-    // Validates scientific-name equivalences and class aliases, then
-    // writes a merged canonical table used by runtime normalization.
-    if args.get(1).map(|s| s.as_str()) == Some("review-taxonomy") {
-        let table = args.get(2).unwrap_or_else(|| {
-            eprintln!(
-                "Usage: gaia-processing review-taxonomy <taxonomy_table.toml> [merged_output.toml]"
-            );
-            std::process::exit(2);
-        });
-        let merged = args.get(3).map(std::path::PathBuf::from);
-        let merged_ref = merged.as_deref();
-        match taxonomy::review_and_merge(Path::new(table), merged_ref) {
-            Ok(report) => {
-                info!(
-                    "taxonomy review OK: species={}, aliases={}, class_aliases={}, class_overrides={}, table={}",
-                    report.species_count,
-                    report.alias_count,
-                    report.class_alias_count,
-                    report.class_override_count,
-                    report.table_path.display(),
-                );
-                if let Some(out) = report.merged_path {
-                    info!("taxonomy merged table written: {}", out.display());
-                }
-                return Ok(());
-            }
+/// Load each model with tract-onnx (load → optimise → runnable),
+/// identical to the runtime code path.  Invoked during `docker build` to
+/// catch tract-incompatible ONNX files before the container is published.
+fn validate_models(paths: &[PathBuf]) -> bool {
+    let mut failed = false;
+    for p in paths {
+        info!("Validating with tract-onnx: {}", p.display());
+        match model::validate_onnx_with_tract(p) {
+            Ok(()) => info!("  PASS ✓  {}", p.display()),
             Err(e) => {
-                error!("taxonomy review failed: {e:#}");
-                std::process::exit(1);
+                tracing::error!("  FAIL ✗  {}: {e:#}", p.display());
+                failed = true;
             }
         }
     }
+    !failed
+}
 
-    if std::env::var("RUST_LOG").map_or(false, |v| v.contains("debug")) {
-        info!("🔍 Debug logging ENABLED (RUST_LOG={})", std::env::var("RUST_LOG").unwrap_or_default());
+/// Parse every manifest.toml under `dir` with strict TOML validation
+/// (duplicate keys, missing required fields, wrong types, unknown keys,
+/// unsupported `schema_version`).
+///
+/// Invoked during `docker build` before the post-build container e2e
+/// check so malformed manifests are caught early instead of silently
+/// skipping models.
+fn validate_manifests(dir: &Path) -> bool {
+    info!("Validating manifests in {}", dir.display());
+    match manifest::validate_all_manifests(dir) {
+        Ok(paths) => {
+            info!("✅ All {} manifest(s) valid", paths.len());
+            true
+        }
+        Err(e) => {
+            error!("❌ Manifest validation failed: {e:#}");
+            false
+        }
     }
+}
 
-    // ── load config ──────────────────────────────────────────────────
-    let config_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| gaia_common::config::Config::default_path().to_string());
-    let mut config =
-        gaia_common::config::load(&PathBuf::from(&config_path)).context("Config load failed")?;
-
-    info!(
-        "Processing server starting (capture_url={})",
-        config.capture_server_url
-    );
-
-    // ── GPU acceleration check ───────────────────────────────────────
-    let accel_var = std::env::var("GAIA_ACCEL").unwrap_or_default();
-    match accel::accel_kind() {
-        accel::AccelKind::Rocm => {
-            info!(
-                "Acceleration env: GAIA_ACCEL={:?} ROCM_VISIBLE_DEVICES={:?}",
-                accel_var,
-                std::env::var("ROCM_VISIBLE_DEVICES").unwrap_or_default()
-            );
-            info!("ROCm acceleration requested — ORT will try MIGraphX → ROCm → CPU");
-        }
-        accel::AccelKind::Cuda => {
+/// Validate scientific-name equivalences and class aliases, then write
+/// a merged canonical table used by runtime normalization.
+fn review_taxonomy(table: &Path, merged: Option<&Path>) -> bool {
+    match taxonomy::review_and_merge(table, merged) {
+        Ok(report) => {
             info!(
-                "Acceleration env: GAIA_ACCEL={:?} CUDA_VISIBLE_DEVICES={:?}",
-                accel_var,
-                std::env::var("CUDA_VISIBLE_DEVICES").unwrap_or_default()
+                "taxonomy review OK: species={}, aliases={}, class_aliases={}, class_overrides={}, table={}",
+                report.species_count,
+                report.alias_count,
+                report.class_alias_count,
+                report.class_override_count,
+                report.table_path.display(),
             );
-            info!("CUDA acceleration requested — ORT will try TensorRT → CUDA → CPU");
+            if let Some(out) = report.merged_path {
+                info!("taxonomy merged table written: {}", out.display());
+            }
+            true
         }
-        accel::AccelKind::None => {
-            info!(
-                "GPU acceleration not requested (GAIA_ACCEL={:?}) — using CPU inference (tract-onnx)",
-                accel_var
-            );
+        Err(e) => {
+            error!("taxonomy review failed: {e:#}");
+            false
         }
     }
+}
 
-    // ── initialize Valkey / Redis coordination layer ──────────────────
-    kv::initialize()?;
-
-    // ── initialize Parquet detection store ────────────────────────────
-    {
-        let det_dir = config.db_path.parent().unwrap_or(Path::new("/data")).join("detections");
-        // Run the one-time Sci_Name normalisation migration before
-        // initialising the store, so the store sees clean data.
-        if let Err(e) = migrate_parquet::run_if_needed(&det_dir) {
-            tracing::warn!("Parquet migration failed (non-fatal): {e:#}");
-        }
-        parquet_store::initialize(&det_dir, "default")?;
-    };
-
-    // Register this processing instance for coordination.
-    {
-        kv::register_instance("default")?;
-        info!("Registered processing instance: \"default\"");
+/// One journal per processing instance, next to the detection store.
+fn journal_path(config: &gaia_common::config::Config) -> PathBuf {
+    let data_dir = config.db_path.parent().unwrap_or(Path::new("/data"));
+    if config.processing_instance.is_empty() {
+        data_dir.join("processing_journal.duckdb")
+    } else {
+        data_dir.join(format!("processing_journal_{}.duckdb", config.processing_instance))
     }
+}
 
+/// Discover the manifests selected by `MODEL_SLUGS`, download missing
+/// model files and load one copy of each model.  Exits when no model
+/// can be loaded.
+fn load_models(
+    config: &gaia_common::config::Config,
+) -> Result<(Vec<manifest::ResolvedManifest>, Vec<model::LoadedModel>)> {
     // ── discover and load models ─────────────────────────────────────
     let mut manifests = manifest::discover_manifests(&config.model_dir)?;
     if !config.model_slugs.is_empty() {
@@ -275,14 +327,32 @@ fn main() -> Result<()> {
     }
 
     // ── auto-download models from Zenodo if needed ───────────────────
+    let hardware = hwprobe::probe();
+    info!("Hardware probe: {hardware}");
     for m in &mut manifests {
         // Download individual files (e.g. ONNX from HuggingFace)
         if let Err(e) = download::ensure_direct_files(m) {
             tracing::warn!("Direct file download failed for {}: {e:#}", m.manifest.model.name);
         }
-        // Download variant-based files from Zenodo
+        // Download variant-based files from Zenodo.  Without
+        // MODEL_VARIANT the variant is picked from the hardware probe;
+        // if that one cannot be fetched, fall back to the manifest default.
+        let auto = select_variant(m, config.model_variant.as_deref(), &hardware);
         if let Some(variant) = m.effective_variant(config.model_variant.as_deref()) {
-            download::ensure_model_files(m, &variant)?;
+            match auto {
+                Some(auto) if auto != variant => {
+                    let pristine = m.clone();
+                    if let Err(e) = download::ensure_model_files(m, &auto) {
+                        tracing::warn!(
+                            "{}: variant {auto} unavailable ({e:#}) — using manifest default {variant}",
+                            m.manifest.model.name
+                        );
+                        *m = pristine;
+                        download::ensure_model_files(m, &variant)?;
+                    }
+                }
+                _ => download::ensure_model_files(m, &variant)?,
+            }
         }
         // Convert TFLite → ONNX if needed (best-effort, non-fatal).
         if let Err(e) = download::ensure_onnx_file(m) {
@@ -294,12 +364,14 @@ fn main() -> Result<()> {
         }
     }
 
+    model::configure_inference_threads(config.inference_threads);
+
     let mut models = Vec::with_capacity(manifests.len());
     for m in &manifests {
-        // Wrap in catch_unwind because tract-tflite can panic on
-        // unsupported tensor types (e.g. float16 in the fp16 variant).
+        // Wrap in catch_unwind because tract-tflite can panic on model
+        // contents the pre-load tensor type check does not catch.
         let load_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            model::load_model(m, &config)
+            model::load_model(m, config)
         }));
         match load_result {
             Ok(Ok(loaded)) => {
@@ -317,9 +389,9 @@ fn main() -> Result<()> {
             }
             Err(_) => {
                 tracing::error!(
-                    "Model {} panicked during loading – this usually means the \
-                     TFLite file uses an unsupported tensor type (e.g. float16). \
-                     Set MODEL_VARIANT=fp32 or MODEL_VARIANT=int8 in gaia.conf.",
+                    "Model {} panicked during loading – the TFLite file likely \
+                     uses an operator or tensor layout tract does not support. \
+                     Try MODEL_VARIANT=fp32 in gaia.conf or configure an onnx_file.",
                     m.manifest.model.name,
                 );
             }
@@ -344,6 +416,91 @@ fn main() -> Result<()> {
         );
     }
 
+    Ok((manifests, models))
+}
+
+/// Run the processing server until shutdown.  In `mono` mode it records
+/// with gaia-capture's modules in this process and analyses each
+/// finished segment in place instead of polling a capture server.
+fn run(config_path: &Path, mono: bool) -> Result<()> {
+    if std::env::var("RUST_LOG").map_or(false, |v| v.contains("debug")) {
+        info!("🔍 Debug logging ENABLED (RUST_LOG={})", std::env::var("RUST_LOG").unwrap_or_default());
+    }
+
+    // ── load config ──────────────────────────────────────────────────
+    let mut config = gaia_common::config::load(config_path).context("Config load failed")?;
+
+    info!(
+        "Processing server starting (capture_url={})",
+        config.capture_server_url
+    );
+
+    // ── GPU acceleration check ───────────────────────────────────────
+    let accel_var = std::env::var("GAIA_ACCEL").unwrap_or_default();
+    match accel::accel_kind() {
+        accel::AccelKind::Rocm => {
+            info!(
+                "Acceleration env: GAIA_ACCEL={:?} ROCM_VISIBLE_DEVICES={:?}",
+                accel_var,
+                std::env::var("ROCM_VISIBLE_DEVICES").unwrap_or_default()
+            );
+            info!("ROCm acceleration requested — ORT will try MIGraphX → ROCm → CPU");
+        }
+        accel::AccelKind::Cuda => {
+            info!(
+                "Acceleration env: GAIA_ACCEL={:?} CUDA_VISIBLE_DEVICES={:?}",
+                accel_var,
+                std::env::var("CUDA_VISIBLE_DEVICES").unwrap_or_default()
+            );
+            info!("CUDA acceleration requested — ORT will try TensorRT → CUDA → CPU");
+        }
+        accel::AccelKind::None => {
+            info!(
+                "GPU acceleration not requested (GAIA_ACCEL={:?}) — using CPU inference (tract-onnx)",
+                accel_var
+            );
+        }
+    }
+
+    // ── bring birds.db up to the schema shared with the web server ────
+    db::initialize(&config.db_path)?;
+
+    // ── initialize Valkey / Redis coordination layer ──────────────────
+    kv::initialize()?;
+
+    // ── initialize Parquet detection store ────────────────────────────
+    {
+        let det_dir = detections_dir(&config);
+        // Run the one-time Sci_Name normalisation migration before
+        // initialising the store, so the store sees clean data.
+        if let Err(e) = migrate_parquet::run_if_needed(&det_dir) {
+            tracing::warn!("Parquet migration failed (non-fatal): {e:#}");
+        }
+        parquet_store::initialize(&det_dir, "default")?;
+    };
+
+    // ── open the processing journal (survives crashes and restarts) ──
+    journal::initialize(&journal_path(&config))?;
+
+    // Register this processing instance for coordination.
+    {
+        kv::register_instance("default")?;
+        info!("Registered processing instance: \"default\"");
+    }
+
+    let (manifests, mut models) = load_models(&config)?;
+
+    // ── species reference and expected-occurrence tables for gaia-web ─
+    {
+        let data_dir = config.db_path.parent().unwrap_or(Path::new("/data"));
+        if let Err(e) = species_ref::write_all(&models, data_dir, &config.database_lang) {
+            tracing::warn!("Species reference table not written (non-fatal): {e:#}");
+        }
+        if let Err(e) = occurrence::write_all(&models, data_dir, &config) {
+            tracing::warn!("Expected occurrence not written (non-fatal): {e:#}");
+        }
+    }
+
     // ── backfill per-species taxonomic class ─────────────────────────
     // Models whose labels CSV contains a `class` column (e.g. BirdNET+
     // V3.0) now set Domain per-species.  The old SQLite migration is
@@ -359,15 +516,23 @@ fn main() -> Result<()> {
 
     // ── ctrl-c ───────────────────────────────────────────────────────
     let force_exit_on_sigint = exit_after_one_batch_enabled();
+    // The first signal stops polling; workers finish the file they are
+    // analysing and the reporting queue is drained before exit.  A second
+    // signal exits immediately.
     ctrlc::set_handler(move || {
-        SHUTDOWN.store(true, Ordering::Relaxed);
-        info!("Shutdown signal received");
+        if SHUTDOWN.swap(true, Ordering::Relaxed) {
+            tracing::warn!("Second shutdown signal — exiting immediately");
+            std::process::exit(130);
+        }
+        info!("Shutdown signal received — finishing in-flight analysis");
         if force_exit_on_sigint {
             std::process::exit(130);
         }
     })
     .context("Cannot set Ctrl-C handler")?;
     start_forced_shutdown_watchdog();
+    // After the Ctrl-C handler, so SIGHUP reloads instead of stopping.
+    reload::init(config_path, &config);
 
     // ── mDNS registration + capture discovery ──────────────────────
     // With network_mode: host, mDNS multicast reaches the physical
@@ -376,7 +541,9 @@ fn main() -> Result<()> {
     //
     // Setting GAIA_DISABLE_MDNS=1 skips mDNS for environments where
     // multicast is not available (e.g. bridge networking, CI).
-    let discovery = if std::env::var("GAIA_DISABLE_MDNS").is_ok() {
+    let discovery = if mono {
+        None
+    } else if std::env::var("GAIA_DISABLE_MDNS").is_ok() {
         info!(
             "GAIA_DISABLE_MDNS set – using {} (mDNS skipped)",
             config.capture_server_url
@@ -384,9 +551,17 @@ fn main() -> Result<()> {
         None
     } else {
         info!("mDNS: starting processing discovery handle");
+        // Advertised (with its version) only when the REST API is on;
+        // otherwise processing has no HTTP port and just browses.
+        let api_port: u16 = config
+            .processing_api_addr
+            .as_deref()
+            .and_then(|addr| addr.rsplit(':').next()?.parse().ok())
+            .unwrap_or(0);
         match gaia_common::discovery::register(
             gaia_common::discovery::ServiceRole::Processing,
-            0, // processing doesn't expose an HTTP port
+            api_port,
+            &[],
         ) {
             Ok(h) => {
                 info!("mDNS: processing discovery ready as {}", h.instance_name());
@@ -399,24 +574,104 @@ fn main() -> Result<()> {
         }
     };
 
+    // ── work channel: poll thread (uploads, imports) → worker threads ─────────────────
+    let (work_tx, work_rx) = mpsc::sync_channel::<WorkItem>(num_workers * 2);
+    let work_rx = std::sync::Arc::new(std::sync::Mutex::new(work_rx));
+
+    // ── optional headless REST API ───────────────────────────────────
+    let api_thread = match config.processing_api_addr.as_deref() {
+        Some(addr) => {
+            let det_dir = detections_dir(&config);
+            let statuses = models
+                .iter()
+                .map(|m| {
+                    let section = &m.manifest.manifest.model;
+                    gaia_common::protocol::ModelStatus {
+                        slug: m.manifest.slug(),
+                        name: section.name.clone(),
+                        domain: section.domain.clone(),
+                        sample_rate: section.sample_rate,
+                        chunk_duration: section.chunk_duration,
+                        beta: section.beta,
+                        version: m.manifest.version(),
+                        variant: m.manifest.variant.clone().unwrap_or_default(),
+                        runtime: m.runtime().to_string(),
+                        enabled: true,
+                    }
+                })
+                .collect();
+            let writes = [
+                upload::router(&config, work_tx.clone(), &SHUTDOWN),
+                folder_import::router(&config, work_tx.clone(), &SHUTDOWN),
+            ]
+            .into_iter()
+            .flatten()
+            .reduce(axum::Router::merge);
+            Some(api::spawn(addr, det_dir, statuses, writes, &SHUTDOWN)?)
+        }
+        None => None,
+    };
+
+    // ── pipeline activity for the dashboard ──────────────────────────
+    let activity_thread = activity::spawn_writer(&SHUTDOWN)?;
+
+    // ── clock sync monitor (reported by /api/health) ─────────────────
+    if let Err(e) = gaia_common::timesync::spawn_monitor(config.clock_max_offset_ms) {
+        tracing::warn!("Cannot start the clock sync monitor: {e}");
+    }
+
+    // ── uptime heartbeat (HEARTBEAT_URL) ─────────────────────────────
+    let heartbeat_thread = heartbeat::spawn(&config, &SHUTDOWN)?;
+
     // ── compression thread (fallback sweep every 30 min) ──────────
-    // Clips are now converted to Opus inline during extraction, but
+    // Clips are transcoded to CLIP_FORMAT inline during extraction, but
     // the background sweep catches any files that were missed (e.g.
-    // ffmpeg was temporarily unavailable, or legacy WAV files).
+    // ffmpeg was temporarily unavailable, or imported MP3 clips).
     let compress_extracted = config.extracted_dir.clone();
     let compress_db = config.db_path.clone();
+    let compress_encoding = compress::ClipEncoding::from_config(&config);
     let compress_thread = std::thread::Builder::new()
         .name("compression".into())
         .spawn(move || {
             compress::compress_loop(
                 compress_extracted,
                 compress_db,
+                compress_encoding,
                 std::time::Duration::from_secs(30 * 60), // every 30 min
                 &SHUTDOWN,
             );
         })
         .context("Cannot spawn compression thread")?;
 
+    // ── backup thread (weekly snapshot of the detection store) ──────
+    let backup_config = config.clone();
+    let backup_det_dir = detections_dir(&config);
+    let backup_thread = std::thread::Builder::new()
+        .name("backup".into())
+        .spawn(move || {
+            backup::backup_loop(backup_config, backup_det_dir, &SHUTDOWN);
+        })
+        .context("Cannot spawn backup thread")?;
+
+    // ── retention thread (daily clip pruning, see retention.rs) ─────
+    let retention_config = config.clone();
+    let retention_det_dir = detections_dir(&config);
+    let retention_thread = std::thread::Builder::new()
+        .name("retention".into())
+        .spawn(move || {
+            retention::retention_loop(retention_config, retention_det_dir, &SHUTDOWN);
+        })
+        .context("Cannot spawn retention thread")?;
+
+    // ── watchdog thread (quiet stations, see watchdog.rs) ───────────
+    let watchdog_config = config.clone();
+    let watchdog_thread = std::thread::Builder::new()
+        .name("watchdog".into())
+        .spawn(move || {
+            watchdog::watchdog_loop(watchdog_config, &SHUTDOWN);
+        })
+        .context("Cannot spawn watchdog thread")?;
+
     // ── reporting thread ─────────────────────────────────────────────
     let (report_tx, report_rx) = mpsc::sync_channel::<ReportPayload>(16);
     let report_config = config.clone();
@@ -428,10 +683,6 @@ fn main() -> Result<()> {
         })
         .context("Cannot spawn reporting thread")?;
 
-    // ── work channel: poll thread → worker threads ───────────────────
-    let (work_tx, work_rx) = mpsc::sync_channel::<WorkItem>(num_workers * 2);
-    let work_rx = std::sync::Arc::new(std::sync::Mutex::new(work_rx));
-
     // ── spawn worker threads ─────────────────────────────────────────
     // Worker 0 takes the already-loaded models; workers 1..N each load
     // their own copy from the same manifests.
@@ -470,16 +721,14 @@ fn main() -> Result<()> {
             m
         };
 
+        // Per-worker HTTP client for deletion requests.
+        let client = client::capture_client(&config)?;
+
         let handle = std::thread::Builder::new()
             .name(format!("worker-{worker_id}"))
             .spawn(move || {
                 info!("Worker {worker_id} ready ({} model(s))", worker_models.len());
-
-                // Build a per-worker HTTP client for deletion requests.
-                let client = reqwest::blocking::Client::builder()
-                    .timeout(std::time::Duration::from_secs(30))
-                    .build()
-                    .expect("Cannot create HTTP client");
+                activity::register_worker(worker_id);
 
                 loop {
                     // Receive work items from the shared channel.
@@ -491,46 +740,99 @@ fn main() -> Result<()> {
                         Ok(item) => item,
                         Err(_) => break, // channel closed → shutdown
                     };
+                    activity::dequeue(activity::Queue::Analysis);
+
+                    // Queued but not started: leave it on the capture
+                    // node, it is fetched again on the next start.  Files
+                    // analysed in place (mono mode, uploads) are the only
+                    // copy and are queued again from disk.
+                    if SHUTDOWN.load(Ordering::Relaxed) {
+                        info!("W{worker_id} shutting down — leaving {} for later", item.filename);
+                        if !item.base_url.is_empty() {
+                            let _ = std::fs::remove_file(&item.local_path);
+                        }
+                        continue;
+                    }
 
                     tracing::debug!("W{worker_id} analysing {}", item.filename);
 
                     // ── run analysis ──────────────────────────────────
-                    if let Err(e) = analysis::process_file(
+                    activity::begin_file(&item.filename, &item.source_node);
+                    let analysed = analysis::process_file(
                         &item.local_path,
                         &mut worker_models,
                         &item.config_snapshot,
                         &report_tx,
-                        &item.base_url,
-                    ) {
-                        tracing::error!(
-                            "W{worker_id} error processing {}: {e:#}",
-                            item.filename
-                        );
+                        &item.source_node,
+                        &item.source_node_id,
+                        item.expected_secs,
+                    );
+                    activity::end_file();
+                    let failed = analysed.is_err();
+                    let interest = match analysed {
+                        Ok(interest) => interest,
+                        Err(e) => {
+                            tracing::error!(
+                                "W{worker_id} error processing {}: {e:#}",
+                                item.filename
+                            );
+                            trigger::Interest::default()
+                        }
+                    };
+
+                    // Journal before deleting, so a crash in between
+                    // never analyses the recording a second time.
+                    let key = journal::key(&item.source_node_id, &item.filename);
+                    if let Err(e) = journal::mark(&key, journal::Stage::Analysed) {
+                        tracing::warn!("W{worker_id} cannot journal {}: {e:#}", item.filename);
+                    }
+
+                    // Mono mode or an upload: the file was analysed in
+                    // place.  The reporting thread removes it, unless
+                    // analysis failed before anything was reported.
+                    if item.base_url.is_empty() {
+                        if failed {
+                            let _ = std::fs::remove_file(&item.local_path);
+                        }
+                        continue;
+                    }
+
+                    // ── keep interesting ring-buffer segments ────────
+                    // Nodes not in ring-buffer mode decline, and the
+                    // recording is deleted as usual.  When asking fails
+                    // it stays put: the polling loop deletes analysed
+                    // recordings it lists again, a ring drops it.
+                    if interest.worth_keeping(item.config_snapshot.ring_keep_energy_db) {
+                        match client.keep(&item.base_url, &item.filename) {
+                            Ok(true) => {
+                                info!(
+                                    "W{worker_id} kept {} on the capture node ({} detection(s), {:.0} dB burst)",
+                                    item.filename, interest.detections, interest.burst_db
+                                );
+                                continue;
+                            }
+                            Ok(false) => {}
+                            Err(e) => {
+                                tracing::warn!(
+                                    "W{worker_id} failed to keep {}: {e}",
+                                    item.filename
+                                );
+                                continue;
+                            }
+                        }
                     }
 
                     // ── delete recording from capture server ─────────
                     // Single processing container: delete immediately
                     // after analysis (no multi-instance coordination).
-                    let url = format!(
-                        "{}/api/recordings/{}",
-                        item.base_url, item.filename
-                    );
-                    match client.delete(&url).send() {
-                        Ok(resp) if resp.status().is_success()
-                            || resp.status() == reqwest::StatusCode::NOT_FOUND =>
-                        {
+                    // A failed delete is retried by the polling loop.
+                    match client.delete(&item.base_url, &item.filename) {
+                        Ok(_) => {
                             info!(
                                 "W{worker_id} deleted {}",
                                 item.filename
                             );
                         }
-                        Ok(resp) => {
-                            tracing::warn!(
-                                "W{worker_id} DELETE {} returned {}",
-                                item.filename,
-                                resp.status()
-                            );
-                        }
                         Err(e) => {
                             tracing::warn!(
                                 "W{worker_id} failed to delete {}: {e}",
@@ -548,13 +850,25 @@ fn main() -> Result<()> {
     }
 
     // ── poll capture server(s) and dispatch to workers ───────────────
-    if let Err(e) = client::poll_and_dispatch(
-        &mut config,
-        discovery.as_ref(),
-        &work_tx,
-        &SHUTDOWN,
-    ) {
+    #[cfg(feature = "mono")]
+    let dispatched = if mono {
+        mono::run(&mut config, &work_tx, &SHUTDOWN)
+    } else {
+        client::poll_and_dispatch(&mut config, discovery.as_ref(), &work_tx, &SHUTDOWN)
+    };
+    #[cfg(not(feature = "mono"))]
+    let dispatched =
+        client::poll_and_dispatch(&mut config, discovery.as_ref(), &work_tx, &SHUTDOWN);
+    if let Err(e) = dispatched {
         tracing::error!("Processing loop error: {e:#}");
+        heartbeat::fail(&config, &format!("Processing loop error: {e:#}"));
+    }
+
+    // Stop the API first: the upload route holds a sender of the work
+    // channel, which must close for the workers to finish.
+    SHUTDOWN.store(true, Ordering::Relaxed);
+    if let Some(h) = api_thread {
+        h.join().ok();
     }
 
     // Signal workers to finish, then wait.
@@ -563,10 +877,18 @@ fn main() -> Result<()> {
         h.join().ok();
     }
 
-    // Signal reporting thread to finish
+    // Signal reporting thread to finish; it drains what the workers
+    // queued (flushing Parquet after each report) before returning.
     drop(report_tx);
     report_thread.join().ok();
     compress_thread.join().ok();
+    backup_thread.join().ok();
+    retention_thread.join().ok();
+    watchdog_thread.join().ok();
+    activity_thread.join().ok();
+    if let Some(h) = heartbeat_thread {
+        h.join().ok();
+    }
 
     // Clean up mDNS
     if let Some(dh) = discovery {
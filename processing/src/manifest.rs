@@ -3,14 +3,18 @@
 //! Each model lives in its own directory with a `manifest.toml`:
 //!
 //! ```toml
+//! schema_version = 1
+//!
 //! [model]
 //! name = "BirdNET V2.4"
+//! version = "2.4"
 //! domain = "birds"
 //! sample_rate = 48000
 //! chunk_duration = 3.0
 //! tflite_file = "audio-model-fp16.tflite"
 //! labels_file = "en_us.txt"
 //! v1_metadata = false
+//! ignore_labels = ["Engine", "Siren", "Fireworks", "Gun", "Power tools"]
 //!
 //! [metadata_model]
 //! enabled = true
@@ -35,11 +39,16 @@
 //! md5 = "69becc3e8eb1c72d1d9dae7f21062c74"
 //! tflite_file = "audio-model-int8.tflite"
 //! ```
+//!
+//! Unknown keys are ignored when a model is loaded, with a warning;
+//! `gaia-processing validate-manifests` (alias `validate-models`) rejects
+//! them, listing the keys the table accepts.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use gaia_common::audio::{Chunking, PreFilter};
 use serde::Deserialize;
 use tracing::info;
 
@@ -72,16 +81,42 @@ pub enum ScoreTransform {
     None,
 }
 
+/// What the entries of `labels_file` are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelFormat {
+    /// Scientific names, optionally `Sci_Common` or CSV with a
+    /// `sci_name` column (BirdNET, Perch 2.0).
+    #[default]
+    ScientificNames,
+    /// eBird 6-letter species codes (`amerob`), as emitted by Google's
+    /// bird-vocalization-classifier (Perch v1).  Translated through
+    /// `label_map_file`.
+    EbirdCodes,
+}
+
+/// Newest manifest format this build reads.
+pub const SCHEMA_VERSION: u32 = 1;
+
 /// Top-level manifest structure.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Manifest {
+    /// Format of the manifest; older manifests without one are version 1.
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     pub model: ModelSection,
     #[serde(default)]
     pub metadata_model: Option<MetadataSection>,
     #[serde(default)]
+    pub classifier_head: Option<ClassifierHeadSection>,
+    #[serde(default)]
     pub language: Option<LanguageSection>,
     #[serde(default)]
     pub download: Option<DownloadSection>,
+    /// High-pass / low-pass / noise gate applied to the signal before it
+    /// is chunked (`[filter]`); off by default.
+    #[serde(default)]
+    pub filter: PreFilter,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -91,6 +126,10 @@ pub struct ModelSection {
     /// from `name` when not explicitly set in the manifest.
     #[serde(default)]
     pub slug: Option<String>,
+    /// Model version recorded with each detection (e.g. `"2.4"`).
+    /// Taken from the last word of `name` (`"BirdNET V2.4"`) when not set.
+    #[serde(default)]
+    pub version: Option<String>,
     pub domain: String,
     pub sample_rate: u32,
     pub chunk_duration: f64,
@@ -151,6 +190,11 @@ pub struct ModelSection {
     /// the model is less proven than established ones (e.g. BirdNET V2.4).
     #[serde(default)]
     pub beta: bool,
+    /// The model listens above human hearing (e.g. bats).  Extracted
+    /// clips get a time-expanded companion (`TIME_EXPANSION`) so they
+    /// can be listened to in the dashboard.
+    #[serde(default)]
+    pub ultrasonic: bool,
     /// Trust weight for cross-model agreement scoring.
     ///
     /// Higher values give this model more influence when computing
@@ -158,6 +202,55 @@ pub struct ModelSection {
     /// 1.0; experimental models should use lower values (e.g. 0.5).
     #[serde(default = "default_trust_weight")]
     pub trust_weight: f64,
+    /// Format of the labels file entries.
+    #[serde(default)]
+    pub label_format: LabelFormat,
+    /// CSV mapping label codes to scientific names (and optionally
+    /// common names), relative to the model directory.  Required for
+    /// `label_format = "ebird_codes"`; the eBird taxonomy CSV works as-is.
+    #[serde(default)]
+    pub label_map_file: Option<String>,
+    /// Labels never worth storing, e.g. BirdNET's `"Engine"`, `"Siren"`
+    /// and `"Fireworks"`.  Matched case-insensitively against the label
+    /// or its common name and dropped right after prediction, before
+    /// any threshold, so users need not list them in
+    /// `exclude_species_list.txt`.
+    #[serde(default)]
+    pub ignore_labels: Vec<String>,
+    /// Shortest input (seconds) a variable-length model accepts; shorter
+    /// audio is zero-padded to it.  Defaults to `chunk_duration`.
+    /// Setting `min_chunk`, `max_chunk` or `whole_file` marks the model
+    /// as variable-length: chunks are no longer padded to one size.
+    #[serde(default)]
+    pub min_chunk: Option<f64>,
+    /// Longest input (seconds) of a variable-length model: the file is
+    /// cut into windows this long and the last one keeps its own length.
+    /// Defaults to `chunk_duration`.
+    #[serde(default)]
+    pub max_chunk: Option<f64>,
+    /// Score each recording as a single input, however long it is
+    /// (e.g. frog call classifiers).  Excludes `max_chunk`.
+    #[serde(default)]
+    pub whole_file: bool,
+}
+
+impl ModelSection {
+    /// How recordings are cut for this model.
+    pub fn chunking(&self) -> Chunking {
+        if self.min_chunk.is_none() && self.max_chunk.is_none() && !self.whole_file {
+            return Chunking::Fixed(self.chunk_duration);
+        }
+        let max = if self.whole_file {
+            None
+        } else {
+            Some(self.max_chunk.unwrap_or(self.chunk_duration))
+        };
+        let min = self.min_chunk.unwrap_or(self.chunk_duration);
+        Chunking::Variable {
+            min: max.map_or(min, |m| min.min(m)),
+            max,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -179,6 +272,41 @@ pub struct MetadataSection {
     pub labels_file: Option<String>,
 }
 
+/// A small classifier (e.g. an ONNX MLP or logistic regression) run on
+/// the base model's embeddings to recognise locally-trained classes,
+/// such as a frog the base model does not know.
+///
+/// ```toml
+/// [classifier_head]
+/// onnx_file = "local-head.onnx"
+/// labels_file = "local-labels.txt"
+/// embedding_output_index = 0
+/// domain = "local"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClassifierHeadSection {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// ONNX head taking a `[1, D]` embedding and returning one score per
+    /// label.
+    pub onnx_file: String,
+    /// Labels of the head's classes, in output order (same formats as
+    /// the base model's `labels_file`).
+    pub labels_file: String,
+    /// Which output of the base model holds the embedding vector
+    /// (BirdNET+ V3.0: `0`, the 1280-dim embedding).
+    #[serde(default)]
+    pub embedding_output_index: usize,
+    /// Domain recorded on the head's detections, kept apart from the
+    /// base model's so local classes can be filtered on their own.
+    #[serde(default = "default_head_domain")]
+    pub domain: String,
+    /// How the head's outputs become 0..1 scores.  Defaults to
+    /// sigmoid, which suits logistic heads exported with raw logits.
+    #[serde(default)]
+    pub score_transform: ScoreTransform,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct LanguageSection {
     /// Subdirectory containing `labels_{lang}.json` files.
@@ -209,6 +337,19 @@ pub struct DownloadSection {
     /// Expected MD5 hex digest of the Keras zip file.
     #[serde(default)]
     pub keras_md5: Option<String>,
+    /// Expected SHA-256 hex digest of the Keras zip file.  Checked
+    /// instead of `keras_md5` when both are given.
+    #[serde(default)]
+    pub keras_sha256: Option<String>,
+    /// Alternative full URLs for the Keras zip, tried in order when the
+    /// Zenodo download fails or does not match the checksum.
+    #[serde(default)]
+    pub keras_mirrors: Vec<String>,
+    /// Number of parallel HTTP range requests used for large archives
+    /// (1 = a single connection).  Only used when the server advertises
+    /// `Accept-Ranges: bytes`.
+    #[serde(default = "default_segments")]
+    pub segments: u32,
     /// Direct file downloads: maps local filename → remote URL.
     ///
     /// Files are downloaded individually (not from a Zenodo zip).  Useful
@@ -231,6 +372,12 @@ pub struct VariantInfo {
     /// Expected MD5 hex digest of the zip file.
     #[serde(default)]
     pub md5: Option<String>,
+    /// Expected SHA-256 hex digest of the zip file (preferred over `md5`).
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Alternative full URLs for the same zip, tried in order after Zenodo.
+    #[serde(default)]
+    pub mirrors: Vec<String>,
     /// Override for `[model].tflite_file` when this variant is selected.
     #[serde(default)]
     pub tflite_file: Option<String>,    /// Override for `[model].onnx_file` when this variant is selected.
@@ -243,14 +390,26 @@ pub struct VariantInfo {
     pub metadata_tflite_file: Option<String>,
 }
 
+fn default_schema_version() -> u32 {
+    1
+}
+
 fn default_variant() -> String {
     "fp16".to_string()
 }
 
+fn default_segments() -> u32 {
+    1
+}
+
 fn default_true() -> bool {
     true
 }
 
+fn default_head_domain() -> String {
+    "local".to_string()
+}
+
 fn default_trust_weight() -> f64 {
     1.0
 }
@@ -264,6 +423,8 @@ pub struct ResolvedManifest {
     pub manifest: Manifest,
     /// Directory containing the manifest and model files.
     pub base_dir: PathBuf,
+    /// Download variant applied by [`Self::apply_variant`] (e.g. `"fp16"`).
+    pub variant: Option<String>,
 }
 
 impl ResolvedManifest {
@@ -284,6 +445,15 @@ impl ResolvedManifest {
         self.base_dir.join(&self.manifest.model.labels_file)
     }
 
+    /// Path to the code → scientific-name map, if configured.
+    pub fn label_map_path(&self) -> Option<PathBuf> {
+        self.manifest
+            .model
+            .label_map_file
+            .as_ref()
+            .map(|f| self.base_dir.join(f))
+    }
+
     pub fn metadata_tflite_path(&self) -> Option<PathBuf> {
         self.manifest
             .metadata_model
@@ -315,6 +485,24 @@ impl ResolvedManifest {
             .unwrap_or_else(|| self.labels_path())
     }
 
+    /// ONNX file of the embedding classifier head, if one is enabled.
+    pub fn classifier_head_path(&self) -> Option<PathBuf> {
+        self.manifest
+            .classifier_head
+            .as_ref()
+            .filter(|h| h.enabled)
+            .map(|h| self.base_dir.join(&h.onnx_file))
+    }
+
+    /// Labels file of the embedding classifier head, if one is enabled.
+    pub fn classifier_head_labels_path(&self) -> Option<PathBuf> {
+        self.manifest
+            .classifier_head
+            .as_ref()
+            .filter(|h| h.enabled)
+            .map(|h| self.base_dir.join(&h.labels_file))
+    }
+
     pub fn language_dir(&self) -> PathBuf {
         let sub = self
             .manifest
@@ -366,6 +554,25 @@ impl ResolvedManifest {
         slug.trim_end_matches('-').to_string()
     }
 
+    /// Version of the model: the manifest's `version`, else the last word
+    /// of the name when it looks like one (`"BirdNET V2.4"` → `"2.4"`),
+    /// else empty.
+    pub fn version(&self) -> String {
+        if let Some(v) = self.manifest.model.version.as_deref().map(str::trim) {
+            if !v.is_empty() {
+                return v.to_string();
+            }
+        }
+        let name = &self.manifest.model.name;
+        let last = name.split_whitespace().last().unwrap_or("");
+        let number = last.strip_prefix(['v', 'V']).unwrap_or(last);
+        if number.starts_with(|c: char| c.is_ascii_digit()) {
+            number.to_string()
+        } else {
+            String::new()
+        }
+    }
+
     /// Apply variant overrides from the `[download]` section.
     ///
     /// If the selected variant provides `tflite_file`, `labels_file`, or
@@ -408,14 +615,18 @@ impl ResolvedManifest {
                 meta.tflite_file = mf.clone();
             }
         }
+        self.variant = Some(variant_name.to_string());
 
         Ok(())
     }
 
     /// Resolve the effective variant name from config or manifest default.
+    /// `MODEL_VARIANT=default` selects the manifest default explicitly
+    /// (turning off hardware-based auto-selection).
     pub fn effective_variant(&self, config_variant: Option<&str>) -> Option<String> {
         self.manifest.download.as_ref().map(|d| {
             config_variant
+                .filter(|v| !v.eq_ignore_ascii_case("default"))
                 .unwrap_or(&d.default_variant)
                 .to_string()
         })
@@ -434,11 +645,126 @@ pub fn validate_manifest_toml(text: &str) -> Result<()> {
     let _: toml::Value = toml::from_str(text).context("TOML syntax error")?;
     // Step 2: deserialise into the typed Manifest struct — catches
     // missing required fields, wrong types, unknown enum variants.
-    let _: Manifest = toml::from_str(text).context("Manifest schema error")?;
+    let m: Manifest = toml::from_str(text).context("Manifest schema error")?;
+    if m.schema_version == 0 || m.schema_version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "schema_version {} is not supported: this gaia-processing reads versions 1 to \
+             {SCHEMA_VERSION}; update it to load this model",
+            m.schema_version
+        );
+    }
+    if m.model.label_format == LabelFormat::EbirdCodes && m.model.label_map_file.is_none() {
+        anyhow::bail!("label_format = \"ebird_codes\" requires label_map_file");
+    }
+    validate_chunking(&m.model)?;
+    validate_filter(&m.filter, m.model.sample_rate)?;
+    Ok(())
+}
+
+/// [`validate_manifest_toml`], also rejecting keys no manifest table
+/// accepts (usually typos, which a normal load only warns about).
+pub fn validate_manifest_toml_strict(text: &str) -> Result<()> {
+    validate_manifest_toml(text)?;
+    let (_, unknown) = parse_manifest(text)?;
+    if !unknown.is_empty() {
+        anyhow::bail!("{}", unknown.join("\n"));
+    }
+    Ok(())
+}
+
+/// Deserialise `text` into a [`Manifest`], with one message per key
+/// the manifest structs do not accept (and serde would silently skip).
+fn parse_manifest(text: &str) -> Result<(Manifest, Vec<String>)> {
+    let mut unknown = Vec::new();
+    let deserializer = toml::Deserializer::parse(text).context("TOML syntax error")?;
+    let manifest: Manifest = serde_ignored::deserialize(deserializer, |path| {
+        let mut parts = Vec::new();
+        key_path(&path, &mut parts);
+        let key = parts.pop().unwrap_or_default();
+        let place = if parts.is_empty() {
+            "at the top level".to_string()
+        } else {
+            format!("in [{}]", parts.join("."))
+        };
+        unknown.push(format!("unknown key \"{key}\" {place}"));
+    })
+    .context("Manifest schema error")?;
+    Ok((manifest, unknown))
+}
+
+/// The table keys leading to `path`, outermost first.
+fn key_path(path: &serde_ignored::Path, parts: &mut Vec<String>) {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => {}
+        Path::Seq { parent, index } => {
+            key_path(parent, parts);
+            parts.push(index.to_string());
+        }
+        Path::Map { parent, key } => {
+            key_path(parent, parts);
+            parts.push(key.clone());
+        }
+        Path::Some { parent } | Path::NewtypeStruct { parent } | Path::NewtypeVariant { parent } => {
+            key_path(parent, parts)
+        }
+    }
+}
+
+/// Check that the `[filter]` cutoffs lie below Nyquist and leave a
+/// passband, and that the noise gate is below full scale.
+fn validate_filter(filter: &PreFilter, sample_rate: u32) -> Result<()> {
+    let nyquist = sample_rate as f64 / 2.0;
+    for (key, cutoff) in [
+        ("highpass_hz", filter.highpass_hz),
+        ("lowpass_hz", filter.lowpass_hz),
+    ] {
+        if let Some(hz) = cutoff.filter(|hz| !(*hz > 0.0 && *hz < nyquist)) {
+            anyhow::bail!("{key} ({hz}) must be between 0 and half the sample rate ({nyquist})");
+        }
+    }
+    if let (Some(high), Some(low)) = (filter.highpass_hz, filter.lowpass_hz) {
+        if high >= low {
+            anyhow::bail!("highpass_hz ({high}) must be below lowpass_hz ({low})");
+        }
+    }
+    if filter.noise_gate_dbfs.is_some_and(|db| db >= 0.0) {
+        anyhow::bail!("noise_gate_dbfs must be negative (dB below full scale)");
+    }
     Ok(())
 }
 
-/// Validate all manifest TOML files under `root_dir`.
+/// Check that `chunk_duration`, `min_chunk` and `max_chunk` are
+/// positive and ordered, and that `whole_file` is not mixed with
+/// `max_chunk`.
+fn validate_chunking(model: &ModelSection) -> Result<()> {
+    if model.chunk_duration <= 0.0 {
+        anyhow::bail!("chunk_duration must be positive");
+    }
+    if model.whole_file && model.max_chunk.is_some() {
+        anyhow::bail!("whole_file = true cannot be combined with max_chunk");
+    }
+    if let Some(min) = model.min_chunk {
+        if min <= 0.0 || min > model.chunk_duration {
+            anyhow::bail!(
+                "min_chunk ({min}) must be positive and at most chunk_duration ({})",
+                model.chunk_duration
+            );
+        }
+    }
+    if let Some(max) = model.max_chunk {
+        if max < model.chunk_duration {
+            anyhow::bail!(
+                "max_chunk ({max}) must be at least chunk_duration ({})",
+                model.chunk_duration
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Validate all manifest TOML files under `root_dir`, rejecting unknown
+/// keys ([`validate_manifest_toml_strict`]).
 ///
 /// Returns the list of validated paths on success, or an error listing
 /// every file that failed validation.  This is stricter than
@@ -467,7 +793,7 @@ pub fn validate_all_manifests(root_dir: &Path) -> Result<Vec<PathBuf>> {
                 continue;
             }
         };
-        match validate_manifest_toml(&text) {
+        match validate_manifest_toml_strict(&text) {
             Ok(()) => {
                 info!("  PASS  {}", manifest_path.display());
                 ok.push(manifest_path);
@@ -497,8 +823,11 @@ pub fn load_manifest(dir: &Path) -> Result<ResolvedManifest> {
         .with_context(|| format!("Cannot read {}", manifest_path.display()))?;
     validate_manifest_toml(&text)
         .with_context(|| format!("Invalid manifest: {}", manifest_path.display()))?;
-    let manifest: Manifest =
-        toml::from_str(&text).with_context(|| format!("Invalid manifest: {}", manifest_path.display()))?;
+    let (manifest, unknown) =
+        parse_manifest(&text).with_context(|| format!("Invalid manifest: {}", manifest_path.display()))?;
+    for problem in unknown {
+        tracing::warn!("{}: {problem} (ignored)", manifest_path.display());
+    }
     info!(
         "Loaded model manifest: {} (domain={}, sr={}, chunk={}s)",
         manifest.model.name,
@@ -509,6 +838,7 @@ pub fn load_manifest(dir: &Path) -> Result<ResolvedManifest> {
     Ok(ResolvedManifest {
         manifest,
         base_dir: dir.to_path_buf(),
+        variant: None,
     })
 }
 
@@ -653,10 +983,36 @@ labels_file = "bat_labels.txt"
         assert_eq!(m.model.domain, "bats");
         assert_eq!(m.model.sample_rate, 256000);
         assert!(!m.model.v1_metadata);
+        assert!(!m.model.ultrasonic);
         assert!(m.metadata_model.is_none());
+        assert!(m.classifier_head.is_none());
         assert!(m.download.is_none());
     }
 
+    #[test]
+    fn test_classifier_head() {
+        let toml = r#"
+[model]
+name = "BirdNET+ V3.0"
+domain = "birds"
+sample_rate = 32000
+chunk_duration = 3.0
+tflite_file = "model.tflite"
+labels_file = "labels.csv"
+prediction_output_index = 1
+
+[classifier_head]
+onnx_file = "frog-head.onnx"
+labels_file = "frog-labels.txt"
+"#;
+        let m: Manifest = toml::from_str(toml).unwrap();
+        let head = m.classifier_head.unwrap();
+        assert!(head.enabled);
+        assert_eq!(head.embedding_output_index, 0);
+        assert_eq!(head.domain, "local");
+        assert_eq!(head.score_transform, ScoreTransform::Sigmoid);
+    }
+
     #[test]
     fn test_manifest_with_download() {
         let toml = r#"
@@ -727,6 +1083,7 @@ tflite_file = "small_model.tflite"
         let mut resolved = ResolvedManifest {
             manifest: m,
             base_dir: PathBuf::from("/tmp/models/test"),
+            variant: None,
         };
 
         // Apply fp32 variant
@@ -741,6 +1098,35 @@ tflite_file = "small_model.tflite"
         assert_eq!(resolved.manifest.model.tflite_file, "small_model.tflite");
         // labels_file not overridden by int8 variant
         assert_eq!(resolved.manifest.model.labels_file, "labels.txt");
+        assert_eq!(resolved.variant.as_deref(), Some("int8"));
+    }
+
+    #[test]
+    fn test_version() {
+        let resolved = |name: &str, version: &str| {
+            let toml = format!(
+                r#"
+[model]
+name = "{name}"
+{version}
+domain = "birds"
+sample_rate = 48000
+chunk_duration = 3.0
+tflite_file = "model.tflite"
+labels_file = "labels.txt"
+"#
+            );
+            ResolvedManifest {
+                manifest: toml::from_str(&toml).unwrap(),
+                base_dir: PathBuf::from("/tmp"),
+                variant: None,
+            }
+            .version()
+        };
+        assert_eq!(resolved("BirdNET V2.4", ""), "2.4");
+        assert_eq!(resolved("Google Perch 2.0", ""), "2.0");
+        assert_eq!(resolved("BatDetect2", ""), "");
+        assert_eq!(resolved("BirdNET V2.4", r#"version = "2.4.1""#), "2.4.1");
     }
 
     #[test]
@@ -765,6 +1151,7 @@ zenodo_file = "test.zip"
         let resolved = ResolvedManifest {
             manifest: m,
             base_dir: PathBuf::from("/tmp"),
+            variant: None,
         };
 
         // No config override → uses default
@@ -774,6 +1161,11 @@ zenodo_file = "test.zip"
             resolved.effective_variant(Some("int8")),
             Some("int8".to_string())
         );
+        // Explicit manifest default
+        assert_eq!(
+            resolved.effective_variant(Some("default")),
+            Some("fp16".to_string())
+        );
     }
 
     #[test]
@@ -800,6 +1192,123 @@ trust_weight = 0.5
         );
     }
 
+    #[test]
+    fn test_ebird_codes_need_label_map() {
+        let base = r#"
+[model]
+name = "Perch v1"
+domain = "birds"
+sample_rate = 32000
+chunk_duration = 5.0
+tflite_file = "model.tflite"
+labels_file = "label.csv"
+label_format = "ebird_codes"
+"#;
+        assert!(validate_manifest_toml(base).is_err());
+        let with_map = format!("{base}label_map_file = \"ebird_taxonomy.csv\"\n");
+        assert!(validate_manifest_toml(&with_map).is_ok());
+        let m: Manifest = toml::from_str(&with_map).unwrap();
+        assert_eq!(m.model.label_format, LabelFormat::EbirdCodes);
+    }
+
+    #[test]
+    fn test_ignore_labels() {
+        let toml = r#"
+[model]
+name = "BirdNET V2.4"
+domain = "birds"
+sample_rate = 48000
+chunk_duration = 3.0
+tflite_file = "model.tflite"
+labels_file = "labels.txt"
+ignore_labels = ["Engine", "Siren"]
+"#;
+        let m: Manifest = toml::from_str(toml).unwrap();
+        assert_eq!(m.model.ignore_labels, vec!["Engine", "Siren"]);
+        let without = toml.replace("ignore_labels = [\"Engine\", \"Siren\"]\n", "");
+        let m: Manifest = toml::from_str(&without).unwrap();
+        assert!(m.model.ignore_labels.is_empty());
+    }
+
+    #[test]
+    fn test_chunking() {
+        let toml = r#"
+[model]
+name = "Frog calls"
+domain = "frogs"
+sample_rate = 16000
+chunk_duration = 3.0
+tflite_file = "model.tflite"
+labels_file = "labels.txt"
+"#;
+        let m: Manifest = toml::from_str(toml).unwrap();
+        assert_eq!(m.model.chunking(), Chunking::Fixed(3.0));
+
+        let variable = format!("{toml}min_chunk = 1.0\nmax_chunk = 10.0\n");
+        validate_manifest_toml(&variable).unwrap();
+        let m: Manifest = toml::from_str(&variable).unwrap();
+        assert_eq!(
+            m.model.chunking(),
+            Chunking::Variable {
+                min: 1.0,
+                max: Some(10.0)
+            }
+        );
+
+        let whole = format!("{toml}whole_file = true\n");
+        validate_manifest_toml(&whole).unwrap();
+        let m: Manifest = toml::from_str(&whole).unwrap();
+        assert_eq!(
+            m.model.chunking(),
+            Chunking::Variable {
+                min: 3.0,
+                max: None
+            }
+        );
+
+        for bad in [
+            "whole_file = true\nmax_chunk = 10.0\n",
+            "min_chunk = 5.0\n",
+            "min_chunk = 0.0\n",
+            "max_chunk = 2.0\n",
+        ] {
+            let text = format!("{toml}{bad}");
+            assert!(validate_manifest_toml(&text).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let toml = r#"
+[model]
+name = "Bats"
+domain = "bats"
+sample_rate = 256000
+chunk_duration = 1.0
+tflite_file = "model.tflite"
+labels_file = "labels.txt"
+"#;
+        let m: Manifest = toml::from_str(toml).unwrap();
+        assert!(!m.filter.is_active());
+
+        let filtered = format!("{toml}\n[filter]\nhighpass_hz = 12000\nnoise_gate_dbfs = -70\n");
+        validate_manifest_toml(&filtered).unwrap();
+        let m: Manifest = toml::from_str(&filtered).unwrap();
+        assert_eq!(m.filter.highpass_hz, Some(12_000.0));
+        assert_eq!(m.filter.lowpass_hz, None);
+        assert_eq!(m.filter.noise_gate_dbfs, Some(-70.0));
+
+        for bad in [
+            "highpass_hz = 200000",
+            "lowpass_hz = 0",
+            "highpass_hz = 20000\nlowpass_hz = 10000",
+            "noise_gate_dbfs = 6",
+        ] {
+            let text = format!("{toml}\n[filter]\n{bad}\n");
+            assert!(validate_manifest_toml(&text).is_err(), "{bad}");
+        }
+    }
+
     #[test]
     fn test_validate_accepts_valid_manifest() {
         let toml = r#"
@@ -816,4 +1325,61 @@ trust_weight = 0.5
 "#;
         validate_manifest_toml(toml).unwrap();
     }
+
+    const MINIMAL: &str = r#"
+[model]
+name = "BirdNET V2.4"
+domain = "birds"
+sample_rate = 48000
+chunk_duration = 3.0
+tflite_file = "model.tflite"
+labels_file = "labels.txt"
+"#;
+
+    #[test]
+    fn test_schema_version() {
+        let m: Manifest = toml::from_str(MINIMAL).unwrap();
+        assert_eq!(m.schema_version, 1);
+        validate_manifest_toml(&format!("schema_version = 1\n{MINIMAL}")).unwrap();
+        let newer = format!("schema_version = {}\n{MINIMAL}", SCHEMA_VERSION + 1);
+        let msg = format!("{:#}", validate_manifest_toml(&newer).unwrap_err());
+        assert!(msg.contains("update it"), "{msg}");
+        assert!(validate_manifest_toml(&format!("schema_version = 0\n{MINIMAL}")).is_err());
+    }
+
+    #[test]
+    fn test_strict_rejects_unknown_keys() {
+        validate_manifest_toml_strict(MINIMAL).unwrap();
+
+        // A typo in a key and in a section name: loading ignores both.
+        let typos = format!(
+            "{MINIMAL}trust_wieght = 0.5\n\n[metadata-model]\ntflite_file = \"meta.tflite\"\n"
+        );
+        validate_manifest_toml(&typos).unwrap();
+        let msg = format!("{:#}", validate_manifest_toml_strict(&typos).unwrap_err());
+        assert!(msg.contains("unknown key \"trust_wieght\" in [model]"), "{msg}");
+        assert!(msg.contains("unknown key \"metadata-model\" at the top level"), "{msg}");
+    }
+
+    #[test]
+    fn test_strict_checks_variants_not_direct_files() {
+        let text = format!(
+            "{MINIMAL}\n[download.variants.fp16]\nzenodo_file = \"m.zip\"\nsha265 = \"abc\"\n\n\
+             [download.direct_files]\n\"model.onnx\" = \"https://example.org/model.onnx\"\n"
+        );
+        let (_, unknown) = parse_manifest(&text).unwrap();
+        assert_eq!(unknown, ["unknown key \"sha265\" in [download.variants.fp16]"]);
+    }
+
+    #[test]
+    fn test_examples_pass_strict_validation() {
+        for text in [
+            include_str!("../../examples/birds_manifest.toml"),
+            include_str!("../../examples/birdnet3_manifest.toml"),
+            include_str!("../../examples/perch_manifest.toml"),
+            include_str!("../../examples/bats_manifest.toml"),
+        ] {
+            validate_manifest_toml_strict(text).unwrap();
+        }
+    }
 }
@@ -20,8 +20,15 @@
 //!
 //! The output is a `[1, 96, 511, 2]` tensor (96 mel bins × 511 time frames
 //! × 2 channels) ready to feed the ONNX classifier.
+//!
+//! This runs for every 3 s chunk on ONNX deployments, so the layers (FFT
+//! plan, window, filterbank) are built once and reused, the STFT uses a
+//! real-to-complex FFT, and each mel filter only visits the FFT bins
+//! under its triangle.
+
+use std::sync::{Arc, OnceLock};
 
-use rustfft::{num_complex::Complex, FftPlanner};
+use realfft::{RealFftPlanner, RealToComplex};
 
 // ── types ────────────────────────────────────────────────────────────────
 
@@ -48,13 +55,19 @@ pub struct MelSpecParams {
 /// Pre-computed state for a [`MelSpecParams`] configuration.
 pub struct MelSpecLayer {
     params: MelSpecParams,
-    /// Mel filterbank matrix, shape `[n_fft_bins, n_mels]` stored in
-    /// row-major order.
-    mel_filterbank: Vec<f32>,
-    /// Number of FFT bins = frame_length / 2 + 1.
-    n_fft_bins: usize,
+    /// One triangular filter per mel bin, lowest frequency first.
+    mel_bands: Vec<MelBand>,
     /// Hann window of length `frame_length`.
     hann: Vec<f32>,
+    /// Real-to-complex FFT of length `frame_length`.
+    fft: Arc<dyn RealToComplex<f32>>,
+}
+
+/// The nonzero stretch of one mel filter: weights for the FFT bins
+/// `start..start + weights.len()`.
+struct MelBand {
+    start: usize,
+    weights: Vec<f32>,
 }
 
 // ── BirdNET V2.4 defaults ────────────────────────────────────────────────
@@ -91,14 +104,26 @@ impl MelSpecLayer {
     /// Build a new layer, pre-computing the Hann window and mel filterbank.
     pub fn new(params: MelSpecParams) -> Self {
         let n_fft_bins = params.frame_length / 2 + 1;
-        let mel_filterbank =
+        let filterbank =
             linear_to_mel_weight_matrix(params.n_mels, n_fft_bins, params.sample_rate, params.fmin, params.fmax);
+        let mel_bands = (0..params.n_mels)
+            .map(|m| {
+                let column: Vec<f32> = (0..n_fft_bins).map(|b| filterbank[b * params.n_mels + m]).collect();
+                let start = column.iter().position(|&w| w > 0.0).unwrap_or(0);
+                let end = column.iter().rposition(|&w| w > 0.0).map_or(start, |e| e + 1);
+                MelBand {
+                    start,
+                    weights: column[start..end].to_vec(),
+                }
+            })
+            .collect();
         let hann = hann_window(params.frame_length);
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(params.frame_length);
         Self {
             params,
-            mel_filterbank,
-            n_fft_bins,
+            mel_bands,
             hann,
+            fft,
         }
     }
 
@@ -117,77 +142,43 @@ impl MelSpecLayer {
         let range = max_val - min_val + 1e-6;
         let norm: Vec<f32> = audio.iter().map(|&v| ((v - min_val) / range - 0.5) * 2.0).collect();
 
-        // ── 2. STFT ──────────────────────────────────────────────────
+        // ── 2. STFT, real part only (matches `tf.cast(complex64, float32)`)
         let n_frames = (norm.len().saturating_sub(p.frame_length)) / p.frame_step + 1;
-        let n_bins = self.n_fft_bins;
-
-        let mut planner = FftPlanner::<f32>::new();
-        let fft = planner.plan_fft_forward(p.frame_length);
-
-        // stft_real: [n_frames, n_bins]  (real part of complex STFT)
-        let mut stft_real = vec![0.0f32; n_frames * n_bins];
-
-        let mut buf = vec![Complex::new(0.0f32, 0.0); p.frame_length];
-        for frame_idx in 0..n_frames {
-            let start = frame_idx * p.frame_step;
-            for (i, (&s, &w)) in norm[start..start + p.frame_length]
-                .iter()
-                .zip(self.hann.iter())
-                .enumerate()
-            {
-                buf[i] = Complex::new(s * w, 0.0);
-            }
-            fft.process(&mut buf);
-            // Take real part only (matches `tf.cast(complex64, float32)`)
-            let row_offset = frame_idx * n_bins;
-            for (bin, c) in buf.iter().take(n_bins).enumerate() {
-                stft_real[row_offset + bin] = c.re;
-            }
-        }
-
-        // ── 3. Mel filterbank  (matmul: [n_frames, n_bins] × [n_bins, n_mels])
         let n_mels = p.n_mels;
-        let mut mel = vec![0.0f32; n_frames * n_mels];
-        for f in 0..n_frames {
-            let stft_row = &stft_real[f * n_bins..(f + 1) * n_bins];
-            let mel_row = &mut mel[f * n_mels..(f + 1) * n_mels];
-            for m in 0..n_mels {
-                let mut acc = 0.0f32;
-                for b in 0..n_bins {
-                    acc += stft_row[b] * self.mel_filterbank[b * n_mels + m];
-                }
-                mel_row[m] = acc;
-            }
-        }
+        let mut frame = self.fft.make_input_vec();
+        let mut spectrum = self.fft.make_output_vec();
+        let mut scratch = self.fft.make_scratch_vec();
+        let mut stft_real = vec![0.0f32; spectrum.len()];
 
-        // ── 4. Power spectrogram ─────────────────────────────────────
-        for v in mel.iter_mut() {
-            *v = *v * *v;
-        }
-
-        // ── 5. Nonlinear magnitude scaling ───────────────────────────
+        // ── 3–7. Mel filterbank, power, magnitude scaling, frequency flip
+        // and transpose, written straight into the [n_mels, n_frames] output.
         // Python: spec = tf.math.pow(spec, 1.0 / (1.0 + tf.math.exp(self.mag_scale)))
         let exponent = 1.0 / (1.0 + p.mag_scale.exp());
-        for v in mel.iter_mut() {
-            *v = v.powf(exponent);
-        }
-
-        // ── 6. Flip frequency axis (reverse along mel dim per frame) ─
-        // mel is [n_frames, n_mels]; we need to reverse each row.
+        let mut out = vec![0.0f32; n_mels * n_frames];
         for f in 0..n_frames {
-            let start = f * n_mels;
-            mel[start..start + n_mels].reverse();
-        }
+            let start = f * p.frame_step;
+            for ((dst, &s), &w) in frame
+                .iter_mut()
+                .zip(&norm[start..start + p.frame_length])
+                .zip(&self.hann)
+            {
+                *dst = s * w;
+            }
+            self.fft
+                .process_with_scratch(&mut frame, &mut spectrum, &mut scratch)
+                .expect("FFT buffers are sized by the plan");
+            for (re, c) in stft_real.iter_mut().zip(&spectrum) {
+                *re = c.re;
+            }
 
-        // ── 7. Transpose to [n_mels, n_frames] ──────────────────────
-        let mut transposed = vec![0.0f32; n_mels * n_frames];
-        for f in 0..n_frames {
-            for m in 0..n_mels {
-                transposed[m * n_frames + f] = mel[f * n_mels + m];
+            for (m, band) in self.mel_bands.iter().enumerate() {
+                let bins = &stft_real[band.start..band.start + band.weights.len()];
+                let energy = dot(bins, &band.weights);
+                out[(n_mels - 1 - m) * n_frames + f] = (energy * energy).powf(exponent);
             }
         }
 
-        (transposed, n_mels, n_frames)
+        (out, n_mels, n_frames)
     }
 }
 
@@ -196,8 +187,13 @@ impl MelSpecLayer {
 /// Returns a `Vec<f32>` of shape `[1, 96, 511, 2]` in row-major order
 /// (NHWC layout) suitable for feeding the classifier ONNX model.
 pub fn birdnet_mel_spectrogram(audio: &[f32]) -> Vec<f32> {
-    let layer1 = MelSpecLayer::new(birdnet_mel_spec1());
-    let layer2 = MelSpecLayer::new(birdnet_mel_spec2());
+    static LAYERS: OnceLock<(MelSpecLayer, MelSpecLayer)> = OnceLock::new();
+    let (layer1, layer2) = LAYERS.get_or_init(|| {
+        (
+            MelSpecLayer::new(birdnet_mel_spec1()),
+            MelSpecLayer::new(birdnet_mel_spec2()),
+        )
+    });
 
     let (ch0, n_mels, n_frames) = layer1.compute(audio);
     let (ch1, n_mels2, n_frames2) = layer2.compute(audio);
@@ -222,6 +218,26 @@ pub fn birdnet_mel_spectrogram(audio: &[f32]) -> Vec<f32> {
 
 // ── helpers ──────────────────────────────────────────────────────────────
 
+/// Dot product over fixed-width lanes, which the compiler turns into SIMD
+/// multiply-adds.
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    const LANES: usize = 8;
+    let (a_chunks, b_chunks) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let tail: f32 = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(x, y)| x * y)
+        .sum();
+    let mut lanes = [0.0f32; LANES];
+    for (xs, ys) in a_chunks.zip(b_chunks) {
+        for ((acc, x), y) in lanes.iter_mut().zip(xs).zip(ys) {
+            *acc += x * y;
+        }
+    }
+    lanes.iter().sum::<f32>() + tail
+}
+
 /// Hann window of length `n` (periodic version matching `tf.signal.hann_window`).
 ///
 /// TensorFlow uses the **periodic** convention:
@@ -305,6 +321,7 @@ fn linear_to_mel_weight_matrix(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rustfft::{num_complex::Complex, FftPlanner};
 
     #[test]
     fn test_hann_window_periodic() {
@@ -438,6 +455,61 @@ mod tests {
         assert_eq!(data.len(), 96 * 511);
     }
 
+    /// The optimised layer against the straightforward computation:
+    /// complex FFT, dense filterbank matmul, then scale, flip, transpose.
+    #[test]
+    fn test_mel_spec_matches_dense() {
+        let audio: Vec<f32> = (0..144000)
+            .map(|i| {
+                let t = i as f32 / 48000.0;
+                (std::f32::consts::TAU * 2200.0 * t).sin() * 0.4
+                    + (std::f32::consts::TAU * 640.0 * t).sin() * 0.2
+                    + ((i * 7919 % 1000) as f32 / 1000.0 - 0.5) * 0.05
+            })
+            .collect();
+
+        for params in [birdnet_mel_spec1(), birdnet_mel_spec2()] {
+            let (fast, n_mels, n_frames) = MelSpecLayer::new(params.clone()).compute(&audio);
+
+            let (min_val, max_val) = audio.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(mn, mx), &v| {
+                (mn.min(v), mx.max(v))
+            });
+            let range = max_val - min_val + 1e-6;
+            let norm: Vec<f32> = audio.iter().map(|&v| ((v - min_val) / range - 0.5) * 2.0).collect();
+            let n_bins = params.frame_length / 2 + 1;
+            let fb = linear_to_mel_weight_matrix(n_mels, n_bins, params.sample_rate, params.fmin, params.fmax);
+            let hann = hann_window(params.frame_length);
+            let fft = FftPlanner::<f32>::new().plan_fft_forward(params.frame_length);
+            let exponent = 1.0 / (1.0 + params.mag_scale.exp());
+
+            let mut max_diff = 0.0f32;
+            for f in 0..n_frames {
+                let start = f * params.frame_step;
+                let mut buf: Vec<Complex<f32>> = norm[start..start + params.frame_length]
+                    .iter()
+                    .zip(&hann)
+                    .map(|(&s, &w)| Complex::new(s * w, 0.0))
+                    .collect();
+                fft.process(&mut buf);
+                for m in 0..n_mels {
+                    let acc: f32 = (0..n_bins).map(|b| buf[b].re * fb[b * n_mels + m]).sum();
+                    let dense = (acc * acc).powf(exponent);
+                    let d = (fast[(n_mels - 1 - m) * n_frames + f] - dense).abs();
+                    max_diff = max_diff.max(d / dense.max(1.0));
+                }
+            }
+            assert!(max_diff < 1e-2, "fmax {}: max diff {max_diff}", params.fmax);
+        }
+    }
+
+    #[test]
+    fn test_dot() {
+        let a: Vec<f32> = (0..19).map(|i| i as f32).collect();
+        let b = vec![2.0f32; 19];
+        assert_eq!(dot(&a, &b), 342.0);
+        assert_eq!(dot(&[], &[]), 0.0);
+    }
+
     #[test]
     fn test_birdnet_mel_spectrogram_shape() {
         let audio = vec![0.0f32; 144000];
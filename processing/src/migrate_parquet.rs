@@ -21,11 +21,11 @@ const MARKER: &str = ".sci_name_normalised";
 ///
 /// Call this at startup in both the processing and web containers.
 /// The detections directory is the folder containing `*.parquet` files
-/// (e.g. `/data/detections`).
-pub fn run_if_needed(detections_dir: &Path) -> Result<()> {
+/// (e.g. `/data/detections`).  Returns `false` when it had already run.
+pub fn run_if_needed(detections_dir: &Path) -> Result<bool> {
     let marker = detections_dir.join(MARKER);
     if marker.exists() {
-        return Ok(()); // already migrated
+        return Ok(false); // already migrated
     }
 
     // Check if there are any Parquet files to migrate.
@@ -39,7 +39,7 @@ pub fn run_if_needed(detections_dir: &Path) -> Result<()> {
     if !has_files {
         // Nothing to migrate — write marker and return.
         std::fs::write(&marker, "no files to migrate\n").ok();
-        return Ok(());
+        return Ok(true);
     }
 
     info!(
@@ -67,7 +67,7 @@ pub fn run_if_needed(detections_dir: &Path) -> Result<()> {
     if count_before == 0 {
         info!("Migration: no rows found, skipping");
         std::fs::write(&marker, "0 rows\n").ok();
-        return Ok(());
+        return Ok(true);
     }
 
     // ── Build the best common-name lookup ────────────────────────────
@@ -167,5 +167,5 @@ pub fn run_if_needed(detections_dir: &Path) -> Result<()> {
 
     // Write marker so we don't run again.
     std::fs::write(&marker, format!("{count_after} rows migrated\n")).ok();
-    Ok(())
+    Ok(true)
 }
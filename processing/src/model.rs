@@ -17,7 +17,8 @@ use tract_tflite::prelude::*;
 use tract_onnx::prelude::InferenceModelExt as _;
 use tracing::info;
 
-use crate::manifest::ResolvedManifest;
+use crate::chunk_cache::{self, ChunkCache};
+use crate::manifest::{LabelFormat, ResolvedManifest};
 use gaia_common::config::Config;
 use gaia_common::detection::normalize_sci_name;
 
@@ -61,6 +62,23 @@ pub struct LoadedModel {
     /// One-time diagnostic flag: log raw logit statistics on the first
     /// inference so operators can verify the model's output scale.
     first_predict_logged: bool,
+    /// Locally-trained classifier on the embedding output, if any.
+    head: Option<ClassifierHead>,
+    /// `true` for labels listed in the manifest's `ignore_labels`, by
+    /// index into `labels`; empty when nothing is ignored.
+    ignored: Vec<bool>,
+    /// Outputs of recently scored chunks (`CHUNK_CACHE_SIZE`).
+    chunk_cache: ChunkCache,
+}
+
+/// Classifier head run on the base model's embedding (`[classifier_head]`).
+struct ClassifierHead {
+    runner: TypedRunnableModel<TypedModel>,
+    labels: Vec<String>,
+    common_names: HashMap<String, String>,
+    domain: String,
+    embedding_output_index: usize,
+    transform: crate::manifest::ScoreTransform,
 }
 
 /// Species-occurrence metadata model (filters by location/week).
@@ -370,6 +388,34 @@ pub fn load_model(resolved: &ResolvedManifest, config: &Config) -> Result<Loaded
         (Some(load_tflite_runner(&resolved.tflite_path())?), None, false)
     };
     let (labels, csv_common_names, csv_classes) = load_labels(&resolved.labels_path())?;
+    let (labels, csv_common_names) = match resolved.manifest.model.label_format {
+        LabelFormat::ScientificNames => (labels, csv_common_names),
+        LabelFormat::EbirdCodes => {
+            let map_path = resolved
+                .label_map_path()
+                .context("label_format = \"ebird_codes\" requires label_map_file")?;
+            let text = fs::read_to_string(&map_path)
+                .with_context(|| format!("Cannot read label map: {}", map_path.display()))?;
+            let map = parse_label_map(&text);
+            let (labels, common, unmapped) = translate_codes(labels, csv_common_names, &map);
+            info!(
+                "Translated {} eBird codes via {} ({} mapping entries)",
+                labels.len() - unmapped,
+                map_path.display(),
+                map.len()
+            );
+            if unmapped > 0 {
+                tracing::warn!("{unmapped} label code(s) have no entry in the label map and are kept as-is");
+            }
+            (labels, common)
+        }
+    };
+
+    let ignored = ignore_mask(
+        &labels,
+        &csv_common_names,
+        &resolved.manifest.model.ignore_labels,
+    );
 
     let meta_model = match load_meta_model(resolved, &labels, config.sf_thresh) {
         Ok(m) => m,
@@ -382,6 +428,17 @@ pub fn load_model(resolved: &ResolvedManifest, config: &Config) -> Result<Loaded
         }
     };
 
+    let head = match load_classifier_head(resolved) {
+        Ok(h) => h,
+        Err(e) => {
+            tracing::warn!(
+                "Classifier head failed to load – only the base model's \
+                 classes will be detected: {e:#}"
+            );
+            None
+        }
+    };
+
     // BirdNET-Analyzer uses SIGMOID_SENSITIVITY (default 1.0) directly
     // as the slope of flat_sigmoid: 1/(1+exp(-sensitivity * clip(x,-20,20))).
     // Higher values → steeper sigmoid → higher reported confidences.
@@ -405,13 +462,61 @@ pub fn load_model(resolved: &ResolvedManifest, config: &Config) -> Result<Loaded
         sensitivity,
         onnx_classifier,
         first_predict_logged: false,
+        head,
+        ignored,
+        chunk_cache: ChunkCache::new(config.chunk_cache_size),
     })
 }
 
+/// Let tract's matrix-multiply kernels split each inference over
+/// `threads` threads (`INFERENCE_THREADS`).  tract runs on the calling
+/// thread unless told otherwise; the pool set here is process-wide and
+/// shared by all analysis workers.  Call once, before any model runs.
+pub fn configure_inference_threads(threads: usize) {
+    if threads > 1 {
+        tract_linalg::multithread::set_default_executor(
+            tract_linalg::multithread::Executor::multithread(threads),
+        );
+    }
+    info!(
+        "tract: {threads} inference thread(s), {} kernels (set INFERENCE_THREADS to change)",
+        simd_kernels()
+    );
+}
+
+/// The SIMD kernel family tract-linalg selects at runtime on this CPU.
+fn simd_kernels() -> &'static str {
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return "NEON";
+        }
+    }
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx512f") {
+            return "AVX-512";
+        }
+        if is_x86_feature_detected!("fma") {
+            return "AVX2/FMA";
+        }
+    }
+    "generic"
+}
+
 /// Load and optimise a TFLite model file.
+///
+/// Models with tensor types tract-tflite cannot handle (float16 weights
+/// in the fp16 variants) are converted to a sibling `.onnx` file once
+/// and loaded through tract-onnx instead of panicking inside tract.
 fn load_tflite_runner(path: &Path) -> Result<TypedRunnableModel<TypedModel>> {
     validate_tflite_file(path)
         .with_context(|| format!("Pre-flight check failed for {}", path.display()))?;
+    match crate::tflite_probe::unsupported_tensor_types(path) {
+        Ok(types) if !types.is_empty() => return load_tflite_via_onnx(path, &types),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Tensor type check skipped: {e:#}"),
+    }
     info!("Loading TFLite model from {}", path.display());
 
     tract_tflite::tflite()
@@ -423,6 +528,29 @@ fn load_tflite_runner(path: &Path) -> Result<TypedRunnableModel<TypedModel>> {
         .context("Cannot make TFLite model runnable")
 }
 
+/// Load a TFLite model that uses `types` unsupported by tract-tflite via
+/// an ONNX conversion next to it (e.g. `audio-model-fp16.onnx`).
+fn load_tflite_via_onnx(path: &Path, types: &[String]) -> Result<TypedRunnableModel<TypedModel>> {
+    let onnx_path = path.with_extension("onnx");
+    info!(
+        "{} uses tensor types tract-tflite cannot load ({}); using ONNX {}",
+        path.display(),
+        types.join(", "),
+        onnx_path.display()
+    );
+    if !onnx_path.exists() {
+        crate::download::tflite_to_onnx(path, &onnx_path).with_context(|| {
+            format!(
+                "{} needs an ONNX conversion for {} tensors; install tf2onnx or \
+                 set MODEL_VARIANT=fp32 (or int8)",
+                path.display(),
+                types.join("/")
+            )
+        })?;
+    }
+    load_onnx_runner(&onnx_path)
+}
+
 /// Load and optimise an ONNX model file.
 fn load_onnx_runner(path: &Path) -> Result<TypedRunnableModel<TypedModel>> {
     tract_onnx::onnx()
@@ -434,6 +562,34 @@ fn load_onnx_runner(path: &Path) -> Result<TypedRunnableModel<TypedModel>> {
         .context("Cannot make ONNX model runnable")
 }
 
+/// Load the `[classifier_head]` model and labels, if the manifest
+/// enables one.
+fn load_classifier_head(resolved: &ResolvedManifest) -> Result<Option<ClassifierHead>> {
+    let (Some(section), Some(onnx_path), Some(labels_path)) = (
+        resolved.manifest.classifier_head.as_ref(),
+        resolved.classifier_head_path(),
+        resolved.classifier_head_labels_path(),
+    ) else {
+        return Ok(None);
+    };
+    info!(
+        "Loading classifier head from {} (embedding output {}, domain {})",
+        onnx_path.display(),
+        section.embedding_output_index,
+        section.domain,
+    );
+    let runner = load_onnx_runner(&onnx_path)?;
+    let (labels, common_names, _classes) = load_labels(&labels_path)?;
+    Ok(Some(ClassifierHead {
+        runner,
+        labels,
+        common_names,
+        domain: section.domain.clone(),
+        embedding_output_index: section.embedding_output_index,
+        transform: section.score_transform,
+    }))
+}
+
 /// Validate that an ONNX file can be loaded, optimised, and made
 /// runnable by tract-onnx.
 ///
@@ -453,14 +609,36 @@ impl LoadedModel {
         self.manifest.domain()
     }
 
+    /// Inference runtime the model was loaded with, recorded with each
+    /// detection: `tract-onnx`, `tract-tflite`, `onnxruntime` or
+    /// `onnxruntime-rocm` / `onnxruntime-cuda` for a GPU session.
+    pub fn runtime(&self) -> &'static str {
+        match &self.ort_session {
+            Some(session) => match session.accel() {
+                crate::accel::AccelKind::Rocm => "onnxruntime-rocm",
+                crate::accel::AccelKind::Cuda => "onnxruntime-cuda",
+                crate::accel::AccelKind::None => "onnxruntime",
+            },
+            // `load_model` only falls back to TFLite when no ONNX file
+            // is configured or present.
+            None if self.manifest.onnx_path().is_some_and(|p| p.exists()) => "tract-onnx",
+            None => "tract-tflite",
+        }
+    }
+
     /// Target sample rate for this model.
     pub fn sample_rate(&self) -> u32 {
         self.manifest.manifest.model.sample_rate
     }
 
-    /// Chunk duration (seconds) expected by this model.
-    pub fn chunk_duration(&self) -> f64 {
-        self.manifest.manifest.model.chunk_duration
+    /// How recordings are cut for this model (fixed or variable-length).
+    pub fn chunking(&self) -> gaia_common::audio::Chunking {
+        self.manifest.manifest.model.chunking()
+    }
+
+    /// Filtering applied to the signal before chunking (`[filter]`).
+    pub fn pre_filter(&self) -> &gaia_common::audio::PreFilter {
+        &self.manifest.manifest.filter
     }
 
     /// Whether this model uses V1-style metadata input.
@@ -492,6 +670,16 @@ impl LoadedModel {
         &self.labels
     }
 
+    /// Domain of the classifier head's detections, when one is loaded.
+    pub fn head_domain(&self) -> Option<&str> {
+        self.head.as_ref().map(|h| h.domain.as_str())
+    }
+
+    /// Common names from the classifier head's labels file.
+    pub fn head_common_names(&self) -> Option<&HashMap<String, String>> {
+        self.head.as_ref().map(|h| &h.common_names)
+    }
+
     /// Whether this model has a species-range (metadata) model loaded.
     pub fn has_species_range_model(&self) -> bool {
         self.meta_model.is_some()
@@ -512,94 +700,129 @@ impl LoadedModel {
         lon: f64,
         week: u32,
     ) -> Result<Vec<Prediction>> {
-        // ── ORT path (GPU-accelerated or CPU fallback) ───────────────
-        if let Some(ort) = &mut self.ort_session {
-            let out_idx = self.manifest.manifest.model.prediction_output_index;
-            let logits = if self.onnx_classifier {
-                let mel = crate::mel::birdnet_mel_spectrogram(chunk);
-                ort.predict(mel, vec![1, 96, 511, 2], out_idx)?
-            } else {
-                let n = chunk.len();
-                ort.predict(chunk.to_vec(), vec![1, n], out_idx)?
-            };
+        Ok(self.predict_with_head(chunk, lat, lon, week)?.0)
+    }
 
-            self.log_first_prediction(&logits);
-            let scores = self.transform_scores(&logits);
+    /// Like [`predict`](Self::predict), but also runs the classifier
+    /// head (when the manifest has one) on the same inference's
+    /// embedding.  The head's predictions are sorted the same way and
+    /// are empty without a head.
+    pub fn predict_with_head(
+        &mut self,
+        chunk: &[f32],
+        lat: f64,
+        lon: f64,
+        week: u32,
+    ) -> Result<(Vec<Prediction>, Vec<Prediction>)> {
+        crate::faults::check(crate::faults::Fault::InferenceError)?;
+        let key = self
+            .chunk_cache
+            .enabled()
+            .then(|| chunk_cache::key(chunk, lat, lon, week));
+        let (logits, embedding) = match key.and_then(|k| self.chunk_cache.get(k)) {
+            Some(outputs) => outputs,
+            None => {
+                let outputs = self.run_model(chunk, lat, lon, week)?;
+                if let Some(k) = key {
+                    self.chunk_cache.insert(k, outputs.clone());
+                }
+                outputs
+            }
+        };
 
-            let mut predictions: Vec<Prediction> = self
-                .labels
-                .iter()
-                .zip(scores.iter())
-                .map(|(label, &score)| {
-                    let safe_score = if score.is_finite() { score } else { 0.0 };
-                    (label.clone(), safe_score as f64)
-                })
-                .collect();
-            predictions.sort_by(|a, b| b.1.total_cmp(&a.1));
-            return Ok(predictions);
-        }
+        self.log_first_prediction(&logits);
+        let scores = self.transform_scores(&logits);
+        let predictions = ranked(&self.labels, &scores, &self.ignored);
 
-        // ── tract path (tract-onnx / tract-tflite) ──────────────────
-        let runner = self.runner.as_ref()
-            .context("No inference backend available (tract did not load and ORT is absent)")?;
-
-        let result = if self.onnx_classifier {
-            // ── ONNX classifier: audio → Rust mel → CNN ──────────
-            let mel = crate::mel::birdnet_mel_spectrogram(chunk);
-            let input: Tensor =
-                tract_ndarray::Array4::from_shape_vec((1, 96, 511, 2), mel)
-                    .context("Cannot reshape mel spectrogram")?
-                    .into();
-            runner
-                .run(tvec![input.into()])
-                .context("ONNX classifier inference failed")?
-        } else if self.v1_metadata() {
-            // ── TFLite V1 with metadata sidecar ──────────────────
-            let n = chunk.len();
-            let input: Tensor =
-                tract_ndarray::Array2::from_shape_vec((1, n), chunk.to_vec())
-                    .context("Cannot reshape audio chunk")?
-                    .into();
-            let mdata = convert_v1_metadata(lat, lon, week);
-            let mdata_tensor: Tensor =
-                tract_ndarray::Array2::from_shape_vec((1, 6), mdata.to_vec())
-                    .context("Cannot reshape metadata")?
-                    .into();
-            runner
-                .run(tvec![input.into(), mdata_tensor.into()])
-                .context("V1 inference failed")?
-        } else {
-            // ── TFLite standard ──────────────────────────────────
-            let n = chunk.len();
-            let input: Tensor =
-                tract_ndarray::Array2::from_shape_vec((1, n), chunk.to_vec())
-                    .context("Cannot reshape audio chunk")?
-                    .into();
-            runner
-                .run(tvec![input.into()])
-                .context("Inference failed")?
+        let head_predictions = match (&self.head, embedding) {
+            (Some(head), Some(embedding)) => {
+                let head_logits = head.run(embedding)?;
+                let scores = self.apply_transform(head.transform, &head_logits);
+                ranked(&head.labels, &scores, &[])
+            }
+            _ => Vec::new(),
         };
+        Ok((predictions, head_predictions))
+    }
 
-        let output = result[self.manifest.manifest.model.prediction_output_index]
-            .to_array_view::<f32>()
-            .context("Cannot read output tensor")?;
+    /// Run the model on `chunk`: its raw scores, and the embedding when a
+    /// classifier head needs it.
+    fn run_model(
+        &mut self,
+        chunk: &[f32],
+        lat: f64,
+        lon: f64,
+        week: u32,
+    ) -> Result<chunk_cache::Outputs> {
+        let out_idx = self.manifest.manifest.model.prediction_output_index;
+        let emb_idx = self.head.as_ref().map(|h| h.embedding_output_index);
 
-        let logits: Vec<f32> = output.iter().copied().collect();
-        self.log_first_prediction(&logits);
-        let scores = self.transform_scores(&logits);
+        // ── ORT path (GPU-accelerated or CPU fallback) ───────────────
+        let outputs = if let Some(ort) = &mut self.ort_session {
+            let indices: Vec<usize> = std::iter::once(out_idx).chain(emb_idx).collect();
+            let mut outputs = if self.onnx_classifier {
+                let mel = crate::mel::birdnet_mel_spectrogram(chunk);
+                ort.predict_outputs(mel, vec![1, 96, 511, 2], &indices)?
+            } else {
+                let n = chunk.len();
+                ort.predict_outputs(chunk.to_vec(), vec![1, n], &indices)?
+            };
+            let embedding = emb_idx.map(|_| outputs.pop().unwrap_or_default());
+            (outputs.swap_remove(0), embedding)
+        } else {
+            // ── tract path (tract-onnx / tract-tflite) ──────────────
+            let runner = self.runner.as_ref()
+                .context("No inference backend available (tract did not load and ORT is absent)")?;
 
-        let mut predictions: Vec<Prediction> = self
-            .labels
-            .iter()
-            .zip(scores.iter())
-            .map(|(label, &score)| {
-                let safe_score = if score.is_finite() { score } else { 0.0 };
-                (label.clone(), safe_score as f64)
-            })
-            .collect();
+            let result = if self.onnx_classifier {
+                // ── ONNX classifier: audio → Rust mel → CNN ──────
+                let mel = crate::mel::birdnet_mel_spectrogram(chunk);
+                let input: Tensor =
+                    tract_ndarray::Array4::from_shape_vec((1, 96, 511, 2), mel)
+                        .context("Cannot reshape mel spectrogram")?
+                        .into();
+                runner
+                    .run(tvec![input.into()])
+                    .context("ONNX classifier inference failed")?
+            } else if self.v1_metadata() {
+                // ── TFLite V1 with metadata sidecar ──────────────
+                let n = chunk.len();
+                let input: Tensor =
+                    tract_ndarray::Array2::from_shape_vec((1, n), chunk.to_vec())
+                        .context("Cannot reshape audio chunk")?
+                        .into();
+                let mdata = convert_v1_metadata(lat, lon, week);
+                let mdata_tensor: Tensor =
+                    tract_ndarray::Array2::from_shape_vec((1, 6), mdata.to_vec())
+                        .context("Cannot reshape metadata")?
+                        .into();
+                runner
+                    .run(tvec![input.into(), mdata_tensor.into()])
+                    .context("V1 inference failed")?
+            } else {
+                // ── TFLite standard ──────────────────────────────
+                let n = chunk.len();
+                let input: Tensor =
+                    tract_ndarray::Array2::from_shape_vec((1, n), chunk.to_vec())
+                        .context("Cannot reshape audio chunk")?
+                        .into();
+                runner
+                    .run(tvec![input.into()])
+                    .context("Inference failed")?
+            };
 
-        predictions.sort_by(|a, b| b.1.total_cmp(&a.1));
-        Ok(predictions)
+            let read_output = |idx: usize| -> Result<Vec<f32>> {
+                let tensor = result.get(idx).with_context(|| {
+                    format!("Model has {} outputs, no output {idx}", result.len())
+                })?;
+                let view = tensor
+                    .to_array_view::<f32>()
+                    .context("Cannot read output tensor")?;
+                Ok(view.iter().copied().collect())
+            };
+            (read_output(out_idx)?, emb_idx.map(read_output).transpose()?)
+        };
+        Ok(outputs)
     }
 
     /// Log raw logit statistics once per model so operators can verify
@@ -715,8 +938,13 @@ impl LoadedModel {
 
     /// Transform raw model logits into 0..1 confidence scores.
     fn transform_scores(&self, logits: &[f32]) -> Vec<f32> {
+        self.apply_transform(self.effective_transform(), logits)
+    }
+
+    /// Apply `transform` to `logits` (shared with the classifier head).
+    fn apply_transform(&self, transform: crate::manifest::ScoreTransform, logits: &[f32]) -> Vec<f32> {
         use crate::manifest::ScoreTransform;
-        match self.effective_transform() {
+        match transform {
             ScoreTransform::Softmax => softmax(logits),
             ScoreTransform::Sigmoid => self.sigmoid_scale(logits),
             ScoreTransform::CenteredSigmoid => centered_sigmoid(logits),
@@ -765,6 +993,96 @@ impl LoadedModel {
             None => vec![],
         }
     }
+
+    /// The metadata model's occurrence probability for each of its labels
+    /// at the location in `week` (the ISO week the range filter passes),
+    /// or `None` without a metadata model.
+    pub fn occurrence_scores(&self, lat: f64, lon: f64, week: u32) -> Result<Option<Vec<(&str, f32)>>> {
+        let Some(meta) = &self.meta_model else {
+            return Ok(None);
+        };
+        let scores = meta.scores(lat, lon, week)?;
+        Ok(Some(meta.labels.iter().map(String::as_str).zip(scores).collect()))
+    }
+}
+
+impl ClassifierHead {
+    /// Score one embedding; returns the head's raw outputs.
+    fn run(&self, embedding: Vec<f32>) -> Result<Vec<f32>> {
+        let dim = embedding.len();
+        let input: Tensor = tract_ndarray::Array2::from_shape_vec((1, dim), embedding)
+            .context("Cannot reshape embedding")?
+            .into();
+        let result = self
+            .runner
+            .run(tvec![input.into()])
+            .context("Classifier head inference failed")?;
+        let output = result[0]
+            .to_array_view::<f32>()
+            .context("Cannot read classifier head output")?;
+        Ok(output.iter().copied().collect())
+    }
+}
+
+/// Pair labels with scores, highest first.  Non-finite scores become 0.
+/// Labels set in `ignored` are dropped before any threshold sees them.
+fn ranked(labels: &[String], scores: &[f32], ignored: &[bool]) -> Vec<Prediction> {
+    let mut predictions: Vec<Prediction> = labels
+        .iter()
+        .zip(scores.iter())
+        .enumerate()
+        .filter(|(i, _)| !ignored.get(*i).copied().unwrap_or(false))
+        .map(|(_, (label, &score))| {
+            let safe_score = if score.is_finite() { score } else { 0.0 };
+            (label.clone(), safe_score as f64)
+        })
+        .collect();
+    predictions.sort_by(|a, b| b.1.total_cmp(&a.1));
+    predictions
+}
+
+/// Mask of the labels named in a manifest's `ignore_labels`, matched
+/// case-insensitively against the label or its common name.  Human
+/// labels are never ignored: the privacy filter depends on their scores.
+fn ignore_mask(
+    labels: &[String],
+    common_names: &HashMap<String, String>,
+    ignore_labels: &[String],
+) -> Vec<bool> {
+    if ignore_labels.is_empty() {
+        return Vec::new();
+    }
+    let wanted: Vec<String> = ignore_labels
+        .iter()
+        .map(|l| l.trim().to_lowercase())
+        .collect();
+    let mut matched = vec![false; wanted.len()];
+    let mask: Vec<bool> = labels
+        .iter()
+        .map(|label| {
+            let names = [Some(label), common_names.get(label)];
+            let Some(i) = wanted
+                .iter()
+                .position(|w| names.iter().flatten().any(|n| n.to_lowercase() == *w))
+            else {
+                return false;
+            };
+            matched[i] = true;
+            if label.contains("Human") {
+                tracing::warn!("ignore_labels: keeping {label}, needed by the privacy filter");
+                return false;
+            }
+            true
+        })
+        .collect();
+    for (label, _) in ignore_labels.iter().zip(&matched).filter(|(_, m)| !**m) {
+        tracing::warn!("ignore_labels: {label:?} matches no label of this model");
+    }
+    info!(
+        "Ignoring {} label(s) listed in the manifest",
+        mask.iter().filter(|m| **m).count()
+    );
+    mask
 }
 
 // ── softmax ──────────────────────────────────────────────────────────────
@@ -871,15 +1189,9 @@ fn load_meta_model(
         _ => return Ok(None),
     };
 
-    validate_tflite_file(&meta_path)
-        .with_context(|| format!("Pre-flight check failed for metadata model {}", meta_path.display()))?;
-
     info!("Loading metadata model: {}", meta_path.display());
-    let runner = tract_tflite::tflite()
-        .model_for_path(&meta_path)
-        .context("Cannot load metadata model")?
-        .into_optimized()?
-        .into_runnable()?;
+    let runner = load_tflite_runner(&meta_path)
+        .with_context(|| format!("Cannot load metadata model {}", meta_path.display()))?;
 
     let (labels, _, _) = load_labels(&resolved.metadata_labels_path())?;
 
@@ -893,38 +1205,40 @@ fn load_meta_model(
 }
 
 impl MetaDataModel {
+    /// Occurrence probability of every label at the location and week.
+    ///
+    /// BirdNET-Analyzer's explore() compares the meta-model output
+    /// DIRECTLY to LOCATION_FILTER_THRESHOLD (no sigmoid).  The model
+    /// already outputs occurrence probabilities in [0, 1].
+    fn scores(&self, lat: f64, lon: f64, week: u32) -> Result<Vec<f32>> {
+        let input: Tensor =
+            tract_ndarray::Array2::from_shape_vec((1, 3), vec![lat as f32, lon as f32, week as f32])
+                .context("metadata input shape")?
+                .into();
+        let result = self
+            .runner
+            .run(tvec![input.into()])
+            .context("Metadata model inference failed")?;
+        let output = result[0]
+            .to_array_view::<f32>()
+            .context("Cannot read metadata output")?;
+        Ok(output.iter().copied().collect())
+    }
+
     fn get_species_list(&mut self, lat: f64, lon: f64, week: u32) -> Vec<String> {
         let params = (lat, lon, week);
         if self.cached_params == Some(params) {
             return self.cached_list.clone();
         }
 
-        let input: Tensor =
-            tract_ndarray::Array2::from_shape_vec((1, 3), vec![lat as f32, lon as f32, week as f32])
-                .expect("metadata input shape")
-                .into();
-
-        let result = match self.runner.run(tvec![input.into()]) {
-            Ok(r) => r,
+        let raw = match self.scores(lat, lon, week) {
+            Ok(raw) => raw,
             Err(e) => {
-                tracing::error!("Metadata model inference failed: {e}");
+                tracing::error!("{e:#}");
                 return vec![];
             }
         };
 
-        let output = match result[0].to_array_view::<f32>() {
-            Ok(o) => o,
-            Err(e) => {
-                tracing::error!("Cannot read metadata output: {e}");
-                return vec![];
-            }
-        };
-
-        // BirdNET-Analyzer's explore() compares the meta-model output
-        // DIRECTLY to LOCATION_FILTER_THRESHOLD (no sigmoid).  The model
-        // already outputs occurrence probabilities in [0, 1].
-        let raw: Vec<f32> = output.iter().copied().collect();
-
         let mut scored: Vec<(f32, &str)> = raw
             .iter()
             .zip(self.labels.iter())
@@ -1122,6 +1436,99 @@ fn load_labels(
     Ok((labels, common_names, classes))
 }
 
+/// A label-map entry: scientific name plus optional common name.
+type LabelMapEntry = (String, Option<String>);
+
+/// Parse a code → name CSV, keyed by lower-case code.
+///
+/// With a header row, the code column is `species_code` / `ebird_code` /
+/// `code`, the name columns `sci_name` / `scientific_name` and
+/// `com_name` / `common_name` / `primary_com_name` (so the eBird taxonomy
+/// CSV can be used unchanged).  Without one, columns are
+/// `code,sci_name[,com_name]`.
+fn parse_label_map(text: &str) -> HashMap<String, LabelMapEntry> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty()).peekable();
+    let Some(first) = lines.peek() else {
+        return HashMap::new();
+    };
+    let header: Vec<String> = split_csv_line(first).iter().map(|c| c.to_lowercase()).collect();
+    let col = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let code_col = col(&["species_code", "ebird_code", "code"]);
+    let sci_col = col(&["sci_name", "scientific_name"]);
+    let (code_col, sci_col, com_col) = match (code_col, sci_col) {
+        (Some(code), Some(sci)) => {
+            lines.next();
+            (code, sci, col(&["com_name", "common_name", "primary_com_name"]))
+        }
+        _ => (0, 1, Some(2)),
+    };
+
+    let mut map = HashMap::new();
+    for line in lines {
+        let cols = split_csv_line(line);
+        let (Some(code), Some(sci)) = (cols.get(code_col), cols.get(sci_col)) else {
+            continue;
+        };
+        if code.is_empty() || sci.is_empty() {
+            continue;
+        }
+        let com = com_col.and_then(|i| cols.get(i)).filter(|c| !c.is_empty()).cloned();
+        map.insert(code.to_lowercase(), (normalize_sci_name(sci), com));
+    }
+    map
+}
+
+/// Split one CSV line on commas, honouring double-quoted fields.
+pub(crate) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => out.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    out.push(field.trim().to_string());
+    out
+}
+
+/// Replace code labels with scientific names from `map`.
+///
+/// Common names from the map fill in for codes the labels file had
+/// none for.  Returns the translated labels, common names and the
+/// number of codes left untranslated.
+fn translate_codes(
+    labels: Vec<String>,
+    mut common_names: HashMap<String, String>,
+    map: &HashMap<String, LabelMapEntry>,
+) -> (Vec<String>, HashMap<String, String>, usize) {
+    let mut unmapped = 0;
+    let labels = labels
+        .into_iter()
+        .map(|code| match map.get(&code.to_lowercase()) {
+            Some((sci, com)) => {
+                if let Some(com) = common_names.remove(&code).or_else(|| com.clone()) {
+                    common_names.entry(sci.clone()).or_insert(com);
+                }
+                sci.clone()
+            }
+            None => {
+                unmapped += 1;
+                code
+            }
+        })
+        .collect();
+    (labels, common_names, unmapped)
+}
+
 /// Load the JSON language file that maps `scientific_name → common_name`.
 pub fn load_language(lang_dir: &Path, lang: &str) -> Result<HashMap<String, String>> {
     let file = lang_dir.join(format!("labels_{lang}.json"));
@@ -1132,6 +1539,21 @@ pub fn load_language(lang_dir: &Path, lang: &str) -> Result<HashMap<String, Stri
     Ok(map)
 }
 
+/// Load the custom species list `name` (e.g. `include_species_list.txt`)
+/// from `$GAIA_DIR` (default `/app`), or else from next to the database
+/// on the data volume, where the BirdNET-Pi import writes migrated lists.
+pub fn load_named_species_list(name: &str, config: &Config) -> Vec<String> {
+    let base = std::env::var("GAIA_DIR").unwrap_or_else(|_| "/app".to_string());
+    let path = Path::new(&base).join(name);
+    if path.exists() {
+        return load_species_list(&path);
+    }
+    match config.db_path.parent() {
+        Some(data_dir) => load_species_list(&data_dir.join(name)),
+        None => vec![],
+    }
+}
+
 /// Load a custom species list (include / exclude / whitelist).
 pub fn load_species_list(path: &Path) -> Vec<String> {
     match std::fs::read_to_string(path) {
@@ -1184,6 +1606,69 @@ mod tests {
         assert_eq!(m[5], 1.0);
     }
 
+    #[test]
+    fn test_parse_label_map() {
+        // eBird taxonomy CSV layout (extra columns, quoted commas).
+        let ebird = "SCI_NAME,PRIMARY_COM_NAME,SPECIES_CODE,FAMILY_COM_NAME\n\
+                     Turdus migratorius,American Robin,amerob,\"Thrushes, Allies\"\n\
+                     Struthio camelus,Common Ostrich,ostric2,Ostriches\n";
+        let map = parse_label_map(ebird);
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            map["amerob"],
+            ("Turdus migratorius".to_string(), Some("American Robin".to_string()))
+        );
+
+        // Headerless code,sci_name.
+        let plain = parse_label_map("AMEROB,turdus migratorius\n");
+        assert_eq!(plain["amerob"], ("Turdus migratorius".to_string(), None));
+    }
+
+    #[test]
+    fn test_translate_codes() {
+        let map = parse_label_map("amerob,Turdus migratorius,American Robin\n");
+        // load_labels normalises codes like names: "amerob" → "Amerob".
+        let labels = vec!["Amerob".to_string(), "Noname".to_string()];
+        let (labels, common, unmapped) = translate_codes(labels, HashMap::new(), &map);
+        assert_eq!(labels, vec!["Turdus migratorius", "Noname"]);
+        assert_eq!(common["Turdus migratorius"], "American Robin");
+        assert_eq!(unmapped, 1);
+    }
+
+    #[test]
+    fn test_ranked() {
+        let labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let preds = ranked(&labels, &[0.2, f32::NAN, 0.9], &[]);
+        assert_eq!(preds[0], ("c".to_string(), 0.9f32 as f64));
+        assert_eq!(preds[1], ("a".to_string(), 0.2f32 as f64));
+        assert_eq!(preds[2], ("b".to_string(), 0.0));
+
+        let preds = ranked(&labels, &[0.2, f32::NAN, 0.9], &[false, false, true]);
+        assert_eq!(preds.len(), 2);
+        assert_eq!(preds[0].0, "a");
+    }
+
+    #[test]
+    fn test_ignore_mask() {
+        let labels: Vec<String> = ["Engine", "Turdus merula", "Human vocal", "Siren"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let common = HashMap::from([(
+            "Turdus merula".to_string(),
+            "Eurasian Blackbird".to_string(),
+        )]);
+        let ignore: Vec<String> = ["engine", "Eurasian blackbird", "Human vocal", "Fireworks"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            ignore_mask(&labels, &common, &ignore),
+            vec![true, true, false, false]
+        );
+        assert!(ignore_mask(&labels, &common, &[]).is_empty());
+    }
+
     #[test]
     fn test_softmax() {
         let logits = vec![1.0, 2.0, 3.0];
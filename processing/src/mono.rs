@@ -0,0 +1,193 @@
+//! `gaia-processing mono`: capture and processing in one process, for
+//! single-Pi deployments that don't want two containers.
+//!
+//! Recording, segment stamping, the disk guard and the capture HTTP API
+//! (still served, for the web UI's levels, devices and stream status)
+//! are `gaia-capture`'s own modules.  Instead of polling that API, every
+//! stamped segment is handed to the workers over a channel and analysed
+//! where it lies; reporting deletes it afterwards, as it does with the
+//! downloaded copies in split mode.
+//!
+//! The node does not register on mDNS, so a processing container that
+//! runs elsewhere on the network never fetches (and deletes) its
+//! recordings.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use gaia_capture::{server, stamp, supervisor, DiskState, LevelState, StreamStatusState};
+use gaia_common::config::Config;
+use gaia_common::node_id::NodeIdentity;
+use tracing::{info, warn};
+
+use crate::journal::{self, Stage};
+use crate::WorkItem;
+
+/// Record with the local capture modules and dispatch every finished
+/// segment to the workers.  Blocks until `shutdown` is set, then stops
+/// capture (closing the segment being written) and the HTTP API.
+pub fn run(
+    config: &mut Config,
+    work_tx: &SyncSender<WorkItem>,
+    shutdown: &'static AtomicBool,
+) -> Result<()> {
+    let stream_dir = config.stream_data_dir();
+    std::fs::create_dir_all(&stream_dir).context("Cannot create StreamData directory")?;
+    let identity = gaia_common::node_id::load_or_create(
+        &std::env::var("HOSTNAME").unwrap_or_else(|_| "gaia".into()),
+    );
+    let source_node = identity.name.clone();
+    info!(
+        "Mono mode: recording to {} as {source_node}, analysing in-process",
+        stream_dir.display()
+    );
+
+    let disk_state = Arc::new(DiskState::new());
+    let level_state = Arc::new(LevelState::default());
+    let stream_state = Arc::new(StreamStatusState::default());
+
+    // ── capture HTTP API (levels, devices, status for the web UI) ────
+    let server_shutdown = Arc::new(AtomicBool::new(false));
+    let server_thread = {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Cannot create capture API runtime")?;
+        let server_config = config.clone();
+        let (disk, levels, streams) = (disk_state.clone(), level_state.clone(), stream_state.clone());
+        let (stop, identity) = (server_shutdown.clone(), identity.clone());
+        std::thread::Builder::new()
+            .name("capture-api".into())
+            .spawn(move || {
+                let served = runtime.block_on(server::run(
+                    server_config,
+                    stop,
+                    disk,
+                    levels,
+                    streams,
+                    identity,
+                ));
+                if let Err(e) = served {
+                    tracing::error!("Capture HTTP server error: {e:#}");
+                }
+            })
+            .context("Cannot spawn capture API thread")?
+    };
+
+    // ── recording, stamping and the disk guard ───────────────────────
+    let capture_handle = supervisor::start_with_retries(config);
+    let capture_shutdown = Arc::new(AtomicBool::new(false));
+    let health_thread = supervisor::spawn_health(
+        config.clone(),
+        capture_handle,
+        disk_state,
+        stream_state,
+        capture_shutdown.clone(),
+    );
+    let (segment_tx, segment_rx) = mpsc::channel::<PathBuf>();
+    // Stopped only after capture, so the final segment is stamped too.
+    let stamp_shutdown = Arc::new(AtomicBool::new(false));
+    let stamp_thread = {
+        let (dir, node, stop) = (stream_dir.clone(), source_node.clone(), stamp_shutdown.clone());
+        std::thread::Builder::new()
+            .name("wav-stamp".into())
+            .spawn(move || stamp::stamp_loop(dir, node, level_state, Some(segment_tx), stop))
+            .context("Cannot spawn stamping thread")?
+    };
+
+    // ── hand finished segments to the workers ────────────────────────
+    let poll_interval = Duration::from_secs(config.poll_interval_secs.max(1));
+    let mut pending: VecDeque<PathBuf> = VecDeque::new();
+    let mut reloader = crate::reload::Reloader::default();
+    while !shutdown.load(Ordering::Relaxed) {
+        match segment_rx.recv_timeout(poll_interval) {
+            Ok(path) => pending.push_back(path),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                warn!("Stamping thread stopped — no more segments");
+                break;
+            }
+        }
+        pending.extend(segment_rx.try_iter());
+
+        crate::reload::check();
+        reloader.apply(config);
+        crate::kv::apply_settings_overrides(config);
+        crate::kv::update_heartbeat("default");
+        // Like split mode: with every model disabled, recordings wait.
+        if matches!(crate::kv::get_enabled_models_state(), Some(v) if v.is_empty()) {
+            continue;
+        }
+
+        while let Some(path) = pending.pop_front() {
+            if !dispatch(path, &identity, config, work_tx) {
+                shutdown.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+    }
+
+    // Capture first, then the last stamping pass, then the API.
+    capture_shutdown.store(true, Ordering::Relaxed);
+    if let Some(t) = health_thread {
+        t.join().ok();
+    }
+    stamp_shutdown.store(true, Ordering::Relaxed);
+    stamp_thread.join().ok();
+    server_shutdown.store(true, Ordering::Relaxed);
+    server_thread.join().ok();
+    if !pending.is_empty() {
+        info!("{} segment(s) left for the next start", pending.len());
+    }
+    Ok(())
+}
+
+/// Journal and queue one segment; `false` once the workers are gone.
+fn dispatch(
+    path: PathBuf,
+    identity: &NodeIdentity,
+    config: &Config,
+    work_tx: &SyncSender<WorkItem>,
+) -> bool {
+    let Some(filename) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+        return true;
+    };
+    let key = journal::key(&identity.id, &filename);
+    match journal::stage(&key) {
+        // Analysed before a restart but not yet deleted.
+        Ok(Some(Stage::Analysed)) => {
+            info!("{filename} already analysed — deleting");
+            let _ = std::fs::remove_file(&path);
+            return true;
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Cannot read journal for {filename}: {e:#}"),
+    }
+    if let Err(e) = journal::mark(&key, Stage::Dispatched) {
+        warn!("Cannot journal {filename}: {e:#} — skipping");
+        return true;
+    }
+
+    let item = WorkItem {
+        local_path: path,
+        filename,
+        // No capture server to delete from: the segment is analysed in
+        // place and removed by the reporting thread.
+        base_url: String::new(),
+        source_node: identity.name.clone(),
+        source_node_id: identity.id.clone(),
+        expected_secs: Some(config.recording_length as f64),
+        config_snapshot: config.clone(),
+    };
+    crate::activity::enqueue(crate::activity::Queue::Analysis);
+    if work_tx.send(item).is_err() {
+        warn!("Work channel closed — stopping dispatch");
+        return false;
+    }
+    true
+}
@@ -0,0 +1,106 @@
+//! Expected seasonal occurrence, written as
+//! `/data/species_occurrence/<slug>.parquet` for models with a metadata
+//! (species-range) model.
+//!
+//! For the configured location the metadata model is queried for every
+//! week of the year, with the same ISO week numbers the range filter
+//! passes it during analysis.  gaia-web's seasonal page plots these
+//! scores next to the weekly detection histogram, which shows whether
+//! the range filter agrees with what is actually heard.
+//!
+//! Scores below [`MIN_SCORE`] are left out to keep the files small; a
+//! missing row means "not expected".
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use duckdb::params;
+use gaia_common::config::Config;
+use gaia_common::detection::normalize_sci_name;
+use tracing::info;
+
+use crate::model::LoadedModel;
+use crate::taxonomy;
+
+/// Weeks queried: ISO weeks 1 – 53.
+const WEEKS: std::ops::RangeInclusive<u32> = 1..=53;
+
+/// Scores lower than this are not written.
+const MIN_SCORE: f32 = 0.001;
+
+/// Write the occurrence file of every model with a metadata model into
+/// `<data_dir>/species_occurrence/`.  Does nothing without a configured
+/// location.
+pub fn write_all(models: &[LoadedModel], data_dir: &Path, config: &Config) -> Result<()> {
+    let Some((lat, lon)) = config.location() else {
+        info!("Expected occurrence not written: no location configured");
+        return Ok(());
+    };
+    let dir = data_dir.join("species_occurrence");
+    for m in models.iter().filter(|m| m.has_species_range_model()) {
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Cannot create {}", dir.display()))?;
+        let slug = m.manifest.slug();
+        let n = write_model(m, lat, lon, &dir.join(format!("{slug}.parquet")))
+            .with_context(|| format!("Cannot write expected occurrence for {slug}"))?;
+        info!("Expected occurrence: {n} species for {slug} at ({lat}, {lon})");
+    }
+    Ok(())
+}
+
+/// Write one model's weekly scores; returns the number of species with
+/// any row.
+fn write_model(m: &LoadedModel, lat: f64, lon: f64, path: &Path) -> Result<usize> {
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open in-memory DuckDB")?;
+    conn.execute_batch(
+        "CREATE TABLE occurrence (
+            Sci_Name   VARCHAR NOT NULL,
+            Week       INTEGER NOT NULL,
+            Score      DOUBLE NOT NULL,
+            Latitude   DOUBLE NOT NULL,
+            Longitude  DOUBLE NOT NULL,
+            Model_Slug VARCHAR NOT NULL
+        );
+        BEGIN;",
+    )?;
+
+    let slug = m.manifest.slug();
+    let mut species = HashSet::new();
+    {
+        let mut stmt = conn.prepare("INSERT INTO occurrence VALUES (?, ?, ?, ?, ?, ?)")?;
+        for week in WEEKS {
+            let Some(scores) = m.occurrence_scores(lat, lon, week)? else {
+                return Ok(0);
+            };
+            // Labels are `Sci_Common`, like the classifier's.
+            let mut seen = HashSet::new();
+            for (label, score) in scores {
+                if score < MIN_SCORE {
+                    continue;
+                }
+                let sci = label.split('_').next().unwrap_or(label);
+                let sci = taxonomy::canonical_species_name(&normalize_sci_name(sci));
+                if sci.is_empty() || !seen.insert(sci.clone()) {
+                    continue;
+                }
+                stmt.execute(params![sci, week, f64::from(score), lat, lon, slug])?;
+                species.insert(sci);
+            }
+        }
+    }
+    conn.execute_batch("COMMIT")?;
+
+    let tmp = path.with_extension("parquet.tmp");
+    conn.execute(
+        &format!(
+            "COPY occurrence TO '{}' (FORMAT PARQUET)",
+            tmp.display().to_string().replace('\'', "''")
+        ),
+        [],
+    )
+    .with_context(|| format!("Failed to write Parquet: {}", tmp.display()))?;
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to rename {} → {}", tmp.display(), path.display()))?;
+    Ok(species.len())
+}
@@ -19,6 +19,11 @@
 //!
 //! Each Parquet file is written atomically: first to a `.tmp` file,
 //! then renamed to the final name.
+//!
+//! A flush writes at most one row per (`Source_Node`, `File_Name`,
+//! `Time`, `Sci_Name`, `Model_Slug`, `Channel`).  Repeats across flushes
+//! would need the same recording analysed twice, which the processing
+//! journal prevents, also across restarts.
 
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
@@ -89,7 +94,23 @@ pub fn initialize(output_dir: &Path, instance: &str) -> Result<()> {
             Model_Name  VARCHAR  NOT NULL,
             Model_Beta  INTEGER  NOT NULL,
             Agreement_Score  DOUBLE  NOT NULL,
-            Agreement_Models VARCHAR NOT NULL
+            Agreement_Models VARCHAR NOT NULL,
+            Peak_Freq_Hz     DOUBLE,
+            Bandwidth_Hz     DOUBLE,
+            Call_Duration_S  DOUBLE,
+            Moon_Phase        DOUBLE,
+            Moon_Illumination DOUBLE,
+            Day_Of_Year       INTEGER,
+            Season            VARCHAR,
+            Rarity            DOUBLE,
+            Rare              INTEGER,
+            Model_Version     VARCHAR,
+            Model_Variant     VARCHAR,
+            Model_Runtime     VARCHAR,
+            Top_Predictions   VARCHAR,
+            Channel           INTEGER,
+            Bearing           DOUBLE,
+            Source_Node_Id    VARCHAR
         );",
     )
     .context("Cannot create DuckDB buffer table")?;
@@ -114,6 +135,7 @@ pub fn initialize(output_dir: &Path, instance: &str) -> Result<()> {
 /// The detection is inserted into the in-memory DuckDB table.  When the
 /// buffer reaches [`FLUSH_THRESHOLD`] rows it is automatically flushed
 /// to a Parquet file.
+#[allow(clippy::too_many_arguments)]
 pub fn write_detection(
     d: &Detection,
     lat: f64,
@@ -123,6 +145,7 @@ pub fn write_detection(
     overlap: f64,
     file_name: &str,
     source_node: &str,
+    source_node_id: &str,
 ) -> Result<()> {
     let store = STORE.get().context("Parquet store not initialised")?;
     let mut s = store
@@ -139,8 +162,16 @@ pub fn write_detection(
     s.seq += 1;
     let id = ((epoch_ms & 0xFFFF_FFFF_FFFF) << 16) | (s.seq & 0xFFFF);
 
+    // JSON array of {scientific_name, common_name, confidence}; NULL
+    // unless TOP_PREDICTIONS is set.
+    let top_predictions = if d.top_predictions.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&d.top_predictions)?)
+    };
+
     s.conn.execute(
-        "INSERT INTO buffer VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO buffer VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         params![
             id as i64,
             d.date,
@@ -163,6 +194,22 @@ pub fn write_detection(
             d.model_beta as i32,
             d.agreement_score,
             d.agreement_models,
+            d.peak_freq_hz,
+            d.bandwidth_hz,
+            d.call_duration_secs,
+            d.moon_phase,
+            d.moon_illumination,
+            d.day_of_year,
+            d.season,
+            d.rarity,
+            d.rare as i32,
+            d.model_version,
+            d.model_variant,
+            d.model_runtime,
+            top_predictions,
+            d.channel.map(i32::from),
+            d.bearing_deg,
+            source_node_id,
         ],
     )
     .context("Failed to buffer detection in DuckDB")?;
@@ -210,7 +257,11 @@ fn flush_locked(s: &mut Store) -> Result<()> {
     s.conn
         .execute(
             &format!(
-                "COPY buffer TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD)",
+                "COPY (SELECT * FROM buffer \
+                 QUALIFY row_number() OVER ( \
+                     PARTITION BY Source_Node, File_Name, Time, Sci_Name, Model_Slug, Channel \
+                     ORDER BY id) = 1) \
+                 TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD)",
                 tmp_path.display()
             ),
             [],
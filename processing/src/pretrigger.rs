@@ -0,0 +1,107 @@
+//! Pre-trigger audio: the end of each stream's previous segment, held in
+//! memory after the recording is deleted so that the clip of a call at
+//! the very start of the next segment can include the call's beginning.
+//!
+//! Only consecutive segments are joined: the held audio is used when it
+//! ended within [`MAX_GAP`] of the next segment's start.  Segments that
+//! reach reporting out of order (several workers) or after a capture gap
+//! are clipped at the segment start as before.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use tracing::debug;
+
+use gaia_common::audio::{self, PreRoll};
+use gaia_common::detection::ParsedFileName;
+
+/// Largest gap between two segments of a stream still treated as
+/// continuous audio.  Capture restarts its recorder between segments.
+const MAX_GAP: f64 = 1.5;
+
+struct Held {
+    ends_at: NaiveDateTime,
+    pre_roll: PreRoll,
+}
+
+/// The last segment's tail per capture stream (node + stream id).
+#[derive(Default)]
+pub struct PreTriggerBuffer {
+    held: HashMap<String, Held>,
+}
+
+impl PreTriggerBuffer {
+    /// The end of the segment recorded just before `file`, if it is held.
+    pub fn before(&self, file: &ParsedFileName, source_node: &str) -> Option<&PreRoll> {
+        let held = self.held.get(&stream_key(file, source_node))?;
+        let gap = (file.file_date - held.ends_at).num_milliseconds() as f64 / 1000.0;
+        (gap.abs() <= MAX_GAP).then_some(&held.pre_roll)
+    }
+
+    /// Keep the last `secs` seconds of `file` before it is deleted.
+    pub fn hold(&mut self, file: &ParsedFileName, source_node: &str, secs: f64) {
+        let is_wav = file
+            .file_path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+        if secs <= 0.0 || !is_wav {
+            return;
+        }
+        match audio::read_pre_roll(&file.file_path, secs) {
+            Ok(pre_roll) => {
+                let ends_at = file.file_date
+                    + chrono::Duration::milliseconds((pre_roll.file_duration * 1000.0) as i64);
+                self.held.insert(stream_key(file, source_node), Held { ends_at, pre_roll });
+            }
+            Err(e) => debug!("No pre-trigger audio from {}: {e:#}", file.file_path.display()),
+        }
+    }
+}
+
+fn stream_key(file: &ParsedFileName, source_node: &str) -> String {
+    let node = file.capture_node.as_deref().unwrap_or(source_node);
+    format!("{node}/{}", file.rtsp_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn segment(dir: &Path, name: &str) -> ParsedFileName {
+        let path = dir.join(name);
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut w = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..8_000 * 15 {
+            w.write_sample(0i16).unwrap();
+        }
+        w.finalize().unwrap();
+        ParsedFileName::parse(&path).unwrap()
+    }
+
+    #[test]
+    fn test_pre_trigger_joins_consecutive_segments() {
+        let dir = std::env::temp_dir().join(format!("gaia-pretrigger-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = segment(&dir, "2026-05-01-birdnet-08:00:00.wav");
+        let next = segment(&dir, "2026-05-01-birdnet-08:00:15.wav");
+        let later = segment(&dir, "2026-05-01-birdnet-08:05:00.wav");
+
+        let mut buffer = PreTriggerBuffer::default();
+        assert!(buffer.before(&next, "garden").is_none());
+
+        buffer.hold(&first, "garden", 1.5);
+        let pre_roll = buffer.before(&next, "garden").unwrap();
+        assert!((pre_roll.duration_secs() - 1.5).abs() < 1e-9);
+        // Another node, or a segment after a gap, is not continuous.
+        assert!(buffer.before(&next, "roof").is_none());
+        assert!(buffer.before(&later, "garden").is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,196 @@
+//! Regional rarity of detected species, from a checklist of how often
+//! each species is reported in the region.
+//!
+//! The checklist (`RARITY_CHECKLIST`, default
+//! `/data/regional_checklist.csv`) lists a scientific name followed by
+//! either one reporting frequency or 48 weekly ones (four per month, the
+//! layout of eBird's bar-chart download):
+//!
+//! ```csv
+//! sci_name,frequency
+//! Turdus grayi,0.62
+//! Harpia harpyja,0.001
+//! ```
+//!
+//! Frequencies are the share of checklists reporting the species
+//! (0.0 – 1.0).  Commas, semicolons and tabs all work as separators, and
+//! eBird's `Common Name (<em class="sci">Scientific name</em>)` cells are
+//! understood, so the tab-separated bar-chart file can be used as is.
+//!
+//! A detection's rarity is `1 − frequency` for the week it was made.
+//! Birds missing from the checklist were never reported there and score
+//! 1.0; other unlisted species are not scored, since most checklists
+//! only cover birds.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use chrono::{Datelike, NaiveDate};
+use gaia_common::detection::normalize_sci_name;
+use tracing::info;
+
+/// Weekly columns of an eBird bar chart: four per month.
+const WEEKS: usize = 48;
+
+static CHECKLIST: OnceLock<Checklist> = OnceLock::new();
+
+/// The checklist at `path`, loaded on the first call.
+pub fn global(path: &Path) -> &'static Checklist {
+    CHECKLIST.get_or_init(|| Checklist::load(path))
+}
+
+/// Reporting frequencies per normalised scientific name: one value for
+/// the whole year, or [`WEEKS`] weekly values.
+pub struct Checklist {
+    frequencies: HashMap<String, Vec<f64>>,
+}
+
+impl Checklist {
+    /// Load from a file.  Returns an empty checklist (no scoring) if the
+    /// file doesn't exist.
+    pub fn load(path: &Path) -> Self {
+        let checklist = match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(_) => {
+                info!("No regional checklist at {} — rarity not scored", path.display());
+                return Self { frequencies: HashMap::new() };
+            }
+        };
+        info!(
+            "Loaded regional frequencies of {} species from {}",
+            checklist.frequencies.len(),
+            path.display()
+        );
+        checklist
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut frequencies = HashMap::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let delim = if line.contains('\t') {
+                '\t'
+            } else if line.contains(';') {
+                ';'
+            } else {
+                ','
+            };
+            let mut cols = line.split(delim);
+            let Some(name) = cols.next().map(sci_name_cell) else {
+                continue;
+            };
+            // Header rows and eBird's preamble don't parse as numbers.
+            let values: Option<Vec<f64>> = cols
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(|c| c.parse::<f64>().ok().filter(|f| (0.0..=1.0).contains(f)))
+                .collect();
+            match values {
+                Some(v) if v.len() == 1 || v.len() == WEEKS => {
+                    let sci = normalize_sci_name(&name);
+                    if !sci.is_empty() {
+                        frequencies.insert(sci, v);
+                    }
+                }
+                _ => continue,
+            }
+        }
+        Self { frequencies }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frequencies.is_empty()
+    }
+
+    /// Rarity (0.0 common – 1.0 never reported) of a species on `date`.
+    ///
+    /// `None` when no checklist is loaded, or when the species is not
+    /// listed and is not a bird.
+    pub fn rarity(&self, sci_name: &str, domain: &str, date: NaiveDate) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        let Some(freqs) = self.frequencies.get(&normalize_sci_name(sci_name)) else {
+            return is_bird_domain(domain).then_some(1.0);
+        };
+        let freq = if freqs.len() == WEEKS {
+            freqs[week_index(date)]
+        } else {
+            freqs[0]
+        };
+        Some(((1.0 - freq) * 1000.0).round() / 1000.0)
+    }
+}
+
+/// Column of `date` in a 48-week bar chart: days 1–7, 8–14, 15–21 and
+/// 22–end of each month.
+fn week_index(date: NaiveDate) -> usize {
+    let quarter = ((date.day() as usize - 1) / 7).min(3);
+    date.month0() as usize * 4 + quarter
+}
+
+/// Scientific name from a checklist cell: plain, or eBird's
+/// `Common Name (<em class="sci">Scientific name</em>)`.
+fn sci_name_cell(cell: &str) -> String {
+    let cell = cell.trim().trim_matches('"');
+    if let Some(start) = cell.find("<em") {
+        let inner = &cell[start..];
+        if let (Some(open), Some(close)) = (inner.find('>'), inner.find("</em>")) {
+            if open < close {
+                return inner[open + 1..close].trim().to_string();
+            }
+        }
+    }
+    cell.to_string()
+}
+
+/// Domains (coarse model domain or taxonomic class) holding birds.
+fn is_bird_domain(domain: &str) -> bool {
+    domain.eq_ignore_ascii_case("birds") || domain.eq_ignore_ascii_case("aves")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checklist_rarity() {
+        let mut weekly = vec!["0.0"; WEEKS];
+        weekly[19] = "0.40"; // late May
+        let text = format!(
+            "sci_name,frequency\n\
+             Turdus grayi,0.62\n\
+             Harpia harpyja;0.001\n\
+             Wood Thrush (<em class=\"sci\">Hylocichla mustelina</em>)\t{}\n\
+             Broken row,abc\n",
+            weekly.join("\t")
+        );
+        let list = Checklist::parse(&text);
+        let may = NaiveDate::from_ymd_opt(2026, 5, 25).unwrap();
+        let june = NaiveDate::from_ymd_opt(2026, 6, 2).unwrap();
+
+        assert_eq!(list.rarity("Turdus grayi", "birds", may), Some(0.38));
+        assert_eq!(list.rarity("Harpia harpyja", "Aves", may), Some(0.999));
+        assert_eq!(list.rarity("Hylocichla mustelina", "birds", may), Some(0.6));
+        assert_eq!(list.rarity("Hylocichla mustelina", "birds", june), Some(1.0));
+        // Unlisted: rare for birds, unscored otherwise.
+        assert_eq!(list.rarity("Tyto alba", "birds", may), Some(1.0));
+        assert_eq!(list.rarity("Rana temporaria", "frogs", may), None);
+        assert_eq!(list.rarity("Broken row", "birds", may), Some(1.0));
+
+        assert_eq!(Checklist::parse("").rarity("Turdus grayi", "birds", may), None);
+    }
+
+    #[test]
+    fn test_week_index() {
+        let d = |m, day| NaiveDate::from_ymd_opt(2026, m, day).unwrap();
+        assert_eq!(week_index(d(1, 1)), 0);
+        assert_eq!(week_index(d(1, 8)), 1);
+        assert_eq!(week_index(d(2, 28)), 7);
+        assert_eq!(week_index(d(12, 31)), 47);
+    }
+}
@@ -0,0 +1,362 @@
+//! Re-scoring of stored audio with the current models
+//! (`gaia-processing reanalyze`).
+//!
+//! After a model upgrade the clips the detection store points to — or
+//! the recordings in a directory of retained originals — are analysed
+//! again.  Each model's results go to a table of their own,
+//! `<data>/reanalysis/<model slug>.parquet`, never into the detection
+//! store.  Slugs carry the model version (`birdnet-v2-4`), so the output
+//! of two versions can be joined on `Original_Id` or `Source` to compare
+//! them.  Every analysed file gets at least one row per model; a row
+//! without `Sci_Name` means the model found nothing above the threshold.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use duckdb::params;
+use serde::Serialize;
+
+use gaia_common::detection::Detection;
+
+use crate::export::{file_list_sql, sql_str, validate_date};
+
+/// A file to analyse again.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub path: PathBuf,
+    /// Start time the analysis dates detections from.
+    pub start: NaiveDateTime,
+    /// The stored detection the clip was extracted for; `None` for
+    /// retained recordings.
+    pub original: Option<Original>,
+}
+
+/// The stored detection a clip belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Original {
+    pub id: i64,
+    pub scientific_name: String,
+    pub confidence: f64,
+    pub model_slug: String,
+}
+
+/// One row of a reanalysis table.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub source: String,
+    pub original: Option<Original>,
+    pub model_slug: String,
+    pub model_name: String,
+    /// `None` when the model found nothing in the file.
+    pub detection: Option<Detection>,
+}
+
+/// A table written by [`write_tables`].
+#[derive(Debug, Serialize)]
+pub struct Table {
+    pub model: String,
+    pub path: PathBuf,
+    pub rows: usize,
+    pub detections: usize,
+}
+
+/// Clips of the detections in `det_files` dated `from..=to` (both
+/// optional), found under `extracted_dir/By_Date`, oldest first.
+/// Returns the targets and the number of clips no longer on disk.
+pub fn stored_clips(
+    det_files: &[PathBuf],
+    extracted_dir: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(Vec<Target>, usize)> {
+    let mut filter = vec!["File_Name != ''".to_string()];
+    if let Some(f) = from {
+        filter.push(format!("Date >= {}", sql_str(validate_date(f)?)));
+    }
+    if let Some(t) = to {
+        filter.push(format!("Date <= {}", sql_str(validate_date(t)?)));
+    }
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+    conn.execute_batch(&format!(
+        "CREATE VIEW detections AS SELECT * FROM read_parquet({}, union_by_name=true)",
+        file_list_sql(det_files)
+    ))
+    .context("Cannot read Parquet files")?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, Date, Time, File_Name, Sci_Name, Confidence, COALESCE(Model_Slug, '') \
+         FROM detections WHERE {} ORDER BY Date, Time, id",
+        filter.join(" AND ")
+    ))?;
+    let stored: Vec<(String, String, String, Original)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                Original {
+                    id: row.get(0)?,
+                    scientific_name: row.get(4)?,
+                    confidence: row.get(5)?,
+                    model_slug: row.get(6)?,
+                },
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let dates: HashSet<&str> = stored.iter().map(|(date, ..)| date.as_str()).collect();
+    let clips = clip_index(&extracted_dir.join("By_Date"), &dates);
+    let mut seen = HashSet::new();
+    let mut targets = Vec::new();
+    let mut missing = 0;
+    for (date, time, file_name, original) in stored {
+        let Some(path) = clips.get(&file_name) else {
+            missing += 1;
+            continue;
+        };
+        // Clips shared by several detections (one per model) are
+        // analysed once, for the first of them.
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let Ok(start) =
+            NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M:%S")
+        else {
+            continue;
+        };
+        targets.push(Target {
+            path: path.clone(),
+            start,
+            original: Some(original),
+        });
+    }
+    Ok((targets, missing))
+}
+
+/// Clip file name → path, for the `By_Date/<date>/<species>/` folders
+/// of `dates`.
+fn clip_index(by_date: &Path, dates: &HashSet<&str>) -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    for date in dates {
+        let Ok(species_dirs) = std::fs::read_dir(by_date.join(date)) else {
+            continue;
+        };
+        for species in species_dirs.flatten() {
+            let Ok(files) = std::fs::read_dir(species.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                index.insert(file.file_name().to_string_lossy().into_owned(), file.path());
+            }
+        }
+    }
+    index
+}
+
+/// Rows for the detections of one analysed `target`: each model in
+/// `models` (`(slug, name)`) without a detection gets an empty row.
+pub fn rows_for(
+    target: &Target,
+    models: &[(String, String)],
+    detections: Vec<Detection>,
+) -> Vec<Row> {
+    let source = target.path.display().to_string();
+    let found: HashSet<String> = detections.iter().map(|d| d.model_slug.clone()).collect();
+    let mut rows: Vec<Row> = detections
+        .into_iter()
+        .map(|d| Row {
+            source: source.clone(),
+            original: target.original.clone(),
+            model_slug: d.model_slug.clone(),
+            model_name: d.model_name.clone(),
+            detection: Some(d),
+        })
+        .collect();
+    for (slug, name) in models.iter().filter(|(slug, _)| !found.contains(slug)) {
+        rows.push(Row {
+            source: source.clone(),
+            original: target.original.clone(),
+            model_slug: slug.clone(),
+            model_name: name.clone(),
+            detection: None,
+        });
+    }
+    rows
+}
+
+/// Write `rows` to `<dir>/<model slug>.parquet`, one file per model,
+/// replacing the tables of an earlier run of the same models.
+pub fn write_tables(dir: &Path, rows: &[Row]) -> Result<Vec<Table>> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    let mut by_model: BTreeMap<&str, Vec<&Row>> = BTreeMap::new();
+    for row in rows {
+        by_model.entry(&row.model_slug).or_default().push(row);
+    }
+
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+    let reanalysed_at = chrono::Local::now().to_rfc3339();
+    let mut tables = Vec::new();
+    for (slug, rows) in by_model {
+        conn.execute_batch(
+            "CREATE OR REPLACE TABLE reanalysis (
+                Original_Id      BIGINT,
+                Source           VARCHAR NOT NULL,
+                Orig_Sci_Name    VARCHAR,
+                Orig_Confidence  DOUBLE,
+                Orig_Model_Slug  VARCHAR,
+                Model_Slug       VARCHAR NOT NULL,
+                Model_Name       VARCHAR NOT NULL,
+                Date             VARCHAR,
+                Time             VARCHAR,
+                Start_S          DOUBLE,
+                Stop_S           DOUBLE,
+                Domain           VARCHAR,
+                Sci_Name         VARCHAR,
+                Com_Name         VARCHAR,
+                Confidence       DOUBLE,
+                Excluded         INTEGER,
+                Reanalysed_At    VARCHAR NOT NULL
+            );",
+        )?;
+        for row in &rows {
+            let o = row.original.as_ref();
+            let d = row.detection.as_ref();
+            conn.execute(
+                "INSERT INTO reanalysis VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    o.map(|o| o.id),
+                    row.source,
+                    o.map(|o| o.scientific_name.as_str()),
+                    o.map(|o| o.confidence),
+                    o.map(|o| o.model_slug.as_str()),
+                    row.model_slug,
+                    row.model_name,
+                    d.map(|d| d.date.as_str()),
+                    d.map(|d| d.time.as_str()),
+                    d.map(|d| d.start),
+                    d.map(|d| d.stop),
+                    d.map(|d| d.domain.as_str()),
+                    d.map(|d| d.scientific_name.as_str()),
+                    d.map(|d| d.common_name.as_str()),
+                    d.map(|d| d.confidence),
+                    d.map(|d| d.excluded as i32),
+                    reanalysed_at,
+                ],
+            )?;
+        }
+
+        let name = if slug.is_empty() { "unknown" } else { slug };
+        let path = dir.join(format!("{name}.parquet"));
+        let tmp = dir.join(format!(".{name}.parquet.tmp"));
+        conn.execute_batch(&format!(
+            "COPY reanalysis TO {} (FORMAT PARQUET, COMPRESSION ZSTD)",
+            sql_str(&tmp.display().to_string())
+        ))
+        .with_context(|| format!("Cannot write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Cannot rename {} → {}", tmp.display(), path.display()))?;
+        tables.push(Table {
+            model: slug.to_string(),
+            path,
+            rows: rows.len(),
+            detections: rows.iter().filter(|r| r.detection.is_some()).count(),
+        });
+    }
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "gaia-reanalyze-{label}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_stored_clips_and_tables() {
+        let dir = temp_dir("clips");
+        let clip_dir = dir.join("Extracted/By_Date/2025-05-01/Common_Blackbird");
+        std::fs::create_dir_all(&clip_dir).unwrap();
+        std::fs::write(clip_dir.join("blackbird.opus"), b"x").unwrap();
+
+        let det_dir = dir.join("detections");
+        std::fs::create_dir_all(&det_dir).unwrap();
+        let store = det_dir.join("a.parquet");
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "COPY (SELECT id::BIGINT AS id, Date, Time, File_Name, Sci_Name, \
+                     Confidence::DOUBLE AS Confidence, Model_Slug FROM (VALUES \
+                (1, '2025-05-01', '06:00:03', 'blackbird.opus', 'Turdus merula', 0.9, 'birdnet-v2-4'), \
+                (2, '2025-05-01', '06:00:03', 'blackbird.opus', 'Turdus merula', 0.8, 'perch'), \
+                (3, '2025-05-01', '07:00:00', 'gone.opus', 'Pica pica', 0.7, 'birdnet-v2-4'), \
+                (4, '2025-04-30', '07:00:00', 'blackbird.opus', 'Pica pica', 0.7, 'birdnet-v2-4')) \
+             AS t(id, Date, Time, File_Name, Sci_Name, Confidence, Model_Slug)) TO {} (FORMAT PARQUET)",
+            sql_str(&store.display().to_string())
+        ))
+        .unwrap();
+
+        let (targets, missing) =
+            stored_clips(&[store], &dir.join("Extracted"), Some("2025-05-01"), None).unwrap();
+        assert_eq!(missing, 1);
+        assert_eq!(
+            targets.len(),
+            1,
+            "a clip shared by two models is analysed once"
+        );
+        assert_eq!(targets[0].path, clip_dir.join("blackbird.opus"));
+        assert_eq!(targets[0].original.as_ref().unwrap().id, 1);
+        assert_eq!(targets[0].start.to_string(), "2025-05-01 06:00:03");
+
+        let mut found = Detection::new(
+            "birds",
+            targets[0].start,
+            0.0,
+            3.0,
+            "Turdus merula",
+            "Common Blackbird",
+            0.95,
+        );
+        found.model_slug = "birdnet-v3-0".into();
+        found.model_name = "BirdNET V3.0".into();
+        let models = [
+            ("birdnet-v3-0".to_string(), "BirdNET V3.0".to_string()),
+            ("perch".to_string(), "Perch".to_string()),
+        ];
+        let rows = rows_for(&targets[0], &models, vec![found]);
+        assert_eq!(rows.len(), 2);
+        assert!(rows[1].detection.is_none() && rows[1].model_slug == "perch");
+
+        let out = dir.join("reanalysis");
+        let tables = write_tables(&out, &rows).unwrap();
+        let summary: Vec<(&str, usize, usize)> = tables
+            .iter()
+            .map(|t| (t.model.as_str(), t.rows, t.detections))
+            .collect();
+        assert_eq!(summary, [("birdnet-v3-0", 1, 1), ("perch", 1, 0)]);
+        let (sci, orig): (String, f64) = conn
+            .query_row(
+                &format!(
+                    "SELECT Sci_Name, Orig_Confidence FROM read_parquet({})",
+                    sql_str(&out.join("birdnet-v3-0.parquet").display().to_string())
+                ),
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((sci.as_str(), orig), ("Turdus merula", 0.9));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
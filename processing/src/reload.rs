@@ -0,0 +1,291 @@
+//! Runtime reload of `gaia.conf`, on SIGHUP or when the file changes.
+//!
+//! Settings that are only read per recording or per report (thresholds,
+//! location, clip length, rules…) are applied to the running pipeline;
+//! everything else is logged as needing a restart.  The species lists
+//! are already read from disk for every recording, so for them a change
+//! is only logged, and a changed rules file is picked up by the reporting
+//! thread.
+//!
+//! [`check`] is called from the poll loop; each consumer of the config
+//! keeps a [`Reloader`] and calls [`Reloader::apply`] on its own copy.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use tracing::{info, warn};
+
+use gaia_common::config::{self, Config};
+
+/// Keys applied without a restart.
+const RUNTIME_KEYS: &[&str] = &[
+    "LATITUDE",
+    "LONGITUDE",
+    "CONFIDENCE",
+    "SENSITIVITY",
+    "OVERLAP",
+    "SF_THRESH",
+    "PRIVACY_THRESHOLD",
+    "EXTRACTION_LENGTH",
+    "RAW_SPECTROGRAM",
+    "COLORMAP",
+    "RARITY_THRESHOLD",
+    "BIRDWEATHER_ID",
+    "RULES_FILE",
+];
+
+/// Keys the web UI can override (`settings` hash field); a value saved
+/// there still wins over the file.
+const UI_SETTINGS: &[(&str, &str)] = &[
+    ("CONFIDENCE", "confidence"),
+    ("SENSITIVITY", "sensitivity"),
+    ("OVERLAP", "overlap"),
+    ("SF_THRESH", "sf_thresh"),
+    ("COLORMAP", "colormap"),
+];
+
+const SPECIES_LISTS: &[&str] = &[
+    "include_species_list.txt",
+    "exclude_species_list.txt",
+    "whitelist_species_list.txt",
+];
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+/// Bumped on every applied reload.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+struct State {
+    path: PathBuf,
+    keys: HashMap<String, String>,
+    config: Config,
+    /// `gaia.conf`, the species lists and the rules file, with the
+    /// modification time last seen.
+    watched: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn watched_files(path: &Path, config: &Config) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let base = std::env::var("GAIA_DIR").unwrap_or_else(|_| "/app".to_string());
+    let mut files = vec![path.to_path_buf(), config.rules_file.clone()];
+    for name in SPECIES_LISTS {
+        files.push(Path::new(&base).join(name));
+        if let Some(data_dir) = config.db_path.parent() {
+            files.push(data_dir.join(name));
+        }
+    }
+    files.into_iter().map(|f| (f.clone(), mtime(&f))).collect()
+}
+
+extern "C" fn on_sighup(_: libc::c_int) {
+    REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Remember the loaded config and install the SIGHUP handler.
+///
+/// Must run after `ctrlc::set_handler`, which would otherwise treat
+/// SIGHUP as a shutdown signal.
+pub fn init(path: &Path, config: &Config) {
+    let keys = config::read_keys(path).unwrap_or_default();
+    let watched = watched_files(path, config);
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some(State {
+        path: path.to_path_buf(),
+        keys,
+        config: config.clone(),
+        watched,
+    });
+    // SAFETY: the handler only stores to an atomic.
+    let handler = on_sighup as extern "C" fn(libc::c_int);
+    if unsafe { libc::signal(libc::SIGHUP, handler as libc::sighandler_t) } == libc::SIG_ERR {
+        warn!("Cannot install SIGHUP handler — config reloads only follow file changes");
+    } else {
+        info!("Config reload: send SIGHUP or edit {}", path.display());
+    }
+}
+
+/// Reload the config if SIGHUP was received or a watched file changed.
+pub fn check() {
+    let mut guard = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(state) = guard.as_mut() else {
+        return;
+    };
+    let signalled = REQUESTED.swap(false, Ordering::Relaxed);
+    let changed: Vec<PathBuf> = state
+        .watched
+        .iter_mut()
+        .filter_map(|(path, seen)| {
+            let now = mtime(path);
+            (now != *seen).then(|| {
+                *seen = now;
+                path.clone()
+            })
+        })
+        .collect();
+    if !signalled && changed.is_empty() {
+        return;
+    }
+    if signalled {
+        info!("SIGHUP received — reloading {}", state.path.display());
+    }
+
+    for path in changed.iter().filter(|p| **p != state.path) {
+        if *path == state.config.rules_file {
+            info!("Rules file {} changed", path.display());
+        } else {
+            info!(
+                "Species list {} changed — applies from the next recording",
+                path.display()
+            );
+        }
+    }
+    if !signalled && !changed.contains(&state.path) {
+        // Only the rules file or a species list changed; the reporting
+        // thread reloads the rules on a new generation.
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+
+    let (keys, new) = match config::read_keys(&state.path)
+        .and_then(|keys| config::load(&state.path).map(|c| (keys, c)))
+    {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Config reload failed, keeping the current settings: {e:#}");
+            return;
+        }
+    };
+    let keys_changed = changed_keys(&state.keys, &keys);
+    if keys_changed.is_empty() {
+        info!("Config reloaded — no settings changed");
+    }
+    for key in &keys_changed {
+        if std::env::var(key).is_ok_and(|v| !v.is_empty()) {
+            warn!("{key} changed in the config file but is set in the environment, which wins");
+        } else if !needs_restart(key) {
+            match ui_override(key) {
+                Some(field) => warn!(
+                    "{key} changed, but the value saved in the web UI ({field}) still applies"
+                ),
+                None => info!("{key} changed — applied"),
+            }
+        } else {
+            warn!("{key} changed — takes effect after a restart");
+        }
+    }
+    state.keys = keys;
+    if new.rules_file != state.config.rules_file {
+        state
+            .watched
+            .push((new.rules_file.clone(), mtime(&new.rules_file)));
+    }
+    state.config = new;
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The web UI field overriding `key`, if one is saved.
+fn ui_override(key: &str) -> Option<&'static str> {
+    UI_SETTINGS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, field)| *field)
+        .filter(|field| crate::kv::get_setting(field).is_some())
+}
+
+fn needs_restart(key: &str) -> bool {
+    !RUNTIME_KEYS.contains(&key)
+}
+
+/// Keys added, removed or changed between two reads, sorted.
+fn changed_keys(old: &HashMap<String, String>, new: &HashMap<String, String>) -> Vec<String> {
+    let all: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    all.into_iter()
+        .filter(|k| old.get(*k) != new.get(*k))
+        .cloned()
+        .collect()
+}
+
+/// Follows reloads for one copy of the config.
+#[derive(Debug, Default)]
+pub struct Reloader {
+    generation: u64,
+}
+
+impl Reloader {
+    /// Copy the runtime settings of the latest reload into `config`.
+    /// Returns `true` when there was a reload since the last call.
+    pub fn apply(&mut self, config: &mut Config) -> bool {
+        let generation = GENERATION.load(Ordering::Relaxed);
+        if generation == self.generation {
+            return false;
+        }
+        self.generation = generation;
+        let guard = STATE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = guard.as_ref() {
+            copy_runtime(&state.config, config);
+        }
+        true
+    }
+}
+
+fn copy_runtime(from: &Config, to: &mut Config) {
+    to.latitude = from.latitude;
+    to.longitude = from.longitude;
+    to.confidence = from.confidence;
+    to.sensitivity = from.sensitivity;
+    to.overlap = from.overlap;
+    to.sf_thresh = from.sf_thresh;
+    to.privacy_threshold = from.privacy_threshold;
+    to.extraction_length = from.extraction_length;
+    to.raw_spectrogram = from.raw_spectrogram;
+    to.colormap = from.colormap.clone();
+    to.rarity_threshold = from.rarity_threshold;
+    to.birdweather_id = from.birdweather_id.clone();
+    to.rules_file = from.rules_file.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_changed_keys() {
+        let old = map(&[
+            ("CONFIDENCE", "0.7"),
+            ("MODEL", "birdnet"),
+            ("OVERLAP", "0.0"),
+        ]);
+        let new = map(&[
+            ("CONFIDENCE", "0.8"),
+            ("OVERLAP", "0.0"),
+            ("LATITUDE", "9.9"),
+        ]);
+        assert_eq!(
+            changed_keys(&old, &new),
+            ["CONFIDENCE", "LATITUDE", "MODEL"]
+        );
+        assert!(changed_keys(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_needs_restart() {
+        assert!(!needs_restart("CONFIDENCE"));
+        assert!(!needs_restart("RULES_FILE"));
+        assert!(needs_restart("MODEL_DIR"));
+        assert!(needs_restart("CAPTURE_SERVER_URL"));
+        // Every UI-overridable key is also applied at runtime.
+        assert!(UI_SETTINGS.iter().all(|(k, _)| !needs_restart(k)));
+    }
+}
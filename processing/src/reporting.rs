@@ -1,5 +1,5 @@
 //! Reporting: write detections to DB, extract audio clips, generate
-//! spectrograms, send notifications.
+//! spectrograms and waveform peaks, send notifications.
 //!
 //! Evolved from `birdnet-server/src/reporting.rs`.
 
@@ -11,24 +11,41 @@ use tracing::{debug, error, info, warn};
 
 use gaia_common::audio;
 use gaia_common::config::Config;
-use gaia_common::detection::{Detection, ParsedFileName};
+use gaia_common::detection::{safe_file_stem, safe_path_component, Detection, ParsedFileName};
 
+use crate::compress::ClipEncoding;
 use crate::kv;
 use crate::parquet_store;
+use crate::pretrigger::PreTriggerBuffer;
+use crate::rarity;
+use crate::reload::Reloader;
+use crate::rules::RuleSet;
 use crate::spectrogram::{self, Colormap, SpectrogramParams};
+use crate::watchdog;
+use crate::waveform;
 use crate::ReportPayload;
 
 /// Run the reporting loop on its own thread.
 pub fn handle_queue(rx: Receiver<ReportPayload>, config: &Config, db_path: &Path) {
     let mut config = config.clone();
+    let mut rules = RuleSet::load(&config.rules_file);
+    let mut pre_trigger = PreTriggerBuffer::default();
+    let mut reloader = Reloader::default();
     while let Ok(payload) = rx.recv() {
+        // gaia.conf or the rules file changed (SIGHUP or an edit).
+        if reloader.apply(&mut config) {
+            rules = RuleSet::load(&config.rules_file);
+        }
         // Refresh settings (colormap, thresholds) from Redis so web UI
         // changes are picked up without restarting the container.
         kv::apply_settings_overrides(&mut config);
 
-        if let Err(e) = process_report(&payload, &config, db_path) {
+        let pre_roll = pre_trigger.before(&payload.file, &payload.source_node);
+        if let Err(e) = process_report(&payload, &config, &mut rules, pre_roll, db_path) {
             error!("Reporting error: {e:#}");
         }
+        // Keep the end of this segment for clips at the start of the next.
+        pre_trigger.hold(&payload.file, &payload.source_node, clip_padding(&config));
 
         // Flush buffered detections to Parquet so the web UI sees them
         // promptly (instead of waiting for FLUSH_THRESHOLD accumulation).
@@ -67,9 +84,29 @@ pub fn handle_queue(rx: Receiver<ReportPayload>, config: &Config, db_path: &Path
     info!("Reporting thread finished");
 }
 
-fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) -> Result<()> {
+fn process_report(
+    payload: &ReportPayload,
+    config: &Config,
+    rules: &mut RuleSet,
+    pre_roll: Option<&audio::PreRoll>,
+    _db_path: &Path,
+) -> Result<()> {
     let file = &payload.file;
 
+    // Store and forward detections at the capture node's own location.
+    let node_config;
+    let config = match payload.location {
+        Some((latitude, longitude)) if payload.location != config.location() => {
+            node_config = Config {
+                latitude,
+                longitude,
+                ..config.clone()
+            };
+            &node_config
+        }
+        _ => config,
+    };
+
     // Separate urban-noise detections (Engine, Dog, Human, …) from real
     // species.  Noise detections are counted but NOT stored in the main
     // detections table.
@@ -87,20 +124,33 @@ fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) ->
     );
 
     write_json_file(file, &payload.detections, config)?;
+    let encoding = ClipEncoding::from_config(config);
+    let extraction_paused = crate::disk_guard::extraction_paused(config);
 
     // ── real species detections ──────────────────────────────────────
+    let mut dropped: Vec<&Detection> = Vec::new();
     for detection in &species_dets {
+        let verdict = rules.apply(detection);
+        if verdict.drop {
+            dropped.push(*detection);
+            continue;
+        }
+        let mut detection = (*detection).clone();
+        detection.excluded |= verdict.exclude;
         // Attempt audio clip extraction.  Extraction failure MUST NOT
         // prevent the detection from being recorded in the database.
-        let extracted = match extract_detection(file, detection, config) {
-            Ok(path) => {
+        let extracted = match (!extraction_paused)
+            .then(|| extract_detection(file, &detection, pre_roll, config))
+        {
+            None => None,
+            Some(Ok(path)) => {
                 // Only generate a spectrogram for freshly-extracted WAV
-                // files.  When extract_detection returns an .opus path the
-                // clip was already processed (and its spectrogram created)
-                // in a previous run — re-generating would fail because
-                // generate_from_wav cannot read Opus.
-                let is_opus = path.extension().and_then(|e| e.to_str()) == Some("opus");
-                if !is_opus {
+                // files.  When extract_detection returns an .opus / .mp3
+                // path the clip was already processed (and its spectrogram
+                // created) in a previous run — re-generating would fail
+                // because generate_from_wav cannot read compressed audio.
+                let is_compressed = path.extension().and_then(|e| e.to_str()) != Some("wav");
+                if !is_compressed {
                     let spec_path = format!("{}.png", path.display());
                     let spec_params = SpectrogramParams {
                         colormap: config.colormap.parse::<Colormap>().unwrap_or_default(),
@@ -113,25 +163,61 @@ fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) ->
                     ) {
                         warn!("Spectrogram failed for {}: {e}", path.display());
                     }
+                    let peaks_path = waveform::sidecar_path(&path);
+                    if let Err(e) = waveform::write_from_wav(&path, &peaks_path) {
+                        warn!("Waveform peaks failed for {}: {e:#}", path.display());
+                    }
+                    // Peak frequency / bandwidth / call duration are read
+                    // from the uncompressed clip at its native rate.
+                    match spectrogram::call_features_from_wav(&path) {
+                        Ok(Some(f)) => {
+                            detection.peak_freq_hz = Some(f.peak_freq_hz);
+                            detection.bandwidth_hz = Some(f.bandwidth_hz);
+                            detection.call_duration_secs = Some(f.duration_secs);
+                        }
+                        Ok(None) => {}
+                        Err(e) => debug!("Call features failed for {}: {e}", path.display()),
+                    }
+                    if payload.ultrasonic_slugs.contains(&detection.model_slug) {
+                        write_time_expanded(&path, config, &encoding);
+                    }
                 } else {
                     debug!("Skipping spectrogram for already-compressed {}", path.display());
                 }
-                // Convert WAV clip → Opus immediately.  Falls back to
-                // the original WAV path if ffmpeg is unavailable.
-                let final_path = if is_opus {
+                // Transcode the WAV clip to CLIP_FORMAT immediately.  Falls
+                // back to the WAV path for CLIP_FORMAT=wav or if ffmpeg is
+                // unavailable.
+                let final_path = if is_compressed {
                     path
                 } else {
-                    crate::compress::compress_inline(&path).unwrap_or(path)
+                    crate::compress::compress_inline(&path, &encoding).unwrap_or(path)
                 };
                 Some(final_path)
             }
-            Err(e) => {
+            Some(Err(e)) => {
                 warn!("Clip extraction failed (detection will still be recorded): {e:#}");
                 None
             }
         };
 
-        let summary = format_summary(detection, config);
+        detection.annotate_sky(config.latitude);
+        let checklist = rarity::global(&config.rarity_checklist);
+        detection.rarity = checklist.rarity(
+            &detection.scientific_name,
+            &detection.domain,
+            detection.datetime.date_naive(),
+        );
+        detection.rare = detection.rarity.is_some_and(|r| r >= config.rarity_threshold);
+        if detection.rare && !detection.excluded {
+            info!(
+                "Rare species: {} {} (rarity {:.3})",
+                detection.common_name,
+                detection.scientific_name,
+                detection.rarity.unwrap_or_default()
+            );
+        }
+
+        let summary = format_summary(&detection, config);
         let basename = extracted
             .as_ref()
             .and_then(|p| p.file_name())
@@ -155,7 +241,7 @@ fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) ->
         write_to_log(&summary, &config.recs_dir);
 
         if let Err(e) = parquet_store::write_detection(
-            detection,
+            &detection,
             config.latitude,
             config.longitude,
             config.confidence,
@@ -163,9 +249,16 @@ fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) ->
             config.overlap,
             &basename,
             &payload.source_node,
+            &payload.source_node_id,
         ) {
             error!("Parquet insert failed: {e}");
         }
+        // Excluded or muted, a detection still shows the microphone works.
+        watchdog::record(&detection, &payload.source_node);
+
+        if !detection.excluded && !verdict.mute {
+            kv::queue_push_event(&detection, &payload.source_node);
+        }
     }
 
     // ── urban noise detections ───────────────────────────────────────
@@ -176,10 +269,10 @@ fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) ->
         let is_human = detection.scientific_name.contains("Human");
 
         if !is_human {
-            match extract_detection(file, detection, config) {
+            match extract_detection(file, detection, pre_roll, config) {
                 Ok(path) => {
-                    // Convert noise clip to Opus inline as well.
-                    crate::compress::compress_inline(&path);
+                    // Transcode noise clips inline as well.
+                    crate::compress::compress_inline(&path, &encoding);
                 }
                 Err(e) => {
                     warn!("Noise clip extraction failed: {e}");
@@ -215,40 +308,45 @@ fn process_report(payload: &ReportPayload, config: &Config, _db_path: &Path) ->
     }
 
     if config.birdweather_id.is_some() {
-        if let Err(e) = bird_weather(file, &payload.detections, config) {
+        // Detections dropped by a rule are not forwarded either.
+        let forwarded: Vec<Detection> = payload
+            .detections
+            .iter()
+            .filter(|d| !dropped.iter().any(|x| std::ptr::eq(*x, *d)))
+            .cloned()
+            .collect();
+        if let Err(e) = bird_weather(file, &forwarded, config) {
             error!("BirdWeather error: {e}");
         }
     }
 
-    heartbeat(config);
     Ok(())
 }
 
 // ── audio clip extraction ────────────────────────────────────────────────
 
+/// Seconds of audio added on each side of a detection's chunk in its clip.
+fn clip_padding(config: &Config) -> f64 {
+    (config.extraction_length as f64 - 3.0).max(0.0) / 2.0
+}
+
+/// Extract the clip of `detection`.  A clip that would start before the
+/// segment reaches back into `pre_roll`, the end of the previous segment
+/// of the same stream, when reporting still holds it.
 fn extract_detection(
     file: &ParsedFileName,
     detection: &Detection,
+    pre_roll: Option<&audio::PreRoll>,
     config: &Config,
 ) -> Result<PathBuf> {
-    let spacer = (config.extraction_length as f64 - 3.0).max(0.0) / 2.0;
-    let safe_start = (detection.start - spacer).max(0.0);
+    let spacer = clip_padding(config);
+    let lead_in = pre_roll.map_or(0.0, |p| p.duration_secs());
+    let safe_start = (detection.start - spacer).max(-lead_in);
     let safe_stop = (detection.stop + spacer).min(config.recording_length as f64);
 
-    let model_tag = if detection.model_slug.is_empty() {
-        "unknown"
-    } else {
-        &detection.model_slug
-    };
     let new_name = format!(
-        "{}-{}-{}-{}-{}-{}{}.wav",
-        detection.domain,
-        detection.common_name_safe,
-        detection.confidence_pct(),
-        detection.date,
-        model_tag,
-        file.rtsp_id,
-        detection.time,
+        "{}.wav",
+        render_clip_name(&config.clip_name_template, file, detection)
     );
     let new_dir = config
         .extracted_dir
@@ -257,22 +355,32 @@ fn extract_detection(
         .join(&detection.common_name_safe);
     let new_path = new_dir.join(&new_name);
 
+    // Check whether a transcoded version already exists (from a previous
+    // run).  If so, return it directly — no need to re-extract and
+    // re-encode.  Checked before the WAV, which KEEP_WAV_CLIPS retains.
+    for ext in ["opus", "mp3"] {
+        let encoded = new_path.with_extension(ext);
+        if encoded.exists() {
+            debug!("Extraction already exists ({ext}): {}", encoded.display());
+            return Ok(encoded);
+        }
+    }
+
     if new_path.exists() {
         debug!("Extraction already exists (WAV): {}", new_path.display());
         return Ok(new_path);
     }
 
-    // Check whether an Opus-compressed version already exists (from a
-    // previous inline compression).  If so, return the Opus path
-    // directly — no need to re-extract and re-compress.
-    let opus_name = format!("{}.opus", &new_name[..new_name.len() - 4]);
-    let opus_path = new_dir.join(&opus_name);
-    if opus_path.exists() {
-        debug!("Extraction already exists (Opus): {}", opus_path.display());
-        return Ok(opus_path);
+    match pre_roll {
+        Some(pre_roll) if safe_start < 0.0 => audio::extract_clip_with_pre_roll(
+            pre_roll,
+            &file.file_path,
+            &new_path,
+            safe_start,
+            safe_stop,
+        )?,
+        _ => audio::extract_clip(&file.file_path, &new_path, safe_start, safe_stop)?,
     }
-
-    audio::extract_clip(&file.file_path, &new_path, safe_start, safe_stop)?;
     debug!(
         "Extracted clip {:.1}s–{:.1}s from {} → {}",
         safe_start, safe_stop,
@@ -282,6 +390,60 @@ fn extract_detection(
     Ok(new_path)
 }
 
+/// Write the audible, time-expanded companion of an ultrasonic clip (see
+/// [`audio::time_expanded_path`]) and transcode it like the clip itself.
+/// Failures are logged only; the original clip is what gets stored.
+fn write_time_expanded(clip: &Path, config: &Config, encoding: &ClipEncoding) {
+    if config.time_expansion <= 1 {
+        return;
+    }
+    let out = audio::time_expanded_path(clip);
+    let highpass = (config.time_expansion_highpass_hz > 0.0)
+        .then_some(config.time_expansion_highpass_hz);
+    match audio::time_expand_clip(clip, &out, config.time_expansion, highpass) {
+        Ok(()) => {
+            debug!("Time-expanded ×{} → {}", config.time_expansion, out.display());
+            crate::compress::compress_inline(&out, encoding);
+        }
+        Err(e) => warn!("Time expansion failed for {}: {e:#}", clip.display()),
+    }
+}
+
+/// Expand a clip filename template (see `Config::clip_name_template`).
+///
+/// Every substituted value goes through `safe_path_component` and the
+/// whole stem through `safe_file_stem`, so neither species names in any
+/// script nor an operator-supplied template can produce path separators,
+/// `:` or other characters that break URLs, SMB shares or FAT-formatted
+/// USB drives.  `{stream}` expands to `RTSP_1-` / `UDP_1-` (separator
+/// included) or to nothing for the local microphone.
+fn render_clip_name(template: &str, file: &ParsedFileName, detection: &Detection) -> String {
+    let model = if detection.model_slug.is_empty() {
+        "unknown"
+    } else {
+        &detection.model_slug
+    };
+    let stream = if file.rtsp_id.is_empty() {
+        String::new()
+    } else {
+        safe_path_component(&file.rtsp_id)
+    };
+    let confidence = detection.confidence_pct().to_string();
+    let mut name = template.replace("{stream}", &stream);
+    for (key, value) in [
+        ("{domain}", detection.domain.as_str()),
+        ("{common_name}", detection.common_name_safe.as_str()),
+        ("{scientific_name}", detection.scientific_name.as_str()),
+        ("{confidence}", confidence.as_str()),
+        ("{date}", detection.date.as_str()),
+        ("{time}", detection.time.as_str()),
+        ("{model}", model),
+    ] {
+        name = name.replace(key, &safe_path_component(value));
+    }
+    safe_file_stem(&name)
+}
+
 // ── summary / logging ────────────────────────────────────────────────────
 
 fn format_summary(d: &Detection, config: &Config) -> String {
@@ -453,13 +615,45 @@ fn bird_weather(
     Ok(())
 }
 
-// ── heartbeat ────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use gaia_common::config::DEFAULT_CLIP_NAME_TEMPLATE;
+
+    fn sample(common_name: &str, rtsp_id: &str) -> (ParsedFileName, Detection) {
+        let file_date = NaiveDate::from_ymd_opt(2024, 6, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap();
+        let file = ParsedFileName {
+            file_path: PathBuf::from("/tmp/rec.wav"),
+            file_date,
+            rtsp_id: rtsp_id.to_string(),
+            capture_node: None,
+        };
+        let mut det = Detection::new("birds", file_date, 0.0, 3.0, "Parus major", common_name, 0.87);
+        det.model_slug = "birdnet".into();
+        (file, det)
+    }
 
-fn heartbeat(config: &Config) {
-    if let Some(url) = &config.heartbeat_url {
-        match reqwest::blocking::get(url) {
-            Ok(r) => info!("Heartbeat: {}", r.status()),
-            Err(e) => error!("Heartbeat failed: {e}"),
-        }
+    #[test]
+    fn test_default_template_layout() {
+        let (file, det) = sample("Great Tit", "RTSP_1-");
+        let name = render_clip_name(DEFAULT_CLIP_NAME_TEMPLATE, &file, &det);
+        assert_eq!(name, format!("birds-Great_Tit-87-{}-birdnet-RTSP_1-{}", det.date, det.time.replace(':', "_")));
+
+        let (file, det) = sample("Great Tit", "");
+        let name = render_clip_name(DEFAULT_CLIP_NAME_TEMPLATE, &file, &det);
+        assert!(name.contains("-birdnet-") && !name.contains("RTSP"));
+    }
+
+    #[test]
+    fn test_template_sanitises_values_and_literals() {
+        let (file, det) = sample("Mésange charbonnière / 大山雀", "");
+        let name = render_clip_name("{date} {common_name}: {confidence}", &file, &det);
+        assert!(!name.contains('/') && !name.contains(':') && !name.contains(' '));
+        assert!(name.contains("Mésange_charbonnière_大山雀"));
+        assert!(name.ends_with("_87"));
     }
 }
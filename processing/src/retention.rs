@@ -0,0 +1,583 @@
+//! Scheduled pruning of extracted clips.
+//!
+//! A busy garden station extracts hundreds of blackbird clips a day, but
+//! every one of a rare visitor is worth keeping.  Policies live in a TOML
+//! file (`RETENTION_FILE`, default `/data/retention.toml`) and are
+//! applied once a day:
+//!
+//! ```toml
+//! # Rare species keep every clip (no limits).
+//! [[policy]]
+//! name = "Keep rarities"
+//! rare = true
+//!
+//! # Bats: 5 clips per species and day, nothing older than a year.
+//! [[policy]]
+//! domain = "bats"
+//! best_per_day = 5
+//! max_age_days = 365
+//!
+//! # Everything else: the 3 most confident clips per species and day.
+//! [[policy]]
+//! name = "Common species"
+//! best_per_day = 3
+//! ```
+//!
+//! Conditions (all must hold; none matches every detection):
+//!
+//! * `species` — scientific or common names (case-insensitive);
+//! * `domain` — model domain, e.g. `birds`;
+//! * `rare` — whether the detection was flagged rare when stored.
+//!
+//! Limits: `best_per_day` keeps the N highest-confidence clips per
+//! species and day, `max_age_days` removes clips older than that.  A
+//! policy without limits keeps everything it matches.  Each detection
+//! follows the *first* matching policy, so put exceptions first;
+//! detections matching none are left alone.
+//!
+//! Pruning removes the clip with its transcoded siblings, spectrogram
+//! and time-expanded copy.  The detection itself stays (the web UI just
+//! has no audio for it) unless the policy sets `delete_rows = true`, in
+//! which case the Parquet batches holding it are rewritten without it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use tracing::{error, info, warn};
+
+use gaia_common::config::Config;
+use gaia_common::detection::normalize_sci_name;
+
+/// Time between retention runs.
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Delay after start-up before the first run, so it doesn't compete with
+/// model loading.
+const STARTUP_DELAY: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Deserialize)]
+struct RetentionFile {
+    #[serde(default)]
+    policy: Vec<Policy>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Policy {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    species: Vec<String>,
+    domain: Option<String>,
+    rare: Option<bool>,
+    best_per_day: Option<usize>,
+    max_age_days: Option<u32>,
+    #[serde(default)]
+    delete_rows: bool,
+}
+
+/// A policy with its species names normalised.
+#[derive(Debug)]
+struct CompiledPolicy {
+    name: String,
+    /// Normalised scientific names and lowercased common names.
+    species: Vec<String>,
+    domain: Option<String>,
+    rare: Option<bool>,
+    best_per_day: Option<usize>,
+    max_age_days: Option<u32>,
+    delete_rows: bool,
+}
+
+impl CompiledPolicy {
+    fn matches(&self, clip: &StoredClip) -> bool {
+        if !self.species.is_empty()
+            && !self.species.contains(&normalize_sci_name(&clip.sci_name))
+            && !self.species.contains(&clip.com_name.trim().to_lowercase())
+        {
+            return false;
+        }
+        if self
+            .domain
+            .as_deref()
+            .is_some_and(|d| !d.eq_ignore_ascii_case(&clip.domain))
+        {
+            return false;
+        }
+        self.rare.is_none_or(|rare| rare == clip.rare)
+    }
+}
+
+/// The loaded policies.  Empty (nothing is pruned) without a file.
+#[derive(Debug, Default)]
+pub struct RetentionPolicies {
+    policies: Vec<CompiledPolicy>,
+}
+
+impl RetentionPolicies {
+    /// Load from `path`.  A missing file means no pruning; an invalid one
+    /// is logged and ignored.
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => return Self::default(),
+        };
+        match Self::parse(&text) {
+            Ok(set) => set,
+            Err(e) => {
+                warn!("Ignoring retention policies in {}: {e:#}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: RetentionFile = toml::from_str(text).context("Invalid retention file")?;
+        let policies = file
+            .policy
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let name = if p.name.is_empty() {
+                    format!("policy #{}", i + 1)
+                } else {
+                    p.name
+                };
+                if p.delete_rows && p.best_per_day.is_none() && p.max_age_days.is_none() {
+                    anyhow::bail!("In {name}: `delete_rows` needs `best_per_day` or `max_age_days`");
+                }
+                Ok(CompiledPolicy {
+                    name,
+                    species: p
+                        .species
+                        .iter()
+                        .flat_map(|s| [normalize_sci_name(s), s.trim().to_lowercase()])
+                        .collect(),
+                    domain: p.domain,
+                    rare: p.rare,
+                    best_per_day: p.best_per_day,
+                    max_age_days: p.max_age_days,
+                    delete_rows: p.delete_rows,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { policies })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// Decide which of `clips` to prune as of `today`.  Within each
+    /// policy, species and day the clips are ranked by confidence, so
+    /// `best_per_day` keeps the most confident ones.
+    fn plan<'a>(&'a self, clips: &'a [StoredClip], today: NaiveDate) -> Vec<Prune<'a>> {
+        let mut groups: BTreeMap<(usize, String, &str), Vec<&StoredClip>> = BTreeMap::new();
+        for clip in clips {
+            if let Some(idx) = self.policies.iter().position(|p| p.matches(clip)) {
+                groups
+                    .entry((idx, normalize_sci_name(&clip.sci_name), clip.date.as_str()))
+                    .or_default()
+                    .push(clip);
+            }
+        }
+
+        let mut prune = Vec::new();
+        for ((idx, _, date), mut group) in groups {
+            let policy = &self.policies[idx];
+            let expired = policy.max_age_days.is_some_and(|days| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .is_ok_and(|d| (today - d).num_days() > i64::from(days))
+            });
+            let keep = if expired {
+                0
+            } else {
+                policy.best_per_day.unwrap_or(usize::MAX)
+            };
+            if group.len() <= keep {
+                continue;
+            }
+            group.sort_by(|a, b| {
+                b.confidence
+                    .total_cmp(&a.confidence)
+                    .then_with(|| a.file_name.cmp(&b.file_name))
+            });
+            prune.extend(group.into_iter().skip(keep).map(|clip| Prune {
+                clip,
+                policy: &policy.name,
+                delete_row: policy.delete_rows,
+            }));
+        }
+        prune
+    }
+}
+
+/// A stored detection that has a clip.
+#[derive(Debug, Clone)]
+struct StoredClip {
+    /// Parquet batch holding the row.
+    batch: PathBuf,
+    id: i64,
+    date: String,
+    domain: String,
+    sci_name: String,
+    com_name: String,
+    confidence: f64,
+    file_name: String,
+    rare: bool,
+}
+
+/// A clip selected for removal.
+#[derive(Debug)]
+struct Prune<'a> {
+    clip: &'a StoredClip,
+    policy: &'a str,
+    delete_row: bool,
+}
+
+/// What one run removed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub clips: usize,
+    pub files: usize,
+    pub bytes: u64,
+    pub rows: usize,
+}
+
+fn sql_path(path: &Path) -> String {
+    path.display().to_string().replace('\'', "''")
+}
+
+/// Every detection with a clip, across all batches in `det_dir`.
+fn load_clips(det_dir: &Path) -> Result<Vec<StoredClip>> {
+    let mut files: Vec<String> = std::fs::read_dir(det_dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|x| x == "parquet"))
+                .map(|p| format!("'{}'", sql_path(&p)))
+                .collect()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    files.sort();
+
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+    conn.execute_batch(&format!(
+        "CREATE VIEW d AS SELECT * FROM read_parquet([{}], union_by_name=true, filename=true)",
+        files.join(", ")
+    ))
+    .context("Cannot read detection store")?;
+    // Batches written before rarity scoring have no Rare column.
+    let has_rare: u64 = conn.query_row(
+        "SELECT COUNT(*) FROM (DESCRIBE d) WHERE column_name = 'Rare'",
+        [],
+        |r| r.get(0),
+    )?;
+    let rare = if has_rare > 0 { "COALESCE(Rare, 0)" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT filename, id, Date, Domain, Sci_Name, Com_Name, Confidence, File_Name, {rare} \
+         FROM d WHERE File_Name <> ''"
+    ))?;
+    let clips = stmt
+        .query_map([], |row| {
+            Ok(StoredClip {
+                batch: PathBuf::from(row.get::<_, String>(0)?),
+                id: row.get(1)?,
+                date: row.get(2)?,
+                domain: row.get(3)?,
+                sci_name: row.get(4)?,
+                com_name: row.get(5)?,
+                confidence: row.get(6)?,
+                file_name: row.get(7)?,
+                rare: row.get::<_, i32>(8)? != 0,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(clips)
+}
+
+/// Remove a clip and everything derived from it: the transcoded
+/// siblings (`.opus`, `.mp3`, a kept `.wav`), their spectrograms
+/// (`<clip>.png`) and time-expanded copies (`<stem>.te.*`).  Returns the
+/// files and bytes removed.
+fn remove_clip(extracted_dir: &Path, date: &str, file_name: &str) -> (usize, u64) {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|s| format!("{}.", s.to_string_lossy()))
+        .unwrap_or_default();
+    if stem.len() <= 1 {
+        return (0, 0);
+    }
+    // The species directory is the sanitised common name at extraction
+    // time, which a relabel may since have changed — look in all of them.
+    let day = extracted_dir.join("By_Date").join(date);
+    let Ok(species_dirs) = std::fs::read_dir(&day) else {
+        return (0, 0);
+    };
+    let (mut files, mut bytes) = (0, 0);
+    for dir in species_dirs.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            let matches = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&stem));
+            if !matches {
+                continue;
+            }
+            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    files += 1;
+                    bytes += size;
+                }
+                Err(e) => warn!("Cannot remove {}: {e}", path.display()),
+            }
+        }
+    }
+    (files, bytes)
+}
+
+/// Rewrite `batch` without the rows in `ids`, deleting it when nothing
+/// is left.  Returns the number of rows removed.
+fn delete_rows(batch: &Path, ids: &[i64]) -> Result<usize> {
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open DuckDB")?;
+    conn.execute_batch(&format!(
+        "CREATE TABLE batch AS SELECT * FROM read_parquet('{}')",
+        sql_path(batch)
+    ))
+    .with_context(|| format!("Cannot read {}", batch.display()))?;
+    let id_list = ids.iter().map(i64::to_string).collect::<Vec<_>>().join(", ");
+    let removed = conn.execute(&format!("DELETE FROM batch WHERE id IN ({id_list})"), [])?;
+    if removed == 0 {
+        return Ok(0);
+    }
+    let left: u64 = conn.query_row("SELECT COUNT(*) FROM batch", [], |r| r.get(0))?;
+    if left == 0 {
+        drop(conn);
+        std::fs::remove_file(batch).with_context(|| format!("Cannot remove {}", batch.display()))?;
+        return Ok(removed);
+    }
+
+    let name = batch
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = batch.with_file_name(format!(".{name}.tmp"));
+    conn.execute_batch(&format!(
+        "COPY batch TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD)",
+        sql_path(&tmp)
+    ))
+    .with_context(|| format!("Cannot write {}", tmp.display()))?;
+    drop(conn);
+    std::fs::rename(&tmp, batch).with_context(|| format!("Cannot rename {}", tmp.display()))?;
+    Ok(removed)
+}
+
+/// Apply `policies` to the detection store in `det_dir` once.
+pub fn run_once(
+    policies: &RetentionPolicies,
+    extracted_dir: &Path,
+    det_dir: &Path,
+    today: NaiveDate,
+) -> Result<Summary> {
+    let clips = load_clips(det_dir)?;
+    let mut summary = Summary::default();
+    let mut rows_by_batch: HashMap<&Path, Vec<i64>> = HashMap::new();
+    let mut per_policy: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for prune in policies.plan(&clips, today) {
+        let clip = prune.clip;
+        let (files, bytes) = remove_clip(extracted_dir, &clip.date, &clip.file_name);
+        if files > 0 {
+            summary.clips += 1;
+            summary.files += files;
+            summary.bytes += bytes;
+            *per_policy.entry(prune.policy).or_default() += 1;
+        }
+        if prune.delete_row {
+            rows_by_batch.entry(&clip.batch).or_default().push(clip.id);
+        }
+    }
+
+    for (batch, ids) in rows_by_batch {
+        match delete_rows(batch, &ids) {
+            Ok(n) => summary.rows += n,
+            Err(e) => error!("Retention: cannot delete rows from {}: {e:#}", batch.display()),
+        }
+    }
+    for (policy, n) in per_policy {
+        info!("Retention \"{policy}\": pruned {n} clip(s)");
+    }
+    Ok(summary)
+}
+
+/// Background loop run by the processing server: applies the policies
+/// in `config.retention_file` once a day.  The file is re-read on every
+/// run, so edits apply without a restart.
+pub fn retention_loop(config: Config, det_dir: PathBuf, shutdown: &AtomicBool) {
+    if !config.retention_file.exists() {
+        info!(
+            "No clip retention policies at {} — clips are kept",
+            config.retention_file.display()
+        );
+        return;
+    }
+    info!("Retention thread started ({})", config.retention_file.display());
+
+    let mut next_run = Instant::now() + STARTUP_DELAY;
+    while !shutdown.load(Ordering::Relaxed) {
+        if Instant::now() >= next_run {
+            next_run = Instant::now() + RUN_INTERVAL;
+            let policies = RetentionPolicies::load(&config.retention_file);
+            if policies.is_empty() {
+                continue;
+            }
+            let today = chrono::Local::now().date_naive();
+            match run_once(&policies, &config.extracted_dir, &det_dir, today) {
+                Ok(s) if s.clips > 0 || s.rows > 0 => info!(
+                    "Retention removed {} clip(s) ({} files, {:.1} MB) and {} detection row(s)",
+                    s.clips,
+                    s.files,
+                    s.bytes as f64 / 1_048_576.0,
+                    s.rows
+                ),
+                Ok(_) => info!("Retention: nothing to prune"),
+                Err(e) => error!("Retention run failed: {e:#}"),
+            }
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    info!("Retention thread stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(sci: &str, date: &str, confidence: f64, rare: bool) -> StoredClip {
+        StoredClip {
+            batch: PathBuf::from("batch.parquet"),
+            id: 0,
+            date: date.into(),
+            domain: "birds".into(),
+            sci_name: sci.into(),
+            com_name: String::new(),
+            confidence,
+            file_name: format!("{sci}-{date}-{confidence}.opus"),
+            rare,
+        }
+    }
+
+    fn pruned(policies: &RetentionPolicies, clips: &[StoredClip], today: &str) -> Vec<String> {
+        let today = NaiveDate::parse_from_str(today, "%Y-%m-%d").unwrap();
+        let mut names: Vec<String> = policies
+            .plan(clips, today)
+            .iter()
+            .map(|p| p.clip.file_name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_best_per_day_keeps_most_confident() {
+        let policies = RetentionPolicies::parse(
+            "[[policy]]\nrare = true\n\n[[policy]]\nbest_per_day = 2\n",
+        )
+        .unwrap();
+        let clips = vec![
+            clip("Turdus merula", "2024-05-01", 0.9, false),
+            clip("Turdus merula", "2024-05-01", 0.7, false),
+            clip("Turdus merula", "2024-05-01", 0.8, false),
+            clip("Turdus merula", "2024-05-01", 0.6, false),
+            // Another day is ranked on its own.
+            clip("Turdus merula", "2024-05-02", 0.5, false),
+            // Rare species match the first policy and keep everything.
+            clip("Upupa epops", "2024-05-01", 0.3, true),
+            clip("Upupa epops", "2024-05-01", 0.2, true),
+            clip("Upupa epops", "2024-05-01", 0.1, true),
+        ];
+        assert_eq!(
+            pruned(&policies, &clips, "2024-05-03"),
+            ["Turdus merula-2024-05-01-0.6.opus", "Turdus merula-2024-05-01-0.7.opus"]
+        );
+    }
+
+    #[test]
+    fn test_conditions_and_max_age() {
+        let policies = RetentionPolicies::parse(
+            r#"
+            [[policy]]
+            species = ["upupa EPOPS"]
+            max_age_days = 30
+            delete_rows = true
+
+            [[policy]]
+            domain = "bats"
+            best_per_day = 1
+            "#,
+        )
+        .unwrap();
+        let mut bat = clip("Pipistrellus pipistrellus", "2024-05-01", 0.5, false);
+        bat.domain = "Bats".into();
+        let clips = vec![
+            clip("Upupa epops", "2024-03-01", 0.9, false),
+            clip("Upupa epops", "2024-04-20", 0.9, false),
+            // Matches no policy.
+            clip("Turdus merula", "2023-01-01", 0.1, false),
+            bat.clone(),
+            StoredClip { confidence: 0.4, file_name: "bat-low.opus".into(), ..bat },
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let plan = policies.plan(&clips, today);
+        assert!(plan
+            .iter()
+            .any(|p| p.clip.file_name == "Upupa epops-2024-03-01-0.9.opus" && p.delete_row));
+        assert_eq!(
+            pruned(&policies, &clips, "2024-05-01"),
+            ["Upupa epops-2024-03-01-0.9.opus", "bat-low.opus"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_policies() {
+        assert!(RetentionPolicies::parse("[[policy]]\nkeep = 3\n").is_err());
+        assert!(RetentionPolicies::parse("[[policy]]\ndelete_rows = true\n").is_err());
+        assert!(RetentionPolicies::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_remove_clip_and_companions() {
+        let root = std::env::temp_dir().join(format!("gaia-retention-test-{}", std::process::id()));
+        let dir = root.join("By_Date/2024-05-01/Blackbird");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "Blackbird-80-2024-05-01.opus",
+            "Blackbird-80-2024-05-01.opus.png",
+            "Blackbird-80-2024-05-01.wav",
+            "Blackbird-80-2024-05-01.te.opus",
+            "Blackbird-81-2024-05-01.opus",
+        ] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+
+        let (files, bytes) = remove_clip(&root, "2024-05-01", "Blackbird-80-2024-05-01.wav");
+        assert_eq!((files, bytes), (4, 4));
+        assert!(dir.join("Blackbird-81-2024-05-01.opus").exists());
+        assert_eq!(remove_clip(&root, "2024-05-02", "x.wav"), (0, 0));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,361 @@
+//! Post-processing rules applied by reporting before a detection is
+//! stored or announced.
+//!
+//! Rules live in a TOML file (`RULES_FILE`, default `/data/rules.toml`).
+//! Each `[[rule]]` lists conditions, all of which must hold, and an
+//! action:
+//!
+//! ```toml
+//! # Early "cuckoos" are misidentified hawks.
+//! [[rule]]
+//! name = "Cuckoo only from April"
+//! species = ["Cuculus canorus"]
+//! before = "04-01"
+//! action = "drop"
+//!
+//! # Nightjars don't call during the day.
+//! [[rule]]
+//! name = "Nightjar is night-only"
+//! species = ["Caprimulgus europaeus"]
+//! hours = "06-20"
+//! action = "exclude"
+//!
+//! # Only notify once a species has been heard 3 times in 10 minutes.
+//! [[rule]]
+//! name = "Confirm before notifying"
+//! domain = "birds"
+//! fewer_than = 3
+//! within_minutes = 10
+//! action = "mute"
+//! ```
+//!
+//! Conditions:
+//!
+//! * `species` — scientific or common names (case-insensitive);
+//! * `domain` — model domain, e.g. `birds`;
+//! * `after` / `before` — `MM-DD` dates, `after` inclusive and `before`
+//!   exclusive; `after = "11-01"` with `before = "03-01"` spans New Year;
+//! * `hours` — `HH-HH` local hours, start inclusive and end exclusive,
+//!   wrapping past midnight (`"20-06"` is the night);
+//! * `below_confidence` — confidence under this value (0.0 – 1.0);
+//! * `fewer_than` + `within_minutes` — fewer than N detections of the
+//!   species (this one included) in the last M minutes.
+//!
+//! Actions: `drop` (not stored at all), `exclude` (stored but flagged
+//! excluded, like out-of-range species, and not announced) and `mute`
+//! (stored, not announced).  When several rules match, every action
+//! applies.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Local, Timelike};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use gaia_common::detection::{normalize_sci_name, Detection};
+
+/// What happens to a detection matched by a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Drop,
+    Exclude,
+    Mute,
+}
+
+/// The combined outcome of every rule matching a detection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Verdict {
+    pub drop: bool,
+    pub exclude: bool,
+    pub mute: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    species: Vec<String>,
+    domain: Option<String>,
+    after: Option<String>,
+    before: Option<String>,
+    hours: Option<String>,
+    below_confidence: Option<f64>,
+    fewer_than: Option<usize>,
+    within_minutes: Option<i64>,
+    action: Action,
+}
+
+/// A rule with its conditions parsed, plus the recent detections its
+/// `fewer_than` condition counts.
+struct CompiledRule {
+    name: String,
+    /// Normalised scientific names and lowercased common names.
+    species: Vec<String>,
+    domain: Option<String>,
+    /// `(month, day)` bounds.
+    after: Option<(u32, u32)>,
+    before: Option<(u32, u32)>,
+    hours: Option<(u32, u32)>,
+    below_confidence: Option<f64>,
+    burst: Option<(usize, chrono::Duration)>,
+    action: Action,
+    recent: HashMap<String, VecDeque<DateTime<Local>>>,
+}
+
+/// The loaded rules.  Empty (every detection passes) without a file.
+#[derive(Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    /// Load from `path`.  A missing file means no rules; an invalid one
+    /// is logged and ignored rather than stopping reporting.
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => {
+                info!("No detection rules at {}", path.display());
+                return Self::default();
+            }
+        };
+        match Self::parse(&text) {
+            Ok(set) => {
+                info!("Loaded {} detection rule(s) from {}", set.rules.len(), path.display());
+                set
+            }
+            Err(e) => {
+                warn!("Ignoring detection rules in {}: {e:#}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: RulesFile = toml::from_str(text).context("Invalid rules file")?;
+        let rules = file
+            .rule
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let label = if rule.name.is_empty() {
+                    format!("rule #{}", i + 1)
+                } else {
+                    rule.name.clone()
+                };
+                CompiledRule::compile(rule, label.clone()).with_context(|| format!("In {label}"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Run every rule against `detection`.
+    pub fn apply(&mut self, detection: &Detection) -> Verdict {
+        let mut verdict = Verdict::default();
+        for rule in &mut self.rules {
+            if !rule.matches(detection) {
+                continue;
+            }
+            info!(
+                "Rule \"{}\" ({:?}): {} {} at {}",
+                rule.name, rule.action, detection.common_name, detection.scientific_name, detection.iso8601
+            );
+            match rule.action {
+                Action::Drop => verdict.drop = true,
+                Action::Exclude => verdict.exclude = true,
+                Action::Mute => verdict.mute = true,
+            }
+        }
+        verdict
+    }
+}
+
+impl CompiledRule {
+    fn compile(rule: Rule, name: String) -> Result<Self> {
+        let burst = match (rule.fewer_than, rule.within_minutes) {
+            (Some(n), Some(m)) if m > 0 => Some((n, chrono::Duration::minutes(m))),
+            (None, None) => None,
+            _ => anyhow::bail!("`fewer_than` needs a positive `within_minutes`"),
+        };
+        Ok(Self {
+            name,
+            species: rule
+                .species
+                .iter()
+                .flat_map(|s| [normalize_sci_name(s), s.trim().to_lowercase()])
+                .collect(),
+            domain: rule.domain,
+            after: rule.after.as_deref().map(parse_month_day).transpose()?,
+            before: rule.before.as_deref().map(parse_month_day).transpose()?,
+            hours: rule.hours.as_deref().map(parse_hours).transpose()?,
+            below_confidence: rule.below_confidence,
+            burst,
+            action: rule.action,
+            recent: HashMap::new(),
+        })
+    }
+
+    fn matches(&mut self, d: &Detection) -> bool {
+        if !self.species.is_empty()
+            && !self.species.contains(&normalize_sci_name(&d.scientific_name))
+            && !self.species.contains(&d.common_name.to_lowercase())
+        {
+            return false;
+        }
+        if self.domain.as_ref().is_some_and(|dom| !dom.eq_ignore_ascii_case(&d.domain)) {
+            return false;
+        }
+        if !in_date_window((d.datetime.month(), d.datetime.day()), self.after, self.before) {
+            return false;
+        }
+        if let Some((start, end)) = self.hours {
+            let hour = d.datetime.hour();
+            let inside = if start <= end {
+                (start..end).contains(&hour)
+            } else {
+                hour >= start || hour < end
+            };
+            if !inside {
+                return false;
+            }
+        }
+        if self.below_confidence.is_some_and(|c| d.confidence >= c) {
+            return false;
+        }
+        // Counted last, so only detections meeting the other conditions
+        // add to the window.
+        if let Some((fewer_than, window)) = self.burst {
+            let seen = self
+                .recent
+                .entry(normalize_sci_name(&d.scientific_name))
+                .or_default();
+            seen.push_back(d.datetime);
+            while seen.front().is_some_and(|&t| d.datetime - t > window) {
+                seen.pop_front();
+            }
+            if seen.len() >= fewer_than {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Whether `(month, day)` is inside `[after, before)`, wrapping past the
+/// end of the year when `after` is later than `before`.
+fn in_date_window(day: (u32, u32), after: Option<(u32, u32)>, before: Option<(u32, u32)>) -> bool {
+    match (after, before) {
+        (Some(a), Some(b)) if a > b => day >= a || day < b,
+        (a, b) => a.is_none_or(|a| day >= a) && b.is_none_or(|b| day < b),
+    }
+}
+
+fn parse_month_day(s: &str) -> Result<(u32, u32)> {
+    let (m, d) = s.trim().split_once('-').context("expected MM-DD")?;
+    let (m, d): (u32, u32) = (m.parse()?, d.parse()?);
+    anyhow::ensure!((1..=12).contains(&m) && (1..=31).contains(&d), "invalid date {s:?}");
+    Ok((m, d))
+}
+
+fn parse_hours(s: &str) -> Result<(u32, u32)> {
+    let (a, b) = s.trim().split_once('-').context("expected HH-HH")?;
+    let (a, b): (u32, u32) = (a.parse()?, b.parse()?);
+    anyhow::ensure!(a <= 24 && b <= 24, "invalid hours {s:?}");
+    Ok((a % 24, b % 24))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    fn detection(sci: &str, at: NaiveDateTime, confidence: f64) -> Detection {
+        Detection::new("birds", at, 0.0, 3.0, sci, "", confidence)
+    }
+
+    fn at(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rules_conditions_and_actions() {
+        let mut rules = RuleSet::parse(
+            r#"
+            [[rule]]
+            species = ["Cuculus canorus"]
+            before = "04-01"
+            action = "drop"
+
+            [[rule]]
+            name = "night only"
+            species = ["caprimulgus europaeus"]
+            hours = "06-20"
+            action = "exclude"
+
+            [[rule]]
+            name = "winter"
+            after = "11-01"
+            before = "03-01"
+            below_confidence = 0.8
+            action = "mute"
+            "#,
+        )
+        .unwrap();
+
+        let v = rules.apply(&detection("Cuculus canorus", at(3, 20, 9, 0), 0.9));
+        assert_eq!(v, Verdict { drop: true, ..Verdict::default() });
+        assert_eq!(rules.apply(&detection("Cuculus canorus", at(4, 2, 9, 0), 0.9)), Verdict::default());
+
+        let nightjar = |h| rules.apply(&detection("Caprimulgus europaeus", at(6, 1, h, 0), 0.9)).exclude;
+        assert!(nightjar(12));
+        assert!(!nightjar(22));
+        assert!(!nightjar(3));
+
+        assert!(rules.apply(&detection("Turdus merula", at(1, 10, 9, 0), 0.5)).mute);
+        assert!(!rules.apply(&detection("Turdus merula", at(1, 10, 9, 0), 0.9)).mute);
+        assert!(!rules.apply(&detection("Turdus merula", at(6, 10, 9, 0), 0.5)).mute);
+    }
+
+    #[test]
+    fn test_rules_burst() {
+        let mut rules = RuleSet::parse(
+            r#"
+            [[rule]]
+            fewer_than = 3
+            within_minutes = 10
+            action = "mute"
+            "#,
+        )
+        .unwrap();
+        let muted = |rules: &mut RuleSet, sci: &str, minute| rules.apply(&detection(sci, at(5, 1, 8, minute), 0.9)).mute;
+
+        assert!(muted(&mut rules, "Turdus merula", 0));
+        assert!(muted(&mut rules, "Turdus merula", 4));
+        assert!(muted(&mut rules, "Erithacus rubecula", 5));
+        assert!(!muted(&mut rules, "Turdus merula", 8));
+        // The first detection has left the window.
+        assert!(muted(&mut rules, "Turdus merula", 30));
+    }
+
+    #[test]
+    fn test_rules_invalid() {
+        assert!(RuleSet::parse("[[rule]]\naction = \"explode\"").is_err());
+        assert!(RuleSet::parse("[[rule]]\nbefore = \"13-01\"\naction = \"drop\"").is_err());
+        assert!(RuleSet::parse("[[rule]]\nfewer_than = 2\naction = \"drop\"").is_err());
+        assert!(RuleSet::parse("").unwrap().rules.is_empty());
+    }
+}
@@ -228,6 +228,7 @@ pub fn run(
             manifest.manifest.model.sample_rate,
             manifest.manifest.model.chunk_duration,
             0.0,  // no overlap for smoke test
+            &manifest.manifest.filter,
         )
         .with_context(|| {
             format!(
@@ -621,10 +622,12 @@ pub fn run_pipeline(
     let capture_discovery = gaia_common::discovery::register(
         gaia_common::discovery::ServiceRole::Capture,
         mock_port,
+        &[],
     );
     let processing_discovery = gaia_common::discovery::register(
         gaia_common::discovery::ServiceRole::Processing,
         0,
+        &[],
     );
 
     let capture_dh = match capture_discovery {
@@ -796,6 +799,8 @@ pub fn run_pipeline(
         &config,
         &report_tx,
         &capture_url,
+        &capture_url,
+        Some(config.recording_length as f64),
     )
     .context("analysis::process_file failed")?;
 
@@ -0,0 +1,308 @@
+//! Species reference table, written as `/data/species/<slug>.parquet`.
+//!
+//! One row per label of each loaded model: the scientific name as it is
+//! stored on detections, common names in every language the model ships,
+//! the domain and — when a taxonomy CSV is available — the eBird/IOC
+//! species code, taxonomic sort order, order and family.  gaia-web reads
+//! these files as a `species` view and joins detections against it, so
+//! names stay consistent across models and the dashboard can group by
+//! family.
+//!
+//! The taxonomy CSV is looked up at `GAIA_SPECIES_TAXONOMY`, then
+//! `/data/_taxonomy/species_taxonomy.csv`, then
+//! `/models/_taxonomy/species_taxonomy.csv`.  The eBird/Clements
+//! taxonomy CSV works unchanged; for other lists (e.g. IOC) any CSV with
+//! a header row, a scientific-name column and some of these columns will
+//! do:
+//!
+//! | Field        | Header names                                    |
+//! |--------------|-------------------------------------------------|
+//! | species code | `species_code`, `ebird_code`, `ioc_code`, `code` |
+//! | sort order   | `taxon_order`, `seq`                            |
+//! | order        | `order`, `order1`                               |
+//! | family       | `family_sci_name`, `family`                     |
+//! | family name  | `family_com_name`                               |
+//!
+//! A `family` value of the form `Turdidae (Thrushes and Allies)` is
+//! split into the scientific and common family names.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use duckdb::params;
+use gaia_common::detection::normalize_sci_name;
+use tracing::{debug, info, warn};
+
+use crate::model::{self, LoadedModel};
+use crate::taxonomy;
+
+/// Taxonomy details for one species.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaxonInfo {
+    pub species_code: Option<String>,
+    pub taxon_order: Option<i64>,
+    pub order: Option<String>,
+    pub family: Option<String>,
+    pub family_common: Option<String>,
+}
+
+/// Path of the taxonomy CSV, if one is present.
+fn taxonomy_csv_path() -> Option<PathBuf> {
+    if let Ok(p) = std::env::var("GAIA_SPECIES_TAXONOMY") {
+        if !p.trim().is_empty() {
+            return Some(PathBuf::from(p));
+        }
+    }
+    [
+        "/data/_taxonomy/species_taxonomy.csv",
+        "/models/_taxonomy/species_taxonomy.csv",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .find(|p| p.exists())
+}
+
+/// Parse a taxonomy CSV into `canonical sci_name → TaxonInfo`.
+///
+/// Rows without a scientific name are skipped; so are non-species rows
+/// of the eBird taxonomy (`CATEGORY` other than `species`), which would
+/// otherwise shadow the species entry for hybrids and slashes.
+pub fn parse_taxonomy_csv(text: &str) -> HashMap<String, TaxonInfo> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return HashMap::new();
+    };
+    let header: Vec<String> = model::split_csv_line(header)
+        .iter()
+        .map(|c| c.to_lowercase())
+        .collect();
+    let col = |names: &[&str]| names.iter().find_map(|n| header.iter().position(|h| h == n));
+    let Some(sci_col) = col(&["sci_name", "scientific_name"]) else {
+        return HashMap::new();
+    };
+    let code_col = col(&["species_code", "ebird_code", "ioc_code", "code"]);
+    let sort_col = col(&["taxon_order", "seq"]);
+    let order_col = col(&["order", "order1"]);
+    let family_col = col(&["family_sci_name", "family"]);
+    let family_com_col = col(&["family_com_name"]);
+    let category_col = col(&["category"]);
+
+    let mut out = HashMap::new();
+    for line in lines {
+        let cols = model::split_csv_line(line);
+        let get = |i: Option<usize>| {
+            i.and_then(|i| cols.get(i))
+                .filter(|v| !v.is_empty())
+                .cloned()
+        };
+        let Some(sci) = get(Some(sci_col)) else {
+            continue;
+        };
+        if get(category_col).is_some_and(|c| !c.eq_ignore_ascii_case("species")) {
+            continue;
+        }
+        let (family, mut family_common) = match get(family_col) {
+            Some(f) => match f.split_once(" (") {
+                Some((sci, com)) => (Some(sci.trim().to_string()), Some(com.trim_end_matches(')').to_string())),
+                None => (Some(f), None),
+            },
+            None => (None, None),
+        };
+        if let Some(com) = get(family_com_col) {
+            family_common = Some(com);
+        }
+        let info = TaxonInfo {
+            species_code: get(code_col),
+            taxon_order: get(sort_col).and_then(|v| v.parse::<f64>().ok()).map(|v| v as i64),
+            order: get(order_col),
+            family,
+            family_common,
+        };
+        out.insert(taxonomy::canonical_species_name(&normalize_sci_name(&sci)), info);
+    }
+    out
+}
+
+/// Load the taxonomy CSV, or an empty map when there is none.
+pub fn load_taxonomy() -> HashMap<String, TaxonInfo> {
+    let Some(path) = taxonomy_csv_path() else {
+        debug!("No species taxonomy CSV found — order/family will be empty");
+        return HashMap::new();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(text) => {
+            let map = parse_taxonomy_csv(&text);
+            info!("Loaded taxonomy for {} species from {}", map.len(), path.display());
+            map
+        }
+        Err(e) => {
+            warn!("Cannot read species taxonomy {}: {e}", path.display());
+            HashMap::new()
+        }
+    }
+}
+
+/// Common names from every `labels_<lang>.json` in `lang_dir`, as
+/// `canonical sci_name → (lang → name)`.
+fn language_names(lang_dir: &Path) -> HashMap<String, BTreeMap<String, String>> {
+    let mut out: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(lang_dir) else {
+        return out;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(lang) = name
+            .strip_prefix("labels_")
+            .and_then(|s| s.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        match model::load_language(lang_dir, lang) {
+            Ok(names) => {
+                for (sci, com) in names {
+                    let sci = taxonomy::canonical_species_name(&normalize_sci_name(&sci));
+                    out.entry(sci).or_default().insert(lang.to_string(), com);
+                }
+            }
+            Err(e) => warn!("Skipping language file {name}: {e:#}"),
+        }
+    }
+    out
+}
+
+/// Write the species reference file for every model into
+/// `<data_dir>/species/`.  Files of models no longer loaded are left in
+/// place; the web view de-duplicates by scientific name.
+pub fn write_all(models: &[LoadedModel], data_dir: &Path, lang: &str) -> Result<()> {
+    let dir = data_dir.join("species");
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Cannot create species dir: {}", dir.display()))?;
+    let taxa = load_taxonomy();
+    for m in models {
+        let slug = m.manifest.slug();
+        let n = write_model(m, &taxa, lang, &dir.join(format!("{slug}.parquet")))
+            .with_context(|| format!("Cannot write species table for {slug}"))?;
+        info!("Species reference: {n} species for {slug}");
+    }
+    Ok(())
+}
+
+/// Write one model's species rows; returns the row count.
+fn write_model(
+    m: &LoadedModel,
+    taxa: &HashMap<String, TaxonInfo>,
+    lang: &str,
+    path: &Path,
+) -> Result<usize> {
+    let names = language_names(&m.manifest.language_dir());
+    let conn = duckdb::Connection::open_in_memory().context("Cannot open in-memory DuckDB")?;
+    conn.execute_batch(
+        "CREATE TABLE species (
+            Sci_Name        VARCHAR NOT NULL,
+            Com_Name        VARCHAR,
+            Com_Names       VARCHAR NOT NULL,
+            Domain          VARCHAR NOT NULL,
+            Species_Code    VARCHAR,
+            Taxon_Order     BIGINT,
+            Order_Name      VARCHAR,
+            Family          VARCHAR,
+            Family_Com_Name VARCHAR,
+            Model_Slug      VARCHAR NOT NULL
+        );
+        BEGIN;",
+    )?;
+
+    let slug = m.manifest.slug();
+    let mut seen = std::collections::HashSet::new();
+    {
+        let mut stmt = conn.prepare(
+            "INSERT INTO species VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        for label in m.labels() {
+            let sci = taxonomy::canonical_species_name(&normalize_sci_name(label));
+            if sci.is_empty() || !seen.insert(sci.clone()) {
+                continue;
+            }
+            let com_names = names.get(&sci).cloned().unwrap_or_default();
+            let com = com_names
+                .get(lang)
+                .or_else(|| m.csv_common_names().get(label))
+                .or_else(|| m.csv_common_names().get(&sci))
+                .cloned();
+            // Same precedence as the Domain written on detections.
+            let domain = taxonomy::class_for_species(&sci)
+                .or_else(|| {
+                    m.csv_classes()
+                        .get(label)
+                        .map(|c| taxonomy::normalize_classification(c))
+                })
+                .unwrap_or_else(|| taxonomy::normalize_classification(m.domain()));
+            let taxon = taxa.get(&sci).cloned().unwrap_or_default();
+            stmt.execute(params![
+                sci,
+                com,
+                serde_json::to_string(&com_names)?,
+                domain,
+                taxon.species_code,
+                taxon.taxon_order,
+                taxon.order,
+                taxon.family,
+                taxon.family_common,
+                slug,
+            ])?;
+        }
+    }
+    conn.execute_batch("COMMIT")?;
+
+    let tmp = path.with_extension("parquet.tmp");
+    conn.execute(
+        &format!(
+            "COPY species TO '{}' (FORMAT PARQUET)",
+            tmp.display().to_string().replace('\'', "''")
+        ),
+        [],
+    )
+    .with_context(|| format!("Failed to write Parquet: {}", tmp.display()))?;
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to rename {} → {}", tmp.display(), path.display()))?;
+    Ok(seen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ebird_taxonomy() {
+        let csv = "\u{feff}TAXON_ORDER,CATEGORY,SPECIES_CODE,PRIMARY_COM_NAME,SCI_NAME,ORDER1,FAMILY\n\
+                   27001,species,eurbla,Eurasian Blackbird,Turdus merula,Passeriformes,\"Turdidae (Thrushes and Allies)\"\n\
+                   27002,hybrid,x00001,Blackbird x Song Thrush,Turdus merula x philomelos,Passeriformes,Turdidae (Thrushes and Allies)\n";
+        let map = parse_taxonomy_csv(csv);
+        assert_eq!(map.len(), 1);
+        let info = &map["Turdus merula"];
+        assert_eq!(info.species_code.as_deref(), Some("eurbla"));
+        assert_eq!(info.taxon_order, Some(27001));
+        assert_eq!(info.order.as_deref(), Some("Passeriformes"));
+        assert_eq!(info.family.as_deref(), Some("Turdidae"));
+        assert_eq!(info.family_common.as_deref(), Some("Thrushes and Allies"));
+    }
+
+    #[test]
+    fn test_parse_generic_taxonomy() {
+        let csv = "seq,scientific_name,ioc_code,order,family_sci_name,family_com_name\n\
+                   12.5,Erithacus_rubecula,ERIRUB,Passeriformes,Muscicapidae,Chats and Old World Flycatchers\n\
+                   13,,x,,,\n";
+        let map = parse_taxonomy_csv(csv);
+        assert_eq!(map.len(), 1);
+        let info = &map["Erithacus rubecula"];
+        assert_eq!(info.species_code.as_deref(), Some("ERIRUB"));
+        assert_eq!(info.taxon_order, Some(12));
+        assert_eq!(info.family.as_deref(), Some("Muscicapidae"));
+        assert_eq!(info.family_common.as_deref(), Some("Chats and Old World Flycatchers"));
+
+        assert!(parse_taxonomy_csv("code,name\nx,y\n").is_empty());
+        assert!(parse_taxonomy_csv("").is_empty());
+    }
+}
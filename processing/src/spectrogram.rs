@@ -1,13 +1,19 @@
 //! Spectrogram generation using FFT.
 //!
 //! Reused from `birdnet-server/src/spectrogram.rs`.
+//!
+//! Every extracted clip gets one, so FFT plans are shared across clips,
+//! frames are transformed in parallel and the magnitude matrix is
+//! resampled (not point-sampled) to the image size.
 
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use anyhow::{Context, Result};
-use image::{ImageBuffer, Rgb};
-use rustfft::{num_complex::Complex, FftPlanner};
+use image::{Rgb, RgbImage};
+use rayon::prelude::*;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
 use tracing::debug;
 
 /// Available colour palettes for spectrograms.
@@ -96,91 +102,7 @@ pub fn generate(
         out_path.display()
     );
 
-    let fft_size = params.fft_size;
-    let hop = params.hop_size;
-
-    let mut planner = FftPlanner::<f32>::new();
-    let fft = planner.plan_fft_forward(fft_size);
-
-    let n_frames = if samples.len() > fft_size {
-        (samples.len() - fft_size) / hop + 1
-    } else {
-        1
-    };
-
-    let n_bins = fft_size / 2 + 1;
-
-    let max_bin = if params.max_freq > 0.0 {
-        ((params.max_freq / sample_rate as f64) * fft_size as f64)
-            .ceil() as usize
-            + 1
-    } else {
-        n_bins
-    }
-    .min(n_bins);
-
-    let hann = hann_window(fft_size);
-    let mut magnitude = vec![vec![0.0f32; max_bin]; n_frames];
-
-    for (frame_idx, frame_start) in (0..samples.len().saturating_sub(fft_size))
-        .step_by(hop)
-        .enumerate()
-    {
-        if frame_idx >= n_frames {
-            break;
-        }
-        let mut buf: Vec<Complex<f32>> = samples[frame_start..frame_start + fft_size]
-            .iter()
-            .zip(hann.iter())
-            .map(|(&s, &w)| Complex::new(s * w, 0.0))
-            .collect();
-
-        fft.process(&mut buf);
-
-        for (bin, val) in buf.iter().take(max_bin).enumerate() {
-            let mag = (val.re * val.re + val.im * val.im).sqrt();
-            let db = 20.0 * (mag + 1e-10).log10();
-            magnitude[frame_idx][bin] = db;
-        }
-    }
-
-    // Normalise to 0..1
-    let global_min = magnitude
-        .iter()
-        .flat_map(|row| row.iter())
-        .cloned()
-        .fold(f32::INFINITY, f32::min);
-    let global_max = magnitude
-        .iter()
-        .flat_map(|row| row.iter())
-        .cloned()
-        .fold(f32::NEG_INFINITY, f32::max);
-    let range = (global_max - global_min).max(1e-6);
-
-    for row in &mut magnitude {
-        for val in row.iter_mut() {
-            *val = (*val - global_min) / range;
-        }
-    }
-
-    // Render to image
-    let img_w = params.width;
-    let img_h = params.height;
-    let mut img = ImageBuffer::<Rgb<u8>, _>::new(img_w, img_h);
-
-    for x in 0..img_w {
-        let src_frame = (x as f64 / img_w as f64 * n_frames as f64) as usize;
-        let src_frame = src_frame.min(n_frames.saturating_sub(1));
-
-        for y in 0..img_h {
-            let bin = ((img_h - 1 - y) as f64 / img_h as f64 * max_bin as f64) as usize;
-            let bin = bin.min(max_bin.saturating_sub(1));
-
-            let val = magnitude[src_frame][bin];
-            let pixel = apply_colormap(params.colormap, val);
-            img.put_pixel(x, y, pixel);
-        }
-    }
+    let img = render(samples, sample_rate, params);
 
     if let Some(parent) = out_path.parent() {
         std::fs::create_dir_all(parent)?;
@@ -205,78 +127,154 @@ pub fn generate_to_png_buffer(
     use image::ImageEncoder;
     use std::io::Cursor;
 
-    let fft_size = params.fft_size;
-    let hop = params.hop_size;
-
-    let mut planner = FftPlanner::<f32>::new();
-    let fft = planner.plan_fft_forward(fft_size);
-
-    let n_frames = if samples.len() > fft_size {
-        (samples.len() - fft_size) / hop + 1
-    } else {
-        1
-    };
+    let img = render(samples, sample_rate, params);
+    let mut cursor = Cursor::new(Vec::new());
+    PngEncoder::new(&mut cursor)
+        .write_image(
+            img.as_raw(),
+            img.width(),
+            img.height(),
+            image::ExtendedColorType::Rgb8,
+        )
+        .context("PNG encode")?;
+    Ok(cursor.into_inner())
+}
 
-    let n_bins = fft_size / 2 + 1;
+/// Compute the magnitude matrix and resample it to the image size.
+fn render(samples: &[f32], sample_rate: u32, params: &SpectrogramParams) -> RgbImage {
+    let n_bins = params.fft_size / 2 + 1;
     let max_bin = if params.max_freq > 0.0 {
-        ((params.max_freq / sample_rate as f64) * fft_size as f64)
-            .ceil() as usize
-            + 1
+        ((params.max_freq / sample_rate as f64) * params.fft_size as f64).ceil() as usize + 1
     } else {
         n_bins
     }
     .min(n_bins);
 
-    let hann = hann_window(fft_size);
-    let mut magnitude = vec![vec![0.0f32; max_bin]; n_frames];
-
-    for (frame_idx, frame_start) in (0..samples.len().saturating_sub(fft_size))
-        .step_by(hop)
-        .enumerate()
-    {
-        if frame_idx >= n_frames {
-            break;
-        }
-        let mut buf: Vec<Complex<f32>> = samples[frame_start..frame_start + fft_size]
-            .iter()
-            .zip(hann.iter())
-            .map(|(&s, &w)| Complex::new(s * w, 0.0))
-            .collect();
-        fft.process(&mut buf);
-        for (bin, val) in buf.iter().take(max_bin).enumerate() {
-            let mag = (val.re * val.re + val.im * val.im).sqrt();
-            magnitude[frame_idx][bin] = 20.0 * (mag + 1e-10).log10();
+    let magnitude = magnitude_db(samples, params.fft_size, params.hop_size, max_bin);
+    let n_frames = magnitude.len();
+
+    // Resample time, then frequency.  Every source frame contributes to
+    // the image, so short calls don't vanish between sampled columns.
+    let (img_w, img_h) = (params.width, params.height);
+    let x_weights = resample_weights(n_frames, img_w as usize);
+    let y_weights = resample_weights(max_bin, img_h as usize);
+    let columns: Vec<Vec<f32>> = x_weights
+        .par_iter()
+        .map(|xw| {
+            let spectrum: Vec<f32> = (0..max_bin)
+                .map(|bin| xw.iter().map(|&(f, w)| magnitude[f][bin] * w).sum())
+                .collect();
+            y_weights
+                .iter()
+                .map(|yw| yw.iter().map(|&(b, w)| spectrum[b] * w).sum())
+                .collect()
+        })
+        .collect();
+
+    let mut img = RgbImage::new(img_w, img_h);
+    for (x, column) in columns.iter().enumerate() {
+        // Row 0 is the highest frequency.
+        for (i, &val) in column.iter().enumerate() {
+            let y = img_h - 1 - i as u32;
+            img.put_pixel(x as u32, y, apply_colormap(params.colormap, val));
         }
     }
+    img
+}
+
+/// FFT plan of `size` points, shared across clips; the planner keeps
+/// every plan it made.
+fn fft_plan(size: usize) -> Arc<dyn Fft<f32>> {
+    static PLANNER: OnceLock<Mutex<FftPlanner<f32>>> = OnceLock::new();
+    PLANNER
+        .get_or_init(|| Mutex::new(FftPlanner::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .plan_fft_forward(size)
+}
+
+/// Hann-windowed STFT magnitude in dB, frames × bins `0..max_bin`,
+/// normalised to 0..1 over the clip.  Frames are transformed in
+/// parallel; the last frame is zero-padded.
+fn magnitude_db(samples: &[f32], fft_size: usize, hop: usize, max_bin: usize) -> Vec<Vec<f32>> {
+    let n_frames = if samples.len() > fft_size {
+        (samples.len() - fft_size) / hop + 1
+    } else {
+        1
+    };
 
-    // Normalise
-    let global_min = magnitude.iter().flat_map(|r| r.iter()).cloned().fold(f32::INFINITY, f32::min);
-    let global_max = magnitude.iter().flat_map(|r| r.iter()).cloned().fold(f32::NEG_INFINITY, f32::max);
+    let fft = fft_plan(fft_size);
+    let hann = hann_window(fft_size);
+    let mut magnitude: Vec<Vec<f32>> = (0..n_frames)
+        .into_par_iter()
+        .map_init(
+            || {
+                let zero = Complex::new(0.0f32, 0.0);
+                let scratch = vec![zero; fft.get_inplace_scratch_len()];
+                (vec![zero; fft_size], scratch)
+            },
+            |(buf, scratch), frame| {
+                let start = frame * hop;
+                let frame_samples = samples.get(start..).unwrap_or_default();
+                for (i, (c, &w)) in buf.iter_mut().zip(&hann).enumerate() {
+                    *c = Complex::new(frame_samples.get(i).copied().unwrap_or(0.0) * w, 0.0);
+                }
+                fft.process_with_scratch(buf, scratch);
+                buf.iter()
+                    .take(max_bin)
+                    .map(|c| 20.0 * (c.norm() + 1e-10).log10())
+                    .collect()
+            },
+        )
+        .collect();
+
+    // Normalise to 0..1
+    let (global_min, global_max) = magnitude
+        .iter()
+        .flatten()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
     let range = (global_max - global_min).max(1e-6);
     for row in &mut magnitude {
         for val in row.iter_mut() {
             *val = (*val - global_min) / range;
         }
     }
+    magnitude
+}
 
-    let img_w = params.width;
-    let img_h = params.height;
-    let mut img = ImageBuffer::<Rgb<u8>, _>::new(img_w, img_h);
-    for x in 0..img_w {
-        let src_frame = (x as f64 / img_w as f64 * n_frames as f64) as usize;
-        let src_frame = src_frame.min(n_frames.saturating_sub(1));
-        for y in 0..img_h {
-            let bin = ((img_h - 1 - y) as f64 / img_h as f64 * max_bin as f64) as usize;
-            let bin = bin.min(max_bin.saturating_sub(1));
-            img.put_pixel(x, y, apply_colormap(params.colormap, magnitude[src_frame][bin]));
-        }
+/// Weights mapping `src` samples onto `dst`: a triangle filter as wide
+/// as one output step when shrinking (area average), linear
+/// interpolation when stretching.  Each output's weights sum to 1.
+fn resample_weights(src: usize, dst: usize) -> Vec<Vec<(usize, f32)>> {
+    if src == 0 {
+        return vec![Vec::new(); dst];
     }
-
-    let mut cursor = Cursor::new(Vec::new());
-    PngEncoder::new(&mut cursor)
-        .write_image(img.as_raw(), img_w, img_h, image::ExtendedColorType::Rgb8)
-        .context("PNG encode")?;
-    Ok(cursor.into_inner())
+    let scale = src as f64 / dst.max(1) as f64;
+    let support = scale.max(1.0);
+    (0..dst)
+        .map(|i| {
+            let center = (i as f64 + 0.5) * scale - 0.5;
+            let lo = (center - support).floor().max(0.0) as usize;
+            let hi = ((center + support).ceil() as usize).min(src - 1);
+            let mut weights: Vec<(usize, f32)> = (lo..=hi)
+                .filter_map(|j| {
+                    let w = 1.0 - (j as f64 - center).abs() / support;
+                    (w > 0.0).then_some((j, w as f32))
+                })
+                .collect();
+            if weights.is_empty() {
+                // Past either end: hold the nearest sample.
+                weights.push((center.round().clamp(0.0, (src - 1) as f64) as usize, 1.0));
+            }
+            let total: f32 = weights.iter().map(|&(_, w)| w).sum();
+            for (_, w) in &mut weights {
+                *w /= total;
+            }
+            weights
+        })
+        .collect()
 }
 
 /// Generate a spectrogram directly from a WAV file.
@@ -285,21 +283,27 @@ pub fn generate_from_wav(
     out_path: &Path,
     params: &SpectrogramParams,
 ) -> Result<()> {
+    let (mono, sample_rate) = read_wav_mono(wav_path)?;
+
+    // Downsample to 24 kHz for display
+    let target_rate = 24000;
+    let mono = if sample_rate != target_rate {
+        simple_downsample(&mono, sample_rate, target_rate)
+    } else {
+        mono
+    };
+
+    generate(&mono, target_rate, out_path, params)
+}
+
+/// Read a WAV file as mono f32 at its native sample rate.
+pub(crate) fn read_wav_mono(wav_path: &Path) -> Result<(Vec<f32>, u32)> {
     let reader = hound::WavReader::open(wav_path)
         .with_context(|| format!("Cannot open {}", wav_path.display()))?;
     let spec = reader.spec();
     let n_ch = spec.channels as usize;
 
-    let samples: Vec<f32> = match spec.sample_format {
-        hound::SampleFormat::Int => reader
-            .into_samples::<i32>()
-            .map(|s| s.unwrap_or(0) as f32 / i32::MAX as f32)
-            .collect(),
-        hound::SampleFormat::Float => reader
-            .into_samples::<f32>()
-            .map(|s| s.unwrap_or(0.0))
-            .collect(),
-    };
+    let samples = gaia_common::audio::normalised_samples(reader);
 
     let mono: Vec<f32> = if n_ch == 1 {
         samples
@@ -309,16 +313,103 @@ pub fn generate_from_wav(
             .map(|frame| frame.iter().sum::<f32>() / n_ch as f32)
             .collect()
     };
+    Ok((mono, spec.sample_rate))
+}
 
-    // Downsample to 24 kHz for display
-    let target_rate = 24000;
-    let mono = if spec.sample_rate != target_rate {
-        simple_downsample(&mono, spec.sample_rate, target_rate)
-    } else {
-        mono
-    };
+// ── acoustic features ────────────────────────────────────────────────────
+
+/// Basic acoustic measurements of the dominant call in a clip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallFeatures {
+    /// Frequency bin with the most energy, summed over the clip (Hz).
+    pub peak_freq_hz: f64,
+    /// Contiguous band around the peak within [`FEATURE_FLOOR_DB`] (Hz).
+    pub bandwidth_hz: f64,
+    /// Time span of frames whose in-band energy is within
+    /// [`FEATURE_FLOOR_DB`] of the loudest frame (s).
+    pub duration_secs: f64,
+}
 
-    generate(&mono, target_rate, out_path, params)
+/// Threshold below the peak used for both bandwidth and duration.
+pub const FEATURE_FLOOR_DB: f32 = 20.0;
+
+/// Ignore energy below this frequency when picking the peak – wind and
+/// handling noise would otherwise win for most outdoor recordings.
+const FEATURE_MIN_FREQ_HZ: f64 = 150.0;
+
+/// Measure peak frequency, bandwidth and call duration of a WAV clip at
+/// its native sample rate (so ultrasonic bat calls keep their full range).
+pub fn call_features_from_wav(wav_path: &Path) -> Result<Option<CallFeatures>> {
+    let (mono, sample_rate) = read_wav_mono(wav_path)?;
+    Ok(call_features(&mono, sample_rate))
+}
+
+/// Measure [`CallFeatures`] from mono samples.  Returns `None` for clips
+/// that are too short or silent.
+pub fn call_features(samples: &[f32], sample_rate: u32) -> Option<CallFeatures> {
+    // ~5 ms frames at any sample rate, rounded up to a power of two.
+    let fft_size = ((sample_rate as usize) / 200).next_power_of_two().clamp(256, 4096);
+    let hop = fft_size / 4;
+    if samples.len() < fft_size || sample_rate == 0 {
+        return None;
+    }
+
+    let fft = fft_plan(fft_size);
+    let hann = hann_window(fft_size);
+    let n_bins = fft_size / 2 + 1;
+    let bin_hz = sample_rate as f64 / fft_size as f64;
+    let min_bin = ((FEATURE_MIN_FREQ_HZ / bin_hz).ceil() as usize).min(n_bins - 1);
+
+    // Power spectrum per frame.
+    let mut frames: Vec<Vec<f32>> = Vec::new();
+    let mut buf = vec![Complex::new(0.0f32, 0.0); fft_size];
+    for start in (0..=samples.len() - fft_size).step_by(hop) {
+        for (i, (&s, &w)) in samples[start..start + fft_size].iter().zip(&hann).enumerate() {
+            buf[i] = Complex::new(s * w, 0.0);
+        }
+        fft.process(&mut buf);
+        frames.push(buf.iter().take(n_bins).map(|c| c.norm_sqr()).collect());
+    }
+
+    // Peak of the clip-wide spectrum.
+    let mut total = vec![0.0f32; n_bins];
+    for frame in &frames {
+        for (t, p) in total.iter_mut().zip(frame) {
+            *t += p;
+        }
+    }
+    let (peak_bin, &peak_power) = total
+        .iter()
+        .enumerate()
+        .skip(min_bin)
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    if peak_power <= f32::EPSILON {
+        return None;
+    }
+
+    // Contiguous band around the peak above the floor.
+    let floor = peak_power * 10f32.powf(-FEATURE_FLOOR_DB / 10.0);
+    let mut lo = peak_bin;
+    while lo > min_bin && total[lo - 1] >= floor {
+        lo -= 1;
+    }
+    let mut hi = peak_bin;
+    while hi + 1 < n_bins && total[hi + 1] >= floor {
+        hi += 1;
+    }
+
+    // Frames where the call band is active.
+    let band_energy: Vec<f32> = frames.iter().map(|f| f[lo..=hi].iter().sum()).collect();
+    let loudest = band_energy.iter().cloned().fold(0.0f32, f32::max);
+    let active_floor = loudest * 10f32.powf(-FEATURE_FLOOR_DB / 10.0);
+    let first = band_energy.iter().position(|&e| e >= active_floor)?;
+    let last = band_energy.iter().rposition(|&e| e >= active_floor)?;
+
+    Some(CallFeatures {
+        peak_freq_hz: peak_bin as f64 * bin_hz,
+        bandwidth_hz: (hi - lo + 1) as f64 * bin_hz,
+        duration_secs: ((last - first) * hop + fft_size) as f64 / sample_rate as f64,
+    })
 }
 
 fn simple_downsample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
@@ -438,3 +529,90 @@ fn lerp3(a: (f32, f32, f32), b: (f32, f32, f32), t: f32) -> (f32, f32, f32) {
         a.2 + (b.2 - a.2) * t,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_features_tone_burst() {
+        // 100 ms of 40 kHz in the middle of 1 s of silence at 192 kHz.
+        let sr = 192_000u32;
+        let samples: Vec<f32> = (0..sr as usize)
+            .map(|i| {
+                let t = i as f32 / sr as f32;
+                if (0.45..0.55).contains(&t) {
+                    (2.0 * std::f32::consts::PI * 40_000.0 * t).sin()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let f = call_features(&samples, sr).unwrap();
+        assert!((f.peak_freq_hz - 40_000.0).abs() < 200.0, "peak {}", f.peak_freq_hz);
+        assert!(f.bandwidth_hz < 2_000.0, "bandwidth {}", f.bandwidth_hz);
+        assert!((f.duration_secs - 0.1).abs() < 0.02, "duration {}", f.duration_secs);
+    }
+
+    #[test]
+    fn test_resample_weights() {
+        // Same size: identity.
+        for (i, w) in resample_weights(4, 4).iter().enumerate() {
+            assert_eq!(w, &vec![(i, 1.0)]);
+        }
+        // Shrinking averages every source sample into some output.
+        let shrink = resample_weights(1000, 10);
+        let mut covered = vec![false; 1000];
+        for w in &shrink {
+            assert!((w.iter().map(|&(_, w)| w).sum::<f32>() - 1.0).abs() < 1e-5);
+            for &(j, _) in w {
+                covered[j] = true;
+            }
+        }
+        assert!(covered.iter().all(|&c| c));
+        // Stretching interpolates between neighbours.
+        let stretch = resample_weights(2, 8);
+        assert_eq!(stretch[0], vec![(0, 1.0)]);
+        assert_eq!(stretch[7], vec![(1, 1.0)]);
+        assert_eq!(stretch[4].len(), 2);
+        assert!(resample_weights(0, 3).iter().all(|w| w.is_empty()));
+    }
+
+    #[test]
+    fn test_magnitude_db_tone() {
+        // 3 kHz at 24 kHz lands in bin 128 of a 1024-point FFT.
+        let sr = 24_000.0;
+        let samples: Vec<f32> = (0..24_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 3_000.0 * i as f32 / sr).sin())
+            .collect();
+        let magnitude = magnitude_db(&samples, 1024, 512, 513);
+        assert_eq!(magnitude.len(), (24_000 - 1024) / 512 + 1);
+        for frame in &magnitude {
+            let peak = frame.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap();
+            assert_eq!(peak.0, 128);
+        }
+        assert!(magnitude.iter().flatten().all(|v| (0.0..=1.0).contains(v)));
+
+        // Shorter than one frame: a single zero-padded frame.
+        assert_eq!(magnitude_db(&samples[..100], 1024, 512, 513).len(), 1);
+    }
+
+    #[test]
+    fn test_render_size() {
+        let samples: Vec<f32> = (0..48_000).map(|i| ((i as f32) * 0.3).sin()).collect();
+        let params = SpectrogramParams {
+            width: 123,
+            height: 45,
+            ..Default::default()
+        };
+        let img = render(&samples, 24_000, &params);
+        assert_eq!(img.dimensions(), (123, 45));
+    }
+
+    #[test]
+    fn test_call_features_silence() {
+        assert!(call_features(&[0.0; 48_000], 48_000).is_none());
+        assert!(call_features(&[0.1; 10], 48_000).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! Pre-load capability check for TFLite models.
+//!
+//! tract-tflite panics — instead of returning an error — on tensor types
+//! it does not implement, most notably `FLOAT16` in the fp16 BirdNET
+//! variant.  This module reads just enough of the TFLite FlatBuffer to
+//! list the tensor types a model uses, so the loader can route such
+//! models through ONNX before tract ever sees them.
+//!
+//! Only the path `Model.subgraphs[*].tensors[*].type` is decoded; see
+//! the TFLite `schema.fbs` for the field numbers used below.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// `TensorType` names, indexed by the schema's enum value.
+const TENSOR_TYPE_NAMES: [&str; 18] = [
+    "FLOAT32", "FLOAT16", "INT32", "UINT8", "INT64", "STRING", "BOOL", "INT16", "COMPLEX64",
+    "INT8", "FLOAT64", "COMPLEX128", "UINT64", "RESOURCE", "VARIANT", "UINT32", "UINT16", "INT4",
+];
+
+/// Tensor types tract-tflite loads (float32 and the integer types used
+/// by the int8-quantised variants).
+const SUPPORTED: [u8; 7] = [0, 2, 3, 4, 6, 7, 9];
+
+/// Names of the tensor types in the model at `path` that tract-tflite
+/// cannot load.  Empty when the model is safe to hand to tract.
+pub fn unsupported_tensor_types(path: &Path) -> Result<Vec<String>> {
+    let buf = std::fs::read(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    let types = tensor_types(&buf)
+        .with_context(|| format!("Cannot parse TFLite FlatBuffer {}", path.display()))?;
+    Ok(types
+        .into_iter()
+        .filter(|t| !SUPPORTED.contains(t))
+        .map(|t| match TENSOR_TYPE_NAMES.get(t as usize) {
+            Some(name) => name.to_string(),
+            None => format!("type {t}"),
+        })
+        .collect())
+}
+
+/// All `TensorType` values used by any tensor in any subgraph.
+fn tensor_types(buf: &[u8]) -> Result<BTreeSet<u8>> {
+    let fb = FlatBuffer { buf };
+    let model = fb.deref(0)?;
+    let mut types = BTreeSet::new();
+    // Model.subgraphs = field 2, SubGraph.tensors = field 0,
+    // Tensor.type = field 1 (a byte, default FLOAT32).
+    let Some(subgraphs) = fb.table_field(model, 2)? else {
+        return Ok(types);
+    };
+    for subgraph in fb.tables(subgraphs)? {
+        let Some(tensors) = fb.table_field(subgraph, 0)? else {
+            continue;
+        };
+        for tensor in fb.tables(tensors)? {
+            let ty = match fb.field(tensor, 1)? {
+                Some(pos) => fb.bytes(pos, 1)?[0],
+                None => 0,
+            };
+            types.insert(ty);
+        }
+    }
+    Ok(types)
+}
+
+/// Bounds-checked FlatBuffer accessors.
+struct FlatBuffer<'a> {
+    buf: &'a [u8],
+}
+
+impl FlatBuffer<'_> {
+    fn bytes(&self, pos: usize, len: usize) -> Result<&[u8]> {
+        match self.buf.get(pos..pos.saturating_add(len)) {
+            Some(b) => Ok(b),
+            None => bail!("offset {pos} out of bounds ({} bytes)", self.buf.len()),
+        }
+    }
+
+    fn u16(&self, pos: usize) -> Result<usize> {
+        let b = self.bytes(pos, 2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]) as usize)
+    }
+
+    fn u32(&self, pos: usize) -> Result<usize> {
+        let b = self.bytes(pos, 4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    }
+
+    /// Follow the unsigned offset stored at `pos`.
+    fn deref(&self, pos: usize) -> Result<usize> {
+        Ok(pos + self.u32(pos)?)
+    }
+
+    /// Position of field `index` of the table at `table`, or `None` when
+    /// the field is absent (default value).
+    fn field(&self, table: usize, index: usize) -> Result<Option<usize>> {
+        let b = self.bytes(table, 4)?;
+        let soffset = i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as i64;
+        let vtable = usize::try_from(table as i64 - soffset).context("vtable before start of buffer")?;
+        let vtable_len = self.u16(vtable)?;
+        let entry = 4 + 2 * index;
+        if entry + 2 > vtable_len {
+            return Ok(None);
+        }
+        match self.u16(vtable + entry)? {
+            0 => Ok(None),
+            off => Ok(Some(table + off)),
+        }
+    }
+
+    /// Target of an offset-typed field (sub-table or vector).
+    fn table_field(&self, table: usize, index: usize) -> Result<Option<usize>> {
+        self.field(table, index)?.map(|pos| self.deref(pos)).transpose()
+    }
+
+    /// Positions of the tables in the vector of tables at `vector`.
+    fn tables(&self, vector: usize) -> Result<Vec<usize>> {
+        let len = self.u32(vector)?;
+        self.bytes(vector + 4, len.saturating_mul(4))?;
+        (0..len).map(|i| self.deref(vector + 4 + 4 * i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A model with one subgraph holding a FLOAT32 and a FLOAT16 tensor.
+    fn tiny_model() -> Vec<u8> {
+        let mut b = vec![0u8; 88];
+        let put16 = |b: &mut Vec<u8>, pos: usize, v: u16| b[pos..pos + 2].copy_from_slice(&v.to_le_bytes());
+        let put32 = |b: &mut Vec<u8>, pos: usize, v: u32| b[pos..pos + 4].copy_from_slice(&v.to_le_bytes());
+        put32(&mut b, 0, 20); // root table
+        b[4..8].copy_from_slice(b"TFL3");
+        // Model vtable @8: fields 0, 1 absent, 2 (subgraphs) at +4.
+        for (i, v) in [10u16, 8, 0, 0, 4].into_iter().enumerate() {
+            put16(&mut b, 8 + 2 * i, v);
+        }
+        put32(&mut b, 20, 12); // Model @20 → vtable @8
+        put32(&mut b, 24, 28 - 24); // subgraphs vector @28
+        put32(&mut b, 28, 1);
+        put32(&mut b, 32, 44 - 32); // SubGraph @44
+        // SubGraph vtable @36: field 0 (tensors) at +4.
+        for (i, v) in [6u16, 8, 4].into_iter().enumerate() {
+            put16(&mut b, 36 + 2 * i, v);
+        }
+        put32(&mut b, 44, 8); // → vtable @36
+        put32(&mut b, 48, 52 - 48); // tensors vector @52
+        put32(&mut b, 52, 2);
+        put32(&mut b, 56, 72 - 56);
+        put32(&mut b, 60, 80 - 60);
+        // Tensor vtable @64: field 1 (type) at +4.
+        for (i, v) in [8u16, 8, 0, 4].into_iter().enumerate() {
+            put16(&mut b, 64 + 2 * i, v);
+        }
+        put32(&mut b, 72, 8); // Tensor @72, FLOAT32
+        put32(&mut b, 80, 16); // Tensor @80
+        b[84] = 1; // FLOAT16
+        b
+    }
+
+    #[test]
+    fn test_tensor_types() {
+        let types = tensor_types(&tiny_model()).unwrap();
+        assert_eq!(types.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+
+        let dir = std::env::temp_dir().join(format!("gaia-tflite-probe-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("fp16.tflite");
+        std::fs::write(&path, tiny_model()).unwrap();
+        assert_eq!(unsupported_tensor_types(&path).unwrap(), vec!["FLOAT16"]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_truncated_is_error() {
+        let model = tiny_model();
+        assert!(tensor_types(&model[..60]).is_err());
+        assert!(tensor_types(&[]).is_err());
+    }
+}
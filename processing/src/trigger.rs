@@ -0,0 +1,98 @@
+//! First-pass trigger for ring-buffer capture nodes.
+//!
+//! A capture node with `RING_BUFFER_SECS` set keeps its segments in RAM
+//! only.  After analysing one, the worker asks the node to keep it on
+//! disk when a model detected something or, failing that, when a short
+//! burst stands well above the segment's background: a call the models
+//! missed or do not know is still worth a listen.
+
+/// Length of the frames compared by [`burst_db`].
+const FRAME_SECS: f64 = 0.1;
+
+/// Share of the quietest frames taken as the background level.
+const FLOOR_PERCENTILE: f64 = 0.2;
+
+/// What analysing one recording found, for deciding whether to keep it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interest {
+    pub detections: usize,
+    /// Loudest frame over the background, in dB (see [`burst_db`]).
+    pub burst_db: f64,
+}
+
+impl Interest {
+    /// Any detection, or a burst of at least `energy_db`
+    /// (`RING_KEEP_ENERGY_DB`; `0` ignores bursts).
+    pub fn worth_keeping(&self, energy_db: f64) -> bool {
+        self.detections > 0 || (energy_db > 0.0 && self.burst_db >= energy_db)
+    }
+}
+
+/// How far the loudest 100 ms frame of `signal` rises above its
+/// background (the 20th percentile frame), in dB.  Steady noise such as
+/// rain or wind scores near 0, a call over a quiet night well above 20.
+pub fn burst_db(signal: &[f32], sample_rate: u32) -> f64 {
+    let frame = ((sample_rate as f64 * FRAME_SECS) as usize).max(1);
+    let mut levels: Vec<f64> = signal
+        .chunks_exact(frame)
+        .map(|f| (f.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / frame as f64).sqrt())
+        .collect();
+    if levels.is_empty() {
+        return 0.0;
+    }
+    levels.sort_by(f64::total_cmp);
+    let floor = levels[((levels.len() - 1) as f64 * FLOOR_PERCENTILE) as usize].max(1e-6);
+    let peak = levels[levels.len() - 1].max(1e-6);
+    20.0 * (peak / floor).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(secs: f64, amplitude: f32, sr: u32) -> Vec<f32> {
+        (0..(secs * sr as f64) as usize)
+            .map(|i| {
+                amplitude
+                    * (i as f64 * 2.0 * std::f64::consts::PI * 3_000.0 / sr as f64).sin() as f32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_burst_db() {
+        let sr = 24_000;
+        // Steady background: no burst.
+        let steady = tone(3.0, 0.01, sr);
+        assert!(burst_db(&steady, sr).abs() < 1.0);
+
+        // A half-second call 30 dB over the background.
+        let mut call = steady.clone();
+        call.extend(tone(0.5, 0.316, sr));
+        call.extend(tone(3.0, 0.01, sr));
+        let db = burst_db(&call, sr);
+        assert!((db - 30.0).abs() < 1.0, "{db}");
+
+        assert_eq!(burst_db(&[], sr), 0.0);
+    }
+
+    #[test]
+    fn test_worth_keeping() {
+        let quiet = Interest {
+            detections: 0,
+            burst_db: 6.0,
+        };
+        assert!(!quiet.worth_keeping(18.0));
+        assert!(Interest {
+            detections: 1,
+            ..quiet
+        }
+        .worth_keeping(18.0));
+        let loud = Interest {
+            detections: 0,
+            burst_db: 24.0,
+        };
+        assert!(loud.worth_keeping(18.0));
+        assert!(!loud.worth_keeping(0.0));
+    }
+}
@@ -0,0 +1,387 @@
+//! `POST /api/upload`: push ingestion for recorders that cannot be polled.
+//!
+//! An ESP32 with an I²S microphone has neither the storage nor the
+//! memory to serve the capture API, but it can POST each finished WAV
+//! segment to the processing server:
+//!
+//! ```text
+//! POST /api/upload
+//! Authorization: Bearer <CAPTURE_AUTH_TOKEN>
+//! X-Node-Id: hedge-esp32
+//! X-Recorded-At: 2026-05-01T06:12:30Z
+//! Content-Type: audio/wav
+//!
+//! <RIFF/WAVE bytes>
+//! ```
+//!
+//! `X-Node-Id` is stored as the detections' `Source_Node`; `X-Recorded-At`
+//! is the RFC 3339 start of the segment.  Segments may be any length up
+//! to `UPLOAD_MAX_MB` and are not checked against `RECORDING_LENGTH`.
+//! The file is written under `<RECS_DIR>/uploads/<node>/` and queued for
+//! the workers like a downloaded recording; reporting deletes it after
+//! analysis.  Uploads still on disk at startup (accepted, then
+//! interrupted by a restart) are queued again.
+//!
+//! Responses: `202` queued, `200` already received (a retry after a lost
+//! response, or the same audio under another timestamp within
+//! `DEDUP_WINDOW_HOURS`), `400` bad headers, `401` bad token, `413` over
+//! `UPLOAD_MAX_MB`, `415` not a WAV file, and `503` with `Retry-After`
+//! while the workers are busy or every model is disabled — the device
+//! keeps the segment and tries again later.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use gaia_common::config::Config;
+use gaia_common::detection::segment_name_pattern;
+
+use crate::journal::{self, Stage};
+use crate::WorkItem;
+
+/// Seconds a device is asked to wait when the workers are busy.
+const RETRY_AFTER_SECS: u32 = 30;
+
+struct Uploads {
+    config: Config,
+    work_tx: SyncSender<WorkItem>,
+    dir: PathBuf,
+}
+
+/// The upload route, or `None` when `UPLOAD_MAX_MB` is 0.
+pub fn router(
+    config: &Config,
+    work_tx: SyncSender<WorkItem>,
+    shutdown: &'static AtomicBool,
+) -> Option<Router> {
+    if config.upload_max_mb == 0 {
+        return None;
+    }
+    if config.capture_auth_token.is_none() {
+        warn!("CAPTURE_AUTH_TOKEN not set – anyone on the network can upload recordings");
+    }
+    info!("Accepting recording uploads up to {} MiB", config.upload_max_mb);
+    let limit = config.upload_max_mb as usize * 1024 * 1024;
+    let state = Arc::new(Uploads {
+        config: config.clone(),
+        work_tx,
+        dir: config.recs_dir.join("uploads"),
+    });
+    let leftovers = state.clone();
+    let spawned = std::thread::Builder::new()
+        .name("upload-requeue".into())
+        .spawn(move || {
+            requeue(&leftovers.dir, &leftovers.config, &leftovers.work_tx, shutdown)
+        });
+    if let Err(e) = spawned {
+        warn!("Cannot queue earlier uploads: {e}");
+    }
+    Some(
+        Router::new()
+            .route("/api/upload", post(upload))
+            .layer(DefaultBodyLimit::max(limit))
+            .with_state(state),
+    )
+}
+
+/// What an upload's headers say about it.
+#[derive(Debug, PartialEq)]
+struct UploadMeta {
+    node: String,
+    recorded_at: DateTime<Utc>,
+}
+
+impl UploadMeta {
+    /// Segment name in the capture node format, in UTC, so analysis reads
+    /// the time from it like from any other recording.
+    fn filename(&self) -> String {
+        self.recorded_at
+            .format(&segment_name_pattern("", false, true))
+            .to_string()
+    }
+}
+
+enum Outcome {
+    Queued(String),
+    Duplicate(String),
+    Busy(&'static str),
+}
+
+async fn upload(State(state): State<Arc<Uploads>>, headers: HeaderMap, body: Bytes) -> Response {
+    if let Err(denied) = check_token(&state.config, &headers) {
+        return denied;
+    }
+    let meta = match parse_headers(&headers) {
+        Ok(meta) => meta,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    if !is_wav(&body) {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "body is not a RIFF/WAVE file").into_response();
+    }
+
+    let outcome = tokio::task::spawn_blocking(move || accept(&state, meta, &body)).await;
+    match outcome {
+        Ok(Ok(Outcome::Queued(name))) => (StatusCode::ACCEPTED, name).into_response(),
+        Ok(Ok(Outcome::Duplicate(name))) => (StatusCode::OK, name).into_response(),
+        Ok(Ok(Outcome::Busy(why))) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+            why,
+        )
+            .into_response(),
+        Ok(Err(e)) => {
+            warn!("Upload failed: {e:#}");
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// `401` unless the request carries the `CAPTURE_AUTH_TOKEN` bearer
+/// token, when one is set.
+pub(crate) fn check_token(config: &Config, headers: &HeaderMap) -> Result<(), Response> {
+    let Some(token) = &config.capture_auth_token else {
+        return Ok(());
+    };
+    let given = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    if gaia_common::capture_api::bearer_matches(given, token) {
+        return Ok(());
+    }
+    Err((
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        "missing or invalid bearer token",
+    )
+        .into_response())
+}
+
+/// Store the recording and queue it for analysis.
+fn accept(state: &Uploads, meta: UploadMeta, body: &[u8]) -> anyhow::Result<Outcome> {
+    use anyhow::Context;
+
+    if matches!(crate::kv::get_enabled_models_state(), Some(v) if v.is_empty()) {
+        return Ok(Outcome::Busy("every model is disabled"));
+    }
+    let filename = meta.filename();
+    let key = journal::key(&meta.node, &filename);
+    if journal::stage(&key)?.is_some() {
+        return Ok(Outcome::Duplicate(filename));
+    }
+    if state.config.dedup_window_hours > 0 {
+        let window = Duration::from_secs(u64::from(state.config.dedup_window_hours) * 3600);
+        let hash = journal::content_hash(body);
+        if let Some(original) = journal::claim_content(&key, &hash, window)? {
+            info!("{key} is identical to {original} — not analysed");
+            journal::mark(&key, Stage::Analysed)?;
+            return Ok(Outcome::Duplicate(filename));
+        }
+    }
+
+    let dir = state.dir.join(&meta.node);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    let path = dir.join(&filename);
+    std::fs::write(&path, body).with_context(|| format!("Cannot write {}", path.display()))?;
+    journal::mark(&key, Stage::Dispatched)?;
+
+    match state.work_tx.try_send(work_item(&state.config, &path, &meta.node)) {
+        Ok(()) => {
+            crate::activity::enqueue(crate::activity::Queue::Analysis);
+            info!("Received {filename} from {} ({} bytes)", meta.node, body.len());
+            Ok(Outcome::Queued(filename))
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            let _ = journal::forget(&key);
+            Ok(Outcome::Busy(match e {
+                TrySendError::Full(_) => "analysis queue is full",
+                TrySendError::Disconnected(_) => "processing is shutting down",
+            }))
+        }
+    }
+}
+
+/// Work item for a recording analysed in place at `path`.
+pub(crate) fn work_item(config: &Config, path: &Path, node: &str) -> WorkItem {
+    let mut config = config.clone();
+    crate::kv::apply_settings_overrides(&mut config);
+    WorkItem {
+        local_path: path.to_path_buf(),
+        filename: path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        // Nothing to delete remotely: reporting removes the local copy.
+        base_url: String::new(),
+        source_node: node.to_string(),
+        source_node_id: node.to_string(),
+        // Uploaded segments and imported clips have no fixed length.
+        expected_secs: None,
+        config_snapshot: config,
+    }
+}
+
+/// Queue recordings left in `dir/<node>/` by the previous run, oldest
+/// first.  Shared with folder imports.
+pub(crate) fn requeue(
+    dir: &Path,
+    config: &Config,
+    work_tx: &SyncSender<WorkItem>,
+    shutdown: &AtomicBool,
+) {
+    let mut files: Vec<(String, PathBuf)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .flat_map(|node_dir| {
+            let node = node_dir.file_name().to_string_lossy().into_owned();
+            std::fs::read_dir(node_dir.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| is_audio(p))
+                .map(move |p| (node.clone(), p))
+        })
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+    files.sort_by(|a, b| a.1.file_name().cmp(&b.1.file_name()));
+    info!(
+        "Queuing {} recording(s) left in {} from the previous run",
+        files.len(),
+        dir.display()
+    );
+    for (node, path) in files {
+        if shutdown.load(Ordering::Relaxed) {
+            return;
+        }
+        let item = work_item(config, &path, &node);
+        let key = journal::key(&node, &item.filename);
+        if let Err(e) = journal::mark(&key, Stage::Dispatched) {
+            warn!("Cannot journal {}: {e:#}", item.filename);
+        }
+        crate::activity::enqueue(crate::activity::Queue::Analysis);
+        // Blocks while the workers are busy, which is what we want here.
+        if work_tx.send(item).is_err() {
+            return;
+        }
+    }
+}
+
+fn parse_headers(headers: &HeaderMap) -> Result<UploadMeta, String> {
+    let get = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| format!("missing {name} header"))
+    };
+    let node = get("x-node-id")?;
+    if !valid_node_id(node) {
+        return Err("X-Node-Id must be 1–64 letters, digits, '-', '_' or '.'".into());
+    }
+    let recorded_at = DateTime::parse_from_rfc3339(get("x-recorded-at")?)
+        .map_err(|e| format!("X-Recorded-At is not an RFC 3339 time: {e}"))?
+        .with_timezone(&Utc);
+    Ok(UploadMeta {
+        node: node.to_string(),
+        recorded_at,
+    })
+}
+
+/// Whether `node` is safe as a `Source_Node` and directory name.
+pub(crate) fn valid_node_id(node: &str) -> bool {
+    !node.is_empty()
+        && node.len() <= 64
+        && !node.starts_with('.')
+        && node
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Whether `path` has one of the audio extensions analysis reads.
+pub(crate) fn is_audio(path: &Path) -> bool {
+    let ext = crate::export::extension(path);
+    crate::cli::AUDIO_EXTENSIONS.contains(&ext.as_str())
+}
+
+fn is_wav(body: &[u8]) -> bool {
+    body.len() > 44 && &body[..4] == b"RIFF" && &body[8..12] == b"WAVE"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_upload_headers() {
+        let meta = parse_headers(&headers(&[
+            ("x-node-id", "hedge-esp32"),
+            ("x-recorded-at", "2026-05-01T08:12:30+02:00"),
+        ]))
+        .unwrap();
+        assert_eq!(meta.node, "hedge-esp32");
+        assert_eq!(meta.filename(), "2026-05-01-birdnet-06:12:30Z.wav");
+
+        assert!(parse_headers(&headers(&[("x-node-id", "a")])).is_err());
+        assert!(parse_headers(&headers(&[
+            ("x-node-id", "../etc"),
+            ("x-recorded-at", "2026-05-01T06:12:30Z"),
+        ]))
+        .is_err());
+        assert!(parse_headers(&headers(&[
+            ("x-node-id", "a"),
+            ("x-recorded-at", "yesterday"),
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_upload_name_round_trips() {
+        let meta = UploadMeta {
+            node: "n".into(),
+            recorded_at: DateTime::parse_from_rfc3339("2026-05-01T06:12:30Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        };
+        let parsed =
+            gaia_common::detection::ParsedFileName::parse(std::path::Path::new(&meta.filename()))
+                .unwrap();
+        assert_eq!(
+            parsed.file_date,
+            meta.recorded_at.with_timezone(&chrono::Local).naive_local()
+        );
+    }
+
+    #[test]
+    fn test_is_wav() {
+        let mut wav = b"RIFF\0\0\0\0WAVE".to_vec();
+        wav.resize(64, 0);
+        assert!(is_wav(&wav));
+        assert!(!is_wav(b"RIFF\0\0\0\0WAVE"));
+        assert!(!is_wav(&[0u8; 64]));
+    }
+}
@@ -0,0 +1,196 @@
+//! Quiet-station watchdog.
+//!
+//! A station that stops producing detections for a day usually has a
+//! dead microphone, not a silent garden.  Reporting counts the stored
+//! detections of each node and domain in hourly buckets in Redis (see
+//! [`record`]); every [`CHECK_INTERVAL`] the watchdog sums the last
+//! `WATCHDOG_HOURS` and compares them with the node's baseline, the
+//! median of the [`BASELINE_WINDOWS`] windows before.  Below
+//! `WATCHDOG_FRACTION` of the baseline, a notification goes to the
+//! browsers subscribed to station alerts.
+//!
+//! Each quiet spell is announced once, by whichever processing instance
+//! notices first; the node is watched again once it detects something.
+//! Nodes that usually detect less than once per window are left alone,
+//! as are nodes whose recordings are still waiting to be analysed.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use gaia_common::config::Config;
+use gaia_common::detection::Detection;
+
+use crate::kv;
+
+/// Windows before the current one the baseline is the median of.
+const BASELINE_WINDOWS: usize = 7;
+
+/// How often the counts are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Count a stored detection of `node` towards the watchdog, in the hour
+/// it was recorded.
+pub fn record(detection: &Detection, node: &str) {
+    let hour = hour_key(detection.datetime.with_timezone(&Utc));
+    kv::count_node_detection(&hour, node, &detection.domain);
+}
+
+/// Check the nodes until `shutdown`.  Returns at once when
+/// `WATCHDOG_HOURS` is 0.
+pub fn watchdog_loop(config: Config, shutdown: &AtomicBool) {
+    if config.watchdog_hours == 0 {
+        info!("Quiet-station watchdog off (WATCHDOG_HOURS=0)");
+        return;
+    }
+    info!(
+        "Quiet-station watchdog: alerting below {:.0}% of the usual detections per {} h",
+        config.watchdog_fraction * 100.0,
+        config.watchdog_hours
+    );
+
+    let mut next_run = Instant::now() + CHECK_INTERVAL;
+    while !shutdown.load(Ordering::Relaxed) {
+        if Instant::now() >= next_run {
+            next_run = Instant::now() + CHECK_INTERVAL;
+            if let Err(e) = check(&config, Utc::now()) {
+                warn!("Watchdog check failed: {e:#}");
+            }
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    info!("Watchdog thread stopped");
+}
+
+fn check(config: &Config, now: DateTime<Utc>) -> anyhow::Result<()> {
+    let hours = config.watchdog_hours as usize;
+    let keys: Vec<String> = (0..hours * (BASELINE_WINDOWS + 1))
+        .map(|i| hour_key(now - chrono::Duration::hours(i as i64)))
+        .collect();
+    let buckets = kv::node_detection_counts(&keys)?;
+    let busy: Vec<String> = crate::client::node_backlog()
+        .into_iter()
+        .filter(|b| b.pending > 0)
+        .map(|b| b.node)
+        .collect();
+    let alerted = kv::node_alerts();
+
+    for (field, counts) in windows(&buckets, hours) {
+        let (node, domain) = field.split_once('|').unwrap_or((field.as_str(), ""));
+        if busy.iter().any(|b| b == node) {
+            continue;
+        }
+        match quiet(&counts, config.watchdog_fraction) {
+            Some(baseline) => {
+                if kv::claim_node_alert(&field) {
+                    warn!(
+                        "Station {node} ({domain}) is quiet: {} detection(s) in {hours} h, \
+                         usually {baseline:.0}",
+                        counts[0]
+                    );
+                    kv::queue_station_alert(
+                        node,
+                        domain,
+                        counts[0],
+                        baseline,
+                        config.watchdog_hours,
+                    );
+                }
+            }
+            None if counts[0] > 0 && alerted.contains(&field) => {
+                info!("Station {node} ({domain}) detects again");
+                kv::clear_node_alert(&field);
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Redis bucket of the hour `at` falls in.
+fn hour_key(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H").to_string()
+}
+
+/// Detections per `node|domain` and window of `hours` hourly buckets,
+/// current window first.  `buckets` are newest first.
+fn windows(buckets: &[HashMap<String, u64>], hours: usize) -> HashMap<String, Vec<u64>> {
+    let mut out: HashMap<String, Vec<u64>> = HashMap::new();
+    let n_windows = buckets.len().div_ceil(hours.max(1));
+    for (i, bucket) in buckets.iter().enumerate() {
+        for (field, &n) in bucket {
+            let counts = out
+                .entry(field.clone())
+                .or_insert_with(|| vec![0; n_windows]);
+            counts[i / hours.max(1)] += n;
+        }
+    }
+    out
+}
+
+/// The baseline, when the current window (`counts[0]`) fell below
+/// `fraction` of it.  The baseline is the median of the earlier windows
+/// and must be at least one detection.
+fn quiet(counts: &[u64], fraction: f64) -> Option<f64> {
+    let (&current, earlier) = counts.split_first()?;
+    if earlier.is_empty() {
+        return None;
+    }
+    let mut sorted = earlier.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let baseline = if sorted.len() % 2 == 1 {
+        sorted[mid] as f64
+    } else {
+        (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+    };
+    (baseline >= 1.0 && (current as f64) < baseline * fraction).then_some(baseline)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        let bucket = |pairs: &[(&str, u64)]| -> HashMap<String, u64> {
+            pairs.iter().map(|(f, n)| (f.to_string(), *n)).collect()
+        };
+        let buckets = [
+            bucket(&[]),
+            bucket(&[("roof|birds", 1)]),
+            bucket(&[("garden|birds", 4), ("roof|birds", 2)]),
+            bucket(&[("garden|birds", 5)]),
+            bucket(&[("garden|bats", 3)]),
+        ];
+        let found = windows(&buckets, 2);
+        assert_eq!(found["roof|birds"], [1, 2, 0]);
+        assert_eq!(found["garden|birds"], [0, 9, 0]);
+        assert_eq!(found["garden|bats"], [0, 0, 3]);
+    }
+
+    #[test]
+    fn test_quiet() {
+        // Silent after a week of about 40 a day.
+        assert_eq!(quiet(&[0, 40, 38, 45, 41, 0, 39, 50], 0.1), Some(40.0));
+        assert_eq!(quiet(&[3, 40, 38, 45, 41, 0, 39, 50], 0.1), Some(40.0));
+        assert_eq!(quiet(&[4, 40, 38, 45, 41, 0, 39, 50], 0.1), None);
+        // A new node has no baseline yet; a rarely used one never does.
+        assert_eq!(quiet(&[0, 12, 0, 0, 0, 0, 0, 0], 0.1), None);
+        assert_eq!(quiet(&[0, 1, 0, 1, 0, 1, 0, 0], 0.1), None);
+        // Fraction 0 never alerts.
+        assert_eq!(quiet(&[0, 40, 40, 40, 40, 40, 40, 40], 0.0), None);
+        assert_eq!(quiet(&[0], 0.1), None);
+    }
+
+    #[test]
+    fn test_hour_key() {
+        let at = Utc.with_ymd_and_hms(2026, 5, 1, 6, 59, 59).unwrap();
+        assert_eq!(hour_key(at), "2026-05-01T06");
+    }
+}
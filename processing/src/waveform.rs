@@ -0,0 +1,89 @@
+//! Peak envelope of an extracted clip, written next to it as
+//! `<clip>.peaks.json` so the dashboard can draw the waveform without
+//! downloading and decoding the audio.
+//!
+//! The file holds [`POINTS`] min/max pairs scaled to `-127..=127`:
+//!
+//! ```json
+//! {"duration_secs":6.0,"min":[-12,-40,…],"max":[15,38,…]}
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// Number of min/max pairs per clip, about one per pixel of a card.
+pub const POINTS: usize = 1000;
+
+/// The envelope as stored in the sidecar.
+#[derive(Debug, Serialize)]
+pub struct Peaks {
+    pub duration_secs: f64,
+    pub min: Vec<i8>,
+    pub max: Vec<i8>,
+}
+
+/// Sidecar path for `clip`: `<clip>.peaks.json`.
+pub fn sidecar_path(clip: &Path) -> PathBuf {
+    let mut name = clip.as_os_str().to_os_string();
+    name.push(".peaks.json");
+    PathBuf::from(name)
+}
+
+/// Reduce `samples` to at most `points` min/max pairs.
+pub fn peaks(samples: &[f32], sample_rate: u32, points: usize) -> Peaks {
+    let scale = |s: f32| (s.clamp(-1.0, 1.0) * 127.0).round() as i8;
+    let per_point = samples.len().div_ceil(points.max(1)).max(1);
+    let (min, max) = samples
+        .chunks(per_point)
+        .map(|bucket| {
+            let (lo, hi) = bucket
+                .iter()
+                .fold((0.0f32, 0.0f32), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+            (scale(lo), scale(hi))
+        })
+        .unzip();
+    Peaks {
+        duration_secs: samples.len() as f64 / f64::from(sample_rate.max(1)),
+        min,
+        max,
+    }
+}
+
+/// Compute the envelope of a WAV clip and write it to `out_path`.
+pub fn write_from_wav(wav_path: &Path, out_path: &Path) -> Result<()> {
+    let (mono, sample_rate) = crate::spectrogram::read_wav_mono(wav_path)?;
+    let json = serde_json::to_vec(&peaks(&mono, sample_rate, POINTS))?;
+    std::fs::write(out_path, json).with_context(|| format!("Cannot write {}", out_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peaks() {
+        let mut samples = vec![0.0f32; 10_000];
+        samples[25] = 0.5;
+        samples[9_999] = -1.5;
+        let p = peaks(&samples, 1_000, 100);
+        assert_eq!(p.min.len(), 100);
+        assert_eq!(p.max.len(), 100);
+        assert_eq!((p.min[0], p.max[0]), (0, 64));
+        assert_eq!((p.min[99], p.max[99]), (-127, 0));
+        assert!((p.duration_secs - 10.0).abs() < 1e-9);
+
+        // Shorter than the number of points: one pair per sample.
+        assert_eq!(peaks(&samples[..10], 1_000, 100).max.len(), 10);
+        assert!(peaks(&[], 1_000, 100).max.is_empty());
+    }
+
+    #[test]
+    fn test_sidecar_path() {
+        assert_eq!(
+            sidecar_path(Path::new("/x/Robin-80.wav")),
+            Path::new("/x/Robin-80.wav.peaks.json")
+        );
+    }
+}
@@ -0,0 +1,35 @@
+// Service worker for Gaia Audio push notifications.
+//
+// Registered from the "Notify me" toggles; the server sends a JSON payload
+// of the form { title, body, tag, url }.
+
+self.addEventListener('push', (event) => {
+  let data = {};
+  try {
+    data = event.data ? event.data.json() : {};
+  } catch (_) {
+    data = { body: event.data ? event.data.text() : '' };
+  }
+  event.waitUntil(
+    self.registration.showNotification(data.title || 'Gaia Audio', {
+      body: data.body || '',
+      tag: data.tag,
+      data: { url: data.url || '/' },
+    })
+  );
+});
+
+self.addEventListener('notificationclick', (event) => {
+  event.notification.close();
+  const url = (event.notification.data && event.notification.data.url) || '/';
+  event.waitUntil(
+    self.clients.matchAll({ type: 'window', includeUncontrolled: true }).then((windows) => {
+      for (const w of windows) {
+        if ('navigate' in w) {
+          return w.navigate(url).then((c) => (c || w).focus());
+        }
+      }
+      return self.clients.openWindow(url);
+    })
+  );
+});
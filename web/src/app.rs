@@ -5,20 +5,34 @@ use leptos::prelude::{ElementChild, IntoView};
 use leptos_meta::*;
 use leptos_router::{
     components::{FlatRoutes, Route, Router},
-    ParamSegment, StaticSegment,
+    ParamSegment, SsrMode, StaticSegment,
 };
 
+use crate::components::connection::{error_fallback, provide_connection, OfflineBanner};
+use crate::components::domain_filter::provide_domain;
+use crate::components::favourite::provide_favourites;
+use crate::components::locale::provide_locale;
 use crate::components::nav::Nav;
+use crate::components::theme::{provide_theme, THEME_INIT_SCRIPT};
 use crate::pages::{
+    audit::AuditPage,
     calendar::CalendarPage,
+    compare::ComparePage,
     day::DayView,
+    detection::DetectionPage,
     excluded::ExcludedPage,
+    favourites::FavouritesPage,
     home::Home,
     import::ImportPage,
     learning::LearningPage,
+    map::MapPage,
+    review::ReviewPage,
+    richness::RichnessPage,
+    seasonal::SeasonalPage,
     settings::SettingsPage,
     species::SpeciesPage,
     species_list::SpeciesListPage,
+    stations::StationsPage,
 };
 
 /// Server-side application state, provided as Leptos context for server functions.
@@ -27,7 +41,7 @@ use crate::pages::{
 pub struct AppState {
     pub db_path: std::path::PathBuf,
     pub extracted_dir: std::path::PathBuf,
-    pub photo_cache: crate::server::inaturalist::PhotoCache,
+    pub photo_cache: crate::server::species_images::PhotoCache,
     pub leptos_options: leptos::config::LeptosOptions,
 }
 
@@ -49,6 +63,7 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
                 <meta charset="utf-8"/>
                 <meta name="viewport" content="width=device-width, initial-scale=1"/>
                 <meta name="description" content="Real-time audio species monitoring dashboard"/>
+                <script inner_html=THEME_INIT_SCRIPT></script>
                 <Stylesheet id="leptos" href="/pkg/gaia-web.css"/>
                 <AutoReload options=options.clone() />
                 <HydrationScripts options />
@@ -64,22 +79,45 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
 /// The root `<App/>` component.
 #[component]
 pub fn App() -> impl IntoView {
+    provide_theme();
+    provide_connection();
+    provide_domain();
+    provide_favourites();
+    provide_locale();
+
     view! {
         <Title text="Gaia Audio – Species Monitor"/>
         <Router>
             <Nav/>
+            <OfflineBanner/>
             <main class="main-content">
-                <FlatRoutes fallback=|| "Page not found.">
-                    <Route path=StaticSegment("") view=Home/>
-                    <Route path=StaticSegment("calendar") view=CalendarPage/>
-                    <Route path=(StaticSegment("calendar"), ParamSegment("date")) view=DayView/>
-                    <Route path=StaticSegment("species") view=SpeciesListPage/>
-                    <Route path=(StaticSegment("species"), ParamSegment("name")) view=SpeciesPage/>
-                    <Route path=StaticSegment("excluded") view=ExcludedPage/>
-                    <Route path=StaticSegment("learning") view=LearningPage/>
-                    <Route path=StaticSegment("import") view=ImportPage/>
-                    <Route path=StaticSegment("settings") view=SettingsPage/>
-                </FlatRoutes>
+                <ErrorBoundary fallback=error_fallback>
+                    <FlatRoutes fallback=|| "Page not found.">
+                        <Route path=StaticSegment("") view=Home/>
+                        <Route path=StaticSegment("calendar") view=CalendarPage/>
+                        <Route path=(StaticSegment("calendar"), ParamSegment("date")) view=DayView/>
+                        <Route path=StaticSegment("compare") view=ComparePage/>
+                        // Async SSR so the OpenGraph tags are in <head> for link previews.
+                        <Route
+                            path=(StaticSegment("detection"), ParamSegment("id"))
+                            view=DetectionPage
+                            ssr=SsrMode::Async
+                        />
+                        <Route path=StaticSegment("species") view=SpeciesListPage/>
+                        <Route path=(StaticSegment("species"), ParamSegment("name")) view=SpeciesPage/>
+                        <Route path=StaticSegment("seasonal") view=SeasonalPage/>
+                        <Route path=StaticSegment("richness") view=RichnessPage/>
+                        <Route path=StaticSegment("map") view=MapPage/>
+                        <Route path=StaticSegment("stations") view=StationsPage/>
+                        <Route path=StaticSegment("favourites") view=FavouritesPage/>
+                        <Route path=StaticSegment("excluded") view=ExcludedPage/>
+                        <Route path=StaticSegment("learning") view=LearningPage/>
+                        <Route path=StaticSegment("review") view=ReviewPage/>
+                        <Route path=StaticSegment("import") view=ImportPage/>
+                        <Route path=StaticSegment("settings") view=SettingsPage/>
+                        <Route path=StaticSegment("audit") view=AuditPage/>
+                    </FlatRoutes>
+                </ErrorBoundary>
             </main>
         </Router>
     }
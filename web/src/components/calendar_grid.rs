@@ -4,6 +4,7 @@ use leptos::prelude::*;
 use leptos::prelude::{ElementChild, IntoView};
 use leptos::either::Either;
 
+use crate::components::locale::use_locale;
 use crate::model::CalendarDay;
 
 /// Props for the calendar grid.
@@ -19,7 +20,8 @@ pub fn CalendarGrid(
     #[prop(optional)]
     highlight_dates: Option<Vec<String>>,
 ) -> impl IntoView {
-    let month_name = month_label(month);
+    let locale = use_locale();
+    let heading = format!("{} {year}", month_label(month));
     let first_weekday = first_day_of_month(year, month);
     let days_in_month = num_days_in_month(year, month);
     let max_detections = days.iter().map(|d| d.total_detections).max().unwrap_or(1).max(1);
@@ -75,7 +77,7 @@ pub fn CalendarGrid(
     view! {
         <div class="calendar">
             <div class="cal-header">
-                <h2>{month_name}" "{year}</h2>
+                <h2>{move || locale.month(year, month, &heading)}</h2>
             </div>
             <div class="cal-weekdays">
                 <span>"Mon"</span><span>"Tue"</span><span>"Wed"</span>
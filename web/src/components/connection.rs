@@ -0,0 +1,192 @@
+//! Connection to the web server as seen from the browser, so a backend
+//! restart degrades the dashboard instead of breaking it.
+//!
+//! [`provide_connection`] pings the server every [`HEARTBEAT_MS`] and
+//! keeps a [`ConnectionContext`] with the result; `<OfflineBanner/>`
+//! shows while pings fail.  Pages combine two helpers with it:
+//!
+//! - [`retry_on_error`] refetches a failed resource with exponential
+//!   backoff, and once more when the server comes back;
+//! - [`last_good`] keeps the last successful value of a resource on
+//!   screen while refetches fail, instead of replacing it with an error.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, ServerFnError};
+
+/// Interval between heartbeat pings.
+pub const HEARTBEAT_MS: i32 = 10_000;
+
+/// Delays before each automatic retry of a failed resource; after the
+/// last one the resource waits for the heartbeat to succeed again.
+const RETRY_DELAYS_MS: [i32; 5] = [1_000, 2_000, 4_000, 8_000, 16_000];
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn ping() -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+// ─── Context ─────────────────────────────────────────────────────────────────
+
+/// Whether the server answered the last heartbeat.
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionContext {
+    pub online: RwSignal<bool>,
+    /// Incremented each time the server answers again after a failure.
+    pub reconnects: RwSignal<u32>,
+}
+
+/// Provide [`ConnectionContext`] and start the heartbeat.
+///
+/// The server render assumes it is online; the first ping runs once the
+/// app has hydrated.
+pub fn provide_connection() -> ConnectionContext {
+    let ctx = ConnectionContext {
+        online: RwSignal::new(true),
+        reconnects: RwSignal::new(0),
+    };
+    provide_context(ctx);
+
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        let cb = Closure::wrap(Box::new(move || {
+            leptos::task::spawn_local(async move {
+                let ok = ping().await.is_ok();
+                if ok && !ctx.online.get_untracked() {
+                    ctx.reconnects.update(|n| *n += 1);
+                }
+                if ok != ctx.online.get_untracked() {
+                    ctx.online.set(ok);
+                }
+            });
+        }) as Box<dyn Fn()>);
+        let _ = web_sys::window()
+            .unwrap()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                cb.as_ref().unchecked_ref(),
+                HEARTBEAT_MS,
+            );
+        cb.forget();
+    }
+
+    ctx
+}
+
+// ─── Resource helpers ────────────────────────────────────────────────────────
+
+/// Call `refetch` with exponential backoff while `failed` is true, and
+/// right away whenever the heartbeat reports the server back.
+///
+/// ```ignore
+/// retry_on_error(move || matches!(data.get(), Some(Err(_))), move || data.refetch());
+/// ```
+#[allow(unused_variables)] // the retry timer only exists in the hydrate build
+pub fn retry_on_error(
+    failed: impl Fn() -> bool + 'static,
+    refetch: impl Fn() + Clone + 'static,
+) {
+    let conn = use_context::<ConnectionContext>();
+    let attempt = StoredValue::new(0usize);
+
+    // Back online: start over.
+    if let Some(conn) = conn {
+        let refetch = refetch.clone();
+        Effect::new(move |prev: Option<u32>| {
+            let n = conn.reconnects.get();
+            if prev.is_some_and(|p| p != n) {
+                attempt.set_value(0);
+                refetch();
+            }
+            n
+        });
+    }
+
+    Effect::new(move || {
+        if !failed() {
+            attempt.set_value(0);
+            return;
+        }
+        let n = attempt.get_value();
+        let Some(&delay) = RETRY_DELAYS_MS.get(n) else {
+            return;
+        };
+        attempt.set_value(n + 1);
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+            use wasm_bindgen::JsCast;
+            let refetch = refetch.clone();
+            let cb = Closure::once_into_js(move || refetch());
+            if let Some(window) = web_sys::window() {
+                let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                    cb.unchecked_ref(),
+                    delay,
+                );
+            }
+        }
+    });
+}
+
+/// The resource read by `read`, except that an error after a success
+/// yields the last successful value, so the page keeps showing data
+/// while the server restarts.
+pub fn last_good<T>(
+    read: impl Fn() -> Option<Result<T, ServerFnError>> + Clone + Send + Sync + 'static,
+) -> Signal<Option<Result<T, ServerFnError>>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let last = RwSignal::new(None::<T>);
+    {
+        let read = read.clone();
+        Effect::new(move || {
+            if let Some(Ok(value)) = read() {
+                last.set(Some(value));
+            }
+        });
+    }
+    Signal::derive(move || match read() {
+        Some(Err(e)) => Some(last.get().map(Ok).unwrap_or(Err(e))),
+        other => other,
+    })
+}
+
+// ─── Components ──────────────────────────────────────────────────────────────
+
+/// Banner shown while the server does not answer the heartbeat.
+#[component]
+pub fn OfflineBanner() -> impl IntoView {
+    let conn = expect_context::<ConnectionContext>();
+    view! {
+        <Show when=move || !conn.online.get()>
+            <div class="offline-banner" role="status">
+                "⚠ Connection to the server lost — showing the last data loaded, reconnecting…"
+            </div>
+        </Show>
+    }
+}
+
+/// Fallback of the app-wide `<ErrorBoundary>`: what failed, and a way
+/// out that does not need the failing page.
+pub fn error_fallback(errors: ArcRwSignal<Errors>) -> impl IntoView {
+    view! {
+        <div class="error-page">
+            <h2>"Something went wrong"</h2>
+            <ul class="error">
+                {move || errors
+                    .get()
+                    .into_iter()
+                    .map(|(_, e)| view! { <li>{e.to_string()}</li> })
+                    .collect::<Vec<_>>()}
+            </ul>
+            <p>
+                <a href="/">"Back to the live feed"</a>
+                " · "
+                <a href="" rel="external">"Reload this page"</a>
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,117 @@
+//! Training dataset export panel (Settings) – packages reviewed clips and
+//! their labels for fine-tuning a custom classifier.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+use crate::model::DatasetExport;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn export_training_dataset(
+    min_confidence: f64,
+    verified_only: bool,
+    per_class_folders: bool,
+) -> Result<DatasetExport, ServerFnError> {
+    use crate::server::{dataset, detections_duckdb as ddb, kv};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let candidates = ddb::dataset_candidates(&state.db_path, min_confidence, None)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
+    let reviews = kv::get_reviews().await.map_err(ServerFnError::new)?;
+    let extracted_dir = state.extracted_dir.clone();
+    tokio::task::spawn_blocking(move || {
+        dataset::export(&extracted_dir, candidates, &reviews, verified_only, per_class_folders)
+    })
+    .await
+    .map_err(|e| ServerFnError::new(format!("Export task failed: {e}")))?
+    .map_err(ServerFnError::new)
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Filters plus an export button; shows a download link when done.
+#[component]
+pub fn DatasetExportPanel() -> impl IntoView {
+    let (min_confidence, set_min_confidence) = signal(0.5f64);
+    let (verified_only, set_verified_only) = signal(true);
+    let (per_class_folders, set_per_class_folders) = signal(true);
+    let (busy, set_busy) = signal(false);
+    let (result, set_result) = signal(Option::<Result<DatasetExport, String>>::None);
+
+    let on_export = move |_| {
+        set_busy.set(true);
+        set_result.set(None);
+        let (conf, verified, folders) =
+            (min_confidence.get(), verified_only.get(), per_class_folders.get());
+        leptos::task::spawn_local(async move {
+            let res = export_training_dataset(conf, verified, folders)
+                .await
+                .map_err(|e| e.to_string());
+            set_result.set(Some(res));
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <div class="setting-group dataset-export">
+            <label class="setting-label">"Training Dataset"</label>
+            <p class="setting-help">
+                "Download clips with their labels as a tar archive for fine-tuning a custom "
+                "classifier. Clips rejected on the Review page are never included; reviewer "
+                "corrections and relabels replace the predicted species."
+            </p>
+            <div class="dataset-export-options">
+                <label>
+                    "Min. confidence "
+                    <input
+                        class="setting-input"
+                        type="number"
+                        min="0"
+                        max="1"
+                        step="0.05"
+                        prop:value=move || min_confidence.get().to_string()
+                        on:input=move |ev| {
+                            if let Ok(v) = event_target_value(&ev).parse::<f64>() {
+                                set_min_confidence.set(v.clamp(0.0, 1.0));
+                            }
+                        }
+                    />
+                </label>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || verified_only.get()
+                        on:change=move |ev| set_verified_only.set(event_target_checked(&ev))
+                    />
+                    " Reviewed or relabelled clips only"
+                </label>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || per_class_folders.get()
+                        on:change=move |ev| set_per_class_folders.set(event_target_checked(&ev))
+                    />
+                    " One folder per class (otherwise flat, with manifest.csv)"
+                </label>
+            </div>
+            <button class="btn btn-primary" on:click=on_export disabled=move || busy.get()>
+                {move || if busy.get() { "Packaging…" } else { "Export Dataset" }}
+            </button>
+            {move || result.get().map(|res| match res {
+                Ok(export) => view! {
+                    <div class="settings-success">
+                        <a href=export.url.clone() download>"⬇ Download dataset"</a>
+                        {format!(" — {} clips in {} classes", export.clips, export.classes)}
+                        {(export.missing > 0).then(|| {
+                            format!(" ({} without a clip on disk skipped)", export.missing)
+                        })}
+                    </div>
+                }.into_any(),
+                Err(e) => view! { <div class="settings-error">{e}</div> }.into_any(),
+            })}
+        </div>
+    }
+}
@@ -3,8 +3,29 @@
 use leptos::prelude::*;
 use leptos::prelude::{ElementChild, IntoView};
 
+use crate::components::domain_filter::domain_class;
+use crate::components::favourite::FavouriteButton;
+use crate::components::inat_export::InatExportButton;
+use crate::components::locale::use_locale;
+use crate::components::relabel::RelabelButton;
+use crate::components::spectrogram_viewer::SpectrogramViewer;
+use crate::components::waveform_player::WaveformPlayer;
 use crate::model::WebDetection;
 
+// ─── Server function ─────────────────────────────────────────────────────────
+
+/// Whether the time-expanded companion at `url` (see
+/// [`WebDetection::time_expanded_url`]) was written.
+#[server(prefix = "/api")]
+pub async fn has_time_expanded(url: String) -> Result<bool, ServerFnError> {
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let uri_path = url.strip_prefix("/extracted").unwrap_or(&url);
+    Ok(crate::server::clips::resolve_legacy_path(&state.extracted_dir, uri_path).is_some())
+}
+
+// ─── Components ──────────────────────────────────────────────────────────────
+
 /// Renders a detection card with species image, spectrogram, species info, capture node, and audio player.
 #[component]
 pub fn DetectionCard(detection: WebDetection) -> impl IntoView {
@@ -17,33 +38,57 @@ pub fn DetectionCard(detection: WebDetection) -> impl IntoView {
         "confidence low"
     };
 
-    let datetime = if detection.display_date.is_empty() {
-        format!("{} {}", &detection.date, &detection.time)
-    } else {
-        format!("{} {}", &detection.display_date, &detection.display_time)
+    let locale = use_locale();
+    let datetime = {
+        let (date, time) = (detection.date.clone(), detection.time.clone());
+        let (display_date, display_time) = (detection.display_date.clone(), detection.display_time.clone());
+        move || locale.detection_time(&date, &time, &display_date, &display_time)
     };
     let source_label = detection.source_label();
+    let channel = detection.channel;
+    let bearing = detection.bearing_label();
     let model_label = detection.model_label();
+    let model_title = match detection.model_provenance() {
+        Some(provenance) => format!("Detection model · {provenance}"),
+        None => "Detection model".to_string(),
+    };
     let is_excluded = detection.excluded;
     let is_beta = detection.model_beta;
+    let rare_title = detection
+        .rare
+        .then(|| match detection.rarity {
+            Some(r) => format!("Rarely reported in this region (rarity {:.0}%)", r * 100.0),
+            None => "Rarely reported in this region".to_string(),
+        });
     let agreement = detection.agreement_score;
     // Show agreement badge only when multiple models are active
     // (agreement < 1.0 means not all models agree, or only one model ran).
     let show_agreement = agreement > 0.0 && agreement < 1.0;
+    let call_features = detection.call_features_label();
 
     // URLs for the extracted audio clip and its spectrogram
     let audio_url = detection.clip_url();
+    let expanded_url = detection.time_expanded_url();
     let spectrogram_url = detection.spectrogram_url();
     let species_image = detection.image_url.clone();
     let common_name_alt = detection.common_name.clone();
+    let export = (!detection.file_name.is_empty())
+        .then(|| (detection.id, detection.file_name.clone()));
+    let relabel = export
+        .clone()
+        .map(|(id, file_name)| (id, file_name, detection.model_slug.clone(), detection.scientific_name.clone()));
+    let zoom_clip = audio_url.clone();
+    let (zoom_open, set_zoom_open) = signal(false);
+    let predicted = detection.original_scientific_name.clone();
 
     let species_href = format!("/species/{}", urlencoded(&detection.scientific_name));
+    let permalink = detection.permalink();
 
-    let card_class = if is_excluded {
-        "detection-card excluded"
-    } else {
-        "detection-card"
-    };
+    let card_class = format!(
+        "detection-card {}{}",
+        domain_class(&detection.domain),
+        if is_excluded { " excluded" } else { "" }
+    );
 
     view! {
         <div class={card_class}>
@@ -72,9 +117,10 @@ pub fn DetectionCard(detection: WebDetection) -> impl IntoView {
                 <div class="detection-meta">
                     <span class="domain-badge">{detection.domain.clone()}</span>
                     <span class={confidence_class}>{confidence_pct}</span>
-                    <span class="model-badge" title="Detection model">"🧠 " {model_label}</span>
+                    <span class="model-badge" title=model_title>"🧠 " {model_label}</span>
                     {is_beta.then(|| view! { <span class="beta-badge" title="Experimental model">"BETA"</span> })}
                     {is_excluded.then(|| view! { <span class="excluded-badge">"Excluded"</span> })}
+                    {rare_title.map(|title| view! { <span class="rare-badge" title=title>"Rare"</span> })}
                     {show_agreement.then(|| {
                         let pct = format!("{:.0}%", agreement * 100.0);
                         let cls = if agreement >= 0.7 {
@@ -86,14 +132,33 @@ pub fn DetectionCard(detection: WebDetection) -> impl IntoView {
                         };
                         view! { <span class={cls} title="Cross-model agreement">"🤝 " {pct}</span> }
                     })}
+                    {call_features.map(|label| view! {
+                        <span class="call-badge" title="Peak frequency · bandwidth · call duration">{label}</span>
+                    })}
+                    {predicted.map(|name| view! {
+                        <span class="relabel-badge" title="Species the model predicted">"✎ was " {name}</span>
+                    })}
                     <span class="source-badge" title="Capture node">{source_label}</span>
+                    {channel.map(|c| view! {
+                        <span class="source-badge" title="Input channel">{format!("Ch {}", c + 1)}</span>
+                    })}
+                    {bearing.map(|label| view! {
+                        <span class="source-badge" title="Estimated direction of the sound">"🧭 " {label}</span>
+                    })}
                 </div>
                 <div class="detection-timestamp">
                     <svg class="icon-clock" viewBox="0 0 16 16" width="14" height="14">
                         <circle cx="8" cy="8" r="7" fill="none" stroke="currentColor" stroke-width="1.5"/>
                         <polyline points="8,4 8,8 11,10" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
                     </svg>
-                    <time>{datetime}</time>
+                    <a href=permalink class="detection-permalink" title="Link to this detection">
+                        <time>{datetime}</time>
+                    </a>
+                    <FavouriteButton id=detection.id/>
+                    {export.map(|(id, file_name)| view! { <InatExportButton id=id file_name=file_name/> })}
+                    {relabel.map(|(id, file_name, model_slug, scientific_name)| view! {
+                        <RelabelButton id=id file_name=file_name model_slug=model_slug scientific_name=scientific_name/>
+                    })}
                 </div>
 
                 // Spectrogram inline (below metadata)
@@ -102,26 +167,48 @@ pub fn DetectionCard(detection: WebDetection) -> impl IntoView {
                         <img src={url} alt="spectrogram" loading="lazy"/>
                     </div>
                 })}
-
-                {audio_url.map(|url| {
-                    let mime = if url.ends_with(".opus") {
-                        "audio/ogg; codecs=opus"
-                    } else if url.ends_with(".mp3") {
-                        "audio/mpeg"
-                    } else {
-                        "audio/wav"
-                    };
-                    view! {
-                        <audio class="detection-audio" controls preload="metadata">
-                            <source src={url} type={mime}/>
-                        </audio>
-                    }
+                {zoom_clip.map(|url| view! {
+                    <button
+                        class="spectrogram-zoom-btn"
+                        title="Open a zoomable spectrogram of the clip"
+                        on:click=move |_| set_zoom_open.update(|o| *o = !*o)
+                    >
+                        {move || if zoom_open.get() { "✕ Close zoom" } else { "🔍 Zoom" }}
+                    </button>
+                    <Show when=move || zoom_open.get()>
+                        <SpectrogramViewer clip_url=url.clone()/>
+                    </Show>
                 })}
+
+                {audio_url.map(|url| view! { <WaveformPlayer clip_url=url/> })}
+                {expanded_url.map(|url| view! { <TimeExpandedPlayer url/> })}
             </div>
         </div>
     }.into_any()
 }
 
+/// Player for the slowed-down copy of an ultrasonic clip, shown once the
+/// server confirms there is one.
+#[component]
+fn TimeExpandedPlayer(url: String) -> impl IntoView {
+    let mime = crate::model::clip_mime_type(&url);
+    let target = StoredValue::new(url);
+    let exists = Resource::new(move || target.get_value(), has_time_expanded);
+
+    view! {
+        <Transition fallback=|| ()>
+            {move || exists.get().and_then(Result::ok).unwrap_or(false).then(|| view! {
+                <div class="detection-expanded" title="Played slowed down so the ultrasonic call is audible">
+                    <span>"Time-expanded"</span>
+                    <audio class="detection-audio" controls preload="none">
+                        <source src=target.get_value() type=mime/>
+                    </audio>
+                </div>
+            })}
+        </Transition>
+    }
+}
+
 fn urlencoded(s: &str) -> String {
     s.replace(' ', "%20")
 }
@@ -0,0 +1,116 @@
+//! Detection export panel (Settings) – downloads filtered detections as
+//! CSV or Parquet from `/api/export/detections`.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView};
+
+use super::query_encode;
+
+/// Filters plus a download link; the server streams the file.
+#[component]
+pub fn DetectionExportPanel() -> impl IntoView {
+    let (from, set_from) = signal(String::new());
+    let (to, set_to) = signal(String::new());
+    let (species, set_species) = signal(String::new());
+    let (domain, set_domain) = signal(String::new());
+    let (min_confidence, set_min_confidence) = signal(0.0f64);
+    let (format, set_format) = signal("csv".to_string());
+
+    let href = move || {
+        let mut params = vec![format!("format={}", format.get())];
+        for (key, value) in [
+            ("from", from.get()),
+            ("to", to.get()),
+            ("species", species.get()),
+            ("domain", domain.get()),
+        ] {
+            let value = value.trim().to_string();
+            if !value.is_empty() {
+                params.push(format!("{key}={}", query_encode(&value)));
+            }
+        }
+        let conf = min_confidence.get();
+        if conf > 0.0 {
+            params.push(format!("min_confidence={conf}"));
+        }
+        format!("/api/export/detections?{}", params.join("&"))
+    };
+
+    view! {
+        <div class="setting-group dataset-export">
+            <label class="setting-label">"Export Detections"</label>
+            <p class="setting-help">
+                "Download detections as CSV or Parquet for analysis in R or Python. "
+                "Leave a filter empty to include everything; dates are the stored (UTC) dates."
+            </p>
+            <div class="dataset-export-options">
+                <label>
+                    "From "
+                    <input
+                        class="setting-input detection-export-date"
+                        type="date"
+                        prop:value=move || from.get()
+                        on:input=move |ev| set_from.set(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "To "
+                    <input
+                        class="setting-input detection-export-date"
+                        type="date"
+                        prop:value=move || to.get()
+                        on:input=move |ev| set_to.set(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Species "
+                    <input
+                        class="setting-input detection-export-text"
+                        type="text"
+                        placeholder="Scientific name"
+                        prop:value=move || species.get()
+                        on:input=move |ev| set_species.set(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Domain "
+                    <input
+                        class="setting-input detection-export-text"
+                        type="text"
+                        placeholder="e.g. birds"
+                        prop:value=move || domain.get()
+                        on:input=move |ev| set_domain.set(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Min. confidence "
+                    <input
+                        class="setting-input"
+                        type="number"
+                        min="0"
+                        max="1"
+                        step="0.05"
+                        prop:value=move || min_confidence.get().to_string()
+                        on:input=move |ev| {
+                            if let Ok(v) = event_target_value(&ev).parse::<f64>() {
+                                set_min_confidence.set(v.clamp(0.0, 1.0));
+                            }
+                        }
+                    />
+                </label>
+                <label>
+                    "Format "
+                    <select
+                        class="setting-input"
+                        prop:value=move || format.get()
+                        on:change=move |ev| set_format.set(event_target_value(&ev))
+                    >
+                        <option value="csv">"CSV"</option>
+                        <option value="parquet">"Parquet"</option>
+                    </select>
+                </label>
+            </div>
+            <a class="btn btn-primary" href=href download>"⬇ Export Detections"</a>
+        </div>
+    }
+}
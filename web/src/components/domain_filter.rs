@@ -0,0 +1,183 @@
+//! Global domain selector (birds, bats, …) in the navigation bar.
+//!
+//! The chosen domain lives in a [`DomainContext`] provided by `<App/>` and
+//! is mirrored in the `?domain=` query parameter, so a filtered page can
+//! be bookmarked or shared.  The home feed, calendar, day view, species
+//! list, compare, richness and map pages pass it to their queries; `""`
+//! means every domain.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, For, IntoView, Resource, ServerFnError, Suspense};
+use leptos_router::hooks::use_query_map;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+/// Domains that have recorded detections, busiest first.
+#[server(prefix = "/api")]
+pub async fn get_available_domains() -> Result<Vec<String>, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    ddb::available_domains(&state.db_path)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Context ─────────────────────────────────────────────────────────────────
+
+/// The selected domain (`""` = all), shared through context.
+#[derive(Debug, Clone, Copy)]
+pub struct DomainContext(pub RwSignal<String>);
+
+/// Provide an empty [`DomainContext`]; [`DomainFilter`] fills it from the
+/// URL.
+pub fn provide_domain() -> DomainContext {
+    let ctx = DomainContext(RwSignal::new(String::new()));
+    provide_context(ctx);
+    ctx
+}
+
+/// The selected domain, or a fresh "all domains" signal outside `<App/>`.
+pub fn use_domain() -> RwSignal<String> {
+    use_context::<DomainContext>()
+        .map(|DomainContext(domain)| domain)
+        .unwrap_or_else(|| RwSignal::new(String::new()))
+}
+
+/// CSS class colour-coding a card or badge by domain, e.g. `domain-bats`.
+/// Species seen in several domains (`"birds,bats"`) take the first.
+pub fn domain_class(domain: &str) -> String {
+    let first = domain.split(',').next().unwrap_or_default().trim();
+    let slug: String = first
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("domain-{slug}")
+}
+
+/// `search` (`"?a=1&domain=x"` or `""`) with `domain` set, or removed
+/// when empty.
+#[cfg(feature = "hydrate")]
+fn with_domain(search: &str, domain: &str) -> String {
+    let mut params: Vec<String> = search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("domain="))
+        .map(String::from)
+        .collect();
+    if !domain.is_empty() {
+        let encoded: String = domain
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{b:02X}"),
+            })
+            .collect();
+        params.push(format!("domain={encoded}"));
+    }
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("?{}", params.join("&"))
+    }
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Domain picker; only rendered when detections span ≥ 2 domains.
+#[component]
+pub fn DomainFilter() -> impl IntoView {
+    let DomainContext(domain) = expect_context::<DomainContext>();
+    let query = use_query_map();
+    let domains = Resource::new(|| (), |_| async { get_available_domains().await });
+
+    // Read `?domain=` while rendering, so server-rendered pages are
+    // already filtered.
+    if let Some(d) = query.with_untracked(|q| q.get("domain")) {
+        domain.set(d);
+    }
+
+    // Point the address bar at the current page with the selection,
+    // without adding a history entry.
+    #[cfg(feature = "hydrate")]
+    let sync_url = {
+        let navigate = leptos_router::hooks::use_navigate();
+        move |selected: &str| {
+            let Some(location) = web_sys::window().map(|w| w.location()) else {
+                return;
+            };
+            let (Ok(path), Ok(search)) = (location.pathname(), location.search()) else {
+                return;
+            };
+            navigate(
+                &format!("{path}{}", with_domain(&search, selected)),
+                leptos_router::NavigateOptions {
+                    replace: true,
+                    ..Default::default()
+                },
+            );
+        }
+    };
+
+    // A link with `?domain=` selects that domain; a link without one
+    // (e.g. the nav bar) keeps the current selection.
+    #[cfg(feature = "hydrate")]
+    {
+        let sync_url = sync_url.clone();
+        Effect::new(move |_| {
+            let from_url = query.with(|q| q.get("domain")).unwrap_or_default();
+            let current = domain.get_untracked();
+            if !from_url.is_empty() {
+                if from_url != current {
+                    domain.set(from_url);
+                }
+            } else if !current.is_empty() {
+                sync_url(&current);
+            }
+        });
+    }
+
+    let on_change = move |ev: leptos::ev::Event| {
+        let selected = event_target_value(&ev);
+        #[cfg(feature = "hydrate")]
+        sync_url(&selected);
+        domain.set(selected);
+    };
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || domains.get().map(|res| match res {
+                Ok(list) if list.len() >= 2 => {
+                    let items = list.clone();
+                    view! {
+                        <select
+                            class="domain-filter"
+                            title="Show detections of one domain"
+                            prop:value=move || domain.get()
+                            on:change=on_change.clone()
+                        >
+                            <option value="">"All domains"</option>
+                            <For
+                                each=move || items.clone()
+                                key=|d| d.clone()
+                                children=move |d: String| {
+                                    let label = d.clone();
+                                    view! { <option value=d>{label}</option> }
+                                }
+                            />
+                        </select>
+                    }.into_any()
+                }
+                _ => ().into_any(),
+            })}
+        </Suspense>
+    }
+}
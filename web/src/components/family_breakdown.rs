@@ -0,0 +1,76 @@
+//! Family breakdown panel – today's detections grouped by taxonomic
+//! family, from the `species` reference table written by processing.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, ReadSignal, Resource, ServerFnError, Suspense};
+
+use crate::components::domain_filter::use_domain;
+use crate::model::FamilyCount;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_family_counts(
+    model_slug: String,
+    domain: String,
+) -> Result<Vec<FamilyCount>, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, kv};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let today = kv::today_for_tz().await;
+    let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    ddb::family_counts_for_date(&state.db_path, &today, slug_opt, domain_opt)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Compact panel listing today's families; hidden until at least one
+/// detected species has family information.
+#[component]
+pub fn FamilyBreakdown(model_slug: ReadSignal<String>) -> impl IntoView {
+    let domain = use_domain();
+    let data = Resource::new(move || (model_slug.get(), domain.get()), |(slug, domain)| async move {
+        get_family_counts(slug, domain).await
+    });
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || data.get().map(|res| match res {
+                Ok(items) if items.iter().any(|f| f.family.is_some()) => {
+                    view! {
+                        <div class="family-breakdown">
+                            <h3>"Today's Families"</h3>
+                            <ul class="family-list">
+                                {items.into_iter().map(|item| {
+                                    let name = match (&item.family_common_name, &item.family) {
+                                        (Some(com), _) => com.clone(),
+                                        (None, Some(sci)) => sci.clone(),
+                                        (None, None) => "Unclassified".to_string(),
+                                    };
+                                    let title = [item.order.clone(), item.family.clone()]
+                                        .into_iter()
+                                        .flatten()
+                                        .collect::<Vec<_>>()
+                                        .join(" › ");
+                                    view! {
+                                        <li class="family-item" title=title>
+                                            <span class="family-name">{name}</span>
+                                            <span class="family-species">
+                                                {format!("{} sp.", item.species_count)}
+                                            </span>
+                                            <span class="family-count">{item.detection_count}</span>
+                                        </li>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                        </div>
+                    }.into_any()
+                }
+                _ => view! { <div></div> }.into_any(),
+            })}
+        </Suspense>
+    }
+}
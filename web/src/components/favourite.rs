@@ -0,0 +1,110 @@
+//! Star button on a detection card – keeps the clip in the user's
+//! favourites (`/favourites`).
+//!
+//! Favourites belong to the user an authenticating proxy names (see
+//! `server::audit`); without one the station shares a single collection.
+//! `<App/>` loads the starred ids once into a [`FavouritesContext`] so the
+//! cards of a page don't each ask the server.
+
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+/// The user making the request, `""` without an authenticating proxy.
+#[cfg(feature = "ssr")]
+pub fn request_user() -> String {
+    use_context::<http::request::Parts>()
+        .and_then(|parts| crate::server::audit::proxy_user(&parts.headers))
+        .unwrap_or_default()
+}
+
+/// Ids of the detections the user starred.
+#[server(prefix = "/api")]
+pub async fn get_favourite_ids() -> Result<Vec<i64>, ServerFnError> {
+    let favourites = crate::server::kv::get_favourites(&request_user())
+        .await
+        .map_err(ServerFnError::new)?;
+    Ok(favourites.into_keys().collect())
+}
+
+/// Star or unstar detection `id`.
+#[server(prefix = "/api")]
+pub async fn set_favourite(id: i64, starred: bool) -> Result<(), ServerFnError> {
+    crate::server::kv::set_favourite(&request_user(), id, starred)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+// ─── Context ─────────────────────────────────────────────────────────────────
+
+/// Ids of the detections the user starred, shared through context.
+#[derive(Debug, Clone, Copy)]
+pub struct FavouritesContext(pub RwSignal<HashSet<i64>>);
+
+/// Provide the [`FavouritesContext`], filled from the server once the
+/// page is running in the browser.
+pub fn provide_favourites() {
+    let ids = RwSignal::new(HashSet::new());
+    provide_context(FavouritesContext(ids));
+
+    #[cfg(feature = "hydrate")]
+    leptos::task::spawn_local(async move {
+        if let Ok(list) = get_favourite_ids().await {
+            ids.set(list.into_iter().collect());
+        }
+    });
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// "☆" / "★" toggle for detection `id`.  The star changes at once and is
+/// put back if the server refuses.
+#[component]
+pub fn FavouriteButton(id: i64) -> impl IntoView {
+    let ids = use_context::<FavouritesContext>()
+        .map(|FavouritesContext(ids)| ids)
+        .unwrap_or_else(|| RwSignal::new(HashSet::new()));
+    let (error, set_error) = signal(Option::<String>::None);
+    let starred = move || ids.with(|ids| ids.contains(&id));
+
+    let toggle = move |star: bool| {
+        ids.update(|ids| {
+            if star {
+                ids.insert(id);
+            } else {
+                ids.remove(&id);
+            }
+        });
+    };
+    let on_click = move |_| {
+        let star = !starred();
+        toggle(star);
+        leptos::task::spawn_local(async move {
+            match set_favourite(id, star).await {
+                Ok(()) => set_error.set(None),
+                Err(e) => {
+                    toggle(!star);
+                    set_error.set(Some(e.to_string()));
+                }
+            }
+        });
+    };
+
+    view! {
+        <button
+            class="favourite-btn"
+            class:starred=starred
+            title=move || match (error.get(), starred()) {
+                (Some(e), _) => format!("Could not save: {e}"),
+                (None, true) => "Remove from favourites".to_string(),
+                (None, false) => "Add to favourites".to_string(),
+            }
+            on:click=on_click
+        >
+            {move || if starred() { "★" } else { "☆" }}
+        </button>
+    }
+}
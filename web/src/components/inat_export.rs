@@ -0,0 +1,101 @@
+//! "Export to iNaturalist" action on a detection card.
+//!
+//! Creates an observation (species, time, station location) with the
+//! audio clip attached as a sound, using the token in `GAIA_INAT_TOKEN`.
+//! Each clip is exported at most once; clicking again returns the
+//! existing observation.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+/// Export one detection; returns the observation URL.
+#[server(prefix = "/api")]
+pub async fn export_to_inaturalist(id: i64, file_name: String) -> Result<String, ServerFnError> {
+    use crate::server::{clips, detections_duckdb as ddb, inaturalist, kv};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+
+    if let Some(obs) = kv::get_inat_export(&file_name).await.map_err(ServerFnError::new)? {
+        return Ok(inaturalist::observation_url(obs));
+    }
+    let token = inaturalist::submission_token().ok_or_else(|| {
+        ServerFnError::new("iNaturalist export is not configured (set GAIA_INAT_TOKEN)")
+    })?;
+    let (det, lat, lon) = ddb::detection_for_export(&state.db_path, id, &file_name)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?
+        .ok_or_else(|| ServerFnError::new("Detection not found"))?;
+
+    let draft = inaturalist::ObservationDraft {
+        scientific_name: det.scientific_name.clone(),
+        observed_on: format!("{} {}", det.display_date, det.display_time),
+        latitude: lat,
+        longitude: lon,
+        description: format!(
+            "Acoustic detection by {} ({:.0}% confidence), recorded by Gaia Audio station {}.",
+            det.model_label(),
+            det.confidence * 100.0,
+            det.source_label()
+        ),
+    };
+    let clip_dir_name = det.original_common_name.as_deref().unwrap_or(&det.common_name);
+    let clip = clips::clip_for_upload(&state.extracted_dir, &det.date, clip_dir_name, &file_name);
+    if clip.is_none() {
+        tracing::warn!("No clip on disk for {file_name}; exporting without sound");
+    }
+    let obs = inaturalist::submit_observation(&token, &draft, clip.as_deref())
+        .await
+        .map_err(ServerFnError::new)?;
+    kv::save_inat_export(&file_name, obs).await.map_err(ServerFnError::new)?;
+    tracing::info!("Exported {} ({file_name}) to iNaturalist as observation {obs}", det.scientific_name);
+    Ok(inaturalist::observation_url(obs))
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Button that exports the detection, then turns into a link to the
+/// observation.
+#[component]
+pub fn InatExportButton(id: i64, file_name: String) -> impl IntoView {
+    let (busy, set_busy) = signal(false);
+    let (result, set_result) = signal(Option::<Result<String, String>>::None);
+    let file_name = StoredValue::new(file_name);
+
+    let on_click = move |_| {
+        set_busy.set(true);
+        leptos::task::spawn_local(async move {
+            let res = export_to_inaturalist(id, file_name.get_value())
+                .await
+                .map_err(|e| e.to_string());
+            set_result.set(Some(res));
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <span class="inat-export">
+            {move || match result.get() {
+                Some(Ok(url)) => view! {
+                    <a class="inat-export-link" href=url target="_blank" rel="noopener">
+                        "✔ On iNaturalist"
+                    </a>
+                }.into_any(),
+                other => view! {
+                    <button
+                        class="inat-export-btn"
+                        title="Create an iNaturalist observation with this clip"
+                        disabled=move || busy.get()
+                        on:click=on_click
+                    >
+                        {move || if busy.get() { "Exporting…" } else { "↗ iNaturalist" }}
+                    </button>
+                    {other.and_then(|r| r.err()).map(|e| view! {
+                        <span class="inat-export-error">{e}</span>
+                    })}
+                }.into_any(),
+            }}
+        </span>
+    }
+}
@@ -0,0 +1,88 @@
+//! Input level meter – RMS and peak of the capture node's last finished
+//! segment, with a warning when the input clips or is dead, so the gain
+//! can be set from the browser.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, Resource, ServerFnError, Suspense};
+
+use crate::model::{InputLevels, LevelStatus};
+
+/// Lowest level shown on the meter (dBFS).
+const METER_FLOOR_DBFS: f64 = -90.0;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_input_levels() -> Result<InputLevels, ServerFnError> {
+    crate::server::capture_api::get_json("/api/levels")
+        .await
+        .map_err(ServerFnError::new)
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Meter bar width (percent) for a level in dBFS.
+fn meter_pct(dbfs: f64) -> f64 {
+    ((dbfs - METER_FLOOR_DBFS) / -METER_FLOOR_DBFS * 100.0).clamp(0.0, 100.0)
+}
+
+/// Compact panel with the current input level; refreshed every 15 s.
+#[component]
+pub fn InputLevel() -> impl IntoView {
+    #[allow(unused_variables)] // written only in the hydrate (WASM) build
+    let (tick, set_tick) = signal(0u32);
+    let data = Resource::new(move || tick.get(), |_| async { get_input_levels().await });
+
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        let cb = Closure::wrap(Box::new(move || set_tick.update(|n| *n += 1)) as Box<dyn Fn()>);
+        let _ = web_sys::window()
+            .unwrap()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                cb.as_ref().unchecked_ref(),
+                15_000,
+            );
+        cb.forget();
+    }
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || data.get().map(|res| match res {
+                Ok(l) => {
+                    let (class, message) = match l.status {
+                        LevelStatus::Ok => ("level-ok", "Input level OK"),
+                        LevelStatus::Clipping => ("level-warn", "Clipping – reduce the gain"),
+                        LevelStatus::Quiet => ("level-warn", "Very quiet – increase the gain"),
+                        LevelStatus::Flatline => ("level-bad", "No signal – check the microphone"),
+                    };
+                    let rms_width = format!("width: {:.0}%", meter_pct(l.rms_dbfs));
+                    let peak_left = format!("left: {:.0}%", meter_pct(l.peak_dbfs));
+                    view! {
+                        <div class="input-level" title=l.filename.clone()>
+                            <h3>"Input Level"</h3>
+                            <div class="level-meter">
+                                <div class="level-rms" style=rms_width></div>
+                                <div class="level-peak" style=peak_left></div>
+                            </div>
+                            <div class="level-values">
+                                <span>{format!("RMS {:.1} dBFS", l.rms_dbfs)}</span>
+                                <span>{format!("Peak {:.1} dBFS", l.peak_dbfs)}</span>
+                            </div>
+                            <p class=format!("level-status {class}")>
+                                {message}
+                                {(l.clipped_fraction > 0.0).then(|| {
+                                    format!(" ({:.2}% clipped)", l.clipped_fraction * 100.0)
+                                })}
+                            </p>
+                        </div>
+                    }.into_any()
+                }
+                // No capture node configured or nothing recorded yet.
+                Err(_) => view! { <div></div> }.into_any(),
+            })}
+        </Suspense>
+    }
+}
+
@@ -0,0 +1,402 @@
+//! Display localisation: the language dates and times are written in,
+//! the time zone they are shown in and the clock (12 or 24 hours).
+//!
+//! The database keeps UTC, and the server renders the station's time
+//! (`tz_offset`) as `YYYY-MM-DD HH:MM:SS`.  Once the app has hydrated,
+//! dates and times are re-written in the browser with
+//! `Intl.DateTimeFormat` according to the [`LocalePrefs`] in the
+//! [`LocaleContext`] provided by `<App/>`.  Like the theme, the
+//! preferences belong to the browser (`localStorage`) and are chosen on
+//! the Settings page ([`LocalePanel`]).
+//!
+//! The UI shows no lengths, speeds or temperatures, so the clock is the
+//! only unit to choose.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView};
+use serde::{Deserialize, Serialize};
+
+/// `localStorage` key holding the JSON-encoded [`LocalePrefs`].
+pub const STORAGE_KEY: &str = "gaia-locale";
+
+/// `locale` value writing dates the way the server does.
+pub const ISO: &str = "iso";
+
+/// Languages offered in the settings; any BCP 47 tag can be typed.
+const SUGGESTED_LOCALES: &[(&str, &str)] = &[
+    ("en-GB", "English (UK)"),
+    ("en-US", "English (US)"),
+    ("es", "Español"),
+    ("pt-BR", "Português (Brasil)"),
+    ("fr", "Français"),
+    ("de", "Deutsch"),
+    ("nl", "Nederlands"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Clock {
+    /// Whatever the language uses.
+    #[default]
+    Auto,
+    H24,
+    H12,
+}
+
+impl Clock {
+    fn as_str(self) -> &'static str {
+        match self {
+            Clock::Auto => "auto",
+            Clock::H24 => "h24",
+            Clock::H12 => "h12",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "h24" => Clock::H24,
+            "h12" => Clock::H12,
+            _ => Clock::Auto,
+        }
+    }
+}
+
+/// How this browser shows dates and times.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LocalePrefs {
+    /// BCP 47 language tag, `""` for the browser's language or [`ISO`].
+    #[serde(default)]
+    pub locale: String,
+    /// `""` for station time (`tz_offset`), `"browser"` for the browser's
+    /// zone, otherwise an IANA zone such as `America/Costa_Rica`.
+    #[serde(default)]
+    pub time_zone: String,
+    #[serde(default)]
+    pub clock: Clock,
+}
+
+/// The display preferences and the station offset they may need.
+#[derive(Debug, Clone, Copy)]
+pub struct LocaleContext {
+    /// `None` until the stored preferences are read after hydration; the
+    /// server's strings are shown until then.
+    pub prefs: RwSignal<Option<LocalePrefs>>,
+    /// Station `tz_offset` in hours, for station time.
+    pub tz_offset: RwSignal<i32>,
+}
+
+impl LocaleContext {
+    /// A calendar day (`YYYY-MM-DD`).  Days are not moved between zones.
+    pub fn date(&self, ymd: &str) -> String {
+        let Some(prefs) = self.prefs.get() else {
+            return ymd.to_string();
+        };
+        let Some(ms) = epoch_ms(ymd, "00:00:00") else {
+            return ymd.to_string();
+        };
+        intl_format(&prefs, ms, "UTC", Style::Date).unwrap_or_else(|| ymd.to_string())
+    }
+
+    /// The heading of `month` (1–12) in `year`; `fallback` is the
+    /// server's.
+    pub fn month(&self, year: i32, month: u32, fallback: &str) -> String {
+        let Some(prefs) = self.prefs.get() else {
+            return fallback.to_string();
+        };
+        let Some(ms) = epoch_ms(&format!("{year:04}-{month:02}-01"), "00:00") else {
+            return fallback.to_string();
+        };
+        intl_format(&prefs, ms, "UTC", Style::Month).unwrap_or_else(|| fallback.to_string())
+    }
+
+    /// A moment stored as UTC `date` and `time`.  `fallback` is what the
+    /// server rendered for it.
+    pub fn moment(&self, date: &str, time: &str, fallback: &str) -> String {
+        let Some(prefs) = self.prefs.get() else {
+            return fallback.to_string();
+        };
+        let Some(ms) = epoch_ms(date, time) else {
+            return fallback.to_string();
+        };
+        let (ms, zone) = match prefs.time_zone.as_str() {
+            "" => (ms + self.tz_offset.get() as f64 * 3_600_000.0, "UTC"),
+            "browser" => (ms, ""),
+            zone => (ms, zone),
+        };
+        intl_format(&prefs, ms, zone, Style::DateTime).unwrap_or_else(|| fallback.to_string())
+    }
+
+    /// A detection's or recording's time, from its UTC `date` / `time`
+    /// and the station-time `display_date` / `display_time`.
+    pub fn detection_time(
+        &self,
+        date: &str,
+        time: &str,
+        display_date: &str,
+        display_time: &str,
+    ) -> String {
+        let fallback = if display_date.is_empty() {
+            format!("{date} {time}")
+        } else {
+            format!("{display_date} {display_time}")
+        };
+        self.moment(date, time, &fallback)
+    }
+
+    /// A UTC `YYYY-MM-DD HH:MM:SS` timestamp such as an audit entry's.
+    pub fn utc_timestamp(&self, at: &str) -> String {
+        let (date, time) = at.split_once(' ').unwrap_or((at, ""));
+        self.moment(date, time, &format!("{at} UTC"))
+    }
+}
+
+/// The [`LocaleContext`] provided by `<App/>`, or one showing the
+/// server's strings.
+pub fn use_locale() -> LocaleContext {
+    use_context::<LocaleContext>().unwrap_or_else(|| LocaleContext {
+        prefs: RwSignal::new(None),
+        tz_offset: RwSignal::new(0),
+    })
+}
+
+/// Provide the [`LocaleContext`], reading the stored preferences and the
+/// station offset once the page is running in the browser.
+pub fn provide_locale() {
+    let ctx = LocaleContext {
+        prefs: RwSignal::new(None),
+        tz_offset: RwSignal::new(0),
+    };
+    provide_context(ctx);
+
+    // After hydration, so the first render matches the server's.
+    #[cfg(feature = "hydrate")]
+    {
+        Effect::new(move |_| ctx.prefs.set(Some(browser::load().unwrap_or_default())));
+        leptos::task::spawn_local(async move {
+            if let Ok(offset) = crate::components::live_analysis::get_tz_offset().await {
+                ctx.tz_offset.set(offset);
+            }
+        });
+    }
+}
+
+/// Milliseconds since 1970 of UTC `date` (`YYYY-MM-DD`) and `time`
+/// (`HH:MM` or `HH:MM:SS`).
+fn epoch_ms(date: &str, time: &str) -> Option<f64> {
+    let mut d = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, day) = (d.next()??, d.next()??, d.next()??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut t = time.split(':').map(|p| p.parse::<i64>().ok());
+    let (h, min) = (t.next()??, t.next()??);
+    let s = t.next().flatten().unwrap_or(0);
+
+    // Days from civil date (proleptic Gregorian), H. Hinnant's algorithm.
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Some(((days * 86_400 + h * 3600 + min * 60 + s) * 1000) as f64)
+}
+
+/// What [`intl_format`] writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Date,
+    DateTime,
+    /// Month and year, e.g. a calendar heading.
+    Month,
+}
+
+/// `ms` written per `prefs` in `zone` (`""` for the browser's).  `None`
+/// when the browser rejects the language or zone.
+#[cfg(feature = "hydrate")]
+fn intl_format(prefs: &LocalePrefs, ms: f64, zone: &str, style: Style) -> Option<String> {
+    // Swedish writes dates and times the ISO way.
+    let (locale, date_style) = match prefs.locale.as_str() {
+        ISO if style == Style::Month => return None,
+        ISO => ("sv-SE", "short"),
+        l => (l, "medium"),
+    };
+    let mut options = match style {
+        Style::Date => vec![("dateStyle", date_style)],
+        Style::DateTime => vec![("dateStyle", date_style), ("timeStyle", "medium")],
+        Style::Month => vec![("month", "long"), ("year", "numeric")],
+    };
+    if !zone.is_empty() {
+        options.push(("timeZone", zone));
+    }
+    match prefs.clock {
+        Clock::Auto => {}
+        Clock::H24 => options.push(("hourCycle", "h23")),
+        Clock::H12 => options.push(("hourCycle", "h12")),
+    }
+    browser::format(locale, ms, &options)
+}
+
+/// The server has no `Intl`; it renders its own strings.
+#[cfg(not(feature = "hydrate"))]
+fn intl_format(_prefs: &LocalePrefs, _ms: f64, _zone: &str, _style: Style) -> Option<String> {
+    None
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Settings panel choosing the language, time zone and clock of this
+/// browser.  Changes apply at once.
+#[component]
+pub fn LocalePanel() -> impl IntoView {
+    let ctx = use_locale();
+    let current = move || ctx.prefs.get().unwrap_or_default();
+
+    let update = move |change: &dyn Fn(&mut LocalePrefs)| {
+        let mut prefs = ctx.prefs.get_untracked().unwrap_or_default();
+        change(&mut prefs);
+        #[cfg(feature = "hydrate")]
+        browser::save(&prefs);
+        ctx.prefs.set(Some(prefs));
+    };
+
+    // Preview of the choices: the time now, `""` if the browser refuses.
+    let now = move || {
+        #[cfg(feature = "hydrate")]
+        let at = String::from(js_sys::Date::new_0().to_iso_string());
+        #[cfg(not(feature = "hydrate"))]
+        let at = String::new();
+        let (date, time) = at.split_once('T').unwrap_or_default();
+        ctx.moment(date, &time[..time.len().min(8)], "")
+    };
+    let rejected =
+        move || cfg!(feature = "hydrate") && ctx.prefs.get().is_some() && now().is_empty();
+
+    view! {
+        <div class="setting-group locale-panel">
+            <label class="setting-label">"Dates and Times"</label>
+            <p class="setting-help">
+                "How this browser shows dates and times. Stored in the browser, not on the station."
+            </p>
+
+            <label class="setting-label" for="locale-language">"Language"</label>
+            <input
+                id="locale-language"
+                class="setting-input"
+                list="locale-suggestions"
+                placeholder="Browser language"
+                prop:value=move || current().locale
+                on:change=move |ev| {
+                    let locale = event_target_value(&ev).trim().to_string();
+                    update(&|p| p.locale = locale.clone());
+                }
+            />
+            <datalist id="locale-suggestions">
+                <option value=ISO>"ISO 8601 (2026-05-01 06:30:00)"</option>
+                {SUGGESTED_LOCALES.iter().map(|(tag, name)| view! {
+                    <option value=*tag>{*name}</option>
+                }).collect::<Vec<_>>()}
+            </datalist>
+
+            <label class="setting-label" for="locale-zone">"Time zone"</label>
+            <div class="locale-zone">
+                <select
+                    id="locale-zone"
+                    class="setting-select"
+                    on:change=move |ev| {
+                        let zone = match event_target_value(&ev).as_str() {
+                            "station" => String::new(),
+                            "browser" => "browser".to_string(),
+                            _ => "UTC".to_string(),
+                        };
+                        update(&|p| p.time_zone = zone.clone());
+                    }
+                >
+                    <option value="station" prop:selected=move || current().time_zone.is_empty()>
+                        {move || format!("Station (UTC{:+})", ctx.tz_offset.get())}
+                    </option>
+                    <option value="browser" prop:selected=move || current().time_zone == "browser">"This browser's"</option>
+                    <option value="named" prop:selected=move || !matches!(current().time_zone.as_str(), "" | "browser")>
+                        "Named zone…"
+                    </option>
+                </select>
+                {move || (!matches!(current().time_zone.as_str(), "" | "browser")).then(|| view! {
+                    <input
+                        class="setting-input"
+                        placeholder="e.g. America/Costa_Rica"
+                        prop:value=move || current().time_zone
+                        on:change=move |ev| {
+                            let zone = event_target_value(&ev).trim().to_string();
+                            update(&|p| p.time_zone = if zone.is_empty() { "UTC".to_string() } else { zone.clone() });
+                        }
+                    />
+                })}
+            </div>
+
+            <label class="setting-label" for="locale-clock">"Clock"</label>
+            <select
+                id="locale-clock"
+                class="setting-select"
+                on:change=move |ev| {
+                    let clock = Clock::parse(&event_target_value(&ev));
+                    update(&|p| p.clock = clock);
+                }
+            >
+                <option value=Clock::Auto.as_str() prop:selected=move || current().clock == Clock::Auto>"As the language does"</option>
+                <option value=Clock::H24.as_str() prop:selected=move || current().clock == Clock::H24>"24-hour"</option>
+                <option value=Clock::H12.as_str() prop:selected=move || current().clock == Clock::H12>"12-hour"</option>
+            </select>
+
+            {move || if rejected() {
+                view! { <p class="settings-error">"This browser does not know that language or time zone."</p> }.into_any()
+            } else {
+                view! { <p class="setting-help">"Now: " {now}</p> }.into_any()
+            }}
+        </div>
+    }
+}
+
+#[cfg(feature = "hydrate")]
+mod browser {
+    use js_sys::{Array, Function, Object, Reflect};
+    use wasm_bindgen::{JsCast, JsValue};
+
+    use super::{LocalePrefs, STORAGE_KEY};
+
+    pub fn load() -> Option<LocalePrefs> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        serde_json::from_str(&storage.get_item(STORAGE_KEY).ok()??).ok()
+    }
+
+    pub fn save(prefs: &LocalePrefs) {
+        let Ok(json) = serde_json::to_string(prefs) else {
+            return;
+        };
+        if let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) {
+            let _ = storage.set_item(STORAGE_KEY, &json);
+        }
+    }
+
+    /// `Intl.DateTimeFormat(locale, options).format(ms)`.  Built through
+    /// `Reflect` so an unknown language or zone (a `RangeError`) comes
+    /// back as `None` instead of aborting.
+    pub fn format(locale: &str, ms: f64, options: &[(&str, &str)]) -> Option<String> {
+        let intl = Reflect::get(&js_sys::global(), &"Intl".into()).ok()?;
+        let ctor: Function = Reflect::get(&intl, &"DateTimeFormat".into())
+            .ok()?
+            .dyn_into()
+            .ok()?;
+        let opts = Object::new();
+        for (key, value) in options {
+            Reflect::set(&opts, &(*key).into(), &(*value).into()).ok()?;
+        }
+        let locales = if locale.is_empty() {
+            JsValue::UNDEFINED
+        } else {
+            locale.into()
+        };
+        let fmt = Reflect::construct(&ctor, &Array::of2(&locales, &opts)).ok()?;
+        let format: Function = Reflect::get(&fmt, &"format".into()).ok()?.dyn_into().ok()?;
+        format.call1(&fmt, &JsValue::from_f64(ms)).ok()?.as_string()
+    }
+}
@@ -1,8 +1,41 @@
 pub mod calendar_grid;
+pub mod connection;
+pub mod dataset_export;
 pub mod detection_card;
+pub mod detection_export;
+pub mod domain_filter;
+pub mod family_breakdown;
+pub mod favourite;
 pub mod hourly_chart;
+pub mod inat_export;
+pub mod input_level;
 pub mod live_analysis;
+pub mod locale;
 pub mod model_filter;
+pub mod moon_activity;
 pub mod nav;
+pub mod node_map;
+pub mod pipeline_activity;
+pub mod push_toggle;
+pub mod relabel;
+pub mod sparkline;
 pub mod species_card;
+pub mod spectrogram_viewer;
+pub mod stream_health;
+pub mod theme;
 pub mod urban_noise;
+pub mod waveform_player;
+pub mod weekly_digest;
+
+/// Percent-encode a query value.  `/` is kept, as query strings allow it
+/// and clip URLs stay readable.
+pub(crate) fn query_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,84 @@
+//! Moon activity panel – detections per active night in each moon phase,
+//! for spotting nocturnal species that respond to moonlight.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, ReadSignal, Resource, ServerFnError, Suspense};
+
+use crate::components::domain_filter::use_domain;
+use crate::model::MoonPhaseActivity;
+
+/// Moon glyphs for the eight phases, starting at new moon.
+const PHASE_ICONS: [&str; 8] = ["🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘"];
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_moon_activity(
+    model_slug: String,
+    domain: String,
+) -> Result<Vec<MoonPhaseActivity>, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    ddb::moon_phase_activity(&state.db_path, slug_opt, domain_opt)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Mean detections per night on which the phase saw any activity.
+fn per_night(p: &MoonPhaseActivity) -> f64 {
+    if p.nights == 0 {
+        0.0
+    } else {
+        p.detections as f64 / p.nights as f64
+    }
+}
+
+/// Bar chart of activity by moon phase; hidden until there are detections.
+#[component]
+pub fn MoonActivity(model_slug: ReadSignal<String>) -> impl IntoView {
+    let domain = use_domain();
+    let data = Resource::new(move || (model_slug.get(), domain.get()), |(slug, domain)| async move {
+        get_moon_activity(slug, domain).await
+    });
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || data.get().map(|res| match res {
+                Ok(phases) if phases.iter().any(|p| p.detections > 0) => {
+                    let max = phases.iter().map(per_night).fold(0.0, f64::max);
+                    view! {
+                        <div class="moon-activity">
+                            <h3>"Activity by Moon Phase"</h3>
+                            <div class="moon-chart">
+                                {phases.into_iter().map(|p| {
+                                    let rate = per_night(&p);
+                                    let height = format!("height: {:.0}%", rate / max * 100.0);
+                                    let title = format!(
+                                        "{}: {} detections over {} nights ({:.1}/night)",
+                                        p.label, p.detections, p.nights, rate,
+                                    );
+                                    let icon = PHASE_ICONS[usize::from(p.phase) % 8];
+                                    view! {
+                                        <div class="moon-bar" title=title>
+                                            <div class="moon-bar-track">
+                                                <div class="moon-bar-fill" style=height></div>
+                                            </div>
+                                            <span class="moon-icon">{icon}</span>
+                                        </div>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                            <p class="moon-caption">"Detections per active night"</p>
+                        </div>
+                    }.into_any()
+                }
+                _ => view! { <div></div> }.into_any(),
+            })}
+        </Suspense>
+    }
+}
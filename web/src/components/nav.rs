@@ -3,6 +3,9 @@
 use leptos::prelude::*;
 use leptos::prelude::{ElementChild, IntoView};
 
+use crate::components::domain_filter::DomainFilter;
+use crate::components::theme::ThemeToggle;
+
 /// Site-wide navigation bar.
 #[component]
 pub fn Nav() -> impl IntoView {
@@ -14,12 +17,21 @@ pub fn Nav() -> impl IntoView {
             <div class="nav-links">
                 <a href="/" class="nav-link">"Live Feed"</a>
                 <a href="/calendar" class="nav-link">"Calendar"</a>
+                <a href="/compare" class="nav-link">"Compare"</a>
                 <a href="/species" class="nav-link">"Species"</a>
+                <a href="/seasonal" class="nav-link">"Seasons"</a>
+                <a href="/richness" class="nav-link">"Richness"</a>
+                <a href="/map" class="nav-link">"Map"</a>
+                <a href="/stations" class="nav-link">"Stations"</a>
+                <a href="/favourites" class="nav-link">"Favourites"</a>
                 <a href="/learning" class="nav-link">"Learning"</a>
+                <a href="/review" class="nav-link">"Review"</a>
                 <a href="/excluded" class="nav-link">"Excluded"</a>
                 <a href="/import" class="nav-link">"Import"</a>
                 <a href="/settings" class="nav-link">"Settings"</a>
             </div>
+            <DomainFilter/>
+            <ThemeToggle/>
         </nav>
     }
 }
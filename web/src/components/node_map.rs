@@ -0,0 +1,337 @@
+//! Map of capture nodes over OpenStreetMap tiles.
+//!
+//! Drawn as plain SVG – tiles as `<image>`s, nodes as circles – so it
+//! renders on the server and hydrates without a JavaScript map library.
+//! Nodes closer than [`CLUSTER_PX`] at the current zoom are merged into
+//! one marker; clicking it zooms in on them.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, Resource, ServerFnError, Suspense};
+
+use crate::model::NodeLocation;
+
+const MAP_WIDTH: f64 = 640.0;
+const MAP_HEIGHT: f64 = 360.0;
+const TILE_SIZE: f64 = 256.0;
+const TILE_URL: &str = "https://tile.openstreetmap.org";
+const MIN_ZOOM: u8 = 1;
+const MAX_ZOOM: u8 = 17;
+/// Zoom for a single node, or several at the same spot.
+const SINGLE_NODE_ZOOM: u8 = 13;
+/// Markers closer than this (in map pixels) are clustered.
+const CLUSTER_PX: f64 = 36.0;
+/// Space kept free around the nodes when fitting the view.
+const FIT_MARGIN_PX: f64 = 48.0;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+/// Located nodes, optionally of one domain (`""` = all) and species.
+#[server(prefix = "/api")]
+pub async fn get_node_locations(
+    domain: String,
+    scientific_name: String,
+) -> Result<Vec<NodeLocation>, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let domain_opt = (!domain.is_empty()).then_some(domain.as_str());
+    let species_opt = (!scientific_name.is_empty()).then_some(scientific_name.as_str());
+    ddb::node_locations(&state.db_path, domain_opt, species_opt)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Web Mercator ────────────────────────────────────────────────────────────
+
+/// World pixel coordinates of `lat`/`lon` at `zoom`.
+fn project(lat: f64, lon: f64, zoom: u8) -> (f64, f64) {
+    let world = TILE_SIZE * f64::from(1u32 << zoom);
+    let lat = lat.clamp(-85.0511, 85.0511).to_radians();
+    let x = (lon + 180.0) / 360.0 * world;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / std::f64::consts::PI) / 2.0 * world;
+    (x, y)
+}
+
+/// Inverse of [`project`].
+fn unproject(x: f64, y: f64, zoom: u8) -> (f64, f64) {
+    let world = TILE_SIZE * f64::from(1u32 << zoom);
+    let lon = x / world * 360.0 - 180.0;
+    let n = std::f64::consts::PI * (1.0 - 2.0 * y / world);
+    (n.sinh().atan().to_degrees(), lon)
+}
+
+/// What the map shows: its centre and zoom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MapView {
+    lat: f64,
+    lon: f64,
+    zoom: u8,
+}
+
+impl MapView {
+    /// The closest view showing every node.
+    fn fit(nodes: &[NodeLocation]) -> Self {
+        if nodes.is_empty() {
+            return Self {
+                lat: 0.0,
+                lon: 0.0,
+                zoom: MIN_ZOOM,
+            };
+        }
+        let (mut south, mut north, mut west, mut east) = (90.0f64, -90.0f64, 180.0f64, -180.0f64);
+        for n in nodes {
+            south = south.min(n.lat);
+            north = north.max(n.lat);
+            west = west.min(n.lon);
+            east = east.max(n.lon);
+        }
+        let (lat, lon) = ((south + north) / 2.0, (west + east) / 2.0);
+        let zoom = (MIN_ZOOM..=SINGLE_NODE_ZOOM)
+            .rev()
+            .find(|&z| {
+                let (x0, y0) = project(north, west, z);
+                let (x1, y1) = project(south, east, z);
+                x1 - x0 <= MAP_WIDTH - 2.0 * FIT_MARGIN_PX
+                    && y1 - y0 <= MAP_HEIGHT - 2.0 * FIT_MARGIN_PX
+            })
+            .unwrap_or(MIN_ZOOM);
+        Self { lat, lon, zoom }
+    }
+
+    /// World pixel coordinates of the map's top-left corner.
+    fn origin(&self) -> (f64, f64) {
+        let (x, y) = project(self.lat, self.lon, self.zoom);
+        (x - MAP_WIDTH / 2.0, y - MAP_HEIGHT / 2.0)
+    }
+
+    /// Map pixel coordinates of `lat`/`lon`.
+    fn to_map(&self, lat: f64, lon: f64) -> (f64, f64) {
+        let (ox, oy) = self.origin();
+        let (x, y) = project(lat, lon, self.zoom);
+        (x - ox, y - oy)
+    }
+
+    fn zoomed(self, by: i8) -> Self {
+        let zoom = (self.zoom as i8 + by).clamp(MIN_ZOOM as i8, MAX_ZOOM as i8) as u8;
+        Self { zoom, ..self }
+    }
+
+    /// `(url, x, y)` of every tile covering the map.
+    fn tiles(&self) -> Vec<(String, f64, f64)> {
+        let (ox, oy) = self.origin();
+        let count = 1i64 << self.zoom;
+        let first_x = (ox / TILE_SIZE).floor() as i64;
+        let last_x = ((ox + MAP_WIDTH) / TILE_SIZE).floor() as i64;
+        let first_y = ((oy / TILE_SIZE).floor() as i64).max(0);
+        let last_y = (((oy + MAP_HEIGHT) / TILE_SIZE).floor() as i64).min(count - 1);
+        let mut tiles = Vec::new();
+        for ty in first_y..=last_y {
+            for tx in first_x..=last_x {
+                // Wrap around the antimeridian.
+                let wrapped = tx.rem_euclid(count);
+                tiles.push((
+                    format!("{TILE_URL}/{}/{wrapped}/{ty}.png", self.zoom),
+                    tx as f64 * TILE_SIZE - ox,
+                    ty as f64 * TILE_SIZE - oy,
+                ));
+            }
+        }
+        tiles
+    }
+}
+
+/// One marker: a node, or several close together.
+#[derive(Debug, Clone)]
+struct Cluster {
+    x: f64,
+    y: f64,
+    nodes: Vec<NodeLocation>,
+}
+
+impl Cluster {
+    fn detections(&self) -> u64 {
+        self.nodes.iter().map(|n| n.detections).sum()
+    }
+
+    /// Centre of the clustered nodes.
+    fn centre(&self) -> (f64, f64) {
+        let n = self.nodes.len().max(1) as f64;
+        (
+            self.nodes.iter().map(|n| n.lat).sum::<f64>() / n,
+            self.nodes.iter().map(|n| n.lon).sum::<f64>() / n,
+        )
+    }
+}
+
+/// Group the nodes visible in `view` greedily, busiest first: each node
+/// joins the first marker within [`CLUSTER_PX`] or starts its own.
+fn cluster(nodes: &[NodeLocation], view: &MapView) -> Vec<Cluster> {
+    let mut sorted: Vec<&NodeLocation> = nodes.iter().collect();
+    sorted.sort_by(|a, b| b.detections.cmp(&a.detections));
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for node in sorted {
+        let (x, y) = view.to_map(node.lat, node.lon);
+        if !(-CLUSTER_PX..=MAP_WIDTH + CLUSTER_PX).contains(&x)
+            || !(-CLUSTER_PX..=MAP_HEIGHT + CLUSTER_PX).contains(&y)
+        {
+            continue;
+        }
+        match clusters
+            .iter_mut()
+            .find(|c| (c.x - x).hypot(c.y - y) < CLUSTER_PX)
+        {
+            Some(c) => c.nodes.push(node.clone()),
+            None => clusters.push(Cluster {
+                x,
+                y,
+                nodes: vec![node.clone()],
+            }),
+        }
+    }
+    clusters
+}
+
+// ─── Components ──────────────────────────────────────────────────────────────
+
+/// Map of `nodes` with zoom buttons; starts fitted to all of them.
+#[component]
+pub fn NodeMap(nodes: Vec<NodeLocation>) -> impl IntoView {
+    let initial = MapView::fit(&nodes);
+    let view_state = RwSignal::new(initial);
+    let nodes = StoredValue::new(nodes);
+
+    let markers = move || {
+        let current = view_state.get();
+        let max = nodes
+            .with_value(|n| n.iter().map(|n| n.detections).max().unwrap_or(1))
+            .max(1);
+        cluster(&nodes.get_value(), &current)
+            .into_iter()
+            .map(|c| {
+                let total = c.detections();
+                let radius = 7.0 + 11.0 * (total as f64).ln_1p() / (max as f64).ln_1p().max(1.0);
+                let (label, title) = if c.nodes.len() == 1 {
+                    let n = &c.nodes[0];
+                    (
+                        total.to_string(),
+                        format!(
+                            "{}: {} detection(s), {} species, last {}",
+                            n.node, n.detections, n.species, n.last_seen
+                        ),
+                    )
+                } else {
+                    let names: Vec<String> = c
+                        .nodes
+                        .iter()
+                        .map(|n| format!("{} ({})", n.node, n.detections))
+                        .collect();
+                    (
+                        format!("{}×", c.nodes.len()),
+                        format!("{} nodes, {total} detection(s): {}", c.nodes.len(), names.join(", ")),
+                    )
+                };
+                let class = if c.nodes.len() > 1 { "map-marker map-cluster" } else { "map-marker" };
+                let (lat, lon) = c.centre();
+                let zoom_in = c.nodes.len() > 1;
+                view! {
+                    <g
+                        class=class
+                        on:click=move |_| {
+                            if zoom_in {
+                                view_state.update(|v| *v = MapView { lat, lon, ..v.zoomed(2) });
+                            }
+                        }
+                    >
+                        <title>{title}</title>
+                        <circle cx=format!("{:.1}", c.x) cy=format!("{:.1}", c.y) r=format!("{radius:.1}") />
+                        <text x=format!("{:.1}", c.x) y=format!("{:.1}", c.y) dy="0.35em">{label}</text>
+                    </g>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let tiles = move || {
+        view_state
+            .get()
+            .tiles()
+            .into_iter()
+            .map(|(href, x, y)| {
+                view! {
+                    <image
+                        href=href
+                        x=format!("{x:.1}")
+                        y=format!("{y:.1}")
+                        width=TILE_SIZE.to_string()
+                        height=TILE_SIZE.to_string()
+                    />
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Pan by a third of the map in each direction.
+    let pan = move |dx: f64, dy: f64| {
+        view_state.update(|v| {
+            let (ox, oy) = v.origin();
+            let (lat, lon) = unproject(
+                ox + MAP_WIDTH / 2.0 + dx * MAP_WIDTH / 3.0,
+                oy + MAP_HEIGHT / 2.0 + dy * MAP_HEIGHT / 3.0,
+                v.zoom,
+            );
+            *v = MapView { lat, lon, ..*v };
+        })
+    };
+
+    view! {
+        <div class="node-map">
+            <svg
+                viewBox=format!("0 0 {MAP_WIDTH} {MAP_HEIGHT}")
+                role="img"
+                aria-label="Map of capture nodes"
+            >
+                <g class="map-tiles">{tiles}</g>
+                <g class="map-markers">{markers}</g>
+            </svg>
+            <div class="map-controls">
+                <button title="Zoom in" on:click=move |_| view_state.update(|v| *v = v.zoomed(1))>"+"</button>
+                <button title="Zoom out" on:click=move |_| view_state.update(|v| *v = v.zoomed(-1))>"−"</button>
+                <button title="Pan north" on:click=move |_| pan(0.0, -1.0)>"↑"</button>
+                <button title="Pan west" on:click=move |_| pan(-1.0, 0.0)>"←"</button>
+                <button title="Pan east" on:click=move |_| pan(1.0, 0.0)>"→"</button>
+                <button title="Pan south" on:click=move |_| pan(0.0, 1.0)>"↓"</button>
+                <button title="Show all nodes" on:click=move |_| view_state.set(initial)>"⤢"</button>
+            </div>
+            <p class="map-attribution">
+                "© "
+                <a href="https://www.openstreetmap.org/copyright" target="_blank" rel="noopener">
+                    "OpenStreetMap"
+                </a>
+                " contributors"
+            </p>
+        </div>
+    }
+}
+
+/// Where a species was detected, on its page.  Only shown once it has
+/// been detected at two or more located nodes.
+#[component]
+pub fn SpeciesMap(scientific_name: String) -> impl IntoView {
+    let nodes = Resource::new(
+        move || scientific_name.clone(),
+        |name| async move { get_node_locations(String::new(), name).await },
+    );
+    view! {
+        <Suspense fallback=|| ()>
+            {move || nodes.get().map(|res| match res {
+                Ok(nodes) if nodes.len() >= 2 => view! {
+                    <section class="species-map">
+                        <h2>"Stations"</h2>
+                        <NodeMap nodes=nodes />
+                    </section>
+                }.into_any(),
+                _ => ().into_any(),
+            })}
+        </Suspense>
+    }
+}
@@ -0,0 +1,196 @@
+//! System activity panel – what each analysis worker is doing and how
+//! long the queues are, pushed over the `/ws/pipeline` WebSocket so the
+//! dashboard shows the system is alive even when nothing is detected.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView};
+
+use crate::model::{PipelineStatus, WorkerActivity};
+
+/// Seconds without a message before the processing server is shown as
+/// not responding.  It refreshes its status at least every 10 s.
+const STALE_SECS: u32 = 30;
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+#[component]
+pub fn PipelineActivity() -> impl IntoView {
+    #[allow(unused_variables)] // written only in the hydrate (WASM) build
+    let (status, set_status) = signal::<Option<PipelineStatus>>(None);
+    // Seconds since the last message, counted in 5 s steps.
+    #[allow(unused_variables)]
+    let (age, set_age) = signal(0u32);
+
+    #[cfg(feature = "hydrate")]
+    {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let alive = Arc::new(AtomicBool::new(true));
+        socket::connect(set_status, set_age, alive.clone());
+
+        let cb = Closure::wrap(Box::new(move || set_age.update(|a| *a += 5)) as Box<dyn Fn()>);
+        let handle = web_sys::window()
+            .unwrap()
+            .set_interval_with_callback_and_timeout_and_arguments_0(cb.as_ref().unchecked_ref(), 5000)
+            .ok();
+        cb.forget();
+        on_cleanup(move || {
+            alive.store(false, Ordering::Relaxed);
+            if let (Some(window), Some(handle)) = (web_sys::window(), handle) {
+                window.clear_interval_with_handle(handle);
+            }
+        });
+    }
+
+    view! {
+        <div class="pipeline-activity">
+            <h3>"System Activity"</h3>
+            {move || match status.get() {
+                None => view! { <p class="text-muted">"Connecting to the processing server…"</p> }.into_any(),
+                Some(_) if age.get() > STALE_SECS => view! {
+                    <p class="level-bad">"✖ Processing server not responding"</p>
+                }.into_any(),
+                Some(st) => {
+                    let backlog: usize = st.node_backlog.iter().map(|b| b.pending).sum();
+                    view! {
+                        {st.extraction_paused.then(|| view! {
+                            <p class="level-bad">
+                                "⚠ Data volume nearly full — clip extraction paused, detections are still stored"
+                            </p>
+                        })}
+                        <ul>
+                            {st.workers.into_iter().map(|w| view! { <WorkerRow worker=w/> }).collect::<Vec<_>>()}
+                        </ul>
+                        <p class="activity-queues">
+                            {format!(
+                                "Queued: {} on capture nodes · {} downloading · {} waiting for a worker",
+                                backlog, st.download_queue, st.analysis_queue,
+                            )}
+                        </p>
+                        <p class="activity-total text-muted">
+                            {format!("{} recording(s) analysed since start", st.files_analysed)}
+                            {(st.chunk_cache_hits > 0).then(|| format!(
+                                " · {:.0}% of chunks repeated earlier audio (scored from cache)",
+                                st.chunk_cache_hit_rate() * 100.0,
+                            ))}
+                        </p>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn WorkerRow(worker: WorkerActivity) -> impl IntoView {
+    let label = format!("Worker {}", worker.worker + 1);
+    let Some(file) = worker.file else {
+        return view! {
+            <li class="activity-worker">
+                <span class="activity-label">{label}</span>
+                <span class="activity-state text-muted">"○ idle"</span>
+            </li>
+        }
+        .into_any();
+    };
+    let pct = if worker.chunks > 0 {
+        (worker.chunk * 100 / worker.chunks).min(100)
+    } else {
+        0
+    };
+    let title = if worker.source_node.is_empty() {
+        file.clone()
+    } else {
+        format!("{file} from {}", worker.source_node)
+    };
+    let model = worker.model.unwrap_or_else(|| "reading audio".into());
+
+    view! {
+        <li class="activity-worker" title=title>
+            <span class="activity-label">{label}</span>
+            <span class="activity-state level-ok">"● analysing"</span>
+            <span class="activity-file">{file}</span>
+            <div class="activity-progress">
+                <div class="activity-progress-bar" style=format!("width: {pct}%")></div>
+            </div>
+            <span class="activity-model text-muted">
+                {format!("{model} · chunk {}/{}", worker.chunk, worker.chunks)}
+            </span>
+        </li>
+    }
+    .into_any()
+}
+
+#[cfg(feature = "hydrate")]
+mod socket {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use leptos::prelude::*;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use crate::model::PipelineStatus;
+
+    /// Delay before reconnecting a dropped socket (e.g. web server restart).
+    const RECONNECT_MS: i32 = 5000;
+
+    /// Open `/ws/pipeline` and feed its messages into `set_status`,
+    /// reconnecting until `alive` is cleared by the component's cleanup.
+    pub fn connect(
+        set_status: WriteSignal<Option<PipelineStatus>>,
+        set_age: WriteSignal<u32>,
+        alive: Arc<AtomicBool>,
+    ) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let location = window.location();
+        let scheme = if location.protocol().as_deref() == Ok("https:") { "wss" } else { "ws" };
+        let Ok(host) = location.host() else {
+            return;
+        };
+        let Ok(ws) = web_sys::WebSocket::new(&format!("{scheme}://{host}/ws/pipeline")) else {
+            return;
+        };
+
+        let on_message = {
+            let alive = alive.clone();
+            let ws = ws.clone();
+            Closure::wrap(Box::new(move |ev: web_sys::MessageEvent| {
+                if !alive.load(Ordering::Relaxed) {
+                    let _ = ws.close();
+                    return;
+                }
+                let Some(text) = ev.data().as_string() else {
+                    return;
+                };
+                if let Ok(status) = serde_json::from_str::<PipelineStatus>(&text) {
+                    set_status.set(Some(status));
+                    set_age.set(0);
+                }
+            }) as Box<dyn FnMut(web_sys::MessageEvent)>)
+        };
+        ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget();
+
+        let on_close = Closure::wrap(Box::new(move || {
+            if !alive.load(Ordering::Relaxed) {
+                return;
+            }
+            let alive = alive.clone();
+            let retry = Closure::once_into_js(move || connect(set_status, set_age, alive));
+            if let Some(window) = web_sys::window() {
+                let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                    retry.unchecked_ref(),
+                    RECONNECT_MS,
+                );
+            }
+        }) as Box<dyn FnMut()>);
+        ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        on_close.forget();
+    }
+}
@@ -0,0 +1,202 @@
+//! "Notify me" button backed by the browser Push API.
+//!
+//! The browser side (service-worker registration, `PushManager.subscribe`)
+//! lives in a small inline JS shim; the resulting subscription is handed
+//! to the server, which stores it together with the species it targets.
+//! Notifications are rendered by `public/sw.js`.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+use serde::{Deserialize, Serialize};
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+/// VAPID public key (`applicationServerKey`) for `PushManager.subscribe`.
+#[server(prefix = "/api")]
+pub async fn get_push_public_key() -> Result<String, ServerFnError> {
+    crate::server::push::public_key()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Push error: {e}")))
+}
+
+/// Targets this browser's subscription is currently registered for.
+#[server(prefix = "/api")]
+pub async fn get_push_targets(endpoint: String) -> Result<Vec<String>, ServerFnError> {
+    crate::server::push::targets(&endpoint)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Push error: {e}")))
+}
+
+/// Turn notifications for one target on or off for this browser.
+#[server(prefix = "/api")]
+pub async fn set_push_target(
+    endpoint: String,
+    p256dh: String,
+    auth: String,
+    target: String,
+    enabled: bool,
+) -> Result<Vec<String>, ServerFnError> {
+    crate::server::push::set_target(&endpoint, &p256dh, &auth, &target, enabled)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Push error: {e}")))
+}
+
+// ─── Browser glue ────────────────────────────────────────────────────────────
+
+/// Push subscription as reported by the JS shim.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "hydrate"), allow(dead_code))]
+struct BrowserSubscription {
+    endpoint: String,
+    p256dh: String,
+    auth: String,
+}
+
+#[cfg(feature = "hydrate")]
+mod js {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen(inline_js = r#"
+function b64(buf) {
+  return btoa(String.fromCharCode(...new Uint8Array(buf)))
+    .replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+function unb64(s) {
+  s = s.replace(/-/g, '+').replace(/_/g, '/');
+  return Uint8Array.from(atob(s + '='.repeat((4 - s.length % 4) % 4)), c => c.charCodeAt(0));
+}
+function describe(sub) {
+  return sub ? JSON.stringify({ endpoint: sub.endpoint, p256dh: b64(sub.getKey('p256dh')), auth: b64(sub.getKey('auth')) }) : '';
+}
+export function push_supported() {
+  return window.isSecureContext && 'serviceWorker' in navigator && 'PushManager' in window;
+}
+export function push_current(cb) {
+  if (!push_supported()) { cb(''); return; }
+  navigator.serviceWorker.getRegistration('/')
+    .then(reg => reg ? reg.pushManager.getSubscription() : null)
+    .then(sub => cb(describe(sub)), () => cb(''));
+}
+export function push_subscribe(key, cb) {
+  Notification.requestPermission()
+    .then(p => {
+      if (p !== 'granted') throw new Error('notification permission was not granted');
+      return navigator.serviceWorker.register('/sw.js');
+    })
+    .then(() => navigator.serviceWorker.ready)
+    .then(reg => reg.pushManager.getSubscription()
+      .then(sub => sub || reg.pushManager.subscribe({ userVisibleOnly: true, applicationServerKey: unb64(key) })))
+    .then(sub => cb(describe(sub)), e => cb('error:' + e.message));
+}
+"#)]
+    extern "C" {
+        pub fn push_supported() -> bool;
+        fn push_current(cb: JsValue);
+        fn push_subscribe(key: &str, cb: JsValue);
+    }
+
+    /// Current subscription JSON, or `""` when there is none.
+    pub fn current(f: impl FnOnce(String) + 'static) {
+        push_current(Closure::once_into_js(f));
+    }
+
+    /// Subscribe (or reuse the existing subscription).  The callback gets
+    /// the subscription JSON or `"error:<message>"`.
+    pub fn subscribe(key: &str, f: impl FnOnce(String) + 'static) {
+        push_subscribe(key, Closure::once_into_js(f));
+    }
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Toggle push notifications for `target` — a scientific name, or one of
+/// the `PUSH_*` targets in [`crate::model`].
+#[component]
+pub fn PushToggle(
+    #[prop(into)] target: String,
+    #[prop(into)] label: String,
+) -> impl IntoView {
+    let (supported, set_supported) = signal(false);
+    let (enabled, set_enabled) = signal(false);
+    let (busy, set_busy) = signal(false);
+    let (status, set_status) = signal(Option::<String>::None);
+    let target = StoredValue::new(target);
+
+    #[cfg(feature = "hydrate")]
+    Effect::new(move |_| {
+        if !js::push_supported() {
+            return;
+        }
+        set_supported.set(true);
+        js::current(move |json| {
+            let Ok(sub) = serde_json::from_str::<BrowserSubscription>(&json) else { return };
+            leptos::task::spawn_local(async move {
+                if let Ok(targets) = get_push_targets(sub.endpoint).await {
+                    set_enabled.set(targets.contains(&target.get_value()));
+                }
+            });
+        });
+    });
+
+    let on_click = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            let enable = !enabled.get_untracked();
+            set_busy.set(true);
+            set_status.set(None);
+            let finish = move |json: String| {
+                let sub = match serde_json::from_str::<BrowserSubscription>(&json) {
+                    Ok(sub) => sub,
+                    Err(_) => {
+                        let msg = json.strip_prefix("error:").unwrap_or("no subscription").to_string();
+                        set_status.set(Some(format!("Cannot enable notifications: {msg}")));
+                        set_busy.set(false);
+                        return;
+                    }
+                };
+                leptos::task::spawn_local(async move {
+                    match set_push_target(sub.endpoint, sub.p256dh, sub.auth, target.get_value(), enable).await {
+                        Ok(targets) => set_enabled.set(targets.contains(&target.get_value())),
+                        Err(e) => set_status.set(Some(format!("Error: {e}"))),
+                    }
+                    set_busy.set(false);
+                });
+            };
+            if enable {
+                leptos::task::spawn_local(async move {
+                    match get_push_public_key().await {
+                        Ok(key) => js::subscribe(&key, finish),
+                        Err(e) => {
+                            set_status.set(Some(format!("Error: {e}")));
+                            set_busy.set(false);
+                        }
+                    }
+                });
+            } else {
+                js::current(finish);
+            }
+        }
+    };
+    #[cfg(not(feature = "hydrate"))]
+    let _ = (set_supported, set_enabled, set_busy, set_status, target);
+
+    view! {
+        <span class="push-toggle-wrap">
+            <button
+                class="push-toggle"
+                class:active=move || enabled.get()
+                title=move || if supported.get() {
+                    "Browser notifications"
+                } else {
+                    "Push notifications need HTTPS and a supporting browser"
+                }
+                disabled=move || busy.get() || !supported.get()
+                on:click=on_click
+            >
+                {move || if enabled.get() { "🔔 " } else { "🔕 " }}
+                {label}
+            </button>
+            {move || status.get().map(|s| view! { <span class="push-toggle-status">{s}</span> })}
+        </span>
+    }
+}
@@ -0,0 +1,208 @@
+//! Relabel control on a detection card – corrects the species of a
+//! misidentified detection, keeping the model's prediction for audit.
+//!
+//! The species is picked rather than typed: from the model's stored top
+//! predictions for the clip, or by searching the model's label set in
+//! any of its languages, so corrections match names already in the DB.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+use crate::model::SpeciesSuggestion;
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn relabel_detection(
+    id: i64,
+    file_name: String,
+    scientific_name: String,
+    common_name: String,
+    fine_tune: bool,
+) -> Result<(), ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    ddb::relabel_detection(&state.db_path, id, &file_name, &scientific_name, &common_name, fine_tune)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Relabel failed: {e}")))?;
+    let tag = if fine_tune { ", fine-tune" } else { "" };
+    let details = format!("→ {}{tag}", scientific_name.trim());
+    crate::server::audit::record("detection.relabel", &file_name, details).await;
+    Ok(())
+}
+
+/// The detection's stored top predictions other than `current`, best first.
+#[server(prefix = "/api")]
+pub async fn relabel_suggestions(id: i64, current: String) -> Result<Vec<SpeciesSuggestion>, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, species_search};
+    let predictions = ddb::top_predictions(id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Cannot read predictions: {e}")))?;
+    Ok(species_search::from_predictions(predictions, &current))
+}
+
+/// Labels of model `model_slug` matching `query`, best match first.
+#[server(prefix = "/api")]
+pub async fn search_species_labels(
+    query: String,
+    model_slug: String,
+) -> Result<Vec<SpeciesSuggestion>, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, species_search};
+    let labels = ddb::species_labels(&model_slug)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Cannot read labels: {e}")))?;
+    Ok(species_search::search(&query, &labels, species_search::MAX_RESULTS))
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// "✎ Relabel" button that opens a species picker for the detection.
+#[component]
+pub fn RelabelButton(id: i64, file_name: String, model_slug: String, scientific_name: String) -> impl IntoView {
+    let file_name = StoredValue::new(file_name);
+    let model_slug = StoredValue::new(model_slug);
+    let current = StoredValue::new(scientific_name);
+    let (open, set_open) = signal(false);
+    let (query, set_query) = signal(String::new());
+    let (choice, set_choice) = signal(Option::<SpeciesSuggestion>::None);
+    let (fine_tune, set_fine_tune) = signal(true);
+    let (busy, set_busy) = signal(false);
+    let (result, set_result) = signal(Option::<Result<String, String>>::None);
+
+    let predictions = Resource::new(move || open.get(), move |open| async move {
+        if !open {
+            return Ok(Vec::new());
+        }
+        relabel_suggestions(id, current.get_value()).await
+    });
+    let matches = Resource::new(move || query.get(), move |query| async move {
+        if query.trim().chars().count() < 2 {
+            return Ok(Vec::new());
+        }
+        search_species_labels(query, model_slug.get_value()).await
+    });
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(species) = choice.get() else {
+            return;
+        };
+        set_busy.set(true);
+        let f = fine_tune.get();
+        leptos::task::spawn_local(async move {
+            let res = relabel_detection(
+                id,
+                file_name.get_value(),
+                species.scientific_name.clone(),
+                species.common_name.clone(),
+                f,
+            )
+            .await
+            .map(|_| species.common_name)
+            .map_err(|e| e.to_string());
+            if res.is_ok() {
+                set_open.set(false);
+            }
+            set_result.set(Some(res));
+            set_busy.set(false);
+        });
+    };
+
+    // One row of choices; the picked species is highlighted.
+    let options = move |list: Vec<SpeciesSuggestion>| {
+        list.into_iter()
+            .map(|s| {
+                let picked = s.scientific_name.clone();
+                let detail = match (s.confidence, &s.matched_name) {
+                    (Some(c), _) => format!(" {:.0}%", c * 100.0),
+                    (None, Some(name)) => format!(" ({name})"),
+                    (None, None) => String::new(),
+                };
+                let title = s.scientific_name.clone();
+                let label = s.common_name.clone();
+                view! {
+                    <button
+                        type="button"
+                        class="relabel-option"
+                        class:active=move || choice.get().is_some_and(|c| c.scientific_name == picked)
+                        title=title
+                        on:click=move |_| set_choice.set(Some(s.clone()))
+                    >
+                        {label}
+                        <span class="relabel-option-detail">{detail}</span>
+                    </button>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <span class="relabel">
+            {move || match result.get() {
+                Some(Ok(name)) => view! {
+                    <span class="relabel-done">{format!("✔ Relabelled as {name}")}</span>
+                }.into_any(),
+                _ => view! {
+                    <button
+                        class="relabel-btn"
+                        title="Correct the species of this detection"
+                        on:click=move |_| set_open.update(|o| *o = !*o)
+                    >
+                        "✎ Relabel"
+                    </button>
+                }.into_any(),
+            }}
+            <Show when=move || open.get()>
+                <form class="relabel-form" on:submit=on_submit>
+                    <Suspense fallback=|| ()>
+                        {move || predictions.get().map(|res| match res {
+                            Ok(list) if !list.is_empty() => view! {
+                                <div class="relabel-options" title="The model's other predictions for this clip">
+                                    {options(list)}
+                                </div>
+                            }.into_any(),
+                            _ => ().into_any(),
+                        })}
+                    </Suspense>
+                    <input
+                        type="search"
+                        placeholder="Search species…"
+                        prop:value=move || query.get()
+                        on:input=move |ev| set_query.set(event_target_value(&ev))
+                    />
+                    <Suspense fallback=|| ()>
+                        {move || matches.get().map(|res| match res {
+                            Ok(list) if !list.is_empty() => view! {
+                                <div class="relabel-options">{options(list)}</div>
+                            }.into_any(),
+                            Ok(_) if query.get().trim().chars().count() >= 2 => view! {
+                                <span class="relabel-error">"No species of this model match"</span>
+                            }.into_any(),
+                            Ok(_) => ().into_any(),
+                            Err(e) => view! { <span class="relabel-error">{e.to_string()}</span> }.into_any(),
+                        })}
+                    </Suspense>
+                    <label title="Include this clip in fine-tuning dataset exports">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || fine_tune.get()
+                            on:change=move |ev| set_fine_tune.set(event_target_checked(&ev))
+                        />
+                        " Use for fine-tuning"
+                    </label>
+                    <button type="submit" disabled=move || busy.get() || choice.get().is_none()>
+                        {move || match (busy.get(), choice.get()) {
+                            (true, _) => "Saving…".to_string(),
+                            (false, Some(species)) => format!("Save as {}", species.common_name),
+                            (false, None) => "Pick a species".to_string(),
+                        }}
+                    </button>
+                </form>
+            </Show>
+            {move || result.get().and_then(|r| r.err()).map(|e| view! {
+                <span class="relabel-error">{e}</span>
+            })}
+        </span>
+    }
+}
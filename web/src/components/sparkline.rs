@@ -0,0 +1,49 @@
+//! Tiny inline-SVG sparkline of daily detection counts.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView};
+
+const WIDTH: f64 = 100.0;
+const HEIGHT: f64 = 20.0;
+
+/// A line of `counts` (oldest first) scaled to the card width.
+///
+/// The y-axis is relative to the series' own maximum, so the line shows
+/// the shape of a species' activity, not its volume; the tooltip gives
+/// the total.
+#[component]
+pub fn Sparkline(
+    /// One value per day, oldest first.
+    counts: Vec<u32>,
+) -> impl IntoView {
+    let days = counts.len();
+    let total: u32 = counts.iter().sum();
+    let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let step = if days > 1 { WIDTH / (days - 1) as f64 } else { 0.0 };
+    // Keep a 1px margin so the stroke is not clipped at 0 and max.
+    let y = |c: u32| 1.0 + (HEIGHT - 2.0) * (1.0 - c as f64 / max);
+
+    let points = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| format!("{:.1},{:.1}", i as f64 * step, y(c)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let area = format!("0,{HEIGHT} {points} {WIDTH},{HEIGHT}");
+    let title = format!("{total} detections in the last {days} days");
+
+    view! {
+        <span class="sparkline-wrap" title=title.clone()>
+            <svg
+                class="sparkline"
+                viewBox=format!("0 0 {WIDTH} {HEIGHT}")
+                preserveAspectRatio="none"
+                role="img"
+                aria-label=title
+            >
+                <polygon class="sparkline-area" points=area />
+                <polyline class="sparkline-line" points=points />
+            </svg>
+        </span>
+    }
+}
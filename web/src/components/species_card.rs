@@ -6,6 +6,8 @@
 use leptos::prelude::*;
 use leptos::prelude::{ElementChild, IntoView};
 
+use crate::components::domain_filter::domain_class;
+use crate::components::sparkline::Sparkline;
 use crate::model::SpeciesSummary;
 #[cfg(target_arch = "wasm32")]
 use crate::pages::species_list::get_species_photo;
@@ -17,6 +19,7 @@ use crate::pages::species_list::get_species_photo;
 #[component]
 pub fn SpeciesCard(species: SpeciesSummary) -> impl IntoView {
     let href = format!("/species/{}", urlencoded(&species.scientific_name));
+    let card_class = format!("species-card {}", domain_class(&species.domain));
     let fallback_img = "/pkg/placeholder.svg".to_string();
     let default_img = species
         .image_url
@@ -111,7 +114,7 @@ pub fn SpeciesCard(species: SpeciesSummary) -> impl IntoView {
     });
 
     view! {
-        <a href={href} class="species-card">
+        <a href={href} class=card_class>
             {if has_sex_photos() {
                 leptos::either::Either::Left(view! {
                     <div class="species-img-wrap sex-split">
@@ -164,6 +167,9 @@ pub fn SpeciesCard(species: SpeciesSummary) -> impl IntoView {
                         {species.display_count.clone()} " detections"
                     </span>
                 </div>
+                {(!species.daily_counts.is_empty()).then(|| view! {
+                    <Sparkline counts=species.daily_counts.clone() />
+                })}
             </div>
         </a>
     }
@@ -0,0 +1,127 @@
+//! Zoomable spectrogram of a detection clip – each view is rendered on
+//! demand by `/api/spectrogram-tile`, so zooming in resolves detail the
+//! static thumbnail cannot show.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+use super::query_encode;
+use crate::model::ClipInfo;
+
+/// Tile size requested from the server, in pixels.
+const TILE_W: u32 = 800;
+const TILE_H: u32 = 256;
+
+/// Narrowest windows the zoom buttons go down to.
+const MIN_SPAN_SECS: f64 = 0.05;
+const MIN_SPAN_HZ: f64 = 100.0;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_clip_info(clip_url: String) -> Result<ClipInfo, ServerFnError> {
+    use crate::server::spectrogram;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let extracted_dir = state.extracted_dir.clone();
+    let clip = tokio::task::spawn_blocking(move || spectrogram::load_clip(&extracted_dir, &clip_url))
+        .await
+        .map_err(|e| ServerFnError::new(format!("Decode task failed: {e}")))?
+        .map_err(ServerFnError::new)?;
+    Ok(ClipInfo {
+        duration_secs: clip.duration_secs(),
+        sample_rate: clip.sample_rate,
+    })
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Spectrogram of `clip_url` with time/frequency zoom and panning.
+#[component]
+pub fn SpectrogramViewer(clip_url: String) -> impl IntoView {
+    let clip = StoredValue::new(clip_url.clone());
+    let info = Resource::new(move || clip.get_value(), get_clip_info);
+
+    view! {
+        <Suspense fallback=|| view! { <div class="spectrogram-viewer loading">"Decoding clip…"</div> }>
+            {move || info.get().map(|res| match res {
+                Ok(info) => view! { <ZoomView clip=clip.get_value() info=info/> }.into_any(),
+                Err(e) => view! { <div class="spectrogram-viewer settings-error">{e.to_string()}</div> }.into_any(),
+            })}
+        </Suspense>
+    }
+}
+
+#[component]
+fn ZoomView(clip: String, info: ClipInfo) -> impl IntoView {
+    let duration = info.duration_secs.max(MIN_SPAN_SECS);
+    let nyquist = f64::from(info.sample_rate) / 2.0;
+    let (time, set_time) = signal((0.0, duration));
+    let (freq, set_freq) = signal((0.0, nyquist));
+    let clip = query_encode(&clip);
+
+    // Scale a window around its centre, keeping it inside [0, limit].
+    let zoom = move |(a, b): (f64, f64), factor: f64, limit: f64, min: f64| {
+        let span = ((b - a) * factor).clamp(min.min(limit), limit);
+        let centre = (a + b) / 2.0;
+        let start = (centre - span / 2.0).clamp(0.0, limit - span);
+        (start, start + span)
+    };
+    let pan = move |(a, b): (f64, f64), fraction: f64, limit: f64| {
+        let span = b - a;
+        let start = (a + span * fraction).clamp(0.0, limit - span);
+        (start, start + span)
+    };
+
+    let zoom_time = move |factor: f64| set_time.update(|t| *t = zoom(*t, factor, duration, MIN_SPAN_SECS));
+    let zoom_freq = move |factor: f64| set_freq.update(|f| *f = zoom(*f, factor, nyquist, MIN_SPAN_HZ));
+    let pan_time = move |fraction: f64| set_time.update(|t| *t = pan(*t, fraction, duration));
+    let pan_freq = move |fraction: f64| set_freq.update(|f| *f = pan(*f, fraction, nyquist));
+
+    let src = move || {
+        let ((t0, t1), (f0, f1)) = (time.get(), freq.get());
+        format!(
+            "/api/spectrogram-tile?clip={clip}&t0={t0:.4}&t1={t1:.4}&f0={f0:.0}&f1={f1:.0}&w={TILE_W}&h={TILE_H}"
+        )
+    };
+    let label = move || {
+        let ((t0, t1), (f0, f1)) = (time.get(), freq.get());
+        format!("{t0:.2}–{t1:.2} s · {:.1}–{:.1} kHz", f0 / 1000.0, f1 / 1000.0)
+    };
+
+    view! {
+        <div class="spectrogram-viewer">
+            <img
+                class="spectrogram-viewer-tile"
+                src=src
+                alt="zoomable spectrogram"
+                title="Scroll to zoom in time; Shift+scroll to zoom in frequency"
+                on:wheel=move |ev| {
+                    ev.prevent_default();
+                    let factor = if ev.delta_y() < 0.0 { 0.8 } else { 1.25 };
+                    if ev.shift_key() { zoom_freq(factor) } else { zoom_time(factor) }
+                }
+            />
+            <div class="spectrogram-viewer-controls">
+                <button title="Pan left" on:click=move |_| pan_time(-0.25)>"◀"</button>
+                <button title="Zoom in (time)" on:click=move |_| zoom_time(0.5)>"⊕ t"</button>
+                <button title="Zoom out (time)" on:click=move |_| zoom_time(2.0)>"⊖ t"</button>
+                <button title="Pan right" on:click=move |_| pan_time(0.25)>"▶"</button>
+                <button title="Pan down" on:click=move |_| pan_freq(-0.25)>"▼"</button>
+                <button title="Zoom in (frequency)" on:click=move |_| zoom_freq(0.5)>"⊕ f"</button>
+                <button title="Zoom out (frequency)" on:click=move |_| zoom_freq(2.0)>"⊖ f"</button>
+                <button title="Pan up" on:click=move |_| pan_freq(0.25)>"▲"</button>
+                <button
+                    title="Show the whole clip"
+                    on:click=move |_| {
+                        set_time.set((0.0, duration));
+                        set_freq.set((0.0, nyquist));
+                    }
+                >
+                    "Reset"
+                </button>
+                <span class="spectrogram-viewer-range">{label}</span>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,137 @@
+//! Capture stream panel – the RTSP watchdog's view of each camera
+//! stream, so a camera that keeps dropping out shows up in the UI, and
+//! warnings while the disk guard has paused recording, the node's
+//! clock is not synchronised or it speaks another protocol version.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, Resource, ServerFnError, Transition};
+
+use crate::components::connection::last_good;
+use crate::model::{CaptureHealth, CaptureStatus, StreamState, TimeSyncStatus};
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_capture_status() -> Result<CaptureStatus, ServerFnError> {
+    crate::server::capture_api::get_json("/api/status")
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[server(prefix = "/api")]
+pub async fn get_capture_health() -> Result<CaptureHealth, ServerFnError> {
+    crate::server::capture_api::get_json("/api/health")
+        .await
+        .map_err(ServerFnError::new)
+}
+
+/// Warning line for a drifting clock, e.g. "not synchronised" or
+/// "off by 2.4 s (chrony, stratum 3)".
+fn clock_warning(sync: &TimeSyncStatus) -> String {
+    let what = match sync.offset_ms {
+        Some(ms) if sync.synchronized => format!("is off by {:.1} s", ms.abs() / 1000.0),
+        _ => "is not synchronised".to_string(),
+    };
+    let source = match (sync.stratum, &sync.reference) {
+        (Some(stratum), Some(reference)) => format!("{}, stratum {stratum}, {reference}", sync.source),
+        _ => sync.source.clone(),
+    };
+    format!("🕓 Capture node clock {what} ({source}) — detection times may be wrong")
+}
+
+/// Warning line for a capture node on another protocol version.
+fn protocol_warning(health: &CaptureHealth) -> Option<String> {
+    let problem = gaia_client::protocol::protocol_mismatch(health.protocol)?;
+    let node = match health.version.as_str() {
+        "" => "Capture node".to_string(),
+        v => format!("Capture node {v}"),
+    };
+    Some(format!("⚠ {node} {problem}"))
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// One line per stream with its state and restart count; refreshed
+/// every 30 s and hidden for microphone-only nodes that are recording.
+#[component]
+pub fn StreamHealth() -> impl IntoView {
+    #[allow(unused_variables)] // written only in the hydrate (WASM) build
+    let (tick, set_tick) = signal(0u32);
+    let data = Resource::new(move || tick.get(), |_| async { get_capture_status().await });
+    let data = last_good(move || data.get());
+    let health = Resource::new(move || tick.get(), |_| async { get_capture_health().await });
+    let health = last_good(move || health.get());
+
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        let cb = Closure::wrap(Box::new(move || set_tick.update(|n| *n += 1)) as Box<dyn Fn()>);
+        let _ = web_sys::window()
+            .unwrap()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                cb.as_ref().unchecked_ref(),
+                30_000,
+            );
+        cb.forget();
+    }
+
+    view! {
+        <Transition fallback=|| ()>
+            {move || health.get().and_then(|res| protocol_warning(&res.ok()?)).map(|warning| view! {
+                <p class="level-bad protocol-warning">{warning}</p>
+            })}
+            {move || health.get().and_then(|res| res.ok()?.time_sync).filter(|s| s.drifting).map(|sync| view! {
+                <p class="level-bad clock-warning" title=sync.last_sync.clone().map(|t| format!("Last sync {t}"))>
+                    {clock_warning(&sync)}
+                </p>
+            })}
+            {move || data.get().map(|res| match res {
+                Ok(status) if status.capture_paused || !status.streams.is_empty() => view! {
+                    <div class="stream-health">
+                        <h3>"Capture Streams"</h3>
+                        {status.capture_paused.then(|| view! {
+                            <p class="level-bad">
+                                {format!(
+                                    "⏸ Recording paused: disk {:.0}% full, inodes {:.0}% used — resumes once space is freed",
+                                    status.disk_usage_pct, status.inode_usage_pct,
+                                )}
+                            </p>
+                        })}
+                        <ul>
+                            {status.streams.into_iter().map(|s| {
+                                let (class, label) = match s.state {
+                                    StreamState::Recording => ("level-ok", "● recording"),
+                                    StreamState::Starting => ("level-warn", "◌ connecting"),
+                                    StreamState::Down => ("level-bad", "✖ down"),
+                                };
+                                let title = match &s.last_restart {
+                                    Some(at) => format!(
+                                        "Last audio {}s ago · last restart {at}",
+                                        s.last_audio_secs,
+                                    ),
+                                    None => format!("Last audio {}s ago", s.last_audio_secs),
+                                };
+                                view! {
+                                    <li class="stream-row" title=title>
+                                        <span class="stream-url">
+                                            {format!("#{} {}", s.index, s.url)}
+                                        </span>
+                                        <span class=format!("stream-state {class}")>
+                                            {label}
+                                            {(s.restarts > 0).then(|| {
+                                                format!(" · {} restart(s)", s.restarts)
+                                            })}
+                                        </span>
+                                    </li>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </ul>
+                    </div>
+                }.into_any(),
+                // Local microphone, or no capture node reachable.
+                _ => view! { <div></div> }.into_any(),
+            })}
+        </Transition>
+    }
+}
@@ -0,0 +1,149 @@
+//! Colour theme: automatic (follows `prefers-color-scheme`), light, dark,
+//! or night (dim red for displays running in the dark).
+//!
+//! The choice lives in a [`ThemeContext`] provided by `<App/>`, is
+//! reflected as `<html data-theme="…">` (the stylesheet switches its
+//! custom properties on that attribute) and is persisted per browser in
+//! `localStorage`.  [`THEME_INIT_SCRIPT`] applies the stored choice from
+//! `<head>` before first paint, so reloading at night doesn't flash the
+//! default theme.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView};
+
+/// `localStorage` key holding the chosen theme.
+pub const STORAGE_KEY: &str = "gaia-theme";
+
+/// Inline `<head>` script applying the stored theme before hydration.
+pub const THEME_INIT_SCRIPT: &str = "try{var t=localStorage.getItem('gaia-theme');\
+if(t&&t!=='auto')document.documentElement.dataset.theme=t}catch(e){}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    /// Light or dark, following the operating system.
+    #[default]
+    Auto,
+    Light,
+    Dark,
+    /// Red on black, preserving night vision.
+    Night,
+}
+
+impl Theme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::Auto => "auto",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::Night => "night",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(Theme::Auto),
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            "night" => Some(Theme::Night),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Theme::Auto => "◐ Auto",
+            Theme::Light => "☀ Light",
+            Theme::Dark => "☾ Dark",
+            Theme::Night => "● Night",
+        }
+    }
+
+    /// The theme the toggle switches to next.
+    fn next(self) -> Self {
+        match self {
+            Theme::Auto => Theme::Light,
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Night,
+            Theme::Night => Theme::Auto,
+        }
+    }
+}
+
+/// The active theme, shared with every component through context.
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeContext(pub RwSignal<Theme>);
+
+/// Provide [`ThemeContext`] and keep `<html data-theme>` in sync with it.
+///
+/// The server always renders [`Theme::Auto`]; the stored choice is picked
+/// up once the app has hydrated.
+pub fn provide_theme() -> ThemeContext {
+    let theme = RwSignal::new(Theme::Auto);
+
+    #[cfg(feature = "hydrate")]
+    Effect::new(move |prev: Option<()>| {
+        if prev.is_none() {
+            if let Some(saved) = browser::load() {
+                theme.set(saved);
+            }
+        }
+        browser::apply(theme.get());
+    });
+
+    let ctx = ThemeContext(theme);
+    provide_context(ctx);
+    ctx
+}
+
+/// Nav-bar button cycling auto → light → dark → night.
+#[component]
+pub fn ThemeToggle() -> impl IntoView {
+    let ThemeContext(theme) = expect_context::<ThemeContext>();
+
+    let on_click = move |_| {
+        let next = theme.get_untracked().next();
+        theme.set(next);
+        #[cfg(feature = "hydrate")]
+        browser::save(next);
+    };
+
+    view! {
+        <button
+            class="theme-toggle"
+            title="Colour theme (auto follows the system setting)"
+            on:click=on_click
+        >
+            {move || theme.get().label()}
+        </button>
+    }
+}
+
+#[cfg(feature = "hydrate")]
+mod browser {
+    use super::{Theme, STORAGE_KEY};
+
+    pub fn load() -> Option<Theme> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        Theme::parse(&storage.get_item(STORAGE_KEY).ok()??)
+    }
+
+    pub fn save(theme: Theme) {
+        if let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) {
+            let _ = storage.set_item(STORAGE_KEY, theme.as_str());
+        }
+    }
+
+    /// `Auto` drops the attribute so the stylesheet's media query decides.
+    pub fn apply(theme: Theme) {
+        let Some(root) = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.document_element())
+        else {
+            return;
+        };
+        let _ = match theme {
+            Theme::Auto => root.remove_attribute("data-theme"),
+            t => root.set_attribute("data-theme", t.as_str()),
+        };
+    }
+}
@@ -0,0 +1,137 @@
+//! Clip player drawn over the clip's waveform.
+//!
+//! The envelope comes from the `<clip>.peaks.json` sidecar written at
+//! extraction, so the card shows the waveform without downloading the
+//! audio.  Clicking it seeks there and plays.  Clips without a sidecar
+//! (BirdNET-Pi imports, older extractions) get the plain player.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+use crate::model::{clip_mime_type, WaveformPeaks};
+
+/// Height of the SVG view box; the width is one unit per peak.
+const HEIGHT: f64 = 64.0;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_waveform(clip_url: String) -> Result<Option<WaveformPeaks>, ServerFnError> {
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let uri_path = clip_url.strip_prefix("/extracted").unwrap_or(&clip_url);
+    let sidecar = format!("{uri_path}.peaks.json");
+    let Some(path) = crate::server::clips::resolve_legacy_path(&state.extracted_dir, &sidecar)
+    else {
+        return Ok(None);
+    };
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Cannot read {}: {e}", path.display())))?;
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| ServerFnError::new(format!("Invalid {}: {e}", path.display())))
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// `<audio>` player for `clip_url`, with its waveform above it when the
+/// clip has peaks.
+#[component]
+pub fn WaveformPlayer(clip_url: String) -> impl IntoView {
+    let mime = clip_mime_type(&clip_url);
+    let clip = StoredValue::new(clip_url.clone());
+    let peaks = Resource::new(move || clip.get_value(), get_waveform);
+    let audio_ref = NodeRef::<leptos::html::Audio>::new();
+    // Played fraction of the clip, 0..1.
+    let (progress, set_progress) = signal(0.0f64);
+
+    let on_timeupdate = move |_| {
+        #[cfg(feature = "hydrate")]
+        if let Some(audio) = audio_ref.get_untracked() {
+            let duration = audio.duration();
+            if duration.is_finite() && duration > 0.0 {
+                set_progress.set(audio.current_time() / duration);
+            }
+        }
+    };
+    let on_seek = move |ev: leptos::ev::MouseEvent| {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::JsCast;
+            let Some(audio) = audio_ref.get_untracked() else {
+                return;
+            };
+            let Some(target) = ev
+                .current_target()
+                .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            else {
+                return;
+            };
+            let width = f64::from(target.client_width());
+            let duration = audio.duration();
+            if width > 0.0 && duration.is_finite() {
+                let fraction = (f64::from(ev.offset_x()) / width).clamp(0.0, 1.0);
+                audio.set_current_time(fraction * duration);
+                set_progress.set(fraction);
+                let _ = audio.play();
+            }
+        }
+        // Only the browser plays audio.
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (ev, set_progress);
+    };
+
+    view! {
+        <div class="waveform-player">
+            <Transition fallback=|| ()>
+                {move || peaks.get().and_then(|res| res.ok().flatten()).map(|peaks| {
+                    let width = peaks.max.len().max(1) as f64;
+                    view! {
+                        <svg
+                            class="waveform"
+                            viewBox=format!("0 0 {width} {HEIGHT}")
+                            preserveAspectRatio="none"
+                            role="img"
+                            aria-label="Waveform — click to play from there"
+                            on:click=on_seek
+                        >
+                            <path class="waveform-envelope" d=envelope_path(&peaks)/>
+                            <rect
+                                class="waveform-progress"
+                                x="0"
+                                y="0"
+                                height=HEIGHT
+                                width=move || progress.get() * width
+                            />
+                        </svg>
+                    }
+                })}
+            </Transition>
+            <audio
+                node_ref=audio_ref
+                class="detection-audio"
+                controls
+                preload="metadata"
+                on:timeupdate=on_timeupdate
+            >
+                <source src=clip_url type=mime/>
+            </audio>
+        </div>
+    }
+}
+
+/// Closed SVG path around the envelope: along the maxima left to right,
+/// back along the minima.
+fn envelope_path(peaks: &WaveformPeaks) -> String {
+    let y = |v: i8| HEIGHT / 2.0 - f64::from(v) * (HEIGHT / 2.0) / 127.0;
+    let upper = peaks.max.iter().enumerate().map(|(i, &v)| (i, y(v)));
+    let lower = peaks.min.iter().enumerate().rev().map(|(i, &v)| (i, y(v)));
+    let mut d = String::new();
+    for (n, (i, y)) in upper.chain(lower).enumerate() {
+        let cmd = if n == 0 { 'M' } else { 'L' };
+        d.push_str(&format!("{cmd}{i} {y:.1}"));
+    }
+    d.push('Z');
+    d
+}
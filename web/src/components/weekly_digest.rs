@@ -0,0 +1,106 @@
+//! Weekly digest panel (Settings) – builds the standalone HTML report of a
+//! week and lists the reports written so far.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, ServerFnError};
+
+use crate::model::WeeklyDigest;
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+/// Build the digest of the week containing `date` (`YYYY-MM-DD`); an
+/// empty date means the last complete week.
+#[server(prefix = "/api")]
+pub async fn generate_weekly_digest(date: String) -> Result<WeeklyDigest, ServerFnError> {
+    use crate::server::{digest, kv};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let monday = if date.trim().is_empty() {
+        let today = chrono::NaiveDate::parse_from_str(&kv::today_for_tz().await, "%Y-%m-%d")
+            .map_err(|e| ServerFnError::new(format!("Bad station date: {e}")))?;
+        digest::last_complete_week(today)
+    } else {
+        chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| ServerFnError::new(format!("Invalid date {date:?} (expected YYYY-MM-DD)")))?
+    };
+    digest::generate(&state.db_path, &state.extracted_dir, monday)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+/// Reports written so far as `(week, url)`, newest first.
+#[server(prefix = "/api")]
+pub async fn list_weekly_digests() -> Result<Vec<(String, String)>, ServerFnError> {
+    Ok(crate::server::digest::list())
+}
+
+// ─── Component ───────────────────────────────────────────────────────────────
+
+/// Week picker plus a generate button; links to the reports on disk.
+#[component]
+pub fn WeeklyDigestPanel() -> impl IntoView {
+    let (date, set_date) = signal(String::new());
+    let (busy, set_busy) = signal(false);
+    let (result, set_result) = signal(Option::<Result<WeeklyDigest, String>>::None);
+    let digests = Resource::new(move || result.get().is_some(), |_| list_weekly_digests());
+
+    let on_generate = move |_| {
+        set_busy.set(true);
+        set_result.set(None);
+        let date = date.get();
+        leptos::task::spawn_local(async move {
+            let res = generate_weekly_digest(date).await.map_err(|e| e.to_string());
+            set_result.set(Some(res));
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <div class="setting-group weekly-digest">
+            <label class="setting-label">"Weekly Digest"</label>
+            <p class="setting-help">
+                "A single HTML page summarising a week (top and new species, daily activity, "
+                "the best recordings) to mail to a nature group or post online. Print it to "
+                "get a PDF. The last week's digest is written every Monday."
+            </p>
+            <div class="dataset-export-options">
+                <label>
+                    "Week of "
+                    <input
+                        class="setting-input detection-export-date"
+                        type="date"
+                        prop:value=move || date.get()
+                        on:input=move |ev| set_date.set(event_target_value(&ev))
+                    />
+                </label>
+            </div>
+            <button class="btn btn-primary" on:click=on_generate disabled=move || busy.get()>
+                {move || if busy.get() { "Building…" } else { "Build Digest" }}
+            </button>
+            {move || result.get().map(|res| match res {
+                Ok(digest) => view! {
+                    <div class="settings-success">
+                        <a href=digest.url.clone() target="_blank">{format!("📄 {}", digest.week)}</a>
+                        {format!(
+                            " — {} detections of {} species, {} new",
+                            digest.detections, digest.species, digest.new_species
+                        )}
+                    </div>
+                }.into_any(),
+                Err(e) => view! { <div class="settings-error">{e}</div> }.into_any(),
+            })}
+            <Suspense fallback=|| ()>
+                {move || digests.get().map(|res| match res {
+                    Ok(list) if !list.is_empty() => view! {
+                        <ul class="weekly-digest-list">
+                            {list.into_iter().map(|(week, url)| view! {
+                                <li><a href=url target="_blank">{week}</a></li>
+                            }).collect::<Vec<_>>()}
+                        </ul>
+                    }.into_any(),
+                    _ => ().into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
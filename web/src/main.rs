@@ -10,7 +10,7 @@ async fn main() {
     use tower_http::services::ServeDir;
 
     use gaia_web::app::{shell, App, AppState};
-    use gaia_web::server::inaturalist;
+    use gaia_web::server::species_images;
 
     // ── Tracing ──────────────────────────────────────────────────────────
     tracing_subscriber::fmt()
@@ -60,6 +60,9 @@ async fn main() {
         std::process::exit(1);
     }
 
+    // ── Web Push notifier (drains detections queued by processing) ───
+    tokio::spawn(gaia_web::server::push::run());
+
     // ── Migrate existing SQLite detections → Parquet (one-time) ──────
     {
         let db = db_path.clone();
@@ -113,13 +116,33 @@ async fn main() {
     );
     let extracted_serve_path = extracted_dir.to_string_lossy().to_string();
 
+    let graphql_schema = gaia_web::server::graphql::schema(db_path.clone());
+
+    // Species photos are fetched in the background, throttled, so page
+    // renders only ever read the cache.
+    let photo_cache = species_images::new_cache();
+    tokio::spawn(species_images::run_enrichment(
+        photo_cache.clone(),
+        db_path.clone(),
+    ));
+
+    // Weekly digest of the last complete week, written once it is over.
+    tokio::spawn(gaia_web::server::digest::run(
+        db_path.clone(),
+        extracted_dir.clone(),
+    ));
+
     let state = AppState {
         db_path,
         extracted_dir,
-        photo_cache: inaturalist::new_cache(),
+        photo_cache,
         leptos_options: leptos_options.clone(),
     };
 
+    // Other nodes and monitoring read the dashboard's version here.
+    let started = std::time::Instant::now();
+    gaia_web::server::health::advertise(addr.port());
+
     // ── Routes ───────────────────────────────────────────────────────────
     let routes = generate_route_list(App);
 
@@ -143,10 +166,65 @@ async fn main() {
             "/pkg",
             ServeDir::new(format!("{}/pkg", site_root.to_string())),
         )
-        // Serve extracted audio clips + spectrograms
+        // Serve extracted audio clips + spectrograms.  Misses fall back
+        // to clip directories written under the pre-sanitisation names.
         .nest_service(
             "/extracted",
-            ServeDir::new(&extracted_serve_path),
+            ServeDir::new(&extracted_serve_path).fallback(axum::routing::any({
+                let dir = PathBuf::from(&extracted_serve_path);
+                move |req: axum::extract::Request| {
+                    gaia_web::server::clips::legacy_clip_fallback(dir.clone(), req)
+                }
+            })),
+        )
+        // Local species photos (fallback provider and admin pins)
+        .nest_service(
+            species_images::LOCAL_URL_PREFIX,
+            ServeDir::new(species_images::local_dir()),
+        )
+        // Liveness and version (also advertised over mDNS)
+        .route(
+            "/api/health",
+            axum::routing::get(move || gaia_web::server::health::handler(started)),
+        )
+        // Zoomable spectrogram tiles rendered from the clips
+        .route(
+            "/api/spectrogram-tile",
+            axum::routing::get({
+                let dir = PathBuf::from(&extracted_serve_path);
+                move |query| gaia_web::server::spectrogram::tile(dir.clone(), query)
+            }),
+        )
+        // Filtered detections as CSV / Parquet (Settings → Export Detections)
+        .route(
+            "/api/export/detections",
+            axum::routing::get({
+                let db = state.db_path.clone();
+                move |headers, query| {
+                    gaia_web::server::detection_export::detections(db.clone(), headers, query)
+                }
+            }),
+        )
+        // Training dataset archives built from the Settings page
+        .nest_service(
+            "/exports",
+            ServeDir::new(gaia_web::server::dataset::exports_dir()),
+        )
+        // Weekly digest reports (Settings → Weekly Digest)
+        .nest_service(
+            "/digests",
+            ServeDir::new(gaia_web::server::digest::digests_dir()),
+        )
+        // Read-only GraphQL over the detections (GET = GraphiQL explorer)
+        .route(
+            "/graphql",
+            axum::routing::get(gaia_web::server::graphql::graphiql)
+                .post_service(async_graphql_axum::GraphQL::new(graphql_schema)),
+        )
+        // Pipeline activity pushed to the dashboard as it changes
+        .route(
+            "/ws/pipeline",
+            axum::routing::get(gaia_web::server::pipeline_ws::handler),
         )
         // Serve live analysis spectrogram from the shared data volume
         .nest_service(
@@ -1,126 +1,40 @@
 //! Shared data-transfer objects used by both server and client.
+//!
+//! Detections and species summaries live in `gaia-client`, so tools
+//! outside the dashboard can deserialise them too; types the capture and
+//! processing APIs return are re-exported from `gaia_client::protocol`.
 
 use serde::{Deserialize, Serialize};
 
+pub use gaia_client::model::{
+    safe_path_component, ConservationStatus, SpeciesSummary, SpeciesVerification, WebDetection,
+};
+
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
-/// Return the `NODE_NAME` env var if set, otherwise `"local"`.
-///
-/// This gives the operator's chosen friendly name for the local station.
-fn node_name_or_local() -> String {
-    std::env::var("NODE_NAME")
-        .ok()
-        .filter(|v| !v.is_empty())
-        .unwrap_or_else(|| "local".into())
+/// MIME type for an extracted clip URL, for `<source type=…>`.
+pub fn clip_mime_type(url: &str) -> &'static str {
+    if url.ends_with(".opus") {
+        "audio/ogg; codecs=opus"
+    } else if url.ends_with(".mp3") {
+        "audio/mpeg"
+    } else {
+        "audio/wav"
+    }
 }
 
-// ─── Detection ───────────────────────────────────────────────────────────────
+// ─── Push notifications ──────────────────────────────────────────────────────
 
-/// A single detection row, fully serialisable (no DateTime).
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WebDetection {
-    pub id: i64,
-    pub domain: String,
-    pub scientific_name: String,
-    pub common_name: String,
-    pub confidence: f64,
-    pub date: String,
-    pub time: String,
-    pub file_name: String,
-    pub source_node: String,
-    /// `true` when the detection was excluded by the species-range model.
-    #[serde(default)]
-    pub excluded: bool,
-    /// Species photo URL from iNaturalist (populated server-side).
-    #[serde(default)]
-    pub image_url: Option<String>,
-    /// Short identifier for the model that produced this detection.
-    #[serde(default)]
-    pub model_slug: String,
-    /// Human-readable model name (e.g. `"BirdNET V2.4"`).
-    #[serde(default)]
-    pub model_name: String,
-    /// `true` when the model is marked as beta / experimental.
-    #[serde(default)]
-    pub model_beta: bool,
-    /// Cross-model agreement score (0.0 – 1.0).
-    #[serde(default)]
-    pub agreement_score: f64,
-    /// Comma-separated slugs of agreeing models.
-    #[serde(default)]
-    pub agreement_models: String,
-    /// Timezone-adjusted date for display (YYYY-MM-DD).
-    /// Same as `date` when tz_offset is 0 or unset.
-    /// Kept separate so `clip_url()` always uses the UTC `date` for file paths.
-    #[serde(default)]
-    pub display_date: String,
-    /// Timezone-adjusted time for display (HH:MM:SS).
-    #[serde(default)]
-    pub display_time: String,
-}
+/// Push-subscription target meaning "the first ever detection of any
+/// species"; every other target is a scientific name.
+pub const PUSH_NEW_SPECIES: &str = "*new*";
 
-impl WebDetection {
-    /// Build the URL to the extracted audio clip served by `/extracted/`.
-    ///
-    /// Clips are stored as:
-    ///   `{extracted_dir}/By_Date/{date}/{common_name_safe}/{file_name}`
-    ///
-    /// Returns `None` if `file_name` is empty.
-    pub fn clip_url(&self) -> Option<String> {
-        if self.file_name.is_empty() {
-            return None;
-        }
-        let safe_name = self.common_name.replace('\'', "").replace(' ', "_");
-        Some(format!(
-            "/extracted/By_Date/{}/{}/{}",
-            self.date, safe_name, self.file_name
-        ))
-    }
-
-    /// URL to the spectrogram PNG (generated alongside the audio clip).
-    ///
-    /// Spectrograms are named `{clip_file}.png`.  After Opus compression
-    /// the clip extension changes from `.wav`/`.mp3` to `.opus`, and the
-    /// spectrogram is renamed accordingly, so this always works.
-    pub fn spectrogram_url(&self) -> Option<String> {
-        self.clip_url().map(|url| format!("{url}.png"))
-    }
+/// Push-subscription target for detections flagged rare in the region.
+pub const PUSH_RARE_SPECIES: &str = "*rare*";
 
-    /// Human-friendly label for the capture node.
-    ///
-    /// Uses the `NODE_NAME` environment variable when the source is local
-    /// (localhost / 127.x), otherwise extracts hostname from the URL.
-    /// Returns `"local"` when no node was recorded and no name is set.
-    pub fn source_label(&self) -> String {
-        if self.source_node.is_empty() {
-            return node_name_or_local();
-        }
-        let stripped = self.source_node
-            .trim_start_matches("http://")
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
-        let host = stripped.split(':').next().unwrap_or(stripped);
-        if host == "localhost" || host.starts_with("127.") {
-            return node_name_or_local();
-        }
-        // Remote node — show hostname portion (strip port)
-        host.trim_end_matches('.').to_string()
-    }
-
-    /// Display label for the model that produced this detection.
-    ///
-    /// Prefers `model_name` (human-readable) but falls back to
-    /// `model_slug` or `"Unknown model"`.
-    pub fn model_label(&self) -> String {
-        if !self.model_name.is_empty() {
-            self.model_name.clone()
-        } else if !self.model_slug.is_empty() {
-            self.model_slug.clone()
-        } else {
-            "Unknown model".to_string()
-        }
-    }
-}
+/// Push-subscription target for stations the processing watchdog finds
+/// quiet (far fewer detections than usual).
+pub const PUSH_STATION_ALERTS: &str = "*stations*";
 
 // ─── Species ─────────────────────────────────────────────────────────────────
 
@@ -141,22 +55,14 @@ pub struct SpeciesInfo {
     /// Female specimen photo URL (from iNaturalist sex-annotated observations).
     #[serde(default)]
     pub female_image_url: Option<String>,
-    /// Verification state (loaded separately).
+    /// Credit line for `image_url`.
     #[serde(default)]
-    pub verification: Option<SpeciesVerification>,
-}
-
-/// Verification record for a species.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SpeciesVerification {
-    /// `"ornithologist"` or `"inaturalist"`.
-    pub method: String,
-    /// iNaturalist observation URL/ID (only when method == "inaturalist").
+    pub image_attribution: Option<String>,
     #[serde(default)]
-    pub inaturalist_obs: String,
-    /// When the verification was recorded.
+    pub image_source: Option<ImageSource>,
+    /// Verification state (loaded separately).
     #[serde(default)]
-    pub verified_at: String,
+    pub verification: Option<SpeciesVerification>,
 }
 
 // ─── Calendar ────────────────────────────────────────────────────────────────
@@ -203,6 +109,10 @@ pub struct TopRecording {
     pub file_name: String,
     pub source_node: String,
     pub model_name: String,
+    /// Common name the model predicted, when the detection was
+    /// relabelled; the clip directory is named after it.
+    #[serde(default)]
+    pub original_common_name: Option<String>,
     /// TZ-adjusted date for display (same as `date` when offset = 0).
     #[serde(default)]
     pub display_date: String,
@@ -211,13 +121,51 @@ pub struct TopRecording {
     pub display_time: String,
 }
 
+/// A detection for its permalink page.  `origin` is the site's
+/// `scheme://host` as the browser reached it, since OpenGraph image and
+/// page URLs must be absolute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionPermalink {
+    pub detection: WebDetection,
+    pub origin: String,
+    /// The model's best labels for the detection's chunk, highest first;
+    /// empty unless processing stores them (`TOP_PREDICTIONS`).
+    #[serde(default)]
+    pub top_predictions: Vec<TopPrediction>,
+}
+
+/// One of the alternative labels stored with a detection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopPrediction {
+    pub scientific_name: String,
+    pub common_name: String,
+    pub confidence: f64,
+}
+
+/// A species offered when relabelling a detection: one of its stored
+/// top predictions or a match from the model's label set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeciesSuggestion {
+    pub scientific_name: String,
+    pub common_name: String,
+    /// Model confidence, for the stored top predictions.
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    /// Name in another language that matched the search, when it is
+    /// neither the scientific nor the common name.
+    #[serde(default)]
+    pub matched_name: Option<String>,
+}
+
 impl TopRecording {
-    /// URL to the extracted audio clip.
+    /// URL to the extracted audio clip.  Relabelled detections keep
+    /// their clip under the predicted name.
     pub fn clip_url(&self) -> Option<String> {
         if self.file_name.is_empty() {
             return None;
         }
-        let safe_name = self.common_name.replace('\'', "").replace(' ', "_");
+        let name = self.original_common_name.as_deref().unwrap_or(&self.common_name);
+        let safe_name = safe_path_component(name);
         Some(format!(
             "/extracted/By_Date/{}/{}/{}",
             self.date, safe_name, self.file_name
@@ -241,127 +189,40 @@ pub struct DayDetectionGroup {
     pub max_confidence: f64,
 }
 
-// ─── Conservation status ─────────────────────────────────────────────────────
-
-/// IUCN Red List conservation status codes, ordered from most to least
-/// threatened.  The numeric values match the iNaturalist `iucn` field.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ConservationStatus {
-    /// Extinct
-    EX = 70,
-    /// Extinct in the Wild
-    EW = 60,
-    /// Critically Endangered
-    CR = 50,
-    /// Endangered
-    EN = 40,
-    /// Vulnerable
-    VU = 30,
-    /// Near Threatened
-    NT = 20,
-    /// Least Concern
-    LC = 10,
-    /// Data Deficient
-    DD = 5,
-    /// Not Evaluated
-    NE = 0,
-}
-
-impl ConservationStatus {
-    /// Parse from the iNaturalist numeric `iucn` field.
-    pub fn from_iucn(code: u8) -> Option<Self> {
-        match code {
-            70 => Some(Self::EX),
-            60 => Some(Self::EW),
-            50 => Some(Self::CR),
-            40 => Some(Self::EN),
-            30 => Some(Self::VU),
-            20 => Some(Self::NT),
-            10 => Some(Self::LC),
-            5  => Some(Self::DD),
-            0  => Some(Self::NE),
-            _  => None,
-        }
-    }
-
-    /// Parse from a short IUCN code string (e.g. `"VU"`, `"EN"`).
-    pub fn from_code(s: &str) -> Option<Self> {
-        match s.to_uppercase().as_str() {
-            "EX" => Some(Self::EX),
-            "EW" => Some(Self::EW),
-            "CR" => Some(Self::CR),
-            "EN" => Some(Self::EN),
-            "VU" => Some(Self::VU),
-            "NT" => Some(Self::NT),
-            "LC" => Some(Self::LC),
-            "DD" => Some(Self::DD),
-            "NE" => Some(Self::NE),
-            _    => None,
-        }
-    }
-
-    /// Short IUCN code (e.g. `"CR"`, `"LC"`).
-    pub fn code(self) -> &'static str {
-        match self {
-            Self::EX => "EX",
-            Self::EW => "EW",
-            Self::CR => "CR",
-            Self::EN => "EN",
-            Self::VU => "VU",
-            Self::NT => "NT",
-            Self::LC => "LC",
-            Self::DD => "DD",
-            Self::NE => "NE",
-        }
-    }
-
-    /// Human-readable label.
-    pub fn label(self) -> &'static str {
-        match self {
-            Self::EX => "Extinct",
-            Self::EW => "Extinct in the Wild",
-            Self::CR => "Critically Endangered",
-            Self::EN => "Endangered",
-            Self::VU => "Vulnerable",
-            Self::NT => "Near Threatened",
-            Self::LC => "Least Concern",
-            Self::DD => "Data Deficient",
-            Self::NE => "Not Evaluated",
-        }
-    }
-
-    /// Numeric sort key — higher = more threatened.
-    pub fn threat_level(self) -> u8 {
-        self as u8
-    }
+// ─── Species photos ──────────────────────────────────────────────────────────
+
+/// Where a species photo came from (see `server::species_images`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageSource {
+    #[default]
+    INaturalist,
+    WikimediaCommons,
+    /// File in the local override directory (`GAIA_SPECIES_IMAGES_DIR`).
+    Local,
+    /// Chosen by an admin on the species page.
+    Pinned,
+}
 
-    /// CSS modifier class for colour-coding the badge.
-    pub fn css_class(self) -> &'static str {
+impl ImageSource {
+    pub fn label(&self) -> &'static str {
         match self {
-            Self::EX | Self::EW => "status-extinct",
-            Self::CR => "status-critical",
-            Self::EN => "status-endangered",
-            Self::VU => "status-vulnerable",
-            Self::NT => "status-near-threatened",
-            Self::LC => "status-least-concern",
-            Self::DD | Self::NE => "status-unknown",
+            Self::INaturalist => "iNaturalist",
+            Self::WikimediaCommons => "Wikimedia Commons",
+            Self::Local => "local image",
+            Self::Pinned => "pinned image",
         }
     }
 }
 
-impl std::fmt::Display for ConservationStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ({})", self.label(), self.code())
-    }
-}
-
-// ─── iNaturalist API response subset ─────────────────────────────────────────
-
-/// A cached photo record from iNaturalist.
+/// A cached species photo record.  Everything but the main photo comes
+/// from iNaturalist, whichever provider supplied the photo.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeciesPhoto {
     pub medium_url: String,
+    /// Credit line for `medium_url`, as given by its provider.
     pub attribution: String,
+    #[serde(default)]
+    pub source: ImageSource,
     pub wikipedia_url: Option<String>,
     /// IUCN conservation status (from iNaturalist `conservation_status`).
     #[serde(default)]
@@ -374,32 +235,186 @@ pub struct SpeciesPhoto {
     pub female_image_url: Option<String>,
 }
 
-// ─── Species summary (for species list) ──────────────────────────────────────
+/// Detections of one taxonomic family, from the `species` reference table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FamilyCount {
+    /// Scientific family name; `None` for species without taxonomy data.
+    pub family: Option<String>,
+    pub family_common_name: Option<String>,
+    pub order: Option<String>,
+    pub species_count: u32,
+    pub detection_count: u32,
+}
+
+/// Activity in one of the eight named moon phases (0 = new moon).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoonPhaseActivity {
+    pub phase: u8,
+    pub label: String,
+    pub detections: u64,
+    /// Distinct dates with at least one detection in this phase.
+    pub nights: u64,
+}
+
+// ─── Seasonal occurrence ─────────────────────────────────────────────────────
+
+/// Detections of a species in one ISO week of the year, summed over all
+/// years, and what the metadata model expects in that week.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekOccurrence {
+    /// ISO week, 1 – 53.
+    pub week: u32,
+    pub detections: u64,
+    /// Distinct dates with at least one detection.
+    pub days: u64,
+    /// Metadata-model occurrence probability at the configured location.
+    pub expected: Option<f64>,
+}
 
-/// Compact species row shown on the home or calendar page.
+/// Week-of-year histogram for the seasonal occurrence page.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SpeciesSummary {
+pub struct SeasonalOccurrence {
+    pub scientific_name: String,
+    pub common_name: String,
+    /// Always 53 entries, week 1 first.
+    pub weeks: Vec<WeekOccurrence>,
+    /// Years with at least one detection of the species.
+    pub years: Vec<i32>,
+    /// Location the expected occurrence was computed for; `None` when no
+    /// loaded model has a metadata model.
+    pub expected_location: Option<(f64, f64)>,
+    /// Model whose metadata model supplied `expected`.
+    pub expected_model: Option<String>,
+    /// The location filter's threshold (`sf_thresh`): weeks expected
+    /// below it are filtered out during analysis.
+    pub filter_threshold: f64,
+}
+
+// ─── Period comparison ───────────────────────────────────────────────────────
+
+/// How a species' presence changed between the baseline and current period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PresenceChange {
+    /// Detected only in the current period.
+    Arrived,
+    /// Detected only in the baseline period.
+    Departed,
+    /// Detected in both periods.
+    Present,
+}
+
+impl PresenceChange {
+    pub fn from_counts(baseline: u32, current: u32) -> Self {
+        match (baseline, current) {
+            (0, _) => Self::Arrived,
+            (_, 0) => Self::Departed,
+            _ => Self::Present,
+        }
+    }
+
+    /// CSS modifier class for the comparison table row.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            Self::Arrived => "compare-arrived",
+            Self::Departed => "compare-departed",
+            Self::Present => "compare-present",
+        }
+    }
+}
+
+/// Detection counts for one species across two date ranges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesComparison {
     pub scientific_name: String,
     pub common_name: String,
     pub domain: String,
-    pub detection_count: u32,
-    /// Human-friendly count for display (e.g. `"~230"`, `"42"`).
-    #[serde(default)]
-    pub display_count: String,
-    pub last_seen: Option<String>,
-    pub image_url: Option<String>,
-    /// IUCN conservation status (populated from iNaturalist).
-    #[serde(default)]
-    pub conservation_status: Option<ConservationStatus>,
-    /// Male specimen photo URL (from iNaturalist sex-annotated observations).
-    #[serde(default)]
-    pub male_image_url: Option<String>,
-    /// Female specimen photo URL (from iNaturalist sex-annotated observations).
-    #[serde(default)]
-    pub female_image_url: Option<String>,
-    /// Verification record (ornithologist or iNaturalist observation).
-    #[serde(default)]
-    pub verification: Option<SpeciesVerification>,
+    pub baseline_count: u32,
+    pub current_count: u32,
+    pub change: PresenceChange,
+}
+
+/// Result of comparing two date ranges (all dates inclusive, YYYY-MM-DD).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodComparison {
+    pub baseline_from: String,
+    pub baseline_to: String,
+    pub current_from: String,
+    pub current_to: String,
+    pub species: Vec<SpeciesComparison>,
+}
+
+// ─── Species richness ────────────────────────────────────────────────────────
+
+/// One step of the species accumulation curve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccumulationPoint {
+    pub date: String,
+    /// Days with detections up to and including `date`.
+    pub days: u32,
+    /// Distinct species detected by then.
+    pub species: u32,
+}
+
+/// Species first detected during one week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyNewSpecies {
+    /// Monday of the ISO week, YYYY-MM-DD.
+    pub week_start: String,
+    /// Common names, in order of first detection.
+    pub species: Vec<String>,
+}
+
+/// Observed and estimated species richness of one domain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainRichness {
+    /// `""` for all domains together.
+    pub domain: String,
+    pub observed: u32,
+    /// Species detected on exactly one day.
+    pub singletons: u32,
+    /// Species detected on exactly two days.
+    pub doubletons: u32,
+    /// Bias-corrected Chao2 estimate of the total number of species.
+    pub estimated: f64,
+}
+
+impl DomainRichness {
+    /// Share of the estimated species already detected (0 – 1).
+    pub fn completeness(&self) -> f64 {
+        if self.estimated > 0.0 {
+            (self.observed as f64 / self.estimated).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Data of the species richness page.  Sampling units are days with at
+/// least one detection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichnessStats {
+    pub sampling_days: u32,
+    /// One point per sampling day, oldest first.
+    pub curve: Vec<AccumulationPoint>,
+    pub total: DomainRichness,
+    /// Per domain, most species first.
+    pub domains: Vec<DomainRichness>,
+    /// Every week from the first to the last sampling day, oldest first.
+    pub weekly_new: Vec<WeeklyNewSpecies>,
+}
+
+/// Where a capture node is and what it detected, for the map.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeLocation {
+    /// `Source_Node` of its detections.
+    pub node: String,
+    /// Location stored with the node's most recent detection.
+    pub lat: f64,
+    pub lon: f64,
+    pub detections: u64,
+    pub species: u32,
+    /// Date of the most recent detection, YYYY-MM-DD.
+    pub last_seen: String,
 }
 
 /// Health/status snapshot for the in-memory DuckDB summary cache.
@@ -409,12 +424,72 @@ pub struct CacheSummaryStatus {
     pub species_rows: u64,
     pub excluded_rows: u64,
     pub model_species_rows: u64,
+    /// Rows in the per-date, per-hour species counts table.
+    #[serde(default)]
+    pub hourly_rows: u64,
     pub parquet_files: u64,
     /// RFC3339 UTC timestamp of the most recent cache refresh.
     #[serde(default)]
     pub refreshed_at_utc: String,
 }
 
+// ─── Station comparison ──────────────────────────────────────────────────────
+
+/// What one station (`Source_Node`) detected, for comparing stations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StationSummary {
+    /// `Source_Node` of its detections; empty for detections without one.
+    pub node: String,
+    pub detections: u64,
+    pub species: u32,
+    /// Species no other station detected.
+    pub unique_species: u32,
+    /// Days with at least one detection.
+    pub days: u32,
+    /// Bias-corrected Chao2 estimate of the station's species richness.
+    pub estimated: f64,
+    /// YYYY-MM-DD of the first and last detection.
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl StationSummary {
+    /// Mean detections per day with detections.
+    pub fn detections_per_day(&self) -> f64 {
+        if self.days > 0 {
+            self.detections as f64 / self.days as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// One species in the station × species matrix.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StationSpecies {
+    pub scientific_name: String,
+    pub common_name: String,
+    pub domain: String,
+    /// Detections per station, in the order of [`StationComparison::stations`].
+    pub detections: Vec<u64>,
+}
+
+impl StationSpecies {
+    /// Number of stations that detected the species.
+    pub fn stations(&self) -> usize {
+        self.detections.iter().filter(|&&n| n > 0).count()
+    }
+}
+
+/// Data of the station comparison page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StationComparison {
+    /// Most species first.
+    pub stations: Vec<StationSummary>,
+    /// Species seen by most stations first, then most detected.
+    pub species: Vec<StationSpecies>,
+}
+
 // ─── Model info (for filter dropdowns) ───────────────────────────────────────
 
 /// A detection model available in the database.
@@ -462,6 +537,48 @@ pub struct ImportResult {
     pub errors: Vec<String>,
 }
 
+/// Lifecycle of a background import job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Progress of a background import, polled by the import page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub job_id: String,
+    /// What is being imported (node address or archive name).
+    pub source: String,
+    pub state: ImportState,
+    /// Current step, e.g. `"Extracting media"`.
+    pub phase: String,
+    pub detections_imported: u64,
+    pub files_extracted: u64,
+    pub skipped_existing: u64,
+    pub error_count: u64,
+    /// Archive bytes read in the current phase.
+    pub bytes_read: u64,
+    /// Archive size, when known (file imports only).
+    pub bytes_total: Option<u64>,
+    /// Final summary once the job has completed.
+    pub result: Option<ImportResult>,
+    /// Why the job failed.
+    pub error: Option<String>,
+}
+
+impl ImportProgress {
+    /// Fraction of the current phase done, when the total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        self.bytes_total
+            .filter(|&t| t > 0)
+            .map(|t| (self.bytes_read as f64 / t as f64).min(1.0))
+    }
+}
+
 /// A BirdNET-Pi node discovered on the local network via mDNS.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BirdnetNode {
@@ -484,6 +601,69 @@ pub struct BackupFile {
     pub size_bytes: u64,
 }
 
+/// Preview of migrating a BirdNET-Pi `birdnet.conf` (and its species
+/// lists) into `gaia.conf`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigMigration {
+    /// `gaia.conf` the changes apply to.
+    pub gaia_conf: String,
+    /// Whether that file can be written from the web container.
+    pub writable: bool,
+    /// Settings that would change, in `birdnet.conf` order.
+    pub changes: Vec<ConfigChange>,
+    /// Settings that already match.
+    pub unchanged: Vec<String>,
+    /// `birdnet.conf` keys with no Gaia equivalent.
+    pub unmapped: Vec<String>,
+    pub species_lists: Vec<SpeciesListChange>,
+}
+
+/// One `gaia.conf` line a migration adds or replaces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub key: String,
+    /// `birdnet.conf` key it comes from, when named differently.
+    pub from_key: Option<String>,
+    /// Current value (`None` = not set).
+    pub current: Option<String>,
+    pub proposed: String,
+}
+
+/// A BirdNET-Pi species list copied to the data volume.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesListChange {
+    /// File name, e.g. `include_species_list.txt`.
+    pub name: String,
+    /// Entries in the BirdNET-Pi list.
+    pub entries: usize,
+    /// Entries in the list it replaces (0 if none).
+    pub current_entries: usize,
+}
+
+// ─── Import (audio folders) ──────────────────────────────────────────────────
+
+pub use gaia_client::protocol::{FolderImportJob, FolderImportState, ImportFolder};
+
+/// Folder imports as shown on the import page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderImportView {
+    /// Folders below the processing server's `IMPORT_DIR`.
+    pub folders: Vec<ImportFolder>,
+    /// The running or last import.
+    pub job: Option<FolderImportJob>,
+    /// Species detected so far under the job's node, most detected first.
+    pub species: Vec<ImportedSpecies>,
+}
+
+/// One species found by a folder import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedSpecies {
+    pub scientific_name: String,
+    pub common_name: String,
+    pub detections: u64,
+    pub best_confidence: f64,
+}
+
 // ─── Live analysis status ────────────────────────────────────────────────────
 
 /// Snapshot of what the processing server is currently analysing.
@@ -502,6 +682,8 @@ pub struct LiveStatus {
     pub captured_at: String,
 }
 
+pub use gaia_client::protocol::{NodeBacklog, PipelineStatus, WorkerActivity};
+
 /// One prediction entry in the live status.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LivePrediction {
@@ -547,6 +729,65 @@ pub struct ExcludedSpecies {
     pub overridden: bool,
 }
 
+// ─── Review ──────────────────────────────────────────────────────────────────
+
+/// Outcome of checking a detection on the review page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewVerdict {
+    Confirmed,
+    Rejected,
+}
+
+/// A stored review of one detection clip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionReview {
+    pub verdict: ReviewVerdict,
+    /// Scientific name the reviewer says it really is, when the model
+    /// got the species wrong.
+    #[serde(default)]
+    pub corrected_species: Option<String>,
+    pub reviewed_at: String,
+}
+
+/// A training dataset archive written by the export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetExport {
+    /// Download URL of the tar archive.
+    pub url: String,
+    pub clips: u32,
+    pub classes: u32,
+    /// Matching detections whose clip is no longer on disk.
+    pub missing: u32,
+}
+
+/// Length and sample rate of a clip, for the zoomable spectrogram viewer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ClipInfo {
+    pub duration_secs: f64,
+    pub sample_rate: u32,
+}
+
+/// Peak envelope of a clip, read from the `<clip>.peaks.json` sidecar the
+/// processing server writes at extraction: min/max pairs scaled to
+/// `-127..=127`, evenly spread over `duration_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveformPeaks {
+    pub duration_secs: f64,
+    pub min: Vec<i8>,
+    pub max: Vec<i8>,
+}
+
+// ─── Favourites ──────────────────────────────────────────────────────────────
+
+/// A detection the user starred, for the Favourites page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavouriteDetection {
+    pub detection: WebDetection,
+    /// When it was starred (`YYYY-MM-DD HH:MM:SS`, UTC).
+    pub starred_at: String,
+}
+
 // ─── Settings ────────────────────────────────────────────────────────────────
 
 /// Detection settings editable from the web UI.
@@ -576,6 +817,23 @@ pub struct TaxonomyAdminStatus {
     pub class_alias_count: u32,
 }
 
+pub use gaia_client::protocol::{
+    AudioDevice, AudioDevicesResponse, CaptureStatus, InputLevels, LevelStatus, StreamState,
+    StreamStatus, TimeSyncStatus,
+};
+
+/// The part of a capture node's `/api/health` the dashboard shows.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureHealth {
+    #[serde(default)]
+    pub time_sync: Option<TimeSyncStatus>,
+    #[serde(default)]
+    pub version: String,
+    /// HTTP protocol version; `0` for nodes from before it was reported.
+    #[serde(default)]
+    pub protocol: u32,
+}
+
 fn default_colormap() -> String {
     "default".to_string()
 }
@@ -597,3 +855,68 @@ pub struct QuizItem {
     #[serde(default)]
     pub image_url: Option<String>,
 }
+
+// ─── Audit log ───────────────────────────────────────────────────────────────
+
+/// One admin action recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// UTC, `YYYY-MM-DD HH:MM:SS`.
+    pub at: String,
+    /// User name from the reverse proxy, or the client address.
+    pub actor: String,
+    /// What was done, e.g. `settings.save` or `detection.relabel`.
+    pub action: String,
+    /// What it was done to: a species, clip, job id, …
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub details: String,
+}
+
+// ─── Weekly digest ───────────────────────────────────────────────────────────
+
+/// A weekly digest report written to the digests directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    /// ISO week, e.g. `2026-W41`.
+    pub week: String,
+    /// Download URL of the HTML report.
+    pub url: String,
+    pub detections: u32,
+    pub species: u32,
+    /// Species detected for the first time that week.
+    pub new_species: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relabelled_top_recording_keeps_clip_under_predicted_name() {
+        let mut rec = TopRecording {
+            scientific_name: "Corvus corax".into(),
+            common_name: "Common Raven".into(),
+            date: "2025-05-01".into(),
+            time: "06:00:00".into(),
+            confidence: 0.8,
+            file_name: "clip.opus".into(),
+            source_node: String::new(),
+            model_name: String::new(),
+            original_common_name: Some("Carrion Crow".into()),
+            display_date: String::new(),
+            display_time: String::new(),
+        };
+        assert_eq!(
+            rec.clip_url().as_deref(),
+            Some("/extracted/By_Date/2025-05-01/Carrion_Crow/clip.opus")
+        );
+
+        rec.original_common_name = None;
+        assert_eq!(
+            rec.spectrogram_url().as_deref(),
+            Some("/extracted/By_Date/2025-05-01/Common_Raven/clip.opus.png")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Audit page – read-only log of admin actions (settings, imports,
+//! relabels, reviews, overrides, verifications, photo pins), newest
+//! first.  See [`crate::server::audit`] for what identifies the actor.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, Resource, ServerFnError, Suspense};
+
+use crate::components::locale::use_locale;
+use crate::model::AuditEntry;
+
+/// How many entries the page shows.
+const PAGE_LIMIT: usize = 500;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_audit_log(limit: usize) -> Result<Vec<AuditEntry>, ServerFnError> {
+    crate::server::kv::get_audit_entries(limit)
+        .await
+        .map_err(|e| ServerFnError::new(format!("KV error: {e}")))
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+#[component]
+pub fn AuditPage() -> impl IntoView {
+    let entries = Resource::new(|| (), |_| async { get_audit_log(PAGE_LIMIT).await });
+    let (filter, set_filter) = signal(String::new());
+
+    view! {
+        <div class="audit-page">
+            <h1>"Audit Log"</h1>
+            <p class="page-desc">
+                "Admin actions taken in the dashboard, newest first. The actor is the user "
+                "name passed on by an authenticating reverse proxy, or the client address."
+            </p>
+            <input
+                type="search"
+                class="audit-filter"
+                placeholder="Filter by action, actor or target"
+                prop:value=filter
+                on:input=move |ev| set_filter.set(event_target_value(&ev))
+            />
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
+                {move || entries.get().map(|res| match res {
+                    Ok(list) if list.is_empty() => view! {
+                        <p class="text-muted">"Nothing recorded yet."</p>
+                    }.into_any(),
+                    Ok(list) => {
+                        let needle = filter.get().to_lowercase();
+                        let rows: Vec<AuditEntry> = list
+                            .into_iter()
+                            .filter(|e| {
+                                needle.is_empty()
+                                    || [&e.action, &e.actor, &e.target]
+                                        .iter()
+                                        .any(|f| f.to_lowercase().contains(&needle))
+                            })
+                            .collect();
+                        view! { <AuditTable entries=rows /> }.into_any()
+                    }
+                    Err(e) => view! { <p class="error">"Error: " {e.to_string()}</p> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn AuditTable(entries: Vec<AuditEntry>) -> impl IntoView {
+    let locale = use_locale();
+    view! {
+        <table class="report-table audit-table">
+            <thead>
+                <tr>
+                    <th>"When"</th>
+                    <th>"Who"</th>
+                    <th>"Action"</th>
+                    <th>"Target"</th>
+                    <th>"Details"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {entries.into_iter().map(|e| view! {
+                    <tr>
+                        <td class="audit-time">{move || locale.utc_timestamp(&e.at)}</td>
+                        <td>{e.actor}</td>
+                        <td><code>{e.action}</code></td>
+                        <td>{e.target}</td>
+                        <td>{e.details}</td>
+                    </tr>
+                }).collect::<Vec<_>>()}
+            </tbody>
+        </table>
+    }
+}
@@ -8,6 +8,7 @@ use leptos::prelude::{
 use leptos::either::Either;
 
 use crate::components::calendar_grid::CalendarGrid;
+use crate::components::domain_filter::use_domain;
 use crate::model::CalendarDay;
 
 // ─── Server function ─────────────────────────────────────────────────────────
@@ -16,11 +17,13 @@ use crate::model::CalendarDay;
 pub async fn get_calendar_data(
     year: i32,
     month: u32,
+    domain: String,
 ) -> Result<Vec<CalendarDay>, ServerFnError> {
     use crate::server::detections_duckdb as ddb;
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
-    ddb::calendar_data(&state.db_path, year, month)
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    ddb::calendar_data(&state.db_path, year, month, domain_opt)
         .await
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
 }
@@ -35,10 +38,12 @@ pub fn CalendarPage() -> impl IntoView {
     let (year, set_year) = signal(now.0);
     let (month, set_month) = signal(now.1);
 
-    // Fetch calendar data whenever year/month changes
+    let domain = use_domain();
+
+    // Fetch calendar data whenever year/month or the domain changes
     let calendar = Resource::new(
-        move || (year.get(), month.get()),
-        |(y, m)| async move { get_calendar_data(y, m).await },
+        move || (year.get(), month.get(), domain.get()),
+        |(y, m, domain)| async move { get_calendar_data(y, m, domain).await },
     );
 
     let go_prev = move |_| {
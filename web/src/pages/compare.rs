@@ -0,0 +1,153 @@
+//! Compare page – species lists for two date ranges side by side.
+//!
+//! Query parameters (all optional, `YYYY-MM-DD`, inclusive):
+//! `from` / `to` select the current period (default: last 7 days) and
+//! `prev_from` / `prev_to` the baseline (default: the equally long window
+//! right before `from`).
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, For, IntoView, Resource, ServerFnError, Suspense};
+use leptos_router::hooks::use_query_map;
+
+use crate::components::domain_filter::{domain_class, use_domain};
+use crate::model::{PeriodComparison, PresenceChange, SpeciesComparison};
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_period_comparison(
+    prev_from: String,
+    prev_to: String,
+    from: String,
+    to: String,
+    domain: String,
+) -> Result<PeriodComparison, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+
+    let today = chrono::Utc::now().date_naive();
+    let [bf, bt, cf, ct] = ddb::resolve_compare_ranges(today, &prev_from, &prev_to, &from, &to)
+        .map_err(ServerFnError::new)?;
+    let (bf, bt, cf, ct) = (bf.to_string(), bt.to_string(), cf.to_string(), ct.to_string());
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    ddb::compare_periods(&state.db_path, (&bf, &bt), (&cf, &ct), domain_opt)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+/// Side-by-side species counts for a baseline and a current period.
+#[component]
+pub fn ComparePage() -> impl IntoView {
+    let query = use_query_map();
+    let param = move |key: &'static str| query.with(|q| q.get(key).unwrap_or_default());
+
+    let domain = use_domain();
+
+    let data = Resource::new(
+        move || (param("prev_from"), param("prev_to"), param("from"), param("to"), domain.get()),
+        |(pf, pt, f, t, domain)| async move { get_period_comparison(pf, pt, f, t, domain).await },
+    );
+
+    view! {
+        <div class="compare-page">
+            <h1>"Compare Periods"</h1>
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading comparison\u{2026}"</p> }>
+                {move || data.get().map(|res| match res {
+                    Ok(cmp) => view! { <ComparisonView cmp=cmp /> }.into_any(),
+                    Err(e) => view! {
+                        <p class="error">"Error: " {e.to_string()}</p>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Range picker plus the comparison table.
+#[component]
+fn ComparisonView(cmp: PeriodComparison) -> impl IntoView {
+    let count = |c: PresenceChange| cmp.species.iter().filter(|s| s.change == c).count();
+    let arrived = count(PresenceChange::Arrived);
+    let departed = count(PresenceChange::Departed);
+    let present = count(PresenceChange::Present);
+    let species = cmp.species.clone();
+    let domain = use_domain();
+
+    view! {
+        // Plain GET form – the ranges live in the URL so a comparison can
+        // be bookmarked or shared.
+        <form class="compare-form" method="get" action="/compare">
+            <fieldset>
+                <legend>"Baseline"</legend>
+                <input type="date" name="prev_from" value=cmp.baseline_from.clone() />
+                " – "
+                <input type="date" name="prev_to" value=cmp.baseline_to.clone() />
+            </fieldset>
+            <fieldset>
+                <legend>"Current"</legend>
+                <input type="date" name="from" value=cmp.current_from.clone() />
+                " – "
+                <input type="date" name="to" value=cmp.current_to.clone() />
+            </fieldset>
+            // The form reloads the page; carry the domain along.
+            <input type="hidden" name="domain" value=move || domain.get() disabled=move || domain.get().is_empty() />
+            <button type="submit" class="sort-btn">"Compare"</button>
+        </form>
+
+        <p class="compare-summary">
+            <span class="compare-arrived">{arrived} " arrived"</span>
+            " · "
+            <span class="compare-departed">{departed} " disappeared"</span>
+            " · "
+            {present} " in both"
+        </p>
+
+        <table class="report-table compare-table">
+            <thead>
+                <tr>
+                    <th>"Species"</th>
+                    <th>{format!("{} – {}", cmp.baseline_from, cmp.baseline_to)}</th>
+                    <th>{format!("{} – {}", cmp.current_from, cmp.current_to)}</th>
+                    <th>"Change"</th>
+                </tr>
+            </thead>
+            <tbody>
+                <For
+                    each=move || species.clone()
+                    key=|s| s.scientific_name.clone()
+                    children=move |s: SpeciesComparison| view! { <ComparisonRow row=s /> }
+                />
+            </tbody>
+        </table>
+    }
+}
+
+#[component]
+fn ComparisonRow(row: SpeciesComparison) -> impl IntoView {
+    let href = format!("/species/{}", row.scientific_name.replace(' ', "%20"));
+    let label = match row.change {
+        PresenceChange::Arrived => "new".to_string(),
+        PresenceChange::Departed => "gone".to_string(),
+        PresenceChange::Present => {
+            let delta = row.current_count as i64 - row.baseline_count as i64;
+            if delta > 0 { format!("+{delta}") } else { delta.to_string() }
+        }
+    };
+
+    view! {
+        <tr class=row.change.css_class()>
+            <td>
+                <a href=href><strong>{row.common_name.clone()}</strong></a>
+                " (" <em>{row.scientific_name.clone()}</em> ")"
+                <span class=format!("domain-badge {}", domain_class(&row.domain))>{row.domain.clone()}</span>
+            </td>
+            <td>{row.baseline_count}</td>
+            <td>{row.current_count}</td>
+            <td>{label}</td>
+        </tr>
+    }
+}
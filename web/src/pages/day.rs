@@ -8,7 +8,9 @@ use leptos::prelude::{
 use leptos_router::hooks::use_params_map;
 
 use crate::components::detection_card::DetectionCard;
+use crate::components::domain_filter::{domain_class, use_domain};
 use crate::components::hourly_chart::SpeciesHourlyGrid;
+use crate::components::locale::use_locale;
 use crate::components::model_filter::ModelFilter;
 use crate::model::{DayDetectionGroup, SpeciesHourlyCounts, WebDetection};
 
@@ -18,20 +20,20 @@ use crate::model::{DayDetectionGroup, SpeciesHourlyCounts, WebDetection};
 pub async fn get_day_detections(
     date: String,
     model_slug: String,
+    domain: String,
 ) -> Result<Vec<DayDetectionGroup>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
-    let mut groups = ddb::day_detections_filtered(&state.db_path, &date, slug_opt)
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    let mut groups = ddb::day_detections_filtered(&state.db_path, &date, slug_opt, domain_opt)
         .await
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
 
     // Enrich with images
     for g in groups.iter_mut() {
-        if let Some(photo) =
-            inaturalist::lookup(&state.photo_cache, &g.scientific_name).await
-        {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &g.scientific_name) {
             g.image_url = Some(photo.medium_url);
         }
     }
@@ -51,6 +53,28 @@ pub async fn get_day_hourly(
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
 }
 
+/// The day's detections as an eBird Record Format CSV, one stationary
+/// checklist per `period_minutes`.
+#[server(prefix = "/api")]
+pub async fn export_ebird_checklists(
+    date: String,
+    model_slug: String,
+    period_minutes: u32,
+) -> Result<String, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, ebird};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
+    let entries = ddb::ebird_entries(&state.db_path, &date, period_minutes, slug_opt)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
+    if entries.is_empty() {
+        return Err(ServerFnError::new("No detections to export for this day"));
+    }
+    let tz = ddb::read_tz_offset(&state.db_path).await;
+    ebird::checklist_csv(&date, period_minutes, tz, &entries).map_err(ServerFnError::new)
+}
+
 // ─── Page component ──────────────────────────────────────────────────────────
 
 /// Detail view for a single day, showing every species detected.
@@ -61,19 +85,22 @@ pub fn DayView() -> impl IntoView {
         params.with(|p| p.get("date").unwrap_or_default())
     };
     let (model_slug, set_model_slug) = signal(String::new());
+    let domain = use_domain();
+    let locale = use_locale();
 
     let data = Resource::new(
-        move || (date(), model_slug.get()),
-        |(d, slug)| async move { get_day_detections(d.clone(), slug).await },
+        move || (date(), model_slug.get(), domain.get()),
+        |(d, slug, domain)| async move { get_day_detections(d.clone(), slug, domain).await },
     );
     let hourly = Resource::new(date, |d| async move { get_day_hourly(d).await });
 
     view! {
         <div class="day-page">
-            <h1>"Detections for " {date}</h1>
+            <h1>"Detections for " {move || locale.date(&date())}</h1>
             <a href="/calendar" class="back-link">"← Back to Calendar"</a>
 
             <ModelFilter selected=model_slug set_selected=set_model_slug />
+            <EbirdExport date=Signal::derive(date) model_slug=model_slug />
 
             // ── Species × Hour heatmap ───────────────────────────────
             <Suspense fallback=|| view! { <p class="loading">"Loading chart\u{2026}"</p> }>
@@ -127,7 +154,7 @@ fn DayGroup(group: DayDetectionGroup) -> impl IntoView {
     );
 
     view! {
-        <div class="day-group">
+        <div class=format!("day-group {}", domain_class(&group.domain))>
             <div class="day-group-header">
                 <img src={img_src} alt={group.common_name.clone()} class="day-group-img" loading="lazy" />
                 <div class="day-group-info">
@@ -152,3 +179,79 @@ fn DayGroup(group: DayDetectionGroup) -> impl IntoView {
         </div>
     }.into_any()
 }
+
+/// "Export day to eBird" control: builds the CSV on the server and offers
+/// it as a download.
+#[component]
+fn EbirdExport(date: Signal<String>, model_slug: ReadSignal<String>) -> impl IntoView {
+    let (period, set_period) = signal(60u32);
+    let (busy, set_busy) = signal(false);
+    let (result, set_result) = signal(Option::<Result<String, String>>::None);
+
+    let on_click = move |_| {
+        set_busy.set(true);
+        set_result.set(None);
+        let (d, slug, minutes) = (date.get(), model_slug.get(), period.get());
+        leptos::task::spawn_local(async move {
+            let res = export_ebird_checklists(d, slug, minutes)
+                .await
+                .map_err(|e| e.to_string());
+            set_result.set(Some(res));
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <div class="ebird-export">
+            <select
+                title="Checklist length"
+                on:change=move |ev| {
+                    set_period.set(event_target_value(&ev).parse().unwrap_or(60));
+                    set_result.set(None);
+                }
+            >
+                <option value="60" selected>"Hourly checklists"</option>
+                <option value="180">"3-hour checklists"</option>
+                <option value="1440">"One checklist for the day"</option>
+            </select>
+            <button class="ebird-export-btn" disabled=move || busy.get() on:click=on_click>
+                {move || if busy.get() { "Exporting…" } else { "Export day to eBird" }}
+            </button>
+            {move || match result.get() {
+                Some(Ok(csv)) => view! {
+                    <a
+                        class="ebird-export-link"
+                        href=csv_data_url(&csv)
+                        download=format!("ebird-{}.csv", date.get())
+                    >
+                        {format!("⬇ ebird-{}.csv", date.get())}
+                    </a>
+                    <a
+                        class="ebird-export-help"
+                        href="https://ebird.org/import/upload.form"
+                        target="_blank"
+                        rel="noopener"
+                    >
+                        "Upload (eBird Record Format)"
+                    </a>
+                }.into_any(),
+                Some(Err(e)) => view! { <span class="ebird-export-error">{e}</span> }.into_any(),
+                None => ().into_any(),
+            }}
+        </div>
+    }
+}
+
+/// `data:` URL carrying `csv`, so the browser can save it without a
+/// second request.
+fn csv_data_url(csv: &str) -> String {
+    let mut url = String::from("data:text/csv;charset=utf-8,");
+    for b in csv.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+            url.push(b as char);
+        } else {
+            url.push_str(&format!("%{b:02X}"));
+        }
+    }
+    url
+}
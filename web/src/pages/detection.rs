@@ -0,0 +1,211 @@
+//! Detection permalink page – one detection at `/detection/:id`, with
+//! OpenGraph / Twitter card tags so a shared link previews the species,
+//! confidence and spectrogram.
+//!
+//! The route is rendered with `SsrMode::Async`: the detection is loaded
+//! before any HTML is sent, so the `<meta>` tags end up in `<head>` for
+//! crawlers that don't run JavaScript.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, Resource, ServerFnError, Suspense};
+use leptos_meta::{Link, Meta, Title};
+use leptos_router::hooks::use_params_map;
+
+use crate::components::detection_card::DetectionCard;
+use crate::model::{DetectionPermalink, TopPrediction};
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_detection(id: i64) -> Result<Option<DetectionPermalink>, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, share, species_images};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let Some(mut detection) = ddb::detection_by_id(&state.db_path, id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?
+    else {
+        return Ok(None);
+    };
+    let top_predictions = ddb::top_predictions(id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
+    if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &detection.scientific_name) {
+        detection.image_url = Some(photo.medium_url);
+    }
+    let origin = use_context::<http::request::Parts>()
+        .map(|parts| share::request_origin(&parts.headers))
+        .unwrap_or_default();
+    Ok(Some(DetectionPermalink {
+        detection,
+        origin,
+        top_predictions,
+    }))
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+#[component]
+pub fn DetectionPage() -> impl IntoView {
+    let params = use_params_map();
+    let id = move || params.with(|p| p.get("id").and_then(|id| id.parse::<i64>().ok()));
+
+    let detection = Resource::new(id, |id| async move {
+        match id {
+            Some(id) => get_detection(id).await,
+            None => Ok(None),
+        }
+    });
+
+    view! {
+        <div class="detection-page">
+            <a href="/" class="back-link">"← Live feed"</a>
+            <Suspense fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
+                {move || detection.get().map(|res| match res {
+                    Ok(Some(found)) => view! { <DetectionPermalinkView found/> }.into_any(),
+                    Ok(None) => view! {
+                        <Title text="Detection not found – Gaia Audio"/>
+                        <p class="error">"Detection not found. It may have been removed by clip retention."</p>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <p class="error">"Error: " {e.to_string()}</p>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn DetectionPermalinkView(found: DetectionPermalink) -> impl IntoView {
+    let DetectionPermalink {
+        detection,
+        origin,
+        top_predictions,
+    } = found;
+
+    let title = format!(
+        "{} ({:.0}%)",
+        detection.common_name,
+        detection.confidence * 100.0
+    );
+    let when = if detection.display_date.is_empty() {
+        format!("{} {}", detection.date, detection.time)
+    } else {
+        format!("{} {}", detection.display_date, detection.display_time)
+    };
+    let description = format!(
+        "{} detected with {:.0}% confidence on {when} by {} at {}.",
+        detection.scientific_name,
+        detection.confidence * 100.0,
+        detection.model_label(),
+        detection.source_label(),
+    );
+    let page_url = format!("{origin}{}", detection.permalink());
+    // Spectrogram first: it is what was actually heard.  Species photos
+    // from iNaturalist are already absolute.
+    let image = detection
+        .spectrogram_url()
+        .map(|path| format!("{origin}{path}"))
+        .or_else(|| detection.image_url.clone());
+    let audio = detection.clip_url().map(|path| format!("{origin}{path}"));
+    let card = if image.is_some() { "summary_large_image" } else { "summary" };
+    let provenance = detection
+        .model_provenance()
+        .map(|p| format!("{} · {p}", detection.model_label()));
+    let bearing = detection.bearing_deg.zip(detection.bearing_label());
+
+    view! {
+        <Title text=format!("{title} – Gaia Audio")/>
+        <Link rel="canonical" href=page_url.clone()/>
+        <Meta property="og:type" content="website"/>
+        <Meta property="og:site_name" content="Gaia Audio"/>
+        <Meta property="og:title" content=title.clone()/>
+        <Meta property="og:description" content=description.clone()/>
+        <Meta property="og:url" content=page_url/>
+        {image.clone().map(|url| view! { <Meta property="og:image" content=url/> })}
+        {audio.map(|url| view! { <Meta property="og:audio" content=url/> })}
+        <Meta name="twitter:card" content=card/>
+        <Meta name="twitter:title" content=title/>
+        <Meta name="twitter:description" content=description/>
+        {image.map(|url| view! { <Meta name="twitter:image" content=url/> })}
+
+        <DetectionCard detection=detection/>
+        {provenance.map(|p| view! {
+            <p class="detection-provenance" title="Model version · variant · runtime">{p}</p>
+        })}
+        {bearing.map(|(degrees, label)| view! { <CompassRose degrees label/> })}
+        {(!top_predictions.is_empty()).then(|| view! { <TopPredictions predictions=top_predictions/> })}
+    }
+}
+
+/// Where the sound came from, as an arrow on a compass rose.
+#[component]
+fn CompassRose(degrees: f64, label: String) -> impl IntoView {
+    let ticks = (0..16)
+        .map(|i| {
+            let (sin, cos) = (i as f64 * 22.5).to_radians().sin_cos();
+            // Longer ticks for the cardinal and intercardinal points.
+            let inner = match i % 4 {
+                0 => 40.0,
+                2 => 44.0,
+                _ => 47.0,
+            };
+            view! {
+                <line
+                    x1=format!("{:.1}", inner * sin)
+                    y1=format!("{:.1}", -inner * cos)
+                    x2=format!("{:.1}", 50.0 * sin)
+                    y2=format!("{:.1}", -50.0 * cos)
+                />
+            }
+        })
+        .collect::<Vec<_>>();
+    view! {
+        <figure class="compass-rose" title="Estimated direction of the sound">
+            <svg viewBox="-64 -64 128 128" width="160" height="160" role="img">
+                <circle class="compass-ring" r="50"/>
+                <g class="compass-ticks">{ticks}</g>
+                <text x="0" y="-54">"N"</text>
+                <text x="58" y="4">"E"</text>
+                <text x="0" y="62">"S"</text>
+                <text x="-58" y="4">"W"</text>
+                <g transform=format!("rotate({degrees:.1})")>
+                    <line class="compass-needle" x1="0" y1="0" x2="0" y2="-36"/>
+                    <polygon class="compass-head" points="0,-46 -6,-34 6,-34"/>
+                </g>
+                <circle class="compass-hub" r="3"/>
+            </svg>
+            <figcaption>"Bearing " {label}</figcaption>
+        </figure>
+    }
+}
+
+/// The model's alternative labels for the detection, collapsed by default.
+#[component]
+fn TopPredictions(predictions: Vec<TopPrediction>) -> impl IntoView {
+    let summary = format!("Top {} predictions", predictions.len());
+    view! {
+        <details class="top-predictions">
+            <summary>{summary}</summary>
+            <table class="report-table">
+                <thead>
+                    <tr>
+                        <th>"Species"</th>
+                        <th>"Scientific name"</th>
+                        <th>"Confidence"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {predictions.into_iter().map(|p| view! {
+                        <tr>
+                            <td>{p.common_name}</td>
+                            <td><em>{p.scientific_name}</em></td>
+                            <td>{format!("{:.1}%", p.confidence * 100.0)}</td>
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+        </details>
+    }
+}
@@ -12,6 +12,7 @@ use leptos::prelude::{
 };
 
 use crate::components::detection_card::DetectionCard;
+use crate::components::locale::use_locale;
 use crate::model::{ExcludedSpecies, WebDetection};
 use crate::pages::species_list::get_stats_cache_status;
 
@@ -19,7 +20,7 @@ use crate::pages::species_list::get_stats_cache_status;
 
 #[server(prefix = "/api")]
 pub async fn get_excluded_species() -> Result<Vec<ExcludedSpecies>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let mut species = ddb::excluded_species(&state.db_path)
@@ -27,7 +28,7 @@ pub async fn get_excluded_species() -> Result<Vec<ExcludedSpecies>, ServerFnErro
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
 
     for sp in species.iter_mut() {
-        if let Some(photo) = inaturalist::lookup(&state.photo_cache, &sp.scientific_name).await {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &sp.scientific_name) {
             sp.image_url = Some(photo.medium_url);
         }
     }
@@ -43,6 +44,7 @@ pub async fn override_exclusion(
     crate::server::kv::add_exclusion_override(&scientific_name, &notes)
         .await
         .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+    crate::server::audit::record("exclusion.override", &scientific_name, notes).await;
     Ok(())
 }
 
@@ -51,6 +53,7 @@ pub async fn remove_override(scientific_name: String) -> Result<(), ServerFnErro
     crate::server::kv::remove_exclusion_override(&scientific_name)
         .await
         .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+    crate::server::audit::record("exclusion.remove", &scientific_name, "").await;
     Ok(())
 }
 
@@ -58,7 +61,7 @@ pub async fn remove_override(scientific_name: String) -> Result<(), ServerFnErro
 pub async fn get_excluded_detections(
     scientific_name: String,
 ) -> Result<Vec<WebDetection>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let mut detections = ddb::excluded_detections_for_species(&state.db_path, &scientific_name, 20)
@@ -67,7 +70,7 @@ pub async fn get_excluded_detections(
 
     // Enrich with iNaturalist images
     for det in detections.iter_mut() {
-        if let Some(photo) = inaturalist::lookup(&state.photo_cache, &det.scientific_name).await {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &det.scientific_name) {
             det.image_url = Some(photo.medium_url);
         }
     }
@@ -181,6 +184,7 @@ fn ExcludedCard(
     let sci_name = species.scientific_name.clone();
     let sci_name_action = sci_name.clone();
     let is_overridden = species.overridden;
+    let locale = use_locale();
 
     let (busy, set_busy) = signal(false);
 
@@ -262,8 +266,8 @@ fn ExcludedCard(
                     <span class="domain-badge">{species.domain.clone()}</span>
                     <span class="confidence high">{confidence_pct}</span>
                     <span class="count-label">{count_label}</span>
-                    {species.last_seen.as_ref().map(|d| view! {
-                        <span class="last-seen">"Last: " {d.clone()}</span>
+                    {species.last_seen.clone().map(|d| view! {
+                        <span class="last-seen">"Last: " {move || locale.utc_timestamp(&d)}</span>
                     })}
                 </div>
 
@@ -0,0 +1,102 @@
+//! Favourites page – the detections the user starred on a detection
+//! card, most recently starred first, with their clips and spectrograms.
+//!
+//! Starred detections are shown even when the species-range model
+//! excluded them; starred detections no longer in the database are
+//! left out.  The collection can be downloaded through the
+//! detection export (`favourites=true`).
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, Resource, ServerFnError, Suspense};
+
+use crate::components::detection_card::DetectionCard;
+use crate::components::locale::use_locale;
+use crate::model::FavouriteDetection;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_favourites() -> Result<Vec<FavouriteDetection>, ServerFnError> {
+    use crate::components::favourite::request_user;
+    use crate::server::{detections_duckdb as ddb, kv, species_images};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+
+    let starred = kv::get_favourites(&request_user())
+        .await
+        .map_err(ServerFnError::new)?;
+    if starred.is_empty() {
+        return Ok(Vec::new());
+    }
+    let query = ddb::DetectionQuery {
+        ids: Some(starred.keys().copied().collect()),
+        include_excluded: true,
+        ..Default::default()
+    };
+    let detections = ddb::query_detections(&state.db_path, &query, starred.len() as u32)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
+
+    let mut favourites: Vec<FavouriteDetection> = detections
+        .into_iter()
+        .map(|mut detection| {
+            if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &detection.scientific_name) {
+                detection.image_url = Some(photo.medium_url);
+            }
+            let starred_at = starred.get(&detection.id).cloned().unwrap_or_default();
+            FavouriteDetection { detection, starred_at }
+        })
+        .collect();
+    favourites.sort_by(|a, b| b.starred_at.cmp(&a.starred_at));
+    Ok(favourites)
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+#[component]
+pub fn FavouritesPage() -> impl IntoView {
+    let favourites = Resource::new(|| (), |_| async { get_favourites().await });
+    let locale = use_locale();
+
+    view! {
+        <div class="favourites-page">
+            <div class="page-title-row">
+                <h1>"Favourites"</h1>
+                <span class="favourites-export">
+                    "Download: "
+                    <a href="/api/export/detections?favourites=true" download>"CSV"</a>
+                    " · "
+                    <a href="/api/export/detections?favourites=true&format=parquet" download>"Parquet"</a>
+                </span>
+            </div>
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading favourites\u{2026}"</p> }>
+                {move || favourites.get().map(|res| match res {
+                    Ok(list) if list.is_empty() => view! {
+                        <p class="text-muted">
+                            "No favourites yet. Star a detection with ☆ on its card to keep it here."
+                        </p>
+                    }.into_any(),
+                    Ok(list) => view! {
+                        <p class="text-muted">{format!("{} starred detection(s)", list.len())}</p>
+                        <div class="favourites-list">
+                            <For
+                                each=move || list.clone()
+                                key=|f| f.detection.id
+                                children=move |f: FavouriteDetection| view! {
+                                    <div class="favourite-item">
+                                        <span class="favourite-starred">"★ Starred " {move || locale.utc_timestamp(&f.starred_at)}</span>
+                                        <DetectionCard detection=f.detection />
+                                    </div>
+                                }
+                            />
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <p class="error">"Error: " {e.to_string()}</p>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
@@ -3,14 +3,21 @@
 use leptos::prelude::*;
 use leptos::prelude::{
     signal, Effect, ElementChild, For, IntoView, Resource,
-    ServerFnError, Suspense,
+    ServerFnError, Suspense, Transition,
 };
 use leptos::either::Either;
 
+use crate::components::connection::{last_good, retry_on_error, ConnectionContext};
 use crate::components::detection_card::DetectionCard;
+use crate::components::domain_filter::use_domain;
+use crate::components::family_breakdown::FamilyBreakdown;
+use crate::components::input_level::InputLevel;
 use crate::components::live_analysis::LiveAnalysis;
 use crate::components::model_filter::ModelFilter;
+use crate::components::moon_activity::MoonActivity;
+use crate::components::pipeline_activity::PipelineActivity;
 use crate::components::species_card::SpeciesCard;
+use crate::components::stream_health::StreamHealth;
 use crate::components::urban_noise::UrbanNoise;
 use crate::model::{SpeciesSummary, WebDetection};
 
@@ -21,18 +28,20 @@ pub async fn get_recent_detections(
     limit: u32,
     after_rowid: Option<i64>,
     model_slug: String,
+    domain: String,
 ) -> Result<Vec<WebDetection>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
-    let mut detections = ddb::recent_detections_filtered(&state.db_path, limit, after_rowid, slug_opt)
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    let mut detections = ddb::recent_detections_filtered(&state.db_path, limit, after_rowid, slug_opt, domain_opt)
         .await
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
 
     // Enrich with iNaturalist species photos
     for det in detections.iter_mut() {
-        if let Some(photo) = inaturalist::lookup(&state.photo_cache, &det.scientific_name).await {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &det.scientific_name) {
             det.image_url = Some(photo.medium_url);
         }
     }
@@ -43,8 +52,9 @@ pub async fn get_recent_detections(
 pub async fn get_top_species(
     limit: u32,
     model_slug: String,
+    domain: String,
 ) -> Result<Vec<SpeciesSummary>, ServerFnError> {
-    use crate::server::{kv, detections_duckdb as ddb, inaturalist};
+    use crate::server::{kv, detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
 
@@ -53,38 +63,65 @@ pub async fn get_top_species(
     let today = kv::today_for_tz()
         .await;
     let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
-    let mut species = ddb::top_species_for_date_filtered(&state.db_path, &today, limit, slug_opt)
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    let mut species = ddb::top_species_for_date_filtered(&state.db_path, &today, limit, slug_opt, domain_opt)
         .await
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
 
+    // 30-day activity sparklines, one query for the whole sidebar.
+    let names: Vec<String> = species.iter().map(|s| s.scientific_name.clone()).collect();
+    match ddb::species_daily_counts(&state.db_path, &names, &today, SPARKLINE_DAYS, slug_opt).await {
+        Ok(mut counts) => {
+            for sp in species.iter_mut() {
+                sp.daily_counts = counts.remove(&sp.scientific_name).unwrap_or_default();
+            }
+        }
+        Err(e) => tracing::warn!("Sparkline query failed: {e}"),
+    }
+
     // Enrich with iNaturalist images
     for sp in species.iter_mut() {
-        if let Some(photo) = inaturalist::lookup(&state.photo_cache, &sp.scientific_name).await {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &sp.scientific_name) {
             sp.image_url = Some(photo.medium_url);
         }
     }
     Ok(species)
 }
 
+/// Days covered by the Top Species sparklines.
+#[cfg(feature = "ssr")]
+const SPARKLINE_DAYS: u32 = 30;
+
 // ─── Page component ──────────────────────────────────────────────────────────
 
 /// Live detection feed with auto-polling + top species sidebar.
 #[component]
 pub fn Home() -> impl IntoView {
-    // Model filter
+    // Model filter, plus the domain chosen in the nav bar
     let (model_slug, set_model_slug) = signal(String::new());
+    let domain = use_domain();
 
-    // Latest detections resource (initial load) – re-fetches when model changes
+    // Latest detections resource (initial load) – re-fetches when the filters change
     let detections = Resource::new(
-        move || model_slug.get(),
-        |slug| async move { get_recent_detections(50, None, slug).await },
+        move || (model_slug.get(), domain.get()),
+        |(slug, domain)| async move { get_recent_detections(50, None, slug, domain).await },
     );
 
-    // Top species – also re-fetches when model changes
+    // Top species – also re-fetches when the filters change
     let top_species = Resource::new(
-        move || model_slug.get(),
-        |slug| async move { get_top_species(12, slug).await },
+        move || (model_slug.get(), domain.get()),
+        |(slug, domain)| async move { get_top_species(12, slug, domain).await },
+    );
+    // Retry failed loads; keep yesterday's sidebar up while the server restarts.
+    retry_on_error(
+        move || matches!(detections.get(), Some(Err(_))),
+        move || detections.refetch(),
     );
+    retry_on_error(
+        move || matches!(top_species.get(), Some(Err(_))),
+        move || top_species.refetch(),
+    );
+    let top_species = last_good(move || top_species.get());
 
     // Auto-refresh: poll every 4 seconds for new detections
     let (feed, set_feed) = signal::<Vec<WebDetection>>(vec![]);
@@ -106,11 +143,18 @@ pub fn Home() -> impl IntoView {
     {
         use wasm_bindgen::prelude::*;
         use wasm_bindgen::JsCast;
+        let conn = use_context::<ConnectionContext>();
         let cb = Closure::wrap(Box::new(move || {
+            // The banner already says so; the next poll after the
+            // server is back catches up from `max_rowid`.
+            if conn.is_some_and(|c| !c.online.get_untracked()) {
+                return;
+            }
             let rid = max_rowid.get();
             let slug = model_slug.get();
+            let domain = domain.get();
             leptos::task::spawn_local(async move {
-                if let Ok(new) = get_recent_detections(20, rid, slug).await {
+                if let Ok(new) = get_recent_detections(20, rid, slug, domain).await {
                     if !new.is_empty() {
                         if let Some(first) = new.first() {
                             set_max_rowid.set(Some(first.id));
@@ -155,7 +199,7 @@ pub fn Home() -> impl IntoView {
 
             <aside class="top-species">
                 <h2>"Today's Top Species"</h2>
-                <Suspense fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
+                <Transition fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
                     {move || top_species.get().map(|res| match res {
                         Ok(species) => Either::Left(view! {
                             <div class="species-grid">
@@ -172,8 +216,13 @@ pub fn Home() -> impl IntoView {
                             <p class="error">"Error: " {e.to_string()}</p>
                         }),
                     })}
-                </Suspense>
+                </Transition>
+                <FamilyBreakdown model_slug=model_slug/>
+                <MoonActivity model_slug=model_slug/>
                 <UrbanNoise/>
+                <InputLevel/>
+                <StreamHealth/>
+                <PipelineActivity/>
             </aside>
         </div>
     }
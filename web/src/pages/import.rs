@@ -1,4 +1,12 @@
 //! Import page – discover BirdNET-Pi nodes and import observations.
+//!
+//! Imports run as background jobs on the server; the page polls their
+//! progress and can cancel or resume them, and reattaches to the latest
+//! job after a reload.
+//!
+//! Folders of plain audio files (no BirdNET-Pi database) are analysed by
+//! the processing server instead; the page starts those imports through
+//! its REST API and shows what they found.
 
 use leptos::prelude::*;
 use leptos::prelude::{
@@ -6,7 +14,10 @@ use leptos::prelude::{
     Suspense,
 };
 
-use crate::model::{BackupFile, BirdnetNode, ImportReport, ImportResult};
+use crate::model::{
+    BackupFile, BirdnetNode, ConfigMigration, FolderImportState, FolderImportView,
+    ImportProgress, ImportReport, ImportState,
+};
 
 // ─── Server functions ────────────────────────────────────────────────────────
 
@@ -22,37 +33,60 @@ pub async fn discover_nodes() -> Result<Vec<BirdnetNode>, ServerFnError> {
     Ok(nodes)
 }
 
-/// Stream-import a BirdNET-Pi backup directly from a node on the network.
+/// Start streaming a BirdNET-Pi backup directly from a node on the
+/// network; returns the import job id.
 #[server(prefix = "/api")]
 pub async fn import_from_node(
     address: String,
     port: u16,
     username: Option<String>,
     password: Option<String>,
-) -> Result<ImportResult, ServerFnError> {
+) -> Result<String, ServerFnError> {
     use crate::server::import;
 
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
 
-    let db_path = state.db_path.clone();
-    let extracted_dir = state.extracted_dir.clone();
-    let user = username.unwrap_or_else(|| "birdnet".to_string());
-    let pass = password.unwrap_or_default();
+    let source = import::ImportSource::Node {
+        address,
+        port,
+        username: username.unwrap_or_else(|| "birdnet".to_string()),
+        password: password.unwrap_or_default(),
+    };
+    let label = source.label();
+    let job_id = import::start_job(source, state.db_path.clone(), state.extracted_dir.clone())
+        .map_err(ServerFnError::new)?;
+    crate::server::audit::record("import.start", &job_id, format!("from node {label}")).await;
+    Ok(job_id)
+}
 
-    let result = tokio::task::spawn_blocking(move || {
-        import::stream_import(&address, port, &db_path, &extracted_dir, &user, &pass)
-    })
-    .await
-    .map_err(|e| ServerFnError::new(format!("Task error: {e}")))?
-    .map_err(|e| ServerFnError::new(e))?;
-
-    Ok(ImportResult {
-        detections_imported: result.detections_imported,
-        files_extracted: result.files_extracted,
-        skipped_existing: result.skipped_existing,
-        errors: result.errors,
-    })
+/// Progress of an import job.
+#[server(prefix = "/api")]
+pub async fn import_progress(job_id: String) -> Result<ImportProgress, ServerFnError> {
+    crate::server::import::job_progress(&job_id)
+        .ok_or_else(|| ServerFnError::new(format!("Unknown import {job_id}")))
+}
+
+/// The most recent import job, if any ran since the server started.
+#[server(prefix = "/api")]
+pub async fn latest_import() -> Result<Option<ImportProgress>, ServerFnError> {
+    Ok(crate::server::import::list_jobs().into_iter().next())
+}
+
+/// Stop a running import after the current archive entry.
+#[server(prefix = "/api")]
+pub async fn cancel_import(job_id: String) -> Result<(), ServerFnError> {
+    crate::server::import::cancel_job(&job_id).map_err(ServerFnError::new)?;
+    crate::server::audit::record("import.cancel", &job_id, "").await;
+    Ok(())
+}
+
+/// Run a cancelled or failed import again, skipping what it already did.
+#[server(prefix = "/api")]
+pub async fn resume_import(job_id: String) -> Result<(), ServerFnError> {
+    crate::server::import::resume_job(&job_id).map_err(ServerFnError::new)?;
+    crate::server::audit::record("import.resume", &job_id, "").await;
+    Ok(())
 }
 
 /// Scan the `/backups` volume for `.tar` files (legacy file-based import).
@@ -90,11 +124,16 @@ pub async fn list_backups() -> Result<Vec<BackupFile>, ServerFnError> {
 /// Analyse a BirdNET-Pi backup tar without importing (legacy).
 #[server(prefix = "/api")]
 pub async fn analyse_backup(tar_path: String) -> Result<ImportReport, ServerFnError> {
-    use crate::server::import;
+    use crate::server::{birdnet_migration, import};
     use std::path::Path;
 
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let path = Path::new(&tar_path);
-    let report = import::analyse_backup(path).await.map_err(|e| ServerFnError::new(e))?;
+    let keep_dir = birdnet_migration::keep_dir(&state.db_path);
+    let report = import::analyse_backup(path, &keep_dir)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
 
     Ok(ImportReport {
         tar_path: report.tar_path,
@@ -113,27 +152,75 @@ pub async fn analyse_backup(tar_path: String) -> Result<ImportReport, ServerFnEr
     })
 }
 
-/// Import from a tar file on disk (legacy).
+/// Start importing a tar file on disk (legacy); returns the job id.
 #[server(prefix = "/api")]
-pub async fn run_import(tar_path: String) -> Result<ImportResult, ServerFnError> {
+pub async fn run_import(tar_path: String) -> Result<String, ServerFnError> {
     use crate::server::import;
-    use std::path::Path;
 
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
 
-    let path = Path::new(&tar_path);
-    let result = import::import_backup(path, &state.db_path, &state.extracted_dir)
-        .map_err(|e| ServerFnError::new(e))?;
+    let job_id = import::start_job(
+        import::ImportSource::Tar(tar_path.clone().into()),
+        state.db_path.clone(),
+        state.extracted_dir.clone(),
+    )
+    .map_err(ServerFnError::new)?;
+    crate::server::audit::record("import.start", &job_id, tar_path).await;
+    Ok(job_id)
+}
 
-    Ok(ImportResult {
-        detections_imported: result.detections_imported,
-        files_extracted: result.files_extracted,
-        skipped_existing: result.skipped_existing,
-        errors: result.errors,
+/// Compare the settings of the last analysed or imported BirdNET-Pi
+/// backup with `gaia.conf`.
+#[server(prefix = "/api")]
+pub async fn preview_config_migration() -> Result<ConfigMigration, ServerFnError> {
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    crate::server::birdnet_migration::preview(&state.db_path).map_err(ServerFnError::new)
+}
+
+/// Write the previewed BirdNET-Pi settings into `gaia.conf`.
+#[server(prefix = "/api")]
+pub async fn apply_config_migration() -> Result<ConfigMigration, ServerFnError> {
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    crate::server::birdnet_migration::apply(&state.db_path).map_err(ServerFnError::new)
+}
+
+/// Audio folders the processing server can import, its current import
+/// and the species that import found so far.
+#[server(prefix = "/api")]
+pub async fn folder_imports() -> Result<FolderImportView, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, folder_import};
+
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let imports = folder_import::status().await.map_err(ServerFnError::new)?;
+    let species = match &imports.job {
+        Some(job) => ddb::species_for_node(&state.db_path, &job.node)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Cannot read detections: {e}")))?,
+        None => Vec::new(),
+    };
+    Ok(FolderImportView {
+        folders: imports.folders,
+        job: imports.job,
+        species,
     })
 }
 
+/// Have the processing server analyse the audio files of `folder`,
+/// storing detections under `node` (empty for `import-<folder>`).
+#[server(prefix = "/api")]
+pub async fn start_folder_import(folder: String, node: String) -> Result<(), ServerFnError> {
+    let job = crate::server::folder_import::start(folder, node)
+        .await
+        .map_err(ServerFnError::new)?;
+    let details = format!("as node {}", job.node);
+    crate::server::audit::record("import.folder", &job.folder, details).await;
+    Ok(())
+}
+
 // ─── Page component ──────────────────────────────────────────────────────────
 
 /// BirdNET-Pi import page – primary workflow is network streaming import.
@@ -142,10 +229,13 @@ pub fn ImportPage() -> impl IntoView {
     // ── Network import state ─────────────────────────────────────────
     let (nodes, set_nodes) = signal::<Vec<BirdnetNode>>(Vec::new());
     let (discovering, set_discovering) = signal(false);
-    let (importing, set_importing) = signal(false);
-    let (import_result, set_import_result) = signal::<Option<ImportResult>>(None);
     let (error_msg, set_error_msg) = signal::<Option<String>>(None);
 
+    // ── Background import job (network or file) ──────────────────────
+    let (job, set_job) = signal::<Option<ImportProgress>>(None);
+    let importing =
+        Memo::new(move |_| matches!(job.get(), Some(p) if p.state == ImportState::Running));
+
     // Manual entry
     let (manual_addr, set_manual_addr) = signal("birdnet.local".to_string());
     let (manual_port, set_manual_port) = signal(80u16);
@@ -157,9 +247,35 @@ pub fn ImportPage() -> impl IntoView {
     // ── Legacy file-based import state ───────────────────────────────
     let (tar_path, set_tar_path) = signal(String::new());
     let (report, set_report) = signal::<Option<ImportReport>>(None);
-    let (file_result, set_file_result) = signal::<Option<ImportResult>>(None);
     let (analysing, set_analysing) = signal(false);
-    let (file_importing, set_file_importing) = signal(false);
+
+    // Reattach to the latest job (e.g. after a reload mid-import).
+    Effect::new(move || {
+        leptos::task::spawn_local(async move {
+            if let Ok(Some(p)) = latest_import().await {
+                set_job.set(Some(p));
+            }
+        });
+    });
+
+    // Poll the running job.
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        let cb = Closure::wrap(Box::new(move || {
+            if let Some(p) = job.get_untracked().filter(|p| p.state == ImportState::Running) {
+                leptos::task::spawn_local(refresh_job(p.job_id, set_job));
+            }
+        }) as Box<dyn Fn()>);
+        let _ = web_sys::window()
+            .unwrap()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                cb.as_ref().unchecked_ref(),
+                2000,
+            );
+        cb.forget();
+    }
 
     // ── Handlers ─────────────────────────────────────────────────────
 
@@ -185,18 +301,36 @@ pub fn ImportPage() -> impl IntoView {
     };
 
     let do_import = move |addr: String, port: u16| {
-        set_importing.set(true);
         set_error_msg.set(None);
-        set_import_result.set(None);
 
         let user = auth_user.get();
         let pass = auth_pass.get();
         leptos::task::spawn_local(async move {
             match import_from_node(addr, port, Some(user), Some(pass)).await {
-                Ok(r) => set_import_result.set(Some(r)),
+                Ok(id) => refresh_job(id, set_job).await,
                 Err(e) => set_error_msg.set(Some(format!("Import failed: {e}"))),
             }
-            set_importing.set(false);
+        });
+    };
+
+    let on_cancel = move |_| {
+        let Some(id) = job.get_untracked().map(|p| p.job_id) else { return };
+        leptos::task::spawn_local(async move {
+            if let Err(e) = cancel_import(id.clone()).await {
+                set_error_msg.set(Some(format!("Cancel failed: {e}")));
+            }
+            refresh_job(id, set_job).await;
+        });
+    };
+
+    let on_resume = move |_| {
+        let Some(id) = job.get_untracked().map(|p| p.job_id) else { return };
+        set_error_msg.set(None);
+        leptos::task::spawn_local(async move {
+            if let Err(e) = resume_import(id.clone()).await {
+                set_error_msg.set(Some(format!("Resume failed: {e}")));
+            }
+            refresh_job(id, set_job).await;
         });
     };
 
@@ -215,7 +349,6 @@ pub fn ImportPage() -> impl IntoView {
         let val = event_target_value(&ev);
         set_tar_path.set(val);
         set_report.set(None);
-        set_file_result.set(None);
         set_error_msg.set(None);
     };
 
@@ -227,7 +360,6 @@ pub fn ImportPage() -> impl IntoView {
         }
         set_error_msg.set(None);
         set_report.set(None);
-        set_file_result.set(None);
         set_analysing.set(true);
 
         leptos::task::spawn_local(async move {
@@ -241,15 +373,13 @@ pub fn ImportPage() -> impl IntoView {
 
     let on_file_import = move |_| {
         let path = tar_path.get();
-        set_file_importing.set(true);
         set_error_msg.set(None);
 
         leptos::task::spawn_local(async move {
             match run_import(path).await {
-                Ok(r) => set_file_result.set(Some(r)),
+                Ok(id) => refresh_job(id, set_job).await,
                 Err(e) => set_error_msg.set(Some(format!("Import failed: {e}"))),
             }
-            set_file_importing.set(false);
         });
     };
 
@@ -376,35 +506,58 @@ pub fn ImportPage() -> impl IntoView {
                 <div class="import-error">{msg}</div>
             })}
 
-            // ── Import in progress ───────────────────────────────────────
-            {move || importing.get().then(|| view! {
-                <div class="import-progress">
-                    <p>"Streaming backup and importing observations… This may take a while for large datasets."</p>
-                </div>
-            })}
-
-            // ── Network import results ───────────────────────────────────
-            {move || import_result.get().map(|r| {
-                let has_errors = !r.errors.is_empty();
-                let errs = r.errors.clone();
+            // ── Import job progress / result ─────────────────────────────
+            {move || job.get().map(|p| {
+                let running = p.state == ImportState::Running;
+                let resumable = matches!(p.state, ImportState::Failed | ImportState::Cancelled);
+                let title = match p.state {
+                    ImportState::Running => "Importing…",
+                    ImportState::Completed => "Import Complete",
+                    ImportState::Failed => "Import Failed",
+                    ImportState::Cancelled => "Import Cancelled",
+                };
+                let fill = p.fraction().map(|f| format!("width: {:.1}%", f * 100.0));
+                let bytes = match p.bytes_total {
+                    Some(total) => format!("{} / {}", format_mb(p.bytes_read), format_mb(total)),
+                    None => format!("{} received", format_mb(p.bytes_read)),
+                };
+                let errs = p.result.as_ref().map(|r| r.errors.clone()).unwrap_or_default();
+                let error_count = p.error_count;
                 view! {
                     <div class="import-result">
-                        <h2>"Import Complete"</h2>
+                        <h2>{title}</h2>
+                        <p class="import-desc">{format!("{} — {}", p.source, p.phase)}</p>
+                        {running.then(|| view! {
+                            <div class="import-progress">
+                                <div class="import-progress-bar">
+                                    {match fill {
+                                        Some(style) => view! {
+                                            <div class="import-progress-fill" style=style></div>
+                                        }.into_any(),
+                                        None => view! {
+                                            <div class="import-progress-fill indeterminate"></div>
+                                        }.into_any(),
+                                    }}
+                                </div>
+                                <span class="import-progress-bytes">{bytes}</span>
+                            </div>
+                        })}
+                        {p.error.clone().map(|e| view! { <div class="import-error">{e}</div> })}
                         <div class="report-grid">
                             <div class="report-card report-card-success">
                                 <span class="report-label">"Detections"</span>
-                                <span class="report-value">{format_number(r.detections_imported)}</span>
+                                <span class="report-value">{format_number(p.detections_imported)}</span>
                             </div>
                             <div class="report-card report-card-success">
                                 <span class="report-label">"Files Extracted"</span>
-                                <span class="report-value">{format_number(r.files_extracted)}</span>
+                                <span class="report-value">{format_number(p.files_extracted)}</span>
                             </div>
                             <div class="report-card">
                                 <span class="report-label">"Skipped (existing)"</span>
-                                <span class="report-value">{format_number(r.skipped_existing)}</span>
+                                <span class="report-value">{format_number(p.skipped_existing)}</span>
                             </div>
                         </div>
-                        {has_errors.then(|| view! {
+                        {(!errs.is_empty()).then(|| view! {
                             <details class="import-errors">
                                 <summary>{format!("{} errors during import", errs.len())}</summary>
                                 <ul>
@@ -412,8 +565,19 @@ pub fn ImportPage() -> impl IntoView {
                                 </ul>
                             </details>
                         })}
+                        {(running && error_count > 0).then(|| view! {
+                            <p class="import-desc">{format!("{error_count} errors so far")}</p>
+                        })}
+                        <div class="import-actions">
+                            {running.then(|| view! {
+                                <button class="btn" on:click=on_cancel>"Cancel"</button>
+                            })}
+                            {resumable.then(|| view! {
+                                <button class="btn btn-primary" on:click=on_resume>"Resume"</button>
+                            })}
+                        </div>
                     </div>
-                }
+                }.into_any()
             })}
 
             // ── Legacy file-based import (collapsed) ─────────────────────
@@ -518,9 +682,9 @@ pub fn ImportPage() -> impl IntoView {
                                 <button
                                     class="btn btn-success"
                                     on:click=on_file_import
-                                    disabled=move || file_importing.get()
+                                    disabled=move || importing.get()
                                 >
-                                    {move || if file_importing.get() {
+                                    {move || if importing.get() {
                                         "Importing…"
                                     } else {
                                         "Import All Data"
@@ -531,46 +695,347 @@ pub fn ImportPage() -> impl IntoView {
                     }.into_any()
                 })}
 
-                // File import results
-                {move || file_result.get().map(|r| {
-                    let has_errors = !r.errors.is_empty();
-                    let errs = r.errors.clone();
-                    view! {
-                        <div class="import-result">
-                            <h3>"Import Complete"</h3>
-                            {view! {
-                            <div class="report-grid">
-                                <div class="report-card report-card-success">
-                                    <span class="report-label">"Detections"</span>
-                                    <span class="report-value">{format_number(r.detections_imported)}</span>
-                                </div>
-                                <div class="report-card report-card-success">
-                                    <span class="report-label">"Files"</span>
-                                    <span class="report-value">{format_number(r.files_extracted)}</span>
-                                </div>
-                                <div class="report-card">
-                                    <span class="report-label">"Skipped"</span>
-                                    <span class="report-value">{format_number(r.skipped_existing)}</span>
-                                </div>
-                            </div>
-                            }.into_any()}
-                            {has_errors.then(|| view! {
-                                <details class="import-errors">
-                                    <summary>{format!("{} errors", errs.len())}</summary>
-                                    <ul>
-                                        {errs.into_iter().map(|e| view! { <li>{e}</li> }).collect::<Vec<_>>()}
-                                    </ul>
-                                </details>
-                            })}
-                        </div>
-                    }.into_any()
-                })}
             </details>
             }.into_any()}
+
+            <AudioFolderImportPanel/>
+            <ConfigMigrationPanel/>
         </div>
     }
 }
 
+/// Folder import: analyse a folder of audio files on the processing
+/// server as if its files had been recorded by a node.
+#[component]
+fn AudioFolderImportPanel() -> impl IntoView {
+    let (imports, set_imports) = signal::<Option<FolderImportView>>(None);
+    let (folder, set_folder) = signal(String::new());
+    let (node, set_node) = signal(String::new());
+    let (busy, set_busy) = signal(false);
+    let (error, set_error) = signal::<Option<String>>(None);
+    let running = Memo::new(move |_| {
+        imports.with(|i| {
+            i.as_ref().and_then(|i| i.job.as_ref()).is_some_and(|job| {
+                matches!(job.state, FolderImportState::Queuing | FolderImportState::Analysing)
+            })
+        })
+    });
+
+    Effect::new(move || {
+        leptos::task::spawn_local(refresh_folder_imports(set_imports, set_error));
+    });
+
+    // Poll while the workers are busy with the import.
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        let cb = Closure::wrap(Box::new(move || {
+            if running.get_untracked() {
+                leptos::task::spawn_local(refresh_folder_imports(set_imports, set_error));
+            }
+        }) as Box<dyn Fn()>);
+        let _ = web_sys::window()
+            .unwrap()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                cb.as_ref().unchecked_ref(),
+                3000,
+            );
+        cb.forget();
+    }
+
+    let on_start = move |_| {
+        let chosen = folder.get();
+        if chosen.is_empty() {
+            set_error.set(Some("Please select a folder.".into()));
+            return;
+        }
+        set_busy.set(true);
+        set_error.set(None);
+        let node = node.get();
+        leptos::task::spawn_local(async move {
+            match start_folder_import(chosen, node).await {
+                Ok(()) => refresh_folder_imports(set_imports, set_error).await,
+                Err(e) => set_error.set(Some(format!("Import failed: {e}"))),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <section class="import-section folder-import">
+            <h2>"Import Audio Folders"</h2>
+            <p class="import-desc">
+                "Analyse recordings made with other tools, without a BirdNET-Pi database. "
+                "Copy each batch into its own folder under the processing server's "
+                <code>"IMPORT_DIR"</code>
+                "; detections are stored under their own node so they can be told apart."
+            </p>
+            {move || imports.get().map(|i| {
+                let is_empty = i.folders.is_empty();
+                view! {
+                    <div class="import-input-row">
+                        <select
+                            class="import-select"
+                            on:change=move |ev| set_folder.set(event_target_value(&ev))
+                            prop:value=move || folder.get()
+                        >
+                            <option value="" disabled=true selected=true>
+                                {if is_empty { "No folders found in IMPORT_DIR" } else { "Select a folder…" }}
+                            </option>
+                            {i.folders.into_iter().map(|f| {
+                                let label = format!("{} ({} files)", f.name, format_number(f.audio_files as u64));
+                                view! { <option value=f.name>{label}</option> }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <input
+                            type="text"
+                            class="import-input"
+                            placeholder="Node name (default: import-<folder>)"
+                            prop:value=move || node.get()
+                            on:input=move |ev| set_node.set(event_target_value(&ev))
+                        />
+                        <button
+                            class="btn btn-success"
+                            on:click=on_start
+                            disabled=move || busy.get() || running.get() || folder.get().is_empty()
+                        >
+                            {move || if running.get() { "Importing…" } else { "Import" }}
+                        </button>
+                    </div>
+                }
+            })}
+            {move || error.get().map(|e| view! { <div class="import-error">{e}</div> })}
+            {move || imports.get().and_then(|i| i.job.map(|job| (job, i.species))).map(|(job, species)| {
+                let title = match job.state {
+                    FolderImportState::Queuing => "Queuing files…",
+                    FolderImportState::Analysing => "Analysing…",
+                    FolderImportState::Done => "Import Complete",
+                    FolderImportState::Failed => "Import Failed",
+                };
+                let active = matches!(job.state, FolderImportState::Queuing | FolderImportState::Analysing);
+                let fill = (job.queued > 0)
+                    .then(|| format!("width: {:.1}%", job.analysed as f64 / job.queued as f64 * 100.0));
+                let counts = format!("{} / {} files analysed", job.analysed, job.queued);
+                let detections: u64 = species.iter().map(|s| s.detections).sum();
+                let species_count = species.len();
+                view! {
+                    <div class="import-result">
+                        <h3>{title}</h3>
+                        <p class="import-desc">
+                            {format!("{} as node {} — started {}", job.folder, job.node, job.started_at)}
+                        </p>
+                        {active.then(|| view! {
+                            <div class="import-progress">
+                                <div class="import-progress-bar">
+                                    {match fill {
+                                        Some(style) => view! {
+                                            <div class="import-progress-fill" style=style></div>
+                                        }.into_any(),
+                                        None => view! {
+                                            <div class="import-progress-fill indeterminate"></div>
+                                        }.into_any(),
+                                    }}
+                                </div>
+                                <span class="import-progress-bytes">{counts}</span>
+                            </div>
+                        })}
+                        {job.error.clone().map(|e| view! { <div class="import-error">{e}</div> })}
+                        <div class="report-grid">
+                            <div class="report-card report-card-success">
+                                <span class="report-label">"Files Analysed"</span>
+                                <span class="report-value">{format_number(job.analysed as u64)}</span>
+                            </div>
+                            <div class="report-card">
+                                <span class="report-label">"Skipped (imported before)"</span>
+                                <span class="report-value">{format_number(job.skipped as u64)}</span>
+                            </div>
+                            <div class="report-card report-card-success">
+                                <span class="report-label">"Detections"</span>
+                                <span class="report-value">{format_number(detections)}</span>
+                            </div>
+                            <div class="report-card report-card-success">
+                                <span class="report-label">"Species"</span>
+                                <span class="report-value">{species_count.to_string()}</span>
+                            </div>
+                        </div>
+                        {(!species.is_empty()).then(|| view! {
+                            <table class="report-table">
+                                <thead>
+                                    <tr><th>"Species"</th><th>"Detections"</th><th>"Best"</th></tr>
+                                </thead>
+                                <tbody>
+                                    {species.into_iter().map(|s| {
+                                        let href = format!("/species/{}", s.scientific_name.replace(' ', "%20"));
+                                        view! {
+                                            <tr>
+                                                <td>
+                                                    <a href=href title=s.scientific_name.clone()>{s.common_name}</a>
+                                                </td>
+                                                <td>{format_number(s.detections)}</td>
+                                                <td>{format!("{:.0}%", s.best_confidence * 100.0)}</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        })}
+                    </div>
+                }
+            })}
+        </section>
+    }
+}
+
+/// Fetch the folder imports into `set_imports`.
+async fn refresh_folder_imports(
+    set_imports: WriteSignal<Option<FolderImportView>>,
+    set_error: WriteSignal<Option<String>>,
+) {
+    match folder_imports().await {
+        Ok(i) => set_imports.set(Some(i)),
+        Err(e) => set_error.set(Some(e.to_string())),
+    }
+}
+
+/// Settings migration: preview the `birdnet.conf` → `gaia.conf` changes
+/// as a diff, then apply them.
+#[component]
+fn ConfigMigrationPanel() -> impl IntoView {
+    let (migration, set_migration) = signal::<Option<ConfigMigration>>(None);
+    let (applied, set_applied) = signal(false);
+    let (busy, set_busy) = signal(false);
+    let (error, set_error) = signal::<Option<String>>(None);
+
+    let on_preview = move |_| {
+        set_busy.set(true);
+        set_error.set(None);
+        set_applied.set(false);
+        leptos::task::spawn_local(async move {
+            match preview_config_migration().await {
+                Ok(m) => set_migration.set(Some(m)),
+                Err(e) => set_error.set(Some(e.to_string())),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let on_apply = move |_| {
+        set_busy.set(true);
+        set_error.set(None);
+        leptos::task::spawn_local(async move {
+            match apply_config_migration().await {
+                Ok(m) => {
+                    set_migration.set(Some(m));
+                    set_applied.set(true);
+                }
+                Err(e) => set_error.set(Some(e.to_string())),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <section class="import-section config-migration">
+            <h2>"Migrate BirdNET-Pi Settings"</h2>
+            <p class="import-desc">
+                "Location, thresholds, language, BirdWeather id and the include/exclude "
+                "species lists of the last analysed or imported backup can be carried over. "
+                "Review the changes before applying them."
+            </p>
+            <div class="import-input-row">
+                <button class="btn btn-primary" on:click=on_preview disabled=move || busy.get()>
+                    "Preview Changes"
+                </button>
+            </div>
+            {move || error.get().map(|e| view! { <div class="import-error">{e}</div> })}
+            {move || migration.get().map(|m| {
+                let nothing_to_do = m.changes.is_empty() && m.species_lists.is_empty();
+                let can_apply = !nothing_to_do && (m.writable || m.changes.is_empty());
+                view! {
+                    <div class="import-report">
+                        <h3>{m.gaia_conf.clone()}</h3>
+                        {(!m.changes.is_empty()).then(|| view! {
+                            <pre class="config-diff">
+                                {m.changes.iter().map(|c| {
+                                    let origin = c.from_key.as_ref()
+                                        .map(|k| format!("  # from {k}"))
+                                        .unwrap_or_default();
+                                    view! {
+                                        {c.current.as_ref().map(|v| view! {
+                                            <span class="diff-del">{format!("- {}={v}\n", c.key)}</span>
+                                        })}
+                                        <span class="diff-add">{format!("+ {}={}{origin}\n", c.key, c.proposed)}</span>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </pre>
+                        })}
+                        {(!m.species_lists.is_empty()).then(|| view! {
+                            <ul class="config-lists">
+                                {m.species_lists.iter().map(|l| view! {
+                                    <li>
+                                        <code>{l.name.clone()}</code>
+                                        {format!(": {} species (replaces {})", l.entries, l.current_entries)}
+                                    </li>
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+                        {nothing_to_do.then(|| view! {
+                            <p class="import-desc">"gaia.conf already matches the BirdNET-Pi settings."</p>
+                        })}
+                        {(!m.unchanged.is_empty()).then(|| view! {
+                            <p class="import-desc">{format!("Unchanged: {}", m.unchanged.join(", "))}</p>
+                        })}
+                        {(!m.unmapped.is_empty()).then(|| view! {
+                            <details class="import-errors">
+                                <summary>{format!("{} settings not migrated", m.unmapped.len())}</summary>
+                                <p class="import-desc">{m.unmapped.join(", ")}</p>
+                            </details>
+                        })}
+                        {(!m.writable && !m.changes.is_empty()).then(|| view! {
+                            <div class="import-error">
+                                "gaia.conf is not writable here — mount it read-write into the web "
+                                "container, or copy the + lines by hand."
+                            </div>
+                        })}
+                        {move || if applied.get() {
+                            view! {
+                                <div class="settings-success">
+                                    "Applied. Restart the capture and processing services to use the new settings."
+                                </div>
+                            }.into_any()
+                        } else {
+                            view! {
+                                <div class="import-actions">
+                                    <button
+                                        class="btn btn-success"
+                                        on:click=on_apply
+                                        disabled=move || busy.get() || !can_apply
+                                    >
+                                        "Apply"
+                                    </button>
+                                </div>
+                            }.into_any()
+                        }}
+                    </div>
+                }.into_any()
+            })}
+        </section>
+    }
+}
+
+/// Fetch a job's progress into `set_job`.
+async fn refresh_job(job_id: String, set_job: WriteSignal<Option<ImportProgress>>) {
+    if let Ok(p) = import_progress(job_id).await {
+        set_job.set(Some(p));
+    }
+}
+
+/// Format a byte count in megabytes.
+fn format_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
 /// Format a number with thousand separators.
 fn format_number(n: u64) -> String {
     let s = n.to_string();
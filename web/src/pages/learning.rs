@@ -11,7 +11,7 @@ use leptos::prelude::{
     ServerFnError, Suspense, WriteSignal,
 };
 
-use crate::model::QuizItem;
+use crate::model::{clip_mime_type, QuizItem};
 
 // ─── Server function ─────────────────────────────────────────────────────────
 
@@ -26,7 +26,7 @@ pub async fn get_quiz(today_only: bool) -> Result<Vec<QuizItem>, ServerFnError>
 
     // Enrich with iNaturalist photos.
     for item in &mut items {
-        if let Some(photo) = crate::server::inaturalist::lookup(&state.photo_cache, &item.scientific_name).await {
+        if let Some(photo) = crate::server::species_images::lookup_cached(&state.photo_cache, &item.scientific_name) {
             item.image_url = Some(photo.medium_url);
         }
     }
@@ -233,6 +233,7 @@ fn QuizCard(
     let correct_for_check = correct_common.clone();
     let correct_for_reveal = correct_common.clone();
     let sci_for_reveal = correct_scientific.clone();
+    let clip_mime = clip_mime_type(&clip_url);
 
     let is_correct = move || {
         let ans = answers.get();
@@ -268,7 +269,7 @@ fn QuizCard(
             // Audio player
             <div class="quiz-audio">
                 <audio controls preload="none">
-                    <source src=clip_url type="audio/ogg; codecs=opus"/>
+                    <source src=clip_url type=clip_mime/>
                     "Your browser does not support the audio element."
                 </audio>
             </div>
@@ -0,0 +1,73 @@
+//! Map page – where the capture nodes are and what each has detected.
+//!
+//! Nodes are placed at the location stored with their detections (the
+//! node's own `LATITUDE` / `LONGITUDE`, or the processing node's).
+//! Follows the global domain filter.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, Resource, Suspense};
+
+use crate::components::domain_filter::use_domain;
+use crate::components::locale::use_locale;
+use crate::components::node_map::{get_node_locations, NodeMap};
+use crate::model::NodeLocation;
+
+#[component]
+pub fn MapPage() -> impl IntoView {
+    let domain = use_domain();
+    let nodes = Resource::new(
+        move || domain.get(),
+        |domain| async move { get_node_locations(domain, String::new()).await },
+    );
+
+    view! {
+        <div class="map-page">
+            <h1>"Map"</h1>
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
+                {move || nodes.get().map(|res| match res {
+                    Ok(nodes) if nodes.is_empty() => view! {
+                        <p class="text-muted">
+                            "No detections with a location yet. Set LATITUDE and LONGITUDE on the "
+                            "capture or processing node."
+                        </p>
+                    }.into_any(),
+                    Ok(nodes) => view! {
+                        <NodeMap nodes=nodes.clone() />
+                        <NodeTable nodes=nodes />
+                    }.into_any(),
+                    Err(e) => view! { <p class="error">"Error: " {e.to_string()}</p> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn NodeTable(nodes: Vec<NodeLocation>) -> impl IntoView {
+    let locale = use_locale();
+    view! {
+        <table class="report-table map-nodes">
+            <thead>
+                <tr>
+                    <th>"Node"</th>
+                    <th>"Detections"</th>
+                    <th>"Species"</th>
+                    <th>"Last detection"</th>
+                    <th>"Location"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {nodes.into_iter().map(|n| view! {
+                    <tr>
+                        <td>{n.node}</td>
+                        <td>{n.detections}</td>
+                        <td>{n.species}</td>
+                        <td>{move || locale.date(&n.last_seen)}</td>
+                        <td>{format!("{:.4}, {:.4}", n.lat, n.lon)}</td>
+                    </tr>
+                }).collect::<Vec<_>>()}
+            </tbody>
+        </table>
+    }
+}
@@ -1,9 +1,18 @@
+pub mod audit;
 pub mod calendar;
+pub mod compare;
 pub mod day;
+pub mod detection;
 pub mod excluded;
+pub mod favourites;
 pub mod home;
 pub mod import;
 pub mod learning;
+pub mod map;
+pub mod review;
+pub mod richness;
+pub mod seasonal;
 pub mod settings;
 pub mod species;
 pub mod species_list;
+pub mod stations;
@@ -0,0 +1,302 @@
+//! Review page — keyboard-driven triage of recent detections.
+//!
+//! Detections without a verdict are shown one at a time, oldest first,
+//! so a night's results can be checked in a single pass:
+//!
+//! | Key           | Action                                       |
+//! |---------------|----------------------------------------------|
+//! | `Space` / `p` | play / pause the clip                        |
+//! | `c` / `y`     | confirm                                      |
+//! | `r` / `n`     | reject (not this species / not a call)       |
+//! | `s` / `→`     | skip (decide later)                          |
+//! | `←`           | back to the previous detection               |
+//! | `e`           | enter the correct species, `Enter` to save   |
+//!
+//! Verdicts are stored in Redis (`review:verdicts`, keyed by clip file
+//! name), so reviewed detections drop out of the queue.
+
+use leptos::prelude::*;
+use leptos::prelude::{
+    signal, Effect, ElementChild, IntoView, Resource, ServerFnError, Suspense,
+};
+
+use crate::components::locale::use_locale;
+use crate::model::{clip_mime_type, ReviewVerdict, WebDetection};
+
+/// Detections fetched per queue load.
+const QUEUE_SIZE: u32 = 200;
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+/// Unreviewed detections from the last `hours` hours, oldest first.
+#[server(prefix = "/api")]
+pub async fn get_review_queue(hours: u32, limit: u32) -> Result<Vec<WebDetection>, ServerFnError> {
+    use crate::server::{detections_duckdb as ddb, kv};
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+
+    let cutoff = chrono::Utc::now().naive_utc() - chrono::Duration::hours(i64::from(hours.max(1)));
+    let cutoff_str = cutoff.format("%Y-%m-%d %H:%M:%S").to_string();
+    let query = ddb::DetectionQuery {
+        date_from: Some(cutoff.format("%Y-%m-%d").to_string()),
+        ..Default::default()
+    };
+    // Newest first; fetch generously since reviewed ones are dropped below.
+    let dets = ddb::query_detections(&state.db_path, &query, 1000)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
+    let reviewed = kv::get_reviewed_files()
+        .await
+        .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+
+    let mut queue: Vec<WebDetection> = dets
+        .into_iter()
+        .filter(|d| !d.file_name.is_empty() && !reviewed.contains(&d.file_name))
+        .filter(|d| format!("{} {}", d.date, d.time) >= cutoff_str)
+        .collect();
+    queue.reverse();
+    queue.truncate(limit as usize);
+    Ok(queue)
+}
+
+/// Store a verdict; `corrected_species` is the scientific name the
+/// reviewer heard instead, if any.
+#[server(prefix = "/api")]
+pub async fn save_review_verdict(
+    file_name: String,
+    verdict: ReviewVerdict,
+    corrected_species: Option<String>,
+) -> Result<(), ServerFnError> {
+    use crate::model::DetectionReview;
+    if file_name.is_empty() {
+        return Err(ServerFnError::new("Missing file name"));
+    }
+    let review = DetectionReview {
+        verdict,
+        corrected_species: corrected_species
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+        reviewed_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    crate::server::kv::save_review(&file_name, &review)
+        .await
+        .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+    let details = match (&review.verdict, &review.corrected_species) {
+        (ReviewVerdict::Confirmed, _) => "confirmed".to_string(),
+        (ReviewVerdict::Rejected, None) => "rejected".to_string(),
+        (ReviewVerdict::Rejected, Some(species)) => format!("rejected, heard {species}"),
+    };
+    crate::server::audit::record("detection.review", &file_name, details).await;
+    Ok(())
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+#[component]
+pub fn ReviewPage() -> impl IntoView {
+    let (hours, set_hours) = signal(12u32);
+    let (reload, set_reload) = signal(0u32);
+    let locale = use_locale();
+    let queue = Resource::new(
+        move || (hours.get(), reload.get()),
+        |(h, _)| async move { get_review_queue(h, QUEUE_SIZE).await },
+    );
+
+    let (items, set_items) = signal::<Vec<WebDetection>>(vec![]);
+    let (pos, set_pos) = signal(0usize);
+    let (done, set_done) = signal(0usize);
+    let (editing, set_editing) = signal(false);
+    let (species_input, set_species_input) = signal(String::new());
+    let (error_msg, set_error_msg) = signal::<Option<String>>(None);
+    let audio_ref = NodeRef::<leptos::html::Audio>::new();
+
+    Effect::new(move || {
+        if let Some(Ok(list)) = queue.get() {
+            set_items.set(list);
+            set_pos.set(0);
+        }
+    });
+
+    let current = move || items.with(|v| v.get(pos.get()).cloned());
+    let remaining = move || items.with(|v| v.len().saturating_sub(pos.get()));
+
+    // Record a verdict (or skip with `None`) and move on.  Saving runs
+    // in the background so the next clip is shown immediately.
+    let decide = move |verdict: Option<ReviewVerdict>, corrected: Option<String>| {
+        let Some(det) = items.with_untracked(|v| v.get(pos.get_untracked()).cloned()) else {
+            return;
+        };
+        set_editing.set(false);
+        set_species_input.set(String::new());
+        set_pos.update(|p| *p += 1);
+        if let Some(verdict) = verdict {
+            set_done.update(|n| *n += 1);
+            leptos::task::spawn_local(async move {
+                if let Err(e) = save_review_verdict(det.file_name.clone(), verdict, corrected).await {
+                    set_error_msg.set(Some(format!("Could not save verdict for {}: {e}", det.file_name)));
+                }
+            });
+        }
+    };
+    let back = move || set_pos.update(|p| *p = p.saturating_sub(1));
+    let toggle_play = move || {
+        #[cfg(feature = "hydrate")]
+        if let Some(audio) = audio_ref.get_untracked() {
+            if audio.paused() {
+                let _ = audio.play();
+            } else {
+                let _ = audio.pause();
+            }
+        }
+    };
+    let save_correction = move || {
+        let name = species_input.get_untracked();
+        if !name.trim().is_empty() {
+            decide(Some(ReviewVerdict::Confirmed), Some(name));
+        }
+    };
+
+    #[cfg(feature = "hydrate")]
+    {
+        let handle = window_event_listener(leptos::ev::keydown, move |ev| {
+            if editing.get_untracked() {
+                if ev.key() == "Escape" {
+                    set_editing.set(false);
+                }
+                return;
+            }
+            if ev.ctrl_key() || ev.meta_key() || ev.alt_key() {
+                return;
+            }
+            match ev.key().as_str() {
+                " " | "p" => {
+                    ev.prevent_default();
+                    toggle_play();
+                }
+                "c" | "y" => decide(Some(ReviewVerdict::Confirmed), None),
+                "r" | "n" => decide(Some(ReviewVerdict::Rejected), None),
+                "s" | "ArrowRight" => decide(None, None),
+                "ArrowLeft" => back(),
+                "e" => {
+                    ev.prevent_default();
+                    set_editing.set(true);
+                }
+                _ => {}
+            }
+        });
+        on_cleanup(move || handle.remove());
+    }
+
+    view! {
+        <div class="review-page">
+            <h1>"Review"</h1>
+            <p class="page-description">
+                "Check recent detections one by one. "
+                <kbd>"Space"</kbd>" play · "<kbd>"C"</kbd>" confirm · "<kbd>"R"</kbd>" reject · "
+                <kbd>"S"</kbd>" skip · "<kbd>"←"</kbd>" back · "<kbd>"E"</kbd>" correct species"
+            </p>
+
+            <div class="review-toolbar">
+                <label for="review-hours">"Last"</label>
+                <select
+                    id="review-hours"
+                    class="setting-select"
+                    on:change=move |ev| {
+                        if let Ok(h) = event_target_value(&ev).parse::<u32>() {
+                            set_hours.set(h);
+                        }
+                    }
+                >
+                    <option value="12" selected=move || hours.get() == 12>"12 hours"</option>
+                    <option value="24" selected=move || hours.get() == 24>"24 hours"</option>
+                    <option value="72" selected=move || hours.get() == 72>"3 days"</option>
+                    <option value="168" selected=move || hours.get() == 168>"7 days"</option>
+                </select>
+                <span class="review-progress">
+                    {move || format!("{} reviewed · {} left", done.get(), remaining())}
+                </span>
+            </div>
+
+            {move || error_msg.get().map(|msg| view! { <div class="settings-error">{msg}</div> })}
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading…"</p> }>
+                {move || queue.get().map(|res| match res {
+                    Err(e) => view! { <p class="error">"Error: " {e.to_string()}</p> }.into_any(),
+                    Ok(_) => match current() {
+                        None => view! {
+                            <div class="review-empty">
+                                <p>"Nothing left to review."</p>
+                                <button class="btn" on:click=move |_| set_reload.update(|n| *n += 1)>
+                                    "Check again"
+                                </button>
+                            </div>
+                        }.into_any(),
+                        Some(det) => {
+                            let clip = det.clip_url();
+                            let spectrogram = det.spectrogram_url();
+                            let confidence = format!("{:.0}%", det.confidence * 100.0);
+                            let when = {
+                                let det = det.clone();
+                                move || locale.detection_time(&det.date, &det.time, &det.display_date, &det.display_time)
+                            };
+                            view! {
+                                <div class="review-card">
+                                    <div class="review-species">
+                                        <h2>{det.common_name.clone()}</h2>
+                                        <p class="species-sci">{det.scientific_name.clone()}</p>
+                                        <p class="review-meta">
+                                            {confidence} " · " {when} " · " {det.model_name.clone()}
+                                            " · " {det.source_label()}
+                                        </p>
+                                    </div>
+                                    {spectrogram.map(|url| view! {
+                                        <div class="detection-spectrogram">
+                                            <img src=url alt="spectrogram"/>
+                                        </div>
+                                    })}
+                                    {clip.map(|url| {
+                                        let mime = clip_mime_type(&url);
+                                        view! {
+                                            <audio class="detection-audio" controls autoplay preload="auto" node_ref=audio_ref>
+                                                <source src=url type=mime/>
+                                            </audio>
+                                        }
+                                    })}
+                                    <div class="review-actions">
+                                        <button class="btn review-confirm" on:click=move |_| decide(Some(ReviewVerdict::Confirmed), None)>
+                                            "Confirm (C)"
+                                        </button>
+                                        <button class="btn review-reject" on:click=move |_| decide(Some(ReviewVerdict::Rejected), None)>
+                                            "Reject (R)"
+                                        </button>
+                                        <button class="btn" on:click=move |_| decide(None, None)>"Skip (S)"</button>
+                                        <button class="btn" on:click=move |_| back()>"Back (←)"</button>
+                                        <button class="btn" on:click=move |_| toggle_play()>"Play (Space)"</button>
+                                        <button class="btn" on:click=move |_| set_editing.set(true)>"Correct species (E)"</button>
+                                    </div>
+                                    <Show when=move || editing.get()>
+                                        <div class="review-correct">
+                                            <input
+                                                type="text"
+                                                class="setting-input"
+                                                placeholder="Scientific name, then Enter (Esc to cancel)"
+                                                autofocus
+                                                prop:value=move || species_input.get()
+                                                on:input=move |ev| set_species_input.set(event_target_value(&ev))
+                                                on:keydown=move |ev| match ev.key().as_str() {
+                                                    "Enter" => save_correction(),
+                                                    "Escape" => set_editing.set(false),
+                                                    _ => {}
+                                                }
+                                            />
+                                        </div>
+                                    </Show>
+                                </div>
+                            }.into_any()
+                        }
+                    },
+                })}
+            </Suspense>
+        </div>
+    }
+}
@@ -0,0 +1,235 @@
+//! Species richness page – has the station sampled the local community?
+//!
+//! Shows the species accumulation curve (distinct species against days
+//! with detections), observed and estimated richness per domain, and the
+//! species added each week.  A curve that flattens out means few species
+//! remain to be found; the Chao2 estimate puts a number on how many.
+//! Follows the global domain filter.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, Resource, ServerFnError, Suspense};
+
+use crate::components::domain_filter::{domain_class, use_domain};
+use crate::model::{AccumulationPoint, DomainRichness, RichnessStats, WeeklyNewSpecies};
+
+const CURVE_WIDTH: f64 = 600.0;
+const CURVE_HEIGHT: f64 = 200.0;
+
+/// Sampling days the "recently added" figure looks back over.
+const RECENT_DAYS: usize = 30;
+
+/// Weeks listed by name under the weekly chart.
+const LISTED_WEEKS: usize = 8;
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_species_richness(domain: String) -> Result<RichnessStats, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    ddb::species_richness(&state.db_path, domain_opt)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+#[component]
+pub fn RichnessPage() -> impl IntoView {
+    let domain = use_domain();
+    let data = Resource::new(
+        move || domain.get(),
+        |domain| async move { get_species_richness(domain).await },
+    );
+
+    view! {
+        <div class="richness-page">
+            <h1>"Species Richness"</h1>
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
+                {move || data.get().map(|res| match res {
+                    Ok(stats) if stats.sampling_days == 0 => view! {
+                        <p class="text-muted">"No detections yet."</p>
+                    }.into_any(),
+                    Ok(stats) => view! { <RichnessView stats=stats /> }.into_any(),
+                    Err(e) => view! { <p class="error">"Error: " {e.to_string()}</p> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn RichnessView(stats: RichnessStats) -> impl IntoView {
+    let total = stats.total.clone();
+    // Species added over the most recent sampling days.
+    let recent = {
+        let last = stats.curve.last().map_or(0, |p| p.species);
+        let before = stats
+            .curve
+            .len()
+            .checked_sub(RECENT_DAYS + 1)
+            .and_then(|i| stats.curve.get(i))
+            .map_or(0, |p| p.species);
+        last - before
+    };
+    let recent_days = RECENT_DAYS.min(stats.curve.len());
+    let show_domains = stats.domains.len() > 1;
+
+    view! {
+        <p class="richness-summary">
+            {format!(
+                "{} species in {} day(s) with detections · estimated {:.0} ({:.0}% found) · {recent} new in the last {recent_days} day(s)",
+                total.observed,
+                stats.sampling_days,
+                total.estimated,
+                total.completeness() * 100.0,
+            )}
+        </p>
+
+        <h2>"Accumulation curve"</h2>
+        <AccumulationCurve curve=stats.curve.clone() estimated=total.estimated />
+
+        <h2>"Richness by domain"</h2>
+        <table class="report-table richness-table">
+            <thead>
+                <tr>
+                    <th>"Domain"</th>
+                    <th title="Species detected">"Observed"</th>
+                    <th title="Detected on a single day">"Singletons"</th>
+                    <th title="Detected on exactly two days">"Doubletons"</th>
+                    <th title="Bias-corrected Chao2 estimate">"Estimated"</th>
+                    <th>"Found"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {stats.domains.iter().cloned().map(|d| view! { <RichnessRow row=d /> }).collect::<Vec<_>>()}
+                {show_domains.then(|| view! { <RichnessRow row=total.clone() /> })}
+            </tbody>
+        </table>
+
+        <h2>"New species per week"</h2>
+        <WeeklyChart weeks=stats.weekly_new.clone() />
+        <ul class="richness-weeks">
+            {stats
+                .weekly_new
+                .iter()
+                .rev()
+                .filter(|w| !w.species.is_empty())
+                .take(LISTED_WEEKS)
+                .map(|w| view! {
+                    <li>
+                        <span class="richness-week-start">{format!("Week of {}", w.week_start)}</span>
+                        ": " {w.species.join(", ")}
+                    </li>
+                })
+                .collect::<Vec<_>>()}
+        </ul>
+        <p class="seasonal-caption">
+            "A day with at least one detection counts as one sample. Chao2 estimates the species "
+            "not yet detected from how many were seen on only one or two days."
+        </p>
+    }
+}
+
+#[component]
+fn RichnessRow(row: DomainRichness) -> impl IntoView {
+    let (label, class) = if row.domain.is_empty() {
+        ("All domains".to_string(), "richness-total".to_string())
+    } else {
+        (row.domain.clone(), String::new())
+    };
+    let badge = format!("domain-badge {}", domain_class(&row.domain));
+    view! {
+        <tr class=class>
+            <td>
+                {if row.domain.is_empty() {
+                    view! { <strong>{label}</strong> }.into_any()
+                } else {
+                    view! { <span class=badge>{label}</span> }.into_any()
+                }}
+            </td>
+            <td>{row.observed}</td>
+            <td>{row.singletons}</td>
+            <td>{row.doubletons}</td>
+            <td>{format!("{:.1}", row.estimated)}</td>
+            <td>{format!("{:.0}%", row.completeness() * 100.0)}</td>
+        </tr>
+    }
+}
+
+/// Step line of species against sampling days, with the estimate as a
+/// dashed ceiling.
+#[component]
+fn AccumulationCurve(curve: Vec<AccumulationPoint>, estimated: f64) -> impl IntoView {
+    let days = curve.len().max(1) as f64;
+    let top = estimated.max(curve.last().map_or(0, |p| p.species) as f64).max(1.0);
+    let x = |d: u32| d as f64 / days * CURVE_WIDTH;
+    let y = |s: f64| CURVE_HEIGHT - s / top * CURVE_HEIGHT;
+
+    let mut points = vec![format!("0,{CURVE_HEIGHT}")];
+    let mut previous = 0;
+    for p in &curve {
+        points.push(format!("{:.1},{:.1}", x(p.days - 1), y(previous as f64)));
+        points.push(format!("{:.1},{:.1}", x(p.days - 1), y(p.species as f64)));
+        previous = p.species;
+    }
+    points.push(format!("{CURVE_WIDTH},{:.1}", y(previous as f64)));
+    let points = points.join(" ");
+    let ceiling = y(estimated);
+    let (first, last) = (
+        curve.first().map(|p| p.date.clone()).unwrap_or_default(),
+        curve.last().map(|p| p.date.clone()).unwrap_or_default(),
+    );
+    let title = format!("{previous} species after {} day(s)", curve.len());
+
+    view! {
+        <div class="richness-curve" title=title.clone()>
+            <svg
+                viewBox=format!("0 0 {CURVE_WIDTH} {CURVE_HEIGHT}")
+                preserveAspectRatio="none"
+                role="img"
+                aria-label=title
+            >
+                <line
+                    class="richness-estimate"
+                    x1="0"
+                    x2=CURVE_WIDTH.to_string()
+                    y1=format!("{ceiling:.1}")
+                    y2=format!("{ceiling:.1}")
+                />
+                <polyline class="richness-line" points=points />
+            </svg>
+            <div class="richness-axis">
+                <span>{first}</span>
+                <span>{format!("{estimated:.0} species estimated (dashed)")}</span>
+                <span>{last}</span>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn WeeklyChart(weeks: Vec<WeeklyNewSpecies>) -> impl IntoView {
+    let max = weeks.iter().map(|w| w.species.len()).max().unwrap_or(0).max(1);
+    view! {
+        <div class="richness-weekly">
+            {weeks.into_iter().map(|w| {
+                let n = w.species.len();
+                let title = if n == 0 {
+                    format!("Week of {}: no new species", w.week_start)
+                } else {
+                    format!("Week of {}: {n} new – {}", w.week_start, w.species.join(", "))
+                };
+                let height = format!("height: {:.0}%", n as f64 / max as f64 * 100.0);
+                view! {
+                    <div class="richness-week" title=title>
+                        <div class="seasonal-bar" style=height></div>
+                    </div>
+                }
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
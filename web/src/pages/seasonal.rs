@@ -0,0 +1,207 @@
+//! Seasonal occurrence page – week-of-year histogram of one species over
+//! all years, optionally overlaid with the occurrence the metadata
+//! (species-range) model expects at the station's location.
+//!
+//! Query parameter: `species` (scientific name).  Weeks are ISO weeks,
+//! the same numbering the location filter passes the metadata model.
+
+use leptos::prelude::*;
+use leptos::prelude::{signal, ElementChild, IntoView, Resource, ServerFnError, Suspense};
+use leptos_router::hooks::use_query_map;
+
+use crate::model::{SeasonalOccurrence, SpeciesSummary, WeekOccurrence};
+
+/// First ISO week of each month (non-leap year, roughly) for axis labels.
+const MONTH_WEEKS: [(u32, &str); 12] = [
+    (1, "Jan"), (5, "Feb"), (9, "Mar"), (14, "Apr"), (18, "May"), (22, "Jun"),
+    (27, "Jul"), (31, "Aug"), (36, "Sep"), (40, "Oct"), (44, "Nov"), (49, "Dec"),
+];
+
+/// Location filter threshold used when none is saved in the settings.
+#[cfg(feature = "ssr")]
+const DEFAULT_SF_THRESH: f64 = 0.03;
+
+// ─── Server functions ────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_seasonal_occurrence(
+    scientific_name: String,
+) -> Result<SeasonalOccurrence, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let mut occ = ddb::seasonal_occurrence(&state.db_path, &scientific_name)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
+    occ.filter_threshold = crate::server::kv::get_all_settings()
+        .await
+        .ok()
+        .and_then(|m| m.get("sf_thresh").and_then(|v| v.parse().ok()))
+        .unwrap_or(DEFAULT_SF_THRESH);
+    Ok(occ)
+}
+
+/// Species to choose from, most detected first.
+#[server(prefix = "/api")]
+pub async fn get_seasonal_species() -> Result<Vec<SpeciesSummary>, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    ddb::top_species(&state.db_path, 1000)
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+#[component]
+pub fn SeasonalPage() -> impl IntoView {
+    let query = use_query_map();
+    let species = move || query.with(|q| q.get("species").unwrap_or_default());
+
+    let choices = Resource::new(|| (), |_| async move { get_seasonal_species().await });
+    let data = Resource::new(species, |name| async move {
+        if name.is_empty() {
+            Ok(None)
+        } else {
+            get_seasonal_occurrence(name).await.map(Some)
+        }
+    });
+
+    view! {
+        <div class="seasonal-page">
+            <h1>"Seasonal Occurrence"</h1>
+
+            // Plain GET form – the species lives in the URL so the page
+            // can be linked from the species page and bookmarked.
+            <form class="seasonal-form" method="get" action="/seasonal">
+                <input
+                    type="text"
+                    name="species"
+                    list="seasonal-species"
+                    placeholder="Scientific name"
+                    value=species
+                />
+                <datalist id="seasonal-species">
+                    <Suspense fallback=|| ()>
+                        {move || choices.get().and_then(|r| r.ok()).map(|list| {
+                            list.into_iter().map(|s| view! {
+                                <option value=s.scientific_name>{s.common_name}</option>
+                            }).collect::<Vec<_>>()
+                        })}
+                    </Suspense>
+                </datalist>
+                <button type="submit" class="sort-btn">"Show"</button>
+            </form>
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading\u{2026}"</p> }>
+                {move || data.get().map(|res| match res {
+                    Ok(Some(occ)) => view! { <OccurrenceView occ=occ /> }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-muted">"Pick a species to see when in the year it is heard."</p>
+                    }.into_any(),
+                    Err(e) => view! { <p class="error">"Error: " {e.to_string()}</p> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn OccurrenceView(occ: SeasonalOccurrence) -> impl IntoView {
+    let has_expected = occ.weeks.iter().any(|w| w.expected.is_some());
+    let (show_expected, set_show_expected) = signal(has_expected);
+
+    let total: u64 = occ.weeks.iter().map(|w| w.detections).sum();
+    let max = occ.weeks.iter().map(|w| w.detections).max().unwrap_or(0).max(1);
+    let years = match (occ.years.first(), occ.years.last()) {
+        (Some(first), Some(last)) if first != last => format!("{first}–{last}"),
+        (Some(year), _) => year.to_string(),
+        _ => "no detections yet".to_string(),
+    };
+    // Weeks with detections the location filter would have removed.
+    let unexpected = occ
+        .weeks
+        .iter()
+        .filter(|w| w.detections > 0 && w.expected.is_some_and(|e| e < occ.filter_threshold))
+        .count();
+    let species_href = format!("/species/{}", occ.scientific_name.replace(' ', "%20"));
+    let expected_note = match (&occ.expected_location, &occ.expected_model) {
+        (Some((lat, lon)), Some(model)) if has_expected => format!(
+            "Line: occurrence probability from the {model} range model at {lat:.2}, {lon:.2} (0 – 1)."
+        ),
+        (Some(_), _) => "The range model does not expect this species here in any week.".to_string(),
+        _ => "No range model is loaded, so there is no expected occurrence to compare.".to_string(),
+    };
+
+    let weeks = occ.weeks.clone();
+    view! {
+        <h2>
+            <a href=species_href>{occ.common_name.clone()}</a>
+            " " <em class="species-sci-name">{occ.scientific_name.clone()}</em>
+        </h2>
+        <p class="seasonal-summary">
+            {format!("{total} detections · {years}")}
+            {(unexpected > 0).then(|| view! {
+                " · "
+                <span class="seasonal-unexpected">
+                    {format!(
+                        "{unexpected} week(s) with detections below the location filter threshold ({:.3})",
+                        occ.filter_threshold,
+                    )}
+                </span>
+            })}
+        </p>
+
+        {has_expected.then(|| view! {
+            <label class="seasonal-toggle">
+                <input
+                    type="checkbox"
+                    prop:checked=move || show_expected.get()
+                    on:change=move |ev| set_show_expected.set(event_target_checked(&ev))
+                />
+                " Compare with expected occurrence"
+            </label>
+        })}
+
+        <div class="seasonal-chart">
+            {weeks.into_iter().map(|w| view! {
+                <WeekBar week=w max=max show_expected=show_expected />
+            }).collect::<Vec<_>>()}
+        </div>
+        <div class="seasonal-axis">
+            {MONTH_WEEKS.iter().map(|(week, label)| view! {
+                <span style=format!("grid-column: {week}")>{*label}</span>
+            }).collect::<Vec<_>>()}
+        </div>
+        <p class="seasonal-caption">
+            "Bars: detections per ISO week, all years combined, relative to the busiest week. "
+            {expected_note}
+        </p>
+    }
+}
+
+#[component]
+fn WeekBar(week: WeekOccurrence, max: u64, show_expected: ReadSignal<bool>) -> impl IntoView {
+    let height = format!("height: {:.0}%", week.detections as f64 / max as f64 * 100.0);
+    let mut title = format!("Week {}: {} detections on {} day(s)", week.week, week.detections, week.days);
+    if let Some(expected) = week.expected {
+        title.push_str(&format!(", expected {expected:.3}"));
+    }
+    let expected = week.expected.map(|e| e.clamp(0.0, 1.0) * 100.0);
+
+    view! {
+        <div class="seasonal-week" title=title>
+            <div class="seasonal-bar" style=height></div>
+            {expected.map(|pct| view! {
+                <div
+                    class="seasonal-expected"
+                    style=move || {
+                        let display = if show_expected.get() { "block" } else { "none" };
+                        format!("bottom: {pct:.0}%; display: {display}")
+                    }
+                ></div>
+            })}
+        </div>
+    }
+}
@@ -6,7 +6,11 @@ use leptos::prelude::{
     ServerFnError, Suspense,
 };
 
-use crate::model::{DetectionSettings, TaxonomyAdminStatus};
+use crate::components::dataset_export::DatasetExportPanel;
+use crate::components::detection_export::DetectionExportPanel;
+use crate::components::locale::LocalePanel;
+use crate::components::weekly_digest::WeeklyDigestPanel;
+use crate::model::{AudioDevicesResponse, DetectionSettings, TaxonomyAdminStatus};
 
 // ─── Default values (match gaia_common::config defaults) ─────────────────────
 
@@ -50,22 +54,35 @@ pub async fn save_settings(settings: DetectionSettings) -> Result<(), ServerFnEr
     let cmap = settings.colormap.clone();
     let tz = settings.tz_offset.to_string();
 
-    crate::server::kv::save_settings(
-        &[
-            ("sensitivity", &sens),
-            ("confidence", &conf),
-            ("sf_thresh", &sf),
-            ("overlap", &ovlp),
-            ("colormap", &cmap),
-            ("tz_offset", &tz),
-        ],
-    )
-    .await
-    .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+    let entries = [
+        ("sensitivity", sens.as_str()),
+        ("confidence", conf.as_str()),
+        ("sf_thresh", sf.as_str()),
+        ("overlap", ovlp.as_str()),
+        ("colormap", cmap.as_str()),
+        ("tz_offset", tz.as_str()),
+    ];
+    let before = crate::server::kv::get_all_settings().await.unwrap_or_default();
+    crate::server::kv::save_settings(&entries)
+        .await
+        .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
 
+    let changes = crate::server::audit::settings_diff(&before, &entries);
+    if !changes.is_empty() {
+        crate::server::audit::record("settings.save", "detection settings", changes).await;
+    }
     Ok(())
 }
 
+/// Capture devices of the capture node at `CAPTURE_SERVER_URL`
+/// (see [`crate::server::capture_api`]).
+#[server(prefix = "/api")]
+pub async fn get_audio_devices() -> Result<AudioDevicesResponse, ServerFnError> {
+    crate::server::capture_api::get_json("/api/audio-devices")
+        .await
+        .map_err(ServerFnError::new)
+}
+
 #[server(prefix = "/api")]
 pub async fn get_taxonomy_status() -> Result<TaxonomyAdminStatus, ServerFnError> {
     #[cfg(feature = "ssr")]
@@ -92,8 +109,11 @@ pub async fn add_taxonomy_alias(
         } else {
             Some(class_name.trim())
         };
-        crate::server::taxonomy_admin::upsert_species_alias(&canonical, &alias, class_opt)
-            .map_err(ServerFnError::new)
+        let msg =
+            crate::server::taxonomy_admin::upsert_species_alias(&canonical, &alias, class_opt)
+                .map_err(ServerFnError::new)?;
+        crate::server::audit::record("taxonomy.alias", &alias, format!("→ {canonical}")).await;
+        Ok(msg)
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -108,8 +128,11 @@ pub async fn add_class_alias(
 ) -> Result<String, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        crate::server::taxonomy_admin::upsert_class_alias(&alias, &canonical_class)
-            .map_err(ServerFnError::new)
+        let msg = crate::server::taxonomy_admin::upsert_class_alias(&alias, &canonical_class)
+            .map_err(ServerFnError::new)?;
+        let details = format!("→ {canonical_class}");
+        crate::server::audit::record("taxonomy.class_alias", &alias, details).await;
+        Ok(msg)
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -235,7 +258,8 @@ pub fn SettingsPage() -> impl IntoView {
             <h1>"Detection Settings"</h1>
             <p class="settings-desc">
                 "Adjust the detection thresholds used by the processing server. "
-                "Changes are stored in the shared database and picked up on the next analysis cycle."
+                "Changes are stored in the shared database and picked up on the next analysis cycle. "
+                "Every change is recorded in the " <a href="/audit">"audit log"</a> "."
             </p>
 
             <Suspense fallback=|| view! { <p class="text-muted">"Loading settings…"</p> }>
@@ -458,6 +482,16 @@ pub fn SettingsPage() -> impl IntoView {
                         <div class="settings-error">{msg}</div>
                     })}
 
+                    <AudioDeviceSelect/>
+
+                    <LocalePanel/>
+
+                    <DetectionExportPanel/>
+
+                    <DatasetExportPanel/>
+
+                    <WeeklyDigestPanel/>
+
                     // ── Taxonomy Admin ─────────────────────────
                     <div class="setting-group">
                         <label class="setting-label">"Taxonomy Admin"</label>
@@ -553,3 +587,67 @@ pub fn SettingsPage() -> impl IntoView {
         </div>
     }
 }
+
+/// Microphone dropdown fed by the capture node's `arecord -l`.
+///
+/// `REC_CARD` is read by the capture server at startup, so picking a
+/// device shows the gaia.conf line to use rather than saving it.
+#[component]
+fn AudioDeviceSelect() -> impl IntoView {
+    let devices = Resource::new(|| (), |_| get_audio_devices());
+    let (picked, set_picked) = signal::<Option<String>>(None);
+
+    view! {
+        <div class="setting-group">
+            <label class="setting-label" for="rec_card">"Microphone"</label>
+            <p class="setting-help">
+                "Capture devices found on the capture node. "
+                "Pick one and set the shown REC_CARD value in gaia.conf, then restart the capture service."
+            </p>
+            <Suspense fallback=|| view! { <p class="text-muted">"Looking for audio devices…"</p> }>
+                {move || devices.get().map(|res| match res {
+                    Ok(list) if list.devices.is_empty() => leptos::either::EitherOf3::A(view! {
+                        <p class="text-muted">"No capture devices found (current REC_CARD: " {list.current} ")."</p>
+                    }),
+                    Ok(list) => {
+                        let current = list.current.clone();
+                        let selected = move || picked.get().unwrap_or_else(|| current.clone());
+                        let options = list.devices.into_iter().map(|d| {
+                            let value = d.rec_card.clone();
+                            let is_selected = {
+                                let value = value.clone();
+                                let selected = selected.clone();
+                                move || selected() == value
+                            };
+                            view! {
+                                <option value=value.clone() selected=is_selected>
+                                    {format!("{} — {} (card {}, device {})", d.card_name, d.device_name, d.card, d.device)}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>();
+                        let default_selected = {
+                            let selected = selected.clone();
+                            move || selected() == "default"
+                        };
+                        leptos::either::EitherOf3::B(view! {
+                            <select
+                                id="rec_card"
+                                class="setting-select"
+                                on:change=move |ev| set_picked.set(Some(event_target_value(&ev)))
+                            >
+                                <option value="default" selected=default_selected>"ALSA default"</option>
+                                {options}
+                            </select>
+                            <p class="setting-help">
+                                <code>{move || format!("REC_CARD={}", selected())}</code>
+                            </p>
+                        })
+                    }
+                    Err(e) => leptos::either::EitherOf3::C(view! {
+                        <p class="settings-error">{format!("Cannot list audio devices: {e}")}</p>
+                    }),
+                })}
+            </Suspense>
+        </div>
+    }
+}
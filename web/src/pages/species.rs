@@ -1,4 +1,4 @@
-//! Species detail page – iNaturalist photo, detection history, calendar overlay.
+//! Species detail page – species photo, detection history, calendar overlay.
 
 use leptos::prelude::*;
 use leptos::prelude::{
@@ -11,8 +11,14 @@ use leptos_router::hooks::use_params_map;
 use crate::components::calendar_grid::CalendarGrid;
 use crate::components::detection_card::DetectionCard;
 use crate::components::hourly_chart::HourlyChart;
+use crate::components::locale::use_locale;
 use crate::components::model_filter::ModelFilter;
-use crate::model::{CalendarDay, HourlyCount, ModelInfo, SpeciesInfo, TopRecording, WebDetection};
+use crate::components::node_map::SpeciesMap;
+use crate::components::push_toggle::PushToggle;
+use crate::model::{
+    CalendarDay, HourlyCount, ImageSource, ModelInfo, SpeciesInfo, SpeciesPhoto, TopRecording,
+    WebDetection,
+};
 
 // ─── Server functions ────────────────────────────────────────────────────────
 
@@ -20,7 +26,7 @@ use crate::model::{CalendarDay, HourlyCount, ModelInfo, SpeciesInfo, TopRecordin
 pub async fn get_species_info(
     scientific_name: String,
 ) -> Result<Option<SpeciesInfo>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let mut info = ddb::species_info(&state.db_path, &scientific_name)
@@ -28,8 +34,10 @@ pub async fn get_species_info(
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
 
     if let Some(ref mut sp) = info {
-        if let Some(photo) = inaturalist::lookup(&state.photo_cache, &scientific_name).await {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &scientific_name) {
             sp.image_url = Some(photo.medium_url);
+            sp.image_attribution = Some(photo.attribution);
+            sp.image_source = Some(photo.source);
             sp.wikipedia_url = photo.wikipedia_url;
             sp.male_image_url = photo.male_image_url;
             sp.female_image_url = photo.female_image_url;
@@ -54,7 +62,7 @@ pub async fn get_species_calendar(
     // Full-year calendar data
     let mut all_days = Vec::new();
     for m in 1..=12 {
-        let mut month_days = ddb::calendar_data(&state.db_path, year, m)
+        let mut month_days = ddb::calendar_data(&state.db_path, year, m, None)
             .await
             .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
         all_days.append(&mut month_days);
@@ -79,6 +87,11 @@ pub async fn set_species_verification(
     kv::set_species_verification(&scientific_name, &method, &inaturalist_obs)
         .await
         .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+    let details = match inaturalist_obs.trim() {
+        "" => method,
+        obs => format!("{method}, iNaturalist {obs}"),
+    };
+    crate::server::audit::record("verification.set", &scientific_name, details).await;
     Ok(())
 }
 
@@ -91,9 +104,58 @@ pub async fn remove_species_verification(
     kv::remove_species_verification(&scientific_name)
         .await
         .map_err(|e| ServerFnError::new(format!("KV error: {e}")))?;
+    crate::server::audit::record("verification.remove", &scientific_name, "").await;
     Ok(())
 }
 
+/// Pin a photo for a species, overriding the image providers.  Returns
+/// the photo now shown.
+#[server(prefix = "/api")]
+pub async fn pin_species_image(
+    scientific_name: String,
+    url: String,
+    attribution: String,
+) -> Result<Option<SpeciesPhoto>, ServerFnError> {
+    use crate::server::species_images;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    species_images::pin(&state.photo_cache, &scientific_name, &url, &attribution)
+        .await
+        .map_err(ServerFnError::new)?;
+    crate::server::audit::record("photo.pin", &scientific_name, url.trim()).await;
+    Ok(species_images::lookup(&state.photo_cache, &scientific_name).await)
+}
+
+/// Remove the pinned photo of a species.  Returns the provider photo
+/// shown instead.
+#[server(prefix = "/api")]
+pub async fn unpin_species_image(
+    scientific_name: String,
+) -> Result<Option<SpeciesPhoto>, ServerFnError> {
+    use crate::server::species_images;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    species_images::unpin(&state.photo_cache, &scientific_name)
+        .await
+        .map_err(ServerFnError::new)?;
+    crate::server::audit::record("photo.unpin", &scientific_name, "").await;
+    Ok(species_images::lookup(&state.photo_cache, &scientific_name).await)
+}
+
+/// Fetch the photo of a species again from the providers, bypassing the
+/// background worker.  Returns the photo now shown.
+#[server(prefix = "/api")]
+pub async fn refresh_species_image(
+    scientific_name: String,
+) -> Result<Option<SpeciesPhoto>, ServerFnError> {
+    use crate::server::species_images;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+    let photo = species_images::refresh(&state.photo_cache, &scientific_name).await;
+    crate::server::audit::record("photo.refresh", &scientific_name, "").await;
+    Ok(photo)
+}
+
 /// Hourly detection histogram for a species (all-time).
 #[server(prefix = "/api")]
 pub async fn get_species_hourly(
@@ -127,7 +189,7 @@ pub async fn get_species_detections(
     model_slug: String,
     limit: u32,
 ) -> Result<Vec<WebDetection>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
     let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
@@ -135,7 +197,7 @@ pub async fn get_species_detections(
         .await
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
     for det in dets.iter_mut() {
-        if let Some(photo) = inaturalist::lookup(&state.photo_cache, &det.scientific_name).await {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &det.scientific_name) {
             det.image_url = Some(photo.medium_url);
         }
     }
@@ -195,16 +257,25 @@ pub fn SpeciesPage() -> impl IntoView {
 /// Species detail content (factored out for clarity).
 #[component]
 fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
-    let img_src = species
-        .image_url
-        .clone()
-        .unwrap_or_else(|| "/pkg/placeholder.svg".to_string());
+    let (img_src, set_img_src) = signal(
+        species
+            .image_url
+            .clone()
+            .unwrap_or_else(|| "/pkg/placeholder.svg".to_string()),
+    );
+    let (photo_credit, set_photo_credit) = signal(
+        species
+            .image_source
+            .map(|src| credit_line(species.image_attribution.as_deref().unwrap_or(""), src)),
+    );
+    let (pinned, set_pinned) = signal(species.image_source == Some(ImageSource::Pinned));
 
     let male_img = species.male_image_url.clone();
     let female_img = species.female_image_url.clone();
     let has_dimorphism = male_img.is_some() || female_img.is_some();
 
     let wiki_link = species.wikipedia_url.clone();
+    let season_href = format!("/seasonal?species={}", species.scientific_name.replace(' ', "%20"));
     let sci_name = species.scientific_name.clone();
 
     // ── Calendar state: default to current year/month ────────────────────
@@ -237,6 +308,7 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
     // ── Model-filtered detection list ───────────────────────────────────
     let (model_slug, set_model_slug) = signal(String::new());
     let sci_name_for_dets = sci_name.clone();
+    let sci_name_for_map = sci_name.clone();
     let model_detections = Resource::new(
         move || (sci_name_for_dets.clone(), model_slug.get()),
         |(name, slug)| async move { get_species_detections(name, slug, 50).await },
@@ -319,6 +391,85 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
         }
     };
 
+    // ── Photo pin (admin override) ───────────────────────────────────────
+    let (pin_url, set_pin_url) = signal(String::new());
+    let (pin_attribution, set_pin_attribution) = signal(String::new());
+    let (pin_status, set_pin_status) = signal(Option::<String>::None);
+
+    let sci_name_pin = sci_name.clone();
+    let pin_image = Action::new(move |(url, attribution): &(String, String)| {
+        let name = sci_name_pin.clone();
+        let url = url.clone();
+        let attribution = attribution.clone();
+        async move { pin_species_image(name, url, attribution).await }
+    });
+    let sci_name_unpin = sci_name.clone();
+    let unpin_image = Action::new(move |_: &()| {
+        let name = sci_name_unpin.clone();
+        async move { unpin_species_image(name).await }
+    });
+    let sci_name_refresh = sci_name.clone();
+    let refresh_image = Action::new(move |_: &()| {
+        let name = sci_name_refresh.clone();
+        async move { refresh_species_image(name).await }
+    });
+
+    let show_photo = move |photo: Option<SpeciesPhoto>| match photo {
+        Some(p) => {
+            set_photo_credit.set(Some(credit_line(&p.attribution, p.source)));
+            set_pinned.set(p.source == ImageSource::Pinned);
+            set_img_src.set(p.medium_url);
+        }
+        None => {
+            set_photo_credit.set(None);
+            set_pinned.set(false);
+            set_img_src.set("/pkg/placeholder.svg".to_string());
+        }
+    };
+    Effect::new(move || {
+        if let Some(result) = pin_image.value().get() {
+            match result {
+                Ok(photo) => {
+                    show_photo(photo);
+                    set_pin_status.set(Some("Photo pinned.".into()));
+                }
+                Err(e) => set_pin_status.set(Some(format!("Error: {e}"))),
+            }
+        }
+    });
+    Effect::new(move || {
+        if let Some(result) = unpin_image.value().get() {
+            match result {
+                Ok(photo) => {
+                    show_photo(photo);
+                    set_pin_status.set(Some("Pin removed.".into()));
+                }
+                Err(e) => set_pin_status.set(Some(format!("Error: {e}"))),
+            }
+        }
+    });
+    Effect::new(move || {
+        if let Some(result) = refresh_image.value().get() {
+            match result {
+                Ok(photo) => {
+                    show_photo(photo);
+                    set_pin_status.set(Some("Photo refreshed.".into()));
+                }
+                Err(e) => set_pin_status.set(Some(format!("Error: {e}"))),
+            }
+        }
+    });
+
+    let on_pin = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_pin_status.set(None);
+        pin_image.dispatch((pin_url.get(), pin_attribution.get()));
+    };
+
+    let locale = use_locale();
+    let first_seen = species.first_seen.clone().unwrap_or_default();
+    let last_seen = species.last_seen.clone().unwrap_or_default();
+
     // Month names for the dropdown.
     let month_names = vec![
         (1, "January"), (2, "February"), (3, "March"), (4, "April"),
@@ -329,7 +480,12 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
     view! {
         <div class="species-detail">
             <div class="species-hero">
-                <img src={img_src} alt={species.common_name.clone()} class="species-hero-img" />
+                <figure class="species-hero-figure">
+                    <img src=move || img_src.get() alt={species.common_name.clone()} class="species-hero-img" />
+                    {move || photo_credit.get().map(|credit| view! {
+                        <figcaption class="species-photo-credit">{credit}</figcaption>
+                    })}
+                </figure>
                 <div class="species-hero-info">
                     <h1>{species.common_name.clone()}</h1>
                     <p class="species-sci-name">{species.scientific_name.clone()}</p>
@@ -340,11 +496,11 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
                             <span class="stat-label">"Total Detections"</span>
                         </div>
                         <div class="stat">
-                            <span class="stat-value">{species.first_seen.clone().unwrap_or_default()}</span>
+                            <span class="stat-value">{move || locale.date(&first_seen)}</span>
                             <span class="stat-label">"First Seen"</span>
                         </div>
                         <div class="stat">
-                            <span class="stat-value">{species.last_seen.clone().unwrap_or_default()}</span>
+                            <span class="stat-value">{move || locale.date(&last_seen)}</span>
                             <span class="stat-label">"Last Seen"</span>
                         </div>
                     </div>
@@ -353,6 +509,8 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
                             "Wikipedia →"
                         </a>
                     })}
+                    <a href={season_href} class="wiki-link">"Seasonal occurrence →"</a>
+                    <PushToggle target=sci_name.clone() label="Notify me" />
                 </div>
             </div>
 
@@ -433,6 +591,62 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
                 })}
             </section>
 
+            // ── Photo pin ────────────────────────────────────────────
+            <details class="species-photo-admin">
+                <summary>"Change photo"</summary>
+                <form class="inat-obs-form" on:submit=on_pin>
+                    <label class="inat-obs-label">
+                        "Image URL"
+                        <input
+                            type="text"
+                            class="inat-obs-input"
+                            placeholder="https://… or /species-images/…"
+                            prop:value=pin_url
+                            on:input=move |ev| set_pin_url.set(event_target_value(&ev))
+                        />
+                    </label>
+                    <label class="inat-obs-label">
+                        "Credit"
+                        <input
+                            type="text"
+                            class="inat-obs-input"
+                            placeholder="e.g. © Jane Doe, CC BY 4.0"
+                            prop:value=pin_attribution
+                            on:input=move |ev| set_pin_attribution.set(event_target_value(&ev))
+                        />
+                    </label>
+                    <button type="submit" class="inat-obs-save">"Pin"</button>
+                </form>
+                {move || pinned.get().then(|| view! {
+                    <button
+                        class="species-photo-unpin"
+                        on:click=move |_| {
+                            set_pin_status.set(None);
+                            unpin_image.dispatch(());
+                        }
+                    >
+                        "Remove pin"
+                    </button>
+                })}
+                <button
+                    class="species-photo-unpin"
+                    on:click=move |_| {
+                        set_pin_status.set(None);
+                        refresh_image.dispatch(());
+                    }
+                >
+                    "Refetch from providers"
+                </button>
+                {move || pin_status.get().map(|msg| {
+                    let cls = if msg.starts_with("Error") {
+                        "verification-status verification-error"
+                    } else {
+                        "verification-status verification-ok"
+                    };
+                    view! { <p class=cls>{msg}</p> }
+                })}
+            </details>
+
             // ── Hourly activity chart ────────────────────────────────
             <section class="species-hourly">
                 <h2>"Activity by Hour"</h2>
@@ -471,8 +685,7 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
                                     let spec_url = r.spectrogram_url();
                                     let clip     = r.clip_url();
                                     let conf_pct = format!("{:.0}%", r.confidence * 100.0);
-                                    let date     = if r.display_date.is_empty() { r.date.clone() } else { r.display_date.clone() };
-                                    let time     = if r.display_time.is_empty() { r.time.clone() } else { r.display_time.clone() };
+                                    let when     = move || locale.detection_time(&r.date, &r.time, &r.display_date, &r.display_time);
                                     view! {
                                         <div class="recording-card">
                                             <img
@@ -483,7 +696,7 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
                                             />
                                             <div class="recording-info">
                                                 <span class="recording-confidence">{conf_pct}</span>
-                                                <span class="recording-datetime">{date} " " {time}</span>
+                                                <span class="recording-datetime">{when}</span>
                                             </div>
                                             <audio controls preload="none" class="recording-audio">
                                                 <source src={clip} type="audio/mp3" />
@@ -526,6 +739,9 @@ fn SpeciesDetail(species: SpeciesInfo) -> impl IntoView {
                 </Suspense>
             </section>
 
+            // ── Stations (only with two or more) ────────────────────
+            <SpeciesMap scientific_name=sci_name_for_map />
+
             <section class="species-calendar">
                 <h2>"Calendar"</h2>
                 <div class="species-cal-nav">
@@ -594,3 +810,12 @@ fn js_sys_now() -> (i32, u32) {
         (2025, 1)
     }
 }
+
+/// Caption under the species photo, e.g. "Photo: (c) Jane Doe · iNaturalist".
+fn credit_line(attribution: &str, source: ImageSource) -> String {
+    if attribution.is_empty() {
+        format!("Photo: {}", source.label())
+    } else {
+        format!("Photo: {attribution} · {}", source.label())
+    }
+}
@@ -7,9 +7,11 @@ use leptos::prelude::{
 };
 use leptos::either::Either;
 
+use crate::components::domain_filter::use_domain;
 use crate::components::model_filter::ModelFilter;
+use crate::components::push_toggle::PushToggle;
 use crate::components::species_card::SpeciesCard;
-use crate::model::{CacheSummaryStatus, SpeciesSummary};
+use crate::model::{CacheSummaryStatus, SpeciesSummary, PUSH_NEW_SPECIES, PUSH_RARE_SPECIES};
 
 /// Sort criteria for the species list.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,20 +64,22 @@ impl SpeciesSort {
 pub async fn get_all_species(
     limit: u32,
     model_slug: String,
+    domain: String,
 ) -> Result<Vec<SpeciesSummary>, ServerFnError> {
-    use crate::server::{detections_duckdb as ddb, inaturalist};
+    use crate::server::{detections_duckdb as ddb, species_images};
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
 
     let slug_opt = if model_slug.is_empty() { None } else { Some(model_slug.as_str()) };
-    let mut species = ddb::top_species_filtered(&state.db_path, limit, slug_opt)
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    let mut species = ddb::top_species_filtered(&state.db_path, limit, slug_opt, domain_opt)
         .await
         .map_err(|e| ServerFnError::new(format!("DB error: {e}")))?;
 
     // Fast path for initial page render: enrich only from in-memory cache,
     // never perform outbound iNaturalist requests here.
     for sp in species.iter_mut() {
-        if let Some(photo) = inaturalist::lookup_cached(&state.photo_cache, &sp.scientific_name) {
+        if let Some(photo) = species_images::lookup_cached(&state.photo_cache, &sp.scientific_name) {
             sp.image_url = Some(photo.medium_url);
             sp.male_image_url = photo.male_image_url;
             sp.female_image_url = photo.female_image_url;
@@ -97,18 +101,19 @@ pub async fn get_all_species(
     Ok(species)
 }
 
-/// Fetch iNaturalist photo metadata for a single species on demand.
+/// Cached photo metadata for a single species.
 ///
-/// Intended for client-side lazy image hydration so the species list can
-/// render quickly before external API lookups complete.
+/// Intended for client-side lazy image hydration: cards rendered without
+/// a photo ask again after hydration, picking up photos the enrichment
+/// worker fetched in the meantime.
 #[server(prefix = "/api")]
 pub async fn get_species_photo(
     scientific_name: String,
 ) -> Result<Option<crate::model::SpeciesPhoto>, ServerFnError> {
-    use crate::server::inaturalist;
+    use crate::server::species_images;
     let state = use_context::<crate::app::AppState>()
         .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
-    Ok(inaturalist::lookup(&state.photo_cache, &scientific_name).await)
+    Ok(species_images::lookup_cached(&state.photo_cache, &scientific_name))
 }
 
 #[server(prefix = "/api")]
@@ -129,15 +134,20 @@ pub fn SpeciesListPage() -> impl IntoView {
         || (),
         |_| async { get_stats_cache_status().await },
     );
+    let domain = use_domain();
     let species = Resource::new(
-        move || model_slug.get(),
-        |slug| async move { get_all_species(500, slug).await },
+        move || (model_slug.get(), domain.get()),
+        |(slug, domain)| async move { get_all_species(500, slug, domain).await },
     );
     let (sort, set_sort) = signal(SpeciesSort::Detections);
 
     view! {
         <div class="species-list-page">
-            <h1>"All Species"</h1>
+            <div class="page-title-row">
+                <h1>"All Species"</h1>
+                <PushToggle target=PUSH_NEW_SPECIES label="Notify me about new species" />
+                <PushToggle target=PUSH_RARE_SPECIES label="Notify me about rare species" />
+            </div>
 
             <Suspense fallback=|| view! { <p class="meta">"Cache: loading…"</p> }>
                 {move || cache_status.get().map(|res| match res {
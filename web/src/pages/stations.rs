@@ -0,0 +1,202 @@
+//! Stations page – compare the nodes reporting into this database.
+//!
+//! One row per `Source_Node` with its detections, detections per day,
+//! observed and estimated richness and the species no other station
+//! detected, then a matrix of which species each station detected.
+//! Query parameters `from` / `to` (`YYYY-MM-DD`, inclusive, optional)
+//! limit the period; follows the global domain filter.  The title row
+//! subscribes to the processing watchdog's quiet-station alerts.
+
+use leptos::prelude::*;
+use leptos::prelude::{ElementChild, IntoView, Resource, ServerFnError, Suspense};
+use leptos_router::hooks::use_query_map;
+
+use crate::components::domain_filter::{domain_class, use_domain};
+use crate::components::locale::use_locale;
+use crate::components::push_toggle::PushToggle;
+use crate::model::{StationComparison, StationSpecies, StationSummary, PUSH_STATION_ALERTS};
+
+// ─── Server function ─────────────────────────────────────────────────────────
+
+#[server(prefix = "/api")]
+pub async fn get_station_comparison(
+    from: String,
+    to: String,
+    domain: String,
+) -> Result<StationComparison, ServerFnError> {
+    use crate::server::detections_duckdb as ddb;
+    let state = use_context::<crate::app::AppState>()
+        .ok_or_else(|| ServerFnError::new("Missing AppState"))?;
+
+    let date = |s: &str| -> Result<Option<String>, ServerFnError> {
+        if s.is_empty() {
+            return Ok(None);
+        }
+        chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map(|d| Some(d.to_string()))
+            .map_err(|_| ServerFnError::new(format!("Invalid date {s:?}, expected YYYY-MM-DD")))
+    };
+    let (from, to) = (date(&from)?, date(&to)?);
+    let domain_opt = if domain.is_empty() { None } else { Some(domain.as_str()) };
+    ddb::station_comparison(&state.db_path, domain_opt, from.as_deref(), to.as_deref())
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB error: {e}")))
+}
+
+// ─── Page component ──────────────────────────────────────────────────────────
+
+/// Side-by-side figures and species of every station.
+#[component]
+pub fn StationsPage() -> impl IntoView {
+    let query = use_query_map();
+    let param = move |key: &'static str| query.with(|q| q.get(key).unwrap_or_default());
+
+    let domain = use_domain();
+
+    let data = Resource::new(
+        move || (param("from"), param("to"), domain.get()),
+        |(from, to, domain)| async move { get_station_comparison(from, to, domain).await },
+    );
+
+    view! {
+        <div class="stations-page">
+            <div class="page-title-row">
+                <h1>"Compare Stations"</h1>
+                <PushToggle target=PUSH_STATION_ALERTS label="Notify me when a station goes quiet" />
+            </div>
+
+            // Plain GET form, like the Compare page, so a view can be shared.
+            <form class="compare-form" method="get" action="/stations">
+                <fieldset>
+                    <legend>"Period"</legend>
+                    <input type="date" name="from" value=move || param("from") />
+                    " – "
+                    <input type="date" name="to" value=move || param("to") />
+                </fieldset>
+                <input type="hidden" name="domain" value=move || domain.get() disabled=move || domain.get().is_empty() />
+                <button type="submit" class="sort-btn">"Show"</button>
+            </form>
+
+            <Suspense fallback=|| view! { <p class="loading">"Loading stations\u{2026}"</p> }>
+                {move || data.get().map(|res| match res {
+                    Ok(cmp) if cmp.stations.is_empty() => view! {
+                        <p class="text-muted">"No detections in this period."</p>
+                    }.into_any(),
+                    Ok(cmp) => view! { <StationsView cmp=cmp /> }.into_any(),
+                    Err(e) => view! {
+                        <p class="error">"Error: " {e.to_string()}</p>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn StationsView(cmp: StationComparison) -> impl IntoView {
+    let most_species = cmp.stations.iter().map(|s| s.species).max().unwrap_or(0).max(1);
+    let names: Vec<String> = cmp.stations.iter().map(|s| station_name(&s.node)).collect();
+
+    view! {
+        {(cmp.stations.len() == 1).then(|| view! {
+            <p class="text-muted">
+                "Only one station reports into this database; the comparison fills in as more nodes join."
+            </p>
+        })}
+
+        <table class="report-table stations-table">
+            <thead>
+                <tr>
+                    <th>"Station"</th>
+                    <th title="Species detected">"Species"</th>
+                    <th title="Species no other station detected">"Only here"</th>
+                    <th title="Bias-corrected Chao2 estimate">"Estimated"</th>
+                    <th>"Detections"</th>
+                    <th title="Days with at least one detection">"Days"</th>
+                    <th title="Mean detections per day with detections">"Per day"</th>
+                    <th>"Active"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {cmp.stations.iter().cloned().map(|s| view! {
+                    <StationRow station=s most_species=most_species />
+                }).collect::<Vec<_>>()}
+            </tbody>
+        </table>
+
+        <h2>"Species by station"</h2>
+        <div class="stations-matrix-wrap">
+            <table class="report-table stations-matrix">
+                <thead>
+                    <tr>
+                        <th>"Species"</th>
+                        {names.iter().map(|n| view! { <th class="stations-matrix-node">{n.clone()}</th> }).collect::<Vec<_>>()}
+                    </tr>
+                </thead>
+                <tbody>
+                    {cmp.species.into_iter().map(|s| view! { <MatrixRow row=s /> }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+        </div>
+        <p class="seasonal-caption">
+            "Species detected by most stations come first; a filled cell means the station "
+            "detected the species, darker for more detections."
+        </p>
+    }
+}
+
+#[component]
+fn StationRow(station: StationSummary, most_species: u32) -> impl IntoView {
+    let bar = format!("width: {:.1}%", station.species as f64 / most_species as f64 * 100.0);
+    let locale = use_locale();
+    let (first_seen, last_seen) = (station.first_seen.clone(), station.last_seen.clone());
+    view! {
+        <tr>
+            <td><strong>{station_name(&station.node)}</strong></td>
+            <td>
+                <div class="stations-bar"><div class="stations-bar-fill" style=bar></div></div>
+                {station.species}
+            </td>
+            <td>{station.unique_species}</td>
+            <td>{format!("{:.0}", station.estimated)}</td>
+            <td>{station.detections}</td>
+            <td>{station.days}</td>
+            <td>{format!("{:.1}", station.detections_per_day())}</td>
+            <td>{move || format!("{} – {}", locale.date(&first_seen), locale.date(&last_seen))}</td>
+        </tr>
+    }
+}
+
+#[component]
+fn MatrixRow(row: StationSpecies) -> impl IntoView {
+    let href = format!("/species/{}", row.scientific_name.replace(' ', "%20"));
+    let most = row.detections.iter().copied().max().unwrap_or(0).max(1);
+    view! {
+        <tr>
+            <td>
+                <a href=href title=row.scientific_name.clone()>{row.common_name.clone()}</a>
+                <span class=format!("domain-badge {}", domain_class(&row.domain))>{row.domain.clone()}</span>
+            </td>
+            {row.detections.iter().map(|&n| {
+                // Shade by detections relative to the species' busiest station.
+                let intensity = 25.0 + 75.0 * (n as f64).ln_1p() / (most as f64).ln_1p();
+                let style = format!("--intensity: {intensity:.0}%");
+                let seen = n > 0;
+                view! {
+                    <td class="stations-cell" class:seen=seen style=style>
+                        {seen.then_some(n)}
+                    </td>
+                }
+            }).collect::<Vec<_>>()}
+        </tr>
+    }
+}
+
+/// Label of a station; detections stored without a node are grouped too.
+fn station_name(node: &str) -> String {
+    if node.is_empty() {
+        "(unnamed)".to_string()
+    } else {
+        node.to_string()
+    }
+}
@@ -0,0 +1,131 @@
+//! Audit log of admin actions: settings changes, imports, relabels,
+//! review verdicts, exclusion overrides, verifications and photo pins.
+//!
+//! The dashboard has no accounts of its own.  When it sits behind an
+//! authenticating reverse proxy (Authelia, oauth2-proxy, …) the user
+//! name the proxy passes on is recorded; otherwise the client address.
+//! Entries live in the `audit:log` Redis list (see [`kv`]).
+
+use std::collections::HashMap;
+
+use http::HeaderMap;
+use leptos::prelude::use_context;
+use tracing::warn;
+
+use crate::model::AuditEntry;
+use crate::server::kv;
+
+/// Headers an authenticating proxy puts the user name in, by preference.
+const USER_HEADERS: [&str; 4] = [
+    "remote-user",
+    "x-forwarded-user",
+    "x-auth-request-user",
+    "x-forwarded-preferred-username",
+];
+
+/// Who made a request: the proxy-authenticated user, else the client
+/// address from `X-Forwarded-For` / `X-Real-IP`, else `unknown`.
+pub fn actor(headers: &HeaderMap) -> String {
+    if let Some(user) = proxy_user(headers) {
+        return user;
+    }
+    match header(headers, "x-forwarded-for").or_else(|| header(headers, "x-real-ip")) {
+        Some(ip) => format!("@{ip}"),
+        None => "unknown".to_string(),
+    }
+}
+
+/// The user name an authenticating proxy passed on, if any.
+pub fn proxy_user(headers: &HeaderMap) -> Option<String> {
+    USER_HEADERS
+        .iter()
+        .find_map(|h| header(headers, h))
+        .map(str::to_string)
+}
+
+/// First value of header `name`; proxies chain values and the first one
+/// is the client's.
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Record an action by the client of the current request.  A failed
+/// write is logged rather than returned: the action itself already
+/// happened.
+pub async fn record(action: &str, target: &str, details: impl Into<String>) {
+    let actor = use_context::<http::request::Parts>()
+        .map(|parts| actor(&parts.headers))
+        .unwrap_or_else(|| "unknown".to_string());
+    let entry = AuditEntry {
+        at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        actor,
+        action: action.to_string(),
+        target: target.to_string(),
+        details: details.into(),
+    };
+    if let Err(e) = kv::push_audit_entry(&entry).await {
+        warn!("Cannot write audit entry {entry:?}: {e}");
+    }
+}
+
+/// `key: old → new` for every setting in `new` that differs from `old`.
+pub fn settings_diff(old: &HashMap<String, String>, new: &[(&str, &str)]) -> String {
+    new.iter()
+        .filter_map(|&(key, value)| {
+            let before = old.get(key).map(String::as_str);
+            (before != Some(value))
+                .then(|| format!("{key}: {} → {value}", before.unwrap_or("(unset)")))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_actor() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(actor(&headers), "unknown");
+
+        headers.insert("x-real-ip", HeaderValue::from_static("192.168.1.20"));
+        assert_eq!(actor(&headers), "@192.168.1.20");
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("10.0.0.5, 172.17.0.1"),
+        );
+        assert_eq!(actor(&headers), "@10.0.0.5");
+
+        headers.insert("x-forwarded-user", HeaderValue::from_static("alice"));
+        assert_eq!(actor(&headers), "alice");
+        headers.insert("remote-user", HeaderValue::from_static("bob"));
+        assert_eq!(actor(&headers), "bob");
+        assert_eq!(proxy_user(&headers).as_deref(), Some("bob"));
+        assert_eq!(proxy_user(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_settings_diff() {
+        let old: HashMap<String, String> = [("confidence", "0.7"), ("overlap", "0")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let new = [
+            ("confidence", "0.8"),
+            ("overlap", "0"),
+            ("colormap", "magma"),
+        ];
+        assert_eq!(
+            settings_diff(&old, &new),
+            "confidence: 0.7 → 0.8, colormap: (unset) → magma"
+        );
+        assert_eq!(settings_diff(&old, &[("overlap", "0")]), "");
+    }
+}
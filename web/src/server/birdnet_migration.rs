@@ -0,0 +1,377 @@
+//! Migration of BirdNET-Pi settings into `gaia.conf`.
+//!
+//! Analysing or importing a backup keeps its `birdnet.conf` and species
+//! lists under `<data>/birdnetpi/` ([`keep_dir`]).  [`preview`] maps the
+//! settings Gaia understands onto `gaia.conf` keys and compares them
+//! with the current file; [`apply`] writes the result (keeping the old
+//! file as `gaia.conf.bak`) and copies the species lists next to the
+//! database, where the processing server looks for them.
+//!
+//! Paths, hardware and BirdNET-Pi service settings are deliberately not
+//! migrated: they describe the old installation, not this one.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::model::{ConfigChange, ConfigMigration, SpeciesListChange};
+
+/// Files worth keeping from a backup for the migration.
+pub const MIGRATION_FILES: &[&str] = &[
+    "birdnet.conf",
+    "include_species_list.txt",
+    "exclude_species_list.txt",
+    "whitelist_species_list.txt",
+];
+
+/// Header above the lines appended to `gaia.conf`.
+const MIGRATED_HEADER: &str = "# ── Migrated from BirdNET-Pi (birdnet.conf) ──";
+
+/// `birdnet.conf` key → `gaia.conf` key.
+const KEY_MAP: &[(&str, &str)] = &[
+    ("LATITUDE", "LATITUDE"),
+    ("LONGITUDE", "LONGITUDE"),
+    ("CONFIDENCE", "CONFIDENCE"),
+    ("SENSITIVITY", "SENSITIVITY"),
+    ("OVERLAP", "OVERLAP"),
+    ("SF_THRESH", "SF_THRESH"),
+    ("DATABASE_LANG", "DATABASE_LANG"),
+    ("BIRDWEATHER_ID", "BIRDWEATHER_ID"),
+    ("HEARTBEAT_URL", "HEARTBEAT_URL"),
+    ("RECORDING_LENGTH", "RECORDING_LENGTH"),
+    ("EXTRACTION_LENGTH", "EXTRACTION_LENGTH"),
+    ("CHANNELS", "CHANNELS"),
+    ("PRIVACY_THRESHOLD", "PRIVACY_THRESHOLD"),
+    ("RAW_SPECTROGRAM", "RAW_SPECTROGRAM"),
+    ("DATA_MODEL_VERSION", "DATA_MODEL_VERSION"),
+    ("RTSP_STREAM", "RTSP_STREAMS"),
+    ("AUDIOFMT", "CLIP_FORMAT"),
+];
+
+/// Where the files kept from BirdNET-Pi backups live.
+pub fn keep_dir(db_path: &Path) -> PathBuf {
+    data_dir(db_path).join("birdnetpi")
+}
+
+/// The `gaia.conf` to migrate into (`GAIA_CONF`, default
+/// `/etc/gaia/gaia.conf`).
+pub fn gaia_conf_path() -> PathBuf {
+    std::env::var("GAIA_CONF")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/etc/gaia/gaia.conf"))
+}
+
+/// If `tar_path` (an entry of a backup) is one of [`MIGRATION_FILES`],
+/// its file name.  BirdNET-Pi keeps the lists under `scripts/`, so only
+/// the last component is compared.
+pub fn migration_file(tar_path: &str) -> Option<&'static str> {
+    let name = tar_path.rsplit('/').next()?;
+    MIGRATION_FILES.iter().copied().find(|f| *f == name)
+}
+
+/// Compare the kept `birdnet.conf` and species lists with the current
+/// configuration.
+pub fn preview(db_path: &Path) -> Result<ConfigMigration, String> {
+    let kept = keep_dir(db_path);
+    let birdnet_conf = kept.join("birdnet.conf");
+    let text = std::fs::read_to_string(&birdnet_conf).map_err(|_| {
+        "No birdnet.conf yet — analyse or import a BirdNET-Pi backup first".to_string()
+    })?;
+    let gaia_conf = gaia_conf_path();
+    let current_text = std::fs::read_to_string(&gaia_conf).unwrap_or_default();
+
+    let (mapped, unmapped) = map_settings(&text);
+    let current = parse_conf(&current_text);
+    let mut changes = Vec::new();
+    let mut unchanged = Vec::new();
+    for (from_key, key, proposed) in mapped {
+        let now = current.get(key).cloned();
+        if now.as_deref() == Some(proposed.as_str()) {
+            unchanged.push(key.to_string());
+            continue;
+        }
+        changes.push(ConfigChange {
+            key: key.to_string(),
+            from_key: (from_key != key).then(|| from_key.to_string()),
+            current: now,
+            proposed,
+        });
+    }
+
+    let species_lists = MIGRATION_FILES
+        .iter()
+        .filter(|name| name.ends_with(".txt"))
+        .filter_map(|name| {
+            let entries = count_entries(&kept.join(name))?;
+            Some(SpeciesListChange {
+                name: name.to_string(),
+                entries,
+                current_entries: count_entries(&data_dir(db_path).join(name)).unwrap_or(0),
+            })
+        })
+        .collect();
+
+    Ok(ConfigMigration {
+        gaia_conf: gaia_conf.display().to_string(),
+        writable: is_writable(&gaia_conf),
+        changes,
+        unchanged,
+        unmapped,
+        species_lists,
+    })
+}
+
+/// Write the previewed changes to `gaia.conf` and copy the species
+/// lists.  Returns the applied preview.
+pub fn apply(db_path: &Path) -> Result<ConfigMigration, String> {
+    let migration = preview(db_path)?;
+    let gaia_conf = gaia_conf_path();
+
+    if !migration.changes.is_empty() {
+        if !migration.writable {
+            return Err(format!(
+                "{} is not writable from the web container — mount it read-write",
+                gaia_conf.display()
+            ));
+        }
+        let current = std::fs::read_to_string(&gaia_conf).unwrap_or_default();
+        let updates: Vec<(String, String)> = migration
+            .changes
+            .iter()
+            .map(|c| (c.key.clone(), c.proposed.clone()))
+            .collect();
+        if gaia_conf.exists() {
+            std::fs::copy(&gaia_conf, gaia_conf.with_extension("conf.bak"))
+                .map_err(|e| format!("Cannot back up {}: {e}", gaia_conf.display()))?;
+        }
+        // Truncate in place rather than rename: gaia.conf is usually a
+        // single-file bind mount.
+        std::fs::write(&gaia_conf, merge_conf(&current, &updates))
+            .map_err(|e| format!("Cannot write {}: {e}", gaia_conf.display()))?;
+    }
+
+    let kept = keep_dir(db_path);
+    for list in &migration.species_lists {
+        let dest = data_dir(db_path).join(&list.name);
+        std::fs::copy(kept.join(&list.name), &dest)
+            .map_err(|e| format!("Cannot write {}: {e}", dest.display()))?;
+    }
+
+    tracing::info!(
+        "Migrated {} BirdNET-Pi setting(s) into {} and {} species list(s)",
+        migration.changes.len(),
+        gaia_conf.display(),
+        migration.species_lists.len()
+    );
+    Ok(migration)
+}
+
+/// Settings of a `birdnet.conf` with a Gaia equivalent, as
+/// `(birdnet key, gaia key, value)`, plus the keys left behind.
+fn map_settings(text: &str) -> (Vec<(&'static str, &'static str, String)>, Vec<String>) {
+    let mut mapped = Vec::new();
+    let mut unmapped = Vec::new();
+    for (key, value) in conf_lines(text) {
+        if value.is_empty() {
+            continue;
+        }
+        match KEY_MAP.iter().find(|(from, _)| *from == key) {
+            Some(&(from, to)) => match convert(from, &value) {
+                Some(v) => mapped.push((from, to, v)),
+                None => unmapped.push(format!("{key} (unsupported value {value:?})")),
+            },
+            None => unmapped.push(key),
+        }
+    }
+    (mapped, unmapped)
+}
+
+/// Validate and translate one value; `None` when Gaia can't use it.
+fn convert(key: &str, value: &str) -> Option<String> {
+    let number = |lo: f64, hi: f64| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| (lo..=hi).contains(v))
+            .map(|_| value.to_string())
+    };
+    match key {
+        "LATITUDE" => number(-90.0, 90.0),
+        "LONGITUDE" => number(-180.0, 180.0),
+        "CONFIDENCE" | "SF_THRESH" => number(0.0, 1.0),
+        "SENSITIVITY" => number(0.5, 1.5),
+        "OVERLAP" => number(0.0, 2.9),
+        "PRIVACY_THRESHOLD" => number(0.0, 100.0),
+        "RECORDING_LENGTH" | "EXTRACTION_LENGTH" | "CHANNELS" | "DATA_MODEL_VERSION" => {
+            value.parse::<u32>().ok().filter(|v| *v > 0).map(|v| v.to_string())
+        }
+        "RAW_SPECTROGRAM" => match value {
+            "0" | "1" => Some(value.to_string()),
+            "true" => Some("1".into()),
+            "false" => Some("0".into()),
+            _ => None,
+        },
+        "AUDIOFMT" => {
+            let fmt = value.to_ascii_lowercase();
+            matches!(fmt.as_str(), "mp3" | "wav" | "opus").then_some(fmt)
+        }
+        "RTSP_STREAM" => Some(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|u| !u.is_empty())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .filter(|v| !v.is_empty()),
+        _ => Some(value.to_string()),
+    }
+}
+
+/// Rewrite `text` with `updates` applied: an active `KEY=` line is
+/// replaced, else a commented-out default (`# KEY=`) is uncommented, else
+/// the line is appended under [`MIGRATED_HEADER`].
+fn merge_conf(text: &str, updates: &[(String, String)]) -> String {
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let mut pending: Vec<&(String, String)> = Vec::new();
+
+    for update in updates {
+        let (key, value) = update;
+        let line = format!("{key}={}", quote(value));
+        let active = lines.iter().position(|l| line_key(l, false) == Some(key.as_str()));
+        let commented = || lines.iter().position(|l| line_key(l, true) == Some(key.as_str()));
+        match active.or_else(commented) {
+            Some(i) => lines[i] = line,
+            None => pending.push(update),
+        }
+    }
+
+    if !pending.is_empty() {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(MIGRATED_HEADER.to_string());
+        for (key, value) in pending {
+            lines.push(format!("{key}={}", quote(value)));
+        }
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Key of a `KEY=VALUE` line; with `commented`, only of `# KEY=VALUE`.
+fn line_key(line: &str, commented: bool) -> Option<&str> {
+    let line = line.trim();
+    let line = if commented {
+        line.strip_prefix('#')?.trim_start()
+    } else if line.starts_with('#') {
+        return None;
+    } else {
+        line
+    };
+    let (key, _) = line.split_once('=')?;
+    let key = key.trim();
+    (!key.is_empty() && key.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .then_some(key)
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) || value.contains('#') {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+/// `KEY=VALUE` pairs in file order, values unquoted.
+fn conf_lines(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let key = line_key(line, false)?;
+            let (_, value) = line.split_once('=')?;
+            Some((key.to_string(), value.trim().trim_matches('"').trim_matches('\'').to_string()))
+        })
+        .collect()
+}
+
+fn parse_conf(text: &str) -> HashMap<String, String> {
+    conf_lines(text).into_iter().collect()
+}
+
+fn count_entries(path: &Path) -> Option<usize> {
+    let text = std::fs::read_to_string(path).ok()?;
+    Some(text.lines().filter(|l| !l.trim().is_empty()).count())
+}
+
+fn data_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new("/data")).to_path_buf()
+}
+
+fn is_writable(path: &Path) -> bool {
+    match std::fs::metadata(path) {
+        Ok(_) => std::fs::OpenOptions::new().append(true).open(path).is_ok(),
+        Err(_) => path.parent().is_some_and(|p| {
+            std::fs::metadata(p).is_ok_and(|m| !m.permissions().readonly())
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_settings() {
+        let text = "\
+LATITUDE=9.93\n\
+LONGITUDE=\"-84.07\"\n\
+CONFIDENCE=0.75\n\
+SENSITIVITY=3\n\
+AUDIOFMT=mp3\n\
+RTSP_STREAM=\"rtsp://a, rtsp://b\"\n\
+BIRDWEATHER_ID=\n\
+RECS_DIR=/home/pi/BirdSongs\n\
+# CADDY_PWD=x\n";
+        let (mapped, unmapped) = map_settings(text);
+        let get = |k: &str| mapped.iter().find(|(_, to, _)| *to == k).map(|(_, _, v)| v.as_str());
+        assert_eq!(get("LATITUDE"), Some("9.93"));
+        assert_eq!(get("LONGITUDE"), Some("-84.07"));
+        assert_eq!(get("CONFIDENCE"), Some("0.75"));
+        assert_eq!(get("CLIP_FORMAT"), Some("mp3"));
+        assert_eq!(get("RTSP_STREAMS"), Some("rtsp://a,rtsp://b"));
+        assert_eq!(get("SENSITIVITY"), None);
+        assert_eq!(get("BIRDWEATHER_ID"), None);
+        assert_eq!(unmapped, vec!["SENSITIVITY (unsupported value \"3\")", "RECS_DIR"]);
+    }
+
+    #[test]
+    fn test_merge_conf() {
+        let text = "# Gaia config\nLATITUDE=1.0\n# CONFIDENCE=0.7\nRECS_DIR=/data\n";
+        let updates = vec![
+            ("LATITUDE".to_string(), "9.93".to_string()),
+            ("CONFIDENCE".to_string(), "0.75".to_string()),
+            ("BIRDWEATHER_ID".to_string(), "abc def".to_string()),
+        ];
+        assert_eq!(
+            merge_conf(text, &updates),
+            format!(
+                "# Gaia config\nLATITUDE=9.93\nCONFIDENCE=0.75\nRECS_DIR=/data\n\n\
+                 {MIGRATED_HEADER}\nBIRDWEATHER_ID=\"abc def\"\n"
+            )
+        );
+        // A comment that merely mentions a key is left alone.
+        assert_eq!(line_key("# Set LATITUDE=… below", true), None);
+    }
+
+    #[test]
+    fn test_migration_file() {
+        assert_eq!(migration_file("birdnet.conf"), Some("birdnet.conf"));
+        assert_eq!(
+            migration_file("scripts/include_species_list.txt"),
+            Some("include_species_list.txt")
+        );
+        assert_eq!(migration_file("By_Date/2024-01-01/x.mp3"), None);
+    }
+}
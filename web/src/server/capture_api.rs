@@ -0,0 +1,43 @@
+//! Client for the capture node's HTTP API.
+//!
+//! The node is found at `CAPTURE_SERVER_URL` (default
+//! `http://localhost:8089`, fine with host networking) and requests are
+//! authenticated with `CAPTURE_AUTH_TOKEN` when set.
+
+use serde::de::DeserializeOwned;
+
+/// Base URL of the capture node, without a trailing slash.
+pub fn base_url() -> String {
+    std::env::var("CAPTURE_SERVER_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "http://localhost:8089".into())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// `GET` a JSON endpoint of the capture node, e.g. `/api/levels`.
+pub async fn get_json<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let base = base_url();
+    let url = format!("{base}{path}");
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("HTTP client error: {e}"))?;
+    let mut req = client.get(&url);
+    if let Some(token) = std::env::var("CAPTURE_AUTH_TOKEN").ok().filter(|t| !t.is_empty()) {
+        req = req.bearer_auth(token);
+    }
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| format!("Capture server unreachable at {base}: {e}"))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Capture server returned {status}: {body}"));
+    }
+    resp.json::<T>()
+        .await
+        .map_err(|e| format!("Bad response from {path}: {e}"))
+}
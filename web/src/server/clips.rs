@@ -0,0 +1,149 @@
+//! Fallback for `/extracted` clip URLs that point at a renamed directory
+//! or a stale file extension.
+//!
+//! Clip directories are named with [`safe_path_component`].  Clips written
+//! by older processing builds — and everything imported from BirdNET-Pi —
+//! live under the historical `common_name.replace('\'', "").replace(' ', "_")`
+//! name, which differs for names with punctuation or unusual whitespace.
+//! When `ServeDir` misses, look for a sibling directory in the same day
+//! whose sanitised name matches the requested one and serve from there.
+//!
+//! `File_Name` keeps the extension the clip had when it was recorded or
+//! imported, but the processing server's transcoding sweep later turns
+//! `.wav` / `.mp3` clips into `CLIP_FORMAT` (and renames the spectrogram
+//! and waveform peaks with them), so the other audio extensions are tried
+//! as well.
+
+use std::path::{Path, PathBuf};
+
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
+
+use crate::model::safe_path_component;
+
+/// Axum handler used as the `ServeDir` fallback for `/extracted`.
+pub async fn legacy_clip_fallback(extracted_dir: PathBuf, req: Request) -> Response {
+    let Some(path) = resolve_legacy_path(&extracted_dir, req.uri().path()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    tracing::debug!("Serving clip from fallback path: {}", path.display());
+    match ServeFile::new(path).oneshot(req).await {
+        Ok(resp) => resp.into_response(),
+        Err(never) => match never {},
+    }
+}
+
+/// Map `/By_Date/{date}/{safe_dir}/{file}` onto an existing file under
+/// `extracted_dir`, trying legacy directories and transcoded variants.
+pub(crate) fn resolve_legacy_path(extracted_dir: &Path, uri_path: &str) -> Option<PathBuf> {
+    let decoded = percent_decode(uri_path)?;
+    let parts: Vec<&str> = decoded.trim_start_matches('/').split('/').collect();
+    let [by_date, date, dir, file] = parts.as_slice() else {
+        return None;
+    };
+    if *by_date != "By_Date"
+        || [date, dir, file].iter().any(|p| p.is_empty() || **p == "." || **p == "..")
+    {
+        return None;
+    }
+
+    let day_dir = extracted_dir.join("By_Date").join(date);
+    let mut dirs = vec![day_dir.join(dir)];
+    dirs.extend(
+        std::fs::read_dir(&day_dir)
+            .ok()?
+            .flatten()
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .filter(|e| {
+                let name = e.file_name();
+                let name = name.to_string_lossy();
+                name != *dir && safe_path_component(&name) == *dir
+            })
+            .map(|e| e.path()),
+    );
+    let files = file_variants(file);
+    dirs.iter()
+        .flat_map(|d| files.iter().map(move |f| d.join(f)))
+        .find(|p| p.is_file())
+}
+
+/// The clip file to attach when exporting a detection: the kept WAV
+/// original if there is one, else MP3, else Opus.
+pub fn clip_for_upload(extracted_dir: &Path, date: &str, common_name: &str, file_name: &str) -> Option<PathBuf> {
+    let dir = safe_path_component(common_name);
+    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    ["wav", "mp3", "opus"].iter().find_map(|ext| {
+        resolve_legacy_path(extracted_dir, &format!("/By_Date/{date}/{dir}/{stem}.{ext}"))
+    })
+}
+
+/// Audio extensions a clip may have been transcoded between.
+const CLIP_EXTENSIONS: [&str; 3] = ["opus", "mp3", "wav"];
+
+/// Companion files named `<clip><suffix>`: the spectrogram and the
+/// waveform peaks.
+const SIDECAR_SUFFIXES: [&str; 2] = [".png", ".peaks.json"];
+
+/// `file` followed by the same clip (or one of its sidecars) under the
+/// other audio extensions.
+fn file_variants(file: &str) -> Vec<String> {
+    let (audio, suffix) = SIDECAR_SUFFIXES
+        .iter()
+        .find_map(|suffix| file.strip_suffix(suffix).map(|audio| (audio, *suffix)))
+        .unwrap_or((file, ""));
+    let mut out = vec![file.to_string()];
+    if let Some((stem, ext)) = audio.rsplit_once('.') {
+        if CLIP_EXTENSIONS.contains(&ext) {
+            out.extend(
+                CLIP_EXTENSIONS
+                    .iter()
+                    .filter(|e| **e != ext)
+                    .map(|e| format!("{stem}.{e}{suffix}")),
+            );
+        }
+    }
+    out
+}
+
+/// Minimal `%XX` decoder for URI paths (UTF-8 names arrive encoded).
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_variants_cover_transcoded_clips_and_spectrograms() {
+        assert_eq!(
+            file_variants("a-92-0715.mp3"),
+            ["a-92-0715.mp3", "a-92-0715.opus", "a-92-0715.wav"]
+        );
+        assert_eq!(
+            file_variants("a.wav.png"),
+            ["a.wav.png", "a.opus.png", "a.mp3.png"]
+        );
+        assert_eq!(
+            file_variants("a.wav.peaks.json"),
+            ["a.wav.peaks.json", "a.opus.peaks.json", "a.mp3.peaks.json"]
+        );
+        assert_eq!(file_variants("notes.txt"), ["notes.txt"]);
+    }
+}
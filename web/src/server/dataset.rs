@@ -0,0 +1,193 @@
+//! Training dataset export – packages reviewed detections (clip + label)
+//! as a tar archive for fine-tuning custom classifiers on station data.
+//!
+//! The archive holds the clips, either in one folder per class (the
+//! layout most audio training tools expect) or flat under `clips/`, plus
+//! a `manifest.csv` with one row per clip.  Archives are written to
+//! `{GAIA_DATA_DIR}/exports/` and served from `/exports/`.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::csv_field;
+use crate::model::{safe_path_component, DatasetExport, DetectionReview, ReviewVerdict, WebDetection};
+
+/// Header of `manifest.csv`.
+const MANIFEST_HEADER: &str =
+    "path,label,common_name,predicted_label,confidence,date,time,source_node,model,model_version,verification";
+
+/// Directory receiving dataset archives.
+pub fn exports_dir() -> PathBuf {
+    PathBuf::from(std::env::var("GAIA_DATA_DIR").unwrap_or_else(|_| "/data".into())).join("exports")
+}
+
+/// One clip selected for the dataset.
+#[derive(Debug)]
+struct DatasetItem {
+    det: WebDetection,
+    label: String,
+    verification: &'static str,
+}
+
+/// Pick the detections that go into the dataset and their labels.
+///
+/// Rejected clips are always left out.  With `verified_only`, a clip must
+/// be confirmed on the Review page or relabelled with the fine-tuning
+/// flag; a reviewer's correction overrides the stored species.
+fn select(
+    candidates: Vec<(WebDetection, bool)>,
+    reviews: &HashMap<String, DetectionReview>,
+    verified_only: bool,
+) -> Vec<DatasetItem> {
+    candidates
+        .into_iter()
+        .filter_map(|(det, fine_tune)| {
+            let review = reviews.get(&det.file_name);
+            let (label, verification) = match review {
+                Some(r) if r.verdict == ReviewVerdict::Rejected => return None,
+                Some(r) => (
+                    r.corrected_species.clone().unwrap_or_else(|| det.scientific_name.clone()),
+                    "reviewed",
+                ),
+                None if fine_tune => (det.scientific_name.clone(), "relabelled"),
+                None if verified_only => return None,
+                None => (det.scientific_name.clone(), "unverified"),
+            };
+            Some(DatasetItem { det, label, verification })
+        })
+        .collect()
+}
+
+/// Build the dataset archive and return where to download it.
+pub fn export(
+    extracted_dir: &Path,
+    candidates: Vec<(WebDetection, bool)>,
+    reviews: &HashMap<String, DetectionReview>,
+    verified_only: bool,
+    per_class_folders: bool,
+) -> Result<DatasetExport, String> {
+    let items = select(candidates, reviews, verified_only);
+    if items.is_empty() {
+        return Err("No detections match the filters".into());
+    }
+
+    let dir = exports_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Create {}: {e}", dir.display()))?;
+    let name = format!("gaia-dataset-{}.tar", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+    let tmp = dir.join(format!(".{name}.part"));
+    let file = std::fs::File::create(&tmp).map_err(|e| format!("Create {}: {e}", tmp.display()))?;
+    let mut tar = tar::Builder::new(std::io::BufWriter::new(file));
+
+    let mut manifest = String::from(MANIFEST_HEADER);
+    manifest.push('\n');
+    let mut classes = BTreeSet::new();
+    let (mut clips, mut missing) = (0u32, 0u32);
+    for item in &items {
+        let det = &item.det;
+        let clip_dir = det.original_common_name.as_deref().unwrap_or(&det.common_name);
+        let Some(clip) = super::clips::clip_for_upload(extracted_dir, &det.date, clip_dir, &det.file_name)
+        else {
+            missing += 1;
+            continue;
+        };
+        let file_name = clip.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let path = if per_class_folders {
+            format!("{}/{file_name}", safe_path_component(&item.label))
+        } else {
+            format!("clips/{file_name}")
+        };
+        tar.append_path_with_name(&clip, &path)
+            .map_err(|e| format!("Add {}: {e}", clip.display()))?;
+
+        let predicted = det.original_scientific_name.as_deref().unwrap_or(&det.scientific_name);
+        let fields = [
+            path.as_str(),
+            item.label.as_str(),
+            det.common_name.as_str(),
+            predicted,
+            &format!("{:.4}", det.confidence),
+            det.date.as_str(),
+            det.time.as_str(),
+            det.source_node.as_str(),
+            det.model_slug.as_str(),
+            det.model_version.as_str(),
+            item.verification,
+        ];
+        manifest.push_str(&fields.map(csv_field).join(","));
+        manifest.push('\n');
+        classes.insert(item.label.clone());
+        clips += 1;
+    }
+    if clips == 0 {
+        drop(tar);
+        std::fs::remove_file(&tmp).ok();
+        return Err(format!("None of the {missing} matching clips are on disk"));
+    }
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    tar.append_data(&mut header, "manifest.csv", manifest.as_bytes())
+        .map_err(|e| format!("Add manifest: {e}"))?;
+    tar.into_inner()
+        .and_then(|mut w| std::io::Write::flush(&mut w))
+        .map_err(|e| format!("Write archive: {e}"))?;
+    std::fs::rename(&tmp, dir.join(&name)).map_err(|e| format!("Rename archive: {e}"))?;
+    tracing::info!("Exported training dataset {name}: {clips} clips, {} classes", classes.len());
+
+    Ok(DatasetExport {
+        url: format!("/exports/{name}"),
+        clips,
+        classes: classes.len() as u32,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn det(file: &str, sci: &str) -> WebDetection {
+        serde_json::from_value(serde_json::json!({
+            "id": 1, "domain": "birds", "scientific_name": sci, "common_name": sci,
+            "confidence": 0.9, "date": "2026-05-01", "time": "05:00:00",
+            "file_name": file, "source_node": "",
+        }))
+        .unwrap()
+    }
+
+    fn review(verdict: ReviewVerdict, corrected: Option<&str>) -> DetectionReview {
+        DetectionReview {
+            verdict,
+            corrected_species: corrected.map(str::to_string),
+            reviewed_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        let candidates = || {
+            vec![
+                (det("a.opus", "Turdus grayi"), false),
+                (det("b.opus", "Turdus grayi"), false),
+                (det("c.opus", "Tyto alba"), false),
+                (det("d.opus", "Strix varia"), true),
+            ]
+        };
+        let reviews = HashMap::from([
+            ("a.opus".to_string(), review(ReviewVerdict::Confirmed, None)),
+            ("b.opus".to_string(), review(ReviewVerdict::Rejected, None)),
+            ("c.opus".to_string(), review(ReviewVerdict::Confirmed, Some("Megascops choliba"))),
+        ]);
+
+        let verified = select(candidates(), &reviews, true);
+        let labels: Vec<&str> = verified.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, ["Turdus grayi", "Megascops choliba", "Strix varia"]);
+        assert_eq!(verified[2].verification, "relabelled");
+
+        let all = select(candidates(), &HashMap::new(), false);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].verification, "unverified");
+    }
+}
@@ -7,10 +7,13 @@
 //! `display_date` / `display_time` on [`WebDetection`] for the UI.
 
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration as StdDuration;
 
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Duration, Utc};
 use libsql::params;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::info;
 
 use crate::model::{CalendarDay, DayDetectionGroup, ExcludedSpecies, QuizItem, SpeciesInfo, SpeciesSummary, TopRecording, UrbanNoiseSummary, WebDetection,
@@ -67,7 +70,7 @@ pub async fn get_db(db_path: &Path) -> Result<&'static libsql::Database, libsql:
 
 /// Read the `tz_offset` value (hours) from the settings table.
 /// Returns 0 (UTC) if unset or on any error.
-async fn read_tz_offset(conn: &libsql::Connection) -> i32 {
+async fn read_tz_offset(conn: &PooledConn) -> i32 {
     let mut rows = match conn.query("SELECT value FROM settings WHERE key = 'tz_offset'", ()).await {
         Ok(r) => r,
         Err(_) => return 0,
@@ -133,24 +136,205 @@ pub async fn today_for_tz_pub(db_path: &Path) -> Result<String, libsql::Error> {
     Ok(today_for_tz(tz))
 }
 
-/// Open a connection from the cached `Database` with a busy timeout.
+/// Open a new connection from the cached `Database` with a busy timeout
+/// and foreign keys enforced.
 ///
 /// WAL mode is set once at startup (`ensure_gaia_schema`), so we never
 /// touch that PRAGMA here — avoiding the write-lock that caused
 /// "database is locked" errors across containers.
-async fn open(db_path: &Path) -> Result<libsql::Connection, libsql::Error> {
+async fn connect(db_path: &Path) -> Result<libsql::Connection, libsql::Error> {
     let db = get_or_open_db(db_path).await?;
     let conn = db.connect()?;
-    conn.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS};")).await?;
+    conn.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS}; PRAGMA foreign_keys=ON;"))
+        .await?;
     Ok(conn)
 }
 
 /// Open a connection usable from outside this module (e.g. `species.rs`).
 ///
 /// Reads `TURSO_DATABASE_URL` to resolve the database, falling back to
-/// `db_path`.  Sets `busy_timeout` immediately.
+/// `db_path`.  Sets `busy_timeout` immediately.  Not pooled: meant for
+/// one-off jobs such as migrations.
 pub async fn open_conn(db_path: &Path) -> Result<libsql::Connection, libsql::Error> {
-    open(db_path).await
+    connect(db_path).await
+}
+
+// ─── Read connection pool ────────────────────────────────────────────────────
+//
+// Dashboard reads borrow a connection from a small pool instead of opening
+// one per server function, so concurrent page loads neither exhaust file
+// handles nor pay for a new connection each time.  Pooled connections are
+// read-only (`query_only`) and keep their prepared statements.  Writes go
+// through `open_rw`, which still opens a dedicated connection.
+
+/// Most read connections open at once; further readers wait.
+const POOL_SIZE: usize = 8;
+
+/// How long a reader waits for a free pooled connection.
+const ACQUIRE_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+
+/// How long a reader may keep a pooled connection.  A query still
+/// running then is interrupted, and the connection is closed instead of
+/// going back to the pool.
+const QUERY_TIMEOUT: StdDuration = StdDuration::from_secs(15);
+
+/// Prepared statements kept per connection.  Queries built from filters
+/// have many variants, so the cache is reset when it grows past this.
+const STATEMENT_CACHE_SIZE: usize = 32;
+
+/// An idle connection and the statements prepared on it.
+struct PoolEntry {
+    conn: libsql::Connection,
+    statements: HashMap<String, libsql::Statement>,
+}
+
+struct Pool {
+    idle: Mutex<Vec<PoolEntry>>,
+    permits: Arc<Semaphore>,
+}
+
+static POOL: OnceLock<Pool> = OnceLock::new();
+
+fn pool() -> &'static Pool {
+    POOL.get_or_init(|| Pool {
+        idle: Mutex::new(Vec::new()),
+        permits: Arc::new(Semaphore::new(POOL_SIZE)),
+    })
+}
+
+fn timeout_error(what: &str, after: StdDuration) -> libsql::Error {
+    libsql::Error::SqliteFailure(0, format!("Timed out after {}s {what}", after.as_secs()))
+}
+
+/// A read-only connection borrowed from the pool; returned on drop.
+///
+/// [`PooledConn::query`] reuses prepared statements.  Other
+/// `libsql::Connection` methods are reachable through `Deref`.  Reads
+/// still running after [`QUERY_TIMEOUT`] are interrupted.
+pub struct PooledConn {
+    conn: libsql::Connection,
+    statements: Mutex<HashMap<String, libsql::Statement>>,
+    /// Statements whose rows may still be read; back in the cache on drop.
+    in_use: Mutex<Vec<(String, libsql::Statement)>>,
+    /// Task that interrupts the connection once `timeout` has passed.
+    watchdog: tokio::task::AbortHandle,
+    timeout: StdDuration,
+    timed_out: Arc<AtomicBool>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledConn {
+    fn new(entry: PoolEntry, permit: OwnedSemaphorePermit, timeout: StdDuration) -> Self {
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let watchdog = {
+            let conn = entry.conn.clone();
+            let timed_out = timed_out.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                timed_out.store(true, Ordering::Relaxed);
+                if let Err(e) = conn.interrupt() {
+                    tracing::warn!("Cannot interrupt a timed-out read: {e}");
+                }
+            })
+            .abort_handle()
+        };
+        Self {
+            conn: entry.conn,
+            statements: Mutex::new(entry.statements),
+            in_use: Mutex::new(Vec::new()),
+            watchdog,
+            timeout,
+            timed_out,
+            _permit: permit,
+        }
+    }
+
+    /// `e`, or a timeout error if the watchdog interrupted the query.
+    fn timeout_or(&self, e: libsql::Error) -> libsql::Error {
+        if self.timed_out.load(Ordering::Relaxed) {
+            timeout_error("running query", self.timeout)
+        } else {
+            e
+        }
+    }
+
+    /// Run a read query with a cached prepared statement.
+    pub async fn query(
+        &self,
+        sql: &str,
+        params: impl libsql::params::IntoParams,
+    ) -> Result<libsql::Rows, libsql::Error> {
+        let cached = self.statements.lock().ok().and_then(|mut s| s.remove(sql));
+        let stmt = match cached {
+            Some(stmt) => {
+                stmt.reset();
+                stmt
+            }
+            None => self.conn.prepare(sql).await.map_err(|e| self.timeout_or(e))?,
+        };
+        let rows = stmt.query(params).await.map_err(|e| self.timeout_or(e))?;
+        if let Ok(mut in_use) = self.in_use.lock() {
+            in_use.push((sql.to_string(), stmt));
+        }
+        Ok(rows)
+    }
+}
+
+impl std::ops::Deref for PooledConn {
+    type Target = libsql::Connection;
+
+    fn deref(&self) -> &libsql::Connection {
+        &self.conn
+    }
+}
+
+impl Drop for PooledConn {
+    fn drop(&mut self) {
+        self.watchdog.abort();
+        if self.timed_out.load(Ordering::Relaxed) {
+            tracing::warn!(
+                "Closing a pooled database connection held past {}s",
+                self.timeout.as_secs()
+            );
+            return;
+        }
+        let mut statements =
+            std::mem::take(self.statements.get_mut().unwrap_or_else(|e| e.into_inner()));
+        let in_use = std::mem::take(self.in_use.get_mut().unwrap_or_else(|e| e.into_inner()));
+        statements.extend(in_use);
+        if statements.len() > STATEMENT_CACHE_SIZE {
+            statements.clear();
+        }
+        if let Ok(mut idle) = pool().idle.lock() {
+            idle.push(PoolEntry {
+                conn: self.conn.clone(),
+                statements,
+            });
+        }
+    }
+}
+
+/// Borrow a read-only connection from the pool, opening one if none is
+/// idle.
+async fn open(db_path: &Path) -> Result<PooledConn, libsql::Error> {
+    let pool = pool();
+    let permit = tokio::time::timeout(ACQUIRE_TIMEOUT, pool.permits.clone().acquire_owned())
+        .await
+        .map_err(|_| timeout_error("waiting for a database connection", ACQUIRE_TIMEOUT))?
+        .expect("pool semaphore is never closed");
+    let idle = pool.idle.lock().ok().and_then(|mut idle| idle.pop());
+    let entry = match idle {
+        Some(entry) => entry,
+        None => {
+            let conn = connect(db_path).await?;
+            conn.execute_batch("PRAGMA query_only = ON;").await?;
+            PoolEntry {
+                conn,
+                statements: HashMap::new(),
+            }
+        }
+    };
+    Ok(PooledConn::new(entry, permit, QUERY_TIMEOUT))
 }
 
 // ─── Detection queries (DEPRECATED – now served by detections_duckdb.rs) ─────
@@ -209,8 +393,20 @@ pub async fn recent_detections(
             model_slug: row.get::<String>(10)?,
             model_name: row.get::<String>(11)?,
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            original_scientific_name: None,
+            original_common_name: None,
+            rarity: None,
+            rare: false,
             display_date: String::new(),
             display_time: String::new(),
         });
@@ -291,8 +487,20 @@ pub async fn day_detections(
             model_slug: row.get::<String>(10)?,
             model_name: row.get::<String>(11)?,
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            original_scientific_name: None,
+            original_common_name: None,
+            rarity: None,
+            rare: false,
             display_date: String::new(),
             display_time: String::new(),
         });
@@ -357,6 +565,8 @@ pub async fn species_info(
             wikipedia_url: None,
             male_image_url: None,
             female_image_url: None,
+            image_attribution: None,
+            image_source: None,
             verification: None,
         })),
         None => Ok(None),
@@ -537,6 +747,7 @@ pub async fn top_species_for_date(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
         });
     }
     Ok(results)
@@ -587,7 +798,7 @@ pub async fn top_species(
 
 /// Read species from the cached `species_stats` table.
 async fn top_species_from_cache(
-    conn: &libsql::Connection,
+    conn: &PooledConn,
     limit: u32,
 ) -> Result<Vec<SpeciesSummary>, libsql::Error> {
     let mut rows = conn.query(
@@ -612,6 +823,7 @@ async fn top_species_from_cache(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
         });
     }
     Ok(results)
@@ -619,7 +831,7 @@ async fn top_species_from_cache(
 
 /// Live COUNT(*) query — used as fallback when the cache is empty.
 async fn top_species_live(
-    conn: &libsql::Connection,
+    conn: &PooledConn,
     limit: u32,
 ) -> Result<Vec<SpeciesSummary>, libsql::Error> {
     let mut rows = conn.query(
@@ -647,6 +859,7 @@ async fn top_species_live(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
         });
     }
     Ok(results)
@@ -806,7 +1019,7 @@ pub async fn get_top_recordings(
 }
 
 async fn get_top_recordings_cached(
-    conn: &libsql::Connection,
+    conn: &PooledConn,
     scientific_name: &str,
     limit: u32,
 ) -> Result<Vec<TopRecording>, libsql::Error> {
@@ -835,13 +1048,14 @@ async fn get_top_recordings_cached(
             file_name: row.get::<String>(5)?,
             source_node: row.get::<String>(6)?,
             model_name: row.get::<String>(7)?,
+            original_common_name: None,
         });
     }
     Ok(results)
 }
 
 async fn get_top_recordings_live(
-    conn: &libsql::Connection,
+    conn: &PooledConn,
     scientific_name: &str,
     limit: u32,
 ) -> Result<Vec<TopRecording>, libsql::Error> {
@@ -873,6 +1087,7 @@ async fn get_top_recordings_live(
             file_name: row.get::<String>(5)?,
             source_node: row.get::<String>(6)?,
             model_name: row.get::<String>(7)?,
+            original_common_name: None,
         });
     }
     Ok(results)
@@ -975,8 +1190,20 @@ pub async fn recent_detections_filtered(
             model_slug: row.get::<String>(10)?,
             model_name: row.get::<String>(11)?,
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            original_scientific_name: None,
+            original_common_name: None,
+            rarity: None,
+            rare: false,
             display_date: String::new(),
             display_time: String::new(),
         });
@@ -1023,6 +1250,7 @@ pub async fn top_species_filtered(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
         });
     }
     Ok(results)
@@ -1067,6 +1295,7 @@ pub async fn top_species_for_date_filtered(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
         });
     }
     Ok(results)
@@ -1111,8 +1340,20 @@ pub async fn day_detections_filtered(
             model_slug: row.get::<String>(10)?,
             model_name: row.get::<String>(11)?,
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            original_scientific_name: None,
+            original_common_name: None,
+            rarity: None,
+            rare: false,
             display_date: String::new(),
             display_time: String::new(),
         });
@@ -1203,8 +1444,20 @@ pub async fn species_detections_by_model(
             model_slug: row.get::<String>(10)?,
             model_name: row.get::<String>(11)?,
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            original_scientific_name: None,
+            original_common_name: None,
+            rarity: None,
+            rare: false,
             display_date: String::new(),
             display_time: String::new(),
         });
@@ -1221,11 +1474,13 @@ use std::collections::HashMap;
 /// Read all rows from the `settings` table as a key-value map.
 pub async fn get_all_settings(db_path: &Path) -> Result<HashMap<String, String>, libsql::Error> {
     let conn = open(db_path).await?;
-    // The table may not exist in older databases.
-    let _ = conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);"
-    ).await;
-    let mut rows = conn.query("SELECT key, value FROM settings", ()).await?;
+    let mut rows = match conn.query("SELECT key, value FROM settings", ()).await {
+        Ok(rows) => rows,
+        // The table may not exist in older databases (it is created by
+        // the first `save_settings`).
+        Err(e) if e.to_string().contains("no such table") => return Ok(HashMap::new()),
+        Err(e) => return Err(e),
+    };
     let mut map = HashMap::new();
     while let Some(row) = rows.next().await? {
         let k: String = row.get::<String>(0)?;
@@ -1237,16 +1492,13 @@ pub async fn get_all_settings(db_path: &Path) -> Result<HashMap<String, String>,
 
 /// Open a read-write connection from the cached `Database`.
 ///
-/// Only sets `busy_timeout` — WAL is configured once in
+/// Only sets `busy_timeout` and `foreign_keys` — WAL is configured once in
 /// `ensure_gaia_schema`.  Callers that modify data should wrap
 /// multi-statement writes in `BEGIN IMMEDIATE … COMMIT` so that the
 /// write lock is acquired upfront (respecting `busy_timeout` for
 /// cross-process contention).
 async fn open_rw(db_path: &Path) -> Result<libsql::Connection, libsql::Error> {
-    let db = get_or_open_db(db_path).await?;
-    let conn = db.connect()?;
-    conn.execute_batch(&format!("PRAGMA busy_timeout={BUSY_TIMEOUT_MS};")).await?;
-    Ok(conn)
+    connect(db_path).await
 }
 
 /// Save multiple settings in one transaction.
@@ -1374,8 +1626,20 @@ pub async fn excluded_detections_for_species(
             model_slug: row.get::<String>(10)?,
             model_name: row.get::<String>(11)?,
             model_beta: false,
+            model_version: String::new(),
+            model_variant: String::new(),
+            model_runtime: String::new(),
+            channel: None,
+            bearing_deg: None,
             agreement_score: 0.0,
             agreement_models: String::new(),
+            peak_freq_hz: None,
+            bandwidth_hz: None,
+            call_duration_secs: None,
+            original_scientific_name: None,
+            original_common_name: None,
+            rarity: None,
+            rare: false,
             display_date: String::new(),
             display_time: String::new(),
         });
@@ -1564,7 +1828,7 @@ pub async fn quiz_candidates(
         }
         seen_species.insert(sci_name.clone());
 
-        let safe_name = com_name.replace('\'', "").replace(' ', "_");
+        let safe_name = crate::model::safe_path_component(&com_name);
         let clip_url = format!("/extracted/By_Date/{date}/{safe_name}/{file_name}");
         let spectrogram_url = format!("{clip_url}.png");
 
@@ -1583,3 +1847,34 @@ pub async fn quiz_candidates(
 
     Ok(items)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_query_timeout_interrupts_long_read() {
+        let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+        let entry = PoolEntry {
+            conn: db.connect().unwrap(),
+            statements: HashMap::new(),
+        };
+        let permit = Arc::new(Semaphore::new(1)).acquire_owned().await.unwrap();
+        let conn = PooledConn::new(entry, permit, StdDuration::from_millis(200));
+
+        let started = std::time::Instant::now();
+        let endless = "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) \
+                       SELECT COUNT(*) FROM n";
+        let result = match conn.query(endless, ()).await {
+            Ok(mut rows) => rows.next().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        assert!(result.is_err(), "endless read must be cut off");
+        assert!(started.elapsed() < StdDuration::from_secs(10));
+        assert!(conn.timed_out.load(Ordering::Relaxed));
+
+        let idle = pool().idle.lock().unwrap().len();
+        drop(conn);
+        assert_eq!(pool().idle.lock().unwrap().len(), idle, "interrupted connection was pooled");
+    }
+}
@@ -0,0 +1,216 @@
+//! Filtered detection export (`GET /api/export/detections`) as CSV or
+//! Parquet, for analysis in R or Python.
+//!
+//! Query parameters, all optional: `format` (`csv`, the default, or
+//! `parquet`), `from` / `to` (inclusive `YYYY-MM-DD`), `species`
+//! (scientific name), `domain`, `min_confidence` and `favourites`
+//! (`true`: only the requesting user's starred detections, excluded or
+//! not).  DuckDB copies the
+//! matching rows to a temporary file, which is streamed back in chunks and
+//! unlinked as soon as it is open, so memory use doesn't grow with the
+//! number of rows.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::extract::Query;
+use axum::http::{header, HeaderMap, HeaderValue, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
+
+use super::detections_duckdb::{self as ddb, DetectionQuery};
+
+/// Query string of the export endpoint.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportParams {
+    pub format: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub species: Option<String>,
+    pub domain: Option<String>,
+    pub min_confidence: Option<f64>,
+    pub favourites: Option<bool>,
+}
+
+impl ExportParams {
+    /// Validated filter, whether the output is Parquet, and the download
+    /// file name.
+    fn resolve(&self) -> Result<(DetectionQuery, bool, String), String> {
+        let parquet = match non_empty(&self.format).as_deref() {
+            None | Some("csv") => false,
+            Some("parquet") => true,
+            Some(other) => return Err(format!("Unsupported format {other:?} (csv or parquet)")),
+        };
+        let date = |d: &Option<String>| -> Result<Option<String>, String> {
+            match non_empty(d) {
+                Some(d) => chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+                    .map(|_| Some(d.clone()))
+                    .map_err(|_| format!("Invalid date {d:?} (expected YYYY-MM-DD)")),
+                None => Ok(None),
+            }
+        };
+        let (from, to) = (date(&self.from)?, date(&self.to)?);
+        if let Some(c) = self.min_confidence {
+            if !(0.0..=1.0).contains(&c) {
+                return Err(format!("min_confidence {c} is outside 0..1"));
+            }
+        }
+
+        let range = match (&from, &to) {
+            (Some(f), Some(t)) => format!("_{f}_{t}"),
+            (Some(f), None) => format!("_from_{f}"),
+            (None, Some(t)) => format!("_to_{t}"),
+            (None, None) => String::new(),
+        };
+        let favourites = self.favourites.unwrap_or(false);
+        let kind = if favourites {
+            "favourites"
+        } else {
+            "detections"
+        };
+        let file_name = format!(
+            "gaia-{kind}{range}.{}",
+            if parquet { "parquet" } else { "csv" }
+        );
+        let query = DetectionQuery {
+            date_from: from,
+            date_to: to,
+            scientific_name: non_empty(&self.species),
+            domain: non_empty(&self.domain),
+            min_confidence: self.min_confidence,
+            // Starred on purpose, so kept even if the range model disagrees.
+            include_excluded: favourites,
+            // Filled in by the handler, which knows the user.
+            ids: favourites.then(Vec::new),
+            ..Default::default()
+        };
+        Ok((query, parquet, file_name))
+    }
+}
+
+fn non_empty(s: &Option<String>) -> Option<String> {
+    s.as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Axum handler for `/api/export/detections`.
+pub async fn detections(
+    db_path: PathBuf,
+    headers: HeaderMap,
+    Query(params): Query<ExportParams>,
+) -> Response {
+    let (mut query, parquet, file_name) = match params.resolve() {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    if query.ids.is_some() {
+        let user = super::audit::proxy_user(&headers).unwrap_or_default();
+        match super::kv::get_favourites(&user).await {
+            Ok(favourites) => query.ids = Some(favourites.into_keys().collect()),
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+        }
+    }
+
+    // Unique per export so concurrent downloads don't share a file.
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let tmp = std::env::temp_dir().join(format!(
+        "gaia-export-{}-{}.{}",
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed),
+        if parquet { "parquet" } else { "csv" }
+    ));
+    let rows = match ddb::export_detections(&db_path, &query, &tmp, parquet).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Export failed: {e}"),
+            )
+                .into_response();
+        }
+    };
+    tracing::info!("Exporting {rows} detection(s) as {file_name}");
+
+    // ServeFile opens the file before answering, so it can be unlinked
+    // right away; the open handle keeps the data until the body is sent.
+    let served = ServeFile::new(&tmp)
+        .oneshot(Request::new(axum::body::Body::empty()))
+        .await;
+    let _ = std::fs::remove_file(&tmp);
+    let mut resp = match served {
+        Ok(resp) => resp.map(axum::body::Body::new),
+        Err(e) => match e {},
+    };
+    let headers = resp.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(if parquet {
+            "application/vnd.apache.parquet"
+        } else {
+            "text/csv; charset=utf-8"
+        }),
+    );
+    if let Ok(v) = HeaderValue::from_str(&format!("attachment; filename=\"{file_name}\"")) {
+        headers.insert(header::CONTENT_DISPOSITION, v);
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_are_validated() {
+        let params = ExportParams {
+            format: Some("parquet".into()),
+            from: Some("2025-05-01".into()),
+            to: Some("2025-05-31".into()),
+            species: Some(" Turdus merula ".into()),
+            domain: Some(String::new()),
+            min_confidence: Some(0.7),
+            favourites: None,
+        };
+        let (query, parquet, file_name) = params.resolve().unwrap();
+        assert!(parquet);
+        assert_eq!(file_name, "gaia-detections_2025-05-01_2025-05-31.parquet");
+        assert_eq!(query.scientific_name.as_deref(), Some("Turdus merula"));
+        assert_eq!(query.domain, None);
+        assert_eq!(query.min_confidence, Some(0.7));
+        assert!(!query.include_excluded);
+
+        let (query, parquet, file_name) = ExportParams::default().resolve().unwrap();
+        assert!(!parquet);
+        assert_eq!(file_name, "gaia-detections.csv");
+        assert_eq!(query.ids, None);
+
+        let (query, _, file_name) = ExportParams {
+            favourites: Some(true),
+            ..Default::default()
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(file_name, "gaia-favourites.csv");
+        assert!(query.include_excluded);
+        assert_eq!(query.ids, Some(vec![]));
+
+        let bad = |p: ExportParams| p.resolve().is_err();
+        assert!(bad(ExportParams {
+            format: Some("xlsx".into()),
+            ..Default::default()
+        }));
+        assert!(bad(ExportParams {
+            from: Some("May 1".into()),
+            ..Default::default()
+        }));
+        assert!(bad(ExportParams {
+            min_confidence: Some(1.5),
+            ..Default::default()
+        }));
+    }
+}
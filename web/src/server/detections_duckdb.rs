@@ -25,13 +25,17 @@ use duckdb::params;
 use tracing::info;
 
 use crate::model::{
-    CacheSummaryStatus, CalendarDay, DayDetectionGroup, ExcludedSpecies, HourlyCount, ModelInfo,
-    QuizItem, SpeciesHourlyCounts, SpeciesInfo, SpeciesSummary, TopRecording,
-    WebDetection,
+    AccumulationPoint, CacheSummaryStatus, CalendarDay, DayDetectionGroup, DomainRichness,
+    ExcludedSpecies, FamilyCount, HourlyCount, ImportedSpecies, ModelInfo, MoonPhaseActivity,
+    NodeLocation, PeriodComparison, PresenceChange, QuizItem, RichnessStats, SeasonalOccurrence,
+    SpeciesComparison, SpeciesHourlyCounts, SpeciesInfo, SpeciesSummary, StationComparison,
+    StationSpecies, StationSummary, TopPrediction, TopRecording, WebDetection, WeekOccurrence,
+    WeeklyNewSpecies,
 };
 
 // Re-export AvailableModel used by model_filter component.
 pub use super::db::AvailableModel;
+use super::species_search::SpeciesLabel;
 
 // ─── Connection management ───────────────────────────────────────────────────
 
@@ -258,11 +262,37 @@ pub async fn stats_cache_status(_db_path: &Path) -> Res<CacheSummaryStatus> {
         species_rows: table_row_count(&duck, "species_stats"),
         excluded_rows: table_row_count(&duck, "excluded_species_stats"),
         model_species_rows: table_row_count(&duck, "model_species_stats"),
+        hourly_rows: table_row_count(&duck, "hourly_species_stats"),
         parquet_files: DET_DIR.get().map(|d| count_parquet_files(d)).unwrap_or(0),
         refreshed_at_utc,
     })
 }
 
+/// Columns added after the first Parquet files were written, with their
+/// DuckDB type.  Missing ones are NULL-filled in the `detections` view.
+const OPTIONAL_COLUMNS: &[(&str, &str)] = &[
+    ("Peak_Freq_Hz", "DOUBLE"),
+    ("Bandwidth_Hz", "DOUBLE"),
+    ("Call_Duration_S", "DOUBLE"),
+    ("Moon_Phase", "DOUBLE"),
+    ("Moon_Illumination", "DOUBLE"),
+    ("Day_Of_Year", "INTEGER"),
+    ("Season", "VARCHAR"),
+    ("Original_Sci_Name", "VARCHAR"),
+    ("Original_Com_Name", "VARCHAR"),
+    ("Relabeled_At", "VARCHAR"),
+    ("Fine_Tune", "INTEGER"),
+    ("Rarity", "DOUBLE"),
+    ("Rare", "INTEGER"),
+    ("Model_Version", "VARCHAR"),
+    ("Model_Variant", "VARCHAR"),
+    ("Model_Runtime", "VARCHAR"),
+    ("Top_Predictions", "VARCHAR"),
+    ("Channel", "INTEGER"),
+    ("Bearing", "DOUBLE"),
+    ("Source_Node_Id", "VARCHAR"),
+];
+
 fn refresh_view_inner(conn: &duckdb::Connection, dir: &Path) -> Result<(), duckdb::Error> {
     let readable_files = readable_parquet_files(conn, dir);
 
@@ -273,9 +303,29 @@ fn refresh_view_inner(conn: &duckdb::Connection, dir: &Path) -> Result<(), duckd
             .collect::<Vec<_>>()
             .join(", ");
         conn.execute_batch(&format!(
-            "CREATE OR REPLACE VIEW detections AS \
+            "CREATE OR REPLACE VIEW detections_raw AS \
              SELECT * FROM read_parquet([{files_sql}], union_by_name=true)"
         ))?;
+        // Files written before a column was introduced don't carry it at
+        // all; when *none* of the files have it, fill it with NULLs so the
+        // standard SELECTs keep working.
+        let present: std::collections::HashSet<String> = {
+            let mut stmt = conn.prepare(
+                "SELECT column_name FROM information_schema.columns \
+                 WHERE table_name = 'detections_raw'",
+            )?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+        let fill: String = OPTIONAL_COLUMNS
+            .iter()
+            .filter(|(name, _)| !present.contains(*name))
+            .map(|(name, ty)| format!(", NULL::{ty} AS {name}"))
+            .collect();
+        conn.execute_batch(&format!(
+            "CREATE OR REPLACE VIEW detections AS \
+             SELECT *{fill} FROM detections_raw"
+        ))?;
     } else {
         // Empty placeholder with the correct schema so queries don't fail.
         conn.execute_batch(
@@ -292,13 +342,73 @@ fn refresh_view_inner(conn: &duckdb::Connection, dir: &Path) -> Result<(), duckd
              ''::VARCHAR AS Model_Name, \
              0::INTEGER AS Model_Beta, \
              0.0::DOUBLE AS Agreement_Score, \
-             ''::VARCHAR AS Agreement_Models \
+             ''::VARCHAR AS Agreement_Models, \
+             NULL::DOUBLE AS Peak_Freq_Hz, \
+             NULL::DOUBLE AS Bandwidth_Hz, \
+             NULL::DOUBLE AS Call_Duration_S, \
+             NULL::DOUBLE AS Moon_Phase, \
+             NULL::DOUBLE AS Moon_Illumination, \
+             NULL::INTEGER AS Day_Of_Year, \
+             NULL::VARCHAR AS Season, \
+             NULL::VARCHAR AS Original_Sci_Name, \
+             NULL::VARCHAR AS Original_Com_Name, \
+             NULL::VARCHAR AS Relabeled_At, \
+             NULL::INTEGER AS Fine_Tune, \
+             NULL::DOUBLE AS Rarity, \
+             NULL::INTEGER AS Rare, \
+             NULL::VARCHAR AS Model_Version, \
+             NULL::VARCHAR AS Model_Variant, \
+             NULL::VARCHAR AS Model_Runtime, \
+             NULL::VARCHAR AS Top_Predictions, \
+             NULL::INTEGER AS Channel, \
+             NULL::DOUBLE AS Bearing, \
+             NULL::VARCHAR AS Source_Node_Id \
              WHERE false",
         )?;
     }
+    refresh_species_view(conn, dir);
     Ok(())
 }
 
+/// Create the `species` reference view over the files processing writes
+/// to `species/` next to the detections directory (one per model).
+///
+/// A species listed by several models appears once, preferring the row
+/// that carries taxonomy details.  Falls back to an empty view so joins
+/// keep working before processing has run.
+fn refresh_species_view(conn: &duckdb::Connection, detections_dir: &Path) {
+    let files = detections_dir
+        .parent()
+        .map(|p| parquet_files(&p.join("species")))
+        .unwrap_or_default();
+    if !files.is_empty() {
+        let files_sql = files
+            .iter()
+            .map(|path| format!("'{}'", escape_sql_path(path)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "CREATE OR REPLACE VIEW species AS \
+             SELECT DISTINCT ON (Sci_Name) * \
+             FROM read_parquet([{files_sql}], union_by_name=true) \
+             ORDER BY Sci_Name, Family IS NULL, Com_Name IS NULL, Model_Slug"
+        );
+        match conn.execute_batch(&sql) {
+            Ok(()) => return,
+            Err(e) => tracing::warn!("Cannot read species reference files: {e}"),
+        }
+    }
+    let _ = conn.execute_batch(
+        "CREATE OR REPLACE VIEW species AS SELECT \
+         ''::VARCHAR AS Sci_Name, NULL::VARCHAR AS Com_Name, \
+         '{}'::VARCHAR AS Com_Names, ''::VARCHAR AS Domain, \
+         NULL::VARCHAR AS Species_Code, NULL::BIGINT AS Taxon_Order, \
+         NULL::VARCHAR AS Order_Name, NULL::VARCHAR AS Family, \
+         NULL::VARCHAR AS Family_Com_Name, ''::VARCHAR AS Model_Slug \
+         WHERE false",
+    );
+}
+
 /// Get a lock on the DuckDB connection, refreshing the view first.
 fn conn() -> Result<std::sync::MutexGuard<'static, duckdb::Connection>, String> {
     let guard = DUCK
@@ -347,6 +457,18 @@ fn exclusion_clause(overrides: &[String]) -> String {
     }
 }
 
+/// `AND …` keeping rows of `domain` (`None` or empty = every domain).
+/// `col` may hold a comma-joined list, as the stats tables do.
+fn domain_filter(domain: Option<&str>, col: &str) -> String {
+    match domain {
+        Some(d) if !d.is_empty() => format!(
+            "AND list_contains(string_split(COALESCE({col}, ''), ','), {})",
+            sql_str(d)
+        ),
+        _ => String::new(),
+    }
+}
+
 /// Read the exclusion-override species list from Redis.
 pub async fn read_overrides(_db_path: &Path) -> Vec<String> {
     super::kv::read_overrides().await
@@ -388,7 +510,7 @@ fn stamp_recording(rec: &mut TopRecording, offset: i32) {
     rec.display_time = dt;
 }
 
-/// Parse a WebDetection from a DuckDB row (standard 12-column SELECT).
+/// Parse a WebDetection from a DuckDB row (standard 27-column SELECT).
 fn parse_detection(row: &duckdb::Row<'_>) -> Result<WebDetection, duckdb::Error> {
     Ok(WebDetection {
         id: row.get::<_, i64>(0)?,
@@ -407,6 +529,21 @@ fn parse_detection(row: &duckdb::Row<'_>) -> Result<WebDetection, duckdb::Error>
         model_beta: row.get::<_, i32>(12).unwrap_or(0) != 0,
         agreement_score: row.get::<_, f64>(13).unwrap_or(0.0),
         agreement_models: row.get::<_, String>(14).unwrap_or_default(),
+        peak_freq_hz: row.get::<_, Option<f64>>(15).unwrap_or(None),
+        bandwidth_hz: row.get::<_, Option<f64>>(16).unwrap_or(None),
+        call_duration_secs: row.get::<_, Option<f64>>(17).unwrap_or(None),
+        original_scientific_name: row.get::<_, Option<String>>(18).unwrap_or(None),
+        original_common_name: row.get::<_, Option<String>>(19).unwrap_or(None),
+        rarity: row.get::<_, Option<f64>>(20).unwrap_or(None),
+        rare: row.get::<_, i32>(21).unwrap_or(0) != 0,
+        model_version: row.get::<_, String>(22).unwrap_or_default(),
+        model_variant: row.get::<_, String>(23).unwrap_or_default(),
+        model_runtime: row.get::<_, String>(24).unwrap_or_default(),
+        channel: row
+            .get::<_, Option<i32>>(25)
+            .unwrap_or(None)
+            .and_then(|c| u16::try_from(c).ok()),
+        bearing_deg: row.get::<_, Option<f64>>(26).unwrap_or(None),
         display_date: String::new(),
         display_time: String::new(),
     })
@@ -428,12 +565,14 @@ fn round_count(n: u32) -> String {
 
 // ─── Detection queries ───────────────────────────────────────────────────────
 
-/// Recent detections, optionally filtered by model slug and after a cursor ID.
+/// Recent detections, optionally filtered by model slug and domain and
+/// after a cursor ID.
 pub async fn recent_detections_filtered(
     db_path: &Path,
     limit: u32,
     after_id: Option<i64>,
     model_slug: Option<&str>,
+    domain: Option<&str>,
 ) -> Res<Vec<WebDetection>> {
     let tz = read_tz_offset(db_path).await;
     let duck = conn()?;
@@ -446,15 +585,20 @@ pub async fn recent_detections_filtered(
         Some(rid) => format!("AND id > {rid}"),
         None => String::new(),
     };
+    let domain_filter = domain_filter(domain, "Domain");
 
     let sql = format!(
         "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
          COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
          COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
          COALESCE(Model_Beta, 0), \
-         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, '') \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
          FROM detections \
-         WHERE true {id_filter} {slug_filter} \
+         WHERE true {id_filter} {slug_filter} {domain_filter} \
          ORDER BY id DESC LIMIT {limit}"
     );
 
@@ -473,17 +617,19 @@ pub async fn recent_detections(
     limit: u32,
     after_id: Option<i64>,
 ) -> Res<Vec<WebDetection>> {
-    recent_detections_filtered(db_path, limit, after_id, None).await
+    recent_detections_filtered(db_path, limit, after_id, None, None).await
 }
 
-/// Calendar aggregates for a year/month.
+/// Calendar aggregates for a year/month, optionally for one domain.
 pub async fn calendar_data(
     db_path: &Path,
     year: i32,
     month: u32,
+    domain: Option<&str>,
 ) -> Res<Vec<CalendarDay>> {
     let overrides = read_overrides(db_path).await;
     let excl = exclusion_clause(&overrides);
+    let domain_filter = domain_filter(domain, "Domain");
     let start = format!("{year:04}-{month:02}-01");
     let end = if month == 12 {
         format!("{:04}-01-01", year + 1)
@@ -492,12 +638,21 @@ pub async fn calendar_data(
     };
 
     let duck = conn()?;
-    let sql = format!(
-        "SELECT Date, COUNT(*) AS cnt, COUNT(DISTINCT Sci_Name) AS spp \
-         FROM detections \
-         WHERE Date >= '{start}' AND Date < '{end}' AND {excl} \
-         GROUP BY Date ORDER BY Date"
-    );
+    let sql = if stats_populated() {
+        format!(
+            "SELECT Date, SUM(cnt)::BIGINT AS cnt, COUNT(DISTINCT Sci_Name) AS spp \
+             FROM hourly_species_stats \
+             WHERE Date >= '{start}' AND Date < '{end}' {domain_filter} \
+             GROUP BY Date ORDER BY Date"
+        )
+    } else {
+        format!(
+            "SELECT Date, COUNT(*) AS cnt, COUNT(DISTINCT Sci_Name) AS spp \
+             FROM detections \
+             WHERE Date >= '{start}' AND Date < '{end}' AND {excl} {domain_filter} \
+             GROUP BY Date ORDER BY Date"
+        )
+    };
     let mut stmt = duck.prepare(&sql)?;
     let rows = stmt.query_map([], |row| {
         Ok(CalendarDay {
@@ -514,14 +669,15 @@ pub async fn day_detections(
     db_path: &Path,
     date: &str,
 ) -> Res<Vec<DayDetectionGroup>> {
-    day_detections_filtered(db_path, date, None).await
+    day_detections_filtered(db_path, date, None, None).await
 }
 
-/// Day detections filtered by model slug.
+/// Day detections filtered by model slug and domain.
 pub async fn day_detections_filtered(
     db_path: &Path,
     date: &str,
     model_slug: Option<&str>,
+    domain: Option<&str>,
 ) -> Res<Vec<DayDetectionGroup>> {
     let tz = read_tz_offset(db_path).await;
     let duck = conn()?;
@@ -530,14 +686,19 @@ pub async fn day_detections_filtered(
         Some(s) if !s.is_empty() => format!("AND COALESCE(Model_Slug, '') = '{}'", s.replace('\'', "''")),
         _ => String::new(),
     };
+    let domain_filter = domain_filter(domain, "Domain");
     let safe_date = date.replace('\'', "''");
     let sql = format!(
         "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
          COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
          COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
          COALESCE(Model_Beta, 0), \
-         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, '') \
-         FROM detections WHERE Date = '{safe_date}' {slug_filter} \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
+         FROM detections WHERE Date = '{safe_date}' {slug_filter} {domain_filter} \
          ORDER BY Sci_Name, Time DESC"
     );
 
@@ -606,6 +767,8 @@ pub async fn species_info(db_path: &Path, scientific_name: &str) -> Res<Option<S
             last_seen: row.get(4)?,
             male_image_url: None,
             female_image_url: None,
+            image_attribution: None,
+            image_source: None,
             verification: None,
         })
     })?;
@@ -641,11 +804,18 @@ pub async fn species_hourly_histogram(
     let excl = exclusion_clause(&overrides);
     let safe = scientific_name.replace('\'', "''");
     let duck = conn()?;
-    let sql = format!(
-        "SELECT CAST(SUBSTR(Time, 1, 2) AS INTEGER) AS hour, COUNT(*) AS cnt \
-         FROM detections WHERE Sci_Name = '{safe}' AND {excl} \
-         GROUP BY hour ORDER BY hour"
-    );
+    let sql = if stats_populated() {
+        format!(
+            "SELECT Hour, SUM(cnt)::BIGINT FROM hourly_species_stats \
+             WHERE Sci_Name = '{safe}' GROUP BY Hour ORDER BY Hour"
+        )
+    } else {
+        format!(
+            "SELECT CAST(SUBSTR(Time, 1, 2) AS INTEGER) AS hour, COUNT(*) AS cnt \
+             FROM detections WHERE Sci_Name = '{safe}' AND {excl} \
+             GROUP BY hour ORDER BY hour"
+        )
+    };
     let mut stmt = duck.prepare(&sql)?;
     let rows = stmt.query_map([], |row| {
         Ok(HourlyCount {
@@ -665,6 +835,9 @@ pub async fn daily_species_hourly(
     let excl = exclusion_clause(&overrides);
     let safe_date = date.replace('\'', "''");
     let duck = conn()?;
+    if stats_populated() {
+        return daily_species_hourly_cached(&duck, &safe_date);
+    }
 
     // 1) Species list for the day
     let list_sql = format!(
@@ -708,12 +881,51 @@ pub async fn daily_species_hourly(
     Ok(results)
 }
 
-/// Top species for a specific date, optionally filtered by model.
+/// [`daily_species_hourly`] from `hourly_species_stats`, in one query.
+fn daily_species_hourly_cached(
+    duck: &duckdb::Connection,
+    safe_date: &str,
+) -> Res<Vec<SpeciesHourlyCounts>> {
+    let sql = format!(
+        "SELECT Sci_Name, MAX(Com_Name), Hour, SUM(cnt)::BIGINT \
+         FROM hourly_species_stats WHERE Date = '{safe_date}' \
+         GROUP BY Sci_Name, Hour ORDER BY Sci_Name, Hour"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, u32>(2)?,
+            row.get::<_, u32>(3)?,
+        ))
+    })?;
+    let mut results: Vec<SpeciesHourlyCounts> = Vec::new();
+    for (sci, com, hour, count) in rows.filter_map(|r| r.ok()) {
+        match results.last_mut() {
+            Some(sp) if sp.scientific_name == sci => {
+                sp.total += count;
+                sp.hours.push(HourlyCount { hour, count });
+            }
+            _ => results.push(SpeciesHourlyCounts {
+                scientific_name: sci,
+                common_name: com,
+                total: count,
+                hours: vec![HourlyCount { hour, count }],
+            }),
+        }
+    }
+    results.sort_by(|a, b| b.total.cmp(&a.total));
+    Ok(results)
+}
+
+/// Top species for a specific date, optionally filtered by model and domain.
 pub async fn top_species_for_date_filtered(
     db_path: &Path,
     date: &str,
     limit: u32,
     model_slug: Option<&str>,
+    domain: Option<&str>,
 ) -> Res<Vec<SpeciesSummary>> {
     let overrides = read_overrides(db_path).await;
     let excl = exclusion_clause(&overrides);
@@ -722,19 +934,32 @@ pub async fn top_species_for_date_filtered(
         Some(s) if !s.is_empty() => format!("AND COALESCE(d.Model_Slug, '') = '{}'", s.replace('\'', "''")),
         _ => String::new(),
     };
+    let domain_filter = domain_filter(domain, "d.Domain");
     let duck = conn()?;
-    let sql = format!(
-        "SELECT d.Sci_Name, \
-         COALESCE( \
-             MAX(CASE WHEN d.Com_Name != d.Sci_Name THEN d.Com_Name ELSE NULL END), \
-             MAX(d.Com_Name) \
-         ) AS Com_Name, \
-         string_agg(DISTINCT d.Domain, ',') AS Domain, COUNT(*) AS cnt, \
-         MAX(d.Date || ' ' || d.Time) AS last \
-         FROM detections d \
-         WHERE d.Date = '{safe_date}' AND {excl} {slug_filter} \
-         GROUP BY d.Sci_Name ORDER BY cnt DESC LIMIT {limit}"
-    );
+    let sql = if stats_populated() {
+        format!(
+            "SELECT d.Sci_Name, COALESCE(MAX(s.Com_Name), MAX(d.Com_Name)) AS Com_Name, \
+             string_agg(DISTINCT d.Domain, ',') AS Domain, SUM(d.cnt)::BIGINT AS cnt, \
+             MAX(d.last_seen) AS last \
+             FROM hourly_species_stats d LEFT JOIN species s ON s.Sci_Name = d.Sci_Name \
+             WHERE d.Date = '{safe_date}' {slug_filter} {domain_filter} \
+             GROUP BY d.Sci_Name ORDER BY cnt DESC LIMIT {limit}"
+        )
+    } else {
+        format!(
+            "SELECT d.Sci_Name, \
+             COALESCE( \
+                 MAX(s.Com_Name), \
+                 MAX(CASE WHEN d.Com_Name != d.Sci_Name THEN d.Com_Name ELSE NULL END), \
+                 MAX(d.Com_Name) \
+             ) AS Com_Name, \
+             string_agg(DISTINCT d.Domain, ',') AS Domain, COUNT(*) AS cnt, \
+             MAX(d.Date || ' ' || d.Time) AS last \
+             FROM detections d LEFT JOIN species s ON s.Sci_Name = d.Sci_Name \
+             WHERE d.Date = '{safe_date}' AND {excl} {slug_filter} {domain_filter} \
+             GROUP BY d.Sci_Name ORDER BY cnt DESC LIMIT {limit}"
+        )
+    };
     let mut stmt = duck.prepare(&sql)?;
     let rows = stmt.query_map([], |row| {
         let count: u32 = row.get(3)?;
@@ -750,6 +975,291 @@ pub async fn top_species_for_date_filtered(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Detections per taxonomic family for a date, optionally filtered by
+/// model and domain.  Species without family information are grouped
+/// under `family = None`.
+pub async fn family_counts_for_date(
+    db_path: &Path,
+    date: &str,
+    model_slug: Option<&str>,
+    domain: Option<&str>,
+) -> Res<Vec<FamilyCount>> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let safe_date = date.replace('\'', "''");
+    let slug_filter = match model_slug {
+        Some(s) if !s.is_empty() => format!("AND COALESCE(d.Model_Slug, '') = '{}'", s.replace('\'', "''")),
+        _ => String::new(),
+    };
+    let domain_filter = domain_filter(domain, "d.Domain");
+    let duck = conn()?;
+    let (source, count, excl) = if stats_populated() {
+        ("hourly_species_stats", "SUM(d.cnt)::BIGINT", "TRUE".to_string())
+    } else {
+        ("detections", "COUNT(*)", excl)
+    };
+    let sql = format!(
+        "SELECT s.Family, MAX(s.Family_Com_Name), MAX(s.Order_Name), \
+         COUNT(DISTINCT d.Sci_Name) AS species, {count} AS cnt \
+         FROM {source} d LEFT JOIN species s ON s.Sci_Name = d.Sci_Name \
+         WHERE d.Date = '{safe_date}' AND {excl} {slug_filter} {domain_filter} \
+         GROUP BY s.Family ORDER BY s.Family IS NULL, cnt DESC"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(FamilyCount {
+            family: row.get(0)?,
+            family_common_name: row.get(1)?,
+            order: row.get(2)?,
+            species_count: row.get(3)?,
+            detection_count: row.get(4)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Detections and active nights per named moon phase (new moon first),
+/// optionally filtered by model and domain.
+///
+/// Rows written before the moon-phase annotation existed get their phase
+/// from the stored date and time, with the same mean-cycle formula as
+/// `gaia_common::astro::moon_phase`.
+pub async fn moon_phase_activity(
+    db_path: &Path,
+    model_slug: Option<&str>,
+    domain: Option<&str>,
+) -> Res<Vec<MoonPhaseActivity>> {
+    use gaia_common::astro::{PHASE_NAMES, SYNODIC_MONTH_DAYS};
+
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let slug_filter = match model_slug {
+        Some(s) if !s.is_empty() => format!("AND COALESCE(Model_Slug, '') = '{}'", s.replace('\'', "''")),
+        _ => String::new(),
+    };
+    let domain_filter = domain_filter(domain, "Domain");
+    let duck = conn()?;
+    let sql = format!(
+        "WITH p AS ( \
+            SELECT CAST(Date AS VARCHAR) AS day, COALESCE(Moon_Phase, \
+                ((epoch(try_strptime(CAST(Date AS VARCHAR) || ' ' || Time, '%Y-%m-%d %H:%M:%S')) \
+                  - 947182440) / 86400.0 / {SYNODIC_MONTH_DAYS} % 1 + 1) % 1) AS phase \
+            FROM detections WHERE {excl} {slug_filter} {domain_filter} \
+         ) \
+         SELECT CAST(round(phase * 8) AS INTEGER) % 8 AS bin, COUNT(*), COUNT(DISTINCT day) \
+         FROM p WHERE phase IS NOT NULL GROUP BY bin"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, u64>(1)?, row.get::<_, u64>(2)?))
+    })?;
+    let mut out: Vec<MoonPhaseActivity> = PHASE_NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| MoonPhaseActivity {
+            phase: i as u8,
+            label: name.to_string(),
+            detections: 0,
+            nights: 0,
+        })
+        .collect();
+    for (bin, detections, nights) in rows.filter_map(|r| r.ok()) {
+        if let Some(slot) = out.get_mut(bin.rem_euclid(8) as usize) {
+            slot.detections = detections;
+            slot.nights = nights;
+        }
+    }
+    Ok(out)
+}
+
+/// Week-of-year histogram of a species over all years, with the expected
+/// occurrence processing computed from the metadata model (see
+/// [`expected_occurrence`]).
+pub async fn seasonal_occurrence(db_path: &Path, scientific_name: &str) -> Res<SeasonalOccurrence> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let safe = scientific_name.replace('\'', "''");
+    let duck = conn()?;
+
+    let days = format!(
+        "SELECT try_cast(Date AS DATE) AS day FROM detections \
+         WHERE Sci_Name = '{safe}' AND {excl}"
+    );
+    let mut stmt = duck.prepare(&format!(
+        "SELECT weekofyear(day) AS week, COUNT(*), COUNT(DISTINCT day) \
+         FROM ({days}) WHERE day IS NOT NULL GROUP BY week"
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, u64>(1)?, row.get::<_, u64>(2)?))
+    })?;
+    let mut weeks: Vec<WeekOccurrence> = (1..=53)
+        .map(|week| WeekOccurrence { week, detections: 0, days: 0, expected: None })
+        .collect();
+    for (week, detections, days) in rows.filter_map(|r| r.ok()) {
+        if let Some(slot) = weeks.get_mut((week - 1).clamp(0, 52) as usize) {
+            slot.detections = detections;
+            slot.days = days;
+        }
+    }
+
+    let mut stmt = duck.prepare(&format!(
+        "SELECT DISTINCT year(day) AS y FROM ({days}) WHERE day IS NOT NULL ORDER BY y"
+    ))?;
+    let years: Vec<i32> = stmt
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .filter_map(|r| r.ok())
+        .map(|y| y as i32)
+        .collect();
+
+    let common_name: Option<String> = duck
+        .query_row(
+            &format!(
+                "SELECT COALESCE(MAX(CASE WHEN Com_Name != Sci_Name THEN Com_Name END), MAX(Com_Name)) \
+                 FROM detections WHERE Sci_Name = '{safe}'"
+            ),
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(None);
+
+    let expected = match DET_DIR.get().and_then(|d| d.parent()) {
+        Some(data_dir) => expected_occurrence(&duck, &data_dir.join("species_occurrence"), scientific_name)?,
+        None => ExpectedOccurrence::default(),
+    };
+    for (week, score) in &expected.scores {
+        if let Some(slot) = weeks.get_mut(*week as usize - 1) {
+            slot.expected = Some(*score);
+        }
+    }
+    // Weeks without a row were below processing's cut-off.
+    if !expected.scores.is_empty() {
+        for slot in &mut weeks {
+            slot.expected.get_or_insert(0.0);
+        }
+    }
+
+    Ok(SeasonalOccurrence {
+        scientific_name: scientific_name.to_string(),
+        common_name: common_name.unwrap_or_else(|| scientific_name.to_string()),
+        weeks,
+        years,
+        expected_location: expected.location,
+        expected_model: expected.model,
+        filter_threshold: 0.0,
+    })
+}
+
+/// Metadata-model scores for one species, read from the
+/// `species_occurrence/<slug>.parquet` files processing writes at startup.
+#[derive(Debug, Default, PartialEq)]
+struct ExpectedOccurrence {
+    location: Option<(f64, f64)>,
+    model: Option<String>,
+    /// `(ISO week, score)`; empty when no model expects the species in
+    /// any week.
+    scores: Vec<(u32, f64)>,
+}
+
+/// Read the expected occurrence of `scientific_name` from `dir`.  When
+/// several models have a metadata model, the highest score per week wins.
+fn expected_occurrence(
+    duck: &duckdb::Connection,
+    dir: &Path,
+    scientific_name: &str,
+) -> Res<ExpectedOccurrence> {
+    let files = parquet_files(dir);
+    if files.is_empty() {
+        return Ok(ExpectedOccurrence::default());
+    }
+    let files_sql = files
+        .iter()
+        .map(|path| format!("'{}'", escape_sql_path(path)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let source = format!("read_parquet([{files_sql}], union_by_name=true)");
+
+    let first = duck.query_row(
+        &format!("SELECT Latitude, Longitude, Model_Slug FROM {source} LIMIT 1"),
+        [],
+        |row| Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?)),
+    );
+    let (lat, lon, any_model) = match first {
+        Ok(first) => first,
+        Err(duckdb::Error::QueryReturnedNoRows) => return Ok(ExpectedOccurrence::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let safe = scientific_name.replace('\'', "''");
+    let mut stmt = duck.prepare(&format!(
+        "SELECT Week, MAX(Score), arg_max(Model_Slug, Score) FROM {source} \
+         WHERE Sci_Name = '{safe}' GROUP BY Week ORDER BY Week"
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, u32>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?))
+    })?;
+    let mut scores = Vec::new();
+    let mut model = None;
+    for (week, score, slug) in rows.filter_map(|r| r.ok()) {
+        if (1..=53).contains(&week) {
+            scores.push((week, score));
+            model.get_or_insert(slug);
+        }
+    }
+    Ok(ExpectedOccurrence {
+        location: Some((lat, lon)),
+        model: model.or(Some(any_model)),
+        scores,
+    })
+}
+
+/// Species per checklist period of `date` for the eBird export, ordered
+/// by period.  See [`super::ebird`] for how counts are derived.
+pub async fn ebird_entries(
+    db_path: &Path,
+    date: &str,
+    period_minutes: u32,
+    model_slug: Option<&str>,
+) -> Res<Vec<super::ebird::EbirdEntry>> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let safe_date = date.replace('\'', "''");
+    let period = period_minutes.clamp(1, 24 * 60);
+    let slug_filter = match model_slug {
+        Some(s) if !s.is_empty() => format!("AND COALESCE(Model_Slug, '') = '{}'", s.replace('\'', "''")),
+        _ => String::new(),
+    };
+    let duck = conn()?;
+    let sql = format!(
+        "WITH m AS ( \
+            SELECT CAST(floor((CAST(substr(Time, 1, 2) AS INTEGER) * 60 \
+                   + CAST(substr(Time, 4, 2) AS INTEGER)) / {period}) AS INTEGER) AS period, \
+                   substr(Time, 1, 5) AS minute, Sci_Name, Com_Name, Confidence, \
+                   COALESCE(Source_Node, '') AS node, Lat, Lon \
+            FROM detections WHERE Date = '{safe_date}' AND {excl} {slug_filter} \
+         ), per_minute AS ( \
+            SELECT period, Sci_Name, minute, COUNT(DISTINCT node) AS nodes, COUNT(*) AS cnt, \
+                   MAX(Com_Name) AS com, MAX(Confidence) AS conf, AVG(Lat) AS lat, AVG(Lon) AS lon \
+            FROM m GROUP BY period, Sci_Name, minute \
+         ) \
+         SELECT period, Sci_Name, MAX(com), MAX(nodes), SUM(cnt)::BIGINT, MAX(conf), AVG(lat), AVG(lon) \
+         FROM per_minute GROUP BY period, Sci_Name ORDER BY period, Sci_Name"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(super::ebird::EbirdEntry {
+            period: row.get::<_, i32>(0)?.max(0) as u32,
+            scientific_name: row.get(1)?,
+            common_name: row.get(2)?,
+            count: row.get::<_, i64>(3)? as u32,
+            detections: row.get::<_, i64>(4)? as u32,
+            max_confidence: row.get(5)?,
+            latitude: row.get::<_, Option<f64>>(6)?.unwrap_or(0.0),
+            longitude: row.get::<_, Option<f64>>(7)?.unwrap_or(0.0),
         })
     })?;
     Ok(rows.filter_map(|r| r.ok()).collect())
@@ -761,20 +1271,81 @@ pub async fn top_species_for_date(
     date: &str,
     limit: u32,
 ) -> Res<Vec<SpeciesSummary>> {
-    top_species_for_date_filtered(db_path, date, limit, None).await
+    top_species_for_date_filtered(db_path, date, limit, None, None).await
+}
+
+/// Per-day detection counts for `species` over the `days` days ending on
+/// `end_date` (inclusive), oldest first and zero-filled, for sparklines.
+pub async fn species_daily_counts(
+    db_path: &Path,
+    species: &[String],
+    end_date: &str,
+    days: u32,
+    model_slug: Option<&str>,
+) -> Res<std::collections::HashMap<String, Vec<u32>>> {
+    let Ok(end) = chrono::NaiveDate::parse_from_str(end_date, "%Y-%m-%d") else {
+        return Err(format!("Invalid date: {end_date}").into());
+    };
+    if species.is_empty() || days == 0 {
+        return Ok(std::collections::HashMap::new());
+    }
+    let start = end - chrono::Duration::days(i64::from(days) - 1);
+
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let names = species
+        .iter()
+        .map(|n| format!("'{}'", n.replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let slug_filter = match model_slug {
+        Some(s) if !s.is_empty() => format!("AND COALESCE(d.Model_Slug, '') = '{}'", s.replace('\'', "''")),
+        _ => String::new(),
+    };
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT d.Sci_Name, CAST(d.Date AS VARCHAR) AS day, COUNT(*) AS cnt \
+         FROM detections d \
+         WHERE d.Date BETWEEN '{start}' AND '{end}' AND d.Sci_Name IN ({names}) \
+         AND {excl} {slug_filter} \
+         GROUP BY d.Sci_Name, day"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, u32>(2)?))
+    })?;
+
+    let mut out: std::collections::HashMap<String, Vec<u32>> = species
+        .iter()
+        .map(|n| (n.clone(), vec![0; days as usize]))
+        .collect();
+    for (name, day, cnt) in rows.filter_map(|r| r.ok()) {
+        let Ok(date) = chrono::NaiveDate::parse_from_str(&day, "%Y-%m-%d") else {
+            continue;
+        };
+        let idx = (date - start).num_days();
+        if let (Some(series), Ok(idx)) = (out.get_mut(&name), usize::try_from(idx)) {
+            if let Some(slot) = series.get_mut(idx) {
+                *slot = cnt;
+            }
+        }
+    }
+    Ok(out)
 }
 
-/// Top species (all-time), optionally filtered by model slug.
+/// Top species (all-time), optionally filtered by model slug and domain.
 pub async fn top_species_filtered(
     db_path: &Path,
     limit: u32,
     model_slug: Option<&str>,
+    domain: Option<&str>,
 ) -> Res<Vec<SpeciesSummary>> {
     if !STATS_POPULATED.load(std::sync::atomic::Ordering::Relaxed) {
         refresh_species_stats(db_path).await?;
     }
 
     let has_model_filter = matches!(model_slug, Some(s) if !s.is_empty());
+    let domain_filter = domain_filter(domain, "Domain");
 
     // ── Fast path: read from in-memory cache tables ──────────────────
     if STATS_POPULATED.load(std::sync::atomic::Ordering::Relaxed) {
@@ -784,7 +1355,7 @@ pub async fn top_species_filtered(
             let sql = format!(
                 "SELECT Sci_Name, Com_Name, Domain, detection_count, last_seen \
                  FROM model_species_stats \
-                 WHERE Model_Slug = '{safe}' \
+                 WHERE Model_Slug = '{safe}' {domain_filter} \
                  ORDER BY detection_count DESC LIMIT {limit}"
             );
             return read_species_summaries(&duck, &sql);
@@ -792,7 +1363,8 @@ pub async fn top_species_filtered(
         let duck = conn()?;
         let sql = format!(
             "SELECT Sci_Name, Com_Name, Domain, detection_count, last_seen \
-             FROM species_stats ORDER BY detection_count DESC LIMIT {limit}"
+             FROM species_stats WHERE true {domain_filter} \
+             ORDER BY detection_count DESC LIMIT {limit}"
         );
         return read_species_summaries(&duck, &sql);
     }
@@ -821,6 +1393,7 @@ fn read_species_summaries(
             male_image_url: None,
             female_image_url: None,
             verification: None,
+            daily_counts: Vec::new(),
         })
     })?;
     Ok(rows.filter_map(|r| r.ok()).collect())
@@ -828,47 +1401,272 @@ fn read_species_summaries(
 
 /// Top species (all-time, unfiltered).
 pub async fn top_species(db_path: &Path, limit: u32) -> Res<Vec<SpeciesSummary>> {
-    top_species_filtered(db_path, limit, None).await
+    top_species_filtered(db_path, limit, None, None).await
 }
 
-/// Top recordings for a species (by confidence).
-pub async fn get_top_recordings(
+/// Resolve the two date ranges for the compare page.
+///
+/// Empty strings fall back to defaults: the current period is the last
+/// seven days ending `today`, and the baseline is the window of equal
+/// length immediately before the current period.  Rejects malformed dates
+/// and inverted ranges.
+pub fn resolve_compare_ranges(
+    today: chrono::NaiveDate,
+    baseline_from: &str,
+    baseline_to: &str,
+    current_from: &str,
+    current_to: &str,
+) -> Result<[chrono::NaiveDate; 4], String> {
+    use chrono::{Duration, NaiveDate};
+    let parse = |s: &str| -> Result<Option<NaiveDate>, String> {
+        if s.trim().is_empty() {
+            return Ok(None);
+        }
+        NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| format!("Invalid date {s:?} (expected YYYY-MM-DD)"))
+    };
+
+    let cur_to = parse(current_to)?.unwrap_or(today);
+    let cur_from = parse(current_from)?.unwrap_or(cur_to - Duration::days(6));
+    if cur_from > cur_to {
+        return Err(format!("Current period starts after it ends ({cur_from} > {cur_to})"));
+    }
+    let span = cur_to - cur_from;
+    let base_to = parse(baseline_to)?.unwrap_or(cur_from - Duration::days(1));
+    let base_from = parse(baseline_from)?.unwrap_or(base_to - span);
+    if base_from > base_to {
+        return Err(format!("Baseline period starts after it ends ({base_from} > {base_to})"));
+    }
+    Ok([base_from, base_to, cur_from, cur_to])
+}
+
+/// Per-species detection counts for a baseline and a current date range,
+/// optionally for one domain.
+///
+/// Dates must already be validated (see [`resolve_compare_ranges`]).
+pub async fn compare_periods(
     db_path: &Path,
-    scientific_name: &str,
-    limit: u32,
-) -> Res<Vec<TopRecording>> {
-    let tz = read_tz_offset(db_path).await;
-    let safe = scientific_name.replace('\'', "''");
+    baseline: (&str, &str),
+    current: (&str, &str),
+    domain: Option<&str>,
+) -> Res<PeriodComparison> {
+    let overrides = read_overrides(db_path).await;
+    let excl = format!(
+        "{} {}",
+        exclusion_clause(&overrides),
+        domain_filter(domain, "Domain")
+    );
     let duck = conn()?;
+    let species = compare_periods_inner(&duck, &excl, baseline, current)?;
+    Ok(PeriodComparison {
+        baseline_from: baseline.0.to_string(),
+        baseline_to: baseline.1.to_string(),
+        current_from: current.0.to_string(),
+        current_to: current.1.to_string(),
+        species,
+    })
+}
+
+fn compare_periods_inner(
+    duck: &duckdb::Connection,
+    excl: &str,
+    (base_from, base_to): (&str, &str),
+    (cur_from, cur_to): (&str, &str),
+) -> Res<Vec<SpeciesComparison>> {
+    let in_base = format!("(Date >= '{base_from}' AND Date <= '{base_to}')");
+    let in_cur = format!("(Date >= '{cur_from}' AND Date <= '{cur_to}')");
     let sql = format!(
-        "SELECT Sci_Name, Com_Name, Date, Time, Confidence, File_Name, \
-         COALESCE(Source_Node, ''), COALESCE(Model_Name, '') \
+        "SELECT Sci_Name, \
+         COALESCE( \
+             MAX(CASE WHEN Com_Name != Sci_Name THEN Com_Name ELSE NULL END), \
+             MAX(Com_Name) \
+         ) AS Com_Name, \
+         string_agg(DISTINCT Domain, ',') AS Domain, \
+         COUNT(*) FILTER (WHERE {in_base}) AS base_cnt, \
+         COUNT(*) FILTER (WHERE {in_cur}) AS cur_cnt \
          FROM detections \
-         WHERE Sci_Name = '{safe}' AND COALESCE(Excluded, 0) = 0 \
-           AND File_Name != '' AND Confidence >= 0.5 \
-         ORDER BY Confidence DESC, Date DESC, Time DESC \
-         LIMIT {limit}"
+         WHERE ({in_base} OR {in_cur}) AND {excl} \
+         GROUP BY Sci_Name \
+         ORDER BY cur_cnt DESC, base_cnt DESC"
     );
     let mut stmt = duck.prepare(&sql)?;
     let rows = stmt.query_map([], |row| {
-        Ok(TopRecording {
+        let baseline_count: u32 = row.get(3)?;
+        let current_count: u32 = row.get(4)?;
+        Ok(SpeciesComparison {
             scientific_name: row.get(0)?,
             common_name: row.get(1)?,
-            date: row.get(2)?,
-            time: row.get(3)?,
-            confidence: row.get(4)?,
-            file_name: row.get(5)?,
-            source_node: row.get(6)?,
-            model_name: row.get(7)?,
-            display_date: String::new(),
-            display_time: String::new(),
+            domain: row.get(2)?,
+            baseline_count,
+            current_count,
+            change: PresenceChange::from_counts(baseline_count, current_count),
         })
     })?;
-    let mut recs: Vec<TopRecording> = rows.filter_map(|r| r.ok()).collect();
-    for r in &mut recs {
-        stamp_recording(r, tz);
-    }
-    Ok(recs)
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Species accumulation curve, richness estimates and new species per
+/// week over the whole history.
+pub async fn species_richness(db_path: &Path, domain: Option<&str>) -> Res<RichnessStats> {
+    let overrides = read_overrides(db_path).await;
+    let excl = format!(
+        "{} {}",
+        exclusion_clause(&overrides),
+        domain_filter(domain, "Domain")
+    );
+    let duck = conn()?;
+    species_richness_inner(&duck, &excl)
+}
+
+fn species_richness_inner(duck: &duckdb::Connection, excl: &str) -> Res<RichnessStats> {
+    use chrono::{Datelike, Duration, NaiveDate};
+
+    let dated = format!(
+        "SELECT try_cast(Date AS DATE) AS day, Sci_Name, Com_Name, Domain \
+         FROM detections WHERE {excl}"
+    );
+    let parse = |d: String| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok();
+
+    let mut stmt = duck.prepare(&format!(
+        "SELECT DISTINCT CAST(day AS VARCHAR) AS d FROM ({dated}) \
+         WHERE day IS NOT NULL ORDER BY d"
+    ))?;
+    let days: Vec<NaiveDate> = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .filter_map(parse)
+        .collect();
+
+    // (first day, common name, domain, days detected), first detected first.
+    let mut stmt = duck.prepare(&format!(
+        "SELECT CAST(MIN(day) AS VARCHAR) AS first_day, \
+         COALESCE(MAX(CASE WHEN Com_Name != Sci_Name THEN Com_Name END), MAX(Com_Name)), \
+         COALESCE(MIN(Domain), ''), COUNT(DISTINCT day) \
+         FROM ({dated}) WHERE day IS NOT NULL \
+         GROUP BY Sci_Name ORDER BY first_day, Sci_Name"
+    ))?;
+    let species: Vec<(NaiveDate, String, String, u32)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .filter_map(|(first, name, domain, n)| Some((parse(first)?, name, domain, n)))
+        .collect();
+
+    let mut curve = Vec::with_capacity(days.len());
+    let mut seen = 0;
+    for (i, day) in days.iter().enumerate() {
+        while seen < species.len() && species[seen].0 <= *day {
+            seen += 1;
+        }
+        curve.push(AccumulationPoint {
+            date: day.to_string(),
+            days: i as u32 + 1,
+            species: seen as u32,
+        });
+    }
+
+    let sampling_days = days.len() as u32;
+    let richness = |domain: &str| {
+        let of_domain = || species.iter().filter(|s| domain.is_empty() || s.2 == domain);
+        let with_days = |n: u32| of_domain().filter(|s| s.3 == n).count() as u32;
+        let (observed, q1, q2) = (of_domain().count() as u32, with_days(1), with_days(2));
+        DomainRichness {
+            domain: domain.to_string(),
+            observed,
+            singletons: q1,
+            doubletons: q2,
+            estimated: chao2(observed, q1, q2, sampling_days),
+        }
+    };
+    let mut domain_names: Vec<&str> = species.iter().map(|s| s.2.as_str()).collect();
+    domain_names.sort_unstable();
+    domain_names.dedup();
+    let mut domains: Vec<DomainRichness> = domain_names.into_iter().map(richness).collect();
+    domains.sort_by(|a, b| b.observed.cmp(&a.observed).then(a.domain.cmp(&b.domain)));
+
+    let monday = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+    let mut weekly_new = Vec::new();
+    if let (Some(first), Some(last)) = (days.first(), days.last()) {
+        let mut week = monday(*first);
+        while week <= *last {
+            let next = week + Duration::days(7);
+            weekly_new.push(WeeklyNewSpecies {
+                week_start: week.to_string(),
+                species: species
+                    .iter()
+                    .filter(|s| s.0 >= week && s.0 < next)
+                    .map(|s| s.1.clone())
+                    .collect(),
+            });
+            week = next;
+        }
+    }
+
+    Ok(RichnessStats {
+        sampling_days,
+        curve,
+        total: richness(""),
+        domains,
+        weekly_new,
+    })
+}
+
+/// Bias-corrected Chao2 richness estimate from `observed` species, `q1`
+/// of them seen on one day and `q2` on two, over `m` sampling days.
+fn chao2(observed: u32, q1: u32, q2: u32, m: u32) -> f64 {
+    if m == 0 {
+        return observed as f64;
+    }
+    let (q1, q2, m) = (q1 as f64, q2 as f64, m as f64);
+    observed as f64 + (m - 1.0) / m * q1 * (q1 - 1.0) / (2.0 * (q2 + 1.0))
+}
+
+/// Top recordings for a species (by confidence).
+pub async fn get_top_recordings(
+    db_path: &Path,
+    scientific_name: &str,
+    limit: u32,
+) -> Res<Vec<TopRecording>> {
+    let tz = read_tz_offset(db_path).await;
+    let safe = scientific_name.replace('\'', "''");
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT Sci_Name, Com_Name, Date, Time, Confidence, File_Name, \
+         COALESCE(Source_Node, ''), COALESCE(Model_Name, ''), Original_Com_Name \
+         FROM detections \
+         WHERE Sci_Name = '{safe}' AND COALESCE(Excluded, 0) = 0 \
+           AND File_Name != '' AND Confidence >= 0.5 \
+         ORDER BY Confidence DESC, Date DESC, Time DESC \
+         LIMIT {limit}"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(TopRecording {
+            scientific_name: row.get(0)?,
+            common_name: row.get(1)?,
+            date: row.get(2)?,
+            time: row.get(3)?,
+            confidence: row.get(4)?,
+            file_name: row.get(5)?,
+            source_node: row.get(6)?,
+            model_name: row.get(7)?,
+            original_common_name: row.get(8)?,
+            display_date: String::new(),
+            display_time: String::new(),
+        })
+    })?;
+    let mut recs: Vec<TopRecording> = rows.filter_map(|r| r.ok()).collect();
+    for r in &mut recs {
+        stamp_recording(r, tz);
+    }
+    Ok(recs)
 }
 
 /// Distinct models that have produced detections.
@@ -889,6 +1687,17 @@ pub async fn available_models(_db_path: &Path) -> Res<Vec<ModelInfo>> {
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+/// Every domain with detections, busiest first.
+pub async fn available_domains(_db_path: &Path) -> Res<Vec<String>> {
+    let duck = conn()?;
+    let sql = "SELECT Domain FROM detections \
+               WHERE COALESCE(Domain, '') != '' \
+               GROUP BY Domain ORDER BY COUNT(*) DESC";
+    let mut stmt = duck.prepare(sql)?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 /// Species detections by model (or all models).
 pub async fn species_detections_by_model(
     db_path: &Path,
@@ -908,7 +1717,11 @@ pub async fn species_detections_by_model(
          COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
          COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
          COALESCE(Model_Beta, 0), \
-         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, '') \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
          FROM detections WHERE Sci_Name = '{safe}' {slug_filter} \
          ORDER BY Date DESC, Time DESC LIMIT {limit}"
     );
@@ -921,6 +1734,24 @@ pub async fn species_detections_by_model(
     Ok(dets)
 }
 
+/// Scientific names of every detected species, excluded ones included,
+/// most detected first.  Feeds the species photo enrichment worker.
+pub async fn known_species(db_path: &Path) -> Res<Vec<String>> {
+    if !STATS_POPULATED.load(std::sync::atomic::Ordering::Relaxed) {
+        refresh_species_stats(db_path).await?;
+    }
+    let duck = conn()?;
+    let mut stmt = duck.prepare(
+        "SELECT Sci_Name FROM ( \
+             SELECT Sci_Name, detection_count FROM species_stats \
+             UNION ALL \
+             SELECT Sci_Name, detection_count FROM excluded_species_stats) \
+         GROUP BY Sci_Name ORDER BY SUM(detection_count) DESC",
+    )?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 /// Excluded species summary.
 pub async fn excluded_species(db_path: &Path) -> Res<Vec<ExcludedSpecies>> {
     if !STATS_POPULATED.load(std::sync::atomic::Ordering::Relaxed) {
@@ -974,7 +1805,11 @@ pub async fn excluded_detections_for_species(
          COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
          COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
          COALESCE(Model_Beta, 0), \
-         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, '') \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
          FROM detections WHERE Sci_Name = '{safe}' AND COALESCE(Excluded, 0) = 1 \
          ORDER BY Date DESC, Time DESC LIMIT {limit}"
     );
@@ -991,6 +1826,9 @@ pub async fn excluded_detections_for_species(
 ///
 /// Unlike the old SQLite version, this uses DuckDB analytics directly
 /// and stores the cache as in-memory tables within the DuckDB instance.
+/// While the cache is stale (new Parquet files since the last refresh)
+/// date queries fall back to scanning `detections`, so today's counts
+/// are never behind.
 pub async fn refresh_species_stats(db_path: &Path) -> Res<()> {
     let overrides = read_overrides(db_path).await;
     let excl = exclusion_clause(&overrides);
@@ -1033,6 +1871,27 @@ pub async fn refresh_species_stats(db_path: &Path) -> Res<()> {
          GROUP BY d.Model_Slug, d.Sci_Name",
     ))?;
 
+    // hourly_species_stats — detections per (date, hour, model, species),
+    // used by the calendar, day and home pages instead of scanning every
+    // Parquet file.  Orders of magnitude smaller than `detections`.
+    duck.execute_batch(&format!(
+        "CREATE OR REPLACE TABLE hourly_species_stats AS \
+         SELECT Date, \
+                CAST(SUBSTR(Time, 1, 2) AS INTEGER) AS Hour, \
+                COALESCE(Model_Slug, '') AS Model_Slug, \
+                Sci_Name, \
+                COALESCE( \
+                    MAX(CASE WHEN Com_Name != Sci_Name THEN Com_Name ELSE NULL END), \
+                    MAX(Com_Name) \
+                ) AS Com_Name, \
+                string_agg(DISTINCT Domain, ',') AS Domain, \
+                COUNT(*) AS cnt, \
+                MAX(Date || ' ' || Time) AS last_seen \
+         FROM detections \
+         WHERE {excl} \
+         GROUP BY Date, Hour, COALESCE(Model_Slug, ''), Sci_Name",
+    ))?;
+
     // excluded_species_stats — used by the excluded species page.
     duck.execute_batch(
         "CREATE OR REPLACE TABLE excluded_species_stats AS \
@@ -1148,7 +2007,7 @@ pub async fn quiz_candidates(
         }
         seen.insert(sci.clone());
 
-        let safe_name = com.replace('\'', "").replace(' ', "_");
+        let safe_name = crate::model::safe_path_component(&com);
         let clip_url = format!("/extracted/By_Date/{date}/{safe_name}/{file_name}");
         let spectrogram_url = format!("{clip_url}.png");
 
@@ -1190,6 +2049,257 @@ pub async fn get_species_models(
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+/// Whether `scientific_name` has any detection earlier than `date`/`time`
+/// (UTC, as stored).  Used to spot a species' first-ever detection.
+pub async fn species_seen_before(scientific_name: &str, date: &str, time: &str) -> Res<bool> {
+    let duck = conn()?;
+    let n: i64 = duck.query_row(
+        "SELECT COUNT(*) FROM detections \
+         WHERE Sci_Name = ? AND (Date < ? OR (Date = ? AND Time < ?))",
+        params![scientific_name, date, date, time],
+        |row| row.get(0),
+    )?;
+    Ok(n > 0)
+}
+
+/// One detection plus the station coordinates it was recorded with,
+/// looked up by id and clip name (ids are only unique per batch).
+pub async fn detection_for_export(
+    db_path: &Path,
+    id: i64,
+    file_name: &str,
+) -> Res<Option<(WebDetection, f64, f64)>> {
+    let tz = read_tz_offset(db_path).await;
+    let duck = conn()?;
+    let mut stmt = duck.prepare(
+        "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
+         COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
+         COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
+         COALESCE(Model_Beta, 0), \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing, \
+         COALESCE(Lat, 0.0), COALESCE(Lon, 0.0) \
+         FROM detections WHERE id = ? AND File_Name = ? LIMIT 1",
+    )?;
+    let mut rows = stmt.query_map(params![id, file_name], |row| {
+        Ok((parse_detection(row)?, row.get::<_, f64>(27)?, row.get::<_, f64>(28)?))
+    })?;
+    let Some(found) = rows.next().transpose()? else {
+        return Ok(None);
+    };
+    let (mut det, lat, lon) = found;
+    stamp(&mut det, tz);
+    Ok(Some((det, lat, lon)))
+}
+
+/// One detection by id, for its permalink.  Ids written by processing
+/// embed the insertion time and are unique; should an imported batch
+/// reuse one, the most recent detection wins.
+pub async fn detection_by_id(db_path: &Path, id: i64) -> Res<Option<WebDetection>> {
+    let tz = read_tz_offset(db_path).await;
+    let duck = conn()?;
+    let mut stmt = duck.prepare(
+        "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
+         COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
+         COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
+         COALESCE(Model_Beta, 0), \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
+         FROM detections WHERE id = ? ORDER BY Date DESC, Time DESC LIMIT 1",
+    )?;
+    let mut rows = stmt.query_map(params![id], |row| parse_detection(row))?;
+    let Some(mut det) = rows.next().transpose()? else {
+        return Ok(None);
+    };
+    stamp(&mut det, tz);
+    Ok(Some(det))
+}
+
+/// The alternative labels stored with detection `id` (`TOP_PREDICTIONS`
+/// on the processing node); empty when none were stored.
+pub async fn top_predictions(id: i64) -> Res<Vec<TopPrediction>> {
+    let duck = conn()?;
+    top_predictions_inner(&duck, id)
+}
+
+fn top_predictions_inner(duck: &duckdb::Connection, id: i64) -> Res<Vec<TopPrediction>> {
+    let mut stmt =
+        duck.prepare("SELECT Top_Predictions FROM detections WHERE id = ? LIMIT 1")?;
+    let mut rows = stmt.query_map(params![id], |row| row.get::<_, Option<String>>(0))?;
+    let Some(json) = rows.next().transpose()?.flatten() else {
+        return Ok(Vec::new());
+    };
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// The label set of model `model_slug`, read from the species reference
+/// files processing writes to `species/` next to the detections
+/// directory.  Falls back to the labels of every model when `model_slug`
+/// has none there (an empty slug, or a model no longer loaded).
+pub async fn species_labels(model_slug: &str) -> Res<Vec<SpeciesLabel>> {
+    let Some(data_dir) = DET_DIR.get().and_then(|d| d.parent()) else {
+        return Ok(Vec::new());
+    };
+    let duck = conn_raw()?;
+    species_labels_inner(&duck, &data_dir.join("species"), model_slug)
+}
+
+fn species_labels_inner(duck: &duckdb::Connection, dir: &Path, model_slug: &str) -> Res<Vec<SpeciesLabel>> {
+    let files = parquet_files(dir);
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let files_sql = files
+        .iter()
+        .map(|path| format!("'{}'", escape_sql_path(path)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = duck.prepare(&format!(
+        "SELECT Sci_Name, Com_Name, Com_Names, Model_Slug \
+         FROM read_parquet([{files_sql}], union_by_name=true) \
+         ORDER BY Sci_Name, Model_Slug"
+    ))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let own = rows.iter().any(|(.., slug)| slug == model_slug);
+
+    // Several models may list a species; merge their names.
+    let mut labels: Vec<SpeciesLabel> = Vec::new();
+    for (sci, com, names, slug) in rows {
+        if own && slug != model_slug {
+            continue;
+        }
+        let localized: Vec<String> = names
+            .and_then(|json| serde_json::from_str::<std::collections::BTreeMap<String, String>>(&json).ok())
+            .map(|names| names.into_values().filter(|n| !n.is_empty()).collect())
+            .unwrap_or_default();
+        match labels.last_mut() {
+            Some(last) if last.scientific_name == sci => {
+                for name in localized {
+                    if !last.localized.contains(&name) {
+                        last.localized.push(name);
+                    }
+                }
+            }
+            _ => labels.push(SpeciesLabel {
+                common_name: com.filter(|c| !c.is_empty()).unwrap_or_else(|| sci.clone()),
+                scientific_name: sci,
+                localized,
+            }),
+        }
+    }
+    Ok(labels)
+}
+
+/// Change the species of one detection (looked up by id and clip name),
+/// keeping the model's prediction in `Original_Sci_Name` /
+/// `Original_Com_Name`.  `fine_tune` flags the clip for fine-tuning
+/// dataset exports.
+///
+/// Parquet files cannot be edited in place, so the file holding the
+/// detection is rewritten next to the original and renamed over it (the
+/// backup and `gaia-admin` rewrites allow for this); the stats cache
+/// is then rebuilt so counts move to the new species.  An empty
+/// `common_name` is looked up in the species reference table, and the
+/// species must be one of its labels once processing has written it.
+pub async fn relabel_detection(
+    db_path: &Path,
+    id: i64,
+    file_name: &str,
+    scientific_name: &str,
+    common_name: &str,
+    fine_tune: bool,
+) -> Res<()> {
+    let sci = scientific_name.trim();
+    if sci.is_empty() {
+        return Err("Scientific name is required".into());
+    }
+    // Keep species names consistent with the rest of the dashboard.
+    let labels = species_labels("").await?;
+    if !labels.is_empty() && !labels.iter().any(|label| label.scientific_name == sci) {
+        return Err(format!("{sci} is not a label of any model").into());
+    }
+    let com = match common_name.trim() {
+        "" => {
+            let duck = conn()?;
+            let mut stmt = duck.prepare("SELECT MAX(Com_Name) FROM species WHERE Sci_Name = ?")?;
+            let found: Option<String> = stmt.query_row(params![sci], |row| row.get(0)).unwrap_or(None);
+            found.unwrap_or_else(|| sci.to_string())
+        }
+        name => name.to_string(),
+    };
+    let dir = DET_DIR.get().ok_or("DuckDB not initialised")?;
+
+    let duck = duckdb::Connection::open_in_memory()?;
+    let files_sql = readable_parquet_files(&duck, dir)
+        .iter()
+        .map(|path| format!("'{}'", escape_sql_path(path)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if files_sql.is_empty() {
+        return Err("Detection not found".into());
+    }
+    let path: String = {
+        let mut stmt = duck.prepare(&format!(
+            "SELECT filename FROM read_parquet([{files_sql}], union_by_name=true, filename=true) \
+             WHERE id = ? AND File_Name = ? LIMIT 1"
+        ))?;
+        let mut rows = stmt.query_map(params![id, file_name], |row| row.get::<_, String>(0))?;
+        rows.next().transpose()?.ok_or("Detection not found")?
+    };
+    let path = PathBuf::from(path);
+
+    duck.execute_batch(&format!(
+        "CREATE TABLE relabel AS SELECT * FROM read_parquet('{}'); \
+         ALTER TABLE relabel ADD COLUMN IF NOT EXISTS Original_Sci_Name VARCHAR; \
+         ALTER TABLE relabel ADD COLUMN IF NOT EXISTS Original_Com_Name VARCHAR; \
+         ALTER TABLE relabel ADD COLUMN IF NOT EXISTS Relabeled_At VARCHAR; \
+         ALTER TABLE relabel ADD COLUMN IF NOT EXISTS Fine_Tune INTEGER;",
+        escape_sql_path(&path)
+    ))?;
+    let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    duck.execute(
+        "UPDATE relabel SET \
+             Original_Sci_Name = COALESCE(Original_Sci_Name, Sci_Name), \
+             Original_Com_Name = COALESCE(Original_Com_Name, Com_Name), \
+             Sci_Name = ?, Com_Name = ?, Relabeled_At = ?, Fine_Tune = ? \
+         WHERE id = ? AND File_Name = ?",
+        params![sci, com, now, i32::from(fine_tune), id, file_name],
+    )?;
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{name}.tmp"));
+    duck.execute(
+        &format!(
+            "COPY relabel TO '{}' (FORMAT PARQUET, COMPRESSION ZSTD)",
+            escape_sql_path(&tmp)
+        ),
+        [],
+    )?;
+    drop(duck);
+    std::fs::rename(&tmp, &path)?;
+    info!("Relabelled detection {id} ({file_name}) as {sci}");
+
+    refresh_species_stats(db_path).await
+}
+
 /// Get all existing filenames in detections (used by import deduplication).
 pub async fn get_existing_filenames() -> Res<Vec<String>> {
     let duck = conn()?;
@@ -1210,11 +2320,502 @@ pub async fn total_detections() -> Res<u64> {
     Ok(count)
 }
 
+/// Detections with a clip at or above `min_confidence`, oldest first,
+/// for the training dataset export.  Excluded detections are left out
+/// unless overridden.
+pub async fn dataset_candidates(
+    db_path: &Path,
+    min_confidence: f64,
+    model_slug: Option<&str>,
+) -> Res<Vec<(WebDetection, bool)>> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let slug_filter = match model_slug {
+        Some(s) if !s.is_empty() => format!("AND COALESCE(Model_Slug, '') = '{}'", s.replace('\'', "''")),
+        _ => String::new(),
+    };
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
+         COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
+         COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
+         COALESCE(Model_Beta, 0), \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing, \
+         COALESCE(Fine_Tune, 0) \
+         FROM detections \
+         WHERE File_Name != '' AND Confidence >= {min_confidence} AND {excl} {slug_filter} \
+         ORDER BY Date, Time"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| Ok((parse_detection(row)?, row.get::<_, i32>(27)? != 0)))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 /// Return the detections directory path (for import Parquet writes).
 pub fn get_detections_dir() -> Option<PathBuf> {
     DET_DIR.get().cloned()
 }
 
+// ─── Composable queries (GraphQL) ────────────────────────────────────────────
+
+/// Filters shared by the GraphQL detection and daily-count queries.
+/// Every field is optional; `None` means "don't filter".
+#[derive(Debug, Clone, Default)]
+pub struct DetectionQuery {
+    /// Only detections with `id > after_id` (oldest first — for polling).
+    pub after_id: Option<i64>,
+    /// Only detections with `id < before_id` (newest first — for paging back).
+    pub before_id: Option<i64>,
+    /// Inclusive `YYYY-MM-DD` bounds on the stored (UTC) date.
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub scientific_name: Option<String>,
+    pub domain: Option<String>,
+    pub model_slug: Option<String>,
+    pub source_node: Option<String>,
+    pub min_confidence: Option<f64>,
+    /// Also return detections excluded by the species-range model.
+    pub include_excluded: bool,
+    /// Only these detections (e.g. a user's favourites).
+    pub ids: Option<Vec<i64>>,
+}
+
+fn sql_str(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+impl DetectionQuery {
+    /// `WHERE` body for this filter; `overrides` is the exclusion-override
+    /// list (ignored when `include_excluded` is set).
+    fn where_clause(&self, overrides: &[String]) -> String {
+        let mut parts = vec![if self.include_excluded {
+            "true".to_string()
+        } else {
+            exclusion_clause(overrides)
+        }];
+        if let Some(id) = self.after_id {
+            parts.push(format!("id > {id}"));
+        }
+        if let Some(id) = self.before_id {
+            parts.push(format!("id < {id}"));
+        }
+        if let Some(d) = &self.date_from {
+            parts.push(format!("Date >= {}", sql_str(d)));
+        }
+        if let Some(d) = &self.date_to {
+            parts.push(format!("Date <= {}", sql_str(d)));
+        }
+        if let Some(s) = &self.scientific_name {
+            parts.push(format!("Sci_Name = {}", sql_str(s)));
+        }
+        if let Some(s) = &self.domain {
+            parts.push(format!("Domain = {}", sql_str(s)));
+        }
+        if let Some(s) = &self.model_slug {
+            parts.push(format!("COALESCE(Model_Slug, '') = {}", sql_str(s)));
+        }
+        if let Some(s) = &self.source_node {
+            parts.push(format!("COALESCE(Source_Node, '') = {}", sql_str(s)));
+        }
+        if let Some(c) = self.min_confidence.filter(|c| c.is_finite()) {
+            parts.push(format!("Confidence >= {c}"));
+        }
+        if let Some(ids) = &self.ids {
+            let list: Vec<String> = ids.iter().map(i64::to_string).collect();
+            parts.push(if list.is_empty() {
+                "false".to_string()
+            } else {
+                format!("id IN ({})", list.join(", "))
+            });
+        }
+        parts.join(" AND ")
+    }
+}
+
+/// Detections matching `query`, newest first (oldest first when paging
+/// forward with `after_id`).
+pub async fn query_detections(
+    db_path: &Path,
+    query: &DetectionQuery,
+    limit: u32,
+) -> Res<Vec<WebDetection>> {
+    let tz = read_tz_offset(db_path).await;
+    let overrides = read_overrides(db_path).await;
+    let filter = query.where_clause(&overrides);
+    let order = if query.after_id.is_some() { "ASC" } else { "DESC" };
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
+         COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
+         COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
+         COALESCE(Model_Beta, 0), \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
+         FROM detections WHERE {filter} \
+         ORDER BY id {order} LIMIT {limit}"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| parse_detection(row))?;
+    let mut dets: Vec<WebDetection> = rows.filter_map(|r| r.ok()).collect();
+    for d in &mut dets {
+        stamp(d, tz);
+    }
+    Ok(dets)
+}
+
+/// Per-day detection and species counts for detections matching `query`.
+pub async fn daily_counts(db_path: &Path, query: &DetectionQuery) -> Res<Vec<CalendarDay>> {
+    let overrides = read_overrides(db_path).await;
+    let filter = query.where_clause(&overrides);
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT Date, COUNT(*) AS cnt, COUNT(DISTINCT Sci_Name) AS spp \
+         FROM detections WHERE {filter} \
+         GROUP BY Date ORDER BY Date"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(CalendarDay {
+            date: row.get(0)?,
+            total_detections: row.get(1)?,
+            unique_species: row.get(2)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Species whose first detection ever falls between `from` and `to`
+/// (inclusive `YYYY-MM-DD`), as `(Sci_Name, Com_Name, first date)`.
+pub async fn first_seen_between(
+    db_path: &Path,
+    from: &str,
+    to: &str,
+) -> Res<Vec<(String, String, String)>> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT Sci_Name, MAX(Com_Name), MIN(Date) AS first_date \
+         FROM detections WHERE {excl} \
+         GROUP BY Sci_Name \
+         HAVING first_date >= {} AND first_date <= {} \
+         ORDER BY first_date, Sci_Name",
+        sql_str(from),
+        sql_str(to),
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// The most confident detection with a clip of each species in `names`
+/// between `from` and `to` (inclusive `YYYY-MM-DD`), in `names` order.
+pub async fn best_detections_between(
+    db_path: &Path,
+    from: &str,
+    to: &str,
+    names: &[String],
+) -> Res<Vec<WebDetection>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let tz = read_tz_offset(db_path).await;
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let list = names.iter().map(|n| sql_str(n)).collect::<Vec<_>>().join(", ");
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT id, Domain, Sci_Name, Com_Name, Confidence, Date, Time, File_Name, \
+         COALESCE(Source_Node, ''), COALESCE(Excluded, 0), \
+         COALESCE(Model_Slug, ''), COALESCE(Model_Name, ''), \
+         COALESCE(Model_Beta, 0), \
+         COALESCE(Agreement_Score, 0.0), COALESCE(Agreement_Models, ''), \
+         Peak_Freq_Hz, Bandwidth_Hz, Call_Duration_S, Original_Sci_Name, Original_Com_Name, \
+         Rarity, COALESCE(Rare, 0), \
+         COALESCE(Model_Version, ''), COALESCE(Model_Variant, ''), COALESCE(Model_Runtime, ''), \
+         Channel, Bearing \
+         FROM detections \
+         WHERE Date >= {} AND Date <= {} AND File_Name != '' \
+           AND Sci_Name IN ({list}) AND {excl} \
+         QUALIFY ROW_NUMBER() OVER (PARTITION BY Sci_Name ORDER BY Confidence DESC, id) = 1",
+        sql_str(from),
+        sql_str(to),
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| parse_detection(row))?;
+    let mut dets: Vec<WebDetection> = rows.filter_map(|r| r.ok()).collect();
+    for d in &mut dets {
+        stamp(d, tz);
+    }
+    dets.sort_by_key(|d| names.iter().position(|n| *n == d.scientific_name));
+    Ok(dets)
+}
+
+/// Activity summary of one capture node (`Source_Node`).
+#[derive(Debug, Clone)]
+pub struct NodeStats {
+    pub name: String,
+    pub total_detections: u64,
+    pub unique_species: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// Every node that has produced detections, busiest first.
+pub async fn node_stats(db_path: &Path) -> Res<Vec<NodeStats>> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT COALESCE(Source_Node, '') AS node, COUNT(*), COUNT(DISTINCT Sci_Name), \
+         MIN(Date), MAX(Date) \
+         FROM detections WHERE {excl} \
+         GROUP BY node ORDER BY COUNT(*) DESC"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(NodeStats {
+            name: row.get(0)?,
+            total_detections: row.get(1)?,
+            unique_species: row.get(2)?,
+            first_seen: row.get(3)?,
+            last_seen: row.get(4)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Species detected under `node`, most detected first, e.g. the
+/// results of a folder import.
+pub async fn species_for_node(db_path: &Path, node: &str) -> Res<Vec<ImportedSpecies>> {
+    let overrides = read_overrides(db_path).await;
+    let excl = exclusion_clause(&overrides);
+    let duck = conn()?;
+    let sql = format!(
+        "SELECT Sci_Name, MAX(Com_Name), COUNT(*), MAX(Confidence) \
+         FROM detections WHERE Source_Node = ? AND {excl} \
+         GROUP BY Sci_Name ORDER BY COUNT(*) DESC, Sci_Name"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map(params![node], |row| {
+        Ok(ImportedSpecies {
+            scientific_name: row.get(0)?,
+            common_name: row.get(1)?,
+            detections: row.get(2)?,
+            best_confidence: row.get(3)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Every node with a known location and its detections, busiest first,
+/// optionally of one domain or species.  A node that moved is placed
+/// where it recorded last.
+pub async fn node_locations(
+    db_path: &Path,
+    domain: Option<&str>,
+    scientific_name: Option<&str>,
+) -> Res<Vec<NodeLocation>> {
+    let overrides = read_overrides(db_path).await;
+    let species = match scientific_name {
+        Some(name) if !name.is_empty() => format!("AND Sci_Name = {}", sql_str(name)),
+        _ => String::new(),
+    };
+    let filter = format!(
+        "{} {} {species}",
+        exclusion_clause(&overrides),
+        domain_filter(domain, "Domain")
+    );
+    let duck = conn()?;
+    node_locations_inner(&duck, &filter)
+}
+
+fn node_locations_inner(duck: &duckdb::Connection, filter: &str) -> Res<Vec<NodeLocation>> {
+    // -1/-1 is an unset LATITUDE/LONGITUDE, 0/0 an old placeholder.
+    let sql = format!(
+        "SELECT COALESCE(Source_Node, '') AS node, \
+         arg_max(Lat, Date || ' ' || Time), arg_max(Lon, Date || ' ' || Time), \
+         COUNT(*), COUNT(DISTINCT Sci_Name), MAX(Date) \
+         FROM detections \
+         WHERE {filter} \
+           AND Lat BETWEEN -90 AND 90 AND Lon BETWEEN -180 AND 180 \
+           AND NOT (Lat = -1 AND Lon = -1) AND NOT (Lat = 0 AND Lon = 0) \
+         GROUP BY node ORDER BY COUNT(*) DESC, node"
+    );
+    let mut stmt = duck.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(NodeLocation {
+            node: row.get(0)?,
+            lat: row.get(1)?,
+            lon: row.get(2)?,
+            detections: row.get(3)?,
+            species: row.get(4)?,
+            last_seen: row.get(5)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Every station side by side: detections, richness and the species
+/// only it detected, plus which species each one detected.  Optionally
+/// of one domain and between `from` and `to` (YYYY-MM-DD, inclusive,
+/// already validated).
+pub async fn station_comparison(
+    db_path: &Path,
+    domain: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Res<StationComparison> {
+    let overrides = read_overrides(db_path).await;
+    let mut filter = format!(
+        "{} {}",
+        exclusion_clause(&overrides),
+        domain_filter(domain, "Domain")
+    );
+    if let Some(from) = from {
+        filter.push_str(&format!(" AND Date >= {}", sql_str(from)));
+    }
+    if let Some(to) = to {
+        filter.push_str(&format!(" AND Date <= {}", sql_str(to)));
+    }
+    let duck = conn()?;
+    station_comparison_inner(&duck, &filter)
+}
+
+fn station_comparison_inner(duck: &duckdb::Connection, filter: &str) -> Res<StationComparison> {
+    use std::collections::HashMap;
+
+    let mut stmt = duck.prepare(&format!(
+        "SELECT COALESCE(Source_Node, '') AS node, COUNT(*), COUNT(DISTINCT Sci_Name), \
+         COUNT(DISTINCT Date), MIN(Date), MAX(Date) \
+         FROM detections WHERE {filter} \
+         GROUP BY node ORDER BY COUNT(DISTINCT Sci_Name) DESC, node"
+    ))?;
+    let mut stations: Vec<StationSummary> = stmt
+        .query_map([], |row| {
+            Ok(StationSummary {
+                node: row.get(0)?,
+                detections: row.get(1)?,
+                species: row.get(2)?,
+                unique_species: 0,
+                days: row.get(3)?,
+                estimated: 0.0,
+                first_seen: row.get(4)?,
+                last_seen: row.get(5)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    let index: HashMap<String, usize> = stations
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.node.clone(), i))
+        .collect();
+
+    // (node, species, common name, domain, detections, days detected)
+    let mut stmt = duck.prepare(&format!(
+        "SELECT COALESCE(Source_Node, '') AS node, Sci_Name, \
+         COALESCE(MAX(CASE WHEN Com_Name != Sci_Name THEN Com_Name END), MAX(Com_Name)), \
+         COALESCE(MIN(Domain), ''), COUNT(*), COUNT(DISTINCT Date) \
+         FROM detections WHERE {filter} \
+         GROUP BY node, Sci_Name"
+    ))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, u64>(4)?,
+                row.get::<_, u32>(5)?,
+            ))
+        })?
+        .filter_map(|r| r.ok());
+
+    let mut species: HashMap<String, StationSpecies> = HashMap::new();
+    // Species seen on exactly one and two days, per station.
+    let mut rare = vec![(0u32, 0u32); stations.len()];
+    for (node, sci, com, domain, detections, days) in rows {
+        let Some(&i) = index.get(&node) else { continue };
+        match days {
+            1 => rare[i].0 += 1,
+            2 => rare[i].1 += 1,
+            _ => {}
+        }
+        let row = species.entry(sci.clone()).or_insert_with(|| StationSpecies {
+            scientific_name: sci,
+            common_name: com,
+            domain,
+            detections: vec![0; index.len()],
+        });
+        row.detections[i] = detections;
+    }
+
+    for (i, station) in stations.iter_mut().enumerate() {
+        station.estimated = chao2(station.species, rare[i].0, rare[i].1, station.days);
+    }
+    for row in species.values().filter(|row| row.stations() == 1) {
+        if let Some(i) = row.detections.iter().position(|&n| n > 0) {
+            stations[i].unique_species += 1;
+        }
+    }
+
+    let mut species: Vec<StationSpecies> = species.into_values().collect();
+    species.sort_by(|a, b| {
+        b.stations()
+            .cmp(&a.stations())
+            .then_with(|| b.detections.iter().sum::<u64>().cmp(&a.detections.iter().sum()))
+            .then_with(|| a.common_name.cmp(&b.common_name))
+    });
+    Ok(StationComparison { stations, species })
+}
+
+/// Copy the detections matching `query` to `out`, oldest first, as
+/// ZSTD-compressed Parquet or (`parquet = false`) CSV with a header.
+/// Returns the number of rows written.
+///
+/// Runs on its own in-memory DuckDB connection so a long export doesn't
+/// hold the shared one, and DuckDB writes the file as it scans instead of
+/// collecting the rows first.
+pub async fn export_detections(
+    db_path: &Path,
+    query: &DetectionQuery,
+    out: &Path,
+    parquet: bool,
+) -> Res<u64> {
+    let overrides = read_overrides(db_path).await;
+    let filter = query.where_clause(&overrides);
+    let dir = DET_DIR.get().ok_or("DuckDB not initialised")?.clone();
+    let out = out.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Res<u64> {
+        let duck = duckdb::Connection::open_in_memory()?;
+        refresh_view_inner(&duck, &dir)?;
+        let options = if parquet {
+            "FORMAT PARQUET, COMPRESSION ZSTD"
+        } else {
+            "FORMAT CSV, HEADER"
+        };
+        let rows = duck.execute(
+            &format!(
+                "COPY (SELECT * FROM detections WHERE {filter} ORDER BY Date, Time) \
+                 TO '{}' ({options})",
+                escape_sql_path(&out)
+            ),
+            [],
+        )?;
+        Ok(rows as u64)
+    })
+    .await?
+}
+
 // ─── One-time SQLite → Parquet migration ─────────────────────────────────────
 
 /// Migrate existing SQLite detections to Parquet files.
@@ -1464,16 +3065,21 @@ pub fn get_existing_filenames_set() -> Result<std::collections::HashSet<String>,
     let mut names: std::collections::HashSet<String> = rows.filter_map(|r| r.ok()).collect();
 
     // For every .opus filename, also add .mp3 and .wav so the source
-    // BirdNET-Pi filenames (which are always .mp3) still match.
-    let opus_variants: Vec<String> = names
+    // BirdNET-Pi filenames (which are always .mp3) still match; with
+    // CLIP_FORMAT=mp3 the .mp3 names stand in for their .wav originals.
+    let variants: Vec<String> = names
         .iter()
-        .filter(|n| n.ends_with(".opus"))
         .flat_map(|n| {
-            let stem = &n[..n.len() - 5];
-            [format!("{stem}.mp3"), format!("{stem}.wav")]
+            if let Some(stem) = n.strip_suffix(".opus") {
+                vec![format!("{stem}.mp3"), format!("{stem}.wav")]
+            } else if let Some(stem) = n.strip_suffix(".mp3") {
+                vec![format!("{stem}.wav")]
+            } else {
+                vec![]
+            }
         })
         .collect();
-    names.extend(opus_variants);
+    names.extend(variants);
 
     Ok(names)
 }
@@ -1674,4 +3280,349 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn resolve_compare_ranges_defaults_and_validation() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 5, 20).unwrap();
+        let [bf, bt, cf, ct] = resolve_compare_ranges(today, "", "", "", "").unwrap();
+        assert_eq!(
+            [bf, bt, cf, ct].map(|d| d.to_string()),
+            ["2025-05-07", "2025-05-13", "2025-05-14", "2025-05-20"]
+        );
+
+        let [bf, bt, ..] =
+            resolve_compare_ranges(today, "", "", "2025-04-01", "2025-04-30").unwrap();
+        assert_eq!((bf.to_string(), bt.to_string()), ("2025-03-02".into(), "2025-03-31".into()));
+
+        assert!(resolve_compare_ranges(today, "", "", "2025-05-10", "2025-05-01").is_err());
+        assert!(resolve_compare_ranges(today, "x' OR 1=1", "", "", "").is_err());
+    }
+
+    #[test]
+    fn compare_periods_flags_arrivals_and_departures() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE detections AS SELECT * FROM (VALUES \
+                ('2025-03-02', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-04-02', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-04-03', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-03-05', 'Fringilla montifringilla', 'Brambling', 'birds', 0), \
+                ('2025-04-10', 'Hirundo rustica', 'Barn Swallow', 'birds', 0), \
+                ('2025-04-11', 'Pica pica', 'Eurasian Magpie', 'birds', 1)) \
+             AS t(Date, Sci_Name, Com_Name, Domain, Excluded)",
+        )
+        .unwrap();
+
+        let rows = compare_periods_inner(
+            &conn,
+            &exclusion_clause(&[]),
+            ("2025-03-01", "2025-03-31"),
+            ("2025-04-01", "2025-04-30"),
+        )
+        .unwrap();
+        let by_name = |n: &str| rows.iter().find(|r| r.scientific_name == n).unwrap();
+
+        assert_eq!(rows.len(), 3, "excluded species must not appear");
+        assert_eq!(by_name("Turdus merula").change, PresenceChange::Present);
+        assert_eq!(by_name("Turdus merula").current_count, 2);
+        assert_eq!(by_name("Hirundo rustica").change, PresenceChange::Arrived);
+        assert_eq!(by_name("Fringilla montifringilla").change, PresenceChange::Departed);
+    }
+
+    #[test]
+    fn species_richness_builds_curve_and_estimates() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE detections AS SELECT * FROM (VALUES \
+                ('2025-03-03', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-03-03', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-03-04', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-03-04', 'Erithacus rubecula', 'European Robin', 'birds', 0), \
+                ('2025-03-12', 'Erithacus rubecula', 'European Robin', 'birds', 0), \
+                ('2025-03-12', 'Pipistrellus pipistrellus', 'Common Pipistrelle', 'bats', 0), \
+                ('2025-03-13', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('2025-03-13', 'Pica pica', 'Eurasian Magpie', 'birds', 1), \
+                ('not a date', 'Strix aluco', 'Tawny Owl', 'birds', 0)) \
+             AS t(Date, Sci_Name, Com_Name, Domain, Excluded)",
+        )
+        .unwrap();
+
+        let stats = species_richness_inner(&conn, &exclusion_clause(&[])).unwrap();
+        assert_eq!(stats.sampling_days, 4);
+        let curve: Vec<(&str, u32, u32)> = stats
+            .curve
+            .iter()
+            .map(|p| (p.date.as_str(), p.days, p.species))
+            .collect();
+        assert_eq!(
+            curve,
+            [
+                ("2025-03-03", 1, 1),
+                ("2025-03-04", 2, 2),
+                ("2025-03-12", 3, 3),
+                ("2025-03-13", 4, 3),
+            ]
+        );
+
+        // Blackbird on 3 days, robin on 2, pipistrelle on 1.
+        assert_eq!(stats.total.observed, 3);
+        assert_eq!((stats.total.singletons, stats.total.doubletons), (1, 1));
+        assert_eq!(stats.total.estimated, 3.0);
+        let domains: Vec<(&str, u32)> = stats
+            .domains
+            .iter()
+            .map(|d| (d.domain.as_str(), d.observed))
+            .collect();
+        assert_eq!(domains, [("birds", 2), ("bats", 1)]);
+
+        let weeks: Vec<(&str, usize)> = stats
+            .weekly_new
+            .iter()
+            .map(|w| (w.week_start.as_str(), w.species.len()))
+            .collect();
+        assert_eq!(weeks, [("2025-03-03", 2), ("2025-03-10", 1)]);
+        assert_eq!(stats.weekly_new[0].species, ["Common Blackbird", "European Robin"]);
+
+        // Many species seen once push the estimate above the count.
+        assert!((chao2(10, 4, 1, 10) - 12.7).abs() < 1e-9);
+        assert_eq!(chao2(5, 0, 0, 0), 5.0);
+    }
+
+    #[test]
+    fn node_locations_use_latest_position() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE detections AS SELECT * FROM (VALUES \
+                ('garden', '2025-03-03', '06:00:00', 9.90::DOUBLE, -84.00::DOUBLE, 'Turdus grayi', 0), \
+                ('garden', '2025-03-04', '06:00:00', 9.93::DOUBLE, -84.07::DOUBLE, 'Turdus grayi', 0), \
+                ('garden', '2025-03-04', '07:00:00', 9.93::DOUBLE, -84.07::DOUBLE, 'Pitangus sulphuratus', 0), \
+                ('roof', '2025-03-05', '05:30:00', 10.01::DOUBLE, -84.21::DOUBLE, 'Turdus grayi', 0), \
+                ('roof', '2025-03-05', '05:31:00', 10.01::DOUBLE, -84.21::DOUBLE, 'Pica pica', 1), \
+                ('unset', '2025-03-05', '05:00:00', -1.0::DOUBLE, -1.0::DOUBLE, 'Turdus grayi', 0), \
+                ('placeholder', '2025-03-05', '05:00:00', 0.0::DOUBLE, 0.0::DOUBLE, 'Turdus grayi', 0)) \
+             AS t(Source_Node, Date, Time, Lat, Lon, Sci_Name, Excluded)",
+        )
+        .unwrap();
+
+        let nodes = node_locations_inner(&conn, &exclusion_clause(&[])).unwrap();
+        let summary: Vec<(&str, u64, u32)> = nodes
+            .iter()
+            .map(|n| (n.node.as_str(), n.detections, n.species))
+            .collect();
+        assert_eq!(summary, [("garden", 3, 2), ("roof", 1, 1)]);
+        assert_eq!(nodes[1].last_seen, "2025-03-05");
+        // The garden node moved; it is shown where it recorded last.
+        assert_eq!((nodes[0].lat, nodes[0].lon), (9.93, -84.07));
+
+        let filter = format!(
+            "{} AND Sci_Name = {}",
+            exclusion_clause(&[]),
+            sql_str("Pitangus sulphuratus")
+        );
+        let nodes = node_locations_inner(&conn, &filter).unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!((nodes[0].node.as_str(), nodes[0].detections), ("garden", 1));
+    }
+
+    #[test]
+    fn station_comparison_groups_by_node() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE detections AS SELECT * FROM (VALUES \
+                ('garden', '2025-03-03', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('garden', '2025-03-03', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('garden', '2025-03-04', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('garden', '2025-03-04', 'Erithacus rubecula', 'European Robin', 'birds', 0), \
+                ('garden', '2025-03-05', 'Strix aluco', 'Tawny Owl', 'birds', 0), \
+                ('roof', '2025-03-04', 'Turdus merula', 'Common Blackbird', 'birds', 0), \
+                ('roof', '2025-03-04', 'Pica pica', 'Eurasian Magpie', 'birds', 1), \
+                ('roof', '2025-03-06', 'Pipistrellus pipistrellus', 'Common Pipistrelle', 'bats', 0)) \
+             AS t(Source_Node, Date, Sci_Name, Com_Name, Domain, Excluded)",
+        )
+        .unwrap();
+
+        let cmp = station_comparison_inner(&conn, &exclusion_clause(&[])).unwrap();
+        let stations: Vec<(&str, u64, u32, u32, u32)> = cmp
+            .stations
+            .iter()
+            .map(|s| (s.node.as_str(), s.detections, s.species, s.unique_species, s.days))
+            .collect();
+        assert_eq!(stations, [("garden", 5, 3, 2, 3), ("roof", 2, 2, 1, 2)]);
+        assert!((cmp.stations[0].detections_per_day() - 5.0 / 3.0).abs() < 1e-9);
+        assert_eq!(cmp.stations[1].last_seen, "2025-03-06");
+
+        // Shared species first; counts follow the station order.
+        let matrix: Vec<(&str, &[u64])> = cmp
+            .species
+            .iter()
+            .map(|s| (s.scientific_name.as_str(), s.detections.as_slice()))
+            .collect();
+        assert_eq!(
+            matrix,
+            [
+                ("Turdus merula", &[3, 1][..]),
+                ("Pipistrellus pipistrellus", &[0, 1][..]),
+                ("Erithacus rubecula", &[1, 0][..]),
+                ("Strix aluco", &[1, 0][..]),
+            ]
+        );
+
+        let filter = format!("{} AND Date >= '2025-03-05'", exclusion_clause(&[]));
+        let cmp = station_comparison_inner(&conn, &filter).unwrap();
+        assert_eq!(cmp.stations.len(), 2);
+        assert_eq!(cmp.species.len(), 2);
+    }
+
+    #[test]
+    fn top_predictions_parse_stored_json() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE detections AS SELECT * FROM (VALUES
+                (1::BIGINT, '[{"scientific_name":"Turdus grayi","common_name":"Clay-colored Thrush","confidence":0.91},{"scientific_name":"Turdus assimilis","common_name":"White-throated Thrush","confidence":0.05}]'),
+                (2::BIGINT, NULL)) AS t(id, Top_Predictions)"#,
+        )
+        .unwrap();
+
+        let top = top_predictions_inner(&conn, 1).unwrap();
+        let names: Vec<&str> = top.iter().map(|p| p.scientific_name.as_str()).collect();
+        assert_eq!(names, ["Turdus grayi", "Turdus assimilis"]);
+        assert_eq!(top[1].confidence, 0.05);
+        assert!(top_predictions_inner(&conn, 2).unwrap().is_empty());
+        assert!(top_predictions_inner(&conn, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn species_labels_prefer_the_model() {
+        let dir = make_temp_dir("species-labels");
+        let writer = duckdb::Connection::open_in_memory().unwrap();
+        for (slug, rows) in [
+            (
+                "birdnet",
+                "('Turdus merula', 'Eurasian Blackbird', '{\"de\":\"Amsel\",\"es\":\"Mirlo común\"}'), \
+                 ('Strix varia', NULL, '{}')",
+            ),
+            ("perch", "('Turdus merula', 'Blackbird', '{\"fr\":\"Merle noir\"}')"),
+        ] {
+            let path = escape_sql_path(&dir.join(format!("{slug}.parquet")));
+            writer
+                .execute_batch(&format!(
+                    "COPY (SELECT *, '{slug}' AS Model_Slug FROM (VALUES {rows}) AS t(Sci_Name, Com_Name, Com_Names)) \
+                     TO '{path}' (FORMAT PARQUET)"
+                ))
+                .unwrap();
+        }
+
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        let labels = species_labels_inner(&conn, &dir, "birdnet").unwrap();
+        let names: Vec<&str> = labels.iter().map(|l| l.scientific_name.as_str()).collect();
+        assert_eq!(names, ["Strix varia", "Turdus merula"]);
+        assert_eq!(labels[0].common_name, "Strix varia");
+        assert_eq!(labels[1].localized, ["Amsel", "Mirlo común"]);
+
+        // Unknown model: every model's labels, names merged.
+        let labels = species_labels_inner(&conn, &dir, "").unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[1].common_name, "Eurasian Blackbird");
+        assert_eq!(labels[1].localized, ["Amsel", "Mirlo común", "Merle noir"]);
+
+        assert!(species_labels_inner(&conn, &dir.join("missing"), "birdnet").unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn domain_filter_matches_aggregated_domains() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE species_stats AS SELECT * FROM (VALUES \
+                ('Turdus merula', 'birds'), \
+                ('Pipistrellus pipistrellus', 'bats'), \
+                ('Strix aluco', 'birds,bats'), \
+                ('Unknown', NULL)) \
+             AS t(Sci_Name, Domain)",
+        )
+        .unwrap();
+        let count = |domain: Option<&str>| -> i64 {
+            let sql = format!(
+                "SELECT COUNT(*) FROM species_stats WHERE true {}",
+                domain_filter(domain, "Domain")
+            );
+            conn.query_row(&sql, [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count(None), 4);
+        assert_eq!(count(Some("")), 4);
+        assert_eq!(count(Some("birds")), 2);
+        assert_eq!(count(Some("bats")), 2);
+        assert_eq!(count(Some("bird")), 0);
+        assert_eq!(count(Some("it's")), 0);
+    }
+
+    #[test]
+    fn expected_occurrence_takes_best_model_per_week() {
+        let dir = make_temp_dir("expected-occurrence");
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        assert_eq!(
+            expected_occurrence(&conn, &dir, "Cuculus canorus").unwrap(),
+            ExpectedOccurrence::default()
+        );
+
+        for (slug, rows) in [
+            ("birdnet", "('Cuculus canorus', 15, 0.4), ('Cuculus canorus', 16, 0.6)"),
+            ("regional", "('Cuculus canorus', 16, 0.2), ('Turdus merula', 16, 0.9)"),
+        ] {
+            let path = dir.join(format!("{slug}.parquet"));
+            conn.execute_batch(&format!(
+                "COPY (SELECT Sci_Name, Week::INTEGER AS Week, Score::DOUBLE AS Score, \
+                        46.5::DOUBLE AS Latitude, 7.0::DOUBLE AS Longitude, '{slug}' AS Model_Slug \
+                      FROM (VALUES {rows}) AS t(Sci_Name, Week, Score)) \
+                 TO '{}' (FORMAT PARQUET)",
+                escape_sql_path(&path)
+            ))
+            .unwrap();
+        }
+
+        let cuckoo = expected_occurrence(&conn, &dir, "Cuculus canorus").unwrap();
+        assert_eq!(cuckoo.location, Some((46.5, 7.0)));
+        assert_eq!(cuckoo.model.as_deref(), Some("birdnet"));
+        assert_eq!(cuckoo.scores, vec![(15, 0.4), (16, 0.6)]);
+
+        let unknown = expected_occurrence(&conn, &dir, "Pica pica").unwrap();
+        assert!(unknown.scores.is_empty());
+        assert_eq!(unknown.location, Some((46.5, 7.0)));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn detection_query_where_clause() {
+        let q = DetectionQuery::default();
+        assert_eq!(q.where_clause(&[]), "COALESCE(Excluded, 0) = 0");
+
+        let q = DetectionQuery {
+            after_id: Some(10),
+            date_from: Some("2026-05-01".into()),
+            scientific_name: Some("Dryocopus martius".into()),
+            source_node: Some("o'brien-garden".into()),
+            min_confidence: Some(0.7),
+            include_excluded: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            q.where_clause(&["Turdus merula".into()]),
+            "true AND id > 10 AND Date >= '2026-05-01' AND Sci_Name = 'Dryocopus martius' \
+             AND COALESCE(Source_Node, '') = 'o''brien-garden' AND Confidence >= 0.7"
+        );
+
+        let q = DetectionQuery {
+            ids: Some(vec![3, 7]),
+            include_excluded: true,
+            ..Default::default()
+        };
+        assert_eq!(q.where_clause(&[]), "true AND id IN (3, 7)");
+        let q = DetectionQuery {
+            ids: Some(vec![]),
+            include_excluded: true,
+            ..Default::default()
+        };
+        assert_eq!(q.where_clause(&[]), "true AND false");
+    }
 }
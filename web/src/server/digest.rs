@@ -0,0 +1,513 @@
+//! Weekly digest – a standalone HTML report of one Monday–Sunday week for
+//! posting to a community site or mailing to a nature group.
+//!
+//! The report holds the week's totals against the week before, a daily
+//! activity chart (inline SVG), the top species, species detected for the
+//! first time, and the spectrograms of the best recordings.  Everything
+//! is inlined — styles, chart and images as `data:` URIs — so the single
+//! file can be attached to an email as is.  A print stylesheet lays it
+//! out on A4 for "Print → Save as PDF".
+//!
+//! Reports are written to `{GAIA_DATA_DIR}/digests/` and served from
+//! `/digests/`.  [`run`] writes the report of the last complete week once
+//! it is over; the Settings page can build any week on demand.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use chrono::{Datelike, NaiveDate};
+
+use super::{clips, detections_duckdb as ddb, kv};
+use crate::model::{PresenceChange, SpeciesComparison, WeeklyDigest};
+
+/// Species listed in the top species table.
+const TOP_SPECIES: usize = 15;
+
+/// Species whose best recording is featured with its spectrogram.
+const FEATURED: usize = 4;
+
+/// How often the scheduled job checks whether a week has ended.
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Directory receiving digest reports.
+pub fn digests_dir() -> PathBuf {
+    PathBuf::from(std::env::var("GAIA_DATA_DIR").unwrap_or_else(|_| "/data".into())).join("digests")
+}
+
+/// Monday of the week containing `date`.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+/// Monday of the last week that has fully ended by `today`.
+pub fn last_complete_week(today: NaiveDate) -> NaiveDate {
+    week_start(today) - chrono::Duration::days(7)
+}
+
+/// ISO week label of the week starting `monday`, e.g. `2026-W41`.
+fn week_label(monday: NaiveDate) -> String {
+    let week = monday.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+fn file_name(week: &str) -> String {
+    format!("gaia-digest-{week}.html")
+}
+
+/// Generated reports as `(week, url)`, newest first.
+pub fn list() -> Vec<(String, String)> {
+    let mut weeks: Vec<String> = std::fs::read_dir(digests_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    name.strip_prefix("gaia-digest-")?
+                        .strip_suffix(".html")
+                        .map(String::from)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    weeks.sort_unstable();
+    weeks.reverse();
+    weeks
+        .into_iter()
+        .map(|week| {
+            let url = format!("/digests/{}", file_name(&week));
+            (week, url)
+        })
+        .collect()
+}
+
+/// Build the report of the week starting `monday` and write it to
+/// [`digests_dir`], replacing an earlier one for the same week.
+pub async fn generate(
+    db_path: &Path,
+    extracted_dir: &Path,
+    monday: NaiveDate,
+) -> Result<WeeklyDigest, String> {
+    let monday = week_start(monday);
+    let sunday = monday + chrono::Duration::days(6);
+    let (from, to) = (monday.to_string(), sunday.to_string());
+    let prev_from = (monday - chrono::Duration::days(7)).to_string();
+    let prev_to = (monday - chrono::Duration::days(1)).to_string();
+
+    let comparison = ddb::compare_periods(db_path, (&prev_from, &prev_to), (&from, &to), None)
+        .await
+        .map_err(|e| format!("DB error: {e}"))?;
+    let mut species = comparison.species;
+    let previous_detections = species.iter().map(|s| s.baseline_count).sum();
+    species.retain(|s| s.current_count > 0);
+    if species.is_empty() {
+        return Err(format!("No detections between {from} and {to}"));
+    }
+
+    let query = ddb::DetectionQuery {
+        date_from: Some(from.clone()),
+        date_to: Some(to.clone()),
+        ..Default::default()
+    };
+    let days = ddb::daily_counts(db_path, &query)
+        .await
+        .map_err(|e| format!("DB error: {e}"))?;
+    let days = (0..7)
+        .map(|i| {
+            let date = monday + chrono::Duration::days(i);
+            let count = days
+                .iter()
+                .find(|d| d.date == date.to_string())
+                .map_or(0, |d| d.total_detections);
+            (date, count)
+        })
+        .collect();
+
+    let new_species = ddb::first_seen_between(db_path, &from, &to)
+        .await
+        .map_err(|e| format!("DB error: {e}"))?;
+
+    let names: Vec<String> = species
+        .iter()
+        .take(FEATURED)
+        .map(|s| s.scientific_name.clone())
+        .collect();
+    let best = ddb::best_detections_between(db_path, &from, &to, &names)
+        .await
+        .map_err(|e| format!("DB error: {e}"))?;
+    let featured = best
+        .into_iter()
+        .map(|det| Featured {
+            spectrogram: det
+                .spectrogram_url()
+                .and_then(|url| {
+                    let path = url.strip_prefix("/extracted")?.to_string();
+                    clips::resolve_legacy_path(extracted_dir, &path)
+                })
+                .and_then(|path| std::fs::read(path).ok())
+                .map(|png| format!("data:image/png;base64,{}", B64.encode(png))),
+            common_name: det.common_name,
+            scientific_name: det.scientific_name,
+            confidence: det.confidence,
+            when: format!("{} {}", det.display_date, det.display_time),
+        })
+        .collect();
+
+    let content = DigestContent {
+        week: week_label(monday),
+        monday,
+        days,
+        species,
+        previous_detections,
+        new_species,
+        featured,
+        generated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+    };
+    let html = render(&content);
+
+    let dir = digests_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Create {}: {e}", dir.display()))?;
+    let name = file_name(&content.week);
+    let tmp = dir.join(format!(".{name}.part"));
+    std::fs::write(&tmp, html).map_err(|e| format!("Write {}: {e}", tmp.display()))?;
+    std::fs::rename(&tmp, dir.join(&name)).map_err(|e| format!("Rename report: {e}"))?;
+    tracing::info!("Wrote weekly digest {name}");
+
+    Ok(WeeklyDigest {
+        url: format!("/digests/{name}"),
+        week: content.week,
+        detections: content.days.iter().map(|(_, n)| n).sum(),
+        species: content.species.len() as u32,
+        new_species: content.new_species.len() as u32,
+    })
+}
+
+/// Scheduled job: once a week has ended (in the station's time zone),
+/// write its report unless there already is one.  Runs forever; spawn it
+/// once at startup.
+pub async fn run(db_path: PathBuf, extracted_dir: PathBuf) {
+    let mut attempted = None;
+    loop {
+        let today = NaiveDate::parse_from_str(&kv::today_for_tz().await, "%Y-%m-%d")
+            .unwrap_or_else(|_| chrono::Utc::now().date_naive());
+        let monday = last_complete_week(today);
+        let exists = digests_dir().join(file_name(&week_label(monday))).exists();
+        if !exists && attempted != Some(monday) {
+            attempted = Some(monday);
+            if let Err(e) = generate(&db_path, &extracted_dir, monday).await {
+                tracing::info!("No weekly digest for {}: {e}", week_label(monday));
+            }
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+// ─── Rendering ───────────────────────────────────────────────────────────────
+
+/// Best recording of a top species.
+struct Featured {
+    common_name: String,
+    scientific_name: String,
+    confidence: f64,
+    when: String,
+    /// Spectrogram as a `data:` URI, when the file is still on disk.
+    spectrogram: Option<String>,
+}
+
+/// Everything shown in a report.
+struct DigestContent {
+    week: String,
+    monday: NaiveDate,
+    /// Detections per day, Monday first.
+    days: Vec<(NaiveDate, u32)>,
+    /// Species detected this week, most detections first, with last
+    /// week's counts.
+    species: Vec<SpeciesComparison>,
+    previous_detections: u32,
+    /// `(Sci_Name, Com_Name, first date)`.
+    new_species: Vec<(String, String, String)>,
+    featured: Vec<Featured>,
+    generated_at: String,
+}
+
+const STYLE: &str = "\
+body{font-family:system-ui,-apple-system,'Segoe UI',sans-serif;color:#1f2a1f;\
+background:#f6f7f2;margin:0;padding:24px}\
+main{max-width:760px;margin:0 auto;background:#fff;padding:28px 32px;border-radius:8px}\
+h1{margin:0;font-size:1.6em}h2{font-size:1.15em;margin:28px 0 10px;color:#2e5a2e}\
+.range{color:#667;margin:4px 0 18px}\
+.totals{display:flex;gap:24px;flex-wrap:wrap}.totals div{font-size:.9em;color:#556}\
+.totals b{display:block;font-size:1.6em;color:#1f2a1f}\
+table{width:100%;border-collapse:collapse;font-size:.92em}\
+td{padding:4px 6px;border-bottom:1px solid #eee;vertical-align:middle}\
+td.n{text-align:right;white-space:nowrap}i{color:#667}\
+.bar{background:#7aa874;height:10px;border-radius:2px}\
+.up{color:#2e7d32}.down{color:#b23b3b}.new{color:#1565c0;font-weight:600}\
+.featured{display:grid;grid-template-columns:1fr 1fr;gap:14px}\
+figure{margin:0}figure img{width:100%;border-radius:4px;background:#000}\
+figcaption{font-size:.85em;margin-top:4px}\
+footer{margin-top:28px;font-size:.8em;color:#889}\
+@page{size:A4;margin:14mm}\
+@media print{body{background:#fff;padding:0}main{padding:0;max-width:none}\
+h2,figure,tr{break-inside:avoid}}";
+
+/// The report as a standalone HTML document.
+fn render(d: &DigestContent) -> String {
+    let sunday = d.monday + chrono::Duration::days(6);
+    let detections: u32 = d.days.iter().map(|(_, n)| n).sum();
+    let mut html = String::with_capacity(16 * 1024);
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>Gaia Audio weekly digest {week}</title><style>{STYLE}</style></head>\
+         <body><main><h1>Weekly digest</h1><p class=\"range\">{from} – {to} ({week})</p>",
+        week = d.week,
+        from = d.monday.format("%a %-d %b"),
+        to = sunday.format("%a %-d %b %Y"),
+    );
+
+    let _ = write!(
+        html,
+        "<section class=\"totals\"><div><b>{detections}</b>detections {}</div>\
+         <div><b>{}</b>species</div><div><b>{}</b>new to the station</div></section>",
+        change_vs(d.previous_detections, detections),
+        d.species.len(),
+        d.new_species.len(),
+    );
+
+    html.push_str("<h2>Daily activity</h2>");
+    html.push_str(&bar_chart(&d.days));
+
+    html.push_str("<h2>Top species</h2><table>");
+    let max = d.species.first().map_or(1, |s| s.current_count.max(1));
+    for s in d.species.iter().take(TOP_SPECIES) {
+        let trend = match s.change {
+            PresenceChange::Arrived => "<span class=\"new\">new this week</span>".to_string(),
+            _ if s.current_count > s.baseline_count => {
+                format!(
+                    "<span class=\"up\">▲ {}</span>",
+                    s.current_count - s.baseline_count
+                )
+            }
+            _ if s.current_count < s.baseline_count => {
+                format!(
+                    "<span class=\"down\">▼ {}</span>",
+                    s.baseline_count - s.current_count
+                )
+            }
+            _ => "=".to_string(),
+        };
+        let _ = write!(
+            html,
+            "<tr><td>{} <i>{}</i></td><td style=\"width:30%\">\
+             <div class=\"bar\" style=\"width:{:.0}%\"></div></td>\
+             <td class=\"n\">{}</td><td class=\"n\">{trend}</td></tr>",
+            escape(&s.common_name),
+            escape(&s.scientific_name),
+            f64::from(s.current_count) * 100.0 / f64::from(max),
+            s.current_count,
+        );
+    }
+    html.push_str("</table>");
+    if d.species.len() > TOP_SPECIES {
+        let _ = write!(
+            html,
+            "<p><i>… and {} more species.</i></p>",
+            d.species.len() - TOP_SPECIES
+        );
+    }
+
+    if !d.new_species.is_empty() {
+        html.push_str("<h2>New to the station</h2><table>");
+        for (sci, com, first) in &d.new_species {
+            let first = NaiveDate::parse_from_str(first, "%Y-%m-%d")
+                .map_or_else(|_| first.clone(), |day| day.format("%A").to_string());
+            let _ = write!(
+                html,
+                "<tr><td>{} <i>{}</i></td><td class=\"n\">first heard {}</td></tr>",
+                escape(com),
+                escape(sci),
+                escape(&first),
+            );
+        }
+        html.push_str("</table>");
+    }
+
+    let with_image: Vec<&Featured> = d
+        .featured
+        .iter()
+        .filter(|f| f.spectrogram.is_some())
+        .collect();
+    if !with_image.is_empty() {
+        html.push_str("<h2>Best recordings</h2><div class=\"featured\">");
+        for f in with_image {
+            let _ = write!(
+                html,
+                "<figure><img src=\"{}\" alt=\"Spectrogram of {name}\">\
+                 <figcaption><b>{name}</b> <i>{}</i><br>{} · {:.0}% confidence</figcaption></figure>",
+                f.spectrogram.as_deref().unwrap_or_default(),
+                escape(&f.scientific_name),
+                escape(&f.when),
+                f.confidence * 100.0,
+                name = escape(&f.common_name),
+            );
+        }
+        html.push_str("</div>");
+    }
+
+    let _ = writeln!(
+        html,
+        "<footer>Generated by Gaia Audio on {}.</footer></main></body></html>",
+        escape(&d.generated_at)
+    );
+    html
+}
+
+/// "(+12% on last week)" style comparison, empty without a baseline.
+fn change_vs(previous: u32, current: u32) -> String {
+    if previous == 0 {
+        return String::new();
+    }
+    let pct = (f64::from(current) - f64::from(previous)) * 100.0 / f64::from(previous);
+    format!("({pct:+.0}% on last week)")
+}
+
+/// Detections per day as an inline SVG bar chart.
+fn bar_chart(days: &[(NaiveDate, u32)]) -> String {
+    const W: usize = 700;
+    const H: usize = 160;
+    const LABEL: usize = 20;
+    let max = days.iter().map(|(_, n)| *n).max().unwrap_or(0).max(1);
+    let slot = W / days.len().max(1);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {W} {}\" width=\"100%\" \
+         role=\"img\" aria-label=\"Detections per day\" font-size=\"12\" fill=\"#556\">",
+        H + LABEL
+    );
+    for (i, (date, n)) in days.iter().enumerate() {
+        let h = (*n as usize * (H - 16)) / max as usize;
+        let x = i * slot + slot / 6;
+        let w = slot * 2 / 3;
+        let mid = i * slot + slot / 2;
+        let _ = write!(
+            svg,
+            "<rect x=\"{x}\" y=\"{}\" width=\"{w}\" height=\"{h}\" rx=\"2\" fill=\"#7aa874\"/>\
+             <text x=\"{mid}\" y=\"{}\" text-anchor=\"middle\">{n}</text>\
+             <text x=\"{mid}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            H - h,
+            (H - h).saturating_sub(4).max(12),
+            H + LABEL - 4,
+            date.format("%a"),
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Escape text for HTML element content and attribute values.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn species(com: &str, baseline_count: u32, current_count: u32) -> SpeciesComparison {
+        SpeciesComparison {
+            scientific_name: format!("{com} sci"),
+            common_name: com.into(),
+            domain: "birds".into(),
+            baseline_count,
+            current_count,
+            change: PresenceChange::from_counts(baseline_count, current_count),
+        }
+    }
+
+    #[test]
+    fn test_weeks() {
+        // 2026-10-16 is a Friday.
+        assert_eq!(week_start(date("2026-10-16")), date("2026-10-12"));
+        assert_eq!(week_start(date("2026-10-12")), date("2026-10-12"));
+        assert_eq!(week_start(date("2026-10-18")), date("2026-10-12"));
+        assert_eq!(last_complete_week(date("2026-10-16")), date("2026-10-05"));
+        assert_eq!(last_complete_week(date("2026-10-19")), date("2026-10-12"));
+        assert_eq!(week_label(date("2026-10-12")), "2026-W42");
+        // ISO weeks belong to the year of their Thursday.
+        assert_eq!(week_label(date("2026-12-28")), "2026-W53");
+        assert_eq!(week_label(date("2027-01-04")), "2027-W01");
+    }
+
+    #[test]
+    fn test_render() {
+        let monday = date("2026-10-12");
+        let content = DigestContent {
+            week: week_label(monday),
+            monday,
+            days: (0..7)
+                .map(|i| (monday + chrono::Duration::days(i), i as u32 * 3))
+                .collect(),
+            species: vec![
+                species("Eurasian Blackbird", 10, 40),
+                species("Robin <script>", 0, 5),
+                species("Great Tit", 9, 4),
+            ],
+            previous_detections: 21,
+            new_species: vec![(
+                "Parus major".into(),
+                "Great Tit".into(),
+                "2026-10-14".into(),
+            )],
+            featured: vec![
+                Featured {
+                    common_name: "Eurasian Blackbird".into(),
+                    scientific_name: "Turdus merula".into(),
+                    confidence: 0.934,
+                    when: "2026-10-13 05:41:02".into(),
+                    spectrogram: Some("data:image/png;base64,AAAA".into()),
+                },
+                Featured {
+                    common_name: "Great Tit".into(),
+                    scientific_name: "Parus major".into(),
+                    confidence: 0.8,
+                    when: String::new(),
+                    spectrogram: None,
+                },
+            ],
+            generated_at: "2026-10-19 06:00 UTC".into(),
+        };
+        let html = render(&content);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Mon 12 Oct – Sun 18 Oct 2026 (2026-W42)"));
+        assert!(html.contains("<b>63</b>detections (+200% on last week)"));
+        assert!(html.contains("Robin &lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("new this week"));
+        assert!(html.contains("▲ 30"));
+        assert!(html.contains("▼ 5"));
+        assert!(html.contains("first heard Wednesday"));
+        assert!(html.contains("data:image/png;base64,AAAA"));
+        assert!(html.contains("93% confidence"));
+        // Recordings without a spectrogram on disk are left out.
+        assert_eq!(html.matches("<figure>").count(), 1);
+        assert_eq!(html.matches("<rect").count(), 7);
+    }
+}
@@ -0,0 +1,139 @@
+//! eBird Record Format export – a day's detections as stationary
+//! checklists, ready for upload at <https://ebird.org/import/upload.form>.
+//!
+//! The day is cut into fixed checklist periods.  eBird counts are
+//! individuals, not calls, so a species is reported once per period
+//! unless several capture nodes heard it within the same minute.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use super::csv_field;
+
+/// Checklist comment attached to every exported checklist.
+const CHECKLIST_COMMENT: &str = "Automated acoustic detections (Gaia Audio)";
+
+/// One species within one checklist period.
+#[derive(Debug, Clone)]
+pub struct EbirdEntry {
+    /// Index of the checklist period within the day.
+    pub period: u32,
+    pub scientific_name: String,
+    pub common_name: String,
+    /// Individuals reported: the most capture nodes that detected the
+    /// species within a single minute of the period.
+    pub count: u32,
+    pub detections: u32,
+    pub max_confidence: f64,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Build the eBird Record Format (extended) CSV for `date`.
+///
+/// `period_minutes` must match the value the entries were grouped with;
+/// `tz_offset` (hours) converts the stored times to the local time eBird
+/// expects.  The format has no header row.
+pub fn checklist_csv(
+    date: &str,
+    period_minutes: u32,
+    tz_offset: i32,
+    entries: &[EbirdEntry],
+) -> Result<String, String> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {date}"))?;
+    let period_minutes = period_minutes.clamp(1, 24 * 60);
+
+    let mut out = String::new();
+    for chunk in entries.chunk_by(|a, b| a.period == b.period) {
+        let start_min = chunk[0].period * period_minutes;
+        let duration = period_minutes.min(24 * 60 - start_min.min(24 * 60));
+        let start = NaiveDateTime::new(day, NaiveTime::MIN)
+            + Duration::minutes(i64::from(start_min))
+            + Duration::hours(i64::from(tz_offset));
+
+        // Every row of a checklist must share the location, or eBird
+        // splits it; use the detection-weighted centre of the period.
+        let weight: f64 = chunk.iter().map(|e| f64::from(e.detections.max(1))).sum();
+        let lat = chunk.iter().map(|e| e.latitude * f64::from(e.detections.max(1))).sum::<f64>() / weight;
+        let lon = chunk.iter().map(|e| e.longitude * f64::from(e.detections.max(1))).sum::<f64>() / weight;
+
+        for e in chunk {
+            let (genus, species) = e
+                .scientific_name
+                .split_once(' ')
+                .unwrap_or((e.scientific_name.as_str(), ""));
+            let comment = format!(
+                "{} detection(s), best confidence {:.0}%",
+                e.detections,
+                e.max_confidence * 100.0
+            );
+            let fields = [
+                e.common_name.clone(),
+                genus.to_string(),
+                species.to_string(),
+                e.count.max(1).to_string(),
+                comment,
+                "Gaia Audio station".to_string(),
+                format!("{lat:.6}"),
+                format!("{lon:.6}"),
+                start.format("%m/%d/%Y").to_string(),
+                start.format("%H:%M").to_string(),
+                String::new(),
+                String::new(),
+                "Stationary".to_string(),
+                "1".to_string(),
+                duration.to_string(),
+                // Only what the models recognised is listed.
+                "N".to_string(),
+                String::new(),
+                String::new(),
+                CHECKLIST_COMMENT.to_string(),
+            ];
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&line.join(","));
+            out.push_str("\r\n");
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(period: u32, sci: &str, com: &str, count: u32) -> EbirdEntry {
+        EbirdEntry {
+            period,
+            scientific_name: sci.to_string(),
+            common_name: com.to_string(),
+            count,
+            detections: 4,
+            max_confidence: 0.91,
+            latitude: 9.9,
+            longitude: -84.1,
+        }
+    }
+
+    #[test]
+    fn test_checklist_csv() {
+        let entries = vec![
+            entry(5, "Turdus grayi", "Clay-colored Thrush", 1),
+            entry(5, "Pitangus sulphuratus", "Great Kiskadee, local", 2),
+            entry(23, "Tyto alba", "Barn Owl", 0),
+        ];
+        let csv = checklist_csv("2026-03-01", 60, -6, &entries).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "Clay-colored Thrush,Turdus,grayi,1,\"4 detection(s), best confidence 91%\",\
+             Gaia Audio station,9.900000,-84.100000,02/28/2026,23:00,,,Stationary,1,60,N,,,\
+             Automated acoustic detections (Gaia Audio)"
+        );
+        assert!(lines[1].starts_with("\"Great Kiskadee, local\",Pitangus,sulphuratus,2,"));
+        // Zero counts are reported as one individual.
+        assert!(lines[2].starts_with("Barn Owl,Tyto,alba,1,"));
+        assert!(lines[2].contains(",03/01/2026,17:00,"));
+        assert!(checklist_csv("yesterday", 60, 0, &entries).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! Client for the processing server's folder import API.
+//!
+//! The processing server analyses the audio folders below its
+//! `IMPORT_DIR` (see `/api/import` there); the dashboard only lists them,
+//! starts an import and follows its progress.  The server is found at
+//! `PROCESSING_SERVER_URL` (default `http://localhost:8090`) and the
+//! start request carries `CAPTURE_AUTH_TOKEN`, the token uploads use.
+
+use gaia_client::protocol::{FolderImportJob, FolderImportRequest, FolderImports};
+
+/// Base URL of the processing REST API, without a trailing slash.
+pub fn base_url() -> String {
+    std::env::var("PROCESSING_SERVER_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "http://localhost:8090".into())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Importable folders and the current import.
+pub async fn status() -> Result<FolderImports, String> {
+    let base = base_url();
+    let resp = client()?
+        .get(format!("{base}/api/import"))
+        .send()
+        .await
+        .map_err(|e| format!("Processing server unreachable at {base}: {e}"))?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err("Folder imports are off: set IMPORT_DIR on the processing server".into());
+    }
+    json(resp).await
+}
+
+/// Start importing `folder`, storing detections under `node` (empty for
+/// the server's default).
+pub async fn start(folder: String, node: String) -> Result<FolderImportJob, String> {
+    let base = base_url();
+    let mut req = client()?
+        .post(format!("{base}/api/import"))
+        .json(&FolderImportRequest { folder, node });
+    if let Some(token) = std::env::var("CAPTURE_AUTH_TOKEN").ok().filter(|t| !t.is_empty()) {
+        req = req.bearer_auth(token);
+    }
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| format!("Processing server unreachable at {base}: {e}"))?;
+    json(resp).await
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("HTTP client error: {e}"))
+}
+
+async fn json<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> Result<T, String> {
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Processing server returned {status}: {body}"));
+    }
+    resp.json::<T>()
+        .await
+        .map_err(|e| format!("Bad response from the processing server: {e}"))
+}
@@ -0,0 +1,430 @@
+//! Read-only GraphQL endpoint over the detection store.
+//!
+//! Served at `/graphql` (POST for queries, GET for the GraphiQL
+//! explorer).  It exposes the same DuckDB view the dashboard uses, so
+//! external tools and advanced users can compose their own queries
+//! instead of waiting for a bespoke server function:
+//!
+//! ```graphql
+//! {
+//!   species(limit: 5) {
+//!     scientificName
+//!     totalDetections
+//!     dailyCounts(dateFrom: "2026-05-01") { date totalDetections }
+//!   }
+//!   nodes { name lastSeen detections(limit: 3) { commonName confidence } }
+//! }
+//! ```
+//!
+//! There is no mutation root; depth and complexity limits keep nested
+//! queries from turning into full table scans per parent row.
+
+use std::path::PathBuf;
+
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema,
+    SimpleObject,
+};
+
+use super::detections_duckdb::{self as duck, DetectionQuery};
+use crate::model::{CalendarDay, SpeciesSummary, WebDetection};
+
+/// Upper bound for every `limit` argument.
+const MAX_LIMIT: i32 = 1000;
+const DEFAULT_LIMIT: i32 = 50;
+
+pub type GaiaSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the schema; `db_path` is the same path handed to `AppState`.
+pub fn schema(db_path: PathBuf) -> GaiaSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(DbPath(db_path))
+        .limit_depth(6)
+        .limit_complexity(500)
+        .finish()
+}
+
+/// GraphiQL explorer page served on `GET /graphql`.
+pub async fn graphiql() -> axum::response::Html<String> {
+    axum::response::Html(
+        async_graphql::http::GraphiQLSource::build()
+            .endpoint("/graphql")
+            .finish(),
+    )
+}
+
+struct DbPath(PathBuf);
+
+fn db_path<'a>(ctx: &'a Context<'_>) -> &'a std::path::Path {
+    &ctx.data_unchecked::<DbPath>().0
+}
+
+fn clamp_limit(limit: Option<i32>) -> u32 {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as u32
+}
+
+fn gql_err(e: impl std::fmt::Display) -> async_graphql::Error {
+    async_graphql::Error::new(e.to_string())
+}
+
+// ─── Filters ─────────────────────────────────────────────────────────────────
+
+/// Optional filters for `detections` and `dailyCounts`.
+#[derive(InputObject, Default)]
+pub struct DetectionFilter {
+    /// Only detections with a larger id, returned oldest first.
+    after_id: Option<i64>,
+    /// Only detections with a smaller id, returned newest first.
+    before_id: Option<i64>,
+    /// Inclusive `YYYY-MM-DD` (UTC) bounds.
+    date_from: Option<String>,
+    date_to: Option<String>,
+    scientific_name: Option<String>,
+    domain: Option<String>,
+    model_slug: Option<String>,
+    node: Option<String>,
+    min_confidence: Option<f64>,
+    /// Include detections excluded by the species-range model.
+    #[graphql(default)]
+    include_excluded: bool,
+}
+
+impl From<DetectionFilter> for DetectionQuery {
+    fn from(f: DetectionFilter) -> Self {
+        DetectionQuery {
+            after_id: f.after_id,
+            before_id: f.before_id,
+            date_from: f.date_from,
+            date_to: f.date_to,
+            scientific_name: f.scientific_name,
+            domain: f.domain,
+            model_slug: f.model_slug,
+            source_node: f.node,
+            min_confidence: f.min_confidence,
+            include_excluded: f.include_excluded,
+            ids: None,
+        }
+    }
+}
+
+// ─── Output types ────────────────────────────────────────────────────────────
+
+#[derive(SimpleObject)]
+pub struct Detection {
+    id: i64,
+    domain: String,
+    scientific_name: String,
+    common_name: String,
+    confidence: f64,
+    /// Stored (UTC) date and time.
+    date: String,
+    time: String,
+    /// Date and time shifted by the dashboard's timezone offset.
+    display_date: String,
+    display_time: String,
+    file_name: String,
+    /// Clip URL relative to the dashboard root, if the clip was kept.
+    clip_url: Option<String>,
+    node: String,
+    excluded: bool,
+    model_slug: String,
+    model_name: String,
+    /// Model version, download variant and inference runtime; empty for
+    /// detections stored before they were recorded.
+    model_version: String,
+    model_variant: String,
+    model_runtime: String,
+    /// Input channel (0-based) when channels were analysed separately.
+    channel: Option<u16>,
+    /// Compass bearing in degrees (0 = north, clockwise), when estimated.
+    bearing_deg: Option<f64>,
+    agreement_score: f64,
+    agreement_models: Vec<String>,
+    peak_freq_hz: Option<f64>,
+    bandwidth_hz: Option<f64>,
+    call_duration_secs: Option<f64>,
+    /// Regional rarity (0.0 common – 1.0 never reported), when scored.
+    rarity: Option<f64>,
+    rare: bool,
+}
+
+impl From<WebDetection> for Detection {
+    fn from(d: WebDetection) -> Self {
+        Detection {
+            clip_url: d.clip_url(),
+            agreement_models: d
+                .agreement_models
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+            id: d.id,
+            domain: d.domain,
+            scientific_name: d.scientific_name,
+            common_name: d.common_name,
+            confidence: d.confidence,
+            date: d.date,
+            time: d.time,
+            display_date: d.display_date,
+            display_time: d.display_time,
+            file_name: d.file_name,
+            node: d.source_node,
+            excluded: d.excluded,
+            model_slug: d.model_slug,
+            model_name: d.model_name,
+            model_version: d.model_version,
+            model_variant: d.model_variant,
+            model_runtime: d.model_runtime,
+            channel: d.channel,
+            bearing_deg: d.bearing_deg,
+            agreement_score: d.agreement_score,
+            peak_freq_hz: d.peak_freq_hz,
+            bandwidth_hz: d.bandwidth_hz,
+            call_duration_secs: d.call_duration_secs,
+            rarity: d.rarity,
+            rare: d.rare,
+        }
+    }
+}
+
+/// Detections and distinct species on one day.
+#[derive(SimpleObject)]
+pub struct DailyCount {
+    date: String,
+    total_detections: u32,
+    unique_species: u32,
+}
+
+impl From<CalendarDay> for DailyCount {
+    fn from(d: CalendarDay) -> Self {
+        DailyCount {
+            date: d.date,
+            total_detections: d.total_detections,
+            unique_species: d.unique_species,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Species {
+    scientific_name: String,
+    common_name: String,
+    domain: String,
+    total_detections: u32,
+    last_seen: Option<String>,
+}
+
+impl From<SpeciesSummary> for Species {
+    fn from(s: SpeciesSummary) -> Self {
+        Species {
+            scientific_name: s.scientific_name,
+            common_name: s.common_name,
+            domain: s.domain,
+            total_detections: s.detection_count,
+            last_seen: s.last_seen,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Species {
+    /// This species' detections; `filter.scientificName` is ignored.
+    async fn detections(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<DetectionFilter>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<Detection>> {
+        let mut query: DetectionQuery = filter.unwrap_or_default().into();
+        query.scientific_name = Some(self.scientific_name.clone());
+        detections(ctx, &query, limit).await
+    }
+
+    async fn daily_counts(
+        &self,
+        ctx: &Context<'_>,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> async_graphql::Result<Vec<DailyCount>> {
+        let query = DetectionQuery {
+            scientific_name: Some(self.scientific_name.clone()),
+            date_from,
+            date_to,
+            ..Default::default()
+        };
+        daily_counts(ctx, &query).await
+    }
+}
+
+/// A capture node, as recorded in each detection's `Source_Node`.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Node {
+    name: String,
+    total_detections: u64,
+    unique_species: u64,
+    first_seen: String,
+    last_seen: String,
+}
+
+#[ComplexObject]
+impl Node {
+    /// This node's detections; `filter.node` is ignored.
+    async fn detections(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<DetectionFilter>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<Detection>> {
+        let mut query: DetectionQuery = filter.unwrap_or_default().into();
+        query.source_node = Some(self.name.clone());
+        detections(ctx, &query, limit).await
+    }
+
+    async fn daily_counts(
+        &self,
+        ctx: &Context<'_>,
+        date_from: Option<String>,
+        date_to: Option<String>,
+    ) -> async_graphql::Result<Vec<DailyCount>> {
+        let query = DetectionQuery {
+            source_node: Some(self.name.clone()),
+            date_from,
+            date_to,
+            ..Default::default()
+        };
+        daily_counts(ctx, &query).await
+    }
+}
+
+async fn detections(
+    ctx: &Context<'_>,
+    query: &DetectionQuery,
+    limit: Option<i32>,
+) -> async_graphql::Result<Vec<Detection>> {
+    let rows = duck::query_detections(db_path(ctx), query, clamp_limit(limit))
+        .await
+        .map_err(gql_err)?;
+    Ok(rows.into_iter().map(Detection::from).collect())
+}
+
+async fn daily_counts(
+    ctx: &Context<'_>,
+    query: &DetectionQuery,
+) -> async_graphql::Result<Vec<DailyCount>> {
+    let days = duck::daily_counts(db_path(ctx), query).await.map_err(gql_err)?;
+    Ok(days.into_iter().map(DailyCount::from).collect())
+}
+
+// ─── Query root ──────────────────────────────────────────────────────────────
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Detections matching `filter`, newest first.
+    async fn detections(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<DetectionFilter>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<Detection>> {
+        detections(ctx, &filter.unwrap_or_default().into(), limit).await
+    }
+
+    async fn detection(&self, ctx: &Context<'_>, id: i64) -> async_graphql::Result<Option<Detection>> {
+        let query = DetectionQuery {
+            after_id: Some(id - 1),
+            before_id: Some(id + 1),
+            include_excluded: true,
+            ..Default::default()
+        };
+        Ok(detections(ctx, &query, Some(1)).await?.into_iter().next())
+    }
+
+    /// Species by all-time detection count, optionally for one model.
+    async fn species(
+        &self,
+        ctx: &Context<'_>,
+        model_slug: Option<String>,
+        domain: Option<String>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<Species>> {
+        let all = duck::top_species_filtered(db_path(ctx), u32::MAX, model_slug.as_deref(), None)
+            .await
+            .map_err(gql_err)?;
+        Ok(all
+            .into_iter()
+            .filter(|s| domain.as_ref().map_or(true, |d| &s.domain == d))
+            .take(clamp_limit(limit) as usize)
+            .map(Species::from)
+            .collect())
+    }
+
+    /// One species by scientific name.
+    async fn species_by_name(
+        &self,
+        ctx: &Context<'_>,
+        scientific_name: String,
+    ) -> async_graphql::Result<Option<Species>> {
+        let info = duck::species_info(db_path(ctx), &scientific_name)
+            .await
+            .map_err(gql_err)?;
+        Ok(info.map(|i| Species {
+            scientific_name: i.scientific_name,
+            common_name: i.common_name,
+            domain: i.domain,
+            total_detections: i.total_detections as u32,
+            last_seen: i.last_seen,
+        }))
+    }
+
+    /// Per-day totals for detections matching `filter`.
+    async fn daily_counts(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<DetectionFilter>,
+    ) -> async_graphql::Result<Vec<DailyCount>> {
+        daily_counts(ctx, &filter.unwrap_or_default().into()).await
+    }
+
+    /// Capture nodes that have produced detections, busiest first.
+    async fn nodes(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Node>> {
+        let nodes = duck::node_stats(db_path(ctx)).await.map_err(gql_err)?;
+        Ok(nodes
+            .into_iter()
+            .map(|n| Node {
+                name: n.name,
+                total_detections: n.total_detections,
+                unique_species: n.unique_species,
+                first_seen: n.first_seen,
+                last_seen: n.last_seen,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_is_read_only() {
+        let sdl = schema(PathBuf::from("/nonexistent/birds.db")).sdl();
+        assert!(sdl.contains("type QueryRoot"));
+        assert!(sdl.contains("dailyCounts(dateFrom: String, dateTo: String): [DailyCount!]!"));
+        assert!(!sdl.contains("type Mutation"));
+        assert!(!sdl.contains("type Subscription"));
+    }
+
+    #[tokio::test]
+    async fn depth_limit_rejects_deep_queries() {
+        let schema = schema(PathBuf::from("/nonexistent/birds.db"));
+        let res = schema
+            .execute("{ __schema { types { fields { type { ofType { ofType { ofType { name } } } } } } } }")
+            .await;
+        assert!(!res.errors.is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+//! `GET /api/health` – liveness and version of the dashboard, like the
+//! capture and processing servers' routes of the same name, plus its
+//! mDNS advertisement.
+
+use std::time::Instant;
+
+use axum::Json;
+use gaia_client::protocol::{WebHealth, PROTOCOL_VERSION};
+use gaia_common::discovery::{self, ServiceRole};
+
+/// Health of the dashboard running since `started`.
+pub async fn handler(started: Instant) -> Json<WebHealth> {
+    Json(WebHealth {
+        status: "ok".to_string(),
+        uptime_secs: started.elapsed().as_secs(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol: PROTOCOL_VERSION,
+    })
+}
+
+/// Advertise the dashboard on `port` via mDNS, with its version, for the
+/// life of the process.  Skipped when `GAIA_DISABLE_MDNS` is set.
+pub fn advertise(port: u16) {
+    if std::env::var("GAIA_DISABLE_MDNS").is_ok() {
+        return;
+    }
+    // Registration scans the network for a few seconds first.
+    tokio::task::spawn_blocking(
+        move || match discovery::register(ServiceRole::Web, port, &[]) {
+            Ok(handle) => {
+                tracing::info!("mDNS: registered as {}", handle.instance_name());
+                // Dropping the handle would withdraw the advertisement.
+                std::mem::forget(handle);
+            }
+            Err(e) => tracing::warn!("mDNS registration failed (non-fatal): {e:#}"),
+        },
+    );
+}
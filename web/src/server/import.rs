@@ -11,13 +11,24 @@
 //! Both modes handle deduplication: detections and audio clips that have
 //! already been imported — even if subsequently compressed from `.mp3`/`.wav`
 //! to `.opus` by the processing server — are detected and skipped.
-
-use std::collections::HashSet;
+//!
+//! Imports run as background jobs ([`start_job`]) whose progress is polled
+//! by the import page.  A cancelled or failed job can be resumed: the
+//! backup is read again from the start and everything already imported is
+//! skipped by the same deduplication.  Media files are unpacked under a
+//! temporary name and renamed, so an interrupted import never leaves a
+//! half-written clip that would later count as imported.
+
+use std::collections::{HashMap, HashSet};
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use libsql::params;
 
+use super::birdnet_migration;
+
 /// Pre-import report – shows what the backup contains before committing.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ImportReport {
@@ -48,25 +59,259 @@ pub struct ImportReport {
     pub top_species: Vec<(String, u64)>,
 }
 
-/// Progress report emitted during import.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ImportProgress {
-    pub detections_imported: u64,
-    pub files_extracted: u64,
-    pub phase: String,
+
+// ─── Shared model types (node, progress, result) ─────────────────────────────
+pub use crate::model::{BirdnetNode, ImportProgress, ImportResult, ImportState};
+
+// ─── Background jobs ─────────────────────────────────────────────────────────
+
+/// Error returned by an import stopped through [`cancel_job`].
+const CANCELLED: &str = "Import cancelled";
+
+/// How often (in tar entries / source rows) a job publishes its totals.
+const REPORT_EVERY: u64 = 100;
+
+/// Where a job reads its backup from; kept so the job can be resumed.
+#[derive(Debug, Clone)]
+pub enum ImportSource {
+    /// A `.tar` backup on disk.
+    Tar(PathBuf),
+    /// A BirdNET-Pi node, streamed from its `backup.php`.
+    Node {
+        address: String,
+        port: u16,
+        username: String,
+        password: String,
+    },
 }
 
-/// Result of a completed import.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ImportResult {
-    pub detections_imported: u64,
-    pub files_extracted: u64,
-    pub skipped_existing: u64,
-    pub errors: Vec<String>,
+impl ImportSource {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Tar(path) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string()),
+            Self::Node { address, port, .. } => format!("{address}:{port}"),
+        }
+    }
+}
+
+/// A background import and its live progress.
+pub struct ImportJob {
+    source: ImportSource,
+    db_path: PathBuf,
+    extracted_dir: PathBuf,
+    cancel: AtomicBool,
+    /// Archive bytes read in the current phase (see [`CountingReader`]).
+    bytes_read: Arc<AtomicU64>,
+    progress: Mutex<ImportProgress>,
+}
+
+impl ImportJob {
+    fn new(id: String, source: ImportSource, db_path: PathBuf, extracted_dir: PathBuf) -> Self {
+        let progress = ImportProgress {
+            job_id: id,
+            source: source.label(),
+            state: ImportState::Running,
+            phase: "Starting".into(),
+            detections_imported: 0,
+            files_extracted: 0,
+            skipped_existing: 0,
+            error_count: 0,
+            bytes_read: 0,
+            bytes_total: None,
+            result: None,
+            error: None,
+        };
+        Self {
+            source,
+            db_path,
+            extracted_dir,
+            cancel: AtomicBool::new(false),
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            progress: Mutex::new(progress),
+        }
+    }
+
+    /// Current progress.
+    pub fn snapshot(&self) -> ImportProgress {
+        let mut p = match self.progress.lock() {
+            Ok(p) => p.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        p.bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        p
+    }
+
+    fn update(&self, f: impl FnOnce(&mut ImportProgress)) {
+        if let Ok(mut p) = self.progress.lock() {
+            f(&mut p);
+        }
+    }
+
+    /// `Err` once the job has been cancelled; checked between tar entries
+    /// and source rows.
+    fn check_cancelled(&self) -> Result<(), String> {
+        if self.cancel.load(Ordering::Relaxed) {
+            Err(CANCELLED.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Enter a new phase; `bytes_total` is the archive size when known.
+    fn start_phase(&self, phase: &str, bytes_total: Option<u64>) {
+        tracing::info!("Import {}: {phase}…", self.source.label());
+        self.bytes_read.store(0, Ordering::Relaxed);
+        self.update(|p| {
+            p.phase = phase.to_string();
+            p.bytes_total = bytes_total;
+        });
+    }
+
+    /// Publish the running totals.
+    fn report(&self, result: &ImportResult) {
+        self.update(|p| {
+            p.detections_imported = result.detections_imported;
+            p.files_extracted = result.files_extracted;
+            p.skipped_existing = result.skipped_existing;
+            p.error_count = result.errors.len() as u64;
+        });
+    }
+
+    fn finish(&self, outcome: Result<ImportResult, String>) {
+        let cancelled = self.cancel.load(Ordering::Relaxed);
+        self.update(|p| match outcome {
+            Ok(result) => {
+                p.state = ImportState::Completed;
+                p.phase = "Done".into();
+                p.detections_imported = result.detections_imported;
+                p.files_extracted = result.files_extracted;
+                p.skipped_existing = result.skipped_existing;
+                p.error_count = result.errors.len() as u64;
+                p.result = Some(result);
+            }
+            Err(_) if cancelled => {
+                p.state = ImportState::Cancelled;
+                p.phase = "Cancelled".into();
+            }
+            Err(e) => {
+                tracing::error!("Import of {} failed: {e}", p.source);
+                p.state = ImportState::Failed;
+                p.error = Some(e);
+            }
+        });
+    }
+}
+
+fn jobs() -> &'static Mutex<HashMap<String, Arc<ImportJob>>> {
+    static JOBS: OnceLock<Mutex<HashMap<String, Arc<ImportJob>>>> = OnceLock::new();
+    JOBS.get_or_init(Default::default)
+}
+
+/// Run `job` on the blocking pool.
+fn spawn_job(job: Arc<ImportJob>) {
+    tokio::task::spawn_blocking(move || {
+        let outcome = match &job.source {
+            ImportSource::Tar(path) => import_backup(path, &job.db_path, &job.extracted_dir, &job),
+            ImportSource::Node { address, port, username, password } => stream_import(
+                address,
+                *port,
+                &job.db_path,
+                &job.extracted_dir,
+                username,
+                password,
+                &job,
+            ),
+        };
+        job.finish(outcome);
+    });
+}
+
+/// Start importing `source` in the background and return the job id.
+///
+/// Only one import runs at a time: jobs share the temporary work
+/// directories and would race on the same clips.
+pub fn start_job(
+    source: ImportSource,
+    db_path: PathBuf,
+    extracted_dir: PathBuf,
+) -> Result<String, String> {
+    let mut jobs = jobs().lock().map_err(|_| "Import registry unavailable".to_string())?;
+    if let Some(running) = jobs
+        .values()
+        .map(|j| j.snapshot())
+        .find(|p| p.state == ImportState::Running)
+    {
+        return Err(format!("An import from {} is already running", running.source));
+    }
+    let id = format!("import-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f"));
+    let job = Arc::new(ImportJob::new(id.clone(), source, db_path, extracted_dir));
+    jobs.insert(id.clone(), job.clone());
+    spawn_job(job);
+    Ok(id)
 }
 
-// ─── Discovered BirdNET-Pi node (reuse the shared model type) ────────────
-pub use crate::model::BirdnetNode;
+/// Progress of one job.
+pub fn job_progress(id: &str) -> Option<ImportProgress> {
+    jobs().lock().ok()?.get(id).map(|j| j.snapshot())
+}
+
+/// All jobs since the server started, newest first.
+pub fn list_jobs() -> Vec<ImportProgress> {
+    let mut out: Vec<ImportProgress> = jobs()
+        .lock()
+        .map(|jobs| jobs.values().map(|j| j.snapshot()).collect())
+        .unwrap_or_default();
+    out.sort_by(|a, b| b.job_id.cmp(&a.job_id));
+    out
+}
+
+/// Ask a running job to stop after the current tar entry.
+pub fn cancel_job(id: &str) -> Result<(), String> {
+    let jobs = jobs().lock().map_err(|_| "Import registry unavailable".to_string())?;
+    let job = jobs.get(id).ok_or_else(|| format!("Unknown import {id}"))?;
+    job.cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Run a finished, failed or cancelled job again under the same id.
+/// Detections and files imported by the earlier run are skipped.
+pub fn resume_job(id: &str) -> Result<(), String> {
+    let jobs = jobs().lock().map_err(|_| "Import registry unavailable".to_string())?;
+    if let Some(running) = jobs
+        .values()
+        .map(|j| j.snapshot())
+        .find(|p| p.state == ImportState::Running)
+    {
+        return Err(format!("An import from {} is already running", running.source));
+    }
+    let job = jobs.get(id).ok_or_else(|| format!("Unknown import {id}"))?.clone();
+    job.cancel.store(false, Ordering::Relaxed);
+    job.update(|p| {
+        p.state = ImportState::Running;
+        p.phase = "Resuming".into();
+        p.result = None;
+        p.error = None;
+    });
+    spawn_job(job);
+    Ok(())
+}
+
+/// Counts the bytes read through it into a shared counter.
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
 
 /// Open a libsql local connection (helper for import functions).
 /// Used for **foreign** databases (BirdNET-Pi backups, source DBs).
@@ -121,7 +366,10 @@ async fn query_scalar_string(
 }
 
 /// Analyse a BirdNET-Pi backup tar and produce a report *without* importing.
-pub async fn analyse_backup(tar_path: &Path) -> Result<ImportReport, String> {
+///
+/// Its `birdnet.conf` and species lists are kept in `keep_dir` for the
+/// settings migration ([`birdnet_migration`]).
+pub async fn analyse_backup(tar_path: &Path, keep_dir: &Path) -> Result<ImportReport, String> {
     let meta = std::fs::metadata(tar_path)
         .map_err(|e| format!("Cannot stat {}: {e}", tar_path.display()))?;
 
@@ -160,11 +408,9 @@ pub async fn analyse_backup(tar_path: &Path) -> Result<ImportReport, String> {
                 .unpack(tmp_dir.join("birds.db"))
                 .map_err(|e| format!("Cannot extract birds.db: {e}"))?;
             db_extracted = true;
-        } else if path_str == "birdnet.conf" && !conf_extracted {
-            entry
-                .unpack(tmp_dir.join("birdnet.conf"))
-                .map_err(|e| format!("Cannot extract birdnet.conf: {e}"))?;
-            conf_extracted = true;
+        } else if let Some(name) = birdnet_migration::migration_file(&path_str) {
+            keep_for_migration(&mut entry, keep_dir, name);
+            conf_extracted |= name == "birdnet.conf";
         } else if path_str.ends_with(".mp3.png") {
             spectrogram_count += 1;
         } else if path_str.ends_with(".mp3") {
@@ -237,7 +483,7 @@ pub async fn analyse_backup(tar_path: &Path) -> Result<ImportReport, String> {
 
     // Read lat/lon from config if available
     let (latitude, longitude) = if conf_extracted {
-        parse_lat_lon(&tmp_dir.join("birdnet.conf"))
+        parse_lat_lon(&keep_dir.join("birdnet.conf"))
     } else {
         (None, None)
     };
@@ -267,10 +513,12 @@ pub async fn analyse_backup(tar_path: &Path) -> Result<ImportReport, String> {
 /// - `tar_path`: path to the `.tar` backup file
 /// - `gaia_db_path`: path to the Gaia `detections.db` (will be created if needed)
 /// - `extracted_dir`: directory where audio clips and spectrograms are stored
+/// - `job`: receives progress and is checked for cancellation
 pub fn import_backup(
     tar_path: &Path,
     gaia_db_path: &Path,
     extracted_dir: &Path,
+    job: &ImportJob,
 ) -> Result<ImportResult, String> {
     let mut result = ImportResult {
         detections_imported: 0,
@@ -280,11 +528,15 @@ pub fn import_backup(
     };
 
     // ── Phase 1: Extract birds.db to temp and import detections ──────
-    tracing::info!("Phase 1: Importing detections from backup DB…");
+    let tar_size = std::fs::metadata(tar_path).map(|m| m.len()).ok();
+    job.start_phase("Reading backup database", tar_size);
 
     let file = std::fs::File::open(tar_path)
         .map_err(|e| format!("Cannot open tar: {e}"))?;
-    let mut archive = tar::Archive::new(file);
+    let mut archive = tar::Archive::new(CountingReader {
+        inner: file,
+        count: job.bytes_read.clone(),
+    });
 
     let tmp_dir = std::env::temp_dir().join("gaia_import_work");
     std::fs::create_dir_all(&tmp_dir)
@@ -292,6 +544,7 @@ pub fn import_backup(
 
     // First pass: extract just the DB
     for entry_result in archive.entries().map_err(|e| format!("Tar read error: {e}"))? {
+        job.check_cancelled()?;
         let mut entry = match entry_result {
             Ok(e) => e,
             Err(_) => continue,
@@ -331,7 +584,8 @@ pub fn import_backup(
     let detections_dir = super::detections_duckdb::get_detections_dir()
         .unwrap_or_else(|| gaia_db_path.parent().unwrap_or(Path::new("data")).join("detections"));
 
-    import_detections_from_db(&source_db, &detections_dir, &existing_files, &mut result)?;
+    job.start_phase("Importing detections", None);
+    import_detections_from_db(&source_db, &detections_dir, &existing_files, &mut result, job)?;
 
     tracing::info!(
         "Phase 1 complete: {} detections imported, {} skipped (existing)",
@@ -340,9 +594,9 @@ pub fn import_backup(
     );
 
     // ── Phase 2: Extract audio and spectrogram files ─────────────────
-    tracing::info!("Phase 2: Extracting audio files and spectrograms…");
-
-    extract_media_from_tar(tar_path, extracted_dir, &mut result)?;
+    job.start_phase("Extracting media", tar_size);
+    let keep_dir = birdnet_migration::keep_dir(gaia_db_path);
+    extract_media_from_tar(tar_path, extracted_dir, &keep_dir, &mut result, job)?;
 
     // Cleanup temp
     let _ = std::fs::remove_dir_all(&tmp_dir);
@@ -379,6 +633,7 @@ pub fn stream_import(
     extracted_dir: &Path,
     username: &str,
     password: &str,
+    job: &ImportJob,
 ) -> Result<ImportResult, String> {
     let url = format!("http://{}:{}/scripts/backup.php", address, port);
 
@@ -459,17 +714,31 @@ pub fn stream_import(
         .take()
         .ok_or("Cannot capture curl stdout")?;
 
-    let mut archive = tar::Archive::new(stdout);
+    job.start_phase("Downloading backup", None);
+    let mut archive = tar::Archive::new(CountingReader {
+        inner: stdout,
+        count: job.bytes_read.clone(),
+    });
 
     let tmp_dir = std::env::temp_dir().join("gaia_stream_import");
     std::fs::create_dir_all(&tmp_dir)
         .map_err(|e| format!("Cannot create temp dir: {e}"))?;
 
     // ── Single-pass: extract files and DB simultaneously ─────────────
+    let mut entries_seen: u64 = 0;
     for entry_result in archive
         .entries()
         .map_err(|e| format!("Tar stream error: {e}"))?
     {
+        if let Err(e) = job.check_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+        entries_seen += 1;
+        if entries_seen % REPORT_EVERY == 0 {
+            job.report(&result);
+        }
         let mut entry = match entry_result {
             Ok(e) => e,
             Err(e) => {
@@ -492,9 +761,9 @@ pub fn stream_import(
             continue;
         }
 
-        // Extract birdnet.conf to temp (for future use)
-        if path_str == "birdnet.conf" {
-            let _ = entry.unpack(tmp_dir.join("birdnet.conf"));
+        // Keep birdnet.conf and species lists for the settings migration
+        if let Some(name) = birdnet_migration::migration_file(&path_str) {
+            keep_for_migration(&mut entry, &birdnet_migration::keep_dir(gaia_db_path), name);
             continue;
         }
 
@@ -527,7 +796,7 @@ pub fn stream_import(
             }
         }
 
-        if let Err(e) = entry.unpack(&dest) {
+        if let Err(e) = unpack_atomic(&mut entry, &dest) {
             result
                 .errors
                 .push(format!("Cannot extract {path_str}: {e}"));
@@ -567,7 +836,8 @@ pub fn stream_import(
         tracing::info!("Importing detections from birds.db…");
         let detections_dir = super::detections_duckdb::get_detections_dir()
             .unwrap_or_else(|| gaia_db_path.parent().unwrap_or(Path::new("data")).join("detections"));
-        import_detections_from_db(&source_db, &detections_dir, &existing, &mut result)?;
+        job.start_phase("Importing detections", None);
+        import_detections_from_db(&source_db, &detections_dir, &existing, &mut result, job)?;
     } else {
         tracing::warn!("No birds.db found in the streamed backup");
     }
@@ -690,6 +960,7 @@ fn import_detections_from_db(
     detections_dir: &Path,
     existing: &HashSet<String>,
     result: &mut ImportResult,
+    job: &ImportJob,
 ) -> Result<(), String> {
     // In-memory DuckDB for buffering the import batch.
     let duck = duckdb::Connection::open_in_memory()
@@ -745,6 +1016,9 @@ fn import_detections_from_db(
             .map_err(|e| format!("Source query error: {e}"))?;
 
         while let Some(row) = src_rows.next().await.map_err(|e| format!("Row error: {e}"))? {
+            // Nothing is written until the loop ends, so a cancelled
+            // import leaves no partial batch behind.
+            job.check_cancelled()?;
             let (date, time, sci, com, conf, lat, lon, cutoff, week, sens, overlap, fname) =
                 match (|| -> Result<_, libsql::Error> {
                     Ok((
@@ -796,6 +1070,9 @@ fn import_detections_from_db(
             }
 
             result.detections_imported += 1;
+            if result.detections_imported % REPORT_EVERY == 0 {
+                job.report(result);
+            }
 
             if result.detections_imported % 5000 == 0 {
                 tracing::info!(
@@ -845,19 +1122,30 @@ fn import_detections_from_db(
 fn extract_media_from_tar(
     tar_path: &Path,
     extracted_dir: &Path,
+    keep_dir: &Path,
     result: &mut ImportResult,
+    job: &ImportJob,
 ) -> Result<(), String> {
     std::fs::create_dir_all(extracted_dir)
         .map_err(|e| format!("Cannot create extracted dir: {e}"))?;
 
     let file = std::fs::File::open(tar_path)
         .map_err(|e| format!("Cannot reopen tar: {e}"))?;
-    let mut archive = tar::Archive::new(file);
+    let mut archive = tar::Archive::new(CountingReader {
+        inner: file,
+        count: job.bytes_read.clone(),
+    });
 
+    let mut entries_seen: u64 = 0;
     for entry_result in archive
         .entries()
         .map_err(|e| format!("Tar read error: {e}"))?
     {
+        job.check_cancelled()?;
+        entries_seen += 1;
+        if entries_seen % REPORT_EVERY == 0 {
+            job.report(result);
+        }
         let mut entry = match entry_result {
             Ok(e) => e,
             Err(_) => continue,
@@ -868,6 +1156,11 @@ fn extract_media_from_tar(
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        if let Some(name) = birdnet_migration::migration_file(&path_str) {
+            keep_for_migration(&mut entry, keep_dir, name);
+            continue;
+        }
+
         if !path_str.starts_with("By_Date/") {
             continue;
         }
@@ -895,7 +1188,7 @@ fn extract_media_from_tar(
             }
         }
 
-        if let Err(e) = entry.unpack(&dest) {
+        if let Err(e) = unpack_atomic(&mut entry, &dest) {
             result
                 .errors
                 .push(format!("Cannot extract {}: {e}", path_str));
@@ -912,6 +1205,25 @@ fn extract_media_from_tar(
     Ok(())
 }
 
+/// Unpack `entry` through a hidden `.{name}.part` file and rename it to
+/// `dest`, so an interrupted import never leaves a truncated clip that
+/// [`file_already_imported`] would then skip.
+/// Keep a `birdnet.conf` or species list from a backup for the settings
+/// migration; failures only cost the migration, so they are logged.
+fn keep_for_migration<R: Read>(entry: &mut tar::Entry<'_, R>, keep_dir: &Path, name: &str) {
+    let kept = std::fs::create_dir_all(keep_dir).and_then(|_| unpack_atomic(entry, &keep_dir.join(name)));
+    if let Err(e) = kept {
+        tracing::warn!("Cannot keep {name} for the settings migration: {e}");
+    }
+}
+
+fn unpack_atomic<R: Read>(entry: &mut tar::Entry<'_, R>, dest: &Path) -> std::io::Result<()> {
+    let name = dest.file_name().unwrap_or_default().to_string_lossy();
+    let part = dest.with_file_name(format!(".{name}.part"));
+    entry.unpack(&part)?;
+    std::fs::rename(&part, dest)
+}
+
 /// Check whether a media file (or its Opus-converted counterpart) already
 /// exists on disk.
 ///
@@ -938,20 +1250,25 @@ fn file_already_imported(dest: &Path) -> bool {
         return dest.with_file_name(format!("{stem}.opus.png")).exists();
     }
 
-    // audio.wav → check for audio.opus
+    // audio.wav → check for audio.opus / audio.mp3 (CLIP_FORMAT=mp3)
     if let Some(stem) = name.strip_suffix(".wav") {
-        return dest.with_file_name(format!("{stem}.opus")).exists();
+        return ["opus", "mp3"]
+            .iter()
+            .any(|ext| dest.with_file_name(format!("{stem}.{ext}")).exists());
     }
 
-    // audio.wav.png → check for audio.opus.png
+    // audio.wav.png → check for audio.opus.png / audio.mp3.png
     if let Some(stem) = name.strip_suffix(".wav.png") {
-        return dest.with_file_name(format!("{stem}.opus.png")).exists();
+        return ["opus", "mp3"]
+            .iter()
+            .any(|ext| dest.with_file_name(format!("{stem}.{ext}.png")).exists());
     }
 
     false
 }
 
-/// Ensure the Gaia detections table exists.
+/// Ensure the Gaia database exists and is on the latest schema
+/// (see [`gaia_common::schema`]).
 pub async fn ensure_gaia_schema(db_path: &Path) -> Result<(), String> {
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| format!("Cannot create DB dir: {e}"))?;
@@ -965,89 +1282,10 @@ pub async fn ensure_gaia_schema(db_path: &Path) -> Result<(), String> {
         .await
         .map_err(|e| format!("WAL pragma error: {e}"))?;
 
-    conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS detections (
-            Date       DATE,
-            Time       TIME,
-            Domain     VARCHAR(50) NOT NULL DEFAULT 'birds',
-            Sci_Name   VARCHAR(100) NOT NULL,
-            Com_Name   VARCHAR(100) NOT NULL,
-            Confidence FLOAT,
-            Lat        FLOAT,
-            Lon        FLOAT,
-            Cutoff     FLOAT,
-            Week       INT,
-            Sens       FLOAT,
-            Overlap    FLOAT,
-            File_Name  VARCHAR(100) NOT NULL,
-            Source_Node VARCHAR(200) NOT NULL DEFAULT '',
-            Excluded   INTEGER NOT NULL DEFAULT 0
-        );
-        CREATE INDEX IF NOT EXISTS detections_Com_Name    ON detections (Com_Name);
-        CREATE INDEX IF NOT EXISTS detections_Sci_Name    ON detections (Sci_Name);
-        CREATE INDEX IF NOT EXISTS detections_Domain      ON detections (Domain);
-        CREATE INDEX IF NOT EXISTS detections_Date_Time   ON detections (Date DESC, Time DESC);
-
-        CREATE TABLE IF NOT EXISTS urban_noise (
-            Date       DATE    NOT NULL,
-            Hour       INT     NOT NULL,
-            Category   VARCHAR(50) NOT NULL,
-            Count      INT     NOT NULL DEFAULT 1,
-            UNIQUE(Date, Hour, Category)
-        );
-        CREATE INDEX IF NOT EXISTS urban_noise_date ON urban_noise (Date DESC);
-
-        CREATE TABLE IF NOT EXISTS settings (
-            key   TEXT PRIMARY KEY,
-            value TEXT NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS exclusion_overrides (
-            Sci_Name      VARCHAR(100) PRIMARY KEY,
-            overridden_at TEXT NOT NULL DEFAULT (datetime('now')),
-            notes         TEXT NOT NULL DEFAULT ''
-        );
-
-        CREATE TABLE IF NOT EXISTS species_verifications (
-            Sci_Name         VARCHAR(100) PRIMARY KEY,
-            method           VARCHAR(50) NOT NULL DEFAULT 'ornithologist',
-            inaturalist_obs  TEXT NOT NULL DEFAULT '',
-            verified_at      TEXT NOT NULL DEFAULT (datetime('now'))
-        );",
-    )
-    .await
-    .map_err(|e| format!("Schema error: {e}"))?;
-
-    // Migration: add Source_Node to existing databases that lack it.
-    let _ = conn.execute_batch(
-        "ALTER TABLE detections ADD COLUMN Source_Node VARCHAR(200) NOT NULL DEFAULT '';",
-    ).await;
-    // Migration: add Excluded column to existing databases.
-    let _ = conn.execute_batch(
-        "ALTER TABLE detections ADD COLUMN Excluded INTEGER NOT NULL DEFAULT 0;",
-    ).await;
-    // Migration: add model tracking columns.
-    let _ = conn.execute_batch(
-        "ALTER TABLE detections ADD COLUMN Model_Slug VARCHAR(100) NOT NULL DEFAULT '';",
-    ).await;
-    let _ = conn.execute_batch(
-        "ALTER TABLE detections ADD COLUMN Model_Name VARCHAR(200) NOT NULL DEFAULT '';",
-    ).await;
-
-    // ── Species stats cache table ────────────────────────────────────
-    conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS species_stats (
-            Sci_Name       VARCHAR(100) NOT NULL,
-            Com_Name       VARCHAR(100) NOT NULL,
-            Domain         VARCHAR(50)  NOT NULL DEFAULT 'birds',
-            detection_count INTEGER     NOT NULL DEFAULT 0,
-            last_seen      TEXT,
-            updated_at     TEXT NOT NULL DEFAULT (datetime('now')),
-            PRIMARY KEY (Sci_Name, Domain)
-        );",
-    )
-    .await
-    .map_err(|e| format!("species_stats table error: {e}"))?;
+    let version = gaia_common::schema::migrate(&conn)
+        .await
+        .map_err(|e| format!("Schema migration error: {e:#}"))?;
+    tracing::debug!("Gaia DB at schema version {version}");
 
     // Populate the cache if it's empty (first run or after table creation).
     let stats_empty: bool = query_scalar_i64(&conn, "SELECT COUNT(*) FROM species_stats", ())
@@ -1065,24 +1303,6 @@ pub async fn ensure_gaia_schema(db_path: &Path) -> Result<(), String> {
         ).await;
     }
 
-    // ── Top recordings per species ───────────────────────────────────
-    conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS species_top_recordings (
-            Sci_Name       VARCHAR(100) NOT NULL,
-            Com_Name       VARCHAR(100) NOT NULL,
-            Date           DATE         NOT NULL,
-            Time           TIME         NOT NULL,
-            Confidence     FLOAT        NOT NULL,
-            File_Name      VARCHAR(100) NOT NULL,
-            Source_Node    VARCHAR(200) NOT NULL DEFAULT '',
-            Model_Name     VARCHAR(200) NOT NULL DEFAULT '',
-            rank           INTEGER      NOT NULL DEFAULT 0,
-            PRIMARY KEY (Sci_Name, rank)
-        );",
-    )
-    .await
-    .map_err(|e| format!("species_top_recordings table error: {e}"))?;
-
     Ok(())
 }
 
@@ -1176,6 +1396,64 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_extract_media_job_progress_resume_and_cancel() {
+        let dir = std::env::temp_dir().join("gaia_import_job_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let tar_path = dir.join("backup.tar");
+        let mut builder = tar::Builder::new(std::fs::File::create(&tar_path).unwrap());
+        for name in [
+            "By_Date/2024-05-01/Robin/a.mp3",
+            "By_Date/2024-05-01/Robin/a.mp3.png",
+            "scripts/include_species_list.txt",
+            "notes.txt",
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(4);
+            header.set_mode(0o644);
+            builder.append_data(&mut header, name, &b"fake"[..]).unwrap();
+        }
+        builder.into_inner().unwrap();
+
+        let out = dir.join("extracted");
+        let job = ImportJob::new(
+            "import-test".into(),
+            ImportSource::Tar(tar_path.clone()),
+            dir.join("birds.db"),
+            out.clone(),
+        );
+        let mut result = ImportResult {
+            detections_imported: 0,
+            files_extracted: 0,
+            skipped_existing: 0,
+            errors: Vec::new(),
+        };
+        let keep_dir = dir.join("birdnetpi");
+        extract_media_from_tar(&tar_path, &out, &keep_dir, &mut result, &job).unwrap();
+        assert_eq!(result.files_extracted, 2);
+        assert!(keep_dir.join("include_species_list.txt").is_file());
+        let clip_dir = out.join("By_Date/2024-05-01/Robin");
+        assert!(clip_dir.join("a.mp3").is_file());
+        assert!(!clip_dir.join(".a.mp3.part").exists());
+        assert!(job.snapshot().bytes_read > 0);
+
+        // A second run (resume) skips what is already on disk.
+        extract_media_from_tar(&tar_path, &out, &keep_dir, &mut result, &job).unwrap();
+        assert_eq!(result.files_extracted, 2);
+        assert_eq!(result.skipped_existing, 2);
+
+        job.cancel.store(true, Ordering::Relaxed);
+        assert_eq!(
+            extract_media_from_tar(&tar_path, &out, &keep_dir, &mut result, &job),
+            Err(CANCELLED.to_string())
+        );
+        job.finish(Err(CANCELLED.into()));
+        assert_eq!(job.snapshot().state, ImportState::Cancelled);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_existing_filenames_includes_opus_variants() {
         // This test verifies the DuckDB-backed deduplication.
@@ -1229,7 +1507,10 @@ mod tests {
     async fn test_analyse_real_backup() {
         let path = std::env::var("BACKUP_PATH")
             .expect("Set BACKUP_PATH env var to the .tar backup file");
-        let report = analyse_backup(Path::new(&path)).await.expect("analyse_backup failed");
+        let keep_dir = std::env::temp_dir().join("gaia_analyse_real_backup");
+        let report = analyse_backup(Path::new(&path), &keep_dir)
+            .await
+            .expect("analyse_backup failed");
 
         println!("\n╔══════════════════════════════════════╗");
         println!("║   BirdNET-Pi Backup Analysis Report  ║");
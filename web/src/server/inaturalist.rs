@@ -1,88 +1,20 @@
-//! iNaturalist API client with an in-memory cache.
+//! iNaturalist API client.
 //!
 //! Uses the public `v1/taxa` endpoint to look up species photos, Wikipedia
 //! links, and conservation status by scientific name.  Also fetches
 //! sex-annotated observation photos (male / female) from the
 //! `v1/observations` endpoint so both sexes can be shown on species cards.
+//! Results are cached by [`super::species_images`], which falls back to
+//! other photo providers when iNaturalist has nothing.
 //!
-//! The cache is versioned: when new fields are added to [`SpeciesPhoto`]
-//! the [`CACHE_VERSION`] is bumped, causing stale entries to be re-fetched
-//! automatically after an upgrade.
+//! With `GAIA_INAT_TOKEN` set, detections can also be pushed to
+//! iNaturalist as observations with the clip attached as a sound
+//! ([`submit_observation`]).
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-
-use crate::model::SpeciesPhoto;
-
-/// Bump this whenever [`SpeciesPhoto`] gains new fields that require a
-/// fresh iNaturalist fetch.  Stale cache entries with an older version
-/// are silently discarded and re-fetched.
-const CACHE_VERSION: u16 = 3;
-
-/// Wrapper stored in the cache so we can detect outdated entries.
-#[derive(Clone, Debug)]
-pub struct CacheEntry {
-    version: u16,
-    photo: Option<SpeciesPhoto>,
-}
-
-/// Thread-safe cache shared across requests.
-pub type PhotoCache = Arc<Mutex<HashMap<String, CacheEntry>>>;
-
-/// Create an empty cache.
-pub fn new_cache() -> PhotoCache {
-    Arc::new(Mutex::new(HashMap::new()))
-}
-
-/// Read a species photo from cache without performing any network call.
-///
-/// Returns `None` when absent or stale (cache version mismatch).
-pub fn lookup_cached(cache: &PhotoCache, scientific_name: &str) -> Option<SpeciesPhoto> {
-    let guard = cache.lock().unwrap();
-    guard
-        .get(scientific_name)
-        .filter(|entry| entry.version == CACHE_VERSION)
-        .and_then(|entry| entry.photo.clone())
-}
-
-/// Look up a species photo.  Returns a cached result if available and
-/// up-to-date, otherwise queries the iNaturalist API (and caches the
-/// answer).
-pub async fn lookup(
-    cache: &PhotoCache,
-    scientific_name: &str,
-) -> Option<SpeciesPhoto> {
-    // Fast-path: serve from cache if version matches
-    {
-        let guard = cache.lock().unwrap();
-        if let Some(entry) = guard.get(scientific_name) {
-            if entry.version == CACHE_VERSION {
-                return entry.photo.clone();
-            }
-            // Version mismatch → stale; fall through to re-fetch.
-        }
-    }
-
-    // Fetch from iNaturalist
-    let result = fetch_from_inaturalist(scientific_name).await;
-
-    // Only cache successful results.  Transient failures (API rate-
-    // limiting, network blips) return None — leaving them uncached
-    // allows the next request to retry instead of permanently showing
-    // placeholder.svg.
-    if result.is_some() {
-        let mut guard = cache.lock().unwrap();
-        guard.insert(scientific_name.to_string(), CacheEntry {
-            version: CACHE_VERSION,
-            photo: result.clone(),
-        });
-    }
-
-    result
-}
+use crate::model::{ImageSource, SpeciesPhoto};
 
 /// Raw HTTP call to the iNaturalist taxa search API.
-async fn fetch_from_inaturalist(scientific_name: &str) -> Option<SpeciesPhoto> {
+pub async fn fetch_photo(scientific_name: &str) -> Option<SpeciesPhoto> {
     let url = format!(
         "https://api.inaturalist.org/v1/taxa?q={}&rank=species&per_page=1",
         urlencoded(scientific_name),
@@ -128,6 +60,7 @@ async fn fetch_from_inaturalist(scientific_name: &str) -> Option<SpeciesPhoto> {
     Some(SpeciesPhoto {
         medium_url,
         attribution,
+        source: ImageSource::INaturalist,
         wikipedia_url,
         conservation_status,
         male_image_url,
@@ -291,3 +224,160 @@ fn urlencoded(s: &str) -> String {
         .replace('&', "%26")
         .replace('=', "%3D")
 }
+
+// ─── Observation submission ──────────────────────────────────────────────────
+
+const API_BASE: &str = "https://api.inaturalist.org/v1";
+
+/// Access token used to create observations: an OAuth access token or
+/// the 24-hour API token from <https://www.inaturalist.org/users/api_token>.
+pub fn submission_token() -> Option<String> {
+    std::env::var("GAIA_INAT_TOKEN")
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Public URL of an observation.
+pub fn observation_url(id: u64) -> String {
+    format!("https://www.inaturalist.org/observations/{id}")
+}
+
+/// What gets sent for one detection.
+#[derive(Debug, Clone)]
+pub struct ObservationDraft {
+    pub scientific_name: String,
+    /// Local date and time as shown on the dashboard (`YYYY-MM-DD HH:MM:SS`);
+    /// iNaturalist reads it in the account's time zone.
+    pub observed_on: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub description: String,
+}
+
+/// JSON body for `POST /v1/observations`.
+fn observation_body(draft: &ObservationDraft, taxon_id: Option<u64>) -> serde_json::Value {
+    let mut obs = serde_json::json!({
+        "species_guess": draft.scientific_name,
+        "observed_on_string": draft.observed_on,
+        "description": draft.description,
+    });
+    // 0,0 means the station location was never configured.
+    if draft.latitude != 0.0 || draft.longitude != 0.0 {
+        obs["latitude"] = draft.latitude.into();
+        obs["longitude"] = draft.longitude.into();
+    }
+    if let Some(id) = taxon_id {
+        obs["taxon_id"] = id.into();
+    }
+    serde_json::json!({ "observation": obs })
+}
+
+/// Exact-name taxon lookup (the search endpoint is fuzzy).
+async fn fetch_taxon_id(client: &reqwest::Client, scientific_name: &str) -> Option<u64> {
+    let url = format!(
+        "{API_BASE}/taxa?q={}&per_page=5",
+        urlencoded(scientific_name)
+    );
+    let body: serde_json::Value = client.get(&url).send().await.ok()?.json().await.ok()?;
+    body.get("results")?
+        .as_array()?
+        .iter()
+        .find(|t| {
+            t.get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|n| n.eq_ignore_ascii_case(scientific_name))
+        })?
+        .get("id")?
+        .as_u64()
+}
+
+/// Create an observation for `draft` and attach `clip` as its sound.
+/// Returns the new observation id.
+pub async fn submit_observation(
+    token: &str,
+    draft: &ObservationDraft,
+    clip: Option<&std::path::Path>,
+) -> Result<u64, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("HTTP client: {e}"))?;
+    let auth = format!("Bearer {token}");
+
+    let taxon_id = fetch_taxon_id(&client, &draft.scientific_name).await;
+    let resp = client
+        .post(format!("{API_BASE}/observations"))
+        .header("Authorization", &auth)
+        .json(&observation_body(draft, taxon_id))
+        .send()
+        .await
+        .map_err(|e| format!("iNaturalist request failed: {e}"))?;
+    let status = resp.status();
+    let body: serde_json::Value = resp.json().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("iNaturalist rejected the observation ({status}): {body}"));
+    }
+    let id = body
+        .get("id")
+        .and_then(|v| v.as_u64())
+        .ok_or("iNaturalist response has no observation id")?;
+
+    if let Some(path) = clip {
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Cannot read clip {}: {e}", path.display()))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "clip".into());
+        let mime = crate::model::clip_mime_type(&name);
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(name)
+            .mime_str(mime.split(';').next().unwrap_or(mime))
+            .map_err(|e| format!("Bad MIME type: {e}"))?;
+        let form = reqwest::multipart::Form::new()
+            .text("observation_sound[observation_id]", id.to_string())
+            .part("file", part);
+        let resp = client
+            .post(format!("{API_BASE}/observation_sounds"))
+            .header("Authorization", &auth)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| format!("Sound upload failed: {e}"))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            // The observation exists; report it but keep the id.
+            tracing::warn!("iNaturalist observation {id} created but sound upload failed ({status}): {text}");
+        }
+    }
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observation_body_omits_unset_location() {
+        let mut draft = ObservationDraft {
+            scientific_name: "Turdus merula".into(),
+            observed_on: "2026-05-01 06:12:30".into(),
+            latitude: 0.0,
+            longitude: 0.0,
+            description: "BirdNET 92%".into(),
+        };
+        let body = observation_body(&draft, None);
+        assert_eq!(body["observation"]["species_guess"], "Turdus merula");
+        assert!(body["observation"].get("latitude").is_none());
+        assert!(body["observation"].get("taxon_id").is_none());
+
+        draft.latitude = 9.93;
+        draft.longitude = -84.08;
+        let body = observation_body(&draft, Some(12716));
+        assert_eq!(body["observation"]["latitude"], 9.93);
+        assert_eq!(body["observation"]["taxon_id"], 12716);
+    }
+}
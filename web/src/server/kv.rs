@@ -13,7 +13,7 @@ use std::sync::OnceLock;
 use redis::AsyncCommands;
 use tracing::info;
 
-use crate::model::{SpeciesVerification, UrbanNoiseSummary};
+use crate::model::{AuditEntry, DetectionReview, SpeciesVerification, UrbanNoiseSummary};
 
 // ── Connection management ────────────────────────────────────────────────────
 
@@ -288,6 +288,238 @@ pub async fn get_species_verifications(
     Ok(out)
 }
 
+// ── Species image pins ───────────────────────────────────────────────────────
+
+/// Photo URL and credit line an admin pinned for a species, if any.
+pub async fn get_image_pin(sci_name: &str) -> Result<Option<(String, String)>, String> {
+    let mut c = conn();
+    let map: HashMap<String, String> = c
+        .hgetall(format!("image_pin:{sci_name}"))
+        .await
+        .map_err(|e| format!("Redis error: {e}"))?;
+    Ok(map.get("url").filter(|u| !u.is_empty()).map(|u| {
+        (
+            u.clone(),
+            map.get("attribution").cloned().unwrap_or_default(),
+        )
+    }))
+}
+
+/// Pin `url` as the photo for a species, replacing any earlier pin.
+pub async fn set_image_pin(sci_name: &str, url: &str, attribution: &str) -> Result<(), String> {
+    let mut c = conn();
+    let key = format!("image_pin:{sci_name}");
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    redis::pipe()
+        .hset(&key, "url", url)
+        .hset(&key, "attribution", attribution)
+        .hset(&key, "pinned_at", &now)
+        .query_async::<()>(&mut c)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Remove the pinned photo of a species.
+pub async fn remove_image_pin(sci_name: &str) -> Result<(), String> {
+    let mut c = conn();
+    c.del::<_, ()>(format!("image_pin:{sci_name}"))
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+// ── iNaturalist exports ──────────────────────────────────────────────────────
+
+/// Observation id a detection clip was exported as, if any.
+pub async fn get_inat_export(file_name: &str) -> Result<Option<u64>, String> {
+    let mut c = conn();
+    c.hget("inat:exports", file_name)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Remember that `file_name` was exported as observation `obs_id`.
+pub async fn save_inat_export(file_name: &str, obs_id: u64) -> Result<(), String> {
+    let mut c = conn();
+    c.hset::<_, _, _, ()>("inat:exports", file_name, obs_id)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+// ── Detection reviews ────────────────────────────────────────────────────────
+
+/// Clip file names that already have a review verdict.
+pub async fn get_reviewed_files() -> Result<HashSet<String>, String> {
+    let mut c = conn();
+    c.hkeys("review:verdicts")
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Every stored review verdict, keyed by clip file name.  Entries that
+/// fail to parse are skipped.
+pub async fn get_reviews() -> Result<HashMap<String, DetectionReview>, String> {
+    let mut c = conn();
+    let raw: HashMap<String, String> = c
+        .hgetall("review:verdicts")
+        .await
+        .map_err(|e| format!("Redis error: {e}"))?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|(file, json)| Some((file, serde_json::from_str(&json).ok()?)))
+        .collect())
+}
+
+/// Store the review verdict for a detection clip (replaces any earlier one).
+pub async fn save_review(file_name: &str, review: &DetectionReview) -> Result<(), String> {
+    let json = serde_json::to_string(review).map_err(|e| e.to_string())?;
+    let mut c = conn();
+    c.hset::<_, _, _, ()>("review:verdicts", file_name, json)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+// ── Favourites ───────────────────────────────────────────────────────────────
+
+/// Hash of the detections `user` starred; without an authenticating
+/// proxy (`user` empty) the station shares one collection.
+fn favourites_key(user: &str) -> String {
+    if user.is_empty() {
+        "favourites".to_string()
+    } else {
+        format!("favourites:{user}")
+    }
+}
+
+/// Detections `user` starred: detection id → when (`YYYY-MM-DD HH:MM:SS`
+/// UTC).  Fields that are not ids are skipped.
+pub async fn get_favourites(user: &str) -> Result<HashMap<i64, String>, String> {
+    let mut c = conn();
+    let raw: HashMap<String, String> = c
+        .hgetall(favourites_key(user))
+        .await
+        .map_err(|e| format!("Redis error: {e}"))?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|(id, at)| Some((id.parse().ok()?, at)))
+        .collect())
+}
+
+/// Star (or unstar) detection `id` for `user`.  Starring again keeps the
+/// original time.
+pub async fn set_favourite(user: &str, id: i64, starred: bool) -> Result<(), String> {
+    let mut c = conn();
+    let key = favourites_key(user);
+    let done = if starred {
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        c.hset_nx::<_, _, _, ()>(key, id, now).await
+    } else {
+        c.hdel::<_, _, ()>(key, id).await
+    };
+    done.map_err(|e| format!("Redis error: {e}"))
+}
+
+// ── Audit log ────────────────────────────────────────────────────────────────
+
+/// Most recent audit entries kept; older ones are trimmed on insert.
+pub const AUDIT_LOG_MAX: isize = 10_000;
+
+/// Prepend an entry to the audit log.
+pub async fn push_audit_entry(entry: &AuditEntry) -> Result<(), String> {
+    let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut c = conn();
+    redis::pipe()
+        .lpush("audit:log", json)
+        .ignore()
+        .ltrim("audit:log", 0, AUDIT_LOG_MAX - 1)
+        .ignore()
+        .query_async::<()>(&mut c)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// The `limit` newest audit entries, newest first.  Entries that fail
+/// to parse are skipped.
+pub async fn get_audit_entries(limit: usize) -> Result<Vec<AuditEntry>, String> {
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+    let mut c = conn();
+    let stop = limit.min(AUDIT_LOG_MAX as usize) as isize - 1;
+    let raw: Vec<String> = c
+        .lrange("audit:log", 0, stop)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))?;
+    Ok(raw
+        .iter()
+        .filter_map(|json| serde_json::from_str(json).ok())
+        .collect())
+}
+
+// ── Web Push ─────────────────────────────────────────────────────────────────
+
+/// All stored push subscriptions (endpoint → subscription JSON).
+pub async fn get_push_subscriptions() -> Result<HashMap<String, String>, String> {
+    let mut c = conn();
+    c.hgetall("push:subscriptions")
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// A single push subscription, by browser endpoint.
+pub async fn get_push_subscription(endpoint: &str) -> Result<Option<String>, String> {
+    let mut c = conn();
+    c.hget("push:subscriptions", endpoint)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Store (or replace) a push subscription.
+pub async fn save_push_subscription(endpoint: &str, json: &str) -> Result<(), String> {
+    let mut c = conn();
+    c.hset::<_, _, _, ()>("push:subscriptions", endpoint, json)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Forget a push subscription (unsubscribed, or expired at the push service).
+pub async fn remove_push_subscription(endpoint: &str) -> Result<(), String> {
+    let mut c = conn();
+    c.hdel::<_, _, ()>("push:subscriptions", endpoint)
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Read the VAPID private key, storing `generate()`'s result first if
+/// none exists yet.  `HSETNX` keeps concurrent first starts from ending
+/// up with different keys.
+pub async fn get_or_init_vapid_key(generate: impl FnOnce() -> String) -> Result<String, String> {
+    let mut c = conn();
+    let existing: Option<String> = c
+        .hget("push:vapid", "private_key")
+        .await
+        .map_err(|e| format!("Redis error: {e}"))?;
+    if let Some(key) = existing {
+        return Ok(key);
+    }
+    let _: bool = c
+        .hset_nx("push:vapid", "private_key", generate())
+        .await
+        .map_err(|e| format!("Redis error: {e}"))?;
+    c.hget("push:vapid", "private_key")
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
+/// Open a separate connection for blocking commands (`BLPOP`), which
+/// would otherwise stall every request sharing the multiplexed one.
+pub async fn dedicated_connection() -> Result<redis::aio::MultiplexedConnection, String> {
+    redis::Client::open(redis_url().as_str())
+        .map_err(|e| format!("Cannot parse Redis URL: {e}"))?
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| format!("Redis error: {e}"))
+}
+
 // ── SQLite → Redis migration ─────────────────────────────────────────────────
 
 /// One-time migration: copy settings, overrides, verifications, and
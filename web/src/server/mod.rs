@@ -1,6 +1,32 @@
+pub mod audit;
+pub mod birdnet_migration;
+pub mod capture_api;
+pub mod clips;
+pub mod dataset;
 pub mod db;
+pub mod detection_export;
 pub mod detections_duckdb;
+pub mod digest;
+pub mod ebird;
+pub mod folder_import;
+pub mod graphql;
+pub mod health;
 pub mod import;
 pub mod inaturalist;
+pub mod pipeline_ws;
 pub mod kv;
+pub mod push;
+pub mod share;
+pub mod species_images;
+pub mod species_search;
+pub mod spectrogram;
 pub mod taxonomy_admin;
+
+/// Quote a CSV field when it contains a separator, quote or newline.
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,80 @@
+//! `GET /ws/pipeline` – WebSocket pushing the processing pipeline's
+//! activity to the dashboard.
+//!
+//! The processing container keeps `<data>/pipeline_status.json` up to
+//! date (see `PipelineStatus`); each socket checks the file once a second
+//! and sends its JSON whenever it changed, starting with the current
+//! state as soon as the socket opens.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::Response;
+
+/// How often the status file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The status file on the shared data volume.
+pub fn status_path() -> PathBuf {
+    PathBuf::from(std::env::var("GAIA_DATA_DIR").unwrap_or_else(|_| "/data".into()))
+        .join("pipeline_status.json")
+}
+
+/// Axum handler for `/ws/pipeline`.
+pub async fn handler(ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(|socket| stream(socket, status_path()))
+}
+
+async fn stream(mut socket: WebSocket, path: PathBuf) {
+    let mut last = String::new();
+    loop {
+        if let Some(json) = read_if_changed(&path, &mut last).await {
+            if socket.send(Message::Text(json.into())).await.is_err() {
+                return;
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            msg = socket.recv() => match msg {
+                // The client never sends anything but pings and close.
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// The file's contents if they differ from `last` (which is updated).
+/// A missing or half-written file is skipped.
+async fn read_if_changed(path: &Path, last: &mut String) -> Option<String> {
+    let json = tokio::fs::read_to_string(path).await.ok()?;
+    if json.trim().is_empty() || json == *last {
+        return None;
+    }
+    *last = json.clone();
+    Some(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_if_changed() {
+        let path = std::env::temp_dir().join(format!("gaia-pipeline-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut last = String::new();
+
+        assert_eq!(read_if_changed(&path, &mut last).await, None);
+
+        std::fs::write(&path, r#"{"updated_at":"a"}"#).unwrap();
+        assert_eq!(read_if_changed(&path, &mut last).await.as_deref(), Some(r#"{"updated_at":"a"}"#));
+        assert_eq!(read_if_changed(&path, &mut last).await, None);
+
+        std::fs::write(&path, r#"{"updated_at":"b"}"#).unwrap();
+        assert!(read_if_changed(&path, &mut last).await.is_some());
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,548 @@
+//! Web Push notifications for selected species (SSR-only).
+//!
+//! Browsers subscribe through the Push API; the subscription (endpoint
+//! plus the browser's public keys) and the species it wants are kept in
+//! Redis under `push:subscriptions`.  While at least one subscription
+//! exists the processing container queues every stored detection on the
+//! `push:events` list; [`run`] drains that list and delivers each match
+//! as an encrypted message (RFC 8291, `aes128gcm`) signed with a VAPID
+//! key (RFC 8292) that is generated on first use and kept in Redis.
+//!
+//! The same list carries the processing watchdog's quiet-station alerts,
+//! delivered to browsers subscribed to [`PUSH_STATION_ALERTS`].
+
+use std::collections::HashSet;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as B64;
+use base64::Engine;
+use hkdf::Hkdf;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use rand_core::{OsRng, RngCore};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tracing::{debug, info, warn};
+
+use super::{detections_duckdb as ddb, kv};
+use crate::model::{PUSH_NEW_SPECIES, PUSH_RARE_SPECIES, PUSH_STATION_ALERTS};
+
+/// Queued detections older than this are dropped instead of delivered.
+const MAX_EVENT_AGE_SECS: i64 = 15 * 60;
+
+/// How long the push service should hold a message for an offline browser.
+const MESSAGE_TTL_SECS: u32 = 3600;
+
+/// Record size advertised in the `aes128gcm` header.  Payloads are a few
+/// hundred bytes, so everything fits in a single record.
+const RECORD_SIZE: u32 = 4096;
+
+/// A browser's push subscription and what it wants to hear about.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub endpoint: String,
+    /// Browser ECDH public key (base64url, uncompressed P-256 point).
+    pub p256dh: String,
+    /// Browser auth secret (base64url, 16 bytes).
+    pub auth: String,
+    /// Scientific names, [`PUSH_NEW_SPECIES`], [`PUSH_RARE_SPECIES`] or
+    /// [`PUSH_STATION_ALERTS`].
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+/// A detection queued by the processing container (`kv::queue_push_event`).
+#[derive(Debug, Deserialize)]
+struct PushEvent {
+    sci_name: String,
+    com_name: String,
+    confidence: f64,
+    date: String,
+    time: String,
+    #[serde(default)]
+    source_node: String,
+    /// Flagged rare against the regional checklist.
+    #[serde(default)]
+    rare: bool,
+    #[serde(default)]
+    queued_at: i64,
+}
+
+/// A node that went quiet (`kv::queue_station_alert` in processing).
+#[derive(Debug, Deserialize)]
+struct StationAlert {
+    /// Always `station_quiet`; detection events have no kind.
+    kind: String,
+    source_node: String,
+    #[serde(default)]
+    domain: String,
+    /// Detections in the last `hours`.
+    detections: u64,
+    /// Usual detections in as many hours.
+    baseline: f64,
+    hours: u32,
+    #[serde(default)]
+    queued_at: i64,
+}
+
+// ─── Subscriptions ───────────────────────────────────────────────────────────
+
+/// Targets the browser at `endpoint` is subscribed to (empty if unknown).
+pub async fn targets(endpoint: &str) -> Result<Vec<String>, String> {
+    Ok(kv::get_push_subscription(endpoint)
+        .await?
+        .and_then(|json| serde_json::from_str::<Subscription>(&json).ok())
+        .map(|s| s.targets)
+        .unwrap_or_default())
+}
+
+/// Add or remove one target for a browser.  The browser's keys are
+/// refreshed on every call; a subscription left with no targets is
+/// deleted.  Returns the remaining targets.
+pub async fn set_target(
+    endpoint: &str,
+    p256dh: &str,
+    auth: &str,
+    target: &str,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    if !endpoint.starts_with("https://") {
+        return Err("Push endpoint must be an https:// URL".into());
+    }
+    let mut targets = targets(endpoint).await?;
+    targets.retain(|t| t != target);
+    if enabled {
+        targets.push(target.to_string());
+    }
+
+    if targets.is_empty() {
+        kv::remove_push_subscription(endpoint).await?;
+    } else {
+        let sub = Subscription {
+            endpoint: endpoint.to_string(),
+            p256dh: p256dh.to_string(),
+            auth: auth.to_string(),
+            targets: targets.clone(),
+        };
+        let json = serde_json::to_string(&sub).map_err(|e| e.to_string())?;
+        kv::save_push_subscription(endpoint, &json).await?;
+    }
+    Ok(targets)
+}
+
+// ─── VAPID ───────────────────────────────────────────────────────────────────
+
+async fn signing_key() -> Result<SigningKey, String> {
+    let encoded = kv::get_or_init_vapid_key(|| {
+        info!("Generating Web Push (VAPID) key pair");
+        B64.encode(SecretKey::random(&mut OsRng).to_bytes())
+    })
+    .await?;
+    let bytes = B64.decode(encoded).map_err(|e| format!("Bad VAPID key: {e}"))?;
+    SigningKey::from_slice(&bytes).map_err(|e| format!("Bad VAPID key: {e}"))
+}
+
+/// Public half of the VAPID key, as the `applicationServerKey` browsers
+/// expect (base64url, uncompressed point).
+pub async fn public_key() -> Result<String, String> {
+    let key = signing_key().await?;
+    let point = PublicKey::from(key.verifying_key()).to_encoded_point(false);
+    Ok(B64.encode(point.as_bytes()))
+}
+
+/// `Authorization` header value for a request to `endpoint`.
+fn vapid_authorization(key: &SigningKey, endpoint: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(endpoint).map_err(|e| format!("Bad endpoint: {e}"))?;
+    let subject = std::env::var("GAIA_PUSH_SUBJECT")
+        .unwrap_or_else(|_| "https://github.com/fede2cr/gaia-audio".into());
+    let claims = serde_json::json!({
+        "aud": url.origin().ascii_serialization(),
+        "exp": chrono::Utc::now().timestamp() + 12 * 3600,
+        "sub": subject,
+    });
+    let signing_input = format!(
+        "{}.{}",
+        B64.encode(br#"{"typ":"JWT","alg":"ES256"}"#),
+        B64.encode(claims.to_string())
+    );
+    let signature: Signature = key.sign(signing_input.as_bytes());
+    let public = PublicKey::from(key.verifying_key()).to_encoded_point(false);
+    Ok(format!(
+        "vapid t={signing_input}.{}, k={}",
+        B64.encode(signature.to_bytes()),
+        B64.encode(public.as_bytes())
+    ))
+}
+
+// ─── Payload encryption (RFC 8291) ───────────────────────────────────────────
+
+fn hkdf_expand(salt: &[u8], ikm: &[u8], info: &[u8], out: &mut [u8]) -> Result<(), String> {
+    Hkdf::<Sha256>::new(Some(salt), ikm)
+        .expand(info, out)
+        .map_err(|e| format!("HKDF: {e}"))
+}
+
+/// Encrypt `plaintext` for a browser, returning the `aes128gcm` body.
+fn encrypt(p256dh: &str, auth: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    encrypt_with(p256dh, auth, plaintext, &SecretKey::random(&mut OsRng), salt)
+}
+
+fn encrypt_with(
+    p256dh: &str,
+    auth: &str,
+    plaintext: &[u8],
+    as_secret: &SecretKey,
+    salt: [u8; 16],
+) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, KeyInit};
+
+    let ua_bytes = B64.decode(p256dh).map_err(|e| format!("Bad p256dh: {e}"))?;
+    let ua_public = PublicKey::from_sec1_bytes(&ua_bytes).map_err(|e| format!("Bad p256dh: {e}"))?;
+    let auth_secret = B64.decode(auth).map_err(|e| format!("Bad auth: {e}"))?;
+    if plaintext.len() + 17 > RECORD_SIZE as usize {
+        return Err("Push payload too large".into());
+    }
+
+    let as_public = as_secret.public_key().to_encoded_point(false);
+    let shared = p256::ecdh::diffie_hellman(as_secret.to_nonzero_scalar(), ua_public.as_affine());
+
+    // IKM = HKDF(auth_secret, ecdh_secret, "WebPush: info" || 0 || ua_public || as_public)
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(&ua_bytes);
+    key_info.extend_from_slice(as_public.as_bytes());
+    let mut ikm = [0u8; 32];
+    hkdf_expand(&auth_secret, shared.raw_secret_bytes(), &key_info, &mut ikm)?;
+
+    let mut cek = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    hkdf_expand(&salt, &ikm, b"Content-Encoding: aes128gcm\0", &mut cek)?;
+    hkdf_expand(&salt, &ikm, b"Content-Encoding: nonce\0", &mut nonce)?;
+
+    // Single (last) record: plaintext followed by the 0x02 delimiter.
+    let mut record = plaintext.to_vec();
+    record.push(2);
+    let cipher = aes_gcm::Aes128Gcm::new_from_slice(&cek).map_err(|e| format!("AES: {e}"))?;
+    let ciphertext = cipher
+        .encrypt(aes_gcm::Nonce::from_slice(&nonce), record.as_slice())
+        .map_err(|e| format!("AES: {e}"))?;
+
+    let mut body = Vec::with_capacity(16 + 4 + 1 + 65 + ciphertext.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.as_bytes().len() as u8);
+    body.extend_from_slice(as_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+// ─── Delivery ────────────────────────────────────────────────────────────────
+
+enum Delivery {
+    Sent,
+    /// The push service no longer knows this subscription (404 / 410).
+    Gone,
+    Failed(String),
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    key: &SigningKey,
+    sub: &Subscription,
+    payload: &[u8],
+) -> Delivery {
+    let result = async {
+        let body = encrypt(&sub.p256dh, &sub.auth, payload)?;
+        let authorization = vapid_authorization(key, &sub.endpoint)?;
+        client
+            .post(&sub.endpoint)
+            .header("TTL", MESSAGE_TTL_SECS.to_string())
+            .header("Content-Encoding", "aes128gcm")
+            .header("Content-Type", "application/octet-stream")
+            .header("Authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())
+    }
+    .await;
+    match result {
+        Ok(resp) if resp.status().is_success() => Delivery::Sent,
+        Ok(resp) if matches!(resp.status().as_u16(), 404 | 410) => Delivery::Gone,
+        Ok(resp) => Delivery::Failed(format!("push service returned {}", resp.status())),
+        Err(e) => Delivery::Failed(e),
+    }
+}
+
+/// Notification shown by the service worker (`public/sw.js`).
+fn notification_payload(ev: &PushEvent, new_species: bool) -> Vec<u8> {
+    let title = if new_species {
+        format!("New species: {}", ev.com_name)
+    } else if ev.rare {
+        format!("Rare species: {}", ev.com_name)
+    } else {
+        ev.com_name.clone()
+    };
+    let mut body = format!("{:.0}% · {} {}", ev.confidence * 100.0, ev.date, ev.time);
+    if !ev.source_node.is_empty() {
+        body.push_str(" · ");
+        body.push_str(&ev.source_node);
+    }
+    serde_json::json!({
+        "title": title,
+        "body": body,
+        "tag": ev.sci_name,
+        "url": format!("/species/{}", ev.sci_name.replace(' ', "%20")),
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Notification for a quiet station, opening the Stations page.
+fn station_alert_payload(alert: &StationAlert) -> Vec<u8> {
+    let node = if alert.source_node.is_empty() { "unnamed" } else { &alert.source_node };
+    let what = if alert.domain.is_empty() {
+        "detections".to_string()
+    } else {
+        format!("{} detections", alert.domain)
+    };
+    serde_json::json!({
+        "title": format!("Station {node} is quiet"),
+        "body": format!(
+            "{} {what} in {} h, usually {:.0}. Check its microphone.",
+            alert.detections, alert.hours, alert.baseline
+        ),
+        "tag": format!("station:{}|{}", alert.source_node, alert.domain),
+        "url": "/stations",
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Drain `push:events` forever, delivering each detection to matching
+/// subscriptions.  Spawned once from `main`.
+pub async fn run() {
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Push notifications disabled: cannot create HTTP client: {e}");
+            return;
+        }
+    };
+    // Species already announced as new by this process; the Parquet view
+    // lags behind ingestion, so a burst of first detections would
+    // otherwise each look new.
+    let mut announced: HashSet<String> = HashSet::new();
+    let mut conn = None;
+
+    loop {
+        if conn.is_none() {
+            match kv::dedicated_connection().await {
+                Ok(c) => conn = Some(c),
+                Err(e) => {
+                    warn!("Push notifier cannot connect to Redis: {e}");
+                    tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                    continue;
+                }
+            }
+        }
+        let Some(c) = conn.as_mut() else { continue };
+        let popped: Option<(String, String)> = match c.blpop("push:events", 30.0).await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("Push notifier: {e}");
+                conn = None;
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        let Some((_, json)) = popped else { continue };
+        if let Ok(alert) = serde_json::from_str::<StationAlert>(&json) {
+            if alert.kind != "station_quiet" {
+                debug!("Skipping push event of unknown kind {}", alert.kind);
+            } else if chrono::Utc::now().timestamp() - alert.queued_at > MAX_EVENT_AGE_SECS {
+                debug!("Skipping stale station alert for {}", alert.source_node);
+            } else {
+                handle_station_alert(&client, &alert).await;
+            }
+            continue;
+        }
+        let ev: PushEvent = match serde_json::from_str(&json) {
+            Ok(ev) => ev,
+            Err(e) => {
+                debug!("Skipping malformed push event: {e}");
+                continue;
+            }
+        };
+        if chrono::Utc::now().timestamp() - ev.queued_at > MAX_EVENT_AGE_SECS {
+            debug!("Skipping stale push event for {}", ev.sci_name);
+            continue;
+        }
+        handle_event(&client, &ev, &mut announced).await;
+    }
+}
+
+async fn handle_event(client: &reqwest::Client, ev: &PushEvent, announced: &mut HashSet<String>) {
+    let subs: Vec<Subscription> = match kv::get_push_subscriptions().await {
+        Ok(map) => map
+            .values()
+            .filter_map(|json| serde_json::from_str(json).ok())
+            .collect(),
+        Err(e) => {
+            warn!("Cannot load push subscriptions: {e}");
+            return;
+        }
+    };
+
+    let wants_new = subs.iter().any(|s| s.targets.iter().any(|t| t == PUSH_NEW_SPECIES));
+    let new_species = wants_new
+        && !announced.contains(&ev.sci_name)
+        && matches!(
+            ddb::species_seen_before(&ev.sci_name, &ev.date, &ev.time).await,
+            Ok(false)
+        );
+    if new_species {
+        announced.insert(ev.sci_name.clone());
+    }
+
+    let matching: Vec<&Subscription> = subs
+        .iter()
+        .filter(|s| {
+            s.targets.iter().any(|t| {
+                t == &ev.sci_name
+                    || (new_species && t == PUSH_NEW_SPECIES)
+                    || (ev.rare && t == PUSH_RARE_SPECIES)
+            })
+        })
+        .collect();
+    if matching.is_empty() {
+        return;
+    }
+
+    send_all(client, &matching, &notification_payload(ev, new_species), &ev.sci_name).await;
+}
+
+async fn handle_station_alert(client: &reqwest::Client, alert: &StationAlert) {
+    let subs: Vec<Subscription> = match kv::get_push_subscriptions().await {
+        Ok(map) => map
+            .values()
+            .filter_map(|json| serde_json::from_str(json).ok())
+            .collect(),
+        Err(e) => {
+            warn!("Cannot load push subscriptions: {e}");
+            return;
+        }
+    };
+    let matching: Vec<&Subscription> = subs
+        .iter()
+        .filter(|s| s.targets.iter().any(|t| t == PUSH_STATION_ALERTS))
+        .collect();
+    if matching.is_empty() {
+        return;
+    }
+    send_all(client, &matching, &station_alert_payload(alert), &alert.source_node).await;
+}
+
+/// Deliver `payload` to each of `subs`, dropping expired subscriptions.
+/// `about` names the event in the logs.
+async fn send_all(client: &reqwest::Client, subs: &[&Subscription], payload: &[u8], about: &str) {
+    let key = match signing_key().await {
+        Ok(k) => k,
+        Err(e) => {
+            warn!("Cannot load VAPID key: {e}");
+            return;
+        }
+    };
+    for sub in subs {
+        match deliver(client, &key, sub, payload).await {
+            Delivery::Sent => debug!("Push sent for {about} to {}", sub.endpoint),
+            Delivery::Gone => {
+                info!("Push subscription expired, removing {}", sub.endpoint);
+                let _ = kv::remove_push_subscription(&sub.endpoint).await;
+            }
+            Delivery::Failed(e) => warn!("Push to {} failed: {e}", sub.endpoint),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decrypt as the browser would and check we get the plaintext back.
+    #[test]
+    fn test_encrypt_roundtrip() {
+        use aes_gcm::aead::{Aead, KeyInit};
+
+        let ua_secret = SecretKey::random(&mut OsRng);
+        let ua_public = ua_secret.public_key().to_encoded_point(false);
+        let auth = [7u8; 16];
+        let body = encrypt(
+            &B64.encode(ua_public.as_bytes()),
+            &B64.encode(auth),
+            b"When I grow up, I want to be a watermelon",
+        )
+        .unwrap();
+
+        let salt = &body[..16];
+        assert_eq!(&body[16..20], &RECORD_SIZE.to_be_bytes());
+        assert_eq!(body[20], 65);
+        let as_public = PublicKey::from_sec1_bytes(&body[21..86]).unwrap();
+        let shared = p256::ecdh::diffie_hellman(ua_secret.to_nonzero_scalar(), as_public.as_affine());
+
+        let mut key_info = b"WebPush: info\0".to_vec();
+        key_info.extend_from_slice(ua_public.as_bytes());
+        key_info.extend_from_slice(&body[21..86]);
+        let mut ikm = [0u8; 32];
+        hkdf_expand(&auth, shared.raw_secret_bytes(), &key_info, &mut ikm).unwrap();
+        let mut cek = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        hkdf_expand(salt, &ikm, b"Content-Encoding: aes128gcm\0", &mut cek).unwrap();
+        hkdf_expand(salt, &ikm, b"Content-Encoding: nonce\0", &mut nonce).unwrap();
+
+        let cipher = aes_gcm::Aes128Gcm::new_from_slice(&cek).unwrap();
+        let plain = cipher
+            .decrypt(aes_gcm::Nonce::from_slice(&nonce), &body[86..])
+            .unwrap();
+        assert_eq!(plain.last(), Some(&2));
+        assert_eq!(&plain[..plain.len() - 1], b"When I grow up, I want to be a watermelon");
+    }
+
+    #[test]
+    fn test_station_alert_payload() {
+        // As queued by the processing watchdog.
+        let json = r#"{"kind":"station_quiet","source_node":"roof","domain":"birds",
+            "detections":0,"baseline":41.5,"hours":24,"queued_at":1}"#;
+        let alert: StationAlert = serde_json::from_str(json).unwrap();
+        let shown: serde_json::Value =
+            serde_json::from_slice(&station_alert_payload(&alert)).unwrap();
+        assert_eq!(shown["title"], "Station roof is quiet");
+        assert_eq!(shown["body"], "0 birds detections in 24 h, usually 42. Check its microphone.");
+        assert_eq!(shown["url"], "/stations");
+
+        // Detection events are not mistaken for alerts.
+        let detection = r#"{"sci_name":"Turdus merula","com_name":"Common Blackbird",
+            "confidence":0.9,"date":"2026-05-01","time":"06:00:00","source_node":"roof"}"#;
+        assert!(serde_json::from_str::<StationAlert>(detection).is_err());
+    }
+
+    #[test]
+    fn test_vapid_authorization_format() {
+        let key = SigningKey::random(&mut OsRng);
+        let header = vapid_authorization(&key, "https://push.example.net:8443/wpush/v2/abc").unwrap();
+        let (token, k) = header
+            .strip_prefix("vapid t=")
+            .and_then(|rest| rest.split_once(", k="))
+            .unwrap();
+        assert_eq!(token.split('.').count(), 3);
+        let claims: serde_json::Value =
+            serde_json::from_slice(&B64.decode(token.split('.').nth(1).unwrap()).unwrap()).unwrap();
+        assert_eq!(claims["aud"], "https://push.example.net:8443");
+        assert_eq!(B64.decode(k).unwrap().len(), 65);
+    }
+}
@@ -0,0 +1,50 @@
+//! Helpers for detection permalinks shared outside the dashboard.
+//!
+//! Link previews in chat apps and social networks are built from the
+//! page's OpenGraph tags by a crawler that never runs the WASM bundle,
+//! so the tags are rendered during SSR and every URL in them must be
+//! absolute.
+
+use http::HeaderMap;
+
+/// `scheme://host` the request was made to, honouring the
+/// `X-Forwarded-Proto` / `X-Forwarded-Host` headers a reverse proxy sets.
+/// Empty when the request carries no host at all.
+pub fn request_origin(headers: &HeaderMap) -> String {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            // Proxies chain values: the first one is the client's.
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let Some(host) = header("x-forwarded-host").or_else(|| header("host")) else {
+        return String::new();
+    };
+    let scheme = match header("x-forwarded-proto") {
+        Some(p) if p.eq_ignore_ascii_case("https") => "https",
+        _ => "http",
+    };
+    format!("{scheme}://{host}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_request_origin() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_origin(&headers), "");
+
+        headers.insert("host", HeaderValue::from_static("gaia.local:3000"));
+        assert_eq!(request_origin(&headers), "http://gaia.local:3000");
+
+        headers.insert("x-forwarded-host", HeaderValue::from_static("birds.example.org"));
+        headers.insert("x-forwarded-proto", HeaderValue::from_static("https, http"));
+        assert_eq!(request_origin(&headers), "https://birds.example.org");
+    }
+}
@@ -0,0 +1,528 @@
+//! Species photo lookup with a provider chain and an in-memory cache.
+//!
+//! Photos come from the first provider that has one: iNaturalist, then
+//! Wikimedia Commons, then the local override directory
+//! (`GAIA_SPECIES_IMAGES_DIR`, served under [`LOCAL_URL_PREFIX`]).  A photo
+//! pinned by an admin on the species page replaces whatever the chain
+//! found.  Every photo keeps its provider and credit line so the UI can
+//! attribute it.
+//!
+//! The cache is versioned: when new fields are added to [`SpeciesPhoto`]
+//! the [`CACHE_VERSION`] is bumped, causing stale entries to be re-fetched
+//! automatically after an upgrade.  Photos from the fallback providers
+//! expire after [`FALLBACK_TTL`] so iNaturalist is asked again once its
+//! rate limit has passed.
+//!
+//! Page renders only read the cache ([`lookup_cached`]).  Fetching is left
+//! to a background worker ([`run_enrichment`]) that walks every known
+//! species, one lookup per [`ENRICH_INTERVAL`], and picks up species a page
+//! asked for first.  Admins can refetch a single species on demand
+//! ([`refresh`]).
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::model::{ImageSource, SpeciesPhoto};
+use crate::server::{detections_duckdb, inaturalist, kv};
+
+/// Bump this whenever [`SpeciesPhoto`] gains new fields that require a
+/// fresh fetch.  Stale cache entries with an older version are silently
+/// discarded and re-fetched.
+const CACHE_VERSION: u16 = 4;
+
+/// How long a Wikimedia Commons or local photo is served before
+/// iNaturalist is tried again.
+const FALLBACK_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// Pause between two lookups of the enrichment worker.  A lookup makes up
+/// to four iNaturalist calls, which keeps the worker at about one call a
+/// second, the rate iNaturalist asks API clients to stay under.
+const ENRICH_INTERVAL: Duration = Duration::from_secs(4);
+
+/// How often the enrichment worker looks for species whose photo is
+/// missing or stale.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Species waiting for the enrichment worker.
+static PENDING: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Wakes the enrichment worker when a species is queued.
+static WAKE: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+/// URL prefix the local override directory is served under.
+pub const LOCAL_URL_PREFIX: &str = "/species-images";
+
+/// Image types picked up from the local override directory.
+const LOCAL_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+
+/// Wikimedia rejects API calls without a descriptive User-Agent.
+const USER_AGENT: &str = concat!("gaia-audio/", env!("CARGO_PKG_VERSION"));
+
+/// Wrapper stored in the cache so we can detect outdated entries.
+#[derive(Clone, Debug)]
+pub struct CacheEntry {
+    version: u16,
+    photo: Option<SpeciesPhoto>,
+    expires: Option<Instant>,
+}
+
+/// Thread-safe cache shared across requests.
+pub type PhotoCache = Arc<Mutex<HashMap<String, CacheEntry>>>;
+
+/// Create an empty cache.
+pub fn new_cache() -> PhotoCache {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Local override directory (`GAIA_SPECIES_IMAGES_DIR`).
+///
+/// Holds `<Scientific name>.jpg` (or `.jpeg`, `.png`, `.webp`; spaces may
+/// be underscores), optionally with a `<Scientific name>.txt` whose first
+/// line is the credit shown under the photo.
+pub fn local_dir() -> PathBuf {
+    PathBuf::from(
+        std::env::var("GAIA_SPECIES_IMAGES_DIR").unwrap_or_else(|_| "data/species-images".into()),
+    )
+}
+
+/// Read a species photo from cache without performing any network call.
+///
+/// Returns `None` when absent or from an older cache version.  Missing,
+/// outdated and expired entries are queued for the enrichment worker, so
+/// the photo shows up on a later render.
+pub fn lookup_cached(cache: &PhotoCache, scientific_name: &str) -> Option<SpeciesPhoto> {
+    let (photo, stale) = {
+        let guard = cache.lock().unwrap();
+        let entry = guard.get(scientific_name);
+        let photo = entry
+            .filter(|entry| entry.version == CACHE_VERSION)
+            .and_then(|entry| entry.photo.clone());
+        (photo, is_stale(entry, Instant::now()))
+    };
+    if stale {
+        enqueue(scientific_name, true);
+    }
+    photo
+}
+
+/// Look up a species photo.  Returns a cached result if available and
+/// up-to-date, otherwise walks the provider chain (and caches the
+/// answer).  Only the enrichment worker and admin actions call this;
+/// pages use [`lookup_cached`].
+pub async fn lookup(cache: &PhotoCache, scientific_name: &str) -> Option<SpeciesPhoto> {
+    // Fast-path: serve from cache if version matches and it has not expired
+    {
+        let guard = cache.lock().unwrap();
+        let entry = guard.get(scientific_name);
+        if !is_stale(entry, Instant::now()) {
+            return entry.and_then(|entry| entry.photo.clone());
+        }
+        // Stale; fall through to re-fetch.
+    }
+
+    let fetched = fetch(scientific_name).await;
+    let pin = kv::get_image_pin(scientific_name).await;
+    if let Err(e) = &pin {
+        tracing::warn!("Cannot read image pin for {scientific_name}: {e}");
+    }
+
+    // Only cache successful results.  Transient failures (API rate-
+    // limiting, network blips) return None — leaving them uncached
+    // allows the next request to retry instead of permanently showing
+    // placeholder.svg.
+    let cacheable = fetched.is_some() && pin.is_ok();
+    let result = match pin {
+        Ok(Some((url, attribution))) => Some(apply_pin(fetched, url, attribution)),
+        _ => fetched,
+    };
+    if cacheable {
+        let expires = result
+            .as_ref()
+            .filter(|p| matches!(p.source, ImageSource::WikimediaCommons | ImageSource::Local))
+            .map(|_| Instant::now() + FALLBACK_TTL);
+        let mut guard = cache.lock().unwrap();
+        guard.insert(
+            scientific_name.to_string(),
+            CacheEntry {
+                version: CACHE_VERSION,
+                photo: result.clone(),
+                expires,
+            },
+        );
+    }
+
+    result
+}
+
+/// Fetch a species photo again straight away.  The cached photo is kept
+/// (and the species queued for the worker) when the providers fail.
+pub async fn refresh(cache: &PhotoCache, scientific_name: &str) -> Option<SpeciesPhoto> {
+    if let Some(entry) = cache.lock().unwrap().get_mut(scientific_name) {
+        entry.expires = Some(Instant::now());
+    }
+    match lookup(cache, scientific_name).await {
+        Some(photo) => Some(photo),
+        None => lookup_cached(cache, scientific_name),
+    }
+}
+
+/// Background enrichment: every [`SWEEP_INTERVAL`] queues each known
+/// species whose photo is missing or stale, and fetches the queue one
+/// species per [`ENRICH_INTERVAL`].  Species a page asked for jump the
+/// queue.  Runs forever; spawn it once at startup.
+pub async fn run_enrichment(cache: PhotoCache, db_path: PathBuf) {
+    let mut next_sweep = tokio::time::Instant::now();
+    loop {
+        if tokio::time::Instant::now() >= next_sweep {
+            match detections_duckdb::known_species(&db_path).await {
+                Ok(names) => {
+                    let now = Instant::now();
+                    let stale: Vec<String> = {
+                        let guard = cache.lock().unwrap();
+                        names
+                            .into_iter()
+                            .filter(|n| is_stale(guard.get(n), now))
+                            .collect()
+                    };
+                    if !stale.is_empty() {
+                        tracing::info!(
+                            "Fetching photos for {} species in the background",
+                            stale.len()
+                        );
+                    }
+                    for name in &stale {
+                        enqueue(name, false);
+                    }
+                }
+                Err(e) => tracing::warn!("Photo enrichment cannot list species: {e}"),
+            }
+            next_sweep = tokio::time::Instant::now() + SWEEP_INTERVAL;
+        }
+
+        let next = PENDING.lock().unwrap().pop_front();
+        let Some(name) = next else {
+            let _ = tokio::time::timeout_at(next_sweep, WAKE.notified()).await;
+            continue;
+        };
+        let stale = is_stale(cache.lock().unwrap().get(&name), Instant::now());
+        if stale {
+            if lookup(&cache, &name).await.is_none() {
+                tracing::debug!("No photo found for {name}; retrying on the next sweep");
+            }
+            tokio::time::sleep(ENRICH_INTERVAL).await;
+        }
+    }
+}
+
+/// Whether a cache entry has to be fetched again: missing, from an older
+/// cache version, or expired.
+fn is_stale(entry: Option<&CacheEntry>, now: Instant) -> bool {
+    entry.is_none_or(|entry| {
+        entry.version != CACHE_VERSION || entry.expires.is_some_and(|t| now >= t)
+    })
+}
+
+/// Queue a species for the enrichment worker, once.  `urgent` species
+/// (asked for by a page) go to the front.
+fn enqueue(scientific_name: &str, urgent: bool) {
+    let mut pending = PENDING.lock().unwrap();
+    if pending.iter().any(|n| n == scientific_name) {
+        return;
+    }
+    if urgent {
+        pending.push_front(scientific_name.to_string());
+    } else {
+        pending.push_back(scientific_name.to_string());
+    }
+    WAKE.notify_one();
+}
+
+/// Pin `url` as the photo of a species.  `url` is either absolute or a
+/// file in the local override directory (`/species-images/...`).
+pub async fn pin(
+    cache: &PhotoCache,
+    scientific_name: &str,
+    url: &str,
+    attribution: &str,
+) -> Result<(), String> {
+    let url = url.trim();
+    let local = url
+        .strip_prefix(LOCAL_URL_PREFIX)
+        .is_some_and(|rest| rest.starts_with('/'));
+    if !(url.starts_with("https://") || url.starts_with("http://") || local) {
+        return Err(format!(
+            "Image URL must start with https:// or {LOCAL_URL_PREFIX}/"
+        ));
+    }
+    kv::set_image_pin(scientific_name, url, attribution.trim()).await?;
+    cache.lock().unwrap().remove(scientific_name);
+    Ok(())
+}
+
+/// Drop the pinned photo of a species; the provider chain applies again.
+pub async fn unpin(cache: &PhotoCache, scientific_name: &str) -> Result<(), String> {
+    kv::remove_image_pin(scientific_name).await?;
+    cache.lock().unwrap().remove(scientific_name);
+    Ok(())
+}
+
+/// First photo from the provider chain.
+async fn fetch(scientific_name: &str) -> Option<SpeciesPhoto> {
+    if let Some(photo) = inaturalist::fetch_photo(scientific_name).await {
+        return Some(photo);
+    }
+    if let Some(photo) = fetch_from_commons(scientific_name).await {
+        tracing::debug!("Photo for {scientific_name} from Wikimedia Commons");
+        return Some(photo);
+    }
+    local_photo(&local_dir(), scientific_name)
+}
+
+/// Replace the main photo with a pinned one, keeping the other
+/// iNaturalist data (Wikipedia link, status, sex photos) when there is any.
+fn apply_pin(photo: Option<SpeciesPhoto>, url: String, attribution: String) -> SpeciesPhoto {
+    let mut photo = photo.unwrap_or_else(|| SpeciesPhoto {
+        medium_url: String::new(),
+        attribution: String::new(),
+        source: ImageSource::Pinned,
+        wikipedia_url: None,
+        conservation_status: None,
+        male_image_url: None,
+        female_image_url: None,
+    });
+    photo.medium_url = url;
+    photo.attribution = attribution;
+    photo.source = ImageSource::Pinned;
+    photo
+}
+
+// ─── Wikimedia Commons ───────────────────────────────────────────────────────
+
+/// Search Commons for a bitmap file mentioning the scientific name.
+async fn fetch_from_commons(scientific_name: &str) -> Option<SpeciesPhoto> {
+    let search = format!("\"{scientific_name}\" filetype:bitmap");
+    let url = format!(
+        "https://commons.wikimedia.org/w/api.php?action=query&format=json&formatversion=2\
+         &generator=search&gsrnamespace=6&gsrlimit=1&gsrsearch={}\
+         &prop=imageinfo&iiprop=url%7Cextmetadata&iiurlwidth=500",
+        percent_encode(&search),
+    );
+    let client = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(15))
+        .build()
+        .ok()?;
+    let body: serde_json::Value = client.get(&url).send().await.ok()?.json().await.ok()?;
+    parse_commons(&body)
+}
+
+/// Photo and credit line from a Commons `imageinfo` response.
+fn parse_commons(body: &serde_json::Value) -> Option<SpeciesPhoto> {
+    let info = body
+        .get("query")?
+        .get("pages")?
+        .as_array()?
+        .first()?
+        .get("imageinfo")?
+        .as_array()?
+        .first()?;
+    let medium_url = info
+        .get("thumburl")
+        .or_else(|| info.get("url"))?
+        .as_str()?
+        .to_string();
+
+    // Artist and licence are HTML snippets in `extmetadata`.
+    let field = |key: &str| {
+        info.get("extmetadata")
+            .and_then(|m| m.get(key))
+            .and_then(|f| f.get("value"))
+            .and_then(|v| v.as_str())
+            .map(strip_tags)
+            .filter(|s| !s.is_empty())
+    };
+    let attribution = match (field("Artist"), field("LicenseShortName")) {
+        (Some(artist), Some(licence)) => format!("{artist} ({licence})"),
+        (Some(text), None) | (None, Some(text)) => text,
+        (None, None) => String::new(),
+    };
+
+    Some(SpeciesPhoto {
+        medium_url,
+        attribution,
+        source: ImageSource::WikimediaCommons,
+        wikipedia_url: None,
+        conservation_status: None,
+        male_image_url: None,
+        female_image_url: None,
+    })
+}
+
+/// Plain text of an HTML snippet, with whitespace collapsed.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ─── Local override directory ────────────────────────────────────────────────
+
+/// Photo for `scientific_name` in `dir`, if there is a file for it.
+fn local_photo(dir: &Path, scientific_name: &str) -> Option<SpeciesPhoto> {
+    let stems = [
+        scientific_name.to_string(),
+        scientific_name.replace(' ', "_"),
+    ];
+    for stem in &stems {
+        for ext in LOCAL_EXTENSIONS {
+            let file = format!("{stem}.{ext}");
+            if !dir.join(&file).is_file() {
+                continue;
+            }
+            let attribution = std::fs::read_to_string(dir.join(format!("{stem}.txt")))
+                .ok()
+                .and_then(|t| t.lines().next().map(|l| l.trim().to_string()))
+                .unwrap_or_default();
+            return Some(SpeciesPhoto {
+                medium_url: format!("{LOCAL_URL_PREFIX}/{}", percent_encode(&file)),
+                attribution,
+                source: ImageSource::Local,
+                wikipedia_url: None,
+                conservation_status: None,
+                male_image_url: None,
+                female_image_url: None,
+            });
+        }
+    }
+    None
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commons_response_is_attributed() {
+        let body = serde_json::json!({
+            "query": { "pages": [{
+                "title": "File:Turdus merula 2.jpg",
+                "imageinfo": [{
+                    "url": "https://upload.wikimedia.org/full.jpg",
+                    "thumburl": "https://upload.wikimedia.org/500px.jpg",
+                    "extmetadata": {
+                        "Artist": { "value": "<a href=\"//commons.wikimedia.org/wiki/User:Ann\">Ann  Example</a>" },
+                        "LicenseShortName": { "value": "CC BY-SA 4.0" }
+                    }
+                }]
+            }]}
+        });
+        let photo = parse_commons(&body).unwrap();
+        assert_eq!(photo.medium_url, "https://upload.wikimedia.org/500px.jpg");
+        assert_eq!(photo.attribution, "Ann Example (CC BY-SA 4.0)");
+        assert_eq!(photo.source, ImageSource::WikimediaCommons);
+
+        assert!(parse_commons(&serde_json::json!({ "batchcomplete": true })).is_none());
+    }
+
+    #[test]
+    fn local_photo_with_credit_file() {
+        let dir = std::env::temp_dir().join(format!("gaia_species_images_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Turdus_merula.png"), b"png").unwrap();
+        std::fs::write(dir.join("Turdus_merula.txt"), "Photo: J. Doe\n").unwrap();
+        std::fs::write(dir.join("Erithacus rubecula.jpg"), b"jpg").unwrap();
+
+        let photo = local_photo(&dir, "Turdus merula").unwrap();
+        assert_eq!(photo.medium_url, "/species-images/Turdus_merula.png");
+        assert_eq!(photo.attribution, "Photo: J. Doe");
+        assert_eq!(photo.source, ImageSource::Local);
+
+        let photo = local_photo(&dir, "Erithacus rubecula").unwrap();
+        assert_eq!(photo.medium_url, "/species-images/Erithacus%20rubecula.jpg");
+        assert_eq!(photo.attribution, "");
+
+        assert!(local_photo(&dir, "Parus major").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stale_entries() {
+        let now = Instant::now();
+        let entry = |version, expires| CacheEntry {
+            version,
+            photo: None,
+            expires,
+        };
+        assert!(is_stale(None, now));
+        assert!(!is_stale(Some(&entry(CACHE_VERSION, None)), now));
+        assert!(is_stale(Some(&entry(CACHE_VERSION - 1, None)), now));
+        let later = now + Duration::from_secs(60);
+        assert!(!is_stale(Some(&entry(CACHE_VERSION, Some(later))), now));
+        assert!(is_stale(Some(&entry(CACHE_VERSION, Some(now))), now));
+    }
+
+    #[test]
+    fn urgent_species_jump_the_queue() {
+        enqueue("Test sweep a", false);
+        enqueue("Test sweep b", false);
+        enqueue("Test page", true);
+        enqueue("Test sweep a", true);
+        let pending: Vec<String> = PENDING
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|n| n.starts_with("Test "))
+            .cloned()
+            .collect();
+        assert_eq!(pending, ["Test page", "Test sweep a", "Test sweep b"]);
+    }
+
+    #[test]
+    fn pin_keeps_inaturalist_extras() {
+        let photo = SpeciesPhoto {
+            medium_url: "https://inat/medium.jpg".into(),
+            attribution: "(c) someone".into(),
+            source: ImageSource::INaturalist,
+            wikipedia_url: Some("https://en.wikipedia.org/wiki/Common_blackbird".into()),
+            conservation_status: None,
+            male_image_url: None,
+            female_image_url: None,
+        };
+        let pinned = apply_pin(Some(photo), "/species-images/a.jpg".into(), "Me".into());
+        assert_eq!(pinned.medium_url, "/species-images/a.jpg");
+        assert_eq!(pinned.source, ImageSource::Pinned);
+        assert!(pinned.wikipedia_url.is_some());
+
+        let pinned = apply_pin(None, "https://x/y.jpg".into(), String::new());
+        assert_eq!(pinned.source, ImageSource::Pinned);
+    }
+}
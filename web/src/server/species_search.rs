@@ -0,0 +1,269 @@
+//! Species suggestions for relabelling a detection.
+//!
+//! Instead of typing a name, the admin picks from the model's stored
+//! top-K predictions or from a fuzzy search over the model's label set,
+//! matching scientific names and the common names in every language the
+//! model ships.  A relabel then always names a species the rest of the
+//! dashboard (stats, species pages, taxonomy joins) already knows.
+
+use crate::model::{SpeciesSuggestion, TopPrediction};
+
+/// Most search results offered at once.
+pub const MAX_RESULTS: usize = 12;
+
+/// Shortest query searched; shorter ones match most of the label set.
+pub const MIN_QUERY_CHARS: usize = 2;
+
+/// One label of a model, from its species reference file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeciesLabel {
+    pub scientific_name: String,
+    pub common_name: String,
+    /// Common names in every language the model ships.
+    pub localized: Vec<String>,
+}
+
+/// The stored predictions as suggestions, best first, leaving out the
+/// species the detection already has.
+pub fn from_predictions(mut predictions: Vec<TopPrediction>, current: &str) -> Vec<SpeciesSuggestion> {
+    predictions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    predictions
+        .into_iter()
+        .filter(|p| p.scientific_name != current)
+        .map(|p| SpeciesSuggestion {
+            scientific_name: p.scientific_name,
+            common_name: p.common_name,
+            confidence: Some(p.confidence),
+            matched_name: None,
+        })
+        .collect()
+}
+
+/// Up to `limit` labels matching `query`, best match first.
+///
+/// Matches rank as exact, prefix, word prefix(es), substring, one typo
+/// off and finally the query's letters in order; ties go to the shorter
+/// name, then alphabetically.  Case and accents are ignored.
+pub fn search(query: &str, labels: &[SpeciesLabel], limit: usize) -> Vec<SpeciesSuggestion> {
+    let query = fold(query);
+    if query.chars().count() < MIN_QUERY_CHARS {
+        return Vec::new();
+    }
+    let mut hits: Vec<(u32, usize, &SpeciesLabel, &str)> = labels
+        .iter()
+        .filter_map(|label| {
+            let names = [label.scientific_name.as_str(), label.common_name.as_str()]
+                .into_iter()
+                .chain(label.localized.iter().map(String::as_str));
+            names
+                .filter_map(|name| Some((score(&query, &fold(name))?, name.chars().count(), label, name)))
+                .min_by_key(|&(score, len, ..)| (score, len))
+        })
+        .collect();
+    hits.sort_by(|a, b| (a.0, a.1, &a.2.scientific_name).cmp(&(b.0, b.1, &b.2.scientific_name)));
+    hits.into_iter()
+        .take(limit)
+        .map(|(_, _, label, name)| SpeciesSuggestion {
+            scientific_name: label.scientific_name.clone(),
+            common_name: label.common_name.clone(),
+            confidence: None,
+            matched_name: (name != label.scientific_name && name != label.common_name)
+                .then(|| name.to_string()),
+        })
+        .collect()
+}
+
+/// How well `query` matches `name` (both folded); lower is better,
+/// `None` for no match.
+fn score(query: &str, name: &str) -> Option<u32> {
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(query) {
+        return Some(1);
+    }
+    let words: Vec<&str> = name.split(' ').collect();
+    let query_words: Vec<&str> = query.split(' ').collect();
+    if query_words.iter().all(|q| words.iter().any(|w| w.starts_with(q))) {
+        return Some(2);
+    }
+    if name.contains(query) {
+        return Some(3);
+    }
+    let query: Vec<char> = query.chars().collect();
+    if query.len() >= 4 && one_typo_off(&query, name) {
+        return Some(4);
+    }
+    subsequence_gaps(&query, name).map(|gaps| 5 + gaps)
+}
+
+/// Whether some stretch of `name` starting at a word differs from
+/// `query` by at most one inserted, deleted, replaced or swapped letter.
+fn one_typo_off(query: &[char], name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let mut starts = (0..name.len()).filter(|&i| i == 0 || name[i - 1] == ' ');
+    starts.any(|start| {
+        (query.len() - 1..=query.len() + 1).any(|len| {
+            name.get(start..start + len)
+                .is_some_and(|stretch| edit_distance(query, stretch) <= 1)
+        })
+    })
+}
+
+/// Optimal string alignment distance (Levenshtein plus swaps).
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = d;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Letters skipped in `name` to find `query`'s letters in order, taking
+/// the first occurrence of each; `None` when they are not all there.
+fn subsequence_gaps(query: &[char], name: &str) -> Option<u32> {
+    if query.len() < 3 {
+        return None;
+    }
+    let mut rest = name.chars().skip_while(|&c| c != query[0]);
+    let mut gaps = 0;
+    for &q in query {
+        loop {
+            match rest.next() {
+                Some(c) if c == q => break,
+                Some(_) => gaps += 1,
+                None => return None,
+            }
+        }
+    }
+    Some(gaps)
+}
+
+/// Lowercase, strip accents from Latin letters and turn everything that
+/// is not a letter or digit into single spaces.
+fn fold(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars().flat_map(char::to_lowercase) {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => out.push('a'),
+            'æ' => out.push_str("ae"),
+            'ç' | 'č' | 'ć' => out.push('c'),
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ě' => out.push('e'),
+            'ì' | 'í' | 'î' | 'ï' | 'ī' => out.push('i'),
+            'ñ' | 'ń' | 'ň' => out.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => out.push('o'),
+            'œ' => out.push_str("oe"),
+            'ř' => out.push('r'),
+            'š' | 'ś' => out.push('s'),
+            'ß' => out.push_str("ss"),
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => out.push('u'),
+            'ý' | 'ÿ' => out.push('y'),
+            'ž' | 'ź' | 'ż' => out.push('z'),
+            'ł' => out.push('l'),
+            c if c.is_alphanumeric() => out.push(c),
+            _ => {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(sci: &str, com: &str, localized: &[&str]) -> SpeciesLabel {
+        SpeciesLabel {
+            scientific_name: sci.into(),
+            common_name: com.into(),
+            localized: localized.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn labels() -> Vec<SpeciesLabel> {
+        vec![
+            label("Turdus merula", "Eurasian Blackbird", &["Mirlo común", "Amsel"]),
+            label("Turdus grayi", "Clay-colored Thrush", &["Yigüirro", "Mirlo pardo"]),
+            label("Turdus assimilis", "White-throated Thrush", &["Mirlo gorgiblanco"]),
+            label("Erithacus rubecula", "European Robin", &["Petirrojo europeo", "Rotkehlchen"]),
+            label("Strix varia", "Barred Owl", &["Búho listado"]),
+        ]
+    }
+
+    fn names(found: &[SpeciesSuggestion]) -> Vec<&str> {
+        found.iter().map(|s| s.scientific_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("  Mirlo común "), "mirlo comun");
+        assert_eq!(fold("Clay-colored  Thrush"), "clay colored thrush");
+        assert_eq!(fold("Yigüirro"), "yiguirro");
+        assert_eq!(fold("Weißstorch"), "weissstorch");
+    }
+
+    #[test]
+    fn test_search_ranks_matches() {
+        let labels = labels();
+        // Prefix of a common name beats a word prefix of another.
+        let found = search("turdus", &labels, MAX_RESULTS);
+        assert_eq!(names(&found), ["Turdus grayi", "Turdus merula", "Turdus assimilis"]);
+
+        // Localized names, without accents, and which name matched.
+        let found = search("yiguirro", &labels, MAX_RESULTS);
+        assert_eq!(names(&found), ["Turdus grayi"]);
+        assert_eq!(found[0].matched_name.as_deref(), Some("Yigüirro"));
+        assert_eq!(names(&search("mirlo comun", &labels, MAX_RESULTS)), ["Turdus merula"]);
+
+        // Word prefixes in any order.
+        assert_eq!(names(&search("thr whi", &labels, MAX_RESULTS)), ["Turdus assimilis"]);
+        // One typo.
+        let found = search("Blakbird", &labels, MAX_RESULTS);
+        assert_eq!(names(&found), ["Turdus merula"]);
+        assert_eq!(found[0].matched_name, None);
+        // Letters in order.
+        assert_eq!(names(&search("eurrob", &labels, MAX_RESULTS)), ["Erithacus rubecula"]);
+
+        assert!(search("t", &labels, MAX_RESULTS).is_empty());
+        assert!(search("penguin", &labels, MAX_RESULTS).is_empty());
+        assert_eq!(search("mirlo", &labels, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_from_predictions() {
+        let prediction = |sci: &str, confidence: f64| TopPrediction {
+            scientific_name: sci.into(),
+            common_name: String::new(),
+            confidence,
+        };
+        let found = from_predictions(
+            vec![
+                prediction("Turdus merula", 0.2),
+                prediction("Turdus grayi", 0.7),
+                prediction("Turdus assimilis", 0.1),
+            ],
+            "Turdus grayi",
+        );
+        assert_eq!(names(&found), ["Turdus merula", "Turdus assimilis"]);
+        assert_eq!(found[0].confidence, Some(0.2));
+    }
+}
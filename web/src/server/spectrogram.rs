@@ -0,0 +1,254 @@
+//! On-demand spectrogram tiles for the zoomable clip viewer.
+//!
+//! `GET /api/spectrogram-tile?clip=/extracted/By_Date/…&t0=&t1=&f0=&f1=&w=&h=`
+//! renders a time (seconds) × frequency (Hz) window of a clip as a PNG of
+//! `w`×`h` pixels.  The FFT size follows the frequency zoom so a pixel row
+//! covers about one bin, and levels map onto a fixed dBFS range so tiles
+//! of the same clip match while panning.
+//!
+//! WAV clips are read directly; Opus and MP3 clips are decoded by
+//! `ffmpeg` at 48 kHz.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::Query;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use image::{ImageBuffer, Rgb, RgbImage};
+use rustfft::{num_complex::Complex, FftPlanner};
+use serde::Deserialize;
+
+/// Largest tile edge accepted, in pixels.
+const MAX_TILE_PX: u32 = 2048;
+
+/// Levels shown from black (floor) to full colour (ceiling), in dBFS.
+const DB_FLOOR: f32 = -100.0;
+const DB_CEIL: f32 = -20.0;
+
+/// Query of `/api/spectrogram-tile`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileParams {
+    /// Clip URL as shown on the detection card (`/extracted/By_Date/…`).
+    pub clip: String,
+    pub t0: f64,
+    pub t1: f64,
+    pub f0: f64,
+    pub f1: f64,
+    pub w: u32,
+    pub h: u32,
+}
+
+/// A clip decoded to mono samples.
+pub struct DecodedClip {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+impl DecodedClip {
+    pub fn duration_secs(&self) -> f64 {
+        self.samples.len() as f64 / f64::from(self.sample_rate.max(1))
+    }
+}
+
+/// The most recently decoded clip: a viewer asks for many tiles of the
+/// same clip while zooming.
+static LAST_CLIP: Mutex<Option<(PathBuf, Arc<DecodedClip>)>> = Mutex::new(None);
+
+/// Axum handler for `/api/spectrogram-tile`.
+pub async fn tile(extracted_dir: PathBuf, Query(params): Query<TileParams>) -> Response {
+    let rendered = tokio::task::spawn_blocking(move || {
+        let clip = load_clip(&extracted_dir, &params.clip)?;
+        let img = render(&clip, &params);
+        let mut png = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| format!("PNG encoding failed: {e}"))?;
+        Ok::<_, String>(png.into_inner())
+    })
+    .await;
+
+    match rendered {
+        Ok(Ok(png)) => (
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "public, max-age=3600"),
+            ],
+            png,
+        )
+            .into_response(),
+        Ok(Err(e)) => (StatusCode::NOT_FOUND, e).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Render task failed: {e}")).into_response(),
+    }
+}
+
+/// Decode the clip at `clip_url` (a `/extracted/…` URL), reusing the
+/// last decoded clip when it is the same file.
+pub fn load_clip(extracted_dir: &Path, clip_url: &str) -> Result<Arc<DecodedClip>, String> {
+    let uri_path = clip_url.strip_prefix("/extracted").unwrap_or(clip_url);
+    let path = super::clips::resolve_legacy_path(extracted_dir, uri_path)
+        .ok_or_else(|| format!("Clip not found: {clip_url}"))?;
+
+    if let Ok(last) = LAST_CLIP.lock() {
+        if let Some((cached, clip)) = last.as_ref() {
+            if *cached == path {
+                return Ok(clip.clone());
+            }
+        }
+    }
+    let is_wav = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    let clip = Arc::new(if is_wav { decode_wav(&path)? } else { decode_ffmpeg(&path)? });
+    if let Ok(mut last) = LAST_CLIP.lock() {
+        *last = Some((path, clip.clone()));
+    }
+    Ok(clip)
+}
+
+/// Read a WAV clip, averaging the channels.
+fn decode_wav(path: &Path) -> Result<DecodedClip, String> {
+    let mut reader =
+        hound::WavReader::open(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().filter_map(Result::ok).collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .filter_map(Result::ok)
+                .map(|s| s as f32 / scale)
+                .collect()
+        }
+    };
+    let channels = usize::from(spec.channels.max(1));
+    let samples = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok(DecodedClip {
+        samples,
+        sample_rate: spec.sample_rate,
+    })
+}
+
+/// Decode a compressed clip to 48 kHz mono with `ffmpeg`.
+fn decode_ffmpeg(path: &Path) -> Result<DecodedClip, String> {
+    const RATE: u32 = 48_000;
+    let output = std::process::Command::new("ffmpeg")
+        .args(["-v", "error", "-nostdin", "-i"])
+        .arg(path)
+        .args(["-f", "f32le", "-ac", "1", "-ar", &RATE.to_string(), "-"])
+        .output()
+        .map_err(|e| format!("Cannot run ffmpeg: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg could not decode {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let samples = output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    Ok(DecodedClip {
+        samples,
+        sample_rate: RATE,
+    })
+}
+
+/// Render the tile described by `p`.  Out-of-range windows are clamped
+/// to the clip and to the Nyquist frequency.
+fn render(clip: &DecodedClip, p: &TileParams) -> RgbImage {
+    let width = p.w.clamp(16, MAX_TILE_PX);
+    let height = p.h.clamp(16, MAX_TILE_PX);
+    let rate = f64::from(clip.sample_rate.max(1));
+    let nyquist = rate / 2.0;
+    let duration = clip.duration_secs();
+
+    let t0 = p.t0.clamp(0.0, duration);
+    let t1 = if p.t1 > t0 { p.t1.min(duration.max(t0 + 0.01)) } else { t0 + 0.01 };
+    let f0 = p.f0.clamp(0.0, nyquist - 1.0);
+    let f1 = if p.f1 > f0 { p.f1.min(nyquist) } else { nyquist };
+
+    // About one FFT bin per pixel row.
+    let fft_size = ((rate * f64::from(height) / (f1 - f0)) as usize)
+        .clamp(256, 8192)
+        .next_power_of_two();
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(fft_size);
+    let window: Vec<f32> = (0..fft_size)
+        .map(|i| {
+            0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (fft_size - 1) as f32).cos())
+        })
+        .collect();
+    // A full-scale sine reads 0 dBFS.
+    let norm = window.iter().sum::<f32>() / 2.0;
+
+    let mut img = ImageBuffer::new(width, height);
+    let mut buf = vec![Complex::new(0.0f32, 0.0); fft_size];
+    for x in 0..width {
+        let centre = t0 + (f64::from(x) + 0.5) * (t1 - t0) / f64::from(width);
+        let start = (centre * rate) as i64 - (fft_size / 2) as i64;
+        for (i, slot) in buf.iter_mut().enumerate() {
+            let sample = usize::try_from(start + i as i64)
+                .ok()
+                .and_then(|idx| clip.samples.get(idx))
+                .copied()
+                .unwrap_or(0.0);
+            *slot = Complex::new(sample * window[i], 0.0);
+        }
+        fft.process(&mut buf);
+
+        for y in 0..height {
+            let freq = f1 - (f64::from(y) + 0.5) * (f1 - f0) / f64::from(height);
+            let bin = ((freq / rate * fft_size as f64).round() as usize).min(fft_size / 2);
+            let db = 20.0 * (buf[bin].norm() / norm + 1e-10).log10();
+            let v = ((db - DB_FLOOR) / (DB_CEIL - DB_FLOOR)).clamp(0.0, 1.0);
+            img.put_pixel(x, y, colour(v));
+        }
+    }
+    img
+}
+
+/// Blue → green → red ramp, matching the default static spectrograms.
+fn colour(v: f32) -> Rgb<u8> {
+    let r = (255.0 * (3.0 * v - 1.0).clamp(0.0, 1.0)) as u8;
+    let g = (255.0 * (3.0 * v).clamp(0.0, 1.0).min((3.0 - 3.0 * v).clamp(0.0, 1.0))) as u8;
+    let b = (255.0 * (2.0 - 3.0 * v).clamp(0.0, 1.0)) as u8;
+    Rgb([r, g, b])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_tone_row() {
+        let rate = 48_000u32;
+        let samples = (0..rate)
+            .map(|i| 1e-3 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / rate as f32).sin())
+            .collect();
+        let clip = DecodedClip { samples, sample_rate: rate };
+        let params = TileParams {
+            clip: String::new(),
+            t0: 0.25,
+            t1: 0.75,
+            f0: 0.0,
+            f1: 4000.0,
+            w: 32,
+            h: 100,
+        };
+        let img = render(&clip, &params);
+        assert_eq!(img.dimensions(), (32, 100));
+
+        // A quiet (-60 dBFS) 1 kHz tone: the reddest row is a quarter up
+        // from the bottom, and only a few rows around it light up.
+        let loudest = (0..100)
+            .max_by_key(|&y| img.get_pixel(16, y).0[0])
+            .unwrap();
+        assert!((74..=76).contains(&loudest), "tone at row {loudest}");
+        assert_eq!(img.get_pixel(16, 10).0[0], 0);
+    }
+}